@@ -118,6 +118,40 @@ impl Blake2sState {
     }
 }
 
+/// Compression function on the plain chaining value, used by the runtime dispatched state
+#[unroll::unroll_for_loops]
+pub(crate) fn compress<const REDUCED_ROUNDS: bool>(
+    h: &mut [u32; BLAKE2S_STATE_WIDTH_IN_U32_WORDS],
+    message_block: &[u32; BLAKE2S_BLOCK_SIZE_U32_WORDS],
+    t: u32,
+    is_last_round: bool,
+) {
+    use core::arch::aarch64::{veorq_u32, vld1q_u32, vst1q_u32};
+
+    unsafe {
+        let h0 = vld1q_u32(&h[0] as *const u32);
+        let h1 = vld1q_u32(&h[4] as *const u32);
+        let mut state = State([h0, h1, h0, h1]);
+        state.reset_for_round(t, is_last_round);
+
+        if REDUCED_ROUNDS {
+            for i in 0..7 {
+                let message_block = MessageBlock::load(message_block, &SIGMAS[i]);
+                mixing_function(&mut state, message_block);
+            }
+        } else {
+            for i in 0..10 {
+                let message_block = MessageBlock::load(message_block, &SIGMAS[i]);
+                mixing_function(&mut state, message_block);
+            }
+        }
+        let h0 = veorq_u32(veorq_u32(state.0[0], h0), state.0[2]);
+        let h1 = veorq_u32(veorq_u32(state.0[1], h1), state.0[3]);
+        vst1q_u32(&mut h[0] as *mut u32, h0);
+        vst1q_u32(&mut h[4] as *mut u32, h1);
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 #[repr(align(16))]
 pub struct State([uint32x4_t; 4]);
@@ -1,2 +1,185 @@
 #[cfg(target_arch = "aarch64")]
 pub mod arm_neon;
+#[cfg(target_arch = "x86_64")]
+mod x86_64;
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub use cpu_features::{simd_backend, SimdBackend};
+
+use crate::*;
+
+/// Same interface as other states, but compression is done by the fastest kernel supported by the
+/// host, that is selected at runtime. Prover hashes leaves and nodes of the Blake2s Merkle trees with it
+#[derive(Clone, Copy, Debug)]
+pub struct Blake2sState {
+    h: [u32; BLAKE2S_STATE_WIDTH_IN_U32_WORDS],
+    t: u32, // we limit ourselves to <4Gb inputs
+}
+
+impl Default for Blake2sState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Blake2sState {
+    pub fn new() -> Self {
+        Self {
+            h: CONFIGURED_IV,
+            t: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.h = CONFIGURED_IV;
+        self.t = 0;
+    }
+
+    #[inline(always)]
+    pub fn absorb<const REDUCED_ROUNDS: bool>(
+        &mut self,
+        message_block: &[u32; BLAKE2S_BLOCK_SIZE_U32_WORDS],
+    ) {
+        self.t += BLAKE2S_BLOCK_SIZE_BYTES as u32;
+        compress::<REDUCED_ROUNDS>(&mut self.h, message_block, self.t, false);
+    }
+
+    #[inline(always)]
+    pub fn absorb_final_block<const REDUCED_ROUNDS: bool>(
+        &mut self,
+        message_block: &[u32; BLAKE2S_BLOCK_SIZE_U32_WORDS],
+        block_len: usize,
+        dst: &mut [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+    ) {
+        self.t += (block_len * core::mem::size_of::<u32>()) as u32;
+        *dst = self.h;
+        compress::<REDUCED_ROUNDS>(dst, message_block, self.t, true);
+    }
+
+    #[inline(always)]
+    pub fn compress_two_to_one<const REDUCED_ROUNDS: bool>(
+        message_block: &[u32; BLAKE2S_BLOCK_SIZE_U32_WORDS],
+        dst: &mut [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+    ) {
+        *dst = CONFIGURED_IV;
+        compress::<REDUCED_ROUNDS>(dst, message_block, BLAKE2S_BLOCK_SIZE_BYTES as u32, true);
+    }
+}
+
+#[inline(always)]
+fn compress<const REDUCED_ROUNDS: bool>(
+    h: &mut [u32; BLAKE2S_STATE_WIDTH_IN_U32_WORDS],
+    message_block: &[u32; BLAKE2S_BLOCK_SIZE_U32_WORDS],
+    t: u32,
+    is_last_round: bool,
+) {
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    match simd_backend() {
+        #[cfg(target_arch = "x86_64")]
+        SimdBackend::Avx512 => {
+            return unsafe {
+                x86_64::avx512::compress::<REDUCED_ROUNDS>(h, message_block, t, is_last_round)
+            };
+        }
+        #[cfg(target_arch = "x86_64")]
+        SimdBackend::Avx2 => {
+            return unsafe {
+                x86_64::avx2::compress::<REDUCED_ROUNDS>(h, message_block, t, is_last_round)
+            };
+        }
+        #[cfg(target_arch = "aarch64")]
+        SimdBackend::Neon => {
+            return arm_neon::compress::<REDUCED_ROUNDS>(h, message_block, t, is_last_round);
+        }
+        _ => {}
+    }
+
+    compress_scalar::<REDUCED_ROUNDS>(h, message_block, t, is_last_round);
+}
+
+fn compress_scalar<const REDUCED_ROUNDS: bool>(
+    h: &mut [u32; BLAKE2S_STATE_WIDTH_IN_U32_WORDS],
+    message_block: &[u32; BLAKE2S_BLOCK_SIZE_U32_WORDS],
+    t: u32,
+    is_last_round: bool,
+) {
+    let mut extended_state = [0u32; BLAKE2S_EXTENDED_STATE_WIDTH_IN_U32_WORDS];
+    extended_state[..8].copy_from_slice(h);
+    extended_state[8..].copy_from_slice(&IV);
+    extended_state[12] ^= t;
+    if is_last_round {
+        extended_state[14] ^= 0xffffffff;
+    }
+
+    if REDUCED_ROUNDS {
+        round_function_reduced_rounds(&mut extended_state, message_block);
+    } else {
+        round_function_full_rounds(&mut extended_state, message_block);
+    }
+
+    for i in 0..8 {
+        h[i] ^= extended_state[i] ^ extended_state[i + 8];
+    }
+}
+
+#[cfg(all(test, any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod test {
+    use super::*;
+
+    fn check_against_baseline() {
+        let input: Vec<u32> = (0..(BLAKE2S_BLOCK_SIZE_U32_WORDS * 5) as u32)
+            .map(|i| i.wrapping_mul(0x9e3779b9))
+            .collect();
+        let blocks = input.as_chunks::<BLAKE2S_BLOCK_SIZE_U32_WORDS>().0;
+        for num_blocks in 1..=blocks.len() {
+            for tail_len in [1, 7, BLAKE2S_BLOCK_SIZE_U32_WORDS] {
+                let mut expected = [[0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]; 2];
+                let mut result = [[0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]; 2];
+                let mut last_block = blocks[num_blocks - 1];
+                last_block[tail_len..].fill(0);
+
+                let mut baseline = crate::Blake2sState::new();
+                let mut hasher = Blake2sState::new();
+                for block in blocks[..num_blocks - 1].iter() {
+                    baseline.absorb::<false>(block);
+                    hasher.absorb::<false>(block);
+                }
+                baseline.absorb_final_block::<false>(&last_block, tail_len, &mut expected[0]);
+                hasher.absorb_final_block::<false>(&last_block, tail_len, &mut result[0]);
+
+                baseline.reset();
+                hasher.reset();
+                for block in blocks[..num_blocks - 1].iter() {
+                    baseline.absorb::<true>(block);
+                    hasher.absorb::<true>(block);
+                }
+                baseline.absorb_final_block::<true>(&last_block, tail_len, &mut expected[1]);
+                hasher.absorb_final_block::<true>(&last_block, tail_len, &mut result[1]);
+
+                assert_eq!(result, expected);
+            }
+
+            let block = &blocks[num_blocks - 1];
+            let mut expected = [[0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]; 2];
+            let mut result = [[0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]; 2];
+            crate::Blake2sState::compress_two_to_one::<false>(block, &mut expected[0]);
+            Blake2sState::compress_two_to_one::<false>(block, &mut result[0]);
+            crate::Blake2sState::compress_two_to_one::<true>(block, &mut expected[1]);
+            Blake2sState::compress_two_to_one::<true>(block, &mut result[1]);
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn check_all_backends() {
+        for backend in SimdBackend::ALL {
+            if !backend.is_supported() {
+                continue;
+            }
+            cpu_features::force_simd_backend(Some(backend));
+            check_against_baseline();
+        }
+        cpu_features::force_simd_backend(None);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::*;
+use core::arch::x86_64::{self, __m128i};
+
+// Same row-wise layout as in the NEON implementation: every row of the extended state is one
+// 128-bit vector, and diagonals are mixed by rotating rows 1-3. Kernels only differ in rotations,
+// but all the helpers have to be compiled with the features of the kernel to be inlined into it,
+// so the code is instantiated once per feature set
+macro_rules! compression_kernel {
+    ($module:ident, $features:literal, $use_ror:literal) => {
+        pub(crate) mod $module {
+            use super::*;
+
+            #[inline]
+            #[target_feature(enable = $features)]
+            unsafe fn load(words: &[u32; 4]) -> __m128i {
+                x86_64::_mm_loadu_si128(words.as_ptr().cast())
+            }
+
+            #[inline]
+            #[target_feature(enable = $features)]
+            unsafe fn load_message_block(
+                message_block: &[u32; BLAKE2S_BLOCK_SIZE_U32_WORDS],
+                sigma: &[usize; 16],
+            ) -> [__m128i; 4] {
+                let mut result = [x86_64::_mm_setzero_si128(); 4];
+                for i in 0..4 {
+                    let offset = (i / 2) * 8 + (i % 2);
+                    result[i] = load(&[
+                        message_block[sigma[offset]],
+                        message_block[sigma[offset + 2]],
+                        message_block[sigma[offset + 4]],
+                        message_block[sigma[offset + 6]],
+                    ]);
+                }
+
+                result
+            }
+
+            #[inline]
+            #[target_feature(enable = $features)]
+            unsafe fn rotate_right<const N: i32, const M: i32>(a: __m128i) -> __m128i {
+                if $use_ror {
+                    x86_64::_mm_ror_epi32::<N>(a)
+                } else if N == 16 {
+                    let shuffle =
+                        x86_64::_mm_setr_epi8(2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13);
+                    x86_64::_mm_shuffle_epi8(a, shuffle)
+                } else if N == 8 {
+                    let shuffle =
+                        x86_64::_mm_setr_epi8(1, 2, 3, 0, 5, 6, 7, 4, 9, 10, 11, 8, 13, 14, 15, 12);
+                    x86_64::_mm_shuffle_epi8(a, shuffle)
+                } else {
+                    // M == 32 - N
+                    x86_64::_mm_or_si128(
+                        x86_64::_mm_srli_epi32::<N>(a),
+                        x86_64::_mm_slli_epi32::<M>(a),
+                    )
+                }
+            }
+
+            #[inline]
+            #[target_feature(enable = $features)]
+            unsafe fn half_mix([a, b, c, d]: &mut [__m128i; 4], x: __m128i, y: __m128i) {
+                *a = x86_64::_mm_add_epi32(x86_64::_mm_add_epi32(*a, *b), x);
+                *d = rotate_right::<16, 16>(x86_64::_mm_xor_si128(*a, *d));
+                *c = x86_64::_mm_add_epi32(*c, *d);
+                *b = rotate_right::<12, 20>(x86_64::_mm_xor_si128(*c, *b));
+
+                *a = x86_64::_mm_add_epi32(x86_64::_mm_add_epi32(*a, *b), y);
+                *d = rotate_right::<8, 24>(x86_64::_mm_xor_si128(*a, *d));
+                *c = x86_64::_mm_add_epi32(*c, *d);
+                *b = rotate_right::<7, 25>(x86_64::_mm_xor_si128(*c, *b));
+            }
+
+            #[inline]
+            #[target_feature(enable = $features)]
+            unsafe fn mixing_function(state: &mut [__m128i; 4], [x, y, xx, yy]: [__m128i; 4]) {
+                half_mix(state, x, y);
+                // rotate rows 1, 2 and 3 left by 1, 2 and 3 to mix diagonals
+                state[1] = x86_64::_mm_shuffle_epi32::<0b00_11_10_01>(state[1]);
+                state[2] = x86_64::_mm_shuffle_epi32::<0b01_00_11_10>(state[2]);
+                state[3] = x86_64::_mm_shuffle_epi32::<0b10_01_00_11>(state[3]);
+                half_mix(state, xx, yy);
+                // and back
+                state[1] = x86_64::_mm_shuffle_epi32::<0b10_01_00_11>(state[1]);
+                state[2] = x86_64::_mm_shuffle_epi32::<0b01_00_11_10>(state[2]);
+                state[3] = x86_64::_mm_shuffle_epi32::<0b00_11_10_01>(state[3]);
+            }
+
+            #[inline]
+            #[target_feature(enable = $features)]
+            pub(crate) unsafe fn compress<const REDUCED_ROUNDS: bool>(
+                h: &mut [u32; BLAKE2S_STATE_WIDTH_IN_U32_WORDS],
+                message_block: &[u32; BLAKE2S_BLOCK_SIZE_U32_WORDS],
+                t: u32,
+                is_last_round: bool,
+            ) {
+                let h0 = load(h[..4].try_into().unwrap());
+                let h1 = load(h[4..].try_into().unwrap());
+                let mut state = [
+                    h0,
+                    h1,
+                    load(EXNTENDED_CONFIGURED_IV[8..12].try_into().unwrap()),
+                    x86_64::_mm_xor_si128(
+                        load(EXNTENDED_CONFIGURED_IV[12..].try_into().unwrap()),
+                        load(&[t, 0, (is_last_round as u32) * 0xffffffff, 0]),
+                    ),
+                ];
+
+                let num_rounds = if REDUCED_ROUNDS {
+                    BLAKE2S_REDUCED_ROUNDS
+                } else {
+                    BLAKE2S_ROUNDS
+                };
+                for sigma in SIGMAS[..num_rounds].iter() {
+                    let message_block = load_message_block(message_block, sigma);
+                    mixing_function(&mut state, message_block);
+                }
+
+                let h0 = x86_64::_mm_xor_si128(x86_64::_mm_xor_si128(state[0], h0), state[2]);
+                let h1 = x86_64::_mm_xor_si128(x86_64::_mm_xor_si128(state[1], h1), state[3]);
+                x86_64::_mm_storeu_si128(h.as_mut_ptr().cast(), h0);
+                x86_64::_mm_storeu_si128(h.as_mut_ptr().add(4).cast(), h1);
+            }
+        }
+    };
+}
+
+// rotations by 16 and 8 are byte shuffles, others are pairs of shifts
+compression_kernel!(avx2, "avx2", false);
+// native rotations of AVX-512VL
+compression_kernel!(avx512, "avx2,avx512f,avx512vl", true);
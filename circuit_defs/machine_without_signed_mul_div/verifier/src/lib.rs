@@ -19,10 +19,10 @@ use verifier_common::fri_folding::fri_fold_by_log_n;
 use verifier_common::fri_folding::fri_fold_by_log_n_with_fma;
 use verifier_common::non_determinism_source::NonDeterminismSource;
 use verifier_common::prover::definitions::*;
-use verifier_common::transcript::Blake2sTranscript;
 use verifier_common::DefaultLeafInclusionVerifier;
 use verifier_common::DefaultNonDeterminismSource;
 use verifier_common::ProofOutput;
+use verifier_common::VerificationError;
 
 pub mod concrete;
 pub mod skeleton;
@@ -65,16 +65,83 @@ pub unsafe fn verify(
     )
 }
 
+/// Same as `verify`, but returns the reason why the proof was rejected instead of panicking
+#[inline(always)]
+pub unsafe fn verify_checked(
+    proof_state_dst: &mut ProofOutput<
+        TREE_CAP_SIZE,
+        NUM_COSETS,
+        NUM_DELEGATION_CHALLENGES,
+        NUM_AUX_BOUNDARY_VALUES,
+    >,
+    proof_input_dst: &mut ProofPublicInputs<NUM_STATE_ELEMENTS>,
+) -> Result<(), VerificationError> {
+    verify_with_configuration_checked::<DefaultNonDeterminismSource, DefaultLeafInclusionVerifier>(
+        proof_state_dst,
+        proof_input_dst,
+    )
+}
+
+/// Same as `verify_checked`, but instantiates the verifier for the hash family recorded in the proof
+/// instead of the default one, so proofs of any family can be verified by the same binary
+#[inline(always)]
+pub unsafe fn verify_for_hash_family_checked(
+    hash_family: HashFamily,
+    proof_state_dst: &mut ProofOutput<
+        TREE_CAP_SIZE,
+        NUM_COSETS,
+        NUM_DELEGATION_CHALLENGES,
+        NUM_AUX_BOUNDARY_VALUES,
+    >,
+    proof_input_dst: &mut ProofPublicInputs<NUM_STATE_ELEMENTS>,
+) -> Result<(), VerificationError> {
+    match hash_family {
+        HashFamily::Blake2sForEverything => verify_with_configuration_checked::<
+            DefaultNonDeterminismSource,
+            Blake2sForEverythingVerifier,
+        >(proof_state_dst, proof_input_dst),
+        HashFamily::Blake2sForLeafsPoseidon2ForNodes => verify_with_configuration_checked::<
+            DefaultNonDeterminismSource,
+            Blake2sForLeafsPoseidon2ForNodesVerifier,
+        >(proof_state_dst, proof_input_dst),
+        HashFamily::Poseidon2ForEverything => verify_with_configuration_checked::<
+            DefaultNonDeterminismSource,
+            Poseidon2ForEverythingVerifier,
+        >(proof_state_dst, proof_input_dst),
+    }
+}
+
 /// This function effectively asserts existence of the valid(!) proof for some fixed
 /// geometry + constraints (but not setup), and returns the values needed to verify chunking
 /// of the statement being proven if needed. Internally if `proof_state_dst` is Some, then
 /// the function assumes that it runs as a part of the chain of proofs of the same "flavour", and enforces some invariants.
 /// It is designed to be run within riscV environment (it loads the proofs from the CSR).
 // If you want to run it outside of riscV environment, make sure to set `verifier_in_rust` feature flag.
+#[inline(always)]
+pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusionVerifier>(
+    proof_state_dst: &mut ProofOutput<
+        TREE_CAP_SIZE,
+        NUM_COSETS,
+        NUM_DELEGATION_CHALLENGES,
+        NUM_AUX_BOUNDARY_VALUES,
+    >,
+    proof_input_dst: &mut ProofPublicInputs<NUM_STATE_ELEMENTS>,
+) {
+    if let Err(error) = verify_with_configuration_checked::<I, V>(proof_state_dst, proof_input_dst)
+    {
+        panic!("proof verification failed: {}", error);
+    }
+}
+
+/// Same as `verify_with_configuration`, but returns the reason why the proof was rejected instead of panicking.
+/// Destinations are only written if verification succeeds
 #[allow(invalid_value)]
 #[allow(unreachable_code)]
 #[inline(never)]
-pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusionVerifier>(
+pub unsafe fn verify_with_configuration_checked<
+    I: NonDeterminismSource,
+    V: LeafInclusionVerifier,
+>(
     proof_state_dst: &mut ProofOutput<
         TREE_CAP_SIZE,
         NUM_COSETS,
@@ -82,7 +149,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
         NUM_AUX_BOUNDARY_VALUES,
     >,
     proof_input_dst: &mut ProofPublicInputs<NUM_STATE_ELEMENTS>,
-) {
+) -> Result<(), VerificationError> {
     // #[cfg(test)]
     // panic!();
 
@@ -91,20 +158,23 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
     let mut leaf_inclusion_verifier = V::new();
 
     let mut skeleton = MaybeUninit::<ProofSkeletonInstance>::uninit().assume_init();
-    ProofSkeletonInstance::fill::<I>((&mut skeleton) as *mut _);
+    ProofSkeletonInstance::fill::<I>((&mut skeleton) as *mut _)?;
     // let skeleton = skeleton.assume_init();
+    if !skeleton.is_valid_transcript_input::<V::Transcript>() {
+        return Err(VerificationError::MalformedProof);
+    }
 
     let mut queries = MaybeUninit::<[QueryValuesInstance; NUM_QUERIES]>::uninit().assume_init();
     QueryValuesInstance::fill_array::<I, V, NUM_QUERIES>(
         (&mut queries) as *mut _,
         &skeleton,
         &mut leaf_inclusion_verifier,
-    );
+    )?;
     // let queries = queries.assume_init();
 
     // now drive the transcript and continue
-    let mut transcript_hasher = blake2s_u32::DelegatedBlake2sState::new();
-    let mut seed = Blake2sTranscript::commit_initial_using_hasher(
+    let mut transcript_hasher = V::Transcript::new_hasher();
+    let mut seed = V::Transcript::commit_initial_using_hasher(
         &mut transcript_hasher,
         skeleton.transcript_elements_before_stage2(),
     );
@@ -115,7 +185,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
             .next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)],
     >::uninit()
     .assume_init();
-    Transcript::draw_randomness_using_hasher(
+    V::Transcript::draw_randomness_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         &mut transcript_challenges,
@@ -137,7 +207,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
     );
 
     // commit stage 2 artifacts - tree and memory grand product / delegation set accumulator
-    Blake2sTranscript::commit_with_seed_using_hasher(
+    V::Transcript::commit_with_seed_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         skeleton.transcript_elements_stage2_to_stage3(),
@@ -148,7 +218,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
         [u32; (2usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)],
     >::uninit()
     .assume_init();
-    Transcript::draw_randomness_using_hasher(
+    V::Transcript::draw_randomness_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         &mut transcript_challenges,
@@ -168,7 +238,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
     );
 
     // commit quotient tree
-    Blake2sTranscript::commit_with_seed_using_hasher(
+    V::Transcript::commit_with_seed_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         skeleton.transcript_elements_stage3_to_stage4(),
@@ -179,7 +249,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
         [u32; (1usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)],
     >::uninit()
     .assume_init();
-    Transcript::draw_randomness_using_hasher(
+    V::Transcript::draw_randomness_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         &mut transcript_challenges,
@@ -193,7 +263,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
     );
 
     // commit evaluations
-    Blake2sTranscript::commit_with_seed_using_hasher(
+    V::Transcript::commit_with_seed_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         skeleton.transcript_elements_evaluations_at_z(),
@@ -204,7 +274,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
         [u32; (1usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)],
     >::uninit()
     .assume_init();
-    Transcript::draw_randomness_using_hasher(
+    V::Transcript::draw_randomness_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         &mut transcript_challenges,
@@ -227,13 +297,13 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
         .into_iter()
         .zip(fri_folding_challenges.iter_mut())
     {
-        Blake2sTranscript::commit_with_seed_using_hasher(&mut transcript_hasher, &mut seed, caps);
+        V::Transcript::commit_with_seed_using_hasher(&mut transcript_hasher, &mut seed, caps);
 
         let mut transcript_challenges = MaybeUninit::<
             [u32; (1usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)],
         >::uninit()
         .assume_init();
-        Transcript::draw_randomness_using_hasher(
+        V::Transcript::draw_randomness_using_hasher(
             &mut transcript_hasher,
             &mut seed,
             &mut transcript_challenges,
@@ -249,7 +319,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
 
     if LAST_FRI_STEP_EXPOSE_LEAFS {
         let dst = &mut fri_folding_challenges[NUM_FRI_STEPS - 1];
-        Blake2sTranscript::commit_with_seed_using_hasher(
+        V::Transcript::commit_with_seed_using_hasher(
             &mut transcript_hasher,
             &mut seed,
             skeleton.transcript_elements_last_fri_step_leaf_values(),
@@ -259,7 +329,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
             [u32; (1usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)],
         >::uninit()
         .assume_init();
-        Transcript::draw_randomness_using_hasher(
+        V::Transcript::draw_randomness_using_hasher(
             &mut transcript_hasher,
             &mut seed,
             &mut transcript_challenges,
@@ -274,24 +344,30 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
     }
 
     // commit monomial coefficients before drawing queries
-    Blake2sTranscript::commit_with_seed_using_hasher(
+    V::Transcript::commit_with_seed_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         skeleton.transcript_elements_monomial_coefficients(),
     );
 
     // now we can verify PoW
-    Blake2sTranscript::verify_pow_using_hasher(
+    if !V::Transcript::check_pow_using_hasher(
         &mut transcript_hasher,
         &mut seed,
         skeleton.pow_nonce,
         POW_BITS as u32,
-    );
+    ) {
+        return Err(VerificationError::PowFailure);
+    }
 
     // now we need to draw enough bits to form query indexes
     let mut indexes_bits: [u32; NUM_REQUIRED_WORDS_FOR_QUERY_INDEXES] =
         MaybeUninit::<[u32; NUM_REQUIRED_WORDS_FOR_QUERY_INDEXES]>::uninit().assume_init();
-    Transcript::draw_randomness_using_hasher(&mut transcript_hasher, &mut seed, &mut indexes_bits);
+    V::Transcript::draw_randomness_using_hasher(
+        &mut transcript_hasher,
+        &mut seed,
+        &mut indexes_bits,
+    );
 
     // NOTE: when we will use queries below, we MUST check that query set's index is exactly the index we draw from transcript.
     // Indexes in `queries` are already checked to be included in merkle tree caps declared in `skeleton`
@@ -397,7 +473,9 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
 
         let all_non_zero = field::batch_inverse_checked(&mut to_batch_inverse, &mut buffer);
         // low probability here
-        assert!(all_non_zero);
+        if !all_non_zero {
+            return Err(VerificationError::DegenerateChallenge);
+        }
 
         let [z_inv, vanishing_inv, first_row, one_before_last_row, last_row] = to_batch_inverse;
 
@@ -447,7 +525,9 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
             AuxArgumentsBoundaryValues::default()
         };
 
-        assert!((u32::MAX >> CIRCUIT_SEQUENCE_BITS_SHIFT) >= skeleton.circuit_sequence_idx);
+        if (u32::MAX >> CIRCUIT_SEQUENCE_BITS_SHIFT) < skeleton.circuit_sequence_idx {
+            return Err(VerificationError::MalformedProof);
+        }
 
         let memory_timestamp_high_from_circuit_sequence =
             Mersenne31Field::new(skeleton.circuit_sequence_idx << CIRCUIT_SEQUENCE_BITS_SHIFT);
@@ -495,10 +575,9 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
             delegation_argument_interpolant_linear_coeff,
         );
 
-        assert_eq!(
-            quotient_recomputed_value, quotient_opening,
-            "quotient evaluation diverged"
-        );
+        if quotient_recomputed_value != quotient_opening {
+            return Err(VerificationError::QuotientMismatch);
+        }
     }
 
     // DEEP poly consistency
@@ -590,7 +669,11 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
                 assemble_query_index(BITS_FOR_QUERY_INDEX, &mut bit_iterator) as u32;
 
             // assert that our query is at the proper index
-            assert_eq!(query.query_index, query_index);
+            if query.query_index != query_index {
+                return Err(VerificationError::QueryIndexMismatch {
+                    query_round: query_round as u32,
+                });
+            }
 
             let tree_index = query_index & TREE_INDEX_MASK;
             let domain_index = bitreverse_for_bitlength(tree_index, TRACE_LEN_LOG2 as u32);
@@ -607,7 +690,9 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
             to_inverse[1].sub_assign_base(&evaluation_point);
 
             let all_nonzero = batch_inverse_checked(&mut to_inverse, &mut inversion_buffer);
-            assert!(all_nonzero);
+            if !all_nonzero {
+                return Err(VerificationError::DegenerateChallenge);
+            }
 
             let [divisor_for_z, divisor_for_z_omega] = to_inverse;
 
@@ -677,7 +762,7 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
                     leaf_projection
                 };
 
-                if Mersenne31Quartic::PREFER_FMA
+                let in_leaf = if Mersenne31Quartic::PREFER_FMA
                     && Mersenne31Quartic::USE_SPEC_MUL_BY_BASE_VIA_MUL_BY_SELF
                     && Mersenne31Quartic::CAN_PROJECT_FROM_BASE
                 {
@@ -685,71 +770,61 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
                     let challenge = fri_folding_challenges.get_unchecked(step);
 
                     match *folding_degree_log_2 {
-                        1 => {
-                            fri_fold_by_log_n_with_fma::<1>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenge,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
-                        2 => {
-                            fri_fold_by_log_n_with_fma::<2>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenge,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
-                        3 => {
-                            fri_fold_by_log_n_with_fma::<3>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenge,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
-                        4 => {
-                            fri_fold_by_log_n_with_fma::<4>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenge,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
-                        5 => {
-                            fri_fold_by_log_n_with_fma::<5>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenge,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
+                        1 => fri_fold_by_log_n_with_fma::<1>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenge,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
+                        2 => fri_fold_by_log_n_with_fma::<2>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenge,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
+                        3 => fri_fold_by_log_n_with_fma::<3>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenge,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
+                        4 => fri_fold_by_log_n_with_fma::<4>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenge,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
+                        5 => fri_fold_by_log_n_with_fma::<5>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenge,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
                         _ => {
                             unreachable!("too high folding degree");
                         }
@@ -760,75 +835,72 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
                     // NOTE: routine below will check that our expected value is indeed in the leaf at the expected position
 
                     match *folding_degree_log_2 {
-                        1 => {
-                            fri_fold_by_log_n::<1>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenges,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
-                        2 => {
-                            fri_fold_by_log_n::<2>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenges,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
-                        3 => {
-                            fri_fold_by_log_n::<3>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenges,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
-                        4 => {
-                            fri_fold_by_log_n::<4>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenges,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
-                        5 => {
-                            fri_fold_by_log_n::<5>(
-                                &mut expected_value,
-                                &mut evaluation_point,
-                                &mut domain_size_log_2,
-                                &mut domain_index,
-                                &mut tree_index,
-                                &mut offset_inv,
-                                leaf_projection,
-                                challenges,
-                                &SHARED_FACTORS_FOR_FOLDING,
-                            );
-                        }
+                        1 => fri_fold_by_log_n::<1>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenges,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
+                        2 => fri_fold_by_log_n::<2>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenges,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
+                        3 => fri_fold_by_log_n::<3>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenges,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
+                        4 => fri_fold_by_log_n::<4>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenges,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
+                        5 => fri_fold_by_log_n::<5>(
+                            &mut expected_value,
+                            &mut evaluation_point,
+                            &mut domain_size_log_2,
+                            &mut domain_index,
+                            &mut tree_index,
+                            &mut offset_inv,
+                            leaf_projection,
+                            challenges,
+                            &SHARED_FACTORS_FOR_FOLDING,
+                        ),
                         _ => {
                             unreachable!("too high folding degree");
                         }
                     }
+                };
+
+                if !in_leaf {
+                    return Err(VerificationError::FriFoldingMismatch {
+                        query_index,
+                        step: step as u32,
+                    });
                 }
             }
 
@@ -883,7 +955,9 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
 
             expected_value.mul_assign_by_base(&taus_in_domain_by_half[coset_index as usize]);
 
-            assert_eq!(value_from_monomial_form, expected_value);
+            if value_from_monomial_form != expected_value {
+                return Err(VerificationError::FriFinalPolynomialMismatch { query_index });
+            }
         }
     }
 
@@ -921,4 +995,6 @@ pub unsafe fn verify_with_configuration<I: NonDeterminismSource, V: LeafInclusio
         proof_input_dst.input_state_variables = *it.next().unwrap_unchecked();
         proof_input_dst.output_state_variables = *it.next().unwrap_unchecked();
     }
+
+    Ok(())
 }
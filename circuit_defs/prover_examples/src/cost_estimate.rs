@@ -0,0 +1,394 @@
+//! Estimation of the proving cost from the execution trace only, without generating the witness
+//! and proofs. Circuit counts of the base layer are exact, while memory, proof size, prover time
+//! and recursion are estimated from the circuit dimensions and `ProvingCostModel`.
+
+use super::*;
+
+use prover::cs::one_row_compiler::CompiledCircuitArtifact;
+
+/// Parameters of the estimation that can not be derived from the circuits themselves.
+/// Defaults are rough figures and should be calibrated on the target hardware and
+/// against the real recursion run
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProvingCostModel {
+    // number of committed field elements (trace length times LDE factor times columns) per second
+    pub prover_cells_per_second: f64,
+    // cycles of the universal verifier spent to verify a single proof
+    pub recursion_cycles_per_proof: u64,
+    // blake2s delegation calls of the universal verifier to verify a single proof
+    pub recursion_delegation_calls_per_proof: u64,
+    // recursion stops after this many layers even if proofs are not reduced enough
+    pub max_recursion_layers: usize,
+}
+
+impl Default for ProvingCostModel {
+    fn default() -> Self {
+        Self {
+            prover_cells_per_second: 2e9,
+            recursion_cycles_per_proof: 1 << 20,
+            recursion_delegation_calls_per_proof: 1 << 12,
+            max_recursion_layers: 8,
+        }
+    }
+}
+
+/// Dimensions of a single circuit instance
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CircuitDimensions {
+    pub trace_len: usize,
+    pub lde_factor: usize,
+    // base field columns over all the committed trees
+    pub num_columns: usize,
+    // cycles for the main circuit and requests for delegation circuits
+    pub capacity: usize,
+}
+
+impl CircuitDimensions {
+    pub fn for_compiled_circuit(
+        compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+        lde_factor: usize,
+        capacity: usize,
+    ) -> Self {
+        let num_columns = compiled_circuit.witness_layout.total_width
+            + compiled_circuit.memory_layout.total_width
+            + compiled_circuit.setup_layout.total_width
+            + compiled_circuit.stage_2_layout.total_width;
+
+        Self {
+            trace_len: compiled_circuit.trace_len,
+            lde_factor,
+            num_columns,
+            capacity,
+        }
+    }
+
+    /// Number of committed field elements
+    pub fn num_cells(&self) -> u64 {
+        (self.trace_len * self.lde_factor * self.num_columns) as u64
+    }
+
+    /// Main domain trace and its LDE, not including the Merkle trees and quotient
+    pub fn memory_bytes(&self) -> u64 {
+        ((self.lde_factor + 1) * self.trace_len * self.num_columns * std::mem::size_of::<u32>())
+            as u64
+    }
+
+    /// Every query opens all the columns together with the Merkle paths in the witness, memory,
+    /// setup, stage 2 and quotient trees, and FRI layers are accounted as one more tree
+    pub fn proof_bytes(&self) -> u64 {
+        const NUM_OPENED_TREES: usize = 6;
+        const DIGEST_BYTES: usize = 32;
+
+        let merkle_path_len = (self.trace_len * self.lde_factor).trailing_zeros() as usize;
+        let per_query = self.num_columns * std::mem::size_of::<u32>()
+            + NUM_OPENED_TREES * merkle_path_len * DIGEST_BYTES;
+
+        (NUM_QUERIES * per_query) as u64
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CircuitCostEstimate {
+    // `main` or `delegation <type>`
+    pub circuit: String,
+    pub count: usize,
+    // cycles or delegation requests used over all the instances
+    pub used: u64,
+    pub dimensions: CircuitDimensions,
+    // per circuit instance
+    pub memory_bytes: u64,
+    // over all the instances
+    pub proof_bytes: u64,
+    pub prover_time_secs: f64,
+}
+
+impl CircuitCostEstimate {
+    fn new(
+        circuit: String,
+        count: usize,
+        used: u64,
+        dimensions: CircuitDimensions,
+        model: &ProvingCostModel,
+    ) -> Self {
+        Self {
+            circuit,
+            count,
+            used,
+            dimensions,
+            memory_bytes: dimensions.memory_bytes(),
+            proof_bytes: dimensions.proof_bytes() * count as u64,
+            prover_time_secs: (dimensions.num_cells() * count as u64) as f64
+                / model.prover_cells_per_second,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LayerCostEstimate {
+    // `base` or `recursion <level>`
+    pub layer: String,
+    pub circuits: Vec<CircuitCostEstimate>,
+}
+
+impl LayerCostEstimate {
+    pub fn num_proofs(&self) -> usize {
+        self.circuits.iter().map(|c| c.count).sum()
+    }
+
+    pub fn proof_bytes(&self) -> u64 {
+        self.circuits.iter().map(|c| c.proof_bytes).sum()
+    }
+
+    pub fn prover_time_secs(&self) -> f64 {
+        self.circuits.iter().map(|c| c.prover_time_secs).sum()
+    }
+
+    fn main_count(&self) -> usize {
+        self.circuits
+            .iter()
+            .filter(|c| c.circuit == "main")
+            .map(|c| c.count)
+            .sum()
+    }
+
+    fn max_delegation_count(&self) -> usize {
+        self.circuits
+            .iter()
+            .filter(|c| c.circuit != "main")
+            .map(|c| c.count)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProvingCostEstimate {
+    pub cycles: u64,
+    pub final_pc: u32,
+    pub layers: Vec<LayerCostEstimate>,
+    // false if recursion did not reduce the proofs enough within `max_recursion_layers`
+    pub recursion_converged: bool,
+    pub total_proofs: usize,
+    pub total_proof_bytes: u64,
+    // largest single circuit, as circuits are proven one by one
+    pub peak_memory_bytes: u64,
+    pub total_prover_time_secs: f64,
+}
+
+/// Runs the same tracing as `trace_execution_for_gpu` and estimates the cost of proving the
+/// execution on the machine `C`, including the recursion on the reduced machine.
+pub fn estimate_proving_cost<
+    ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>,
+    C: MachineConfig,
+>(
+    num_instances_upper_bound: usize,
+    bytecode: &[u32],
+    mut non_determinism: ND,
+    model: &ProvingCostModel,
+    worker: &worker::Worker,
+) -> ProvingCostEstimate {
+    let cycles_per_circuit = setups::num_cycles_for_machine::<C>();
+    let max_cycles_to_run = num_instances_upper_bound * cycles_per_circuit;
+
+    let delegation_factories = setups::delegation_factories_for_machine::<C, Global>();
+
+    let (final_pc, main_circuits_witness, delegation_circuits_witness, _, _) =
+        run_and_split_for_gpu::<ND, C, Global>(
+            max_cycles_to_run,
+            bytecode,
+            &mut non_determinism,
+            delegation_factories,
+            worker,
+        );
+
+    let cycles: u64 = main_circuits_witness
+        .iter()
+        .map(|chunk| chunk.cycles_traced as u64)
+        .sum();
+    let num_main_circuits = main_circuits_witness.len();
+    // traces are not needed anymore, and can take a lot of memory
+    drop(main_circuits_witness);
+
+    let mut delegation_usage: Vec<(u32, usize, u64)> = delegation_circuits_witness
+        .iter()
+        .map(|(delegation_type, witnesses)| {
+            (
+                *delegation_type as u32,
+                witnesses.len(),
+                witnesses
+                    .iter()
+                    .map(|w: &DelegationWitness| w.write_timestamp.len() as u64)
+                    .sum(),
+            )
+        })
+        .collect();
+    drop(delegation_circuits_witness);
+
+    let main_dimensions = CircuitDimensions::for_compiled_circuit(
+        &setups::compiled_circuit_for_machine::<C>(bytecode),
+        setups::lde_factor_for_machine::<C>(),
+        cycles_per_circuit,
+    );
+    let delegation_dimensions = delegation_dimensions_for_machine::<C>();
+
+    let mut base_layer = LayerCostEstimate {
+        layer: "base".to_string(),
+        circuits: vec![CircuitCostEstimate::new(
+            "main".to_string(),
+            num_main_circuits,
+            cycles,
+            main_dimensions,
+            model,
+        )],
+    };
+    delegation_usage.sort_by_key(|(delegation_type, _, _)| *delegation_type);
+    for (delegation_type, count, requests) in delegation_usage.into_iter() {
+        if count == 0 {
+            continue;
+        }
+        let dimensions = delegation_dimensions
+            .get(&delegation_type)
+            .copied()
+            .unwrap_or_else(|| panic!("unknown delegation type {}", delegation_type));
+        base_layer.circuits.push(CircuitCostEstimate::new(
+            format!("delegation {}", delegation_type),
+            count,
+            requests,
+            dimensions,
+            model,
+        ));
+    }
+
+    let (recursion_layers, recursion_converged) = estimate_recursion(&base_layer, model);
+
+    let mut layers = vec![base_layer];
+    layers.extend(recursion_layers);
+
+    ProvingCostEstimate {
+        cycles,
+        final_pc,
+        total_proofs: layers.iter().map(|l| l.num_proofs()).sum(),
+        total_proof_bytes: layers.iter().map(|l| l.proof_bytes()).sum(),
+        peak_memory_bytes: layers
+            .iter()
+            .flat_map(|l| l.circuits.iter())
+            .filter(|c| c.count > 0)
+            .map(|c| c.memory_bytes)
+            .max()
+            .unwrap_or(0),
+        total_prover_time_secs: layers.iter().map(|l| l.prover_time_secs()).sum(),
+        layers,
+        recursion_converged,
+    }
+}
+
+fn delegation_dimensions_for_machine<C: MachineConfig>() -> HashMap<u32, CircuitDimensions> {
+    setups::get_delegation_compiled_circuits_for_machine_type::<C>()
+        .into_iter()
+        .map(|(delegation_type, description)| {
+            let dimensions = CircuitDimensions::for_compiled_circuit(
+                &description.compiled_circuit,
+                // all delegation circuits use the same LDE factor
+                setups::blake2_with_compression::LDE_FACTOR,
+                description.num_requests_per_circuit,
+            );
+            (delegation_type, dimensions)
+        })
+        .collect()
+}
+
+// Mirrors the recursion of the CLI: every layer verifies all the proofs of the previous one on
+// the reduced machine, until there are at most 2 main proofs and a single proof per delegation type.
+// There is always at least one recursion layer
+fn estimate_recursion(
+    base_layer: &LayerCostEstimate,
+    model: &ProvingCostModel,
+) -> (Vec<LayerCostEstimate>, bool) {
+    type RecursionMachine = IWithoutByteAccessIsaConfigWithDelegation;
+
+    let dummy_bytecode = vec![0u32; setups::risc_v_cycles::MAX_ROM_SIZE / 4];
+    let cycles_per_circuit = setups::num_cycles_for_machine::<RecursionMachine>();
+    let main_dimensions = CircuitDimensions::for_compiled_circuit(
+        &setups::compiled_circuit_for_machine::<RecursionMachine>(&dummy_bytecode),
+        setups::lde_factor_for_machine::<RecursionMachine>(),
+        cycles_per_circuit,
+    );
+    let delegation_type = setups::blake2_with_compression::DELEGATION_TYPE_ID;
+    let delegation_dimensions = delegation_dimensions_for_machine::<RecursionMachine>()
+        .get(&delegation_type)
+        .copied()
+        .expect("recursion machine must support blake2s delegation");
+
+    let mut layers: Vec<LayerCostEstimate> = vec![];
+    let mut num_proofs = base_layer.num_proofs();
+    for level in 0..model.max_recursion_layers {
+        let cycles = num_proofs as u64 * model.recursion_cycles_per_proof;
+        let requests = num_proofs as u64 * model.recursion_delegation_calls_per_proof;
+        let layer = LayerCostEstimate {
+            layer: format!("recursion {}", level),
+            circuits: vec![
+                CircuitCostEstimate::new(
+                    "main".to_string(),
+                    cycles.div_ceil(cycles_per_circuit as u64) as usize,
+                    cycles,
+                    main_dimensions,
+                    model,
+                ),
+                CircuitCostEstimate::new(
+                    format!("delegation {}", delegation_type),
+                    requests.div_ceil(delegation_dimensions.capacity as u64) as usize,
+                    requests,
+                    delegation_dimensions,
+                    model,
+                ),
+            ],
+        };
+        let should_stop = layer.main_count() <= 2 && layer.max_delegation_count() <= 1;
+        num_proofs = layer.num_proofs();
+        layers.push(layer);
+        if should_stop {
+            return (layers, true);
+        }
+    }
+
+    (layers, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recursion_estimate_converges() {
+        let dimensions = CircuitDimensions {
+            trace_len: 1 << 22,
+            lde_factor: 2,
+            num_columns: 100,
+            capacity: (1 << 22) - 1,
+        };
+        let model = ProvingCostModel::default();
+        let base_layer = LayerCostEstimate {
+            layer: "base".to_string(),
+            circuits: vec![CircuitCostEstimate::new(
+                "main".to_string(),
+                100,
+                100 * dimensions.capacity as u64,
+                dimensions,
+                &model,
+            )],
+        };
+
+        let (layers, converged) = estimate_recursion(&base_layer, &model);
+        assert!(converged);
+        assert!(layers.len() > 1);
+        // every layer must verify fewer proofs than the previous one
+        let mut num_proofs = base_layer.num_proofs();
+        for layer in layers.iter() {
+            assert!(layer.num_proofs() < num_proofs);
+            num_proofs = layer.num_proofs();
+        }
+        let last = layers.last().unwrap();
+        assert!(last.main_count() <= 2);
+        assert!(last.max_delegation_count() <= 1);
+    }
+}
@@ -0,0 +1,434 @@
+//! CPU implementation of the `ExecutionProverBackend`, with the same API as the GPU `ExecutionProver`,
+//! so the whole pipeline can be exercised on machines without CUDA.
+
+use super::*;
+
+use risc_v_simulator::cycle::IMWithoutSignedMulDivIsaConfig;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::{Condvar, Mutex};
+use trace_and_split::execution_prover::{
+    ExecutableBinary, ExecutionProofs, ExecutionProverBackend, MainCircuitType, MemoryCommitments,
+};
+
+enum MainPrecomputations {
+    FinalReducedRiscVMachine(MainCircuitPrecomputations<IWithoutByteAccessIsaConfig, Global>),
+    MachineWithoutSignedMulDiv(MainCircuitPrecomputations<IMWithoutSignedMulDivIsaConfig, Global>),
+    ReducedRiscVMachine(
+        MainCircuitPrecomputations<IWithoutByteAccessIsaConfigWithDelegation, Global>,
+    ),
+    RiscVCycles(MainCircuitPrecomputations<IMStandardIsaConfig, Global>),
+}
+
+struct BinaryHolder {
+    circuit_type: MainCircuitType,
+    bytecode: Box<[u32]>,
+    precomputations: MainPrecomputations,
+}
+
+#[derive(Clone, Copy)]
+enum ProvingMode {
+    CommitMemory,
+    Prove(ExternalChallenges),
+    CommitMemoryAndProve,
+}
+
+enum ProvingResult {
+    MemoryCommitments(MemoryCommitments),
+    Proofs(ExecutionProofs),
+}
+
+pub struct CpuExecutionProver<K: Debug + Eq + Hash> {
+    worker: worker::Worker,
+    binaries: HashMap<K, BinaryHolder>,
+    delegation_circuits_precomputations: Vec<(u32, DelegationCircuitPrecomputations<Global>)>,
+    // traces are large, so we limit the number of batches being processed
+    batch_limiter: BatchLimiter,
+}
+
+struct BatchLimiter {
+    max_concurrent_batches: usize,
+    active_batches: Mutex<usize>,
+    batch_finished: Condvar,
+}
+
+impl BatchLimiter {
+    fn new(max_concurrent_batches: usize) -> Self {
+        Self {
+            batch_limiter: BatchLimiter::new(max_concurrent_batches),
+        }
+    }
+
+    /// Waits until fewer than `max_concurrent_batches` batches are active. Batch stays active
+    /// until the returned guard is dropped, including unwinding from a panic in the batch
+    fn start_batch(&self) -> ActiveBatch<'_> {
+        let mut active_batches = self.active_batches.lock().unwrap();
+        while *active_batches >= self.max_concurrent_batches {
+            active_batches = self.batch_finished.wait(active_batches).unwrap();
+        }
+        *active_batches += 1;
+
+        ActiveBatch(self)
+    }
+}
+
+struct ActiveBatch<'a>(&'a BatchLimiter);
+
+impl Drop for ActiveBatch<'_> {
+    fn drop(&mut self) {
+        // counter is only changed under the lock, so it's consistent even if the lock is poisoned,
+        // and we must not panic here while unwinding
+        let mut active_batches = self
+            .0
+            .active_batches
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *active_batches -= 1;
+        drop(active_batches);
+        self.0.batch_finished.notify_one();
+    }
+}
+
+impl<K: Clone + Debug + Eq + Hash> CpuExecutionProver<K> {
+    ///  Creates a new instance of `CpuExecutionProver`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrent_batches`: maximum number of batches that are traced and proven at the same time, other batches wait for them to finish
+    /// * `binaries`: a vector of executable binaries that the prover can work with, each binary must have a unique key
+    ///
+    pub fn new(
+        max_concurrent_batches: usize,
+        binaries: Vec<ExecutableBinary<K, impl Into<Box<[u32]>>>>,
+    ) -> Self {
+        assert_ne!(max_concurrent_batches, 0);
+        assert!(!binaries.is_empty());
+        let worker = worker::Worker::new();
+        let binaries = binaries
+            .into_iter()
+            .map(|b| {
+                let ExecutableBinary {
+                    key,
+                    circuit_type,
+                    bytecode,
+                } = b;
+                let bytecode: Box<[u32]> = bytecode.into();
+                println!(
+                    "Producing precomputations for main circuit {:?} with binary {:?}",
+                    circuit_type, key
+                );
+                let precomputations = match circuit_type {
+                    MainCircuitType::FinalReducedRiscVMachine => {
+                        MainPrecomputations::FinalReducedRiscVMachine(
+                            setups::get_final_reduced_riscv_circuit_setup(&bytecode, &worker),
+                        )
+                    }
+                    MainCircuitType::MachineWithoutSignedMulDiv => {
+                        MainPrecomputations::MachineWithoutSignedMulDiv(
+                            setups::get_riscv_without_signed_mul_div_circuit_setup(
+                                &bytecode, &worker,
+                            ),
+                        )
+                    }
+                    MainCircuitType::ReducedRiscVMachine => {
+                        MainPrecomputations::ReducedRiscVMachine(
+                            setups::get_reduced_riscv_circuit_setup(&bytecode, &worker),
+                        )
+                    }
+                    MainCircuitType::RiscVCycles => MainPrecomputations::RiscVCycles(
+                        setups::get_main_riscv_circuit_setup(&bytecode, &worker),
+                    ),
+                };
+                (
+                    key,
+                    BinaryHolder {
+                        circuit_type,
+                        bytecode,
+                        precomputations,
+                    },
+                )
+            })
+            .collect();
+        println!("Producing precomputations for all delegation circuits");
+        let delegation_circuits_precomputations =
+            setups::all_delegation_circuits_precomputations(&worker);
+
+        Self {
+            worker,
+            binaries,
+            delegation_circuits_precomputations,
+            max_concurrent_batches,
+            active_batches: Mutex::new(0),
+            batch_finished: Condvar::new(),
+        }
+    }
+
+    fn execute<ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>>(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+        mode: ProvingMode,
+    ) -> ProvingResult {
+        let holder = self
+            .binaries
+            .get(binary_key)
+            .unwrap_or_else(|| panic!("unknown binary with key {:?}", binary_key));
+
+        let _active_batch = self.batch_limiter.start_batch();
+        let timer = std::time::Instant::now();
+        println!(
+            "BATCH[{batch_id}] PROVER started work for binary with key {:?}",
+            binary_key
+        );
+
+        let result = match &holder.precomputations {
+            MainPrecomputations::FinalReducedRiscVMachine(precomputations) => self
+                .execute_for_machine(
+                    holder,
+                    precomputations,
+                    num_instances_upper_bound,
+                    non_determinism_source,
+                    mode,
+                ),
+            MainPrecomputations::MachineWithoutSignedMulDiv(precomputations) => self
+                .execute_for_machine(
+                    holder,
+                    precomputations,
+                    num_instances_upper_bound,
+                    non_determinism_source,
+                    mode,
+                ),
+            MainPrecomputations::ReducedRiscVMachine(precomputations) => self.execute_for_machine(
+                holder,
+                precomputations,
+                num_instances_upper_bound,
+                non_determinism_source,
+                mode,
+            ),
+            MainPrecomputations::RiscVCycles(precomputations) => self.execute_for_machine(
+                holder,
+                precomputations,
+                num_instances_upper_bound,
+                non_determinism_source,
+                mode,
+            ),
+        };
+
+        println!(
+            "BATCH[{batch_id}] PROVER finished work for binary with key {:?} in {:.3}s",
+            binary_key,
+            timer.elapsed().as_secs_f64()
+        );
+
+        result
+    }
+
+    fn execute_for_machine<
+        ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>,
+        C: MachineConfig,
+    >(
+        &self,
+        holder: &BinaryHolder,
+        precomputations: &MainCircuitPrecomputations<C, Global>,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+        mode: ProvingMode,
+    ) -> ProvingResult {
+        let traced_execution = TracedExecution::<C>::trace(
+            num_instances_upper_bound,
+            &holder.bytecode,
+            non_determinism_source,
+            &self.worker,
+        );
+        let final_register_values: [FinalRegisterValue; 32] = traced_execution
+            .final_register_values
+            .clone()
+            .try_into()
+            .unwrap();
+
+        let external_challenges = match mode {
+            ProvingMode::Prove(external_challenges) => external_challenges,
+            ProvingMode::CommitMemory | ProvingMode::CommitMemoryAndProve => {
+                let (main_memory_commitments, delegation_memory_commitments) =
+                    commit_memory_for_traced_execution(
+                        &traced_execution,
+                        precomputations,
+                        &self.delegation_circuits_precomputations,
+                        &self.worker,
+                    );
+                if let ProvingMode::CommitMemory = mode {
+                    return ProvingResult::MemoryCommitments((
+                        final_register_values,
+                        main_memory_commitments,
+                        delegation_memory_commitments,
+                    ));
+                }
+                // unlike GPU prover we can reuse the trace instead of running the binary once again
+                let memory_challenges_seed = fs_transform_for_memory_and_delegation_arguments(
+                    &DefaultTreeConstructor::dump_caps(&precomputations.setup.trees),
+                    &final_register_values,
+                    &main_memory_commitments,
+                    &delegation_memory_commitments,
+                );
+                ExternalChallenges::draw_from_transcript_seed(
+                    memory_challenges_seed,
+                    holder.circuit_type.supports_delegation(),
+                )
+            }
+        };
+
+        let (main_proofs, delegation_proofs) = prove_traced_execution(
+            &traced_execution,
+            precomputations,
+            &self.delegation_circuits_precomputations,
+            external_challenges,
+            &self.worker,
+        );
+
+        ProvingResult::Proofs((final_register_values, main_proofs, delegation_proofs))
+    }
+}
+
+impl<K: Clone + Debug + Eq + Hash, ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>>
+    ExecutionProverBackend<K, ND> for CpuExecutionProver<K>
+{
+    fn commit_memory(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+    ) -> MemoryCommitments {
+        match self.execute(
+            batch_id,
+            binary_key,
+            num_instances_upper_bound,
+            non_determinism_source,
+            ProvingMode::CommitMemory,
+        ) {
+            ProvingResult::MemoryCommitments(result) => result,
+            ProvingResult::Proofs(_) => unreachable!(),
+        }
+    }
+
+    fn prove(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+        external_challenges: ExternalChallenges,
+    ) -> ExecutionProofs {
+        match self.execute(
+            batch_id,
+            binary_key,
+            num_instances_upper_bound,
+            non_determinism_source,
+            ProvingMode::Prove(external_challenges),
+        ) {
+            ProvingResult::Proofs(result) => result,
+            ProvingResult::MemoryCommitments(_) => unreachable!(),
+        }
+    }
+
+    fn commit_memory_and_prove(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+    ) -> ExecutionProofs {
+        match self.execute(
+            batch_id,
+            binary_key,
+            num_instances_upper_bound,
+            non_determinism_source,
+            ProvingMode::CommitMemoryAndProve,
+        ) {
+            ProvingResult::Proofs(result) => result,
+            ProvingResult::MemoryCommitments(_) => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // lui x5, 0x400
+    const SET_X5_TO_RAM_ADDRESS: u32 = 0x004002b7;
+    // addi x10, x0, 42
+    const SET_X10: u32 = 0x02a00513;
+    // sw x10, 0(x5)
+    const STORE_X10: u32 = 0x00a2a023;
+    // lw x11, 0(x5)
+    const LOAD_X11: u32 = 0x0002a583;
+    // jal x0, 0
+    const HALT: u32 = 0x0000006f;
+
+    fn verify_main_circuit_proof(proof: &Proof) {
+        let mut oracle_data = vec![];
+        oracle_data
+            .extend(verifier_common::proof_flattener::flatten_proof_for_skeleton(proof, true));
+        for query in proof.queries.iter() {
+            oracle_data.extend(verifier_common::proof_flattener::flatten_query(query));
+        }
+        verifier_common::prover::nd_source_std::set_iterator(oracle_data.into_iter());
+
+        // panics if proof is invalid
+        unsafe {
+            risc_v_cycles_verifier::verify(
+                std::mem::MaybeUninit::uninit().assume_init_mut(),
+                &mut verifier_common::ProofPublicInputs::uninit(),
+            )
+        };
+    }
+
+    #[test]
+    fn test_batch_finishes_on_panic() {
+        let batch_limiter = BatchLimiter::new(1);
+        let result = std::panic::catch_unwind(|| {
+            let _active_batch = batch_limiter.start_batch();
+            panic!("batch failed");
+        });
+        assert!(result.is_err());
+        // would wait forever if the failed batch was still active
+        let _active_batch = batch_limiter.start_batch();
+    }
+
+    #[test]
+    fn test_commit_memory_and_prove() {
+        let mut bytecode = vec![SET_X5_TO_RAM_ADDRESS, SET_X10, STORE_X10, LOAD_X11, HALT];
+        setups::pad_bytecode_for_proving(&mut bytecode);
+
+        let prover = CpuExecutionProver::new(
+            1,
+            vec![ExecutableBinary {
+                key: 0,
+                circuit_type: MainCircuitType::RiscVCycles,
+                bytecode,
+            }],
+        );
+        let (final_register_values, main_proofs, delegation_proofs) =
+            ExecutionProverBackend::commit_memory_and_prove(
+                &prover,
+                0,
+                &0,
+                1,
+                QuasiUARTSource::default(),
+            );
+
+        assert_eq!(final_register_values[10].value, 42);
+        assert_eq!(final_register_values[11].value, 42);
+        assert_eq!(main_proofs.len(), 1);
+        assert!(delegation_proofs
+            .iter()
+            .all(|(_, proofs)| proofs.is_empty()));
+
+        for proof in main_proofs.iter() {
+            verify_main_circuit_proof(proof);
+        }
+    }
+}
@@ -14,6 +14,7 @@ use prover::tracers::oracles::main_risc_v_circuit::MainRiscVOracle;
 pub use setups;
 
 use merkle_trees::DefaultTreeConstructor;
+use merkle_trees::MerkleTreeCapVarLength;
 use prover::cs::definitions::ColumnSet;
 use prover::definitions::produce_register_contribution_into_memory_accumulator_raw;
 use prover::definitions::*;
@@ -33,8 +34,11 @@ use risc_v_simulator::cycle::MachineConfig;
 use setups::*;
 use trace_and_split::*;
 
+pub mod cost_estimate;
+pub mod execution_prover;
 #[cfg(feature = "gpu")]
 pub mod gpu;
+pub mod mutation_testing;
 
 pub const NUM_QUERIES: usize = 53;
 pub const POW_BITS: u32 = 28;
@@ -218,6 +222,66 @@ pub fn trace_execution_for_gpu<
     )
 }
 
+/// Execution traced and split into the main and delegation circuits chunks
+pub struct TracedExecution<C: MachineConfig> {
+    pub main_circuits_witness: Vec<CycleData<C>>,
+    // number of chunks at the start that use empty lazy init and teardown
+    pub num_paddings: usize,
+    pub inits_and_teardowns: Vec<ShuffleRamSetupAndTeardown>,
+    pub delegation_circuits_witness: HashMap<u16, Vec<DelegationWitness>>,
+    pub final_register_values: Vec<FinalRegisterValue>,
+    padding_shuffle_ram_inits_and_teardowns: ShuffleRamSetupAndTeardown,
+}
+
+impl<C: MachineConfig> TracedExecution<C> {
+    pub fn trace<ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>>(
+        num_instances_upper_bound: usize,
+        bytecode: &[u32],
+        non_determinism: ND,
+        worker: &worker::Worker,
+    ) -> Self {
+        let (
+            main_circuits_witness,
+            (num_paddings, inits_and_teardowns),
+            delegation_circuits_witness,
+            final_register_values,
+        ) = trace_execution_for_gpu::<ND, C, Global>(
+            num_instances_upper_bound,
+            bytecode,
+            non_determinism,
+            worker,
+        );
+        let padding_shuffle_ram_inits_and_teardowns = ShuffleRamSetupAndTeardown {
+            lazy_init_data: vec![Default::default(); setups::num_cycles_for_machine::<C>()],
+        };
+
+        Self {
+            main_circuits_witness,
+            num_paddings,
+            inits_and_teardowns,
+            delegation_circuits_witness,
+            final_register_values,
+            padding_shuffle_ram_inits_and_teardowns,
+        }
+    }
+
+    fn shuffle_rams(&self, circuit_sequence: usize) -> &ShuffleRamSetupAndTeardown {
+        if circuit_sequence < self.num_paddings {
+            &self.padding_shuffle_ram_inits_and_teardowns
+        } else {
+            &self.inits_and_teardowns[circuit_sequence - self.num_paddings]
+        }
+    }
+
+    fn delegation_types(&self) -> Vec<u16> {
+        let mut delegation_types: Vec<_> =
+            self.delegation_circuits_witness.keys().copied().collect();
+        delegation_types.sort();
+
+        delegation_types
+    }
+}
+
 pub fn prove_image_execution_for_machine_with_gpu_tracers<
     ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>,
     C: MachineConfig,
@@ -230,44 +294,135 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
     delegation_circuits_precomputations: &[(u32, DelegationCircuitPrecomputations<A>)],
     worker: &worker::Worker,
 ) -> (Vec<Proof>, Vec<(u32, Vec<Proof>)>, Vec<FinalRegisterValue>) {
-    let cycles_per_circuit = setups::num_cycles_for_machine::<C>();
-    let max_cycles_to_run = num_instances_upper_bound * cycles_per_circuit;
+    let traced_execution =
+        TracedExecution::<C>::trace(num_instances_upper_bound, bytecode, non_determinism, worker);
 
-    let (
-        main_circuits_witness,
-        inits_and_teardowns,
-        delegation_circuits_witness,
-        final_register_values,
-    ) = trace_execution_for_gpu::<ND, C, A>(max_cycles_to_run, bytecode, non_determinism, worker);
+    let (memory_trees, delegation_memory_trees) = commit_memory_for_traced_execution(
+        &traced_execution,
+        risc_v_circuit_precomputations,
+        delegation_circuits_precomputations,
+        worker,
+    );
 
-    let (num_paddings, inits_and_teardowns) = inits_and_teardowns;
+    #[cfg(feature = "debug_logs")]
+    println!("Will create FS transformation challenge for memory and delegation arguments");
 
-    let should_dump_witness = std::env::var(DUMP_WITNESS_VAR)
-        .map(|el| el.parse::<u32>().unwrap_or(0) == 1)
-        .unwrap_or(false);
+    let setup_caps = DefaultTreeConstructor::dump_caps(&risc_v_circuit_precomputations.setup.trees);
+
+    // commit memory challenges
+    let memory_challenges_seed = fs_transform_for_memory_and_delegation_arguments(
+        &setup_caps,
+        &traced_execution.final_register_values,
+        &memory_trees,
+        &delegation_memory_trees,
+    );
 
+    #[cfg(feature = "debug_logs")]
+    println!(
+        "FS transformation memory seed is {:?}",
+        memory_challenges_seed
+    );
+
+    let external_challenges =
+        ExternalChallenges::draw_from_transcript_seed(memory_challenges_seed, true);
+
+    #[cfg(feature = "debug_logs")]
+    println!("External challenges = {:?}", external_challenges);
+
+    let (main_proofs, delegation_proofs) = prove_traced_execution(
+        &traced_execution,
+        risc_v_circuit_precomputations,
+        delegation_circuits_precomputations,
+        external_challenges,
+        worker,
+    );
+
+    let input = traced_execution
+        .final_register_values
+        .iter()
+        .map(|el| (el.value, split_timestamp(el.last_access_timestamp)))
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    let mut memory_grand_product = produce_register_contribution_into_memory_accumulator_raw(
+        &input,
+        external_challenges
+            .memory_argument
+            .memory_argument_linearization_challenges,
+        external_challenges.memory_argument.memory_argument_gamma,
+    );
+    let mut delegation_argument_sum = Mersenne31Quartic::ZERO;
+    for proof in main_proofs.iter() {
+        memory_grand_product.mul_assign(&proof.memory_grand_product_accumulator);
+        delegation_argument_sum.add_assign(&proof.delegation_argument_accumulator.unwrap());
+    }
+    for (_, proofs) in delegation_proofs.iter() {
+        for proof in proofs.iter() {
+            memory_grand_product.mul_assign(&proof.memory_grand_product_accumulator);
+            delegation_argument_sum.sub_assign(&proof.delegation_argument_accumulator.unwrap());
+        }
+    }
+
+    assert_eq!(memory_grand_product, Mersenne31Quartic::ONE);
+    assert_eq!(delegation_argument_sum, Mersenne31Quartic::ZERO);
+
+    let aux_memory_trees: Vec<_> = main_proofs
+        .iter()
+        .map(|proof| proof.memory_tree_caps.clone())
+        .collect();
+    let aux_delegation_memory_trees: Vec<_> = delegation_proofs
+        .iter()
+        .map(|(delegation_type, proofs)| {
+            (
+                *delegation_type,
+                proofs
+                    .iter()
+                    .map(|proof| proof.memory_tree_caps.clone())
+                    .collect(),
+            )
+        })
+        .collect();
+
+    // compare challenge
+    let aux_memory_challenges_seed = fs_transform_for_memory_and_delegation_arguments(
+        &setup_caps,
+        &traced_execution.final_register_values,
+        &aux_memory_trees,
+        &aux_delegation_memory_trees,
+    );
+
+    assert_eq!(aux_memory_challenges_seed, memory_challenges_seed);
+
+    (
+        main_proofs,
+        delegation_proofs,
+        traced_execution.final_register_values,
+    )
+}
+
+/// Commits memory trees of all the main and delegation circuits of the traced execution
+pub fn commit_memory_for_traced_execution<C: MachineConfig, A: GoodAllocator>(
+    traced_execution: &TracedExecution<C>,
+    risc_v_circuit_precomputations: &MainCircuitPrecomputations<C, A>,
+    delegation_circuits_precomputations: &[(u32, DelegationCircuitPrecomputations<A>)],
+    worker: &worker::Worker,
+) -> (
+    Vec<Vec<MerkleTreeCapVarLength>>,
+    Vec<(u32, Vec<Vec<MerkleTreeCapVarLength>>)>,
+) {
     #[cfg(feature = "timing_logs")]
     let now = std::time::Instant::now();
     let mut memory_trees = vec![];
     let mut previous_aux: Option<WitnessEvaluationAuxData> = None;
-    let padding_shuffle_ram_inits_and_teardowns = ShuffleRamSetupAndTeardown {
-        lazy_init_data: vec![
-            Default::default();
-            risc_v_circuit_precomputations.compiled_circuit.trace_len - 1
-        ],
-    };
 
     // commit memory trees
-    for (circuit_sequence, witness_chunk) in main_circuits_witness.iter().enumerate() {
-        let shuffle_rams = if circuit_sequence < num_paddings {
-            &padding_shuffle_ram_inits_and_teardowns
-        } else {
-            &inits_and_teardowns[circuit_sequence - num_paddings]
-        };
+    for (circuit_sequence, witness_chunk) in
+        traced_execution.main_circuits_witness.iter().enumerate()
+    {
         let (caps, aux_data) = commit_memory_tree_for_riscv_circuit_using_gpu_tracer(
             &risc_v_circuit_precomputations.compiled_circuit,
             witness_chunk,
-            shuffle_rams,
+            traced_execution.shuffle_rams(circuit_sequence),
             circuit_sequence,
             &risc_v_circuit_precomputations.twiddles,
             &risc_v_circuit_precomputations.lde_precomputations,
@@ -300,19 +455,17 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
     #[cfg(feature = "timing_logs")]
     println!(
         "=== Commitment for {} RISC-V circuits memory trees took {:?}",
-        main_circuits_witness.len(),
+        traced_execution.main_circuits_witness.len(),
         now.elapsed()
     );
 
     // same for delegation circuits
+    #[cfg(feature = "timing_logs")]
     let now = std::time::Instant::now();
     let mut delegation_memory_trees = vec![];
 
-    let mut delegation_types: Vec<_> = delegation_circuits_witness.keys().copied().collect();
-    delegation_types.sort();
-
-    for delegation_type in delegation_types.iter() {
-        let els = &delegation_circuits_witness[&delegation_type];
+    for delegation_type in traced_execution.delegation_types().iter() {
+        let els = &traced_execution.delegation_circuits_witness[&delegation_type];
         if els.is_empty() {
             continue;
         }
@@ -341,51 +494,27 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
     #[cfg(feature = "timing_logs")]
     println!(
         "=== Commitment for {} delegation circuits memory trees took {:?}",
-        delegation_circuits_witness.len(),
+        traced_execution.delegation_circuits_witness.len(),
         now.elapsed()
     );
 
-    #[cfg(feature = "debug_logs")]
-    println!("Will create FS transformation challenge for memory and delegation arguments");
-
-    let setup_caps = DefaultTreeConstructor::dump_caps(&risc_v_circuit_precomputations.setup.trees);
-
-    // commit memory challenges
-    let memory_challenges_seed = fs_transform_for_memory_and_delegation_arguments(
-        &setup_caps,
-        &final_register_values,
-        &memory_trees,
-        &delegation_memory_trees,
-    );
-
-    #[cfg(feature = "debug_logs")]
-    println!(
-        "FS transformation memory seed is {:?}",
-        memory_challenges_seed
-    );
-
-    let external_challenges =
-        ExternalChallenges::draw_from_transcript_seed(memory_challenges_seed, true);
-
-    #[cfg(feature = "debug_logs")]
-    println!("External challenges = {:?}", external_challenges);
+    (memory_trees, delegation_memory_trees)
+}
 
-    let input = final_register_values
-        .iter()
-        .map(|el| (el.value, split_timestamp(el.last_access_timestamp)))
-        .collect::<Vec<_>>()
-        .try_into()
-        .unwrap();
-    let mut memory_grand_product = produce_register_contribution_into_memory_accumulator_raw(
-        &input,
-        external_challenges
-            .memory_argument
-            .memory_argument_linearization_challenges,
-        external_challenges.memory_argument.memory_argument_gamma,
-    );
-    let mut delegation_argument_sum = Mersenne31Quartic::ZERO;
+/// Proves all the main and delegation circuits of the traced execution with the given challenges
+pub fn prove_traced_execution<C: MachineConfig, A: GoodAllocator>(
+    traced_execution: &TracedExecution<C>,
+    risc_v_circuit_precomputations: &MainCircuitPrecomputations<C, A>,
+    delegation_circuits_precomputations: &[(u32, DelegationCircuitPrecomputations<A>)],
+    external_challenges: ExternalChallenges,
+    worker: &worker::Worker,
+) -> (Vec<Proof>, Vec<(u32, Vec<Proof>)>) {
+    let cycles_per_circuit = setups::num_cycles_for_machine::<C>();
+    let main_circuits_witness = &traced_execution.main_circuits_witness;
 
-    let mut aux_memory_trees = vec![];
+    let should_dump_witness = std::env::var(DUMP_WITNESS_VAR)
+        .map(|el| el.parse::<u32>().unwrap_or(0) == 1)
+        .unwrap_or(false);
 
     println!(
         "Producing proofs for main RISC-V circuit, {} proofs in total",
@@ -397,12 +526,7 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
     // now prove one by one
     let mut main_proofs = vec![];
     for (circuit_sequence, witness_chunk) in main_circuits_witness.iter().enumerate() {
-        let shuffle_rams = if circuit_sequence < num_paddings {
-            &padding_shuffle_ram_inits_and_teardowns
-        } else {
-            &inits_and_teardowns[circuit_sequence - num_paddings]
-        };
-
+        let shuffle_rams = traced_execution.shuffle_rams(circuit_sequence);
         if should_dump_witness {
             bincode_serialize_to_file(
                 shuffle_rams,
@@ -498,13 +622,6 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
         //     serialize_to_file(&proof, &format!("riscv_proof_{}", circuit_sequence));
         // }
 
-        memory_grand_product.mul_assign(&proof.memory_grand_product_accumulator);
-        delegation_argument_sum.add_assign(&proof.delegation_argument_accumulator.unwrap());
-
-        // assert_eq!(&proof.memory_tree_caps, &memory_trees[circuit_sequence]);
-
-        aux_memory_trees.push(proof.memory_tree_caps.clone());
-
         main_proofs.push(proof);
     }
 
@@ -518,13 +635,11 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
     }
 
     // all the same for delegation circuit
-    let mut aux_delegation_memory_trees = vec![];
     let mut delegation_proofs = vec![];
     let delegation_proving_start = std::time::Instant::now();
     let mut delegation_proofs_count = 0u32;
-    // commit memory trees
-    for delegation_type in delegation_types.iter() {
-        let els = &delegation_circuits_witness[&delegation_type];
+    for delegation_type in traced_execution.delegation_types().iter() {
+        let els = &traced_execution.delegation_circuits_witness[&delegation_type];
         println!(
             "Producing proofs for delegation circuit type {}, {} proofs in total",
             delegation_type,
@@ -540,7 +655,6 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
             .position(|el| el.0 == *delegation_type as u32)
             .unwrap();
         let prec = &delegation_circuits_precomputations[idx].1;
-        let mut per_tree_set = vec![];
 
         let mut per_delegation_type_proofs = vec![];
         for (_circuit_idx, el) in els.iter().enumerate() {
@@ -627,15 +741,9 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
                 now.elapsed()
             );
 
-            memory_grand_product.mul_assign(&proof.memory_grand_product_accumulator);
-            delegation_argument_sum.sub_assign(&proof.delegation_argument_accumulator.unwrap());
-
-            per_tree_set.push(proof.memory_tree_caps.clone());
-
             per_delegation_type_proofs.push(proof);
         }
 
-        aux_delegation_memory_trees.push((*delegation_type as u32, per_tree_set));
         delegation_proofs.push((*delegation_type as u32, per_delegation_type_proofs));
     }
 
@@ -648,25 +756,7 @@ pub fn prove_image_execution_for_machine_with_gpu_tracers<
         )
     }
 
-    assert_eq!(memory_grand_product, Mersenne31Quartic::ONE);
-    assert_eq!(delegation_argument_sum, Mersenne31Quartic::ZERO);
-
-    // assert_eq!(&aux_memory_trees, &memory_trees);
-    // assert_eq!(&aux_delegation_memory_trees, &delegation_memory_trees);
-
-    let setup_caps = DefaultTreeConstructor::dump_caps(&risc_v_circuit_precomputations.setup.trees);
-
-    // compare challenge
-    let aux_memory_challenges_seed = fs_transform_for_memory_and_delegation_arguments(
-        &setup_caps,
-        &final_register_values,
-        &aux_memory_trees,
-        &aux_delegation_memory_trees,
-    );
-
-    assert_eq!(aux_memory_challenges_seed, memory_challenges_seed);
-
-    (main_proofs, delegation_proofs, final_register_values)
+    (main_proofs, delegation_proofs)
 }
 
 pub fn create_circuit_setup<A: GoodAllocator, B: GoodAllocator, const N: usize>(
@@ -732,4 +822,48 @@ mod test {
             &worker,
         );
     }
+
+    #[test]
+    fn test_mutation_testing_full_machine() {
+        let path = "./app.bin";
+        let mut file = std::fs::File::open(path).expect("must open provided file");
+        let mut buffer = vec![];
+        file.read_to_end(&mut buffer).expect("must read the file");
+        let mut binary = vec![];
+        for el in buffer.as_chunks::<4>().0 {
+            binary.push(u32::from_le_bytes(*el));
+        }
+        setups::pad_bytecode_for_proving(&mut binary);
+
+        let worker = worker::Worker::new_with_num_threads(8);
+
+        let delegation_precomputations =
+            setups::all_delegation_circuits_precomputations::<Global, Global>(&worker);
+        let main_circuit_precomputations =
+            setups::get_main_riscv_circuit_setup::<Global, Global>(&binary, &worker);
+
+        let traced_execution = TracedExecution::<IMStandardIsaConfig>::trace(
+            1,
+            &binary,
+            QuasiUARTSource::default(),
+            &worker,
+        );
+        let reports = crate::mutation_testing::run_mutation_testing_for_traced_execution(
+            &traced_execution,
+            &main_circuit_precomputations,
+            &delegation_precomputations,
+            4,
+            &worker,
+        );
+
+        for (instance, report) in reports.iter() {
+            println!("{:?}: {}", instance, report);
+            assert!(report.num_mutations > 0);
+            // any change of multiplicity must break the lookup argument
+            assert!(report
+                .survived
+                .iter()
+                .all(|el| !matches!(el.target, MutationTarget::LookupMultiplicity { .. })));
+        }
+    }
 }
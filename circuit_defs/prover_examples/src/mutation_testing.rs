@@ -0,0 +1,108 @@
+//! Witness mutation testing for all the circuit instances of the traced execution,
+//! see `prover::run_mutation_testing` for what is checked for every perturbed cell
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitInstance {
+    MainRiscV {
+        circuit_sequence: usize,
+    },
+    Delegation {
+        delegation_type: u16,
+        circuit_idx: usize,
+    },
+}
+
+/// Evaluates witness for every main and delegation circuit of the `traced_execution`, and perturbs
+/// `num_rows_per_circuit` rows spread over each of them. Delegation circuits are only covered if
+/// the execution makes the corresponding delegation calls
+pub fn run_mutation_testing_for_traced_execution<C: MachineConfig, A: GoodAllocator>(
+    traced_execution: &TracedExecution<C>,
+    risc_v_circuit_precomputations: &MainCircuitPrecomputations<C, A>,
+    delegation_circuits_precomputations: &[(u32, DelegationCircuitPrecomputations<A>)],
+    num_rows_per_circuit: usize,
+    worker: &worker::Worker,
+) -> Vec<(CircuitInstance, MutationTestingReport)> {
+    let cycles_per_circuit = setups::num_cycles_for_machine::<C>();
+    let mut reports = vec![];
+
+    for (circuit_sequence, witness_chunk) in
+        traced_execution.main_circuits_witness.iter().enumerate()
+    {
+        let oracle = MainRiscVOracle {
+            cycle_data: witness_chunk,
+        };
+        let witness_trace = evaluate_witness(
+            &risc_v_circuit_precomputations.compiled_circuit,
+            risc_v_circuit_precomputations.witness_eval_fn_for_gpu_tracer,
+            cycles_per_circuit,
+            &oracle,
+            &traced_execution
+                .shuffle_rams(circuit_sequence)
+                .lazy_init_data,
+            &risc_v_circuit_precomputations.table_driver,
+            circuit_sequence,
+            worker,
+            A::default(),
+        );
+        let config = MutationTestingConfig::spread_over_trace(
+            witness_trace.exec_trace.len(),
+            num_rows_per_circuit,
+        );
+        let report = run_mutation_testing(
+            &risc_v_circuit_precomputations.compiled_circuit,
+            &risc_v_circuit_precomputations.table_driver,
+            &witness_trace,
+            circuit_sequence,
+            &config,
+        );
+        reports.push((CircuitInstance::MainRiscV { circuit_sequence }, report));
+    }
+
+    for delegation_type in traced_execution.delegation_types().into_iter() {
+        let idx = delegation_circuits_precomputations
+            .iter()
+            .position(|el| el.0 == delegation_type as u32)
+            .unwrap();
+        let prec = &delegation_circuits_precomputations[idx].1;
+
+        for (circuit_idx, el) in traced_execution.delegation_circuits_witness[&delegation_type]
+            .iter()
+            .enumerate()
+        {
+            let oracle = DelegationCircuitOracle { cycle_data: el };
+            let witness_trace = evaluate_witness(
+                &prec.compiled_circuit.compiled_circuit,
+                prec.witness_eval_fn_for_gpu_tracer,
+                prec.compiled_circuit.num_requests_per_circuit,
+                &oracle,
+                &[],
+                &prec.compiled_circuit.table_driver,
+                0,
+                worker,
+                A::default(),
+            );
+            let config = MutationTestingConfig::spread_over_trace(
+                witness_trace.exec_trace.len(),
+                num_rows_per_circuit,
+            );
+            let report = run_mutation_testing(
+                &prec.compiled_circuit.compiled_circuit,
+                &prec.compiled_circuit.table_driver,
+                &witness_trace,
+                0,
+                &config,
+            );
+            reports.push((
+                CircuitInstance::Delegation {
+                    delegation_type,
+                    circuit_idx,
+                },
+                report,
+            ));
+        }
+    }
+
+    reports
+}
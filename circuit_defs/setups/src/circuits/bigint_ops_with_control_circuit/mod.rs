@@ -13,17 +13,16 @@ pub fn get_bigint_with_control_circuit_setup<A: GoodAllocator, B: GoodAllocator>
         bigint_with_control::LDE_SOURCE_COSETS,
         &worker,
     );
-    let setup =
-        SetupPrecomputations::<DEFAULT_TRACE_PADDING_MULTIPLE, A, DefaultTreeConstructor>::from_tables_and_trace_len(
-            &table_driver,
-            bigint_with_control::DOMAIN_SIZE,
-            &machine.compiled_circuit.setup_layout,
-            &twiddles,
-            &lde_precomputations,
-            bigint_with_control::LDE_FACTOR,
-            bigint_with_control::TREE_CAP_SIZE,
-            &worker,
-        );
+    let setup = disk_cache::setup_precomputations_with_cache(
+        &table_driver,
+        bigint_with_control::DOMAIN_SIZE,
+        &machine.compiled_circuit.setup_layout,
+        &twiddles,
+        &lde_precomputations,
+        bigint_with_control::LDE_FACTOR,
+        bigint_with_control::TREE_CAP_SIZE,
+        &worker,
+    );
 
     DelegationCircuitPrecomputations {
         trace_len: bigint_with_control::DOMAIN_SIZE,
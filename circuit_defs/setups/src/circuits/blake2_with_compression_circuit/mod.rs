@@ -13,17 +13,16 @@ pub fn get_blake2_with_compression_circuit_setup<A: GoodAllocator, B: GoodAlloca
         blake2_with_compression::LDE_SOURCE_COSETS,
         &worker,
     );
-    let setup =
-        SetupPrecomputations::<DEFAULT_TRACE_PADDING_MULTIPLE, A, DefaultTreeConstructor>::from_tables_and_trace_len(
-            &table_driver,
-            blake2_with_compression::DOMAIN_SIZE,
-            &machine.compiled_circuit.setup_layout,
-            &twiddles,
-            &lde_precomputations,
-            blake2_with_compression::LDE_FACTOR,
-            blake2_with_compression::TREE_CAP_SIZE,
-            &worker,
-        );
+    let setup = disk_cache::setup_precomputations_with_cache(
+        &table_driver,
+        blake2_with_compression::DOMAIN_SIZE,
+        &machine.compiled_circuit.setup_layout,
+        &twiddles,
+        &lde_precomputations,
+        blake2_with_compression::LDE_FACTOR,
+        blake2_with_compression::TREE_CAP_SIZE,
+        &worker,
+    );
 
     DelegationCircuitPrecomputations {
         trace_len: blake2_with_compression::DOMAIN_SIZE,
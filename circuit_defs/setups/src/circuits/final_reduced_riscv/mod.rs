@@ -17,17 +17,16 @@ pub fn get_final_reduced_riscv_circuit_setup<A: GoodAllocator, B: GoodAllocator>
         ::final_reduced_risc_v_machine::LDE_SOURCE_COSETS,
         &worker,
     );
-    let setup =
-        SetupPrecomputations::<DEFAULT_TRACE_PADDING_MULTIPLE, A, DefaultTreeConstructor>::from_tables_and_trace_len(
-            &table_driver,
-            ::final_reduced_risc_v_machine::DOMAIN_SIZE,
-            &machine.setup_layout,
-            &twiddles,
-            &lde_precomputations,
-            ::final_reduced_risc_v_machine::LDE_FACTOR,
-            ::final_reduced_risc_v_machine::TREE_CAP_SIZE,
-            &worker,
-        );
+    let setup = disk_cache::setup_precomputations_with_cache(
+        &table_driver,
+        ::final_reduced_risc_v_machine::DOMAIN_SIZE,
+        &machine.setup_layout,
+        &twiddles,
+        &lde_precomputations,
+        ::final_reduced_risc_v_machine::LDE_FACTOR,
+        ::final_reduced_risc_v_machine::TREE_CAP_SIZE,
+        &worker,
+    );
 
     MainCircuitPrecomputations {
         compiled_circuit: machine,
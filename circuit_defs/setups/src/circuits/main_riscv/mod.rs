@@ -16,17 +16,16 @@ pub fn get_main_riscv_circuit_setup<A: GoodAllocator, B: GoodAllocator>(
         ::risc_v_cycles::LDE_SOURCE_COSETS,
         &worker,
     );
-    let setup =
-        SetupPrecomputations::<DEFAULT_TRACE_PADDING_MULTIPLE, A, DefaultTreeConstructor>::from_tables_and_trace_len(
-            &table_driver,
-            ::risc_v_cycles::DOMAIN_SIZE,
-            &machine.setup_layout,
-            &twiddles,
-            &lde_precomputations,
-            ::risc_v_cycles::LDE_FACTOR,
-            ::risc_v_cycles::TREE_CAP_SIZE,
-            &worker,
-        );
+    let setup = disk_cache::setup_precomputations_with_cache(
+        &table_driver,
+        ::risc_v_cycles::DOMAIN_SIZE,
+        &machine.setup_layout,
+        &twiddles,
+        &lde_precomputations,
+        ::risc_v_cycles::LDE_FACTOR,
+        ::risc_v_cycles::TREE_CAP_SIZE,
+        &worker,
+    );
 
     MainCircuitPrecomputations {
         compiled_circuit: machine,
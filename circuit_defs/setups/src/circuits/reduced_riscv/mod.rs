@@ -16,17 +16,16 @@ pub fn get_reduced_riscv_circuit_setup<A: GoodAllocator, B: GoodAllocator>(
         ::reduced_risc_v_machine::LDE_SOURCE_COSETS,
         &worker,
     );
-    let setup =
-        SetupPrecomputations::<DEFAULT_TRACE_PADDING_MULTIPLE, A, DefaultTreeConstructor>::from_tables_and_trace_len(
-            &table_driver,
-            ::reduced_risc_v_machine::DOMAIN_SIZE,
-            &machine.setup_layout,
-            &twiddles,
-            &lde_precomputations,
-            ::reduced_risc_v_machine::LDE_FACTOR,
-            ::reduced_risc_v_machine::TREE_CAP_SIZE,
-            &worker,
-        );
+    let setup = disk_cache::setup_precomputations_with_cache(
+        &table_driver,
+        ::reduced_risc_v_machine::DOMAIN_SIZE,
+        &machine.setup_layout,
+        &twiddles,
+        &lde_precomputations,
+        ::reduced_risc_v_machine::LDE_FACTOR,
+        ::reduced_risc_v_machine::TREE_CAP_SIZE,
+        &worker,
+    );
 
     MainCircuitPrecomputations {
         compiled_circuit: machine,
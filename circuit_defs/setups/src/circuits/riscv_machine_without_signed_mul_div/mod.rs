@@ -18,17 +18,16 @@ pub fn get_riscv_without_signed_mul_div_circuit_setup<A: GoodAllocator, B: GoodA
         ::machine_without_signed_mul_div::LDE_SOURCE_COSETS,
         &worker,
     );
-    let setup =
-        SetupPrecomputations::<DEFAULT_TRACE_PADDING_MULTIPLE, A, DefaultTreeConstructor>::from_tables_and_trace_len(
-            &table_driver,
-            ::machine_without_signed_mul_div::DOMAIN_SIZE,
-            &machine.setup_layout,
-            &twiddles,
-            &lde_precomputations,
-            ::machine_without_signed_mul_div::LDE_FACTOR,
-            ::machine_without_signed_mul_div::TREE_CAP_SIZE,
-            &worker,
-        );
+    let setup = disk_cache::setup_precomputations_with_cache(
+        &table_driver,
+        ::machine_without_signed_mul_div::DOMAIN_SIZE,
+        &machine.setup_layout,
+        &twiddles,
+        &lde_precomputations,
+        ::machine_without_signed_mul_div::LDE_FACTOR,
+        ::machine_without_signed_mul_div::TREE_CAP_SIZE,
+        &worker,
+    );
 
     MainCircuitPrecomputations {
         compiled_circuit: machine,
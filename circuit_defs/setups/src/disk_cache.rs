@@ -0,0 +1,583 @@
+//! Content-addressed on-disk cache of the setup precomputations.
+//!
+//! Computing setup LDEs and Merkle trees is the most expensive part of circuit precomputations,
+//! so if cache directory is configured (either by `set_setup_cache_dir` or by the
+//! `AIRBENDER_SETUP_CACHE_DIR` environment variable) we store them on disk and load them on the next run.
+//! Entries are keyed by the Blake2s hash of everything the setup depends on: trace length, LDE parameters,
+//! hash family of the trees, setup layout of the compiled circuit and contents of all the tables (that include the bytecode for the main circuits).
+//! Every entry also contains the hash of its payload, that is checked on load, and any entry that is corrupted
+//! or incompatible is just recomputed and overwritten. Twiddles, LDE precomputations and compiled circuits
+//! are cheap to produce and are always recomputed.
+
+use super::*;
+use cs::definitions::SetupLayout;
+use prover::merkle_trees::blake2s_for_everything_tree::Blake2sU32MerkleTreeWithCap;
+use prover::merkle_trees::blake2s_for_leafs_poseidon2_for_nodes_tree::Blake2sU32ForLeafsPoseidon2ForNodesTree;
+use prover::merkle_trees::poseidon2_for_everything_tree::Poseidon2MerkleTreeWithCap;
+use prover::merkle_trees::MerkleTreeConstructor;
+use prover::prover_stages::CosetBoundTracePart;
+use prover::trace_holder::RowMajorTrace;
+use prover::transcript::blake2s_u32::BLAKE2S_DIGEST_SIZE_U32_WORDS;
+use prover::transcript::Blake2sBufferingTranscript;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub const SETUP_CACHE_DIR_ENV_VAR: &str = "AIRBENDER_SETUP_CACHE_DIR";
+
+const MAGIC: u32 = 0x53455455; // "SETU"
+                               // bump if layout of the entries or the way setup is computed changes
+const FORMAT_VERSION: u32 = 1;
+// payload is hashed in chunks of fixed size in parallel, so the digest doesn't depend on the number of threads
+const DIGEST_CHUNK_SIZE_U32_WORDS: usize = 1 << 20;
+
+pub type CacheKey = [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS];
+
+static SETUP_CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Sets the directory of the on-disk setup cache, it takes precedence over the `AIRBENDER_SETUP_CACHE_DIR`
+/// environment variable. Passing `None` falls back to the environment variable.
+pub fn set_setup_cache_dir(dir: Option<PathBuf>) {
+    *SETUP_CACHE_DIR.write().unwrap() = dir;
+}
+
+/// Directory of the on-disk setup cache, or `None` if caching is disabled
+pub fn setup_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = SETUP_CACHE_DIR.read().unwrap().clone() {
+        return Some(dir);
+    }
+
+    std::env::var_os(SETUP_CACHE_DIR_ENV_VAR).map(PathBuf::from)
+}
+
+/// Digest of the Merkle tree, that is laid out in memory as 8 `u32` words
+pub unsafe trait DigestWords: 'static + Clone + Copy + Send + Sync {
+    const ZERO: Self;
+}
+
+unsafe impl DigestWords for [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS] {
+    const ZERO: Self = [0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS];
+}
+
+unsafe impl DigestWords for [Mersenne31Field; BLAKE2S_DIGEST_SIZE_U32_WORDS] {
+    const ZERO: Self = [Mersenne31Field(0); BLAKE2S_DIGEST_SIZE_U32_WORDS];
+}
+
+/// Merkle trees that can be stored in the setup cache: cap size, leaf hashes and node hashes layer by layer
+pub trait CacheableTree: MerkleTreeConstructor {
+    type LeafDigest: DigestWords;
+    type NodeDigest: DigestWords;
+
+    fn cap_size(&self) -> usize;
+    fn leaf_hashes(&self) -> &[Self::LeafDigest];
+    fn node_layers(&self) -> &[Vec<Self::NodeDigest>];
+    fn from_parts(
+        cap_size: usize,
+        leaf_hashes: Vec<Self::LeafDigest>,
+        node_layers: Vec<Vec<Self::NodeDigest>>,
+    ) -> Self;
+}
+
+macro_rules! impl_cacheable_tree {
+    ($tree:ident, $leaf_digest:ty, $node_digest:ty) => {
+        impl CacheableTree for $tree<Global> {
+            type LeafDigest = $leaf_digest;
+            type NodeDigest = $node_digest;
+
+            fn cap_size(&self) -> usize {
+                self.cap_size
+            }
+            fn leaf_hashes(&self) -> &[Self::LeafDigest] {
+                &self.leaf_hashes
+            }
+            fn node_layers(&self) -> &[Vec<Self::NodeDigest>] {
+                &self.node_hashes_enumerated_from_leafs
+            }
+            fn from_parts(
+                cap_size: usize,
+                leaf_hashes: Vec<Self::LeafDigest>,
+                node_layers: Vec<Vec<Self::NodeDigest>>,
+            ) -> Self {
+                Self {
+                    cap_size,
+                    leaf_hashes,
+                    node_hashes_enumerated_from_leafs: node_layers,
+                }
+            }
+        }
+    };
+}
+
+impl_cacheable_tree!(
+    Blake2sU32MerkleTreeWithCap,
+    [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+    [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]
+);
+impl_cacheable_tree!(
+    Blake2sU32ForLeafsPoseidon2ForNodesTree,
+    [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+    [Mersenne31Field; BLAKE2S_DIGEST_SIZE_U32_WORDS]
+);
+impl_cacheable_tree!(
+    Poseidon2MerkleTreeWithCap,
+    [Mersenne31Field; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+    [Mersenne31Field; BLAKE2S_DIGEST_SIZE_U32_WORDS]
+);
+
+/// Drop-in replacement for `SetupPrecomputations::from_tables_and_trace_len`, that uses on-disk cache if it's enabled
+pub fn setup_precomputations_with_cache<A: GoodAllocator, T: CacheableTree>(
+    table_driver: &TableDriver<Mersenne31Field>,
+    trace_len: usize,
+    setup_layout: &SetupLayout,
+    twiddles: &Twiddles<Mersenne31Complex, A>,
+    lde_precomputations: &LdePrecomputations<A>,
+    lde_factor: usize,
+    tree_cap_size: usize,
+    worker: &Worker,
+) -> SetupPrecomputations<DEFAULT_TRACE_PADDING_MULTIPLE, A, T> {
+    let compute = || {
+        SetupPrecomputations::<DEFAULT_TRACE_PADDING_MULTIPLE, A, T>::from_tables_and_trace_len(
+            table_driver,
+            trace_len,
+            setup_layout,
+            twiddles,
+            lde_precomputations,
+            lde_factor,
+            tree_cap_size,
+            worker,
+        )
+    };
+
+    let Some(cache_dir) = setup_cache_dir() else {
+        return compute();
+    };
+
+    let key = setup_cache_key::<A, T>(
+        table_driver,
+        trace_len,
+        setup_layout,
+        lde_precomputations,
+        lde_factor,
+        tree_cap_size,
+    );
+    let path = setup_cache_entry_path(&cache_dir, &key);
+    if path.exists() {
+        match load_setup(&path, &key, worker) {
+            Ok(setup) => return setup,
+            Err(err) => println!(
+                "Setup cache entry {} is invalid and will be recomputed: {}",
+                path.display(),
+                err
+            ),
+        }
+    }
+
+    let setup = compute();
+    if let Err(err) = store_setup(&cache_dir, &path, &key, &setup, worker) {
+        println!(
+            "Failed to store setup cache entry {}: {}",
+            path.display(),
+            err
+        );
+    }
+
+    setup
+}
+
+/// Hash of all the parameters the setup precomputations depend on
+pub fn setup_cache_key<A: GoodAllocator, T: MerkleTreeConstructor>(
+    table_driver: &TableDriver<Mersenne31Field>,
+    trace_len: usize,
+    setup_layout: &SetupLayout,
+    lde_precomputations: &LdePrecomputations<A>,
+    lde_factor: usize,
+    tree_cap_size: usize,
+) -> CacheKey {
+    let mut hasher = Blake2sBufferingTranscript::new();
+    hasher.absorb(&[
+        FORMAT_VERSION,
+        DEFAULT_TRACE_PADDING_MULTIPLE as u32,
+        trace_len as u32,
+        lde_factor as u32,
+        tree_cap_size as u32,
+        T::HASH_FAMILY as u32,
+    ]);
+    let source_cosets: Vec<u32> = lde_precomputations
+        .domain_bound_precomputations
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, el)| el.as_ref().map(|_| idx as u32))
+        .collect();
+    hasher.absorb(&[source_cosets.len() as u32]);
+    hasher.absorb(&source_cosets);
+
+    let layout = serde_json::to_vec(setup_layout).unwrap();
+    hasher.absorb(&[layout.len() as u32]);
+    let layout: Vec<u32> = layout
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(word)
+        })
+        .collect();
+    hasher.absorb(&layout);
+
+    let tables = table_driver.dump_tables();
+    hasher.absorb(&[tables.len() as u32]);
+    for row in tables.iter() {
+        hasher.absorb(&row.map(|el| el.0));
+    }
+
+    hasher.finalize().0
+}
+
+fn setup_cache_entry_path(cache_dir: &Path, key: &CacheKey) -> PathBuf {
+    let name: String = key.iter().map(|word| format!("{:08x}", word)).collect();
+    cache_dir.join(format!("{}.setup", name))
+}
+
+fn words_of_trace<A: GoodAllocator>(
+    trace: &RowMajorTrace<Mersenne31Field, DEFAULT_TRACE_PADDING_MULTIPLE, A>,
+) -> &[u32] {
+    let values = trace.as_slice();
+    // Mersenne31Field is transparent over u32
+    unsafe { core::slice::from_raw_parts(values.as_ptr().cast::<u32>(), values.len()) }
+}
+
+fn words_of_trace_mut<A: GoodAllocator>(
+    trace: &mut RowMajorTrace<Mersenne31Field, DEFAULT_TRACE_PADDING_MULTIPLE, A>,
+) -> &mut [u32] {
+    let values = trace.as_slice_mut();
+    unsafe { core::slice::from_raw_parts_mut(values.as_mut_ptr().cast::<u32>(), values.len()) }
+}
+
+fn words_per_digest<D: DigestWords>() -> usize {
+    core::mem::size_of::<D>() / core::mem::size_of::<u32>()
+}
+
+fn words_of_hashes<D: DigestWords>(hashes: &[D]) -> &[u32] {
+    let num_words = hashes.len() * words_per_digest::<D>();
+    unsafe { core::slice::from_raw_parts(hashes.as_ptr().cast::<u32>(), num_words) }
+}
+
+fn words_of_hashes_mut<D: DigestWords>(hashes: &mut [D]) -> &mut [u32] {
+    let num_words = hashes.len() * words_per_digest::<D>();
+    unsafe { core::slice::from_raw_parts_mut(hashes.as_mut_ptr().cast::<u32>(), num_words) }
+}
+
+fn digest_words(words: &[u32], worker: &Worker) -> CacheKey {
+    let num_chunks = words.len().div_ceil(DIGEST_CHUNK_SIZE_U32_WORDS);
+    let mut chunk_digests = vec![[0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]; num_chunks];
+    if num_chunks > 0 {
+        worker.scope(num_chunks, |scope, geometry| {
+            let mut dst = &mut chunk_digests[..];
+            for thread_idx in 0..geometry.len() {
+                let chunk_size = geometry.get_chunk_size(thread_idx);
+                let chunk_start = geometry.get_chunk_start_pos(thread_idx);
+                let (dst_chunk, rest) = dst.split_at_mut(chunk_size);
+                dst = rest;
+
+                Worker::smart_spawn(scope, thread_idx == geometry.len() - 1, move |_| {
+                    for (i, dst) in dst_chunk.iter_mut().enumerate() {
+                        let start = (chunk_start + i) * DIGEST_CHUNK_SIZE_U32_WORDS;
+                        let end = core::cmp::min(start + DIGEST_CHUNK_SIZE_U32_WORDS, words.len());
+                        let mut hasher = Blake2sBufferingTranscript::new();
+                        hasher.absorb(&words[start..end]);
+                        *dst = hasher.finalize().0;
+                    }
+                });
+            }
+        });
+    }
+
+    let mut hasher = Blake2sBufferingTranscript::new();
+    hasher.absorb(&[words.len() as u32]);
+    hasher.absorb(words_of_hashes(&chunk_digests));
+
+    hasher.finalize().0
+}
+
+// Payload consists of a small metadata section and large data sections (traces and tree layers),
+// and its digest is a hash of their individual digests
+struct Payload<'a> {
+    metadata: Vec<u32>,
+    sections: Vec<&'a [u32]>,
+}
+
+impl<'a> Payload<'a> {
+    fn digest(&self, worker: &Worker) -> CacheKey {
+        let mut hasher = Blake2sBufferingTranscript::new();
+        hasher.absorb(&digest_words(&self.metadata, worker));
+        for section in self.sections.iter() {
+            hasher.absorb(&digest_words(section, worker));
+        }
+
+        hasher.finalize().0
+    }
+}
+
+fn payload_for_setup<'a, A: GoodAllocator, T: CacheableTree>(
+    setup: &'a SetupPrecomputations<DEFAULT_TRACE_PADDING_MULTIPLE, A, T>,
+) -> Payload<'a> {
+    let mut metadata = vec![];
+    let mut sections = vec![];
+    metadata.push(setup.ldes.len() as u32);
+    for coset in setup.ldes.iter() {
+        metadata.push(coset.tau.c0.0);
+        metadata.push(coset.tau.c1.0);
+        metadata.push(coset.trace.len() as u32);
+        metadata.push(coset.trace.width() as u32);
+        metadata.push(coset.trace.padded_width as u32);
+        sections.push(words_of_trace(&coset.trace));
+    }
+    metadata.push(setup.trees.len() as u32);
+    for tree in setup.trees.iter() {
+        metadata.push(tree.cap_size() as u32);
+        metadata.push(tree.leaf_hashes().len() as u32);
+        sections.push(words_of_hashes(tree.leaf_hashes()));
+        metadata.push(tree.node_layers().len() as u32);
+        for layer in tree.node_layers().iter() {
+            metadata.push(layer.len() as u32);
+            sections.push(words_of_hashes(layer));
+        }
+    }
+
+    Payload { metadata, sections }
+}
+
+fn write_words(dst: &mut impl Write, words: &[u32]) -> std::io::Result<()> {
+    let bytes =
+        unsafe { core::slice::from_raw_parts(words.as_ptr().cast::<u8>(), words.len() * 4) };
+    dst.write_all(bytes)
+}
+
+fn read_words(src: &mut impl Read, words: &mut [u32]) -> std::io::Result<()> {
+    let bytes = unsafe {
+        core::slice::from_raw_parts_mut(words.as_mut_ptr().cast::<u8>(), words.len() * 4)
+    };
+    src.read_exact(bytes)
+}
+
+fn read_word(src: &mut impl Read) -> std::io::Result<u32> {
+    let mut word = [0u32];
+    read_words(src, &mut word)?;
+
+    Ok(word[0])
+}
+
+fn store_setup<A: GoodAllocator, T: CacheableTree>(
+    cache_dir: &Path,
+    path: &Path,
+    key: &CacheKey,
+    setup: &SetupPrecomputations<DEFAULT_TRACE_PADDING_MULTIPLE, A, T>,
+    worker: &Worker,
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(cache_dir)?;
+    let payload = payload_for_setup(setup);
+    let digest = payload.digest(worker);
+
+    // write into temporary file first, so concurrent readers never see partially written entry
+    let tmp_path = path.with_extension(format!("tmp{}", std::process::id()));
+    {
+        let mut dst = BufWriter::new(File::create(&tmp_path)?);
+        write_words(&mut dst, &[MAGIC, FORMAT_VERSION])?;
+        write_words(&mut dst, key)?;
+        write_words(&mut dst, &digest)?;
+        write_words(&mut dst, &[payload.metadata.len() as u32])?;
+        write_words(&mut dst, &payload.metadata)?;
+        for section in payload.sections.iter() {
+            write_words(&mut dst, section)?;
+        }
+        dst.flush()?;
+    }
+    std::fs::rename(&tmp_path, path)
+}
+
+fn invalid_data(description: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, description)
+}
+
+fn load_setup<A: GoodAllocator, T: CacheableTree>(
+    path: &Path,
+    key: &CacheKey,
+    worker: &Worker,
+) -> std::io::Result<SetupPrecomputations<DEFAULT_TRACE_PADDING_MULTIPLE, A, T>> {
+    let mut src = BufReader::new(File::open(path)?);
+    if read_word(&mut src)? != MAGIC {
+        return Err(invalid_data("not a setup cache entry"));
+    }
+    if read_word(&mut src)? != FORMAT_VERSION {
+        return Err(invalid_data("unsupported format version"));
+    }
+    let mut stored_key = [0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS];
+    read_words(&mut src, &mut stored_key)?;
+    if &stored_key != key {
+        return Err(invalid_data("key mismatch"));
+    }
+    let mut expected_digest = [0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS];
+    read_words(&mut src, &mut expected_digest)?;
+    let metadata_len = read_word(&mut src)? as usize;
+
+    // All lengths below come from the file itself, so check each of them against
+    // what is left of it before allocating
+    let file_len = src.get_ref().metadata()?.len();
+    let mut remaining_words = (file_len.saturating_sub(src.stream_position()?) / 4) as usize;
+    let mut take_words = |num_words: Option<usize>| match num_words {
+        Some(num_words) if num_words <= remaining_words => {
+            remaining_words -= num_words;
+            Ok(())
+        }
+        _ => Err(invalid_data("length exceeds file size")),
+    };
+
+    take_words(Some(metadata_len))?;
+    let mut metadata = vec![0u32; metadata_len];
+    read_words(&mut src, &mut metadata)?;
+
+    let mut metadata_iter = metadata.iter().map(|el| *el as usize);
+    let mut next = || {
+        metadata_iter
+            .next()
+            .ok_or_else(|| invalid_data("metadata is too short"))
+    };
+
+    // every coset and tree takes at least one metadata word
+    let num_cosets = next()?;
+    let mut ldes = Vec::with_capacity(num_cosets.min(metadata_len));
+    for _ in 0..num_cosets {
+        let tau = Mersenne31Complex {
+            c0: Mersenne31Field(next()? as u32),
+            c1: Mersenne31Field(next()? as u32),
+        };
+        let trace_len = next()?;
+        let width = next()?;
+        let padded_width = next()?;
+        if width > padded_width {
+            return Err(invalid_data("trace padding mismatch"));
+        }
+        take_words(trace_len.checked_mul(padded_width))?;
+        let mut trace = RowMajorTrace::new_uninit_for_size(trace_len, width, A::default());
+        if trace.padded_width != padded_width {
+            return Err(invalid_data("trace padding mismatch"));
+        }
+        read_words(&mut src, words_of_trace_mut(&mut trace))?;
+        ldes.push(CosetBoundTracePart { trace, tau });
+    }
+
+    let num_trees = next()?;
+    let mut trees = Vec::with_capacity(num_trees.min(metadata_len));
+    for _ in 0..num_trees {
+        let cap_size = next()?;
+        let num_leaf_hashes = next()?;
+        take_words(num_leaf_hashes.checked_mul(words_per_digest::<T::LeafDigest>()))?;
+        let mut leaf_hashes = vec![T::LeafDigest::ZERO; num_leaf_hashes];
+        read_words(&mut src, words_of_hashes_mut(&mut leaf_hashes))?;
+        let num_layers = next()?;
+        let mut node_hashes_enumerated_from_leafs =
+            Vec::with_capacity(num_layers.min(metadata_len));
+        for _ in 0..num_layers {
+            let layer_len = next()?;
+            take_words(layer_len.checked_mul(words_per_digest::<T::NodeDigest>()))?;
+            let mut layer = vec![T::NodeDigest::ZERO; layer_len];
+            read_words(&mut src, words_of_hashes_mut(&mut layer))?;
+            node_hashes_enumerated_from_leafs.push(layer);
+        }
+        trees.push(T::from_parts(
+            cap_size,
+            leaf_hashes,
+            node_hashes_enumerated_from_leafs,
+        ));
+    }
+    if src.read(&mut [0u8])? != 0 {
+        return Err(invalid_data("trailing data"));
+    }
+
+    let setup = SetupPrecomputations { ldes, trees };
+    if payload_for_setup(&setup).digest(worker) != expected_digest {
+        return Err(invalid_data("payload digest mismatch"));
+    }
+
+    Ok(setup)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn store_and_load_setup() {
+        let worker = Worker::new();
+        let machine = blake2_with_compression::get_delegation_circuit();
+        let table_driver = blake2_with_compression::get_table_driver();
+        let twiddles: Twiddles<_, Global> =
+            Twiddles::new(blake2_with_compression::DOMAIN_SIZE, &worker);
+        let lde_precomputations = LdePrecomputations::new(
+            blake2_with_compression::DOMAIN_SIZE,
+            blake2_with_compression::LDE_FACTOR,
+            blake2_with_compression::LDE_SOURCE_COSETS,
+            &worker,
+        );
+        let setup = SetupPrecomputations::<
+            DEFAULT_TRACE_PADDING_MULTIPLE,
+            Global,
+            DefaultTreeConstructor,
+        >::from_tables_and_trace_len(
+            &table_driver,
+            blake2_with_compression::DOMAIN_SIZE,
+            &machine.compiled_circuit.setup_layout,
+            &twiddles,
+            &lde_precomputations,
+            blake2_with_compression::LDE_FACTOR,
+            blake2_with_compression::TREE_CAP_SIZE,
+            &worker,
+        );
+        let key = setup_cache_key::<Global, DefaultTreeConstructor>(
+            &table_driver,
+            blake2_with_compression::DOMAIN_SIZE,
+            &machine.compiled_circuit.setup_layout,
+            &lde_precomputations,
+            blake2_with_compression::LDE_FACTOR,
+            blake2_with_compression::TREE_CAP_SIZE,
+        );
+
+        let cache_dir =
+            std::env::temp_dir().join(format!("setup_cache_test_{}", std::process::id()));
+        let path = setup_cache_entry_path(&cache_dir, &key);
+        store_setup(&cache_dir, &path, &key, &setup, &worker).unwrap();
+
+        let loaded = load_setup::<Global, DefaultTreeConstructor>(&path, &key, &worker).unwrap();
+        assert_eq!(
+            DefaultTreeConstructor::dump_caps(&loaded.trees),
+            DefaultTreeConstructor::dump_caps(&setup.trees)
+        );
+        for (a, b) in loaded.ldes.iter().zip(setup.ldes.iter()) {
+            assert_eq!(words_of_trace(&a.trace), words_of_trace(&b.trace));
+        }
+
+        // other key is rejected
+        let mut other_key = key;
+        other_key[0] ^= 1;
+        assert!(load_setup::<Global, DefaultTreeConstructor>(&path, &other_key, &worker).is_err());
+
+        // oversized lengths are rejected before allocating
+        let bytes = std::fs::read(&path).unwrap();
+        let metadata_len_offset = (2 + 2 * BLAKE2S_DIGEST_SIZE_U32_WORDS) * 4;
+        for offset in [metadata_len_offset, metadata_len_offset + 4] {
+            let mut corrupted = bytes.clone();
+            corrupted[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            std::fs::write(&path, corrupted).unwrap();
+            assert!(load_setup::<Global, DefaultTreeConstructor>(&path, &key, &worker).is_err());
+        }
+
+        // corrupted payload is rejected
+        let mut bytes = bytes;
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        std::fs::write(&path, bytes).unwrap();
+        assert!(load_setup::<Global, DefaultTreeConstructor>(&path, &key, &worker).is_err());
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
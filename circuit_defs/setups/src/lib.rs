@@ -34,6 +34,7 @@ pub use reduced_risc_v_machine;
 pub use risc_v_cycles;
 
 pub mod circuits;
+pub mod disk_cache;
 pub use self::circuits::*;
 
 pub fn pad_bytecode_for_proving(bytecode: &mut Vec<u32>) {
@@ -99,6 +100,33 @@ pub fn lde_factor_for_machine<C: MachineConfig>() -> usize {
     }
 }
 
+/// Compiles the main circuit for the machine. Layout doesn't depend on the bytecode,
+/// so it's enough to pass any bytecode padded for proving to get circuit dimensions
+pub fn compiled_circuit_for_machine<C: MachineConfig>(
+    bytecode: &[u32],
+) -> cs::one_row_compiler::CompiledCircuitArtifact<Mersenne31Field> {
+    if is_default_machine_configuration::<C>() {
+        risc_v_cycles::get_machine(bytecode, risc_v_cycles::ALLOWED_DELEGATION_CSRS)
+    } else if is_reduced_machine_configuration::<C>() {
+        reduced_risc_v_machine::get_machine(
+            bytecode,
+            reduced_risc_v_machine::ALLOWED_DELEGATION_CSRS,
+        )
+    } else if is_final_reduced_machine_configuration::<C>() {
+        final_reduced_risc_v_machine::get_machine(
+            bytecode,
+            final_reduced_risc_v_machine::ALLOWED_DELEGATION_CSRS,
+        )
+    } else if is_machine_without_signed_mul_div_configuration::<C>() {
+        machine_without_signed_mul_div::get_machine(
+            bytecode,
+            machine_without_signed_mul_div::ALLOWED_DELEGATION_CSRS,
+        )
+    } else {
+        panic!("unknown machine configuration {:?}", C::default())
+    }
+}
+
 pub fn delegation_factories_for_machine<C: MachineConfig, A: GoodAllocator>(
 ) -> HashMap<u16, Box<dyn Fn() -> prover::tracers::delegation::DelegationWitness<A>>> {
     if is_default_machine_configuration::<C>()
@@ -253,10 +281,6 @@ pub fn all_delegation_circuits_precomputations<A: GoodAllocator, B: GoodAllocato
         //     blake2_single_round::DELEGATION_TYPE_ID,
         //     get_blake2_single_round_circuit_setup(worker),
         // ),
-        // (
-        //     poseidon2_compression_with_witness::DELEGATION_TYPE_ID,
-        //     get_poseidon2_compress_with_witness_circuit_setup(worker),
-        // ),
     ]
 }
 
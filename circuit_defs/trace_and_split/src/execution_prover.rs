@@ -0,0 +1,94 @@
+//! Backend independent API of the execution prover, so the same code can produce memory commitments
+//! and proofs either on GPU or on CPU.
+
+use super::FinalRegisterValue;
+use prover::definitions::ExternalChallenges;
+use prover::merkle_trees::MerkleTreeCapVarLength;
+use prover::prover_stages::Proof;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MainCircuitType {
+    FinalReducedRiscVMachine,
+    MachineWithoutSignedMulDiv,
+    ReducedRiscVMachine,
+    RiscVCycles,
+}
+
+impl MainCircuitType {
+    /// Whether delegation argument challenge must be drawn for proofs of this circuit
+    pub fn supports_delegation(&self) -> bool {
+        match self {
+            MainCircuitType::FinalReducedRiscVMachine => false,
+            MainCircuitType::MachineWithoutSignedMulDiv => true,
+            MainCircuitType::ReducedRiscVMachine => true,
+            MainCircuitType::RiscVCycles => true,
+        }
+    }
+}
+
+/// Represents an executable binary that can be proven by the prover
+///
+///  # Fields
+/// * `key`: unique identifier for the binary, can be for example a &str or usize, anything that implements Clone, Debug, Eq, and Hash
+/// * `circuit_type`: the type of the circuit this binary is for, one of the values from the `MainCircuitType` enumeration
+/// * `bytecode`: the bytecode of the binary, can be a Vec<u32> or any other type that can be converted into Box<[u32]>
+///
+#[derive(Clone)]
+pub struct ExecutableBinary<K: Clone + Debug + Eq + Hash, B: Into<Box<[u32]>>> {
+    pub key: K,
+    pub circuit_type: MainCircuitType,
+    pub bytecode: B,
+}
+
+/// Final register values, memory commitments for the main circuit chunks, and memory commitments
+/// for the delegation circuits chunks grouped by the delegation type
+pub type MemoryCommitments = (
+    [FinalRegisterValue; 32],
+    Vec<Vec<MerkleTreeCapVarLength>>,
+    Vec<(u32, Vec<Vec<MerkleTreeCapVarLength>>)>,
+);
+
+/// Final register values, proofs for the main circuit chunks, and proofs for the delegation circuits
+/// chunks grouped by the delegation type
+pub type ExecutionProofs = ([FinalRegisterValue; 32], Vec<Proof>, Vec<(u32, Vec<Proof>)>);
+
+/// Orchestration API shared by the GPU and CPU provers. Binaries are registered when the prover
+/// is created and are referred to by the key `K`, and `ND` is the non-determinism source
+/// the binaries are executed with.
+///
+/// Arguments of all the methods are the same:
+/// * `batch_id`: a unique identifier for the batch of work, used to distinguish batches in a multithreaded scenario
+/// * `binary_key`: a key that identifies the binary to work with, it must match one of the binaries provided during the creation of the prover
+/// * `num_instances_upper_bound`: maximum number of main circuit instances that the prover will try to trace, if the simulation does not end within this limit, it will fail
+/// * `non_determinism_source`: provides non-deterministic values for the simulation
+pub trait ExecutionProverBackend<K, ND> {
+    /// Produces memory commitments.
+    fn commit_memory(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+    ) -> MemoryCommitments;
+
+    /// Produces proofs using the given challenges.
+    fn prove(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+        external_challenges: ExternalChallenges,
+    ) -> ExecutionProofs;
+
+    /// Commits to memory and produces proofs using challenges derived from the memory commitments.
+    fn commit_memory_and_prove(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+    ) -> ExecutionProofs;
+}
@@ -1,5 +1,6 @@
 #![allow(incomplete_features)]
 #![feature(generic_const_exprs)]
+#![feature(allocator_api)]
 
 pub const ENTRY_POINT: u32 = 0;
 
@@ -8,6 +9,7 @@ use prover::cs::definitions::TimestampScalar;
 use prover::cs::utils::split_timestamp;
 use prover::tracers::delegation::DelegationWitness;
 use prover::tracers::main_cycle_optimized::CycleData;
+use prover::tracers::main_cycle_optimized::{RamTracingData, RamTracingSnapshot};
 use prover::tracers::oracles::delegation_oracle::DelegationCircuitOracle;
 use prover::tracers::oracles::main_risc_v_circuit::MainRiscVOracle;
 use setups::prover::definitions::OPTIMAL_FOLDING_PROPERTIES;
@@ -16,7 +18,10 @@ use setups::prover::field::*;
 use setups::prover::merkle_trees::DefaultTreeConstructor;
 use setups::prover::merkle_trees::MerkleTreeConstructor;
 use setups::prover::risc_v_simulator::abstractions::non_determinism::*;
+use setups::prover::risc_v_simulator::cycle::state_new::RiscV32StateForUnrolledProver;
 use setups::prover::risc_v_simulator::cycle::MachineConfig;
+use setups::prover::risc_v_simulator::elf::{ElfLoadError, MemoryImage, ProgramImage};
+use setups::prover::risc_v_simulator::snapshot::ExecutionSnapshot;
 use setups::prover::transcript::Seed;
 use setups::prover::*;
 use setups::trace_len_for_machine;
@@ -25,12 +30,28 @@ use worker::Worker;
 
 pub use setups;
 
+pub mod execution_prover;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct FinalRegisterValue {
     pub value: u32,
     pub last_access_timestamp: TimestampScalar,
 }
 
+/// Splits the ELF executable into ROM content (not padded) and initialized RAM words. Circuits always
+/// start execution from `ENTRY_POINT`, so entry point from the header must match it.
+pub fn memory_image_from_elf(elf: &[u8]) -> Result<MemoryImage, ElfLoadError> {
+    let image = ProgramImage::parse_elf(elf)?;
+    if image.entry_point != ENTRY_POINT {
+        return Err(ElfLoadError::EntryPointMismatch {
+            expected: ENTRY_POINT,
+            found: image.entry_point,
+        });
+    }
+
+    image.memory_image(setups::risc_v_cycles::MAX_ROM_SIZE as u32, 1 << 30) // use 1 GB RAM
+}
+
 pub fn run_till_end_for_gpu_for_machine_config<
     ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>,
     C: MachineConfig,
@@ -49,12 +70,168 @@ pub fn run_till_end_for_gpu_for_machine_config<
     HashMap<u16, Vec<DelegationWitness<A>>>,
     Vec<FinalRegisterValue>,
     Vec<Vec<(u32, (TimestampScalar, u32))>>, // lazy iniy/teardown data - all unique words touched, sorted ascending, but not in one vector
+) {
+    run_till_end_for_gpu_impl::<ND, C, A, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(
+        num_cycles_upper_bound,
+        trace_size,
+        binary,
+        &[],
+        non_determinism,
+        delegation_factories,
+        worker,
+        None,
+        |_, _, _, _, _| {},
+    )
+}
+
+/// Same as `run_till_end_for_gpu_for_machine_config`, but takes the ELF executable. Text and other
+/// segments loaded below the ROM bound are placed into ROM, and initialized data above it into RAM.
+/// Note that main circuits lazily initialize RAM with zeroes, so the resulting witness is only provable
+/// if there is no initialized data in RAM (our linker scripts enforce it), but such programs can still be traced.
+pub fn run_till_end_for_gpu_for_machine_config_from_elf<
+    ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>,
+    C: MachineConfig,
+    A: GoodAllocator,
+    const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize,
+>(
+    num_cycles_upper_bound: usize,
+    trace_size: usize,
+    elf: &[u8],
+    non_determinism: &mut ND,
+    delegation_factories: HashMap<u16, Box<dyn Fn() -> DelegationWitness<A>>>,
+    worker: &Worker,
+) -> Result<
+    (
+        u32,
+        Vec<CycleData<C, A>>,
+        HashMap<u16, Vec<DelegationWitness<A>>>,
+        Vec<FinalRegisterValue>,
+        Vec<Vec<(u32, (TimestampScalar, u32))>>,
+    ),
+    ElfLoadError,
+> {
+    let image = memory_image_from_elf(elf)?;
+
+    let result = run_till_end_for_gpu_impl::<ND, C, A, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(
+        num_cycles_upper_bound,
+        trace_size,
+        &image.rom,
+        &image.ram,
+        non_determinism,
+        delegation_factories,
+        worker,
+        None,
+        |_, _, _, _, _| {},
+    );
+
+    Ok(result)
+}
+
+/// Machine state at the start of the main circuit chunk, including RAM timestamps bookkeeping,
+/// so witness generation can be resumed from there
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(bound = "")]
+pub struct ChunkSnapshot<C: MachineConfig> {
+    pub chunk_idx: usize,
+    pub execution: ExecutionSnapshot<RiscV32StateForUnrolledProver<C>>,
+    pub ram_tracing: RamTracingSnapshot,
+}
+
+/// Same as `run_till_end_for_gpu_for_machine_config`, but passes snapshots taken at the start of
+/// every chunk for which `should_snapshot(chunk_idx)` is true into `on_snapshot`, and optionally
+/// resumes execution from the snapshot instead of starting from the first cycle.
+///
+/// Non-determinism source must be created from the same inputs as for the original run, and
+/// `trace_size` must be the same. On resume main circuit traces start from the snapshot chunk,
+/// and delegation traces only contain delegations performed after it, while final register values
+/// and lazy init/teardown data are valid for the full execution.
+pub fn run_till_end_for_gpu_for_machine_config_with_snapshots<
+    ND: NonDeterminismCSRSource<VectorMemoryImplWithRom> + ResumableNonDeterminismSource,
+    C: MachineConfig,
+    A: GoodAllocator,
+    const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize,
+>(
+    num_cycles_upper_bound: usize,
+    trace_size: usize,
+    binary: &[u32],
+    non_determinism: &mut ND,
+    delegation_factories: HashMap<u16, Box<dyn Fn() -> DelegationWitness<A>>>,
+    worker: &Worker,
+    resume_from: Option<&ChunkSnapshot<C>>,
+    mut should_snapshot: impl FnMut(usize) -> bool,
+    mut on_snapshot: impl FnMut(ChunkSnapshot<C>),
+) -> (
+    u32,
+    Vec<CycleData<C, A>>,
+    HashMap<u16, Vec<DelegationWitness<A>>>,
+    Vec<FinalRegisterValue>,
+    Vec<Vec<(u32, (TimestampScalar, u32))>>,
+) {
+    if let Some(snapshot) = resume_from {
+        non_determinism.skip_words(snapshot.execution.non_determinism_position);
+        non_determinism.restore_write_state(&snapshot.execution.non_determinism_write_state);
+    }
+    let cycles_per_chunk = (trace_size - 1) as u64;
+
+    run_till_end_for_gpu_impl::<ND, C, A, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(
+        num_cycles_upper_bound,
+        trace_size,
+        binary,
+        &[],
+        non_determinism,
+        delegation_factories,
+        worker,
+        resume_from,
+        |chunk_idx, state, memory, non_determinism, ram_tracing| {
+            if should_snapshot(chunk_idx) == false {
+                return;
+            }
+            on_snapshot(ChunkSnapshot {
+                chunk_idx,
+                execution: ExecutionSnapshot {
+                    cycle: chunk_idx as u64 * cycles_per_chunk,
+                    state: *state,
+                    memory: memory.snapshot(),
+                    non_determinism_position: non_determinism.num_words_read(),
+                    non_determinism_write_state: non_determinism.write_state(),
+                },
+                ram_tracing: ram_tracing.snapshot(),
+            });
+        },
+    )
+}
+
+fn run_till_end_for_gpu_impl<
+    ND: NonDeterminismCSRSource<VectorMemoryImplWithRom>,
+    C: MachineConfig,
+    A: GoodAllocator,
+    const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize,
+>(
+    num_cycles_upper_bound: usize,
+    trace_size: usize,
+    binary: &[u32],
+    ram_init: &[(u32, u32)],
+    non_determinism: &mut ND,
+    delegation_factories: HashMap<u16, Box<dyn Fn() -> DelegationWitness<A>>>,
+    worker: &Worker,
+    resume_from: Option<&ChunkSnapshot<C>>,
+    mut at_chunk_start: impl FnMut(
+        usize,
+        &RiscV32StateForUnrolledProver<C>,
+        &VectorMemoryImplWithRom,
+        &ND,
+        &RamTracingData<true>,
+    ),
+) -> (
+    u32,
+    Vec<CycleData<C, A>>,
+    HashMap<u16, Vec<DelegationWitness<A>>>,
+    Vec<FinalRegisterValue>,
+    Vec<Vec<(u32, (TimestampScalar, u32))>>,
 ) {
     use crate::cs::one_row_compiler::timestamp_from_chunk_cycle_and_sequence;
     use prover::tracers::main_cycle_optimized::DelegationTracingData;
     use prover::tracers::main_cycle_optimized::GPUFriendlyTracer;
-    use prover::tracers::main_cycle_optimized::RamTracingData;
-    use setups::prover::risc_v_simulator::cycle::state_new::RiscV32StateForUnrolledProver;
     use setups::prover::risc_v_simulator::delegations::DelegationsCSRProcessor;
 
     assert!(trace_size.is_power_of_two());
@@ -64,6 +241,9 @@ pub fn run_till_end_for_gpu_for_machine_config<
     for (idx, insn) in binary.iter().enumerate() {
         memory.populate(ENTRY_POINT + idx as u32 * 4, *insn);
     }
+    for (address, value) in ram_init.iter() {
+        memory.populate(*address, *value);
+    }
 
     let cycles_per_chunk = trace_size - 1;
     let num_cycles_upper_bound = num_cycles_upper_bound.next_multiple_of(cycles_per_chunk);
@@ -71,8 +251,17 @@ pub fn run_till_end_for_gpu_for_machine_config<
 
     let mut state = RiscV32StateForUnrolledProver::<C>::initial(ENTRY_POINT);
 
-    let bookkeeping_aux_data =
+    let mut bookkeeping_aux_data =
         RamTracingData::<true>::new_for_ram_size_and_rom_bound(1 << 30, rom_address_space_bound); // use 1 GB RAM
+
+    let mut first_chunk_idx = 0;
+    if let Some(snapshot) = resume_from {
+        assert!(snapshot.chunk_idx < num_circuits_upper_bound);
+        first_chunk_idx = snapshot.chunk_idx;
+        state = snapshot.execution.state;
+        memory.restore_from_snapshot(&snapshot.execution.memory);
+        bookkeeping_aux_data.restore_from_snapshot(&snapshot.ram_tracing);
+    }
     let delegation_tracer = DelegationTracingData {
         all_per_type_logs: HashMap::new(),
         delegation_witness_factories: delegation_factories,
@@ -87,13 +276,13 @@ pub fn run_till_end_for_gpu_for_machine_config<
 
     let mut custom_csr_processor = DelegationsCSRProcessor;
 
-    let initial_ts = timestamp_from_chunk_cycle_and_sequence(0, cycles_per_chunk, 0);
+    let initial_ts = timestamp_from_chunk_cycle_and_sequence(0, cycles_per_chunk, first_chunk_idx);
     let mut tracer = GPUFriendlyTracer::<_, _, true, true, true>::new(
         initial_ts,
         bookkeeping_aux_data,
         delegation_tracer,
         cycles_per_chunk,
-        num_circuits_upper_bound,
+        num_circuits_upper_bound - first_chunk_idx,
     );
 
     let mut end_reached = false;
@@ -101,12 +290,19 @@ pub fn run_till_end_for_gpu_for_machine_config<
 
     let now = std::time::Instant::now();
 
-    for chunk_idx in 0..num_circuits_upper_bound {
+    for chunk_idx in first_chunk_idx..num_circuits_upper_bound {
         circuits_needed = chunk_idx + 1;
-        if chunk_idx != 0 {
+        if chunk_idx != first_chunk_idx {
             let timestamp = timestamp_from_chunk_cycle_and_sequence(0, cycles_per_chunk, chunk_idx);
             tracer.prepare_for_next_chunk(timestamp);
         }
+        at_chunk_start(
+            chunk_idx,
+            &state,
+            &memory,
+            non_determinism,
+            &tracer.bookkeeping_aux_data,
+        );
 
         let finished = state.run_cycles(
             &mut memory,
@@ -137,10 +333,11 @@ pub fn run_till_end_for_gpu_for_machine_config<
     // put latest chunk manually in traced ones
     let mut traced_chunks = traced_chunks;
     traced_chunks.push(trace_chunk);
-    assert_eq!(traced_chunks.len(), circuits_needed);
+    let circuits_traced = circuits_needed - first_chunk_idx;
+    assert_eq!(traced_chunks.len(), circuits_traced);
 
     let elapsed = now.elapsed();
-    let cycles_upper_bound = circuits_needed * cycles_per_chunk;
+    let cycles_upper_bound = circuits_traced * cycles_per_chunk;
     let speed = (cycles_upper_bound as f64) / elapsed.as_secs_f64() / 1_000_000f64;
     println!(
         "Simulator running speed with witness tracing is {} MHz: ran {} cycles over {:?}",
@@ -226,7 +423,7 @@ pub fn run_till_end_for_gpu_for_machine_config<
         }
     }
 
-    assert_eq!(circuits_needed, traced_chunks.len());
+    assert_eq!(circuits_traced, traced_chunks.len());
 
     (
         state.pc,
@@ -616,3 +813,119 @@ pub fn run_and_split_for_gpu<
         lazy_init_teardown_data,
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use setups::prover::risc_v_simulator::cycle::IMStandardIsaConfig;
+    use std::alloc::Global;
+
+    // lui x5, 0x400
+    const SET_X5_TO_RAM_ADDRESS: u32 = 0x004002b7;
+    // addi x6, x0, -1
+    const SET_X6_TO_UART_HELLO: u32 = 0xfff00313;
+    // csrrw x0, 0x7c0, x6
+    const WRITE_X6: u32 = 0x7c031073;
+    // csrrw x1, 0x7c0, x0
+    const READ_X1: u32 = 0x7c0010f3;
+    // add x3, x3, x1
+    const ADD_X3_X1: u32 = 0x001181b3;
+    // sw x3, 0(x5)
+    const STORE_X3: u32 = 0x0032a023;
+    // addi x5, x5, 4
+    const INCREMENT_X5: u32 = 0x00428293;
+    // csrrw x0, 0x7c0, x3
+    const WRITE_X3: u32 = 0x7c019073;
+    // jal x0, 0
+    const HALT: u32 = 0x0000006f;
+
+    const TRACE_SIZE: usize = 1 << 4;
+
+    fn run(
+        inputs: &[u32],
+        resume_from: Option<&ChunkSnapshot<IMStandardIsaConfig>>,
+        snapshots: &mut Vec<ChunkSnapshot<IMStandardIsaConfig>>,
+    ) -> (
+        u32,
+        Vec<CycleData<IMStandardIsaConfig, Global>>,
+        HashMap<u16, Vec<DelegationWitness<Global>>>,
+        Vec<FinalRegisterValue>,
+        Vec<(u32, (TimestampScalar, u32))>,
+    ) {
+        // UART is left in the middle of the (never finished) message, so its write state is not trivial
+        let mut program = vec![
+            SET_X5_TO_RAM_ADDRESS,
+            SET_X6_TO_UART_HELLO,
+            WRITE_X6,
+            WRITE_X6,
+        ];
+        for _ in 0..inputs.len() {
+            program.extend([READ_X1, ADD_X3_X1, STORE_X3, INCREMENT_X5, WRITE_X3]);
+        }
+        program.push(HALT);
+
+        let mut non_determinism = QuasiUARTSource::new_with_reads(inputs.to_vec());
+        let worker = Worker::new_with_num_threads(2);
+        let (final_pc, traces, delegation_traces, registers, lazy_init_data) =
+            run_till_end_for_gpu_for_machine_config_with_snapshots::<
+                _,
+                IMStandardIsaConfig,
+                Global,
+                { setups::risc_v_cycles::ROM_ADDRESS_SPACE_SECOND_WORD_BITS },
+            >(
+                1 << 10,
+                TRACE_SIZE,
+                &program,
+                &mut non_determinism,
+                HashMap::new(),
+                &worker,
+                resume_from,
+                |_| true,
+                |snapshot| snapshots.push(snapshot),
+            );
+
+        (
+            final_pc,
+            traces,
+            delegation_traces,
+            registers,
+            lazy_init_data.into_iter().flatten().collect(),
+        )
+    }
+
+    #[test]
+    fn test_resume_from_chunk_snapshot() {
+        let inputs: Vec<u32> = (1..=16).collect();
+
+        let mut snapshots = vec![];
+        let (final_pc, traces, delegation_traces, registers, lazy_init_data) =
+            run(&inputs, None, &mut snapshots);
+        assert!(delegation_traces.is_empty());
+        assert!(traces.len() > 4);
+        assert_eq!(snapshots.len(), traces.len());
+        assert_eq!(registers[3].value, inputs.iter().sum::<u32>());
+        assert!(!lazy_init_data.is_empty());
+
+        for chunk_idx in [1, traces.len() / 2, traces.len() - 1] {
+            let snapshot = &snapshots[chunk_idx];
+            assert_eq!(snapshot.chunk_idx, chunk_idx);
+            assert!(!snapshot.execution.non_determinism_write_state.is_empty());
+
+            let mut resumed_snapshots = vec![];
+            let (
+                resumed_final_pc,
+                resumed_traces,
+                resumed_delegation_traces,
+                resumed_registers,
+                resumed_lazy_init_data,
+            ) = run(&inputs, Some(snapshot), &mut resumed_snapshots);
+
+            assert_eq!(resumed_final_pc, final_pc);
+            assert_eq!(resumed_traces, traces[chunk_idx..]);
+            assert!(resumed_delegation_traces.is_empty());
+            assert_eq!(resumed_registers, registers);
+            assert_eq!(resumed_lazy_init_data, lazy_init_data);
+            assert_eq!(resumed_snapshots, snapshots[chunk_idx..]);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+#![cfg_attr(not(test), no_std)]
+
+//! Runtime detection of SIMD extensions, so that the same binary picks the fastest available
+//! kernels in `field`, `fft` and `blake2s_u32` instead of relying on `-C target-cpu=native`.
+//! Detection runs once on the first call, and the result can be overridden (e.g. by tests that
+//! cross-check every backend against the scalar code).
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum SimdBackend {
+    Scalar = 1,
+    Neon = 2,
+    Avx2 = 3,
+    Avx512 = 4,
+}
+
+impl SimdBackend {
+    pub const ALL: [SimdBackend; 4] = [
+        SimdBackend::Scalar,
+        SimdBackend::Neon,
+        SimdBackend::Avx2,
+        SimdBackend::Avx512,
+    ];
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Scalar,
+            2 => Self::Neon,
+            3 => Self::Avx2,
+            4 => Self::Avx512,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Whether kernels of this backend can run on the current host
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Self::Scalar => true,
+            // mandatory on aarch64
+            Self::Neon => cfg!(target_arch = "aarch64"),
+            Self::Avx2 => x86_features().avx2,
+            Self::Avx512 => x86_features().avx512,
+        }
+    }
+}
+
+impl core::fmt::Display for SimdBackend {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Scalar => write!(f, "scalar"),
+            Self::Neon => write!(f, "NEON"),
+            Self::Avx2 => write!(f, "AVX2"),
+            Self::Avx512 => write!(f, "AVX-512"),
+        }
+    }
+}
+
+const NOT_SET: u8 = 0;
+
+static DETECTED_BACKEND: AtomicU8 = AtomicU8::new(NOT_SET);
+static FORCED_BACKEND: AtomicU8 = AtomicU8::new(NOT_SET);
+
+/// The fastest backend supported by the host
+pub fn detected_simd_backend() -> SimdBackend {
+    match DETECTED_BACKEND.load(Ordering::Relaxed) {
+        NOT_SET => {
+            let backend = SimdBackend::ALL
+                .into_iter()
+                .rev()
+                .find(|backend| backend.is_supported())
+                .unwrap();
+            DETECTED_BACKEND.store(backend as u8, Ordering::Relaxed);
+            backend
+        }
+        value => SimdBackend::from_u8(value),
+    }
+}
+
+/// Backend that kernels should use: the forced one if any, otherwise the detected one
+#[inline(always)]
+pub fn simd_backend() -> SimdBackend {
+    match FORCED_BACKEND.load(Ordering::Relaxed) {
+        NOT_SET => detected_simd_backend(),
+        value => SimdBackend::from_u8(value),
+    }
+}
+
+/// Makes all the kernels use the given backend, or go back to the detected one for `None`.
+/// Panics if the host doesn't support the backend.
+pub fn force_simd_backend(backend: Option<SimdBackend>) {
+    let value = match backend {
+        Some(backend) => {
+            assert!(
+                backend.is_supported(),
+                "{} backend is not supported by this host",
+                backend
+            );
+            backend as u8
+        }
+        None => NOT_SET,
+    };
+    FORCED_BACKEND.store(value, Ordering::Relaxed);
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct X86Features {
+    avx2: bool,
+    // F and VL subsets, that are present on all AVX-512 capable CPUs
+    avx512: bool,
+}
+
+#[cfg(target_arch = "x86_64")]
+fn x86_features() -> X86Features {
+    use core::arch::x86_64::{__cpuid, __cpuid_count, _xgetbv};
+
+    // XCR0 bits for SSE, AVX and AVX-512 (opmask, upper halves of ZMM0-15, ZMM16-31) state
+    const XCR0_YMM_STATE: u64 = 0b0000_0110;
+    const XCR0_ZMM_STATE: u64 = 0b1110_0110;
+
+    #[target_feature(enable = "xsave")]
+    unsafe fn read_xcr0() -> u64 {
+        _xgetbv(0)
+    }
+
+    if __cpuid(0).eax < 7 {
+        return X86Features::default();
+    }
+    let leaf_1 = __cpuid(1);
+    let osxsave = leaf_1.ecx & (1 << 27) != 0;
+    let avx = leaf_1.ecx & (1 << 28) != 0;
+    if !osxsave || !avx {
+        return X86Features::default();
+    }
+    // OS must also save the wide registers on context switch
+    let xcr0 = unsafe { read_xcr0() };
+    let leaf_7 = __cpuid_count(7, 0);
+    let avx2 = leaf_7.ebx & (1 << 5) != 0 && xcr0 & XCR0_YMM_STATE == XCR0_YMM_STATE;
+    let avx512f = leaf_7.ebx & (1 << 16) != 0;
+    let avx512vl = leaf_7.ebx & (1 << 31) != 0;
+
+    X86Features {
+        avx2,
+        avx512: avx2 && avx512f && avx512vl && xcr0 & XCR0_ZMM_STATE == XCR0_ZMM_STATE,
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn x86_features() -> X86Features {
+    X86Features::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detected_backend_is_supported() {
+        let detected = detected_simd_backend();
+        assert!(detected.is_supported());
+        assert!(SimdBackend::Scalar.is_supported());
+        if cfg!(target_arch = "x86_64") {
+            assert!(!SimdBackend::Neon.is_supported());
+        }
+        // nothing better than detected one
+        assert!(SimdBackend::ALL
+            .into_iter()
+            .filter(|backend| backend.is_supported())
+            .all(|backend| backend <= detected));
+    }
+
+    #[test]
+    fn test_force_backend() {
+        force_simd_backend(Some(SimdBackend::Scalar));
+        assert_eq!(simd_backend(), SimdBackend::Scalar);
+        force_simd_backend(None);
+        assert_eq!(simd_backend(), detected_simd_backend());
+    }
+}
@@ -0,0 +1,561 @@
+//! Static analysis of the circuit output that looks for variables that the constraint system leaves
+//! (almost) free. It doesn't prove anything, but gives a list of variables to review:
+//!
+//! - variables that are not used in any constraint, lookup, range check or linkage, and are not
+//!   coming from the memory argument;
+//! - variables that are used only linearly in a single constraint, and nowhere else, so such constraint
+//!   merely defines the variable, and the variable itself doesn't affect anything;
+//! - limbs written into memory or into the machine state that are never range checked.
+//!
+//! Values read from memory are considered constrained by the memory argument, as everything that is
+//! written into memory must be range checked by the writer.
+
+use super::circuit::*;
+use super::placeholder::Placeholder;
+use crate::constraint::Term;
+use crate::definitions::*;
+use field::PrimeField;
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UnderconstrainedVariableKind {
+    Unconstrained,
+    LinearInSingleConstraintOnly,
+    LimbWithoutRangeCheck,
+}
+
+/// Where the variable is used in the circuit
+#[derive(Clone, Debug, Default)]
+pub struct VariableUsage {
+    pub num_constraints: usize,
+    pub num_nonlinear_constraints: usize,
+    pub num_lookups: usize,
+    pub is_bounded_by_lookup: bool,
+    pub is_linked: bool,
+    pub is_memory_read_value: bool,
+    pub is_memory_write_value: bool,
+    pub is_memory_address: bool,
+    pub is_state_input: bool,
+    pub is_state_output: bool,
+    pub is_delegation_request: bool,
+    /// Invariants that were required via `Circuit::require_invariant`
+    pub invariants: Vec<Invariant>,
+}
+
+impl VariableUsage {
+    pub fn is_range_checked(&self) -> bool {
+        self.is_bounded_by_lookup
+            || self.invariants.iter().any(|el| match el {
+                Invariant::Boolean | Invariant::RangeChecked { .. } => true,
+                _ => false,
+            })
+    }
+
+    pub fn placeholder(&self) -> Option<(Placeholder, usize)> {
+        self.invariants.iter().find_map(|el| match el {
+            Invariant::Substituted(placeholder) => Some(*placeholder),
+            _ => None,
+        })
+    }
+
+    fn is_constrained(&self) -> bool {
+        self.num_constraints > 0
+            || self.num_lookups > 0
+            || self.is_linked
+            || self.is_memory_read_value
+            || self.is_state_input
+            || self.is_range_checked()
+    }
+
+    fn is_used_outside_of_constraints(&self) -> bool {
+        self.num_lookups > 0
+            || self.is_linked
+            || self.is_memory_read_value
+            || self.is_memory_write_value
+            || self.is_memory_address
+            || self.is_state_input
+            || self.is_state_output
+            || self.is_delegation_request
+            || self.invariants.iter().any(|el| match el {
+                Invariant::Boolean | Invariant::RangeChecked { .. } => true,
+                _ => false,
+            })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UnderconstrainedVariable {
+    pub variable: Variable,
+    pub kind: UnderconstrainedVariableKind,
+    pub usage: VariableUsage,
+}
+
+#[derive(Clone, Debug)]
+pub struct UnderconstrainedVariablesReport {
+    pub num_variables: usize,
+    pub findings: Vec<UnderconstrainedVariable>,
+}
+
+impl UnderconstrainedVariablesReport {
+    pub fn findings_of_kind(
+        &self,
+        kind: UnderconstrainedVariableKind,
+    ) -> impl Iterator<Item = &UnderconstrainedVariable> {
+        self.findings.iter().filter(move |el| el.kind == kind)
+    }
+}
+
+impl Display for UnderconstrainedVariablesReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} findings over {} variables",
+            self.findings.len(),
+            self.num_variables
+        )?;
+        for finding in self.findings.iter() {
+            write!(f, "{:?}: {:?}", finding.variable, finding.kind)?;
+            if let Some((placeholder, subindex)) = finding.usage.placeholder() {
+                write!(f, ", substituted from {:?}[{}]", placeholder, subindex)?;
+            }
+            writeln!(
+                f,
+                ", used in {} constraints and {} lookups",
+                finding.usage.num_constraints, finding.usage.num_lookups
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects usage of every variable of the circuit, indexed by the variable index
+pub fn collect_variables_usage<F: PrimeField>(circuit: &CircuitOutput<F>) -> Vec<VariableUsage> {
+    let mut usage = vec![VariableUsage::default(); circuit.num_of_variables];
+
+    for (constraint, _) in circuit.constraints.iter() {
+        // one variable may be in several terms of the same constraint
+        let mut degrees: Vec<(Variable, usize)> = vec![];
+        for term in constraint.terms.iter() {
+            let Term::Expression { inner, degree, .. } = term else {
+                continue;
+            };
+            for variable in inner[..*degree].iter() {
+                let variable_degree = inner[..*degree].iter().filter(|el| *el == variable).count();
+                let term_degree = std::cmp::max(*degree, variable_degree);
+                if let Some((_, existing)) = degrees.iter_mut().find(|(el, _)| el == variable) {
+                    *existing = std::cmp::max(*existing, term_degree);
+                } else {
+                    degrees.push((*variable, term_degree));
+                }
+            }
+        }
+        for (variable, degree) in degrees.into_iter() {
+            let entry = &mut usage[variable.0 as usize];
+            entry.num_constraints += 1;
+            if degree > 1 {
+                entry.num_nonlinear_constraints += 1;
+            }
+        }
+    }
+
+    for lookup in circuit.lookups.iter() {
+        for input in lookup.row.iter() {
+            match input {
+                LookupInput::Variable(variable) => {
+                    let entry = &mut usage[variable.0 as usize];
+                    entry.num_lookups += 1;
+                    // we do not know which column it is, but any column of a fixed table has a bounded set of values
+                    if let LookupQueryTableType::Constant(..) = lookup.table {
+                        entry.is_bounded_by_lookup = true;
+                    }
+                }
+                LookupInput::Expression { linear_terms, .. } => {
+                    for (_, variable) in linear_terms.iter() {
+                        usage[variable.0 as usize].num_lookups += 1;
+                    }
+                }
+            }
+        }
+        if let LookupQueryTableType::Variable(variable) = lookup.table {
+            usage[variable.0 as usize].num_lookups += 1;
+        }
+    }
+
+    for range_check in circuit.range_check_expressions.iter() {
+        match &range_check.input {
+            LookupInput::Variable(variable) => {
+                usage[variable.0 as usize]
+                    .invariants
+                    .push(Invariant::RangeChecked {
+                        width: range_check.width as u32,
+                    });
+            }
+            LookupInput::Expression { linear_terms, .. } => {
+                // range check of an expression doesn't bound individual variables
+                for (_, variable) in linear_terms.iter() {
+                    usage[variable.0 as usize].num_lookups += 1;
+                }
+            }
+        }
+    }
+
+    for variable in circuit.boolean_vars.iter() {
+        usage[variable.0 as usize]
+            .invariants
+            .push(Invariant::Boolean);
+    }
+
+    for (placeholder, variable) in circuit.substitutions.iter() {
+        usage[variable.0 as usize]
+            .invariants
+            .push(Invariant::Substituted(*placeholder));
+    }
+
+    for pair in circuit.linked_variables.iter() {
+        usage[pair.initial_var.0 as usize].is_linked = true;
+        usage[pair.final_var.0 as usize].is_linked = true;
+    }
+
+    for variable in circuit.state_input.iter() {
+        usage[variable.0 as usize].is_state_input = true;
+    }
+    for variable in circuit.state_output.iter() {
+        usage[variable.0 as usize].is_state_output = true;
+    }
+
+    let mut memory_accesses: Vec<([Variable; REGISTER_SIZE], Option<[Variable; REGISTER_SIZE]>)> =
+        vec![];
+    for query in circuit.shuffle_ram_queries.iter() {
+        match query.query_type {
+            ShuffleRamQueryType::RegisterOnly { register_index } => {
+                usage[register_index.0 as usize].is_memory_address = true;
+            }
+            ShuffleRamQueryType::RegisterOrRam {
+                is_register,
+                address,
+            } => {
+                if let Some(variable) = is_register.get_variable() {
+                    usage[variable.0 as usize].is_memory_address = true;
+                }
+                for variable in address.into_iter() {
+                    usage[variable.0 as usize].is_memory_address = true;
+                }
+            }
+        }
+        if query.is_readonly() {
+            memory_accesses.push((query.read_value, None));
+        } else {
+            memory_accesses.push((query.read_value, Some(query.write_value)));
+        }
+    }
+    for access in circuit.batched_memory_accesses.iter() {
+        match *access {
+            BatchedMemoryAccessType::Read { read_value } => {
+                memory_accesses.push((read_value, None));
+            }
+            BatchedMemoryAccessType::Write {
+                read_value,
+                write_value,
+            } => {
+                memory_accesses.push((read_value, Some(write_value)));
+            }
+        }
+    }
+    for access in circuit.register_and_indirect_memory_accesses.iter() {
+        match access.register_access {
+            RegisterAccessType::Read { read_value } => {
+                memory_accesses.push((read_value, None));
+            }
+            RegisterAccessType::Write {
+                read_value,
+                write_value,
+            } => {
+                memory_accesses.push((read_value, Some(write_value)));
+            }
+        }
+        for indirect in access.indirect_accesses.iter() {
+            match *indirect {
+                IndirectAccessType::Read { read_value } => {
+                    memory_accesses.push((read_value, None));
+                }
+                IndirectAccessType::Write {
+                    read_value,
+                    write_value,
+                } => {
+                    memory_accesses.push((read_value, Some(write_value)));
+                }
+            }
+        }
+    }
+    for (read_value, write_value) in memory_accesses.into_iter() {
+        for variable in read_value.into_iter() {
+            usage[variable.0 as usize].is_memory_read_value = true;
+        }
+        for variable in write_value.into_iter().flatten() {
+            usage[variable.0 as usize].is_memory_write_value = true;
+        }
+    }
+
+    for request in circuit.delegated_computation_requests.iter() {
+        for variable in [
+            request.execute,
+            request.degegation_type,
+            request.memory_offset_high,
+        ] {
+            usage[variable.0 as usize].is_delegation_request = true;
+        }
+    }
+    if let Some(request) = circuit.degegated_request_to_process {
+        for variable in [request.execute, request.memory_offset_high] {
+            usage[variable.0 as usize].is_delegation_request = true;
+        }
+    }
+
+    usage
+}
+
+/// Reports variables that are unconstrained, defined by a single linear constraint only, or are
+/// limbs of values written into memory or state without being range checked
+pub fn find_underconstrained_variables<F: PrimeField>(
+    circuit: &CircuitOutput<F>,
+) -> UnderconstrainedVariablesReport {
+    let usage = collect_variables_usage(circuit);
+
+    let mut findings = vec![];
+    for (idx, usage) in usage.into_iter().enumerate() {
+        let kind = if usage.is_constrained() == false {
+            UnderconstrainedVariableKind::Unconstrained
+        } else if usage.num_constraints == 1
+            && usage.num_nonlinear_constraints == 0
+            && usage.is_used_outside_of_constraints() == false
+        {
+            UnderconstrainedVariableKind::LinearInSingleConstraintOnly
+        } else if (usage.is_memory_write_value || usage.is_state_output)
+            && usage.is_memory_read_value == false
+            && usage.is_state_input == false
+            && usage.is_range_checked() == false
+        {
+            UnderconstrainedVariableKind::LimbWithoutRangeCheck
+        } else {
+            continue;
+        };
+
+        findings.push(UnderconstrainedVariable {
+            variable: Variable(idx as u64),
+            kind,
+            usage,
+        });
+    }
+
+    UnderconstrainedVariablesReport {
+        num_variables: circuit.num_of_variables,
+        findings,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cs::cs_reference::BasicAssembly;
+    use crate::delegation::*;
+    use crate::machine::machine_configurations::compile_machine;
+    use crate::machine::machine_configurations::full_isa_no_exceptions::FullIsaMachineNoExceptionHandling;
+    use crate::machine::machine_configurations::full_isa_with_delegation_and_bitmanip_no_exceptions::FullIsaMachineWithDelegationAndBitmanipNoExceptionHandling;
+    use crate::machine::machine_configurations::full_isa_with_delegation_and_compressed_no_exceptions::FullIsaMachineWithDelegationAndCompressedNoExceptionHandling;
+    use crate::machine::machine_configurations::full_isa_with_delegation_and_trap_handling::FullIsaMachineWithDelegationAndTrapHandling;
+    use crate::machine::machine_configurations::full_isa_with_delegation_no_exceptions::FullIsaMachineWithDelegationNoExceptionHandling;
+    use crate::machine::machine_configurations::full_isa_with_delegation_no_exceptions_no_signed_mul_div::FullIsaMachineWithDelegationNoExceptionHandlingNoSignedMulDiv;
+    use crate::machine::machine_configurations::minimal_no_exceptions::MinimalMachineNoExceptionHandling;
+    use crate::machine::machine_configurations::minimal_no_exceptions_with_delegation::MinimalMachineNoExceptionHandlingWithDelegation;
+    use crate::machine::Machine;
+    use field::Mersenne31Field;
+    type F = Mersenne31Field;
+
+    const SECOND_WORD_BITS: usize = 4;
+
+    fn analyze_machine<M: Machine<F>>(machine: M) -> UnderconstrainedVariablesReport
+    where
+        [(); { <M as Machine<F>>::ASSUME_TRUSTED_CODE } as usize]:,
+        [(); { <M as Machine<F>>::OUTPUT_EXACT_EXCEPTIONS } as usize]:,
+    {
+        let circuit = compile_machine::<F, BasicAssembly<F>, M, SECOND_WORD_BITS>(machine);
+        find_underconstrained_variables(&circuit)
+    }
+
+    fn analyze_delegation_circuit<T>(
+        definition_fn: impl Fn(&mut BasicAssembly<F>) -> T,
+    ) -> UnderconstrainedVariablesReport {
+        let mut cs = BasicAssembly::<F>::new();
+        definition_fn(&mut cs);
+        let (circuit, _) = cs.finalize();
+        find_underconstrained_variables(&circuit)
+    }
+
+    #[test]
+    fn detect_underconstrained_variables() {
+        let mut cs = BasicAssembly::<F>::new();
+        let a = cs.add_variable();
+        let b = cs.add_variable();
+        let c = cs.add_variable();
+        let free = cs.add_variable();
+        let defined = cs.add_variable();
+        cs.require_invariant(a, Invariant::RangeChecked { width: 16 });
+        cs.require_invariant(b, Invariant::RangeChecked { width: 16 });
+        // c = a * b, and `defined` = c + 1 is not used anywhere else
+        cs.add_constraint(Term::from(a) * Term::from(b) - Term::from(c));
+        cs.add_constraint_allow_explicit_linear(
+            Term::from(defined) - Term::from(c) - Term::from(1u64),
+        );
+        let (circuit, _) = cs.finalize();
+
+        let report = find_underconstrained_variables(&circuit);
+        let findings: Vec<_> = report
+            .findings
+            .iter()
+            .map(|el| (el.variable, el.kind))
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                (free, UnderconstrainedVariableKind::Unconstrained),
+                (
+                    defined,
+                    UnderconstrainedVariableKind::LinearInSingleConstraintOnly
+                ),
+            ]
+        );
+    }
+
+    /// Number of findings of every kind, so any change in them shows up in the snapshot diff
+    fn findings_summary(name: &str, report: &UnderconstrainedVariablesReport) -> String {
+        use UnderconstrainedVariableKind::*;
+        let count = |kind| report.findings_of_kind(kind).count();
+
+        format!(
+            "{}: {} unconstrained, {} linear in single constraint only, {} limbs without range check over {} variables\n",
+            name,
+            count(Unconstrained),
+            count(LinearInSingleConstraintOnly),
+            count(LimbWithoutRangeCheck),
+            report.num_variables,
+        )
+    }
+
+    /// Compares against the snapshot in `cs/analysis_snapshots`. After reviewing the new findings
+    /// the snapshot is updated by running the test with `UPDATE_ANALYSIS_SNAPSHOTS=1`
+    fn check_snapshot(snapshot_name: &str, reports: &[(&str, UnderconstrainedVariablesReport)]) {
+        let mut summary = String::new();
+        for (name, report) in reports.iter() {
+            println!("{}:\n{}", name, report);
+            summary.push_str(&findings_summary(name, report));
+        }
+
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("analysis_snapshots")
+            .join(format!("{}.txt", snapshot_name));
+        if std::env::var("UPDATE_ANALYSIS_SNAPSHOTS").is_ok() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, summary).unwrap();
+            return;
+        }
+
+        let snapshot = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "snapshot {:?} is missing, run with UPDATE_ANALYSIS_SNAPSHOTS=1 to create it",
+                path
+            )
+        });
+        assert_eq!(
+            summary, snapshot,
+            "findings differ from the snapshot {:?}, review them and run with UPDATE_ANALYSIS_SNAPSHOTS=1",
+            path
+        );
+    }
+
+    #[test]
+    fn analyze_all_machines() {
+        check_snapshot(
+            "machines",
+            &[
+                (
+                    "Minimal machine",
+                    analyze_machine(MinimalMachineNoExceptionHandling),
+                ),
+                (
+                    "Minimal machine with delegation",
+                    analyze_machine(MinimalMachineNoExceptionHandlingWithDelegation),
+                ),
+                (
+                    "Full ISA machine",
+                    analyze_machine(FullIsaMachineNoExceptionHandling),
+                ),
+                (
+                    "Full ISA machine with delegation",
+                    analyze_machine(FullIsaMachineWithDelegationNoExceptionHandling),
+                ),
+                (
+                    "Full ISA machine with delegation without signed mul/div",
+                    analyze_machine(FullIsaMachineWithDelegationNoExceptionHandlingNoSignedMulDiv),
+                ),
+                (
+                    "Full ISA machine with delegation and bitmanip",
+                    analyze_machine(FullIsaMachineWithDelegationAndBitmanipNoExceptionHandling),
+                ),
+                (
+                    "Full ISA machine with delegation and compressed instructions",
+                    analyze_machine(FullIsaMachineWithDelegationAndCompressedNoExceptionHandling),
+                ),
+                (
+                    "Full ISA machine with delegation and trap handling",
+                    analyze_machine(FullIsaMachineWithDelegationAndTrapHandling),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn analyze_all_delegation_circuits() {
+        check_snapshot(
+            "delegation_circuits",
+            &[
+                (
+                    "Bigint with control",
+                    analyze_delegation_circuit(
+                        bigint_with_control::define_u256_ops_extended_control_delegation_circuit,
+                    ),
+                ),
+                (
+                    "Blake2 round with extended control",
+                    analyze_delegation_circuit(
+                        blake2_round_with_extended_control::define_blake2_with_extended_control_delegation_circuit,
+                    ),
+                ),
+                (
+                    "Blake2 single round",
+                    analyze_delegation_circuit(
+                        blake2_single_round::define_blake2_single_round_delegation_circuit,
+                    ),
+                ),
+                (
+                    "Keccak f1600 round",
+                    analyze_delegation_circuit(
+                        keccak_f1600_round::define_keccak_f1600_round_delegation_circuit,
+                    ),
+                ),
+                (
+                    "Poseidon2 permutation",
+                    analyze_delegation_circuit(
+                        poseidon2_permutation::define_poseidon2_permutation_delegation_circuit,
+                    ),
+                ),
+                (
+                    "SHA256 round with message schedule",
+                    analyze_delegation_circuit(
+                        sha256_round_with_message_schedule::define_sha256_round_with_message_schedule_delegation_circuit,
+                    ),
+                ),
+            ],
+        );
+    }
+}
@@ -27,6 +27,7 @@ pub const ENABLE_LOGGING: bool = true;
 pub const ENABLE_LOGGING: bool = false;
 
 #[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Invariant {
     Boolean,
     RangeChecked { width: u32 },
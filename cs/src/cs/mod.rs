@@ -1,3 +1,4 @@
+pub mod analysis;
 pub mod circuit;
 pub mod cs_reference;
 pub mod oracle;
@@ -0,0 +1,451 @@
+// Expansion of RV32C (compressed) instructions into their 32-bit equivalents. It is shared between
+// the simulator and the ROM table generation for circuits, so both agree on the semantics
+
+pub const COMPRESSED_INSTRUCTION_LEN: u32 = 2;
+pub const FULL_INSTRUCTION_LEN: u32 = 4;
+
+const OPCODE_LOAD: u32 = 0b0000011;
+const OPCODE_OP_IMM: u32 = 0b0010011;
+const OPCODE_STORE: u32 = 0b0100011;
+const OPCODE_OP: u32 = 0b0110011;
+const OPCODE_LUI: u32 = 0b0110111;
+const OPCODE_BRANCH: u32 = 0b1100011;
+const OPCODE_JALR: u32 = 0b1100111;
+const OPCODE_JAL: u32 = 0b1101111;
+
+const EBREAK: u32 = 0x00100073;
+
+const REG_RA: u32 = 1;
+const REG_SP: u32 = 2;
+
+/// Compressed instructions have lowest two bits different from 0b11
+#[must_use]
+#[inline(always)]
+pub const fn is_compressed_instruction(low_half_word: u32) -> bool {
+    low_half_word & 0b11 != 0b11
+}
+
+#[inline(always)]
+const fn bits(src: u32, from_bit: u32, num_bits: u32) -> u32 {
+    (src >> from_bit) & ((1 << num_bits) - 1)
+}
+
+// takes `num_bits` bits starting at `from_bit` and places them at `to_bit`
+#[inline(always)]
+const fn move_bits(src: u32, from_bit: u32, num_bits: u32, to_bit: u32) -> u32 {
+    bits(src, from_bit, num_bits) << to_bit
+}
+
+#[inline(always)]
+const fn sign_extend(value: u32, total_bits: u32) -> u32 {
+    (((value << (32 - total_bits)) as i32) >> (32 - total_bits)) as u32
+}
+
+// registers x8-x15 encoded as 3 bits
+#[inline(always)]
+const fn compressed_reg(src: u32, from_bit: u32) -> u32 {
+    bits(src, from_bit, 3) + 8
+}
+
+#[inline(always)]
+const fn r_type(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+#[inline(always)]
+const fn i_type(imm: u32, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> u32 {
+    ((imm & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+#[inline(always)]
+const fn s_type(imm: u32, rs2: u32, rs1: u32, funct3: u32, opcode: u32) -> u32 {
+    (bits(imm, 5, 7) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 0, 5) << 7)
+        | opcode
+}
+
+#[inline(always)]
+const fn b_type(imm: u32, rs2: u32, rs1: u32, funct3: u32, opcode: u32) -> u32 {
+    (bits(imm, 12, 1) << 31)
+        | (bits(imm, 5, 6) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 1, 4) << 8)
+        | (bits(imm, 11, 1) << 7)
+        | opcode
+}
+
+#[inline(always)]
+const fn u_type(imm: u32, rd: u32, opcode: u32) -> u32 {
+    (imm & 0xfffff000) | (rd << 7) | opcode
+}
+
+#[inline(always)]
+const fn j_type(imm: u32, rd: u32, opcode: u32) -> u32 {
+    (bits(imm, 20, 1) << 31)
+        | (bits(imm, 1, 10) << 21)
+        | (bits(imm, 11, 1) << 20)
+        | (bits(imm, 12, 8) << 12)
+        | (rd << 7)
+        | opcode
+}
+
+// offset of C.J and C.JAL: [11|4|9:8|10|6|7|3:1|5]
+#[inline(always)]
+const fn cj_offset(src: u32) -> u32 {
+    let offset = move_bits(src, 12, 1, 11)
+        | move_bits(src, 11, 1, 4)
+        | move_bits(src, 9, 2, 8)
+        | move_bits(src, 8, 1, 10)
+        | move_bits(src, 7, 1, 6)
+        | move_bits(src, 6, 1, 7)
+        | move_bits(src, 3, 3, 1)
+        | move_bits(src, 2, 1, 5);
+
+    sign_extend(offset, 12)
+}
+
+// offset of C.BEQZ and C.BNEZ: [8|4:3] and [7:6|2:1|5]
+#[inline(always)]
+const fn cb_offset(src: u32) -> u32 {
+    let offset = move_bits(src, 12, 1, 8)
+        | move_bits(src, 10, 2, 3)
+        | move_bits(src, 5, 2, 6)
+        | move_bits(src, 3, 2, 1)
+        | move_bits(src, 2, 1, 5);
+
+    sign_extend(offset, 9)
+}
+
+// 6-bit signed immediate of C.ADDI, C.LI, C.ANDI: [5] and [4:0]
+#[inline(always)]
+const fn ci_imm(src: u32) -> u32 {
+    sign_extend(move_bits(src, 12, 1, 5) | bits(src, 2, 5), 6)
+}
+
+// 6-bit shift amount of C.SLLI, C.SRLI, C.SRAI. Only 5 bits are valid for RV32
+#[inline(always)]
+const fn ci_shamt(src: u32) -> Option<u32> {
+    if bits(src, 12, 1) != 0 {
+        None
+    } else {
+        Some(bits(src, 2, 5))
+    }
+}
+
+// offset of C.LW and C.SW: [5:3] and [2|6]
+#[inline(always)]
+const fn cl_offset(src: u32) -> u32 {
+    move_bits(src, 10, 3, 3) | move_bits(src, 6, 1, 2) | move_bits(src, 5, 1, 6)
+}
+
+/// Expands a 16-bit RV32C instruction into the equivalent 32-bit instruction. Returns `None`
+/// for illegal and reserved encodings, and for instructions from extensions that we do not
+/// support (floating point loads and stores)
+#[must_use]
+pub const fn expand_compressed_instruction(instruction: u16) -> Option<u32> {
+    let src = instruction as u32;
+    let quadrant = bits(src, 0, 2);
+    let funct3 = bits(src, 13, 3);
+    // full 5-bit register indexes of CR/CI/CSS formats
+    let rd_rs1 = bits(src, 7, 5);
+    let rs2 = bits(src, 2, 5);
+
+    let expanded = match (quadrant, funct3) {
+        (0b00, 0b000) => {
+            // C.ADDI4SPN: nzuimm[5:4|9:6|2|3]
+            let imm = move_bits(src, 11, 2, 4)
+                | move_bits(src, 7, 4, 6)
+                | move_bits(src, 6, 1, 2)
+                | move_bits(src, 5, 1, 3);
+            if imm == 0 {
+                // also covers the all-zeroes illegal instruction
+                return None;
+            }
+            i_type(imm, REG_SP, 0b000, compressed_reg(src, 2), OPCODE_OP_IMM)
+        }
+        (0b00, 0b010) => {
+            // C.LW
+            i_type(
+                cl_offset(src),
+                compressed_reg(src, 7),
+                0b010,
+                compressed_reg(src, 2),
+                OPCODE_LOAD,
+            )
+        }
+        (0b00, 0b110) => {
+            // C.SW
+            s_type(
+                cl_offset(src),
+                compressed_reg(src, 2),
+                compressed_reg(src, 7),
+                0b010,
+                OPCODE_STORE,
+            )
+        }
+        (0b01, 0b000) => {
+            // C.ADDI (C.NOP for rd = x0)
+            i_type(ci_imm(src), rd_rs1, 0b000, rd_rs1, OPCODE_OP_IMM)
+        }
+        (0b01, 0b001) => {
+            // C.JAL
+            j_type(cj_offset(src), REG_RA, OPCODE_JAL)
+        }
+        (0b01, 0b010) => {
+            // C.LI
+            i_type(ci_imm(src), 0, 0b000, rd_rs1, OPCODE_OP_IMM)
+        }
+        (0b01, 0b011) => {
+            if rd_rs1 == REG_SP {
+                // C.ADDI16SP: nzimm[9] and [4|6|8:7|5]
+                let imm = move_bits(src, 12, 1, 9)
+                    | move_bits(src, 6, 1, 4)
+                    | move_bits(src, 5, 1, 6)
+                    | move_bits(src, 3, 2, 7)
+                    | move_bits(src, 2, 1, 5);
+                if imm == 0 {
+                    return None;
+                }
+                i_type(sign_extend(imm, 10), REG_SP, 0b000, REG_SP, OPCODE_OP_IMM)
+            } else {
+                // C.LUI: nzimm[17] and [16:12]
+                let imm = move_bits(src, 12, 1, 17) | move_bits(src, 2, 5, 12);
+                if imm == 0 {
+                    return None;
+                }
+                u_type(sign_extend(imm, 18), rd_rs1, OPCODE_LUI)
+            }
+        }
+        (0b01, 0b100) => {
+            let rd_rs1 = compressed_reg(src, 7);
+            match bits(src, 10, 2) {
+                0b00 => {
+                    // C.SRLI
+                    let Some(shamt) = ci_shamt(src) else {
+                        return None;
+                    };
+                    i_type(shamt, rd_rs1, 0b101, rd_rs1, OPCODE_OP_IMM)
+                }
+                0b01 => {
+                    // C.SRAI
+                    let Some(shamt) = ci_shamt(src) else {
+                        return None;
+                    };
+                    i_type(
+                        (0b0100000 << 5) | shamt,
+                        rd_rs1,
+                        0b101,
+                        rd_rs1,
+                        OPCODE_OP_IMM,
+                    )
+                }
+                0b10 => {
+                    // C.ANDI
+                    i_type(ci_imm(src), rd_rs1, 0b111, rd_rs1, OPCODE_OP_IMM)
+                }
+                _ => {
+                    if bits(src, 12, 1) != 0 {
+                        // C.SUBW and C.ADDW are RV64 only
+                        return None;
+                    }
+                    let rs2 = compressed_reg(src, 2);
+                    let (funct7, funct3) = match bits(src, 5, 2) {
+                        0b00 => (0b0100000, 0b000), // C.SUB
+                        0b01 => (0b0000000, 0b100), // C.XOR
+                        0b10 => (0b0000000, 0b110), // C.OR
+                        _ => (0b0000000, 0b111),    // C.AND
+                    };
+                    r_type(funct7, rs2, rd_rs1, funct3, rd_rs1, OPCODE_OP)
+                }
+            }
+        }
+        (0b01, 0b101) => {
+            // C.J
+            j_type(cj_offset(src), 0, OPCODE_JAL)
+        }
+        (0b01, 0b110) => {
+            // C.BEQZ
+            b_type(
+                cb_offset(src),
+                0,
+                compressed_reg(src, 7),
+                0b000,
+                OPCODE_BRANCH,
+            )
+        }
+        (0b01, 0b111) => {
+            // C.BNEZ
+            b_type(
+                cb_offset(src),
+                0,
+                compressed_reg(src, 7),
+                0b001,
+                OPCODE_BRANCH,
+            )
+        }
+        (0b10, 0b000) => {
+            // C.SLLI
+            let Some(shamt) = ci_shamt(src) else {
+                return None;
+            };
+            i_type(shamt, rd_rs1, 0b001, rd_rs1, OPCODE_OP_IMM)
+        }
+        (0b10, 0b010) => {
+            // C.LWSP: uimm[5] and [4:2|7:6]
+            if rd_rs1 == 0 {
+                return None;
+            }
+            let imm = move_bits(src, 12, 1, 5) | move_bits(src, 4, 3, 2) | move_bits(src, 2, 2, 6);
+            i_type(imm, REG_SP, 0b010, rd_rs1, OPCODE_LOAD)
+        }
+        (0b10, 0b100) => {
+            let bit_12 = bits(src, 12, 1);
+            match (bit_12, rd_rs1, rs2) {
+                (0, 0, 0) => {
+                    // C.JR with rs1 = x0 is reserved
+                    return None;
+                }
+                (0, _, 0) => {
+                    // C.JR
+                    i_type(0, rd_rs1, 0b000, 0, OPCODE_JALR)
+                }
+                (0, _, _) => {
+                    // C.MV
+                    r_type(0, rs2, 0, 0b000, rd_rs1, OPCODE_OP)
+                }
+                (_, 0, 0) => {
+                    // C.EBREAK
+                    EBREAK
+                }
+                (_, _, 0) => {
+                    // C.JALR
+                    i_type(0, rd_rs1, 0b000, REG_RA, OPCODE_JALR)
+                }
+                (_, _, _) => {
+                    // C.ADD
+                    r_type(0, rs2, rd_rs1, 0b000, rd_rs1, OPCODE_OP)
+                }
+            }
+        }
+        (0b10, 0b110) => {
+            // C.SWSP: uimm[5:2|7:6]
+            let imm = move_bits(src, 9, 4, 2) | move_bits(src, 7, 2, 6);
+            s_type(imm, rs2, REG_SP, 0b010, OPCODE_STORE)
+        }
+        _ => {
+            // floating point loads and stores, reserved encodings and quadrant 0b11
+            // that is not a compressed instruction at all
+            return None;
+        }
+    };
+
+    Some(expanded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // compressed encodings and their expansions, as produced by the reference assembler
+    const EXPANSIONS: &[(&str, u16, u32)] = &[
+        ("c.addi4spn x8, sp, 16", 0x0800, 0x01010413),
+        ("c.lw x9, 4(x10)", 0x4144, 0x00452483),
+        ("c.sw x15, 124(x8)", 0xdc7c, 0x06f42e23),
+        ("c.nop", 0x0001, 0x00000013),
+        ("c.addi x5, -1", 0x12fd, 0xfff28293),
+        ("c.jal 2046", 0x2ffd, 0x7fe000ef),
+        ("c.jal -2048", 0x3001, 0x801ff0ef),
+        ("c.li x31, 31", 0x4ffd, 0x01f00f93),
+        ("c.addi16sp sp, -512", 0x7101, 0xe0010113),
+        ("c.addi16sp sp, 496", 0x617d, 0x1f010113),
+        ("c.lui x3, 0xfffff", 0x71fd, 0xfffff1b7),
+        ("c.lui x3, 1", 0x6185, 0x000011b7),
+        ("c.srli x8, 31", 0x807d, 0x01f45413),
+        ("c.srai x9, 1", 0x8485, 0x4014d493),
+        ("c.andi x10, -32", 0x9901, 0xfe057513),
+        ("c.sub x8, x9", 0x8c05, 0x40940433),
+        ("c.xor x10, x11", 0x8d2d, 0x00b54533),
+        ("c.or x12, x13", 0x8e55, 0x00d66633),
+        ("c.and x14, x15", 0x8f7d, 0x00f77733),
+        ("c.j -2", 0xbffd, 0xfffff06f),
+        ("c.beqz x8, -256", 0xd001, 0xf00400e3),
+        ("c.bnez x15, 254", 0xeffd, 0x0e079f63),
+        ("c.slli x1, 31", 0x00fe, 0x01f09093),
+        ("c.lwsp x1, 252(sp)", 0x50fe, 0x0fc12083),
+        ("c.jr x1", 0x8082, 0x00008067),
+        ("c.mv x5, x6", 0x829a, 0x006002b3),
+        ("c.ebreak", 0x9002, 0x00100073),
+        ("c.jalr x7", 0x9382, 0x000380e7),
+        ("c.add x8, x9", 0x9426, 0x00940433),
+        ("c.swsp x31, 252(sp)", 0xdffe, 0x0ff12e23),
+        // HINTs are legal and expand to instructions without architectural effect
+        ("c.li x0, 1", 0x4005, 0x00100013),
+        ("c.add x0, x1", 0x9006, 0x00100033),
+    ];
+
+    const ILLEGAL_OR_RESERVED: &[(&str, u16)] = &[
+        ("all zeroes", 0x0000),
+        ("c.addi4spn with zero immediate", 0x0004),
+        ("c.fld", 0x2000),
+        ("c.flw", 0x6000),
+        ("reserved quadrant 0 encoding", 0x8000),
+        ("c.fsd", 0xa000),
+        ("c.fsw", 0xe000),
+        ("c.addi16sp with zero immediate", 0x6101),
+        ("c.lui with zero immediate", 0x6181),
+        ("c.srli with shamt[5] set", 0x9005),
+        ("c.srai with shamt[5] set", 0x9405),
+        ("c.subw", 0x9c01),
+        ("c.addw", 0x9c21),
+        ("reserved arithmetic encoding 0b10", 0x9c41),
+        ("reserved arithmetic encoding 0b11", 0x9c61),
+        ("c.slli with shamt[5] set", 0x1086),
+        ("c.fldsp", 0x2002),
+        ("c.lwsp into x0", 0x4002),
+        ("c.flwsp", 0x6002),
+        ("c.jr x0", 0x8002),
+        ("c.fsdsp", 0xa002),
+        ("c.fswsp", 0xe002),
+    ];
+
+    #[test]
+    fn expands_known_encodings() {
+        for (name, compressed, expected) in EXPANSIONS.iter() {
+            assert!(is_compressed_instruction(*compressed as u32));
+            assert_eq!(
+                expand_compressed_instruction(*compressed),
+                Some(*expected),
+                "unexpected expansion of `{}` (0x{:04x})",
+                name,
+                compressed
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_illegal_and_reserved_encodings() {
+        for (name, compressed) in ILLEGAL_OR_RESERVED.iter() {
+            assert_eq!(
+                expand_compressed_instruction(*compressed),
+                None,
+                "{} (0x{:04x}) must not be expanded",
+                name,
+                compressed
+            );
+        }
+    }
+
+    #[test]
+    fn full_length_instructions_are_not_expanded() {
+        for high in 0..(1u32 << 14) {
+            let instruction = ((high << 2) | 0b11) as u16;
+            assert!(!is_compressed_instruction(instruction as u32));
+            assert_eq!(expand_compressed_instruction(instruction), None);
+        }
+    }
+}
@@ -7,6 +7,7 @@ extern crate alloc;
 use alloc::vec::Vec;
 
 mod columns;
+mod compressed_instructions;
 mod constants;
 mod constraints;
 mod delegation;
@@ -19,6 +20,7 @@ mod table_type;
 mod witness_tree;
 
 pub use self::columns::*;
+pub use self::compressed_instructions::*;
 pub use self::constants::*;
 pub use self::constraints::*;
 pub use self::delegation::*;
@@ -54,6 +54,9 @@ pub enum TableType {
     StoreByteSourceContribution,
     StoreByteExistingContribution,
     TruncateShift,
+    BitCountAndSignExtend,
+    ByteWiseBitmanip,
+    RomInstructionRead,
     DynamicPlaceholder,
 }
 
@@ -0,0 +1,478 @@
+use super::*;
+use crate::cs::circuit::*;
+use crate::cs::utils::collapse_max_quadratic_constraint_into;
+use crate::cs::witness_placer::*;
+use crate::delegation::sha256_round_with_message_schedule::{
+    one_hot_round_selectors, pack_bits_into_limbs, split_word_into_bits,
+};
+use crate::one_row_compiler::Variable;
+use crate::types::Boolean;
+use crate::types::Num;
+
+// ABI:
+// - registers x10-x11 are used to pass the parameters
+// - x10 is a pointer to 50 words of state: 25 lanes in the usual `x + 5 * y` order, each lane is two
+//   little-endian words (low word first)
+// - x11 contains the round index in [0, 24)
+//
+// Single call performs one round of Keccak-f[1600], so full permutation is 24 calls with round indexes
+// 0..24. Round constant for the iota step is selected inside of the circuit by the round index,
+// so caller can not substitute it
+
+const NUM_LANES: usize = 25;
+const NUM_STATE_WORDS: usize = NUM_LANES * 2;
+const LANE_BITS: usize = 64;
+pub const NUM_ROUNDS: usize = 24;
+
+pub const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+const RHO_OFFSETS: [usize; NUM_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+pub fn all_table_types() -> Vec<TableType> {
+    // only bit decompositions are used
+    vec![]
+}
+
+pub fn keccak_f1600_round_delegation_circuit_create_table_driver<F: PrimeField>() -> TableDriver<F>
+{
+    let mut table_driver = TableDriver::new();
+    for el in all_table_types() {
+        table_driver.materialize_table(el);
+    }
+
+    table_driver
+}
+
+pub fn materialize_tables_into_cs<F: PrimeField, CS: Circuit<F>>(cs: &mut CS) {
+    for el in all_table_types() {
+        cs.materialize_table(el);
+    }
+}
+
+pub fn define_keccak_f1600_round_delegation_circuit<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+) -> Vec<[Variable; 2]> {
+    // add tables
+    materialize_tables_into_cs(cs);
+
+    // the only convention we must eventually satisfy is that if we do NOT process delegation request,
+    // then all memory writes in ABI must be 0s
+
+    let execute = cs.process_delegation_request();
+
+    let x10_request = RegisterAccessRequest {
+        register_index: 10,
+        register_write: false,
+        indirects_alignment_log2: 8, // 256 bytes - 200 for the state are needed
+        indirect_accesses: vec![true; NUM_STATE_WORDS],
+    };
+
+    let x11_request = RegisterAccessRequest {
+        register_index: 11,
+        register_write: false,
+        indirects_alignment_log2: 0, // no indirects
+        indirect_accesses: vec![],
+    };
+
+    let x10_and_indirects = cs.create_register_and_indirect_memory_accesses(x10_request);
+    let x11_and_indirects = cs.create_register_and_indirect_memory_accesses(x11_request);
+
+    assert_eq!(x10_and_indirects.indirect_accesses.len(), NUM_STATE_WORDS);
+    assert!(x11_and_indirects.indirect_accesses.is_empty());
+
+    let mut input_state = vec![];
+    let mut output_placeholder_state = vec![];
+    for i in 0..NUM_STATE_WORDS {
+        let IndirectAccessType::Write {
+            read_value,
+            write_value,
+        } = x10_and_indirects.indirect_accesses[i]
+        else {
+            panic!()
+        };
+
+        input_state.push(read_value);
+        output_placeholder_state.push(write_value);
+    }
+
+    let round_index = {
+        let RegisterAccessType::Read { read_value } = x11_and_indirects.register_access else {
+            panic!()
+        };
+
+        read_value
+    };
+
+    {
+        for (i, input) in input_state.iter().enumerate() {
+            let register = Register::<F>(input.map(|el| Num::Var(el)));
+            if let Some(value) = register.get_value_unsigned(&*cs) {
+                println!("Input state element {} = 0x{:08x}", i, value);
+            }
+        }
+
+        let register = Register::<F>(round_index.map(|el| Num::Var(el)));
+        if let Some(value) = register.get_value_unsigned(&*cs) {
+            println!("Round index = {}", value);
+        }
+    }
+
+    let round_constant = select_round_constant(cs, execute, round_index);
+
+    // NOTE on masking: we do NOT need to mask anything here based on the execute/not predicate,
+    // because if we do not execute, then all read values and round constant are 0, and every step
+    // of the round maps zero state into zero state
+
+    // all steps are bitwise, and rotations and lane permutation become free after decomposition
+    let lanes: Vec<[Boolean; LANE_BITS]> = (0..NUM_LANES)
+        .map(|i| split_lane_into_bits(cs, input_state[2 * i], input_state[2 * i + 1]))
+        .collect();
+
+    // theta: column parities C[x] = A[x, 0] ^ ... ^ A[x, 4], and D[x] = C[x - 1] ^ rotl(C[x + 1], 1)
+    let column_parities: [[Boolean; LANE_BITS]; 5] = std::array::from_fn(|x| {
+        std::array::from_fn(|z| {
+            let column: [Boolean; 5] = std::array::from_fn(|y| lanes[x + 5 * y][z]);
+            xor5(cs, column)
+        })
+    });
+    let theta_masks: [[Boolean; LANE_BITS]; 5] = std::array::from_fn(|x| {
+        let left = &column_parities[(x + 4) % 5];
+        let right = rotate_left(&column_parities[(x + 1) % 5], 1);
+        std::array::from_fn(|z| Boolean::xor(&left[z], &right[z], cs))
+    });
+    let lanes: Vec<[Boolean; LANE_BITS]> = lanes
+        .iter()
+        .enumerate()
+        .map(|(idx, lane)| {
+            let mask = &theta_masks[idx % 5];
+            std::array::from_fn(|z| Boolean::xor(&lane[z], &mask[z], cs))
+        })
+        .collect();
+
+    // rho and pi: B[y, 2x + 3y] = rotl(A[x, y], r[x, y])
+    let mut permuted_lanes = vec![[Boolean::Constant(false); LANE_BITS]; NUM_LANES];
+    for x in 0..5 {
+        for y in 0..5 {
+            let idx = x + 5 * y;
+            permuted_lanes[y + 5 * ((2 * x + 3 * y) % 5)] =
+                rotate_left(&lanes[idx], RHO_OFFSETS[idx]);
+        }
+    }
+
+    // chi: A[x, y] = B[x, y] ^ (!B[x + 1, y] & B[x + 2, y]). We need the AND as a variable,
+    // and XOR with it is still degree 2, so we can write it into memory directly
+    let mut output_lanes: Vec<[Constraint<F>; LANE_BITS]> = Vec::with_capacity(NUM_LANES);
+    for y in 0..5 {
+        for x in 0..5 {
+            let b = &permuted_lanes[x + 5 * y];
+            let b1 = &permuted_lanes[(x + 1) % 5 + 5 * y];
+            let b2 = &permuted_lanes[(x + 2) % 5 + 5 * y];
+            let lane: [Constraint<F>; LANE_BITS] = std::array::from_fn(|z| {
+                let t = Boolean::and(&b1[z].toggle(), &b2[z], cs);
+                xor_constraint(b[z], t)
+            });
+            output_lanes.push(lane);
+        }
+    }
+
+    // iota: only the first lane is affected, so we materialize it and XOR with round constant
+    let first_lane: [Constraint<F>; LANE_BITS] = std::array::from_fn(|z| {
+        let chi_output = Boolean::Is(cs.add_variable_from_constraint(output_lanes[0][z].clone()));
+        xor_with_linear_constraint(chi_output, round_constant[z].clone())
+    });
+    output_lanes[0] = first_lane;
+
+    // now we can write everything into memory
+    for (lane_idx, lane) in output_lanes.into_iter().enumerate() {
+        let (low_bits, high_bits) = lane.split_at(32);
+        let words = [
+            pack_bits_into_limbs(low_bits.try_into().unwrap()),
+            pack_bits_into_limbs(high_bits.try_into().unwrap()),
+        ];
+        for (word_idx, word) in words.into_iter().enumerate() {
+            let dst = output_placeholder_state[2 * lane_idx + word_idx];
+            for (src, dst) in word.into_iter().zip(dst.iter()) {
+                let mut constraint = src;
+                // set value
+                collapse_max_quadratic_constraint_into(cs, constraint.clone(), *dst);
+                // add constraint
+                constraint -= Term::from(*dst);
+                cs.add_constraint_allow_explicit_linear(constraint);
+            }
+        }
+    }
+
+    {
+        for (i, input) in output_placeholder_state.iter().enumerate() {
+            let register = Register::<F>(input.map(|el| Num::Var(el)));
+            if let Some(value) = register.get_value_unsigned(&*cs) {
+                println!("Output state element {} = 0x{:08x}", i, value);
+            }
+        }
+    }
+
+    output_placeholder_state
+}
+
+// Little-endian bits of the lane given as low and high words
+fn split_lane_into_bits<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+    low: [Variable; 2],
+    high: [Variable; 2],
+) -> [Boolean; LANE_BITS] {
+    let low = split_word_into_bits(cs, low);
+    let high = split_word_into_bits(cs, high);
+
+    std::array::from_fn(|i| if i < 32 { low[i] } else { high[i - 32] })
+}
+
+// Every bit of the round constant is linear in the one-hot round selectors, and as at most one selector
+// is set, it's boolean
+fn select_round_constant<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+    execute: Boolean,
+    round_index: [Variable; 2],
+) -> [Constraint<F>; LANE_BITS] {
+    let selectors: [Variable; NUM_ROUNDS] = one_hot_round_selectors(cs, execute, round_index);
+
+    std::array::from_fn(|z| {
+        let mut bit = Constraint::<F>::empty();
+        for (selector, round_constant) in selectors.iter().zip(ROUND_CONSTANTS.iter()) {
+            if (round_constant >> z) & 1 == 1 {
+                bit += Term::from(*selector);
+            }
+        }
+
+        bit
+    })
+}
+
+fn rotate_left(bits: &[Boolean; LANE_BITS], amount: usize) -> [Boolean; LANE_BITS] {
+    std::array::from_fn(|i| bits[(i + LANE_BITS - amount) % LANE_BITS])
+}
+
+fn xor_constraint<F: PrimeField>(a: Boolean, b: Boolean) -> Constraint<F> {
+    Constraint::empty() + Term::from(a) + Term::from(b)
+        - Term::from(2u64) * Term::from(a) * Term::from(b)
+}
+
+// Same as above, but `b` is a boolean given as a linear constraint
+fn xor_with_linear_constraint<F: PrimeField>(a: Boolean, b: Constraint<F>) -> Constraint<F> {
+    Constraint::from(a) + b.clone() - (Term::from(2u64) * Term::from(a)) * b
+}
+
+// Parity of 5 bits. Their sum is in [0, 5], so we represent it as `parity + 2 * (q0 + q1)`
+// with all boolean variables, that is cheaper than the chain of 4 XORs
+fn xor5<F: PrimeField, CS: Circuit<F>>(cs: &mut CS, bits: [Boolean; 5]) -> Boolean {
+    let parity = cs.add_boolean_variable();
+    let q0 = cs.add_boolean_variable();
+    let q1 = cs.add_boolean_variable();
+
+    let input_vars = bits.map(|el| el.get_variable().unwrap());
+    let [parity_var, q0_var, q1_var] = [parity, q0, q1].map(|el| el.get_variable().unwrap());
+
+    let value_fn = move |placer: &mut CS::WitnessPlacer| {
+        let mut sum = placer.get_field(input_vars[0]);
+        for var in input_vars[1..].iter() {
+            sum.add_assign(&placer.get_field(*var));
+        }
+        let sum = sum.as_integer();
+        placer.assign_mask(parity_var, &sum.get_bit(0));
+        // sum >> 1 is in [0, 2], and we encode it as q0 + q1
+        let high_bit = sum.get_bit(2);
+        placer.assign_mask(q0_var, &sum.get_bit(1).or(&high_bit));
+        placer.assign_mask(q1_var, &high_bit);
+    };
+    cs.set_values(value_fn);
+
+    let mut constraint = Constraint::<F>::empty();
+    for bit in bits.into_iter() {
+        constraint += Term::from(bit);
+    }
+    constraint -= Term::from(parity);
+    constraint -= Term::from((F::from_u64_unchecked(2), q0_var));
+    constraint -= Term::from((F::from_u64_unchecked(2), q1_var));
+    cs.add_constraint_allow_explicit_linear(constraint);
+
+    parity
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cs::cs_reference::BasicAssembly;
+    use crate::cs::oracle::Oracle;
+    use crate::cs::placeholder::Placeholder;
+    use crate::definitions::TimestampScalar;
+    use crate::one_row_compiler::OneRowCompiler;
+    use crate::utils::serialize_to_file;
+    use field::Mersenne31Field;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    struct KeccakRoundOracle {
+        state: [u32; NUM_STATE_WORDS],
+        round_index: u32,
+    }
+
+    impl<F: PrimeField> Oracle<F> for KeccakRoundOracle {
+        fn get_witness_from_placeholder(
+            &self,
+            placeholder: Placeholder,
+            subindex: usize,
+            trace_row: usize,
+        ) -> F {
+            let value = match placeholder {
+                Placeholder::ExecuteDelegation => 1,
+                _ => {
+                    let value = <Self as Oracle<F>>::get_u32_witness_from_placeholder(
+                        self,
+                        placeholder,
+                        trace_row,
+                    );
+                    (value >> (16 * subindex)) & 0xffff
+                }
+            };
+
+            F::from_u64_unchecked(value as u64)
+        }
+
+        fn get_u32_witness_from_placeholder(&self, placeholder: Placeholder, _: usize) -> u32 {
+            match placeholder {
+                Placeholder::DelegationRegisterReadValue(11) => self.round_index,
+                Placeholder::DelegationIndirectReadValue {
+                    register_index: 10,
+                    word_index,
+                } => self.state[word_index],
+                _ => 0,
+            }
+        }
+
+        fn get_timestamp_witness_from_placeholder(
+            &self,
+            _placeholder: Placeholder,
+            _trace_row: usize,
+        ) -> TimestampScalar {
+            0
+        }
+    }
+
+    fn reference_round(input: &[u32; NUM_STATE_WORDS], round_index: u32) -> [u32; NUM_STATE_WORDS] {
+        let mut a: [u64; NUM_LANES] =
+            std::array::from_fn(|i| (input[2 * i] as u64) | ((input[2 * i + 1] as u64) << 32));
+        let c: [u64; 5] =
+            std::array::from_fn(|x| a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20]);
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+        let mut b = [0u64; NUM_LANES];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    a[x + 5 * y].rotate_left(RHO_OFFSETS[x + 5 * y] as u32);
+            }
+        }
+        for x in 0..5 {
+            for y in 0..5 {
+                a[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        a[0] ^= ROUND_CONSTANTS[round_index as usize];
+
+        std::array::from_fn(|i| (a[i / 2] >> (32 * (i % 2))) as u32)
+    }
+
+    #[test]
+    fn compile_keccak_f1600_round() {
+        let mut cs = BasicAssembly::<Mersenne31Field>::new();
+        define_keccak_f1600_round_delegation_circuit(&mut cs);
+        let (circuit_output, _) = cs.finalize();
+        let compiler = OneRowCompiler::default();
+        let compiled = compiler.compile_to_evaluate_delegations(circuit_output, 20);
+
+        serialize_to_file(&compiled, "keccak_delegation_layout.json");
+    }
+
+    #[test]
+    fn keccak_delegation_get_witness_graph() {
+        let ssa_forms = dump_ssa_witness_eval_form_for_delegation::<Mersenne31Field, _>(
+            define_keccak_f1600_round_delegation_circuit,
+        );
+        serialize_to_file(&ssa_forms, "keccak_delegation_ssa.json");
+    }
+
+    #[test]
+    fn keccak_f1600_round_is_satisfied() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut inputs = vec![
+            ([0u32; NUM_STATE_WORDS], 0u32),
+            ([u32::MAX; NUM_STATE_WORDS], NUM_ROUNDS as u32 - 1),
+        ];
+        for _ in 0..8 {
+            inputs.push((
+                std::array::from_fn(|_| rng.random()),
+                rng.random_range(0..NUM_ROUNDS as u32),
+            ));
+        }
+
+        for (state, round_index) in inputs.into_iter() {
+            let oracle = KeccakRoundOracle { state, round_index };
+            let mut cs = BasicAssembly::<Mersenne31Field>::new_with_oracle(oracle);
+            let output_state = define_keccak_f1600_round_delegation_circuit(&mut cs);
+            assert!(cs.is_satisfied());
+
+            let outputs: Vec<u32> = output_state
+                .iter()
+                .map(|el| {
+                    Register::<Mersenne31Field>(el.map(|el| Num::Var(el)))
+                        .get_value_unsigned(&cs)
+                        .unwrap()
+                })
+                .collect();
+            assert_eq!(outputs, reference_round(&state, round_index).to_vec());
+        }
+    }
+
+    #[test]
+    fn keccak_f1600_round_rejects_out_of_range_round_index() {
+        for round_index in [NUM_ROUNDS as u32, 1 << 16] {
+            let oracle = KeccakRoundOracle {
+                state: [0u32; NUM_STATE_WORDS],
+                round_index,
+            };
+            let mut cs = BasicAssembly::<Mersenne31Field>::new_with_oracle(oracle);
+            define_keccak_f1600_round_delegation_circuit(&mut cs);
+            assert!(!cs.is_satisfied());
+        }
+    }
+}
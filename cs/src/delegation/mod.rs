@@ -8,6 +8,9 @@ use field::PrimeField;
 pub mod bigint_with_control;
 pub mod blake2_round_with_extended_control;
 pub mod blake2_single_round;
+pub mod keccak_f1600_round;
+pub mod poseidon2_permutation;
+pub mod sha256_round_with_message_schedule;
 
 pub fn dump_ssa_witness_eval_form_for_delegation<F: PrimeField, T: Sized>(
     definition_fn: impl Fn(
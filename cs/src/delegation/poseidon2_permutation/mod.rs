@@ -0,0 +1,333 @@
+use super::*;
+use crate::cs::circuit::*;
+use crate::cs::utils::collapse_max_quadratic_constraint_into;
+use crate::cs::witness_placer::*;
+use crate::one_row_compiler::Variable;
+use crate::types::Num;
+use field::Mersenne31Field;
+use poseidon2::m31::{
+    EXTERNAL_INITIAL_CONSTANTS, EXTERNAL_MATRIX, EXTERNAL_TERMINAL_CONSTANTS, INTERNAL_CONSTANTS,
+    INTERNAL_MATRIX,
+};
+
+// ABI:
+// - register x10 is used to pass the parameter
+// - x10 is a pointer to 16 words of the state, each word is interpreted as Mersenne31 element
+//   (not necessarily reduced)
+//
+// Single call performs full Poseidon2 permutation in place. Output words are field elements,
+// but they are also not necessarily reduced, so caller must reduce them. Compression mode
+// (feed-forward of the input) is the caller's responsibility
+
+// NOTE: this circuit only makes sense over Mersenne31, as all constants of the permutation are
+// defined there
+
+const STATE_WIDTH: usize = 16;
+
+pub fn all_table_types() -> Vec<TableType> {
+    // only range checks are used
+    vec![]
+}
+
+pub fn poseidon2_permutation_delegation_circuit_create_table_driver<F: PrimeField>(
+) -> TableDriver<F> {
+    let mut table_driver = TableDriver::new();
+    for el in all_table_types() {
+        table_driver.materialize_table(el);
+    }
+
+    table_driver
+}
+
+pub fn materialize_tables_into_cs<F: PrimeField, CS: Circuit<F>>(cs: &mut CS) {
+    for el in all_table_types() {
+        cs.materialize_table(el);
+    }
+}
+
+pub fn define_poseidon2_permutation_delegation_circuit<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+) -> Vec<[Variable; 2]> {
+    // add tables
+    materialize_tables_into_cs(cs);
+
+    // the only convention we must eventually satisfy is that if we do NOT process delegation request,
+    // then all memory writes in ABI must be 0s
+
+    let execute = cs.process_delegation_request();
+
+    let x10_request = RegisterAccessRequest {
+        register_index: 10,
+        register_write: false,
+        indirects_alignment_log2: 6, // 64 bytes for the state
+        indirect_accesses: vec![true; STATE_WIDTH],
+    };
+
+    let x10_and_indirects = cs.create_register_and_indirect_memory_accesses(x10_request);
+    assert_eq!(x10_and_indirects.indirect_accesses.len(), STATE_WIDTH);
+
+    let mut input_state = vec![];
+    let mut output_placeholder_state = vec![];
+    for i in 0..STATE_WIDTH {
+        let IndirectAccessType::Write {
+            read_value,
+            write_value,
+        } = x10_and_indirects.indirect_accesses[i]
+        else {
+            panic!()
+        };
+
+        input_state.push(read_value);
+        output_placeholder_state.push(write_value);
+    }
+
+    {
+        for (i, input) in input_state.iter().enumerate() {
+            let register = Register::<F>(input.map(|el| Num::Var(el)));
+            if let Some(value) = register.get_value_unsigned(&*cs) {
+                println!("Input state element {} = 0x{:08x}", i, value);
+            }
+        }
+    }
+
+    // NOTE on masking: unlike bitwise delegations, permutation does NOT map zero state into zero
+    // state because of round constants, so we have to explicitly mask the writes by execute predicate
+
+    // word in memory is a field element as `low + 2^16 * high`, and reduction happens for free
+    let mut state: [Constraint<F>; STATE_WIDTH] = std::array::from_fn(|i| {
+        let [low, high] = input_state[i];
+        Constraint::empty() + Term::from(low) + Term::from((F::from_u64_unchecked(1 << 16), high))
+    });
+
+    // everything except s-boxes is linear, so we only materialize the s-box chains
+    // and carry linear combinations in between
+    state = matmul(&state, &EXTERNAL_MATRIX);
+    for round_constants in EXTERNAL_INITIAL_CONSTANTS.iter() {
+        for (el, round_constant) in state.iter_mut().zip(round_constants.iter()) {
+            *el = sbox(cs, el.clone(), *round_constant);
+        }
+        state = matmul(&state, &EXTERNAL_MATRIX);
+    }
+    for round_constant in INTERNAL_CONSTANTS.iter() {
+        state[0] = sbox(cs, state[0].clone(), *round_constant);
+        state = matmul(&state, &INTERNAL_MATRIX);
+    }
+    for round_constants in EXTERNAL_TERMINAL_CONSTANTS.iter() {
+        for (el, round_constant) in state.iter_mut().zip(round_constants.iter()) {
+            *el = sbox(cs, el.clone(), *round_constant);
+        }
+        state = matmul(&state, &EXTERNAL_MATRIX);
+    }
+
+    // now decompose into 16-bit limbs and write into memory
+    for (el, dst) in state.into_iter().zip(output_placeholder_state.iter()) {
+        let limbs = decompose_into_limbs(cs, el);
+        for (src, dst) in limbs.into_iter().zip(dst.iter()) {
+            let mut constraint = Constraint::from(Term::from(execute) * Term::from(src));
+            // set value
+            collapse_max_quadratic_constraint_into(cs, constraint.clone(), *dst);
+            // add constraint
+            constraint -= Term::from(*dst);
+            cs.add_constraint(constraint);
+        }
+    }
+
+    {
+        for (i, input) in output_placeholder_state.iter().enumerate() {
+            let register = Register::<F>(input.map(|el| Num::Var(el)));
+            if let Some(value) = register.get_value_unsigned(&*cs) {
+                println!("Output state element {} = 0x{:08x}", i, value);
+            }
+        }
+    }
+
+    output_placeholder_state
+}
+
+fn matmul<F: PrimeField>(
+    state: &[Constraint<F>; STATE_WIDTH],
+    matrix: &[[Mersenne31Field; STATE_WIDTH]; STATE_WIDTH],
+) -> [Constraint<F>; STATE_WIDTH] {
+    std::array::from_fn(|row| {
+        let mut result = Constraint::empty();
+        for (el, coeff) in state.iter().zip(matrix[row].iter()) {
+            let mut el = el.clone();
+            el.scale(F::from_u64_unchecked(coeff.to_reduced_u32() as u64));
+            result = result + el;
+        }
+
+        result
+    })
+}
+
+// x^5 of the input with round constant added. We materialize the input itself to keep
+// constraints small, and then x^2, x^4 and x^5
+fn sbox<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+    input: Constraint<F>,
+    round_constant: u32,
+) -> Constraint<F> {
+    let input = input + Term::from(round_constant as u64);
+    let x = cs.add_variable_from_constraint_allow_explicit_linear(input);
+    let x2 = cs.add_variable_from_constraint(Constraint::from(Term::from(x) * Term::from(x)));
+    let x4 = cs.add_variable_from_constraint(Constraint::from(Term::from(x2) * Term::from(x2)));
+    let x5 = cs.add_variable_from_constraint(Constraint::from(Term::from(x4) * Term::from(x)));
+
+    Constraint::from(x5)
+}
+
+// Decomposes field element into two range checked 16-bit limbs. Decomposition is not unique,
+// and we do not care, as caller reduces the output
+fn decompose_into_limbs<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+    input: Constraint<F>,
+) -> [Variable; 2] {
+    let value = cs.add_variable_from_constraint_allow_explicit_linear(input);
+    let low = cs.add_variable_with_range_check(16).get_variable();
+    let high = cs.add_variable_with_range_check(16).get_variable();
+
+    let value_fn = move |placer: &mut CS::WitnessPlacer| {
+        let value = placer.get_field(value).as_integer();
+        placer.assign_u16(low, &value.truncate());
+        placer.assign_u16(high, &value.shr(16).truncate());
+    };
+    cs.set_values(value_fn);
+
+    let constraint = Constraint::from(value)
+        - Term::from(low)
+        - Term::from((F::from_u64_unchecked(1 << 16), high));
+    cs.add_constraint_allow_explicit_linear(constraint);
+
+    [low, high]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cs::cs_reference::BasicAssembly;
+    use crate::cs::oracle::Oracle;
+    use crate::cs::placeholder::Placeholder;
+    use crate::definitions::TimestampScalar;
+    use crate::one_row_compiler::OneRowCompiler;
+    use crate::utils::serialize_to_file;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    struct Poseidon2PermutationOracle {
+        execute: bool,
+        state: [u32; STATE_WIDTH],
+    }
+
+    impl<F: PrimeField> Oracle<F> for Poseidon2PermutationOracle {
+        fn get_witness_from_placeholder(
+            &self,
+            placeholder: Placeholder,
+            subindex: usize,
+            trace_row: usize,
+        ) -> F {
+            let value = match placeholder {
+                Placeholder::ExecuteDelegation => self.execute as u32,
+                _ => {
+                    let value = <Self as Oracle<F>>::get_u32_witness_from_placeholder(
+                        self,
+                        placeholder,
+                        trace_row,
+                    );
+                    (value >> (16 * subindex)) & 0xffff
+                }
+            };
+
+            F::from_u64_unchecked(value as u64)
+        }
+
+        fn get_u32_witness_from_placeholder(&self, placeholder: Placeholder, _: usize) -> u32 {
+            match placeholder {
+                Placeholder::DelegationIndirectReadValue {
+                    register_index: 10,
+                    word_index,
+                } if self.execute => self.state[word_index],
+                _ => 0,
+            }
+        }
+
+        fn get_timestamp_witness_from_placeholder(
+            &self,
+            _placeholder: Placeholder,
+            _trace_row: usize,
+        ) -> TimestampScalar {
+            0
+        }
+    }
+
+    fn get_output_state(cs: &BasicAssembly<Mersenne31Field>, output: &[[Variable; 2]]) -> Vec<u32> {
+        output
+            .iter()
+            .map(|el| {
+                Register::<Mersenne31Field>(el.map(|el| Num::Var(el)))
+                    .get_value_unsigned(cs)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compile_poseidon2_permutation() {
+        let mut cs = BasicAssembly::<Mersenne31Field>::new();
+        define_poseidon2_permutation_delegation_circuit(&mut cs);
+        let (circuit_output, _) = cs.finalize();
+        let compiler = OneRowCompiler::default();
+        let compiled = compiler.compile_to_evaluate_delegations(circuit_output, 20);
+
+        serialize_to_file(&compiled, "poseidon2_delegation_layout.json");
+    }
+
+    #[test]
+    fn poseidon2_delegation_get_witness_graph() {
+        let ssa_forms = dump_ssa_witness_eval_form_for_delegation::<Mersenne31Field, _>(
+            define_poseidon2_permutation_delegation_circuit,
+        );
+        serialize_to_file(&ssa_forms, "poseidon2_delegation_ssa.json");
+    }
+
+    #[test]
+    fn poseidon2_permutation_is_satisfied() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut inputs = vec![[0u32; STATE_WIDTH], [u32::MAX; STATE_WIDTH]];
+        for _ in 0..8 {
+            inputs.push(std::array::from_fn(|_| rng.random()));
+        }
+
+        for state in inputs.into_iter() {
+            let oracle = Poseidon2PermutationOracle {
+                execute: true,
+                state,
+            };
+            let mut cs = BasicAssembly::<Mersenne31Field>::new_with_oracle(oracle);
+            let output_state = define_poseidon2_permutation_delegation_circuit(&mut cs);
+            assert!(cs.is_satisfied());
+
+            let mut expected = state.map(Mersenne31Field::from_nonreduced_u32);
+            poseidon2::m31::poseidon_permutation(&mut expected);
+            let outputs: Vec<u32> = get_output_state(&cs, &output_state)
+                .into_iter()
+                .map(|el| Mersenne31Field::from_nonreduced_u32(el).to_reduced_u32())
+                .collect();
+            let expected: Vec<u32> = expected.iter().map(|el| el.to_reduced_u32()).collect();
+            assert_eq!(outputs, expected);
+        }
+    }
+
+    #[test]
+    fn poseidon2_permutation_writes_zeroes_if_not_executed() {
+        let oracle = Poseidon2PermutationOracle {
+            execute: false,
+            state: [0u32; STATE_WIDTH],
+        };
+        let mut cs = BasicAssembly::<Mersenne31Field>::new_with_oracle(oracle);
+        let output_state = define_poseidon2_permutation_delegation_circuit(&mut cs);
+        assert!(cs.is_satisfied());
+        assert!(get_output_state(&cs, &output_state)
+            .into_iter()
+            .all(|el| el == 0));
+    }
+}
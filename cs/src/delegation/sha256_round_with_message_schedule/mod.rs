@@ -0,0 +1,617 @@
+use super::*;
+use crate::cs::circuit::*;
+use crate::cs::utils::collapse_max_quadratic_constraint_into;
+use crate::cs::witness_placer::*;
+use crate::one_row_compiler::Variable;
+use crate::types::Boolean;
+use crate::types::Num;
+
+// ABI:
+// - registers x10-x11 are used to pass the parameters
+// - x10 is a pointer to 24 words: 8 words of working variables a..h, followed by 16 words of
+//   the message schedule window W[t]..W[t+15]
+// - x11 contains the round index t in [0, 64)
+//
+// Single call performs one round of SHA-256 compression using W[t] and K[t], and shifts the message
+// schedule window by one word, so the last word becomes W[t+16] = s1(W[t+14]) + W[t+9] + s0(W[t+1]) + W[t].
+// Round constant is selected inside of the circuit by the round index, so caller can not substitute it.
+// Caller is responsible for initialization of the working variables and for the final feed-forward
+// into the hash state after 64 rounds. Few last window extensions are unused, but it's uniform
+
+const NUM_STATE_WORDS: usize = 8;
+const NUM_SCHEDULE_WORDS: usize = 16;
+pub const NUM_ROUNDS: usize = 64;
+
+pub const ROUND_CONSTANTS: [u32; NUM_ROUNDS] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+pub fn all_table_types() -> Vec<TableType> {
+    // only bit decompositions and 16-bit range checks are used
+    vec![]
+}
+
+pub fn sha256_round_with_message_schedule_delegation_circuit_create_table_driver<F: PrimeField>(
+) -> TableDriver<F> {
+    let mut table_driver = TableDriver::new();
+    for el in all_table_types() {
+        table_driver.materialize_table(el);
+    }
+
+    table_driver
+}
+
+pub fn materialize_tables_into_cs<F: PrimeField, CS: Circuit<F>>(cs: &mut CS) {
+    for el in all_table_types() {
+        cs.materialize_table(el);
+    }
+}
+
+pub fn define_sha256_round_with_message_schedule_delegation_circuit<
+    F: PrimeField,
+    CS: Circuit<F>,
+>(
+    cs: &mut CS,
+) -> (Vec<[Variable; 2]>, Vec<[Variable; 2]>) {
+    // add tables
+    materialize_tables_into_cs(cs);
+
+    // the only convention we must eventually satisfy is that if we do NOT process delegation request,
+    // then all memory writes in ABI must be 0s
+
+    let execute = cs.process_delegation_request();
+
+    let x10_request = RegisterAccessRequest {
+        register_index: 10,
+        register_write: false,
+        indirects_alignment_log2: 7, // 128 bytes - 32 + 64 for state and message schedule are needed
+        indirect_accesses: vec![true; NUM_STATE_WORDS + NUM_SCHEDULE_WORDS],
+    };
+
+    let x11_request = RegisterAccessRequest {
+        register_index: 11,
+        register_write: false,
+        indirects_alignment_log2: 0, // no indirects
+        indirect_accesses: vec![],
+    };
+
+    let x10_and_indirects = cs.create_register_and_indirect_memory_accesses(x10_request);
+    let x11_and_indirects = cs.create_register_and_indirect_memory_accesses(x11_request);
+
+    assert_eq!(
+        x10_and_indirects.indirect_accesses.len(),
+        NUM_STATE_WORDS + NUM_SCHEDULE_WORDS
+    );
+    assert!(x11_and_indirects.indirect_accesses.is_empty());
+
+    let mut input_state = vec![];
+    let mut output_placeholder_state = vec![];
+    for i in 0..NUM_STATE_WORDS {
+        let IndirectAccessType::Write {
+            read_value,
+            write_value,
+        } = x10_and_indirects.indirect_accesses[i]
+        else {
+            panic!()
+        };
+
+        input_state.push(read_value);
+        output_placeholder_state.push(write_value);
+    }
+
+    let mut input_schedule = vec![];
+    let mut output_placeholder_schedule = vec![];
+    for i in NUM_STATE_WORDS..(NUM_STATE_WORDS + NUM_SCHEDULE_WORDS) {
+        let IndirectAccessType::Write {
+            read_value,
+            write_value,
+        } = x10_and_indirects.indirect_accesses[i]
+        else {
+            panic!()
+        };
+
+        input_schedule.push(read_value);
+        output_placeholder_schedule.push(write_value);
+    }
+
+    let round_index = {
+        let RegisterAccessType::Read { read_value } = x11_and_indirects.register_access else {
+            panic!()
+        };
+
+        read_value
+    };
+
+    {
+        for (i, input) in input_state.iter().enumerate() {
+            let register = Register::<F>(input.map(|el| Num::Var(el)));
+            if let Some(value) = register.get_value_unsigned(&*cs) {
+                println!("Input state element {} = 0x{:08x}", i, value);
+            }
+        }
+
+        for (i, input) in input_schedule.iter().enumerate() {
+            let register = Register::<F>(input.map(|el| Num::Var(el)));
+            if let Some(value) = register.get_value_unsigned(&*cs) {
+                println!("Input message schedule element {} = 0x{:08x}", i, value);
+            }
+        }
+
+        let register = Register::<F>(round_index.map(|el| Num::Var(el)));
+        if let Some(value) = register.get_value_unsigned(&*cs) {
+            println!("Round index = {}", value);
+        }
+    }
+
+    let round_constant = select_round_constant(cs, execute, round_index);
+
+    // NOTE on masking: we do NOT need to mask anything here based on the execute/not predicate,
+    // because if we do not execute, then all read values and round constant are 0, and all the functions
+    // below map zero inputs into zero outputs
+
+    let [a, b, c, d, e, f, g, h]: [[Variable; 2]; NUM_STATE_WORDS] =
+        input_state.clone().try_into().unwrap();
+
+    // bitwise functions require boolean decompositions, and rotations become free after it
+    let a_bits = split_word_into_bits(cs, a);
+    let b_bits = split_word_into_bits(cs, b);
+    let c_bits = split_word_into_bits(cs, c);
+    let e_bits = split_word_into_bits(cs, e);
+    let f_bits = split_word_into_bits(cs, f);
+    let g_bits = split_word_into_bits(cs, g);
+    let w1_bits = split_word_into_bits(cs, input_schedule[1]);
+    let w14_bits = split_word_into_bits(cs, input_schedule[14]);
+
+    let big_sigma_0 = xor3(
+        cs,
+        &rotate_right(&a_bits, 2),
+        &rotate_right(&a_bits, 13),
+        &rotate_right(&a_bits, 22),
+    );
+    let big_sigma_1 = xor3(
+        cs,
+        &rotate_right(&e_bits, 6),
+        &rotate_right(&e_bits, 11),
+        &rotate_right(&e_bits, 25),
+    );
+    let small_sigma_0 = xor3(
+        cs,
+        &rotate_right(&w1_bits, 7),
+        &rotate_right(&w1_bits, 18),
+        &shift_right(&w1_bits, 3),
+    );
+    let small_sigma_1 = xor3(
+        cs,
+        &rotate_right(&w14_bits, 17),
+        &rotate_right(&w14_bits, 19),
+        &shift_right(&w14_bits, 10),
+    );
+
+    // Ch(e, f, g) = e * f + (1 - e) * g
+    let choose: [Constraint<F>; 32] = std::array::from_fn(|i| {
+        Term::from(e_bits[i]) * Term::from(f_bits[i]) + Term::from(g_bits[i])
+            - Term::from(e_bits[i]) * Term::from(g_bits[i])
+    });
+
+    // Maj(a, b, c) = ab + ac + bc - 2abc, so we need one product as a variable to stay within degree 2
+    let majority: [Constraint<F>; 32] = std::array::from_fn(|i| {
+        let ab = cs.add_variable_from_constraint(
+            Constraint::empty() + Term::from(a_bits[i]) * Term::from(b_bits[i]),
+        );
+        Constraint::empty()
+            + Term::from(ab)
+            + Term::from(c_bits[i]) * Term::from(a_bits[i])
+            + Term::from(c_bits[i]) * Term::from(b_bits[i])
+            - Term::from(2u64) * Term::from(c_bits[i]) * Term::from(ab)
+    });
+
+    let big_sigma_0 = pack_bits_into_limbs(&big_sigma_0);
+    let big_sigma_1 = pack_bits_into_limbs(&big_sigma_1);
+    let small_sigma_0 = pack_bits_into_limbs(&small_sigma_0);
+    let small_sigma_1 = pack_bits_into_limbs(&small_sigma_1);
+    let choose = pack_bits_into_limbs(&choose);
+    let majority = pack_bits_into_limbs(&majority);
+
+    // T1 = h + S1(e) + Ch(e, f, g) + K + W[t], T2 = S0(a) + Maj(a, b, c),
+    // and we do not need T1 and T2 explicitly - it's enough to have a = T1 + T2 and e = d + T1
+    let t1: [Constraint<F>; 2] = std::array::from_fn(|i| {
+        big_sigma_1[i].clone()
+            + choose[i].clone()
+            + Term::from(h[i])
+            + round_constant[i].clone()
+            + Term::from(input_schedule[0][i])
+    });
+
+    let new_a = add_limbs_modulo_2_32(
+        cs,
+        std::array::from_fn(|i| t1[i].clone() + big_sigma_0[i].clone() + majority[i].clone()),
+        3, // at most 7 words
+    );
+    let new_e = add_limbs_modulo_2_32(
+        cs,
+        std::array::from_fn(|i| t1[i].clone() + Term::from(d[i])),
+        3, // at most 6 words
+    );
+    let new_w = add_limbs_modulo_2_32(
+        cs,
+        std::array::from_fn(|i| {
+            small_sigma_1[i].clone()
+                + small_sigma_0[i].clone()
+                + Term::from(input_schedule[9][i])
+                + Term::from(input_schedule[0][i])
+        }),
+        2, // at most 4 words
+    );
+
+    // now we can write everything into memory. Working variables are shifted, with two new words,
+    // and message schedule window is shifted by one word
+    let output_state_sources = [new_a, a, b, c, new_e, e, f, g];
+    let output_schedule_sources: Vec<[Variable; 2]> = input_schedule[1..]
+        .iter()
+        .copied()
+        .chain(std::iter::once(new_w))
+        .collect();
+
+    for (src, dst) in output_state_sources
+        .iter()
+        .chain(output_schedule_sources.iter())
+        .zip(
+            output_placeholder_state
+                .iter()
+                .chain(output_placeholder_schedule.iter()),
+        )
+    {
+        for (src, dst) in src.iter().zip(dst.iter()) {
+            let mut constraint = Constraint::<F>::from(*src);
+            // set value
+            collapse_max_quadratic_constraint_into(cs, constraint.clone(), *dst);
+            // add constraint
+            constraint -= Term::from(*dst);
+            cs.add_constraint_allow_explicit_linear(constraint);
+        }
+    }
+
+    {
+        for (i, input) in output_placeholder_state.iter().enumerate() {
+            let register = Register::<F>(input.map(|el| Num::Var(el)));
+            if let Some(value) = register.get_value_unsigned(&*cs) {
+                println!("Output state element {} = 0x{:08x}", i, value);
+            }
+        }
+
+        for (i, input) in output_placeholder_schedule.iter().enumerate() {
+            let register = Register::<F>(input.map(|el| Num::Var(el)));
+            if let Some(value) = register.get_value_unsigned(&*cs) {
+                println!("Output message schedule element {} = 0x{:08x}", i, value);
+            }
+        }
+    }
+
+    (output_placeholder_state, output_placeholder_schedule)
+}
+
+// Round constant limbs are linear in the one-hot round selectors
+fn select_round_constant<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+    execute: Boolean,
+    round_index: [Variable; 2],
+) -> [Constraint<F>; 2] {
+    let selectors: [Variable; NUM_ROUNDS] = one_hot_round_selectors(cs, execute, round_index);
+
+    std::array::from_fn(|limb_idx| {
+        let mut limb = Constraint::<F>::empty();
+        for (selector, round_constant) in selectors.iter().zip(ROUND_CONSTANTS.iter()) {
+            let limb_value = (round_constant >> (16 * limb_idx)) & 0xffff;
+            limb += Term::from((F::from_u64_unchecked(limb_value as u64), *selector));
+        }
+
+        limb
+    })
+}
+
+// One-hot selector over the round index, that is zero if we do not execute. Sum of selectors is equal
+// to the execute predicate and weighted sum is equal to the index, so index must be in [0, N) if we
+// execute, and anything that depends on the round only can be made linear in selectors
+pub(crate) fn one_hot_round_selectors<F: PrimeField, CS: Circuit<F>, const N: usize>(
+    cs: &mut CS,
+    execute: Boolean,
+    round_index: [Variable; 2],
+) -> [Variable; N] {
+    let selectors: [Variable; N] =
+        std::array::from_fn(|_| cs.add_boolean_variable().get_variable().unwrap());
+
+    let execute_var = execute.get_variable().unwrap();
+    let selectors_for_witness = selectors;
+    let value_fn = move |placer: &mut CS::WitnessPlacer| {
+        let execute = placer.get_boolean(execute_var);
+        let round_index = placer.get_u16(round_index[0]);
+        for (i, selector) in selectors_for_witness.iter().enumerate() {
+            let is_selected = round_index.equal_to_constant(i as u16);
+            placer.assign_mask(*selector, &is_selected.and(&execute));
+        }
+    };
+    cs.set_values(value_fn);
+
+    let mut selectors_sum = Constraint::<F>::empty();
+    let mut weighted_sum = Constraint::<F>::empty();
+    for (i, selector) in selectors.iter().enumerate() {
+        selectors_sum += Term::from(*selector);
+        weighted_sum += Term::from((F::from_u64_unchecked(i as u64), *selector));
+    }
+    cs.add_constraint_allow_explicit_linear(selectors_sum - Term::from(execute_var));
+    cs.add_constraint_allow_explicit_linear(weighted_sum - Term::from(round_index[0]));
+    cs.add_constraint_allow_explicit_linear(Constraint::<F>::from(round_index[1]));
+
+    selectors
+}
+
+// Little-endian bits of the word given as two 16-bit limbs
+pub(crate) fn split_word_into_bits<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+    word: [Variable; 2],
+) -> [Boolean; 32] {
+    let low = Boolean::split_into_bitmask::<F, CS, 16>(cs, Num::Var(word[0]));
+    let high = Boolean::split_into_bitmask::<F, CS, 16>(cs, Num::Var(word[1]));
+
+    std::array::from_fn(|i| if i < 16 { low[i] } else { high[i - 16] })
+}
+
+fn rotate_right(bits: &[Boolean; 32], amount: usize) -> [Boolean; 32] {
+    std::array::from_fn(|i| bits[(i + amount) % 32])
+}
+
+fn shift_right(bits: &[Boolean; 32], amount: usize) -> [Boolean; 32] {
+    std::array::from_fn(|i| {
+        if i + amount < 32 {
+            bits[i + amount]
+        } else {
+            Boolean::Constant(false)
+        }
+    })
+}
+
+// We only need a variable for XOR of the first pair, and the second XOR is still degree 2
+fn xor3<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+    x: &[Boolean; 32],
+    y: &[Boolean; 32],
+    z: &[Boolean; 32],
+) -> [Constraint<F>; 32] {
+    std::array::from_fn(|i| {
+        let t = Boolean::xor(&x[i], &y[i], cs);
+        Constraint::empty() + Term::from(t) + Term::from(z[i])
+            - Term::from(2u64) * Term::from(t) * Term::from(z[i])
+    })
+}
+
+pub(crate) fn pack_bits_into_limbs<F: PrimeField>(
+    bits: &[Constraint<F>; 32],
+) -> [Constraint<F>; 2] {
+    std::array::from_fn(|limb_idx| {
+        let mut limb = Constraint::<F>::empty();
+        for (i, bit) in bits[limb_idx * 16..][..16].iter().enumerate() {
+            let mut bit = bit.clone();
+            bit.scale(F::from_u64_unchecked(1u64 << i));
+            limb = limb + bit;
+        }
+
+        limb
+    })
+}
+
+// Sums 16-bit limbs of few words. Low limb produces a carry into the high one, and carry from the high limb
+// is dropped. Caller must ensure that carry fits into `num_carry_bits`
+fn add_limbs_modulo_2_32<F: PrimeField, CS: Circuit<F>>(
+    cs: &mut CS,
+    limbs: [Constraint<F>; 2],
+    num_carry_bits: usize,
+) -> [Variable; 2] {
+    assert!(16 + num_carry_bits < F::CHAR_BITS - 1);
+
+    let mut result = [Variable::placeholder_variable(); 2];
+    let mut carry_in: Option<Constraint<F>> = None;
+    for (limb_idx, limb) in limbs.into_iter().enumerate() {
+        let mut limb = limb;
+        if let Some(carry_in) = carry_in.take() {
+            limb = limb + carry_in;
+        }
+        let sum = cs.add_variable_from_constraint(limb);
+        let output = cs.add_variable_with_range_check(16).get_variable();
+        let carry_bits: Vec<Variable> = (0..num_carry_bits)
+            .map(|_| cs.add_boolean_variable().get_variable().unwrap())
+            .collect();
+
+        let carry_bits_for_witness = carry_bits.clone();
+        let value_fn = move |placer: &mut CS::WitnessPlacer| {
+            let sum = placer.get_field(sum).as_integer();
+            placer.assign_u16(output, &sum.truncate());
+            let carry = sum.shr(16);
+            for (i, bit) in carry_bits_for_witness.iter().enumerate() {
+                placer.assign_mask(*bit, &carry.get_bit(i as u32));
+            }
+        };
+        cs.set_values(value_fn);
+
+        let mut carry = Constraint::<F>::empty();
+        for (i, bit) in carry_bits.iter().enumerate() {
+            carry += Term::from((F::from_u64_unchecked(1u64 << i), *bit));
+        }
+
+        let mut constraint = Constraint::<F>::from(sum);
+        constraint -= Term::from(output);
+        let mut shifted_carry = carry.clone();
+        shifted_carry.scale(F::from_u64_unchecked(1u64 << 16));
+        constraint = constraint - shifted_carry;
+        cs.add_constraint_allow_explicit_linear(constraint);
+
+        result[limb_idx] = output;
+        carry_in = Some(carry);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cs::cs_reference::BasicAssembly;
+    use crate::cs::oracle::Oracle;
+    use crate::cs::placeholder::Placeholder;
+    use crate::definitions::TimestampScalar;
+    use crate::one_row_compiler::OneRowCompiler;
+    use crate::utils::serialize_to_file;
+    use field::Mersenne31Field;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    struct Sha256RoundOracle {
+        state_and_schedule: [u32; NUM_STATE_WORDS + NUM_SCHEDULE_WORDS],
+        round_index: u32,
+    }
+
+    impl<F: PrimeField> Oracle<F> for Sha256RoundOracle {
+        fn get_witness_from_placeholder(
+            &self,
+            placeholder: Placeholder,
+            subindex: usize,
+            trace_row: usize,
+        ) -> F {
+            let value = match placeholder {
+                Placeholder::ExecuteDelegation => 1,
+                _ => {
+                    let value = <Self as Oracle<F>>::get_u32_witness_from_placeholder(
+                        self,
+                        placeholder,
+                        trace_row,
+                    );
+                    (value >> (16 * subindex)) & 0xffff
+                }
+            };
+
+            F::from_u64_unchecked(value as u64)
+        }
+
+        fn get_u32_witness_from_placeholder(&self, placeholder: Placeholder, _: usize) -> u32 {
+            match placeholder {
+                Placeholder::DelegationRegisterReadValue(11) => self.round_index,
+                Placeholder::DelegationIndirectReadValue {
+                    register_index: 10,
+                    word_index,
+                } => self.state_and_schedule[word_index],
+                _ => 0,
+            }
+        }
+
+        fn get_timestamp_witness_from_placeholder(
+            &self,
+            _placeholder: Placeholder,
+            _trace_row: usize,
+        ) -> TimestampScalar {
+            0
+        }
+    }
+
+    fn reference_round(input: &[u32; 24], round_index: u32) -> [u32; 24] {
+        let k = ROUND_CONSTANTS[round_index as usize];
+        let [a, b, c, d, e, f, g, h]: [u32; 8] = input[..8].try_into().unwrap();
+        let w = &input[8..];
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(k)
+            .wrapping_add(w[0]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        let sigma0 = w[1].rotate_right(7) ^ w[1].rotate_right(18) ^ (w[1] >> 3);
+        let sigma1 = w[14].rotate_right(17) ^ w[14].rotate_right(19) ^ (w[14] >> 10);
+
+        let mut result = [0u32; 24];
+        result[..8].copy_from_slice(&[t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g]);
+        result[8..23].copy_from_slice(&w[1..]);
+        result[23] = sigma1
+            .wrapping_add(w[9])
+            .wrapping_add(sigma0)
+            .wrapping_add(w[0]);
+
+        result
+    }
+
+    #[test]
+    fn compile_sha256_round_with_message_schedule() {
+        let mut cs = BasicAssembly::<Mersenne31Field>::new();
+        define_sha256_round_with_message_schedule_delegation_circuit(&mut cs);
+        let (circuit_output, _) = cs.finalize();
+        let compiler = OneRowCompiler::default();
+        let compiled = compiler.compile_to_evaluate_delegations(circuit_output, 20);
+
+        serialize_to_file(&compiled, "sha256_delegation_layout.json");
+    }
+
+    #[test]
+    fn sha256_delegation_get_witness_graph() {
+        let ssa_forms = dump_ssa_witness_eval_form_for_delegation::<Mersenne31Field, _>(
+            define_sha256_round_with_message_schedule_delegation_circuit,
+        );
+        serialize_to_file(&ssa_forms, "sha256_delegation_ssa.json");
+    }
+
+    #[test]
+    fn sha256_round_with_message_schedule_is_satisfied() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut inputs = vec![([0u32; 24], 0u32), ([u32::MAX; 24], 63)];
+        for _ in 0..16 {
+            inputs.push((
+                std::array::from_fn(|_| rng.random()),
+                rng.random_range(0..NUM_ROUNDS as u32),
+            ));
+        }
+
+        for (state_and_schedule, round_index) in inputs.into_iter() {
+            let oracle = Sha256RoundOracle {
+                state_and_schedule,
+                round_index,
+            };
+            let mut cs = BasicAssembly::<Mersenne31Field>::new_with_oracle(oracle);
+            let (output_state, output_schedule) =
+                define_sha256_round_with_message_schedule_delegation_circuit(&mut cs);
+            assert!(cs.is_satisfied());
+
+            let outputs: Vec<u32> = output_state
+                .iter()
+                .chain(output_schedule.iter())
+                .map(|el| {
+                    Register::<Mersenne31Field>(el.map(|el| Num::Var(el)))
+                        .get_value_unsigned(&cs)
+                        .unwrap()
+                })
+                .collect();
+            assert_eq!(
+                outputs,
+                reference_round(&state_and_schedule, round_index).to_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn sha256_round_with_message_schedule_rejects_out_of_range_round_index() {
+        for round_index in [NUM_ROUNDS as u32, 1 << 16] {
+            let oracle = Sha256RoundOracle {
+                state_and_schedule: [0u32; 24],
+                round_index,
+            };
+            let mut cs = BasicAssembly::<Mersenne31Field>::new_with_oracle(oracle);
+            define_sha256_round_with_message_schedule_delegation_circuit(&mut cs);
+            assert!(!cs.is_satisfied());
+        }
+    }
+}
@@ -7,6 +7,7 @@ use crate::types::*;
 use field::PrimeField;
 
 pub const PC_INC_STEP: u64 = 4;
+pub const COMPRESSED_PC_INC_STEP: u64 = 2;
 pub const NUM_OF_MEMORY_ACCESSES_PER_CYCLE: usize = 3;
 
 #[derive(Clone, Copy, Debug)]
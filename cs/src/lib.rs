@@ -24,6 +24,8 @@ pub mod machine;
 #[cfg(feature = "compiler")]
 pub mod one_row_compiler;
 #[cfg(feature = "compiler")]
+pub mod smt;
+#[cfg(feature = "compiler")]
 pub mod tables;
 #[cfg(feature = "compiler")]
 pub mod types;
@@ -40,6 +42,37 @@ pub fn default_compile_machine<
     csr_table: Option<crate::tables::LookupTable<field::Mersenne31Field, 3>>,
     trace_len_log2: usize,
 ) -> crate::one_row_compiler::CompiledCircuitArtifact<::field::Mersenne31Field>
+where
+    [(); { <M as crate::machine::Machine<::field::Mersenne31Field>>::ASSUME_TRUSTED_CODE }
+        as usize]:,
+    [(); { <M as crate::machine::Machine<::field::Mersenne31Field>>::OUTPUT_EXACT_EXCEPTIONS }
+        as usize]:,
+{
+    default_compile_machine_with_extra_tables::<M, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(
+        machine,
+        bytecode_table,
+        csr_table,
+        vec![],
+        trace_len_log2,
+    )
+}
+
+/// Same as `default_compile_machine`, but also adds tables with external content, e.g.
+/// ROM table with expanded compressed instructions
+#[cfg(feature = "compiler")]
+pub fn default_compile_machine_with_extra_tables<
+    M: crate::machine::Machine<::field::Mersenne31Field>,
+    const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize,
+>(
+    machine: M,
+    bytecode_table: crate::tables::LookupTable<field::Mersenne31Field, 3>,
+    csr_table: Option<crate::tables::LookupTable<field::Mersenne31Field, 3>>,
+    extra_tables: Vec<(
+        crate::tables::TableType,
+        crate::tables::LookupTable<field::Mersenne31Field, 3>,
+    )>,
+    trace_len_log2: usize,
+) -> crate::one_row_compiler::CompiledCircuitArtifact<::field::Mersenne31Field>
 where
     [(); { <M as crate::machine::Machine<::field::Mersenne31Field>>::ASSUME_TRUSTED_CODE }
         as usize]:,
@@ -70,6 +103,12 @@ where
             crate::tables::LookupWrapper::Dimensional3(csr_table),
         );
     }
+    for (table_type, table) in extra_tables.into_iter() {
+        cs_output.table_driver.add_table_with_content(
+            table_type,
+            crate::tables::LookupWrapper::Dimensional3(table),
+        );
+    }
     let compiler = OneRowCompiler::default();
     let compiler_output =
         compiler.compile_output_for_chunked_memory_argument(cs_output, trace_len_log2);
@@ -174,7 +174,7 @@ pub(crate) fn base_isa_state_transition<
     cs.set_log(&opt_ctx, "DIVREM");
 
     let application_result =
-        ConditionalOp::<true>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        ConditionalOp::<true, false>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
             cs,
             &initial_state,
             &decoder_output,
@@ -195,13 +195,14 @@ pub(crate) fn base_isa_state_transition<
     application_results.push(application_result);
     cs.set_log(&opt_ctx, "SHIFT_SRA_ROT");
 
-    let application_result = JumpOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
-        cs,
-        &initial_state,
-        &decoder_output,
-        &flags_source,
-        &mut opt_ctx,
-    );
+    let application_result =
+        JumpOp::<false>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+            cs,
+            &initial_state,
+            &decoder_output,
+            &flags_source,
+            &mut opt_ctx,
+        );
     application_results.push(application_result);
     cs.set_log(&opt_ctx, "JUMP");
 
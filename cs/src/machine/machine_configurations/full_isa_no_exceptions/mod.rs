@@ -33,9 +33,9 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineNoExceptionHandling {
             Box::new(BinaryOp),
             Box::new(MulOp::<true>),
             Box::new(DivRemOp::<true>),
-            Box::new(ConditionalOp::<true>),
+            Box::new(ConditionalOp::<true, false>),
             Box::new(ShiftOp::<true, false>),
-            Box::new(JumpOp),
+            Box::new(JumpOp::<false>),
             Box::new(LoadOp::<true, true>),
             Box::new(StoreOp::<true>),
             Box::new(CsrOp::<false, false, false>),
@@ -51,7 +51,7 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineNoExceptionHandling {
         set.extend(<BinaryOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<MulOp<true> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<DivRemOp<true> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
-        set.extend(<ConditionalOp<true> as MachineOp<
+        set.extend(<ConditionalOp<true, false> as MachineOp<
             F,
             ST<F>,
             RS<F>,
@@ -65,7 +65,7 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineNoExceptionHandling {
             DE<F>,
             BS,
         >>::define_used_tables());
-        set.extend(<JumpOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
+        set.extend(<JumpOp<false> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<LoadOp<true, true> as MachineOp<
             F,
             ST<F>,
@@ -97,6 +97,8 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineNoExceptionHandling {
             { <Self as Machine<F>>::OUTPUT_EXACT_EXCEPTIONS },
             false,
             true,
+            false,
+            false,
             ROM_ADDRESS_SPACE_SECOND_WORD_BITS,
         >(
             cs,
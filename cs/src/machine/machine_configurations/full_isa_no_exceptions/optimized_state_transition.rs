@@ -8,6 +8,8 @@ pub(crate) fn optimized_base_isa_state_transition<
     const OUTPUT_EXACT_EXCEPTIONS: bool,
     const PERFORM_DELEGATION: bool,
     const SUPPORT_SIGNED_MUL_DIV: bool,
+    const SUPPORT_BITMANIP: bool,
+    const SUPPORT_COMPRESSED: bool,
     const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize,
 >(
     cs: &mut CS,
@@ -39,8 +41,29 @@ pub(crate) fn optimized_base_isa_state_transition<
     // and we may consider to remove this extra range check completely
 
     // TODO: Reading opcode from ROM here checks that PC % 4 == 0, so we can skip checks for PC % 4 == 0 in jump and branch instructions
-    let (memory_queries, src1, src2, raw_decoder_output, flags_source, opcode_types_bits) =
-        optimized_decode_and_preallocate_mem_queries_for_bytecode_in_rom::<
+    let (
+        (memory_queries, src1, src2, raw_decoder_output, flags_source, opcode_types_bits),
+        next_pc,
+    ) = if SUPPORT_COMPRESSED {
+        // Here ROM read checks that PC % 2 == 0, and compressed instruction is expanded
+        // into 32-bit equivalent that goes through the same decoder
+        let (next_opcode, is_compressed) =
+            read_opcode_with_compressed_from_rom::<F, CS, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(
+                cs, pc,
+            );
+        let decoded = optimized_decode_and_preallocate_mem_queries::<
+            F,
+            CS,
+            ASSUME_TRUSTED_CODE,
+            PERFORM_DELEGATION,
+        >(cs, next_opcode, decode_table_splitting, boolean_keys);
+
+        // now with PC considered range-checked we can compute next PC without overflows
+        let next_pc = calculate_pc_next_with_compressed_no_overflows(cs, pc, is_compressed);
+
+        (decoded, next_pc)
+    } else {
+        let decoded = optimized_decode_and_preallocate_mem_queries_for_bytecode_in_rom::<
             F,
             CS,
             ASSUME_TRUSTED_CODE,
@@ -48,8 +71,11 @@ pub(crate) fn optimized_base_isa_state_transition<
             ROM_ADDRESS_SPACE_SECOND_WORD_BITS,
         >(cs, pc, decode_table_splitting, boolean_keys);
 
-    // now with PC considered range-checked we can compute next PC without overflows
-    let next_pc = calculate_pc_next_no_overflows(cs, pc);
+        // now with PC considered range-checked we can compute next PC without overflows
+        let next_pc = calculate_pc_next_no_overflows(cs, pc);
+
+        (decoded, next_pc)
+    };
 
     let mut opt_ctx = OptimizationContext::<F, CS>::new();
 
@@ -144,35 +170,97 @@ pub(crate) fn optimized_base_isa_state_transition<
     application_results.push(application_result);
     cs.set_log(&opt_ctx, "DIVREM");
 
-    let application_result =
-        ConditionalOp::<true>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+    let application_result = ConditionalOp::<true, SUPPORT_COMPRESSED>::apply::<
+        _,
+        ASSUME_TRUSTED_CODE,
+        OUTPUT_EXACT_EXCEPTIONS,
+    >(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "CONDITIONAL");
+
+    let application_result = ShiftOp::<true, SUPPORT_BITMANIP>::apply::<
+        _,
+        ASSUME_TRUSTED_CODE,
+        OUTPUT_EXACT_EXCEPTIONS,
+    >(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "SHIFT_SRA_ROT");
+
+    if SUPPORT_BITMANIP {
+        // Zbb and Zbkb subsets. Rotations are handled by the shift family above
+        let application_result =
+            NegatedBinaryOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+                cs,
+                &initial_state,
+                &decoder_output,
+                &flags_source,
+                &mut opt_ctx,
+            );
+        application_results.push(application_result);
+        cs.set_log(&opt_ctx, "NEGATED_BINARY");
+
+        let application_result = MinMaxOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
             cs,
             &initial_state,
             &decoder_output,
             &flags_source,
             &mut opt_ctx,
         );
-    application_results.push(application_result);
-    cs.set_log(&opt_ctx, "CONDITIONAL");
+        application_results.push(application_result);
+        cs.set_log(&opt_ctx, "MIN_MAX");
 
-    let application_result =
-        ShiftOp::<true, false>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        let application_result =
+            UnaryBitmanipOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+                cs,
+                &initial_state,
+                &decoder_output,
+                &flags_source,
+                &mut opt_ctx,
+            );
+        application_results.push(application_result);
+        cs.set_log(&opt_ctx, "UNARY_BITMANIP");
+
+        let application_result = ByteOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
             cs,
             &initial_state,
             &decoder_output,
             &flags_source,
             &mut opt_ctx,
         );
-    application_results.push(application_result);
-    cs.set_log(&opt_ctx, "SHIFT_SRA_ROT");
+        application_results.push(application_result);
+        cs.set_log(&opt_ctx, "BYTE_OP");
 
-    let application_result = JumpOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
-        cs,
-        &initial_state,
-        &decoder_output,
-        &flags_source,
-        &mut opt_ctx,
-    );
+        let application_result = PackOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+            cs,
+            &initial_state,
+            &decoder_output,
+            &flags_source,
+            &mut opt_ctx,
+        );
+        application_results.push(application_result);
+        cs.set_log(&opt_ctx, "PACK");
+    }
+
+    let application_result =
+        JumpOp::<SUPPORT_COMPRESSED>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+            cs,
+            &initial_state,
+            &decoder_output,
+            &flags_source,
+            &mut opt_ctx,
+        );
     application_results.push(application_result);
     cs.set_log(&opt_ctx, "JUMP");
 
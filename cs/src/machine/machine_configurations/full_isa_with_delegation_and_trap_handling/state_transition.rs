@@ -0,0 +1,273 @@
+use super::*;
+use crate::machine::machine_configurations::state_transition_parts::*;
+
+pub(crate) fn isa_with_delegation_and_trap_handling_state_transition<
+    F: PrimeField,
+    CS: Circuit<F>,
+    const ASSUME_TRUSTED_CODE: bool,
+    const OUTPUT_EXACT_EXCEPTIONS: bool,
+    const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize,
+>(
+    cs: &mut CS,
+    decode_table_splitting: [usize; 2],
+    boolean_keys: DecoderOutputExtraKeysHolder,
+) -> (
+    TrapHandlingStateRegistersInMemory<F>,
+    TrapHandlingStateRegistersInMemory<F>,
+) {
+    assert!(ASSUME_TRUSTED_CODE == false);
+    assert!(OUTPUT_EXACT_EXCEPTIONS);
+
+    let initial_state = TrapHandlingStateRegistersInMemory::<F>::initialize(cs);
+
+    let pc = *initial_state.get_pc();
+
+    // same as for machines without exceptions, we only need to range check low part of PC
+    cs.require_invariant(
+        pc.0[0].get_variable(),
+        Invariant::RangeChecked {
+            width: LIMB_WIDTH as u32,
+        },
+    );
+
+    // NOTE: reading opcode from ROM checks that PC % 4 == 0 and that PC is in ROM. We trap on misaligned jumps
+    // and branches, so PC can only get there via `mtvec` or `mepc` values, and it's unprovable in this case
+    let next_opcode = read_opcode_from_rom::<F, CS, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(cs, pc);
+    let (
+        memory_queries,
+        src1,
+        src2,
+        raw_decoder_output,
+        flags_source,
+        opcode_types_bits,
+        invalid_opcode,
+    ) = optimized_decode_and_preallocate_mem_queries_with_invalid_opcode_flag::<
+        F,
+        CS,
+        ASSUME_TRUSTED_CODE,
+        true,
+    >(cs, next_opcode, decode_table_splitting, boolean_keys);
+
+    // now with PC considered range-checked we can compute next PC without overflows
+    let next_pc = calculate_pc_next_no_overflows(cs, pc);
+
+    let mut opt_ctx = OptimizationContext::<F, CS>::new();
+
+    let src1 = RegisterDecompositionWithSign::parse_reg(cs, src1);
+    let src2 = RegisterDecompositionWithSign::parse_reg(cs, src2);
+
+    let decoder_output = BasicDecodingResultWithSigns {
+        pc_next: next_pc,
+        src1,
+        src2,
+        rs2_index: raw_decoder_output.rs2.clone(),
+        imm: raw_decoder_output.imm,
+        funct3: raw_decoder_output.funct3,
+        funct12: raw_decoder_output.funct12,
+    };
+
+    cs.set_log(&opt_ctx, "DECODER");
+    let mut application_results = Vec::<CommonDiffs<F>>::with_capacity(32);
+
+    let application_result = AddOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "ADD");
+
+    let application_result = SubOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "SUB");
+
+    let application_result = LuiOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "LUI");
+
+    let application_result = AuiPc::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "AUIPC");
+
+    let application_result = BinaryOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "BINARY");
+
+    let application_result = MulOp::<true>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "MUL");
+
+    let application_result =
+        DivRemOp::<true>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+            cs,
+            &initial_state,
+            &decoder_output,
+            &flags_source,
+            &mut opt_ctx,
+        );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "DIVREM");
+
+    let application_result =
+        ConditionalOp::<true, false>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+            cs,
+            &initial_state,
+            &decoder_output,
+            &flags_source,
+            &mut opt_ctx,
+        );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "CONDITIONAL");
+
+    let application_result =
+        ShiftOp::<true, false>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+            cs,
+            &initial_state,
+            &decoder_output,
+            &flags_source,
+            &mut opt_ctx,
+        );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "SHIFT_SRA_ROT");
+
+    let application_result =
+        JumpOp::<false>::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+            cs,
+            &initial_state,
+            &decoder_output,
+            &flags_source,
+            &mut opt_ctx,
+        );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "JUMP");
+
+    let application_result = SystemOp::apply::<_, ASSUME_TRUSTED_CODE, OUTPUT_EXACT_EXCEPTIONS>(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "SYSTEM");
+
+    let [rs1_query, mut rs2_or_mem_load_query, mut rd_or_mem_store_query] = memory_queries;
+
+    let application_result = LoadOp::<true, true>::spec_apply::<
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        ASSUME_TRUSTED_CODE,
+        OUTPUT_EXACT_EXCEPTIONS,
+    >(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut rs2_or_mem_load_query,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "LOAD");
+
+    let application_result = StoreOp::<true>::spec_apply::<
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        ASSUME_TRUSTED_CODE,
+        OUTPUT_EXACT_EXCEPTIONS,
+    >(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut rd_or_mem_store_query,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "STORE");
+
+    let (application_result, csr_writes) = apply_csr_with_delegation_and_trap_csrs::<
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        false,
+        false,
+        false,
+        ASSUME_TRUSTED_CODE,
+        OUTPUT_EXACT_EXCEPTIONS,
+    >(
+        cs,
+        &initial_state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    application_results.push(application_result);
+    cs.set_log(&opt_ctx, "CSR");
+
+    // finish with optimizer, as we do not have any "branching" below
+    opt_ctx.enforce_all(cs);
+    cs.set_log(&opt_ctx, "OPT_CONTEXT");
+
+    // now it's time to merge state, and process traps
+
+    let final_state = writeback_with_traps_with_opcodes_in_rom::<F, CS, true>(
+        cs,
+        opcode_types_bits,
+        raw_decoder_output.rd,
+        rs1_query,
+        rs2_or_mem_load_query,
+        rd_or_mem_store_query,
+        application_results,
+        next_pc,
+        invalid_opcode,
+        next_opcode,
+        csr_writes,
+        &initial_state,
+        &opt_ctx,
+    );
+
+    (initial_state, final_state)
+}
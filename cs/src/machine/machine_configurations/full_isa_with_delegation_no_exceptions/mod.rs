@@ -29,9 +29,9 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineWithDelegationNoExceptionHandli
             Box::new(BinaryOp),
             Box::new(MulOp::<true>),
             Box::new(DivRemOp::<true>),
-            Box::new(ConditionalOp::<true>),
+            Box::new(ConditionalOp::<true, false>),
             Box::new(ShiftOp::<true, false>),
-            Box::new(JumpOp),
+            Box::new(JumpOp::<false>),
             Box::new(LoadOp::<true, true>),
             Box::new(StoreOp::<true>),
             Box::new(CsrOp::<false, false, false>),
@@ -47,7 +47,7 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineWithDelegationNoExceptionHandli
         set.extend(<BinaryOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<MulOp<true> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<DivRemOp<true> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
-        set.extend(<ConditionalOp<true> as MachineOp<
+        set.extend(<ConditionalOp<true, false> as MachineOp<
             F,
             ST<F>,
             RS<F>,
@@ -61,7 +61,7 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineWithDelegationNoExceptionHandli
             DE<F>,
             BS,
         >>::define_used_tables());
-        set.extend(<JumpOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
+        set.extend(<JumpOp<false> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<LoadOp<true, true> as MachineOp<
             F,
             ST<F>,
@@ -96,6 +96,8 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineWithDelegationNoExceptionHandli
             { <Self as Machine<F>>::OUTPUT_EXACT_EXCEPTIONS },
             true,
             true,
+            false,
+            false,
             ROM_ADDRESS_SPACE_SECOND_WORD_BITS,
         >(
             cs,
@@ -28,9 +28,9 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineWithDelegationNoExceptionHandli
             Box::new(BinaryOp),
             Box::new(MulOp::<false>),
             Box::new(DivRemOp::<false>),
-            Box::new(ConditionalOp::<true>),
+            Box::new(ConditionalOp::<true, false>),
             Box::new(ShiftOp::<true, false>),
-            Box::new(JumpOp),
+            Box::new(JumpOp::<false>),
             Box::new(LoadOp::<true, true>),
             Box::new(StoreOp::<true>),
             Box::new(CsrOp::<false, false, false>),
@@ -48,7 +48,7 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineWithDelegationNoExceptionHandli
         set.extend(
             <DivRemOp<false> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables(),
         );
-        set.extend(<ConditionalOp<true> as MachineOp<
+        set.extend(<ConditionalOp<true, false> as MachineOp<
             F,
             ST<F>,
             RS<F>,
@@ -62,7 +62,7 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineWithDelegationNoExceptionHandli
             DE<F>,
             BS,
         >>::define_used_tables());
-        set.extend(<JumpOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
+        set.extend(<JumpOp<false> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<LoadOp<true, true> as MachineOp<
             F,
             ST<F>,
@@ -94,6 +94,8 @@ impl<F: PrimeField> Machine<F> for FullIsaMachineWithDelegationNoExceptionHandli
             { <Self as Machine<F>>::OUTPUT_EXACT_EXCEPTIONS },
             true,
             false,
+            false,
+            false,
             ROM_ADDRESS_SPACE_SECOND_WORD_BITS,
         >(
             cs,
@@ -33,9 +33,9 @@ impl<F: PrimeField> Machine<F> for MinimalMachineNoExceptionHandling {
             Box::new(BinaryOp),
             // Box::new(MulOp::<false>),
             // Box::new(DivRemOp::<false>),
-            Box::new(ConditionalOp::<true>),
+            Box::new(ConditionalOp::<true, false>),
             Box::new(ShiftOp::<true, false>),
-            Box::new(JumpOp),
+            Box::new(JumpOp::<false>),
             Box::new(LoadOp::<false, false>),
             Box::new(StoreOp::<false>),
             Box::new(MopOp),
@@ -54,7 +54,7 @@ impl<F: PrimeField> Machine<F> for MinimalMachineNoExceptionHandling {
         // set.extend(
         //     <DivRemOp<false> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables(),
         // );
-        set.extend(<ConditionalOp<true> as MachineOp<
+        set.extend(<ConditionalOp<true, false> as MachineOp<
             F,
             ST<F>,
             RS<F>,
@@ -68,7 +68,7 @@ impl<F: PrimeField> Machine<F> for MinimalMachineNoExceptionHandling {
             DE<F>,
             BS,
         >>::define_used_tables());
-        set.extend(<JumpOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
+        set.extend(<JumpOp<false> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<LoadOp<false, false> as MachineOp<
             F,
             ST<F>,
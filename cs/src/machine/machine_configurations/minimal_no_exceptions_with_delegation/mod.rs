@@ -30,9 +30,9 @@ impl<F: PrimeField> Machine<F> for MinimalMachineNoExceptionHandlingWithDelegati
             Box::new(LuiOp),
             Box::new(AuiPc),
             Box::new(BinaryOp),
-            Box::new(ConditionalOp::<true>),
+            Box::new(ConditionalOp::<true, false>),
             Box::new(ShiftOp::<true, false>),
-            Box::new(JumpOp),
+            Box::new(JumpOp::<false>),
             Box::new(LoadOp::<false, false>),
             Box::new(StoreOp::<false>),
             Box::new(MopOp),
@@ -47,7 +47,7 @@ impl<F: PrimeField> Machine<F> for MinimalMachineNoExceptionHandlingWithDelegati
         set.extend(<LuiOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<AuiPc as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<BinaryOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
-        set.extend(<ConditionalOp<true> as MachineOp<
+        set.extend(<ConditionalOp<true, false> as MachineOp<
             F,
             ST<F>,
             RS<F>,
@@ -61,7 +61,7 @@ impl<F: PrimeField> Machine<F> for MinimalMachineNoExceptionHandlingWithDelegati
             DE<F>,
             BS,
         >>::define_used_tables());
-        set.extend(<JumpOp as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
+        set.extend(<JumpOp<false> as MachineOp<F, ST<F>, RS<F>, DE<F>, BS>>::define_used_tables());
         set.extend(<LoadOp<false, false> as MachineOp<
             F,
             ST<F>,
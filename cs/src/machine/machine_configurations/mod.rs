@@ -5,12 +5,16 @@ use crate::cs::{
 use super::*;
 
 pub mod full_isa_no_exceptions;
+pub mod full_isa_with_delegation_and_bitmanip_no_exceptions;
+pub mod full_isa_with_delegation_and_compressed_no_exceptions;
+pub mod full_isa_with_delegation_and_trap_handling;
 pub mod full_isa_with_delegation_no_exceptions;
 pub mod full_isa_with_delegation_no_exceptions_no_signed_mul_div;
 pub mod minimal_no_exceptions;
 pub mod minimal_no_exceptions_with_delegation;
 pub mod minimal_state;
 pub mod state_transition_parts;
+pub mod trap_handling_state;
 
 #[derive(Clone, Debug)]
 pub struct BasicFlagsSource {
@@ -212,6 +216,103 @@ pub fn create_table_for_rom_image<
     )
 }
 
+/// Creating a table with ROM (program) data for machines that support compressed instructions.
+/// The table is keyed by half-word aligned PC, so it has size (ROM_ADDRESS_SPACE_BOUND / 2), and
+/// compressed instructions are expanded into their 32-bit equivalents:
+/// (0, expanded instruction bits 0..16, expanded instruction bits 16..32 || is_compressed)
+/// (2, ...)
+// Flag of compressed instruction is placed at bit 16 of the second value, as the circuit range checks
+// high half-word of the instruction anyway. 32-bit instructions at PC % 4 == 2 are assembled from
+// two consecutive words. Invalid compressed instructions are replaced by UNIMP_OPCODE.
+// Separate table is needed as RomRead is also used by loads from ROM, that must see raw image data.
+pub fn create_table_for_rom_image_with_compressed_instructions<
+    F: PrimeField,
+    const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize,
+>(
+    image: &[u32],
+    id: u32,
+) -> LookupTable<F, 3> {
+    assert!(ROM_ADDRESS_SPACE_SECOND_WORD_BITS > 0);
+
+    assert!(
+        image.len() * 4 <= 1 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS),
+        "ROM size can be at most {} bytes ({} words), but input is {} words",
+        1 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS),
+        (1 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS)) / 4,
+        image.len()
+    );
+
+    let mut keys = Vec::with_capacity(1 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS - 1));
+    for i in 0..1 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS - 1) {
+        let mut key = [F::ZERO; 3];
+        let address = i * 2;
+        key[0] = F::from_u64_unchecked(address as u64);
+        keys.push(key);
+    }
+
+    assert_eq!(
+        keys.len(),
+        1 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS - 1)
+    );
+    const TABLE_NAME: &'static str = "ROM table with expanded compressed instructions";
+    let image = image.to_vec();
+    LookupTable::<F, 3>::create_table_from_key_and_key_generation_closure(
+        &keys,
+        TABLE_NAME.to_string(),
+        1,
+        move |key| {
+            let pc = key[0].as_u64_reduced();
+            assert!(
+                pc < 1 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS) as u64,
+                "PC = {} is too large for ROM bound {} bytes",
+                pc,
+                1 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS)
+            );
+            assert!(pc % 2 == 0, "PC = {} is not aligned", pc);
+            let half_word_at = |index: usize| {
+                // UNIMP opcodes
+                let word = image.get(index / 2).copied().unwrap_or(UNIMP_OPCODE);
+                if index % 2 == 0 {
+                    word & 0xffff
+                } else {
+                    word >> 16
+                }
+            };
+            let index = (pc as usize) / 2;
+            let low_half = half_word_at(index);
+            let (opcode, is_compressed) = if is_compressed_instruction(low_half) {
+                let opcode = expand_compressed_instruction(low_half as u16).unwrap_or(UNIMP_OPCODE);
+
+                (opcode, true)
+            } else {
+                (low_half | (half_word_at(index + 1) << 16), false)
+            };
+            let low = opcode as u16;
+            let high = (opcode >> 16) as u16;
+
+            let mut result = [F::ZERO; 3];
+            result[0] = F::from_u64_unchecked(low as u64);
+            result[1] = F::from_u64_unchecked(high as u64 + ((is_compressed as u64) << 16));
+
+            ((pc / 2) as usize, result)
+        },
+        Some(|keys| {
+            let pc = keys[0].as_u64_reduced();
+            assert!(
+                pc < 1u64 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS),
+                "PC = {} is too large for ROM bound {}",
+                pc,
+                1u64 << (16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS)
+            );
+            assert!(pc % 2 == 0, "PC = {} is not aligned", pc);
+            let index = (pc / 2) as usize;
+
+            index
+        }),
+        id,
+    )
+}
+
 pub fn create_csr_table_for_delegation<F: PrimeField>(
     allow_non_determinism: bool,
     allowed_delegation_csrs: &[u32],
@@ -463,4 +564,40 @@ mod tests {
             [Mersenne31Field::new(0x1073), Mersenne31Field::new(0xc000)]
         );
     }
+
+    #[test]
+    fn rom_table_with_compressed_instructions_test() {
+        // c.li a0, 1 || addi a1, x0, 5 || c.add a0, a1
+        let image = [0x0593_4505, 0x952e_0050];
+        let table = create_table_for_rom_image_with_compressed_instructions::<Mersenne31Field, 16>(
+            &image, 15,
+        );
+
+        // Now table should have entries:
+        // 0 -- 0x0513 0x0010 + (1 << 16) (addi a0, x0, 1)
+        // 2 -- 0x0593 0x0050 (addi a1, x0, 5)
+        // 4 -- 0x0613 0x0041 + (1 << 16) (middle of the instruction above, but formally decodes as c.addi4spn a2, sp, 4)
+        // 6 -- 0x0533 0x00b5 + (1 << 16) (add a0, a0, a1)
+        // 8 -- 0x1073 0xc000 (UNIMP)
+        assert_eq!(
+            table.lookup_value::<2>(&[Mersenne31Field::new(0)]),
+            [Mersenne31Field::new(0x0513), Mersenne31Field::new(0x1_0010)]
+        );
+        assert_eq!(
+            table.lookup_value::<2>(&[Mersenne31Field::new(2)]),
+            [Mersenne31Field::new(0x0593), Mersenne31Field::new(0x0050)]
+        );
+        assert_eq!(
+            table.lookup_value::<2>(&[Mersenne31Field::new(4)]),
+            [Mersenne31Field::new(0x0613), Mersenne31Field::new(0x1_0041)]
+        );
+        assert_eq!(
+            table.lookup_value::<2>(&[Mersenne31Field::new(6)]),
+            [Mersenne31Field::new(0x0533), Mersenne31Field::new(0x1_00b5)]
+        );
+        assert_eq!(
+            table.lookup_value::<2>(&[Mersenne31Field::new(8)]),
+            [Mersenne31Field::new(0x1073), Mersenne31Field::new(0xc000)]
+        );
+    }
 }
@@ -118,6 +118,73 @@ pub(crate) fn optimized_decode_and_preallocate_mem_queries_for_bytecode_in_rom<
 ) {
     let next_opcode = read_opcode_from_rom::<F, CS, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(cs, pc);
 
+    optimized_decode_and_preallocate_mem_queries::<F, CS, ASSUME_TRUSTED_CODE, PERFORM_DELEGATION>(
+        cs,
+        next_opcode,
+        decode_table_splitting,
+        boolean_keys,
+    )
+}
+
+/// Same as `optimized_decode_and_preallocate_mem_queries_for_bytecode_in_rom`, but opcode is already
+/// read by the caller (e.g. expanded from compressed instruction)
+pub(crate) fn optimized_decode_and_preallocate_mem_queries<
+    F: PrimeField,
+    CS: Circuit<F>,
+    const ASSUME_TRUSTED_CODE: bool,
+    const PERFORM_DELEGATION: bool,
+>(
+    cs: &mut CS,
+    next_opcode: Register<F>,
+    decode_table_splitting: [usize; 2],
+    boolean_keys: DecoderOutputExtraKeysHolder,
+) -> (
+    [ShuffleRamMemQuery; 3],
+    Register<F>,
+    Register<F>,
+    OptimizedDecoderOutput<F>,
+    BasicFlagsSource,
+    [Boolean; NUM_INSTRUCTION_TYPES_IN_DECODE_BITS],
+) {
+    let (memory_queries, src1, src2, raw_decoder_output, flags_source, opcode_format_bits, _) =
+        optimized_decode_and_preallocate_mem_queries_with_invalid_opcode_flag::<
+            F,
+            CS,
+            ASSUME_TRUSTED_CODE,
+            PERFORM_DELEGATION,
+        >(cs, next_opcode, decode_table_splitting, boolean_keys);
+
+    (
+        memory_queries,
+        src1,
+        src2,
+        raw_decoder_output,
+        flags_source,
+        opcode_format_bits,
+    )
+}
+
+/// Same as `optimized_decode_and_preallocate_mem_queries`, but also returns a flag of invalid opcode.
+/// For trusted code this flag is constrained to be `false`, otherwise the caller must trap on it
+pub(crate) fn optimized_decode_and_preallocate_mem_queries_with_invalid_opcode_flag<
+    F: PrimeField,
+    CS: Circuit<F>,
+    const ASSUME_TRUSTED_CODE: bool,
+    const PERFORM_DELEGATION: bool,
+>(
+    cs: &mut CS,
+    next_opcode: Register<F>,
+    decode_table_splitting: [usize; 2],
+    boolean_keys: DecoderOutputExtraKeysHolder,
+) -> (
+    [ShuffleRamMemQuery; 3],
+    Register<F>,
+    Register<F>,
+    OptimizedDecoderOutput<F>,
+    BasicFlagsSource,
+    [Boolean; NUM_INSTRUCTION_TYPES_IN_DECODE_BITS],
+    Boolean,
+) {
     // there is one small thing here - if we use CSR processing that doesn't have matching over CSR index,
     // then we must handle UNIMP instruction here that is csrrw x0, cycle, x0
     // Also ROM is padded with UNIMP
@@ -131,7 +198,11 @@ pub(crate) fn optimized_decode_and_preallocate_mem_queries_for_bytecode_in_rom<
             assert_no_unimp(cs, next_opcode);
         }
     } else {
-        unimplemented!()
+        // UNIMP will be processed as an access to unsupported CSR, and so will trap
+        assert!(
+            PERFORM_DELEGATION,
+            "untrusted code requires CSR processing with matching over CSR index"
+        );
     }
 
     if let Some(opcode) = next_opcode.get_value_unsigned(cs) {
@@ -152,9 +223,9 @@ pub(crate) fn optimized_decode_and_preallocate_mem_queries_for_bytecode_in_rom<
         cs.add_constraint_allow_explicit_linear_prevent_optimizations(Constraint::<F>::from(
             invalid_opcode,
         ));
-    } else {
-        unimplemented!()
     }
+    // otherwise caller is responsible to trap with illegal instruction. Invalid opcode is decoded
+    // with no family flags set and B-type format, so it doesn't write into RD
 
     let flags_source = BasicFlagsSource::new(boolean_keys, other_bits);
 
@@ -261,5 +332,6 @@ pub(crate) fn optimized_decode_and_preallocate_mem_queries_for_bytecode_in_rom<
         raw_decoder_output,
         flags_source,
         opcode_format_bits,
+        invalid_opcode,
     )
 }
@@ -2,6 +2,8 @@ use super::*;
 
 mod decode_and_read_operands;
 mod writeback_no_exceptions;
+mod writeback_with_traps;
 
 pub(crate) use self::decode_and_read_operands::*;
 pub(crate) use self::writeback_no_exceptions::*;
+pub(crate) use self::writeback_with_traps::*;
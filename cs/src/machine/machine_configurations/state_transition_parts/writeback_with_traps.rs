@@ -0,0 +1,171 @@
+use super::*;
+use crate::machine::machine_configurations::trap_handling_state::*;
+use crate::machine::ops::CsrWrites;
+
+pub(crate) fn writeback_with_traps_with_opcodes_in_rom<
+    F: PrimeField,
+    CS: Circuit<F>,
+    const PERFORM_DELEGATION: bool,
+>(
+    cs: &mut CS,
+    opcode_format_bits: [Boolean; NUM_INSTRUCTION_TYPES_IN_DECODE_BITS],
+    rd_constraint: Constraint<F>,
+    rs1_query: ShuffleRamMemQuery,
+    rs2_or_mem_load_query: ShuffleRamMemQuery,
+    rd_or_mem_store_query: ShuffleRamMemQuery,
+    application_results: Vec<CommonDiffs<F>>,
+    default_next_pc: Register<F>,
+    invalid_opcode: Boolean,
+    opcode: Register<F>,
+    csr_writes: CsrWrites<F>,
+    initial_state: &TrapHandlingStateRegistersInMemory<F>,
+    opt_ctx: &OptimizationContext<F, CS>,
+) -> TrapHandlingStateRegistersInMemory<F> {
+    // collect all the traps. Opcodes are allowed to report `trapped` as a result of conditional lookups,
+    // so we mask it by the execution flag. After masking all the flags are orthogonal
+    let Boolean::Is(invalid_opcode) = invalid_opcode else {
+        unreachable!()
+    };
+    let mut trapped_constraint = Constraint::from(invalid_opcode);
+    let mut trap_reason_constraint = Constraint::from(Term::from((
+        F::from_u64_unchecked(TrapReason::IllegalInstruction as u64),
+        invalid_opcode,
+    )));
+    for el in application_results.iter() {
+        let Some(trapped) = el.trapped else {
+            continue;
+        };
+        let trapped = match trapped {
+            Boolean::Is(trapped) => trapped,
+            Boolean::Constant(false) => continue,
+            _ => unreachable!(),
+        };
+        let trap_reason = el
+            .trap_reason
+            .expect("trap reason must be set if opcode can trap");
+
+        let masked_trapped =
+            cs.add_variable_from_constraint(Term::from(el.exec_flag) * Term::from(trapped));
+        trapped_constraint = trapped_constraint + Term::from(masked_trapped);
+        trap_reason_constraint =
+            trap_reason_constraint + Term::from(masked_trapped) * Term::from(trap_reason);
+    }
+    let trapped = cs.add_variable_from_constraint_allow_explicit_linear(trapped_constraint);
+    let trap_reason = cs.add_variable_from_constraint_allow_explicit_linear(trap_reason_constraint);
+
+    let new_reg_val = CommonDiffs::select_final_rd_value(cs, &application_results);
+
+    // if we will not update register and do not execute memory store, then
+    // we still want to model it as reading x0 (and writing back hardcoded 0)
+
+    let [r_insn, i_insn, _s_insn, b_insn, u_insn, j_insn] = opcode_format_bits;
+
+    // opcode formats are orthogonal flags, so a boolean to update RD is just a linear combination
+    let update_rd = Constraint::from(r_insn.get_variable().unwrap())
+        + Constraint::from(i_insn.get_variable().unwrap())
+        + Constraint::from(j_insn.get_variable().unwrap())
+        + Constraint::from(u_insn.get_variable().unwrap());
+
+    let rd = cs.add_variable_from_constraint_allow_explicit_linear(rd_constraint.clone());
+    let reg_is_zero = cs.is_zero(Num::Var(rd));
+
+    // Mask to get 0s if we write into x0
+    let reg_write_value_low = cs.add_variable_from_constraint(
+        (Term::from(1) - Term::from(reg_is_zero.get_variable().unwrap()))
+            * Term::from(new_reg_val.0[0]),
+    );
+    let reg_write_value_high = cs.add_variable_from_constraint(
+        (Term::from(1) - Term::from(reg_is_zero.get_variable().unwrap()))
+            * Term::from(new_reg_val.0[1]),
+    );
+
+    // and if we trap, then RD is left untouched
+    let [reg_write_value_low, reg_write_value_high] = [
+        (reg_write_value_low, rd_or_mem_store_query.read_value[0]),
+        (reg_write_value_high, rd_or_mem_store_query.read_value[1]),
+    ]
+    .map(|(write_value, read_value)| {
+        cs.add_variable_from_constraint(
+            Term::from(trapped) * (Term::from(read_value) - Term::from(write_value))
+                + Term::from(write_value),
+        )
+    });
+
+    // now constraint that if we do update register, then address is correct
+    let ShuffleRamQueryType::RegisterOrRam {
+        is_register,
+        address,
+    } = rd_or_mem_store_query.query_type
+    else {
+        unreachable!()
+    };
+    let Boolean::Is(..) = is_register else {
+        panic!("Memory opcode must resolve RD/STORE query `is_register` flag");
+    };
+    // if we write to RD - we should make a constraint over the address, that it comes from opcode
+    cs.add_constraint((rd_constraint.clone() - Term::from(address[0])) * update_rd.clone());
+    cs.add_constraint((Term::from(address[1])) * update_rd.clone());
+    // x0 for BRANCH instructions as it's not even encoded in the opcode
+    cs.add_constraint((Term::from(address[0])) * Term::from(b_insn));
+    cs.add_constraint((Term::from(address[1])) * Term::from(b_insn));
+
+    // and constraint value
+    cs.add_constraint(
+        (Term::from(reg_write_value_low) - Term::from(rd_or_mem_store_query.write_value[0]))
+            * update_rd.clone(),
+    );
+    cs.add_constraint(
+        (Term::from(reg_write_value_high) - Term::from(rd_or_mem_store_query.write_value[1]))
+            * update_rd.clone(),
+    );
+    // 0 for BRANCH instructions
+    cs.add_constraint((Term::from(rd_or_mem_store_query.write_value[0])) * Term::from(b_insn));
+    cs.add_constraint((Term::from(rd_or_mem_store_query.write_value[1])) * Term::from(b_insn));
+
+    // push all memory queries
+    cs.add_shuffle_ram_query(rs1_query);
+    cs.add_shuffle_ram_query(rs2_or_mem_load_query);
+    cs.add_shuffle_ram_query(rd_or_mem_store_query);
+
+    let trapped = Boolean::Is(trapped);
+
+    // we only support direct mode of `mtvec`, so we jump there as-is
+    let new_pc = CommonDiffs::select_final_pc_value(cs, &application_results, default_next_pc);
+    let new_pc = Register::choose(cs, &trapped, &initial_state.mtvec, &new_pc);
+
+    // CSR writes can not happen together with the trap, and then trap updates machine state CSRs
+    let mut csrs = initial_state.all_csrs();
+    for (csr, flag) in csr_writes.flags.iter() {
+        csrs.get_mut(csr)
+            .expect("CSR write must be into machine state CSR")
+            .update_if_flag_is_set(cs, flag, &csr_writes.value);
+    }
+
+    let mtvec = csrs[&MTVEC_CSR_INDEX];
+    let mepc = Register::choose(cs, &trapped, initial_state.get_pc(), &csrs[&MEPC_CSR_INDEX]);
+    let mcause = Register::choose(
+        cs,
+        &trapped,
+        &Register([Num::Var(trap_reason), Num::Constant(F::ZERO)]),
+        &csrs[&MCAUSE_CSR_INDEX],
+    );
+    // for simplicity we always put the trapping opcode into `mtval`
+    let mtval = Register::choose(cs, &trapped, &opcode, &csrs[&MTVAL_CSR_INDEX]);
+
+    let final_state = TrapHandlingStateRegistersInMemory {
+        pc: new_pc,
+        mtvec,
+        mepc,
+        mcause,
+        mtval,
+    };
+
+    cs.set_log(&opt_ctx, "EXECUTOR");
+    cs.view_log(if PERFORM_DELEGATION {
+        "ISA_WITH_DELEGATION_AND_TRAPS"
+    } else {
+        "ISA_WITHOUT_DELEGATION_AND_TRAPS"
+    });
+
+    final_state
+}
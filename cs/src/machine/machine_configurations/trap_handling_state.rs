@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::devices::aux_data::PcWrapper;
+
+pub const MTVEC_CSR_INDEX: u16 = 0x305;
+pub const MEPC_CSR_INDEX: u16 = 0x341;
+pub const MCAUSE_CSR_INDEX: u16 = 0x342;
+pub const MTVAL_CSR_INDEX: u16 = 0x343;
+
+pub const TRAP_HANDLING_CSRS: [u16; 4] = [
+    MTVEC_CSR_INDEX,
+    MEPC_CSR_INDEX,
+    MCAUSE_CSR_INDEX,
+    MTVAL_CSR_INDEX,
+];
+
+/// Machine state for configurations that vector traps to `mtvec`. Only direct mode of `mtvec`
+/// is supported, and there are no interrupts, so `mstatus` is not a part of the state
+#[derive(Clone, Copy, Debug)]
+pub struct TrapHandlingStateRegistersInMemory<F: PrimeField> {
+    pub pc: Register<F>,
+    pub mtvec: Register<F>,
+    pub mepc: Register<F>,
+    pub mcause: Register<F>,
+    pub mtval: Register<F>,
+}
+
+impl<F: PrimeField> AbstractMachineState<F> for TrapHandlingStateRegistersInMemory<F> {
+    fn set_size() -> usize {
+        Register::<F>::set_size() * 5
+    }
+
+    fn append_into_variables_set(&self, dst: &mut Vec<Variable>) {
+        self.pc.append_into_variables_set(dst);
+        self.mtvec.append_into_variables_set(dst);
+        self.mepc.append_into_variables_set(dst);
+        self.mcause.append_into_variables_set(dst);
+        self.mtval.append_into_variables_set(dst);
+    }
+}
+
+impl<F: PrimeField> TrapHandlingStateRegistersInMemory<F> {
+    pub fn initialize<CS: Circuit<F>>(circuit: &mut CS) -> Self {
+        // this will link to witness inputs
+        let pc = PcWrapper::<F>::initialize(circuit);
+        let mtvec = Register::new_unchecked_from_placeholder(circuit, Placeholder::TvecInit);
+        let mepc = Register::new_unchecked_from_placeholder(circuit, Placeholder::EpcInit);
+        let mcause = Register::new_unchecked_from_placeholder(circuit, Placeholder::CauseInit);
+        let mtval = Register::new_unchecked_from_placeholder(circuit, Placeholder::TvalInit);
+
+        Self {
+            pc: pc.pc,
+            mtvec,
+            mepc,
+            mcause,
+            mtval,
+        }
+    }
+}
+
+impl<F: PrimeField> BaseMachineState<F> for TrapHandlingStateRegistersInMemory<F> {
+    fn opcodes_are_in_rom() -> bool {
+        true
+    }
+
+    fn get_pc(&self) -> &Register<F> {
+        &self.pc
+    }
+    fn get_pc_mut(&mut self) -> &mut Register<F> {
+        &mut self.pc
+    }
+
+    fn csr_use_props() -> CSRUseProperties {
+        CSRUseProperties {
+            standard_csrs: TRAP_HANDLING_CSRS.to_vec(),
+            allow_non_determinism_csr: true,
+            support_mstatus: false,
+        }
+    }
+
+    fn all_csrs(&self) -> BTreeMap<u16, Register<F>> {
+        BTreeMap::from([
+            (MTVEC_CSR_INDEX, self.mtvec),
+            (MEPC_CSR_INDEX, self.mepc),
+            (MCAUSE_CSR_INDEX, self.mcause),
+            (MTVAL_CSR_INDEX, self.mtval),
+        ])
+    }
+}
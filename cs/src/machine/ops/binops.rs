@@ -218,3 +218,140 @@ impl<
         }
     }
 }
+
+pub const NEGATED_BINOP_COMMON_OP_KEY: DecoderMajorInstructionFamilyKey =
+    DecoderMajorInstructionFamilyKey("NEGATED_BINOP_COMMON_KEY");
+
+/// ANDN/ORN/XNOR from Zbb (and Zbkb). Those are the same as AND/OR/XOR, but second operand is
+/// bitwise negated, so we reuse the same tables (selected by funct3) over the negated bytes of RS2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NegatedBinaryOp;
+
+impl DecodableMachineOp for NegatedBinaryOp {
+    fn define_decoder_subspace(
+        &self,
+        opcode: u8,
+        func3: u8,
+        func7: u8,
+    ) -> Result<
+        (
+            InstructionType,
+            DecoderMajorInstructionFamilyKey,
+            &'static [DecoderInstructionVariantsKey],
+        ),
+        (),
+    > {
+        let params = match (opcode, func3, func7) {
+            (OPERATION_OP, 0b111, 0b010_0000) => {
+                // ANDN
+                (InstructionType::RType, NEGATED_BINOP_COMMON_OP_KEY, &[][..])
+            }
+            (OPERATION_OP, 0b110, 0b010_0000) => {
+                // ORN
+                (InstructionType::RType, NEGATED_BINOP_COMMON_OP_KEY, &[][..])
+            }
+            (OPERATION_OP, 0b100, 0b010_0000) => {
+                // XNOR
+                (InstructionType::RType, NEGATED_BINOP_COMMON_OP_KEY, &[][..])
+            }
+            _ => return Err(()),
+        };
+
+        Ok(params)
+    }
+}
+
+impl<
+        F: PrimeField,
+        ST: BaseMachineState<F>,
+        RS: RegisterValueSource<F>,
+        DE: DecoderOutputSource<F, RS>,
+        BS: IndexableBooleanSet,
+    > MachineOp<F, ST, RS, DE, BS> for NegatedBinaryOp
+{
+    fn define_used_tables() -> Vec<TableType> {
+        vec![TableType::Xor, TableType::Or, TableType::And]
+    }
+
+    fn apply<
+        CS: Circuit<F>,
+        const ASSUME_TRUSTED_CODE: bool,
+        const OUTPUT_EXACT_EXCEPTIONS: bool,
+    >(
+        cs: &mut CS,
+        _machine_state: &ST,
+        inputs: &DE,
+        boolean_set: &BS,
+        opt_ctx: &mut OptimizationContext<F, CS>,
+    ) -> CommonDiffs<F> {
+        opt_ctx.reset_indexers();
+        let exec_flag = boolean_set.get_major_flag(NEGATED_BINOP_COMMON_OP_KEY);
+
+        let src1 = inputs.get_rs1_or_equivalent();
+        let src2 = inputs.get_rs2_or_equivalent();
+
+        let funct3 = inputs.funct3();
+
+        let src1_decomposition = src1.get_register_with_decomposition_and_sign().unwrap();
+        let src2_decomposition = src2.get_register_with_decomposition_and_sign().unwrap();
+
+        let src1_bytes = [
+            Constraint::<F>::from(src1_decomposition.low_word_unconstrained_decomposition.0),
+            src1_decomposition
+                .low_word_unconstrained_decomposition
+                .1
+                .clone(),
+            src1_decomposition.high_word_decomposition.0.clone(),
+            Constraint::<F>::from(src1_decomposition.high_word_decomposition.1),
+        ];
+        let src2_bytes = [
+            Constraint::<F>::from(src2_decomposition.low_word_unconstrained_decomposition.0),
+            src2_decomposition
+                .low_word_unconstrained_decomposition
+                .1
+                .clone(),
+            src2_decomposition.high_word_decomposition.0.clone(),
+            Constraint::<F>::from(src2_decomposition.high_word_decomposition.1),
+        ];
+
+        // NB: as in the BinaryOp we do not need range checks here. Binary tables constraint
+        // inputs to be 8-bits long, and 255 - x is a byte if and only if x is a byte
+        let mut res_chunks = vec![];
+        for (left_in, right_in) in src1_bytes.iter().zip(src2_bytes.iter()) {
+            let negated_right_in = Constraint::<F>::from(0xffu64) - right_in.clone();
+            let [out] = opt_ctx.append_lookup_relation_from_linear_terms::<2, 1>(
+                cs,
+                &[left_in.clone(), negated_right_in],
+                funct3,
+                exec_flag,
+            );
+            res_chunks.push(out);
+        }
+
+        if exec_flag.get_value(cs).unwrap_or(false) {
+            println!("NEGATED BINOP");
+            dbg!(src1.get_register().get_value_unsigned(cs));
+            dbg!(src2.get_register().get_value_unsigned(cs));
+            dbg!(cs.get_value(funct3.get_variable()));
+        }
+
+        let returned_value = [
+            Constraint::<F>::from(
+                Term::from(res_chunks[0])
+                    + Term::from((F::from_u64_unchecked(1 << 8), res_chunks[1])),
+            ),
+            Constraint::<F>::from(
+                Term::from(res_chunks[2])
+                    + Term::from((F::from_u64_unchecked(1 << 8), res_chunks[3])),
+            ),
+        ];
+
+        CommonDiffs {
+            exec_flag,
+            trapped: None,
+            trap_reason: None,
+            rd_value: vec![(returned_value, exec_flag)],
+            new_pc_value: NextPcValue::Default,
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use super::*;
+
+pub const BYTE_OP_COMMON_OP_KEY: DecoderMajorInstructionFamilyKey =
+    DecoderMajorInstructionFamilyKey("BYTE_OP_COMMON_KEY");
+pub const BYTE_OP_ORC_B_KEY: DecoderInstructionVariantsKey = DecoderInstructionVariantsKey("ORC.B");
+
+// immediates of BREV8 and REV8 (both use funct7 = 0b0110100), and ORC.B differs
+// from BREV8 only in funct7 = 0b0010100
+const BREV8_IMM: u64 = 0b0110100_00111;
+const REV8_IMM: u64 = 0b0110100_11000;
+const ORC_B_TO_BREV8_IMM_OFFSET: u64 = 0b0100000_00000;
+
+/// ORC.B and REV8 from Zbb, and BREV8 from Zbkb. All of them act on each byte (or swap bytes),
+/// so we resolve them by looking up both half-words in the ByteWiseBitmanip table, and swap
+/// half-words for REV8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ByteOp;
+
+impl DecodableMachineOp for ByteOp {
+    fn define_decoder_subspace(
+        &self,
+        opcode: u8,
+        func3: u8,
+        func7: u8,
+    ) -> Result<
+        (
+            InstructionType,
+            DecoderMajorInstructionFamilyKey,
+            &'static [DecoderInstructionVariantsKey],
+        ),
+        (),
+    > {
+        let params = match (opcode, func3, func7) {
+            (OPERATION_OP_IMM, 0b101, 0b001_0100) => {
+                // ORC.B
+                (
+                    InstructionType::IType,
+                    BYTE_OP_COMMON_OP_KEY,
+                    &[BYTE_OP_ORC_B_KEY][..],
+                )
+            }
+            (OPERATION_OP_IMM, 0b101, 0b011_0100) => {
+                // REV8/BREV8
+                (InstructionType::IType, BYTE_OP_COMMON_OP_KEY, &[][..])
+            }
+            _ => return Err(()),
+        };
+
+        Ok(params)
+    }
+}
+
+impl<
+        F: PrimeField,
+        ST: BaseMachineState<F>,
+        RS: RegisterValueSource<F>,
+        DE: DecoderOutputSource<F, RS>,
+        BS: IndexableBooleanSet,
+    > MachineOp<F, ST, RS, DE, BS> for ByteOp
+{
+    fn define_used_tables() -> Vec<TableType> {
+        vec![TableType::ByteWiseBitmanip]
+    }
+
+    fn apply<
+        CS: Circuit<F>,
+        const ASSUME_TRUSTED_CODE: bool,
+        const OUTPUT_EXACT_EXCEPTIONS: bool,
+    >(
+        cs: &mut CS,
+        _machine_state: &ST,
+        inputs: &DE,
+        boolean_set: &BS,
+        opt_ctx: &mut OptimizationContext<F, CS>,
+    ) -> CommonDiffs<F> {
+        opt_ctx.reset_indexers();
+        let exec_flag = boolean_set.get_major_flag(BYTE_OP_COMMON_OP_KEY);
+        let is_orc_b = boolean_set.get_minor_flag(BYTE_OP_COMMON_OP_KEY, BYTE_OP_ORC_B_KEY);
+
+        let src1 = inputs.get_rs1_or_equivalent();
+        let imm = inputs.get_imm();
+
+        let input = src1.get_register();
+
+        // immediate is BREV8_IMM for both ORC.B (after adjustment) and BREV8, and REV8_IMM for REV8,
+        // so we can get a REV8 flag linearly
+        let rev8_normalization = F::from_u64_unchecked(REV8_IMM - BREV8_IMM)
+            .inverse()
+            .unwrap();
+        let is_rev8 = (Constraint::<F>::from(imm.0[0])
+            + Term::from(ORC_B_TO_BREV8_IMM_OFFSET) * Term::from(is_orc_b)
+            - Term::from(BREV8_IMM))
+            * Term::from_field(rev8_normalization);
+
+        // table mode is 0 for ORC.B, 1 for BREV8 and 2 for REV8
+        let mode = Constraint::<F>::from(1u64) - Term::from(is_orc_b) + is_rev8.clone();
+
+        let [low_output, _] = opt_ctx.append_lookup_relation_from_linear_terms::<1, 2>(
+            cs,
+            &[Constraint::from(input.0[0]) + mode.clone() * Term::from(1 << 16)],
+            TableType::ByteWiseBitmanip.to_num(),
+            exec_flag,
+        );
+        let [high_output, _] = opt_ctx.append_lookup_relation_from_linear_terms::<1, 2>(
+            cs,
+            &[Constraint::from(input.0[1]) + mode * Term::from(1 << 16)],
+            TableType::ByteWiseBitmanip.to_num(),
+            exec_flag,
+        );
+
+        // REV8 is a byte swap within each half-word followed by swap of half-words
+        let result_low = cs.add_variable_from_constraint(
+            Constraint::from(low_output)
+                + is_rev8 * (Term::from(high_output) - Term::from(low_output)),
+        );
+        let result_high = Term::from(low_output) + Term::from(high_output) - Term::from(result_low);
+
+        if exec_flag.get_value(cs).unwrap_or(false) {
+            println!("BYTE OP");
+            dbg!(src1.get_register().get_value_unsigned(cs));
+            dbg!(imm.get_value_unsigned(cs));
+            dbg!(is_orc_b.get_value(cs));
+        }
+
+        let returned_value = [Constraint::<F>::from(result_low), result_high];
+
+        CommonDiffs {
+            exec_flag,
+            trapped: None,
+            trap_reason: None,
+            rd_value: vec![(returned_value, exec_flag)],
+            new_pc_value: NextPcValue::Default,
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use super::*;
+use std::collections::BTreeMap;
+
+use crate::machine::machine_configurations::trap_handling_state::TRAP_HANDLING_CSRS;
+
+/// Writes into machine state CSRs. Those can not be expressed via `CommonDiffs`,
+/// so they are passed to the writeback separately
+#[derive(Clone, Debug)]
+pub struct CsrWrites<F: PrimeField> {
+    pub value: Register<F>,
+    pub flags: BTreeMap<u16, Boolean>,
+}
+
+/// Same as `apply_csr_with_delegation`, but for untrusted code: in addition to non-determinism
+/// and delegation CSRs it supports CSRRW over machine state CSRs, and access to any other CSR is
+/// a trap with illegal instruction
+pub fn apply_csr_with_delegation_and_trap_csrs<
+    F: PrimeField,
+    CS: Circuit<F>,
+    ST: BaseMachineState<F>,
+    RS: RegisterValueSource<F>,
+    DE: DecoderOutputSource<F, RS>,
+    BS: IndexableBooleanSet,
+    const SUPPORT_CSRRC: bool,
+    const SUPPORT_CSRRS: bool,
+    const SUPPORT_CSR_IMMEDIATES: bool,
+    const ASSUME_TRUSTED_CODE: bool,
+    const OUTPUT_EXACT_EXCEPTIONS: bool,
+>(
+    cs: &mut CS,
+    machine_state: &ST,
+    inputs: &DE,
+    boolean_set: &BS,
+    opt_ctx: &mut OptimizationContext<F, CS>,
+) -> (CommonDiffs<F>, CsrWrites<F>) {
+    const {
+        assert!(
+            !(SUPPORT_CSR_IMMEDIATES || SUPPORT_CSRRC || SUPPORT_CSRRS),
+            "only CSRRW is supported together with trap handling CSRs"
+        );
+        assert!(ASSUME_TRUSTED_CODE == false);
+    }
+
+    opt_ctx.reset_indexers();
+    let exec_flag = boolean_set.get_major_flag(CSR_COMMON_OP_KEY);
+
+    let src1 = inputs.get_rs1_or_equivalent().get_register();
+    let all_csrs = machine_state.all_csrs();
+
+    // access non-determinism CSR, or perform delegation
+    let external_oracle =
+        Register::new_unchecked_from_placeholder::<CS>(cs, Placeholder::ExternalOracle);
+    external_oracle.0.iter().for_each(|x| {
+        cs.require_invariant(x.get_variable(), Invariant::RangeChecked { width: 16 })
+    });
+
+    let csr_index = inputs.funct12();
+    let [is_supported_csr, is_for_delegation] = opt_ctx
+        .append_lookup_relation_from_linear_terms::<1, 2>(
+            cs,
+            &[csr_index.clone()],
+            TableType::SpecialCSRProperties.to_num(),
+            exec_flag,
+        );
+
+    // machine state CSRs are not in the special CSRs table, so we compare explicitly
+    let mut state_csr_flags = BTreeMap::new();
+    for csr in TRAP_HANDLING_CSRS.iter() {
+        let diff = cs.add_variable_from_constraint_allow_explicit_linear(
+            csr_index.clone() - Term::from(*csr as u64),
+        );
+        let is_this_csr = cs.is_zero(Num::Var(diff));
+        let exec_this_csr = Boolean::and(&exec_flag, &is_this_csr, cs);
+        state_csr_flags.insert(*csr, exec_this_csr);
+    }
+
+    // if CSR is not supported, then we trap. NOTE: special CSRs lookup is conditional,
+    // so it's masked by the execution flag
+    let mut trapped_constraint =
+        Term::from(exec_flag) * (Term::from(1) - Term::from(is_supported_csr));
+    for flag in state_csr_flags.values() {
+        trapped_constraint = trapped_constraint - Term::from(*flag);
+    }
+    let trapped = cs.add_variable_from_constraint(trapped_constraint);
+
+    let should_delegate =
+        cs.add_variable_from_constraint(Term::from(is_for_delegation) * Term::from(exec_flag));
+
+    // in our ABI we use highest 16 bits of src1 as the offset
+    let offset = src1.0[1];
+
+    // and to have easier consistency with memory witness delegation we also want to mask everything down to 0s
+    let offset_masked =
+        cs.add_variable_from_constraint(Term::from(should_delegate) * Term::from(offset));
+    let csr_index_masked = cs.add_variable_from_constraint(Term::from(should_delegate) * csr_index);
+
+    let delegation_request = DelegatedComputationRequest {
+        execute: should_delegate,
+        degegation_type: csr_index_masked,
+        memory_offset_high: offset_masked,
+    };
+    cs.add_delegation_request(delegation_request);
+
+    // if we do DO delegate, then we require that oracle is 0 - and prover can still satisfy it
+    // even though `is_delegate` is not strictly boolean
+    cs.add_constraint(Term::from(is_for_delegation) * Term::from(external_oracle.0[0]));
+    cs.add_constraint(Term::from(is_for_delegation) * Term::from(external_oracle.0[1]));
+
+    // oracle value is used for all special CSRs, and for unsupported ones, where RD is not updated anyway
+    let mut exec_oracle_constraint = Constraint::from(exec_flag);
+    for flag in state_csr_flags.values() {
+        exec_oracle_constraint = exec_oracle_constraint - Term::from(*flag);
+    }
+    let exec_oracle = cs.add_variable_from_constraint_allow_explicit_linear(exec_oracle_constraint);
+
+    let mut rd_value = vec![(
+        [
+            Constraint::<F>::from(external_oracle.0[0]),
+            Constraint::<F>::from(external_oracle.0[1]),
+        ],
+        Boolean::Is(exec_oracle),
+    )];
+    for (csr, flag) in state_csr_flags.iter() {
+        let value = all_csrs
+            .get(csr)
+            .expect("machine state must contain trap handling CSRs");
+        rd_value.push((
+            [
+                Constraint::<F>::from(value.0[0]),
+                Constraint::<F>::from(value.0[1]),
+            ],
+            *flag,
+        ));
+    }
+
+    if exec_flag.get_value(cs).unwrap_or(false) {
+        println!("CSR");
+        dbg!(src1.get_value_unsigned(cs));
+        if cs.get_value(trapped).unwrap() == F::ONE {
+            println!("Unsupported CSR");
+        }
+        if cs.get_value(should_delegate).unwrap() == F::ONE {
+            println!(
+                "Perform delegation to CSR {} with offset {}",
+                cs.get_value(csr_index_masked).unwrap(),
+                cs.get_value(offset_masked).unwrap(),
+            );
+        }
+    }
+
+    let diffs = CommonDiffs {
+        exec_flag,
+        trapped: Some(Boolean::Is(trapped)),
+        trap_reason: Some(Num::Constant(F::from_u64_unchecked(
+            TrapReason::IllegalInstruction as u64,
+        ))),
+        rd_value,
+        new_pc_value: NextPcValue::Default,
+    };
+
+    // CSRRW writes src1 into the CSR
+    let writes = CsrWrites {
+        value: src1,
+        flags: state_csr_flags,
+    };
+
+    (diffs, writes)
+}
@@ -2,6 +2,8 @@ use super::*;
 
 pub mod csr;
 pub mod csr_with_delegation;
+pub mod csr_with_trap_handling;
 
 pub use self::csr::*;
 pub use self::csr_with_delegation::*;
+pub use self::csr_with_trap_handling::*;
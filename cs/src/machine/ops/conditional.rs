@@ -3,10 +3,14 @@ use super::*;
 pub const CONDITIONAL_COMMON_OP_KEY: DecoderMajorInstructionFamilyKey =
     DecoderMajorInstructionFamilyKey("CONDITIONAL_COMMON_KEY");
 
+/// Branches. If `SUPPORT_COMPRESSED` is set, then destination only needs to be 2-byte aligned,
+/// that is always true for the encoded offsets
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct ConditionalOp<const SUPPORT_SIGNED: bool>;
+pub struct ConditionalOp<const SUPPORT_SIGNED: bool, const SUPPORT_COMPRESSED: bool>;
 
-impl<const SUPPORT_SIGNED: bool> DecodableMachineOp for ConditionalOp<SUPPORT_SIGNED> {
+impl<const SUPPORT_SIGNED: bool, const SUPPORT_COMPRESSED: bool> DecodableMachineOp
+    for ConditionalOp<SUPPORT_SIGNED, SUPPORT_COMPRESSED>
+{
     fn define_decoder_subspace(
         &self,
         opcode: u8,
@@ -75,7 +79,8 @@ impl<
         DE: DecoderOutputSource<F, RS>,
         BS: IndexableBooleanSet,
         const SUPPORT_SIGNED: bool,
-    > MachineOp<F, ST, RS, DE, BS> for ConditionalOp<SUPPORT_SIGNED>
+        const SUPPORT_COMPRESSED: bool,
+    > MachineOp<F, ST, RS, DE, BS> for ConditionalOp<SUPPORT_SIGNED, SUPPORT_COMPRESSED>
 {
     fn define_used_tables() -> Vec<TableType> {
         if SUPPORT_SIGNED {
@@ -180,77 +185,94 @@ impl<
 
         // NOTE: below lookups are conditional, so we do NOT use Booleans
 
-        if ASSUME_TRUSTED_CODE == false {
-            todo!();
+        // jump destination is always 0 mod 2 as explained above, and that is enough
+        // if compressed instructions are supported
+        let is_misaligned_addr = if SUPPORT_COMPRESSED {
+            None
         } else {
-            // jump destination is always 0 mod 2 as explained above
             let [bit_1, _] = opt_ctx.append_lookup_relation(
                 cs,
                 &[jmp_addr.0[0].get_variable()],
                 TableType::JumpCleanupOffset.to_num(),
                 exec_flag,
             );
-            let is_misaligned_addr = bit_1;
 
-            let table_id = if SUPPORT_SIGNED {
-                TableType::ConditionalOpAllConditionsResolver
-            } else {
-                TableType::ConditionalOpUnsignedConditionsResolver
-            };
+            Some(bit_1)
+        };
+
+        let table_id = if SUPPORT_SIGNED {
+            TableType::ConditionalOpAllConditionsResolver
+        } else {
+            TableType::ConditionalOpUnsignedConditionsResolver
+        };
 
-            let [should_jump, comparison_value] = opt_ctx
-                .append_lookup_relation_from_linear_terms::<1, 2>(
-                    cs,
-                    &[key_constraint],
-                    table_id.to_num(),
-                    exec_flag,
-                );
+        let [should_jump, comparison_value] = opt_ctx
+            .append_lookup_relation_from_linear_terms::<1, 2>(
+                cs,
+                &[key_constraint],
+                table_id.to_num(),
+                exec_flag,
+            );
 
-            let exec_jump = should_jump;
+        let exec_jump = should_jump;
+        let trapped = if let Some(is_misaligned_addr) = is_misaligned_addr {
             let trapped = cs.add_variable_from_constraint(
                 Term::from(should_jump) * Term::from(is_misaligned_addr),
             );
 
-            // if we do jump, then it must be unprovable
-            cs.add_constraint(Term::from(trapped) * exec_flag.get_terms());
+            if ASSUME_TRUSTED_CODE {
+                // if we do jump, then it must be unprovable
+                cs.add_constraint(Term::from(trapped) * exec_flag.get_terms());
 
-            let new_pc_low = cs.add_variable_from_constraint(
-                Term::from(exec_jump) * Term::from(true_jmp_address.0[0].get_variable())
-                    + (Term::from(1) - Term::from(exec_jump))
-                        * Term::from(pc_next.0[0].get_variable()),
-            );
-            let new_pc_high = cs.add_variable_from_constraint(
-                Term::from(exec_jump) * Term::from(true_jmp_address.0[1].get_variable())
-                    + (Term::from(1) - Term::from(exec_jump))
-                        * Term::from(pc_next.0[1].get_variable()),
-            );
+                None
+            } else {
+                // otherwise it's a trap to be processed by the writeback. Both lookups above are
+                // conditional, so it must be masked by the execution flag there
+                Some(Boolean::Is(trapped))
+            }
+        } else {
+            None
+        };
+        let trap_reason = trapped.map(|_| {
+            Num::Constant(F::from_u64_unchecked(
+                TrapReason::InstructionAddressMisaligned as u64,
+            ))
+        });
 
-            let pc = Register([Num::Var(new_pc_low), Num::Var(new_pc_high)]);
+        let new_pc_low = cs.add_variable_from_constraint(
+            Term::from(exec_jump) * Term::from(true_jmp_address.0[0].get_variable())
+                + (Term::from(1) - Term::from(exec_jump)) * Term::from(pc_next.0[0].get_variable()),
+        );
+        let new_pc_high = cs.add_variable_from_constraint(
+            Term::from(exec_jump) * Term::from(true_jmp_address.0[1].get_variable())
+                + (Term::from(1) - Term::from(exec_jump)) * Term::from(pc_next.0[1].get_variable()),
+        );
 
-            let returned_value = [
-                Constraint::<F>::from(comparison_value),
-                Constraint::<F>::empty(),
-            ];
+        let pc = Register([Num::Var(new_pc_low), Num::Var(new_pc_high)]);
 
-            if exec_flag.get_value(cs).unwrap_or(false) {
-                println!("CONDITIONAL");
-                dbg!(src1.get_register().get_value_unsigned(cs));
-                dbg!(src2.get_register().get_value_unsigned(cs));
-                dbg!(pc.get_value_unsigned(cs));
-                dbg!(jump_offset.get_value_unsigned(cs));
-                dbg!(pc_next.get_value_unsigned(cs));
-                dbg!(cs.get_value(should_jump));
-                dbg!(cs.get_value(comparison_value));
-                dbg!(true_jmp_address.get_value_unsigned(cs));
-            }
+        let returned_value = [
+            Constraint::<F>::from(comparison_value),
+            Constraint::<F>::empty(),
+        ];
 
-            CommonDiffs {
-                exec_flag: exec_flag,
-                trapped: None,
-                trap_reason: None,
-                rd_value: vec![(returned_value, exec_flag)],
-                new_pc_value: NextPcValue::Custom(pc),
-            }
+        if exec_flag.get_value(cs).unwrap_or(false) {
+            println!("CONDITIONAL");
+            dbg!(src1.get_register().get_value_unsigned(cs));
+            dbg!(src2.get_register().get_value_unsigned(cs));
+            dbg!(pc.get_value_unsigned(cs));
+            dbg!(jump_offset.get_value_unsigned(cs));
+            dbg!(pc_next.get_value_unsigned(cs));
+            dbg!(cs.get_value(should_jump));
+            dbg!(cs.get_value(comparison_value));
+            dbg!(true_jmp_address.get_value_unsigned(cs));
+        }
+
+        CommonDiffs {
+            exec_flag: exec_flag,
+            trapped,
+            trap_reason,
+            rd_value: vec![(returned_value, exec_flag)],
+            new_pc_value: NextPcValue::Custom(pc),
         }
     }
 }
@@ -4,10 +4,11 @@ pub const JUMP_COMMON_OP_KEY: DecoderMajorInstructionFamilyKey =
     DecoderMajorInstructionFamilyKey("JUMP_COMMON_KEY");
 pub const JAL_OP_KEY: DecoderInstructionVariantsKey = DecoderInstructionVariantsKey("JAL");
 
+/// JAL and JALR. If `SUPPORT_COMPRESSED` is set, then destination only needs to be 2-byte aligned
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct JumpOp;
+pub struct JumpOp<const SUPPORT_COMPRESSED: bool>;
 
-impl DecodableMachineOp for JumpOp {
+impl<const SUPPORT_COMPRESSED: bool> DecodableMachineOp for JumpOp<SUPPORT_COMPRESSED> {
     fn define_decoder_subspace(
         &self,
         opcode: u8,
@@ -47,7 +48,8 @@ impl<
         RS: RegisterValueSource<F>,
         DE: DecoderOutputSource<F, RS>,
         BS: IndexableBooleanSet,
-    > MachineOp<F, ST, RS, DE, BS> for JumpOp
+        const SUPPORT_COMPRESSED: bool,
+    > MachineOp<F, ST, RS, DE, BS> for JumpOp<SUPPORT_COMPRESSED>
 {
     fn define_used_tables() -> Vec<TableType> {
         vec![TableType::JumpCleanupOffset]
@@ -92,8 +94,11 @@ impl<
             );
             let is_misaligned_addr = bit_1;
 
-            // if we have misasigned jump then we should make it unprovable circuit
-            cs.add_constraint(Term::from(is_misaligned_addr) * exec_flag.get_terms());
+            // if we have misasigned jump then we should make it unprovable circuit.
+            // With compressed instructions cleaning the lowest bit is enough
+            if SUPPORT_COMPRESSED == false {
+                cs.add_constraint(Term::from(is_misaligned_addr) * exec_flag.get_terms());
+            }
 
             let dst_low = Num::Var(dst_low);
             let dst_high = x.0[1];
@@ -135,7 +140,11 @@ impl<
                 exec_flag,
             );
             let bit_1 = Boolean::Is(bit_1);
-            let is_misaligned_addr = bit_1;
+            let is_misaligned_addr = if SUPPORT_COMPRESSED {
+                Boolean::Constant(false)
+            } else {
+                bit_1
+            };
 
             let dst_low = Num::Var(dst_low);
             let dst_high = x.0[1];
@@ -177,14 +177,26 @@ impl<const SUPPORT_SIGNED: bool, const SUPPORT_LESS_THAN_WORD: bool>
             };
 
             // check alignment in case of subword accesses
-            if ASSUME_TRUSTED_CODE {
+            let trapped = if ASSUME_TRUSTED_CODE {
                 // unprovable if we do not have proper alignment
                 cs.add_constraint((Term::from(bit_0) + Term::from(bit_1)) * exec_word.get_terms());
 
                 cs.add_constraint(Term::from(bit_0) * exec_half_word.get_terms());
+
+                None
             } else {
-                todo!();
-            }
+                // misaligned access is a trap instead. We still perform a formal read at the aligned address,
+                // and the writeback will not update RD in this case
+                let any_bit_set = cs.add_variable_from_constraint(
+                    Term::from(bit_0) + Term::from(bit_1) - Term::from(bit_0) * Term::from(bit_1),
+                );
+                let trapped = cs.add_variable_from_constraint(
+                    Term::from(any_bit_set) * exec_word.get_terms()
+                        + Term::from(bit_0) * exec_half_word.get_terms(),
+                );
+
+                Some(Boolean::Is(trapped))
+            };
 
             // NOTE: we do NOT cast presumable bits to booleans, as it's under conditional assignment of lookup
 
@@ -354,33 +366,31 @@ impl<const SUPPORT_SIGNED: bool, const SUPPORT_LESS_THAN_WORD: bool>
                 Term::from(address[1]) * (Term::from(1u64) - Term::from(execute_family)),
             );
 
-            if ASSUME_TRUSTED_CODE {
-                CommonDiffs {
-                    exec_flag: execute_family,
-                    trapped: None,
-                    trap_reason: None,
-                    rd_value: vec![
-                        (
-                            [
-                                Constraint::from(rom_value_low),
-                                Constraint::from(rom_value_high),
-                            ],
-                            Boolean::Is(is_rom_read),
-                        ),
-                        (
-                            [
-                                Constraint::from(ram_value_low),
-                                Constraint::from(ram_value_high),
-                            ],
-                            Boolean::Is(is_ram_read),
-                        ),
-                    ],
-                    new_pc_value: NextPcValue::Default,
-                }
-            } else {
-                // we trap if misaligned access that can happen in untrusted code
-
-                todo!();
+            CommonDiffs {
+                exec_flag: execute_family,
+                trapped,
+                trap_reason: trapped.map(|_| {
+                    Num::Constant(F::from_u64_unchecked(
+                        TrapReason::LoadAddressMisaligned as u64,
+                    ))
+                }),
+                rd_value: vec![
+                    (
+                        [
+                            Constraint::from(rom_value_low),
+                            Constraint::from(rom_value_high),
+                        ],
+                        Boolean::Is(is_rom_read),
+                    ),
+                    (
+                        [
+                            Constraint::from(ram_value_low),
+                            Constraint::from(ram_value_high),
+                        ],
+                        Boolean::Is(is_ram_read),
+                    ),
+                ],
+                new_pc_value: NextPcValue::Default,
             }
         } else {
             // support only LW, and so we assume code is trusted
@@ -0,0 +1,171 @@
+use super::*;
+
+pub const MIN_MAX_COMMON_OP_KEY: DecoderMajorInstructionFamilyKey =
+    DecoderMajorInstructionFamilyKey("MIN_MAX_COMMON_KEY");
+pub const MIN_MAX_UNSIGNED_KEY: DecoderInstructionVariantsKey =
+    DecoderInstructionVariantsKey("MINU/MAXU");
+pub const MIN_MAX_IS_MAX_KEY: DecoderInstructionVariantsKey =
+    DecoderInstructionVariantsKey("MAX/MAXU");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinMaxOp;
+
+impl DecodableMachineOp for MinMaxOp {
+    fn define_decoder_subspace(
+        &self,
+        opcode: u8,
+        func3: u8,
+        func7: u8,
+    ) -> Result<
+        (
+            InstructionType,
+            DecoderMajorInstructionFamilyKey,
+            &'static [DecoderInstructionVariantsKey],
+        ),
+        (),
+    > {
+        let params = match (opcode, func3, func7) {
+            (OPERATION_OP, 0b100, 0b000_0101) => {
+                // MIN
+                (InstructionType::RType, MIN_MAX_COMMON_OP_KEY, &[][..])
+            }
+            (OPERATION_OP, 0b101, 0b000_0101) => {
+                // MINU
+                (
+                    InstructionType::RType,
+                    MIN_MAX_COMMON_OP_KEY,
+                    &[MIN_MAX_UNSIGNED_KEY][..],
+                )
+            }
+            (OPERATION_OP, 0b110, 0b000_0101) => {
+                // MAX
+                (
+                    InstructionType::RType,
+                    MIN_MAX_COMMON_OP_KEY,
+                    &[MIN_MAX_IS_MAX_KEY][..],
+                )
+            }
+            (OPERATION_OP, 0b111, 0b000_0101) => {
+                // MAXU
+                (
+                    InstructionType::RType,
+                    MIN_MAX_COMMON_OP_KEY,
+                    &[MIN_MAX_UNSIGNED_KEY, MIN_MAX_IS_MAX_KEY][..],
+                )
+            }
+            _ => return Err(()),
+        };
+
+        Ok(params)
+    }
+}
+
+impl<
+        F: PrimeField,
+        ST: BaseMachineState<F>,
+        RS: RegisterValueSource<F>,
+        DE: DecoderOutputSource<F, RS>,
+        BS: IndexableBooleanSet,
+    > MachineOp<F, ST, RS, DE, BS> for MinMaxOp
+{
+    fn define_used_tables() -> Vec<TableType> {
+        vec![TableType::ConditionalOpAllConditionsResolver]
+    }
+
+    fn apply<
+        CS: Circuit<F>,
+        const ASSUME_TRUSTED_CODE: bool,
+        const OUTPUT_EXACT_EXCEPTIONS: bool,
+    >(
+        cs: &mut CS,
+        _machine_state: &ST,
+        inputs: &DE,
+        boolean_set: &BS,
+        opt_ctx: &mut OptimizationContext<F, CS>,
+    ) -> CommonDiffs<F> {
+        opt_ctx.reset_indexers();
+        let exec_flag = boolean_set.get_major_flag(MIN_MAX_COMMON_OP_KEY);
+        let is_unsigned = boolean_set.get_minor_flag(MIN_MAX_COMMON_OP_KEY, MIN_MAX_UNSIGNED_KEY);
+        let is_max = boolean_set.get_minor_flag(MIN_MAX_COMMON_OP_KEY, MIN_MAX_IS_MAX_KEY);
+
+        let src1 = inputs.get_rs1_or_equivalent();
+        let src2 = inputs.get_rs2_or_equivalent();
+
+        // comparison is the same as for SLT/SLTU, so we resolve it via the same table
+        let (diff, uf_flag) =
+            opt_ctx.append_sub_relation(src1.get_register(), src2.get_register(), exec_flag, cs);
+        let eq_flag = opt_ctx.append_is_zero_relation(diff, exec_flag, cs);
+
+        let src1_sign_bit = src1.get_sign_bit().unwrap();
+        let src2_sign_bit = src2.get_sign_bit().unwrap();
+
+        // funct3 of SLT is 0b010 and of SLTU is 0b011
+        let key_constraint = Constraint::<F>::from(0b010u64)
+            + Term::from(is_unsigned)
+            + Term::from((
+                F::from_u64_unchecked(1u64 << 3),
+                uf_flag.get_variable().unwrap(),
+            ))
+            + Term::from((
+                F::from_u64_unchecked(1u64 << (3 + 1)),
+                eq_flag.get_variable().unwrap(),
+            ))
+            + Term::from((
+                F::from_u64_unchecked(1u64 << (3 + 1 + 1)),
+                src1_sign_bit.get_variable().unwrap(),
+            ))
+            + Term::from((
+                F::from_u64_unchecked(1u64 << (3 + 1 + 1 + 1)),
+                src2_sign_bit.get_variable().unwrap(),
+            ));
+
+        let [_, src1_is_less] = opt_ctx.append_lookup_relation_from_linear_terms::<1, 2>(
+            cs,
+            &[key_constraint],
+            TableType::ConditionalOpAllConditionsResolver.to_num(),
+            exec_flag,
+        );
+
+        // MIN takes src1 if it's less, and MAX takes src1 if it's not less (in case of equality either choice is valid)
+        let take_src1 = cs.add_variable_from_constraint(
+            Term::from(src1_is_less) + Term::from(is_max)
+                - Term::from(2) * Term::from(src1_is_less) * Term::from(is_max),
+        );
+
+        let src1_register = src1.get_register();
+        let src2_register = src2.get_register();
+
+        let selected_low = cs.add_variable_from_constraint(
+            Constraint::from(src2_register.0[0])
+                + Term::from(take_src1)
+                    * (Term::from(src1_register.0[0]) - Term::from(src2_register.0[0])),
+        );
+        let selected_high = cs.add_variable_from_constraint(
+            Constraint::from(src2_register.0[1])
+                + Term::from(take_src1)
+                    * (Term::from(src1_register.0[1]) - Term::from(src2_register.0[1])),
+        );
+
+        if exec_flag.get_value(cs).unwrap_or(false) {
+            println!("MIN/MAX");
+            dbg!(src1.get_register().get_value_unsigned(cs));
+            dbg!(src2.get_register().get_value_unsigned(cs));
+            dbg!(is_unsigned.get_value(cs));
+            dbg!(is_max.get_value(cs));
+            dbg!(cs.get_value(take_src1));
+        }
+
+        let returned_value = [
+            Constraint::<F>::from(selected_low),
+            Constraint::<F>::from(selected_high),
+        ];
+
+        CommonDiffs {
+            exec_flag,
+            trapped: None,
+            trap_reason: None,
+            rd_value: vec![(returned_value, exec_flag)],
+            new_pc_value: NextPcValue::Default,
+        }
+    }
+}
@@ -2,17 +2,22 @@ use super::*;
 
 pub mod add_sub;
 pub mod binops;
+pub mod byte_ops;
 pub mod conditional;
 pub mod constants;
 pub mod csr;
 pub mod jump;
 pub mod lui_auipc;
+pub mod min_max;
 // pub mod memory;
 pub mod load;
 pub mod mop;
 pub mod mul_div;
+pub mod pack;
 pub mod shift;
 pub mod store;
+pub mod system;
+pub mod unary_bitmanip;
 
 pub mod common_impls;
 
@@ -22,17 +27,22 @@ pub const RD_STORE_LOCAL_TIMESTAMP: usize = 2;
 
 pub use self::add_sub::*;
 pub use self::binops::*;
+pub use self::byte_ops::*;
 pub use self::conditional::*;
 pub use self::constants::*;
 pub use self::csr::*;
 pub use self::jump::*;
 pub use self::lui_auipc::*;
+pub use self::min_max::*;
 // pub use self::memory::*;
 pub use self::load::*;
 pub use self::mop::*;
 pub use self::mul_div::*;
+pub use self::pack::*;
 pub use self::shift::*;
 pub use self::store::*;
+pub use self::system::*;
+pub use self::unary_bitmanip::*;
 
 pub use self::common_impls::*;
 
@@ -0,0 +1,121 @@
+use super::*;
+
+pub const PACK_COMMON_OP_KEY: DecoderMajorInstructionFamilyKey =
+    DecoderMajorInstructionFamilyKey("PACK_COMMON_KEY");
+pub const PACK_H_KEY: DecoderInstructionVariantsKey = DecoderInstructionVariantsKey("PACKH");
+
+/// PACK and PACKH from Zbkb. ZEXT.H from Zbb is encoded as PACK with RS2 = x0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PackOp;
+
+impl DecodableMachineOp for PackOp {
+    fn define_decoder_subspace(
+        &self,
+        opcode: u8,
+        func3: u8,
+        func7: u8,
+    ) -> Result<
+        (
+            InstructionType,
+            DecoderMajorInstructionFamilyKey,
+            &'static [DecoderInstructionVariantsKey],
+        ),
+        (),
+    > {
+        let params = match (opcode, func3, func7) {
+            (OPERATION_OP, 0b100, 0b000_0100) => {
+                // PACK
+                (InstructionType::RType, PACK_COMMON_OP_KEY, &[][..])
+            }
+            (OPERATION_OP, 0b111, 0b000_0100) => {
+                // PACKH
+                (
+                    InstructionType::RType,
+                    PACK_COMMON_OP_KEY,
+                    &[PACK_H_KEY][..],
+                )
+            }
+            _ => return Err(()),
+        };
+
+        Ok(params)
+    }
+}
+
+impl<
+        F: PrimeField,
+        ST: BaseMachineState<F>,
+        RS: RegisterValueSource<F>,
+        DE: DecoderOutputSource<F, RS>,
+        BS: IndexableBooleanSet,
+    > MachineOp<F, ST, RS, DE, BS> for PackOp
+{
+    fn define_used_tables() -> Vec<TableType> {
+        vec![TableType::U16SplitAsBytes]
+    }
+
+    fn apply<
+        CS: Circuit<F>,
+        const ASSUME_TRUSTED_CODE: bool,
+        const OUTPUT_EXACT_EXCEPTIONS: bool,
+    >(
+        cs: &mut CS,
+        _machine_state: &ST,
+        inputs: &DE,
+        boolean_set: &BS,
+        opt_ctx: &mut OptimizationContext<F, CS>,
+    ) -> CommonDiffs<F> {
+        opt_ctx.reset_indexers();
+        let exec_flag = boolean_set.get_major_flag(PACK_COMMON_OP_KEY);
+        let is_packh = boolean_set.get_minor_flag(PACK_COMMON_OP_KEY, PACK_H_KEY);
+
+        let src1 = inputs.get_rs1_or_equivalent().get_register();
+        let src2 = inputs.get_rs2_or_equivalent().get_register();
+
+        // PACK just places low half-words of the sources into the result, and PACKH needs
+        // low bytes, that we get via lookup (as byte decomposition of registers is not range checked)
+        let [src1_low_byte, _] = opt_ctx.append_lookup_relation_from_linear_terms::<1, 2>(
+            cs,
+            &[Constraint::from(src1.0[0])],
+            TableType::U16SplitAsBytes.to_num(),
+            exec_flag,
+        );
+        let [src2_low_byte, _] = opt_ctx.append_lookup_relation_from_linear_terms::<1, 2>(
+            cs,
+            &[Constraint::from(src2.0[0])],
+            TableType::U16SplitAsBytes.to_num(),
+            exec_flag,
+        );
+
+        let result_low = cs.add_variable_from_constraint(
+            Constraint::from(src1.0[0])
+                + Term::from(is_packh)
+                    * (Term::from(src1_low_byte)
+                        + Term::from((F::from_u64_unchecked(1 << 8), src2_low_byte))
+                        - Term::from(src1.0[0])),
+        );
+        let result_high = cs.add_variable_from_constraint(
+            (Term::from(1) - Term::from(is_packh)) * Term::from(src2.0[0]),
+        );
+
+        if exec_flag.get_value(cs).unwrap_or(false) {
+            println!("PACK");
+            dbg!(src1.get_value_unsigned(cs));
+            dbg!(src2.get_value_unsigned(cs));
+            dbg!(is_packh.get_value(cs));
+        }
+
+        let returned_value = [
+            Constraint::<F>::from(result_low),
+            Constraint::<F>::from(result_high),
+        ];
+
+        CommonDiffs {
+            exec_flag,
+            trapped: None,
+            trap_reason: None,
+            rd_value: vec![(returned_value, exec_flag)],
+            new_pc_value: NextPcValue::Default,
+        }
+    }
+}
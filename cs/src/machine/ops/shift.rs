@@ -153,12 +153,21 @@ impl<
             .low_word_unconstrained_decomposition
             .0;
 
+        // rotations are implemented as 4-bit rotations of the half-words followed by optional swap of them,
+        // so we truncate the shift amount to 4 bits in this case
+        let shift_amount_mask = if SUPPORT_ROT {
+            let is_cyclic = boolean_set.get_minor_flag(SHIFT_COMMON_OP_KEY, SHIFT_CYCLIC_KEY);
+            Constraint::from(0b1_1111) - (Term::from(1 << 4) * Term::from(is_cyclic))
+        } else {
+            Constraint::from(0b1_1111)
+        };
+
         // This will truncate the shift
         let [shift_amount_to_use] = opt_ctx.append_lookup_relation_from_linear_terms::<2, 1>(
             cs,
             &[
                 Constraint::from(shift_amount_low_byte),
-                Constraint::from(0b1_1111), // truncate to 5 bits
+                shift_amount_mask, // truncate to 5 (or 4 for rotations) bits
             ],
             TableType::And.to_num(),
             exec_flag,
@@ -261,7 +270,99 @@ impl<
                 new_pc_value: NextPcValue::Default,
             }
         } else {
-            todo!();
+            let is_cyclic = boolean_set.get_minor_flag(SHIFT_COMMON_OP_KEY, SHIFT_CYCLIC_KEY);
+
+            // if we rotate by 16 or more, then we rotate by the lowest 4 bits and swap half-words. For non-cyclic
+            // shifts the swap contribution is always 0, and shift amount is 5 bits
+            let [swap_contribution] = opt_ctx.append_lookup_relation_from_linear_terms::<2, 1>(
+                cs,
+                &[
+                    Constraint::from(shift_amount_low_byte),
+                    Term::from(1 << 4) * Term::from(is_cyclic),
+                ],
+                TableType::And.to_num(),
+                exec_flag,
+            );
+            let swap_halves = cs.add_variable_from_constraint_allow_explicit_linear(
+                Constraint::from(Term::from((
+                    F::from_u64_unchecked(1 << 4).inverse().unwrap(),
+                    swap_contribution,
+                ))),
+            );
+
+            let [low_in_place, shifted_from_low_place] = opt_ctx
+                .append_lookup_relation_from_linear_terms::<1, 2>(
+                    cs,
+                    &[Constraint::from(input.0[0])
+                        + (Term::from(1 << 16) * Term::from(shift_amount_to_use))
+                        + (Term::from(1 << 21) * Term::from(is_right_shift))],
+                    TableType::ShiftImplementation.to_num(),
+                    exec_flag,
+                );
+
+            let [high_in_place, shifted_from_high_place] = opt_ctx
+                .append_lookup_relation_from_linear_terms::<1, 2>(
+                    cs,
+                    &[Constraint::from(input.0[1])
+                        + (Term::from(1 << 16) * Term::from(shift_amount_to_use))
+                        + (Term::from(1 << 21) * Term::from(is_right_shift))],
+                    TableType::ShiftImplementation.to_num(),
+                    exec_flag,
+                );
+
+            // Right shifts (and ROR) take bits that leave the high half-word into the low one,
+            // and left shifts (and ROL) do it in the opposite direction. Rotations additionally wrap bits that leave
+            // the full word around, so both overflows are used. Note that for shift amounts below 16
+            // ROR and ROL both end up with the same formulas, as overflows are placed by the table
+            let take_overflow_from_high = cs.add_variable_from_constraint(
+                Term::from(is_right_shift) + Term::from(is_cyclic)
+                    - Term::from(is_right_shift) * Term::from(is_cyclic),
+            );
+            let low_unswapped = cs.add_variable_from_constraint(
+                Constraint::from(low_in_place)
+                    + Term::from(take_overflow_from_high) * Term::from(shifted_from_high_place),
+            );
+            let high_unswapped = cs.add_variable_from_constraint(
+                Constraint::from(high_in_place)
+                    + (Term::from(1) + Term::from(is_cyclic) - Term::from(take_overflow_from_high))
+                        * Term::from(shifted_from_low_place),
+            );
+
+            let selected_low = cs.add_variable_from_constraint(
+                Constraint::from(low_unswapped)
+                    + Term::from(swap_halves)
+                        * (Term::from(high_unswapped) - Term::from(low_unswapped)),
+            );
+            // and high is just what is left
+            let selected_high =
+                Term::from(low_unswapped) + Term::from(high_unswapped) - Term::from(selected_low);
+
+            let mut returned_value = [Constraint::from(selected_low), selected_high];
+
+            if SUPPORT_SRA {
+                let is_sra =
+                    boolean_set.get_minor_flag(SHIFT_COMMON_OP_KEY, SHIFT_RIGHT_ALGEBRAIC_KEY);
+                let [sra_filler_low, sra_filler_high] = opt_ctx
+                    .append_lookup_relation_from_linear_terms::<1, 2>(
+                        cs,
+                        &[Constraint::from(input_sign)
+                            + (Term::from(1 << 1) * Term::from(is_sra))
+                            + (Term::from(1 << 2) * Term::from(shift_amount_to_use))],
+                        TableType::SRASignFiller.to_num(),
+                        exec_flag,
+                    );
+
+                returned_value[0] = returned_value[0].clone() + Term::from(sra_filler_low);
+                returned_value[1] = returned_value[1].clone() + Term::from(sra_filler_high);
+            }
+
+            CommonDiffs {
+                exec_flag,
+                trapped: None,
+                trap_reason: None,
+                rd_value: vec![(returned_value, exec_flag)],
+                new_pc_value: NextPcValue::Default,
+            }
         }
     }
 }
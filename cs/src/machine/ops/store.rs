@@ -153,14 +153,24 @@ impl<const SUPPORT_LESS_THAN_WORD: bool> StoreOp<SUPPORT_LESS_THAN_WORD> {
             };
 
             // check alignment in case of subword accesses
-            if ASSUME_TRUSTED_CODE {
+            let is_misaligned = if ASSUME_TRUSTED_CODE {
                 // unprovable if we do not have proper alignment
                 cs.add_constraint((Term::from(bit_0) + Term::from(bit_1)) * exec_word.get_terms());
 
                 cs.add_constraint(Term::from(bit_0) * exec_half_word.get_terms());
+
+                None
             } else {
-                todo!();
-            }
+                let any_bit_set = cs.add_variable_from_constraint(
+                    Term::from(bit_0) + Term::from(bit_1) - Term::from(bit_0) * Term::from(bit_1),
+                );
+                let is_misaligned = cs.add_variable_from_constraint(
+                    Term::from(any_bit_set) * exec_word.get_terms()
+                        + Term::from(bit_0) * exec_half_word.get_terms(),
+                );
+
+                Some(is_misaligned)
+            };
 
             // NOTE: we do NOT cast presumable bits to booleans, as it's under conditional assignment of lookup
 
@@ -178,15 +188,31 @@ impl<const SUPPORT_LESS_THAN_WORD: bool> StoreOp<SUPPORT_LESS_THAN_WORD> {
             );
 
             // we can not write into ROM
-            if ASSUME_TRUSTED_CODE {
+            let trapped = if let Some(is_misaligned) = is_misaligned {
+                // misaligned address has priority over access fault, and in both cases
+                // we trap and leave memory untouched
+                let is_access_fault = cs.add_variable_from_constraint(
+                    (Term::from(execute_family) - Term::from(is_misaligned))
+                        * (Term::from(1) - Term::from(is_ram_range)),
+                );
+                let trapped = cs.add_variable_from_constraint_allow_explicit_linear(
+                    Term::from(is_misaligned) + Term::from(is_access_fault),
+                );
+                // StoreOrAMOAddressMisaligned = 6, StoreOrAMOAccessFault = 7
+                let trap_reason = cs.add_variable_from_constraint_allow_explicit_linear(
+                    Term::from(is_access_fault)
+                        + Term::from(TrapReason::StoreOrAMOAddressMisaligned as u64),
+                );
+
+                Some((trapped, trap_reason))
+            } else {
                 // NOTE: `should_write_mem` always conditioned over execution of the opcode itself
                 cs.add_constraint(
                     execute_family.get_terms() * (Term::from(1) - Term::from(is_ram_range)),
                 );
-            } else {
-                // we should trap maybe
-                todo!()
-            }
+
+                None
+            };
 
             let base_value = rd_or_mem_store_query.read_value;
             // NOTE: here it's yes unconstrained byte, but if we take this branch - it becomes constrained
@@ -233,16 +259,45 @@ impl<const SUPPORT_LESS_THAN_WORD: bool> StoreOp<SUPPORT_LESS_THAN_WORD> {
             );
 
             // constraint written values
+            let (word_write_mask, subword_write_mask) = if let Some((trapped, _)) = trapped {
+                let word_write_mask = cs.add_variable_from_constraint(
+                    Term::from(exec_word) * (Term::from(1) - Term::from(trapped)),
+                );
+                let subword_write_mask = cs.add_variable_from_constraint(
+                    (Term::from(execute_family) - Term::from(exec_word))
+                        * (Term::from(1) - Term::from(trapped)),
+                );
+
+                // trapped store formally writes back the value that was read
+                for (read, write) in base_value
+                    .iter()
+                    .zip(rd_or_mem_store_query.write_value.iter())
+                {
+                    cs.add_constraint(
+                        (Term::from(*write) - Term::from(*read)) * Term::from(trapped),
+                    );
+                }
+
+                (
+                    Constraint::from(word_write_mask),
+                    Constraint::from(subword_write_mask),
+                )
+            } else {
+                (
+                    Constraint::from(exec_word),
+                    Term::from(execute_family) - Term::from(exec_word),
+                )
+            };
 
             // if we store full word, then it's just src2
             let word_to_store = src2.get_register();
             cs.add_constraint(
                 (Term::from(word_to_store.0[0]) - Term::from(rd_or_mem_store_query.write_value[0]))
-                    * Term::from(exec_word),
+                    * word_write_mask.clone(),
             );
             cs.add_constraint(
                 (Term::from(word_to_store.0[1]) - Term::from(rd_or_mem_store_query.write_value[1]))
-                    * Term::from(exec_word),
+                    * word_write_mask,
             );
 
             // otherwise we have to properly shuffle and constraint
@@ -265,11 +320,11 @@ impl<const SUPPORT_LESS_THAN_WORD: bool> StoreOp<SUPPORT_LESS_THAN_WORD> {
             );
             cs.add_constraint(
                 (Term::from(selected_low) - Term::from(rd_or_mem_store_query.write_value[0]))
-                    * (Term::from(execute_family) - Term::from(exec_word)),
+                    * subword_write_mask.clone(),
             );
             cs.add_constraint(
                 (Term::from(selected_high) - Term::from(rd_or_mem_store_query.write_value[1]))
-                    * (Term::from(execute_family) - Term::from(exec_word)),
+                    * subword_write_mask,
             );
 
             let ShuffleRamQueryType::RegisterOrRam { is_register, .. } =
@@ -284,18 +339,12 @@ impl<const SUPPORT_LESS_THAN_WORD: bool> StoreOp<SUPPORT_LESS_THAN_WORD> {
             // here we do not need to constraint address if case if we did NOT perform write,
             // as we anyway expect a writeback to be performed
 
-            if ASSUME_TRUSTED_CODE {
-                CommonDiffs {
-                    exec_flag: execute_family,
-                    trapped: None,
-                    trap_reason: None,
-                    rd_value: vec![],
-                    new_pc_value: NextPcValue::Default,
-                }
-            } else {
-                // we trap if misaligned access that can happen in untrusted code
-
-                todo!();
+            CommonDiffs {
+                exec_flag: execute_family,
+                trapped: trapped.map(|(trapped, _)| Boolean::Is(trapped)),
+                trap_reason: trapped.map(|(_, trap_reason)| Num::Var(trap_reason)),
+                rd_value: vec![],
+                new_pc_value: NextPcValue::Default,
             }
         } else {
             // support only SW/LW, and so we assume code is trusted
@@ -0,0 +1,125 @@
+use super::*;
+use crate::machine::machine_configurations::trap_handling_state::MEPC_CSR_INDEX;
+
+pub const SYSTEM_COMMON_OP_KEY: DecoderMajorInstructionFamilyKey =
+    DecoderMajorInstructionFamilyKey("SYSTEM_COMMON_KEY");
+pub const MRET_OP_KEY: DecoderInstructionVariantsKey = DecoderInstructionVariantsKey("MRET");
+
+const ECALL_FUNCT12: u64 = 0x000;
+const EBREAK_FUNCT12: u64 = 0x001;
+const MRET_FUNCT12: u64 = 0x302;
+
+/// ECALL, EBREAK and MRET for machines that vector traps to `mtvec`. None of these write into RD,
+/// so we decode them as B-type, same as invalid opcodes. Reserved RS1/RD fields are not checked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SystemOp;
+
+impl DecodableMachineOp for SystemOp {
+    fn define_decoder_subspace(
+        &self,
+        opcode: u8,
+        func3: u8,
+        func7: u8,
+    ) -> Result<
+        (
+            InstructionType,
+            DecoderMajorInstructionFamilyKey,
+            &'static [DecoderInstructionVariantsKey],
+        ),
+        (),
+    > {
+        let params = match (opcode, func3, func7) {
+            (OPERATION_SYSTEM, 0b000, 0b0000000) => {
+                // ECALL/EBREAK
+                (InstructionType::BType, SYSTEM_COMMON_OP_KEY, &[][..])
+            }
+            (OPERATION_SYSTEM, 0b000, 0b0011000) => {
+                // MRET
+                (
+                    InstructionType::BType,
+                    SYSTEM_COMMON_OP_KEY,
+                    &[MRET_OP_KEY][..],
+                )
+            }
+            _ => return Err(()),
+        };
+
+        Ok(params)
+    }
+}
+
+impl<
+        F: PrimeField,
+        ST: BaseMachineState<F>,
+        RS: RegisterValueSource<F>,
+        DE: DecoderOutputSource<F, RS>,
+        BS: IndexableBooleanSet,
+    > MachineOp<F, ST, RS, DE, BS> for SystemOp
+{
+    fn apply<
+        CS: Circuit<F>,
+        const ASSUME_TRUSTED_CODE: bool,
+        const OUTPUT_EXACT_EXCEPTIONS: bool,
+    >(
+        cs: &mut CS,
+        machine_state: &ST,
+        inputs: &DE,
+        boolean_set: &BS,
+        opt_ctx: &mut OptimizationContext<F, CS>,
+    ) -> CommonDiffs<F> {
+        assert!(
+            ASSUME_TRUSTED_CODE == false,
+            "trusted code can not use ECALL/EBREAK/MRET"
+        );
+
+        opt_ctx.reset_indexers();
+        let exec_flag = boolean_set.get_major_flag(SYSTEM_COMMON_OP_KEY);
+        let is_mret = boolean_set.get_minor_flag(SYSTEM_COMMON_OP_KEY, MRET_OP_KEY);
+        let exec_mret = Boolean::and(&exec_flag, &is_mret, cs);
+
+        let mepc = *machine_state
+            .all_csrs()
+            .get(&MEPC_CSR_INDEX)
+            .expect("machine state must contain trap handling CSRs");
+
+        // funct12 is the only thing that distinguishes the variants
+        let funct12 = inputs.funct12();
+        let [is_ecall, is_ebreak, is_mret_encoding] = [ECALL_FUNCT12, EBREAK_FUNCT12, MRET_FUNCT12]
+            .map(|encoding| {
+                let diff = cs.add_variable_from_constraint_allow_explicit_linear(
+                    funct12.clone() - Term::from(encoding),
+                );
+                cs.is_zero(Num::Var(diff))
+            });
+
+        // everything except valid MRET is a trap, either ECALL/EBREAK, or illegal instruction
+        let trapped = cs.add_variable_from_constraint(
+            Constraint::from(exec_flag) - Term::from(exec_mret) * Term::from(is_mret_encoding),
+        );
+
+        // EnvironmentCallFromMMode = 11, Breakpoint = 3, IllegalInstruction = 2
+        let trap_reason = cs.add_variable_from_constraint_allow_explicit_linear(
+            Term::from(TrapReason::IllegalInstruction as u64)
+                + Term::from((F::from_u64_unchecked(9), is_ecall.get_variable().unwrap()))
+                + Term::from(is_ebreak),
+        );
+
+        if exec_flag.get_value(cs).unwrap_or(false) {
+            println!("SYSTEM");
+            if cs.get_value(trapped).unwrap() == F::ONE {
+                dbg!(cs.get_value(trap_reason));
+            } else {
+                dbg!(mepc.get_value_unsigned(cs));
+            }
+        }
+
+        CommonDiffs {
+            exec_flag,
+            trapped: Some(Boolean::Is(trapped)),
+            trap_reason: Some(Num::Var(trap_reason)),
+            rd_value: vec![],
+            // if we do not trap then it's MRET. Otherwise PC is overwritten by the trap vector anyway
+            new_pc_value: NextPcValue::Custom(mepc),
+        }
+    }
+}
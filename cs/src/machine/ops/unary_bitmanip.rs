@@ -0,0 +1,141 @@
+use super::*;
+
+pub const UNARY_BITMANIP_COMMON_OP_KEY: DecoderMajorInstructionFamilyKey =
+    DecoderMajorInstructionFamilyKey("UNARY_BITMANIP_COMMON_KEY");
+
+// CLZ/CTZ/CPOP/SEXT.B/SEXT.H share opcode, funct3 and funct7, and are only distinguished by the
+// rs2 field, that is a part of the immediate
+const UNARY_BITMANIP_IMM_BASE: u64 = 0b0110000_00000;
+const SIGN_EXTEND_SELECTOR_BIT: u64 = 0b100;
+
+/// Zbb's count leading/trailing zeroes, population count and sign extensions. All of them are
+/// resolved by looking up both half-words in the BitCountAndSignExtend table with a selector taken
+/// from the immediate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnaryBitmanipOp;
+
+impl DecodableMachineOp for UnaryBitmanipOp {
+    fn define_decoder_subspace(
+        &self,
+        opcode: u8,
+        func3: u8,
+        func7: u8,
+    ) -> Result<
+        (
+            InstructionType,
+            DecoderMajorInstructionFamilyKey,
+            &'static [DecoderInstructionVariantsKey],
+        ),
+        (),
+    > {
+        let params = match (opcode, func3, func7) {
+            (OPERATION_OP_IMM, 0b001, 0b011_0000) => {
+                // CLZ/CTZ/CPOP/SEXT.B/SEXT.H
+                (
+                    InstructionType::IType,
+                    UNARY_BITMANIP_COMMON_OP_KEY,
+                    &[][..],
+                )
+            }
+            _ => return Err(()),
+        };
+
+        Ok(params)
+    }
+}
+
+impl<
+        F: PrimeField,
+        ST: BaseMachineState<F>,
+        RS: RegisterValueSource<F>,
+        DE: DecoderOutputSource<F, RS>,
+        BS: IndexableBooleanSet,
+    > MachineOp<F, ST, RS, DE, BS> for UnaryBitmanipOp
+{
+    fn define_used_tables() -> Vec<TableType> {
+        vec![TableType::And, TableType::BitCountAndSignExtend]
+    }
+
+    fn apply<
+        CS: Circuit<F>,
+        const ASSUME_TRUSTED_CODE: bool,
+        const OUTPUT_EXACT_EXCEPTIONS: bool,
+    >(
+        cs: &mut CS,
+        _machine_state: &ST,
+        inputs: &DE,
+        boolean_set: &BS,
+        opt_ctx: &mut OptimizationContext<F, CS>,
+    ) -> CommonDiffs<F> {
+        opt_ctx.reset_indexers();
+        let exec_flag = boolean_set.get_major_flag(UNARY_BITMANIP_COMMON_OP_KEY);
+
+        let src1 = inputs.get_rs1_or_equivalent();
+        let imm = inputs.get_imm();
+
+        let input = src1.get_register();
+
+        // NOTE: we take the selector from the full low half-word of the immediate and not from
+        // the unconstrained byte decomposition of it
+        let selector = Constraint::<F>::from(imm.0[0]) - Term::from(UNARY_BITMANIP_IMM_BASE);
+
+        // SEXT.B and SEXT.H are the only ones that have a non-trivial high word of the result
+        let [sign_extend_bit] = opt_ctx.append_lookup_relation_from_linear_terms::<2, 1>(
+            cs,
+            &[selector.clone(), Constraint::from(SIGN_EXTEND_SELECTOR_BIT)],
+            TableType::And.to_num(),
+            exec_flag,
+        );
+
+        let [low_first, low_second] = opt_ctx.append_lookup_relation_from_linear_terms::<1, 2>(
+            cs,
+            &[Constraint::from(input.0[0]) + selector.clone() * Term::from(1 << 16)],
+            TableType::BitCountAndSignExtend.to_num(),
+            exec_flag,
+        );
+        let [high_first, high_second] = opt_ctx.append_lookup_relation_from_linear_terms::<1, 2>(
+            cs,
+            &[Constraint::from(input.0[1])
+                + selector * Term::from(1 << 16)
+                + Term::from(1 << (16 + 3))],
+            TableType::BitCountAndSignExtend.to_num(),
+            exec_flag,
+        );
+
+        // see the table description for the layout of the outputs
+        let result_low = cs.add_variable_from_constraint(
+            Term::from(low_first)
+                + Term::from(high_first)
+                + Term::from(low_second) * Term::from(high_second),
+        );
+        let result_high = cs.add_variable_from_constraint(
+            Term::from((
+                F::from_u64_unchecked(SIGN_EXTEND_SELECTOR_BIT)
+                    .inverse()
+                    .unwrap(),
+                sign_extend_bit,
+            )) * Term::from(low_second),
+        );
+
+        if exec_flag.get_value(cs).unwrap_or(false) {
+            println!("UNARY BITMANIP");
+            dbg!(src1.get_register().get_value_unsigned(cs));
+            dbg!(imm.get_value_unsigned(cs));
+            dbg!(cs.get_value(result_low));
+            dbg!(cs.get_value(result_high));
+        }
+
+        let returned_value = [
+            Constraint::<F>::from(result_low),
+            Constraint::<F>::from(result_high),
+        ];
+
+        CommonDiffs {
+            exec_flag,
+            trapped: None,
+            trap_reason: None,
+            rd_value: vec![(returned_value, exec_flag)],
+            new_pc_value: NextPcValue::Default,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use one_row_compiler::LookupInput;
 
 use super::*;
-use crate::devices::diffs::PC_INC_STEP;
+use crate::devices::diffs::{COMPRESSED_PC_INC_STEP, PC_INC_STEP};
 use crate::tables::*;
 
 pub fn assert_no_unimp<F: PrimeField, C: Circuit<F>>(_cs: &mut C, _next_opcode: Register<F>) {
@@ -54,7 +54,27 @@ pub fn calculate_pc_next_no_overflows<F: PrimeField, CS: Circuit<F>>(
     // Input invariant: PC % 4 == 0, preserved as:
     // - initial PC is valid % 4
     // - jumps and branches check for alignments
+    calculate_pc_next_no_overflows_impl(circuit, pc, None)
+}
 
+/// Same as `calculate_pc_next_no_overflows`, but PC is incremented by 2 if the current
+/// instruction is compressed
+pub fn calculate_pc_next_with_compressed_no_overflows<F: PrimeField, CS: Circuit<F>>(
+    circuit: &mut CS,
+    pc: Register<F>,
+    is_compressed: Boolean,
+) -> Register<F> {
+    // Input invariant: PC % 2 == 0, preserved as:
+    // - initial PC is valid % 4
+    // - jumps and branches check for half-word alignments
+    calculate_pc_next_no_overflows_impl(circuit, pc, Some(is_compressed))
+}
+
+fn calculate_pc_next_no_overflows_impl<F: PrimeField, CS: Circuit<F>>(
+    circuit: &mut CS,
+    pc: Register<F>,
+    is_compressed: Option<Boolean>,
+) -> Register<F> {
     // strategy:
     // - allocate lower part of addition result and ensure that it is 16 bits
     // - do not allocate carry and make sure that (pc_low + 4 - result) >> 16 is boolean
@@ -73,6 +93,13 @@ pub fn calculate_pc_next_no_overflows<F: PrimeField, CS: Circuit<F>>(
     let mut carry_constraint = Constraint::empty();
     carry_constraint += pc_t[0].clone();
     carry_constraint += Term::from(PC_INC_STEP);
+    let is_compressed = is_compressed.map(|el| el.get_variable().unwrap());
+    if let Some(is_compressed) = is_compressed {
+        carry_constraint -= Term::from((
+            F::from_u64_unchecked(PC_INC_STEP - COMPRESSED_PC_INC_STEP),
+            is_compressed,
+        ));
+    }
     carry_constraint -= Term::from(pc_next_low);
     carry_constraint.scale(F::from_u64_unchecked(1 << 16).inverse().unwrap());
 
@@ -106,8 +133,19 @@ pub fn calculate_pc_next_no_overflows<F: PrimeField, CS: Circuit<F>>(
     let value_fn = move |placer: &mut CS::WitnessPlacer| {
         use crate::cs::witness_placer::*;
 
-        let pc_inc_step =
+        let mut pc_inc_step =
             <CS::WitnessPlacer as WitnessTypeSet<F>>::U32::constant(PC_INC_STEP as u32);
+        if let Some(is_compressed) = is_compressed {
+            let is_compressed = placer.get_boolean(is_compressed);
+            let compressed_pc_inc_step = <CS::WitnessPlacer as WitnessTypeSet<F>>::U32::constant(
+                COMPRESSED_PC_INC_STEP as u32,
+            );
+            pc_inc_step = <CS::WitnessPlacer as WitnessTypeSet<F>>::U32::select(
+                &is_compressed,
+                &compressed_pc_inc_step,
+                &pc_inc_step,
+            );
+        }
         let pc = placer.get_u32_from_u16_parts(pc_vars);
         let (pc_next, _of) = pc.overflowing_add(&pc_inc_step);
         placer.assign_u32_from_u16_parts(pc_next_vars, &pc_next);
@@ -281,6 +319,55 @@ pub(crate) fn read_opcode_from_rom<
     result
 }
 
+/// Reads an opcode at half-word aligned PC, expanding it into 32-bit equivalent if it's compressed.
+/// Returns the expanded opcode and a flag that the original instruction was compressed
+pub(crate) fn read_opcode_with_compressed_from_rom<
+    F: PrimeField,
+    C: Circuit<F>,
+    const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize,
+>(
+    cs: &mut C,
+    pc: Register<F>,
+) -> (Register<F>, Boolean) {
+    assert!(16 + ROM_ADDRESS_SPACE_SECOND_WORD_BITS <= F::CHAR_BITS - 1);
+
+    let [is_ram_range, rom_address_low] = cs.get_variables_from_lookup_constrained(
+        &[LookupInput::from(pc.0[1].get_variable())],
+        TableType::RomAddressSpaceSeparator,
+    );
+    // assert that we only read opcodes from ROM, so "is RAM" is always false here
+    cs.add_constraint_allow_explicit_linear(Constraint::<F>::from(is_ram_range));
+    let rom_address_constraint = Term::from(pc.0[0].get_variable())
+        + Term::from((F::from_u64_unchecked(1 << 16), rom_address_low));
+
+    // the table places "is compressed" flag at bit 16 of the high half-word
+    let [low, high_with_flag] = cs.get_variables_from_lookup_constrained(
+        &[LookupInput::from(rom_address_constraint)],
+        TableType::RomInstructionRead,
+    );
+
+    // NOTE: high half-word is range checked by the decoder, so the flag is unique
+    let is_compressed = cs.add_boolean_variable();
+    let is_compressed_var = is_compressed.get_variable().unwrap();
+    let value_fn = move |placer: &mut C::WitnessPlacer| {
+        use crate::cs::witness_placer::*;
+
+        let high_with_flag = placer.get_field(high_with_flag);
+        let flag = high_with_flag.as_integer().get_bit(16);
+        placer.assign_mask(is_compressed_var, &flag);
+    };
+    cs.set_values(value_fn);
+
+    let high = cs.add_variable_from_constraint_allow_explicit_linear(
+        Term::from(high_with_flag)
+            - Term::from((F::from_u64_unchecked(1 << 16), is_compressed_var)),
+    );
+
+    let result = Register([Num::Var(low), Num::Var(high)]);
+
+    (result, is_compressed)
+}
+
 #[allow(dead_code)]
 pub(crate) fn get_register_op_as_shuffle_ram<F: PrimeField, C: Circuit<F>>(
     cs: &mut C,
@@ -16,6 +16,7 @@ use std::collections::BTreeSet;
 
 pub mod delegation;
 pub mod layout_utils;
+pub mod report;
 pub mod stage_2_layout;
 
 mod compile_layout;
@@ -0,0 +1,383 @@
+use super::*;
+use std::collections::BTreeMap;
+
+// Key for lookups where the table is selected by the witness column and not fixed at compile time
+const VARIABLE_TABLE_KEY: &str = "Variable";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SubtreeColumns {
+    pub witness: usize,
+    pub memory: usize,
+    pub setup: usize,
+    pub stage_2: usize,
+}
+
+impl SubtreeColumns {
+    pub fn total(&self) -> usize {
+        self.witness + self.memory + self.setup + self.stage_2
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WitnessColumnsBreakdown {
+    pub multiplicities: usize,
+    pub range_check_8: usize,
+    pub range_check_16: usize,
+    pub boolean: usize,
+    pub other: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RangeChecksCount {
+    pub width_8: usize,
+    pub width_16: usize,
+    pub timestamp: usize,
+}
+
+/// Summary of the circuit dimensions that drive the proving cost, to not read generated layouts
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CircuitLayoutReport {
+    pub trace_len: usize,
+    pub columns: SubtreeColumns,
+    pub witness_columns: WitnessColumnsBreakdown,
+    pub degree_2_constraints: usize,
+    pub degree_1_constraints: usize,
+    pub state_linkage_constraints: usize,
+    pub public_inputs: usize,
+    pub range_checks: RangeChecksCount,
+    // width 3 lookups by the table type name, including 8-bit range checks that are packed in pairs
+    pub lookups_per_table: BTreeMap<String, usize>,
+    pub num_quotient_terms: usize,
+    pub num_openings_at_z: usize,
+    pub num_openings_at_z_omega: usize,
+}
+
+impl CircuitLayoutReport {
+    pub fn from_compiled_circuit<F: PrimeField>(
+        compiled_circuit: &CompiledCircuitArtifact<F>,
+    ) -> Self {
+        let witness_layout = &compiled_circuit.witness_layout;
+
+        let multiplicities = witness_layout
+            .multiplicities_columns_for_range_check_16
+            .num_elements()
+            + witness_layout
+                .multiplicities_columns_for_timestamp_range_check
+                .num_elements()
+            + witness_layout
+                .multiplicities_columns_for_generic_lookup
+                .num_elements();
+        let range_check_8 = witness_layout.range_check_8_columns.num_elements();
+        let range_check_16 = witness_layout.range_check_16_columns.num_elements();
+        let boolean = witness_layout.boolean_vars_columns_range.num_elements();
+        let witness_columns = WitnessColumnsBreakdown {
+            multiplicities,
+            range_check_8,
+            range_check_16,
+            boolean,
+            other: witness_layout.total_width
+                - multiplicities
+                - range_check_8
+                - range_check_16
+                - boolean,
+        };
+
+        let mut width_16 = witness_layout.range_check_16_lookup_expressions.len();
+        if compiled_circuit
+            .memory_layout
+            .shuffle_ram_inits_and_teardowns
+            .is_some()
+        {
+            // lazy init address limbs
+            width_16 += 2;
+        }
+        let range_checks = RangeChecksCount {
+            width_8: range_check_8,
+            width_16,
+            timestamp: witness_layout
+                .timestamp_range_check_lookup_expressions
+                .len(),
+        };
+
+        let mut lookups_per_table = BTreeMap::new();
+        for lookup in witness_layout.width_3_lookups.iter() {
+            let key = match lookup.table_index {
+                TableIndex::Constant(table_type) => format!("{:?}", table_type),
+                TableIndex::Variable(_) => VARIABLE_TABLE_KEY.to_string(),
+            };
+            *lookups_per_table.entry(key).or_insert(0) += 1;
+        }
+
+        Self {
+            trace_len: compiled_circuit.trace_len,
+            columns: SubtreeColumns {
+                witness: witness_layout.total_width,
+                memory: compiled_circuit.memory_layout.total_width,
+                setup: compiled_circuit.setup_layout.total_width,
+                stage_2: compiled_circuit.stage_2_layout.total_width,
+            },
+            witness_columns,
+            degree_2_constraints: compiled_circuit.degree_2_constraints.len(),
+            degree_1_constraints: compiled_circuit.degree_1_constraints.len(),
+            state_linkage_constraints: compiled_circuit.state_linkage_constraints.len(),
+            public_inputs: compiled_circuit.public_inputs.len(),
+            range_checks,
+            lookups_per_table,
+            num_quotient_terms: compiled_circuit.compute_num_quotient_terms(),
+            num_openings_at_z: compiled_circuit.num_openings_at_z(),
+            num_openings_at_z_omega: compiled_circuit.num_openings_at_z_omega(),
+        }
+    }
+
+    /// All the numbers of the report as flat named metrics, in the order of the text report
+    pub fn metrics(&self) -> Vec<(String, usize)> {
+        let mut result = vec![
+            ("trace_len".to_string(), self.trace_len),
+            ("columns.witness".to_string(), self.columns.witness),
+            ("columns.memory".to_string(), self.columns.memory),
+            ("columns.setup".to_string(), self.columns.setup),
+            ("columns.stage_2".to_string(), self.columns.stage_2),
+            ("columns.total".to_string(), self.columns.total()),
+            (
+                "witness_columns.multiplicities".to_string(),
+                self.witness_columns.multiplicities,
+            ),
+            (
+                "witness_columns.range_check_8".to_string(),
+                self.witness_columns.range_check_8,
+            ),
+            (
+                "witness_columns.range_check_16".to_string(),
+                self.witness_columns.range_check_16,
+            ),
+            (
+                "witness_columns.boolean".to_string(),
+                self.witness_columns.boolean,
+            ),
+            (
+                "witness_columns.other".to_string(),
+                self.witness_columns.other,
+            ),
+            (
+                "constraints.degree_2".to_string(),
+                self.degree_2_constraints,
+            ),
+            (
+                "constraints.degree_1".to_string(),
+                self.degree_1_constraints,
+            ),
+            (
+                "constraints.state_linkage".to_string(),
+                self.state_linkage_constraints,
+            ),
+            ("public_inputs".to_string(), self.public_inputs),
+            (
+                "range_checks.width_8".to_string(),
+                self.range_checks.width_8,
+            ),
+            (
+                "range_checks.width_16".to_string(),
+                self.range_checks.width_16,
+            ),
+            (
+                "range_checks.timestamp".to_string(),
+                self.range_checks.timestamp,
+            ),
+        ];
+        for (table, count) in self.lookups_per_table.iter() {
+            result.push((format!("lookups.{}", table), *count));
+        }
+        result.extend([
+            ("quotient_terms".to_string(), self.num_quotient_terms),
+            ("openings.z".to_string(), self.num_openings_at_z),
+            ("openings.z_omega".to_string(), self.num_openings_at_z_omega),
+        ]);
+
+        result
+    }
+
+    /// Changes of all the metrics from `self` to `other`. Tables that are used only by one of the circuits
+    /// are counted as zero lookups for the other one
+    pub fn diff(&self, other: &Self) -> CircuitLayoutReportDiff {
+        let old_metrics = self.metrics();
+        let new_metrics = other.metrics();
+        let old_values: BTreeMap<_, _> = old_metrics.iter().cloned().collect();
+        let new_values: BTreeMap<_, _> = new_metrics.iter().cloned().collect();
+
+        // keep the report order, with metrics that only exist in the new report at the end
+        let names = old_metrics.iter().map(|(name, _)| name).chain(
+            new_metrics
+                .iter()
+                .map(|(name, _)| name)
+                .filter(|name| old_values.contains_key(*name) == false),
+        );
+        let changes = names
+            .filter_map(|name| {
+                let old = old_values.get(name).copied().unwrap_or(0);
+                let new = new_values.get(name).copied().unwrap_or(0);
+                (old != new).then(|| MetricChange {
+                    name: name.clone(),
+                    old,
+                    new,
+                })
+            })
+            .collect();
+
+        CircuitLayoutReportDiff { changes }
+    }
+}
+
+impl std::fmt::Display for CircuitLayoutReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "trace length: 2^{}", self.trace_len.trailing_zeros())?;
+        writeln!(
+            f,
+            "columns: {} witness, {} memory, {} setup, {} stage 2, {} total",
+            self.columns.witness,
+            self.columns.memory,
+            self.columns.setup,
+            self.columns.stage_2,
+            self.columns.total()
+        )?;
+        writeln!(
+            f,
+            "witness columns: {} multiplicities, {} range check 8, {} range check 16, {} boolean, {} other",
+            self.witness_columns.multiplicities,
+            self.witness_columns.range_check_8,
+            self.witness_columns.range_check_16,
+            self.witness_columns.boolean,
+            self.witness_columns.other
+        )?;
+        writeln!(
+            f,
+            "constraints: {} of degree 2, {} of degree 1, {} state linkage, {} public inputs",
+            self.degree_2_constraints,
+            self.degree_1_constraints,
+            self.state_linkage_constraints,
+            self.public_inputs
+        )?;
+        writeln!(
+            f,
+            "range checks: {} of 8 bits, {} of 16 bits, {} of timestamp",
+            self.range_checks.width_8, self.range_checks.width_16, self.range_checks.timestamp
+        )?;
+        writeln!(f, "lookups:")?;
+        for (table, count) in self.lookups_per_table.iter() {
+            writeln!(f, "  {:<32} {:>6}", table, count)?;
+        }
+        writeln!(f, "quotient terms: {}", self.num_quotient_terms)?;
+        writeln!(
+            f,
+            "openings: {} at z, {} at z*omega",
+            self.num_openings_at_z, self.num_openings_at_z_omega
+        )?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MetricChange {
+    pub name: String,
+    pub old: usize,
+    pub new: usize,
+}
+
+impl MetricChange {
+    pub fn delta(&self) -> i64 {
+        self.new as i64 - self.old as i64
+    }
+}
+
+/// Only the metrics that differ between two reports
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CircuitLayoutReportDiff {
+    pub changes: Vec<MetricChange>,
+}
+
+impl CircuitLayoutReportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl std::fmt::Display for CircuitLayoutReportDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        for change in self.changes.iter() {
+            writeln!(
+                f,
+                "{:<40} {:>8} -> {:>8} ({:+})",
+                change.name,
+                change.old,
+                change.new,
+                change.delta()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::machine::machine_configurations::full_isa_with_delegation_no_exceptions::FullIsaMachineWithDelegationNoExceptionHandling;
+    use crate::machine::machine_configurations::full_isa_with_delegation_no_exceptions_no_signed_mul_div::FullIsaMachineWithDelegationNoExceptionHandlingNoSignedMulDiv;
+    use crate::machine::machine_configurations::{
+        create_csr_table_for_delegation, create_table_for_rom_image,
+    };
+    use crate::machine::Machine;
+    use field::Mersenne31Field;
+
+    const SECOND_WORD_BITS: usize = 4;
+
+    fn compile<M: Machine<Mersenne31Field>>(machine: M) -> CompiledCircuitArtifact<Mersenne31Field>
+    where
+        [(); { <M as Machine<Mersenne31Field>>::ASSUME_TRUSTED_CODE } as usize]:,
+        [(); { <M as Machine<Mersenne31Field>>::OUTPUT_EXACT_EXCEPTIONS } as usize]:,
+    {
+        let rom_table = create_table_for_rom_image::<_, SECOND_WORD_BITS>(
+            &[],
+            TableType::RomRead.to_table_id(),
+        );
+        let csr_table = create_csr_table_for_delegation(
+            true,
+            &[1991],
+            TableType::SpecialCSRProperties.to_table_id(),
+        );
+
+        crate::default_compile_machine::<_, SECOND_WORD_BITS>(
+            machine,
+            rom_table,
+            Some(csr_table),
+            20,
+        )
+    }
+
+    #[test]
+    fn report_and_diff_machines() {
+        let full = CircuitLayoutReport::from_compiled_circuit(&compile(
+            FullIsaMachineWithDelegationNoExceptionHandling,
+        ));
+        let no_signed_mul_div = CircuitLayoutReport::from_compiled_circuit(&compile(
+            FullIsaMachineWithDelegationNoExceptionHandlingNoSignedMulDiv,
+        ));
+        println!("{}", full);
+        println!("{}", no_signed_mul_div);
+
+        assert_eq!(full.trace_len, 1 << 20);
+        assert!(full.num_quotient_terms > full.degree_2_constraints + full.degree_1_constraints);
+
+        let serialized = serde_json::to_string(&full).unwrap();
+        let deserialized: CircuitLayoutReport = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, full);
+
+        assert!(full.diff(&full).is_empty());
+        let diff = full.diff(&no_signed_mul_div);
+        println!("{}", diff);
+        assert!(diff.is_empty() == false);
+    }
+}
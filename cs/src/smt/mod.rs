@@ -0,0 +1,408 @@
+//! Export of the constraint systems into SMT-LIB2, so soundness of the gadgets can be checked by SMT solvers.
+//!
+//! Every variable becomes an integer in the range `[0, P)`, constraints are asserted to be zero modulo `P`,
+//! and lookup tables become relations `table_<TableType>` over the three (zero padded) columns. Tables that
+//! have a closed form description are emitted as such, small tables are enumerated, and the rest are left
+//! uninterpreted, so that the solver may only use that the tuple belongs to some fixed set. This keeps all
+//! "unsat" results valid, but some properties may not be provable without the table contents.
+//!
+//! Soundness queries are only built for the R-type register-register instructions (`Rv32RegisterOp`),
+//! each checked against the gadget in isolation. Instructions with immediates, loads and stores, branches,
+//! jumps, CSR access and delegation circuits are not covered, and neither is the decoder itself: its output
+//! is assumed to be the one for the instruction. CI runs the queries with z3 (`smt_soundness` job).
+
+use crate::constraint::{Constraint, Term};
+use crate::cs::circuit::{CircuitOutput, LookupQueryTableType};
+use crate::definitions::{LookupInput, Variable, COMMON_TABLE_WIDTH};
+use crate::tables::{TableDriver, TableType, TABLE_TYPES_UPPER_BOUNDS};
+use crate::types::Boolean;
+use field::PrimeField;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+mod opcode;
+mod rv32_spec;
+mod tables;
+
+pub use self::opcode::*;
+pub use self::rv32_spec::*;
+pub use self::tables::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SmtExportOptions {
+    /// Tables with at most this number of rows are emitted as enumerations, the rest are uninterpreted relations
+    pub max_enumerated_table_size: usize,
+}
+
+impl Default for SmtExportOptions {
+    fn default() -> Self {
+        Self {
+            max_enumerated_table_size: 1 << 10,
+        }
+    }
+}
+
+pub fn smt_variable(variable: Variable) -> String {
+    format!("v{}", variable.0)
+}
+
+fn smt_field_constant<F: PrimeField>(value: F) -> String {
+    // print "negative" constants as such, it makes the output readable
+    let value = value.as_u64_reduced();
+    if value > F::CHARACTERISTICS / 2 {
+        format!("(- {})", F::CHARACTERISTICS - value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+fn smt_sum(mut summands: Vec<String>) -> String {
+    match summands.len() {
+        0 => "0".to_string(),
+        1 => summands.pop().unwrap(),
+        _ => format!("(+ {})", summands.join(" ")),
+    }
+}
+
+/// Expression for the constraint over integers, without reduction modulo `P`
+pub fn smt_constraint<F: PrimeField>(constraint: &Constraint<F>) -> String {
+    let summands = constraint
+        .terms
+        .iter()
+        .map(|term| match term {
+            Term::Constant(constant) => smt_field_constant(*constant),
+            Term::Expression {
+                coeff,
+                inner,
+                degree,
+            } => {
+                let mut factors = vec![];
+                if *coeff != F::ONE {
+                    factors.push(smt_field_constant(*coeff));
+                }
+                factors.extend(inner[..*degree].iter().map(|el| smt_variable(*el)));
+                if factors.len() == 1 {
+                    factors.pop().unwrap()
+                } else {
+                    format!("(* {})", factors.join(" "))
+                }
+            }
+        })
+        .collect();
+
+    smt_sum(summands)
+}
+
+/// Expression for the lookup input over integers, without reduction modulo `P`
+pub fn smt_lookup_input<F: PrimeField>(input: &LookupInput<F>) -> String {
+    match input {
+        LookupInput::Variable(variable) => smt_variable(*variable),
+        LookupInput::Expression {
+            linear_terms,
+            constant_coeff,
+        } => {
+            let mut summands: Vec<_> = linear_terms
+                .iter()
+                .map(|(coeff, variable)| {
+                    if *coeff == F::ONE {
+                        smt_variable(*variable)
+                    } else {
+                        format!(
+                            "(* {} {})",
+                            smt_field_constant(*coeff),
+                            smt_variable(*variable)
+                        )
+                    }
+                })
+                .collect();
+            if *constant_coeff != F::ZERO {
+                summands.push(smt_field_constant(*constant_coeff));
+            }
+
+            smt_sum(summands)
+        }
+    }
+}
+
+pub fn smt_boolean(boolean: Boolean) -> String {
+    match boolean {
+        Boolean::Is(variable) => smt_variable(variable),
+        Boolean::Not(variable) => format!("(- 1 {})", smt_variable(variable)),
+        Boolean::Constant(value) => format!("{}", value as u32),
+    }
+}
+
+pub fn smt_table_name(table_type: TableType) -> String {
+    format!("table_{:?}", table_type)
+}
+
+fn write_table_relation<F: PrimeField>(
+    dst: &mut String,
+    table_driver: &TableDriver<F>,
+    table_type: TableType,
+    options: &SmtExportOptions,
+) {
+    let name = smt_table_name(table_type);
+    if let Some(definition) = smt_table_closed_form(table_type) {
+        writeln!(
+            dst,
+            "(define-fun {} ((a Int) (b Int) (c Int)) Bool {})",
+            name, definition
+        )
+        .unwrap();
+        return;
+    }
+
+    let table = table_driver.get_table(table_type);
+    if table.is_initialized() == false || table.get_size() > options.max_enumerated_table_size {
+        writeln!(dst, "(declare-fun {} (Int Int Int) Bool)", name).unwrap();
+        return;
+    }
+
+    let mut rows = Vec::with_capacity(table.get_size());
+    table.dump_into::<COMMON_TABLE_WIDTH>(&mut rows, None);
+    writeln!(
+        dst,
+        "(define-fun {} ((a Int) (b Int) (c Int)) Bool (or",
+        name
+    )
+    .unwrap();
+    for row in rows.iter() {
+        let [a, b, c] = row.map(|el| el.as_u64_reduced());
+        writeln!(dst, "  (and (= a {}) (= b {}) (= c {}))", a, b, c).unwrap();
+    }
+    writeln!(dst, "  false))").unwrap();
+}
+
+/// Emits declarations and assertions for all the variables, constraints, range checks and lookups of the circuit.
+/// The caller is expected to append assumptions about inputs, the property to check and `(check-sat)`.
+pub fn export_circuit_to_smtlib<F: PrimeField>(
+    circuit: &CircuitOutput<F>,
+    options: &SmtExportOptions,
+) -> String {
+    let mut dst = String::new();
+    writeln!(dst, "(set-logic ALL)").unwrap();
+    writeln!(dst, "(define-const P Int {})", F::CHARACTERISTICS).unwrap();
+    writeln!(dst).unwrap();
+
+    writeln!(dst, "; variables").unwrap();
+    let mut placeholders: Vec<_> = circuit.substitutions.iter().collect();
+    placeholders.sort_by_key(|(_, variable)| variable.0);
+    for ((placeholder, subindex), variable) in placeholders.into_iter() {
+        writeln!(
+            dst,
+            "; {} = {:?}[{}]",
+            smt_variable(*variable),
+            placeholder,
+            subindex
+        )
+        .unwrap();
+    }
+    for idx in 0..circuit.num_of_variables {
+        let variable = smt_variable(Variable(idx as u64));
+        writeln!(dst, "(declare-const {} Int)", variable).unwrap();
+        writeln!(dst, "(assert (and (<= 0 {}) (< {} P)))", variable, variable).unwrap();
+    }
+    writeln!(dst).unwrap();
+
+    writeln!(dst, "; tables").unwrap();
+    let mut used_tables = BTreeSet::new();
+    let mut uses_variable_tables = false;
+    for lookup in circuit.lookups.iter() {
+        match lookup.table {
+            LookupQueryTableType::Constant(table_type) => {
+                used_tables.insert(table_type);
+            }
+            LookupQueryTableType::Variable(_) => {
+                uses_variable_tables = true;
+            }
+        }
+    }
+    let mut all_tables = vec![];
+    if uses_variable_tables {
+        for id in 0..TABLE_TYPES_UPPER_BOUNDS {
+            if circuit
+                .table_driver
+                .get_table_by_id(id as u32)
+                .is_initialized()
+            {
+                let table_type = TableType::get_table_from_id(id as u32);
+                used_tables.insert(table_type);
+                all_tables.push(table_type);
+            }
+        }
+    }
+    for table_type in used_tables.iter() {
+        write_table_relation(&mut dst, &circuit.table_driver, *table_type, options);
+    }
+    if uses_variable_tables {
+        writeln!(
+            dst,
+            "(define-fun table_by_id ((id Int) (a Int) (b Int) (c Int)) Bool (or"
+        )
+        .unwrap();
+        for table_type in all_tables.iter() {
+            writeln!(
+                dst,
+                "  (and (= id {}) ({} a b c))",
+                table_type.to_table_id(),
+                smt_table_name(*table_type)
+            )
+            .unwrap();
+        }
+        writeln!(dst, "  false))").unwrap();
+    }
+    writeln!(dst).unwrap();
+
+    writeln!(dst, "; boolean variables").unwrap();
+    for variable in circuit.boolean_vars.iter() {
+        let variable = smt_variable(*variable);
+        writeln!(dst, "(assert (or (= {} 0) (= {} 1)))", variable, variable).unwrap();
+    }
+    writeln!(dst).unwrap();
+
+    writeln!(dst, "; linked variables").unwrap();
+    for pair in circuit.linked_variables.iter() {
+        writeln!(
+            dst,
+            "(assert (= {} {}))",
+            smt_variable(pair.initial_var),
+            smt_variable(pair.final_var)
+        )
+        .unwrap();
+    }
+    writeln!(dst).unwrap();
+
+    writeln!(dst, "; range checks").unwrap();
+    for range_check in circuit.range_check_expressions.iter() {
+        writeln!(
+            dst,
+            "(assert (< (mod {} P) {}))",
+            smt_lookup_input(&range_check.input),
+            1u64 << range_check.width
+        )
+        .unwrap();
+    }
+    writeln!(dst).unwrap();
+
+    writeln!(dst, "; constraints").unwrap();
+    for (constraint, _) in circuit.constraints.iter() {
+        writeln!(dst, "(assert (= (mod {} P) 0))", smt_constraint(constraint)).unwrap();
+    }
+    writeln!(dst).unwrap();
+
+    writeln!(dst, "; lookups").unwrap();
+    for lookup in circuit.lookups.iter() {
+        let row = lookup
+            .row
+            .each_ref()
+            .map(|el| format!("(mod {} P)", smt_lookup_input(el)))
+            .join(" ");
+        match lookup.table {
+            LookupQueryTableType::Constant(table_type) => {
+                writeln!(dst, "(assert ({} {}))", smt_table_name(table_type), row).unwrap();
+            }
+            LookupQueryTableType::Variable(variable) => {
+                writeln!(
+                    dst,
+                    "(assert (table_by_id {} {}))",
+                    smt_variable(variable),
+                    row
+                )
+                .unwrap();
+            }
+        }
+    }
+    writeln!(dst).unwrap();
+
+    dst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine::ops::*;
+    use field::Mersenne31Field;
+    type F = Mersenne31Field;
+
+    fn soundness_query(instruction: Rv32RegisterOp, options: &SmtExportOptions) -> String {
+        use Rv32RegisterOp::*;
+        match instruction {
+            Add => export_register_op_soundness_query::<F, _>(AddOp, instruction, options),
+            Sub => export_register_op_soundness_query::<F, _>(SubOp, instruction, options),
+            Sll | Srl | Sra => export_register_op_soundness_query::<F, _>(
+                ShiftOp::<true, false>,
+                instruction,
+                options,
+            ),
+            Slt | Sltu => export_register_op_soundness_query::<F, _>(
+                ConditionalOp::<true, false>,
+                instruction,
+                options,
+            ),
+            Xor | Or | And => {
+                export_register_op_soundness_query::<F, _>(BinaryOp, instruction, options)
+            }
+            Mul | Mulh | Mulhsu | Mulhu => {
+                export_register_op_soundness_query::<F, _>(MulOp::<true>, instruction, options)
+            }
+            Div | Divu | Rem | Remu => {
+                export_register_op_soundness_query::<F, _>(DivRemOp::<true>, instruction, options)
+            }
+        }
+    }
+
+    #[test]
+    fn export_soundness_queries() {
+        let options = SmtExportOptions::default();
+        for instruction in Rv32RegisterOp::ALL {
+            let query = soundness_query(instruction, &options);
+            assert!(query.starts_with("(set-logic ALL)"));
+            assert!(query.contains(&format!("(define-fun {} ", instruction.spec_function())));
+            assert!(query.contains(&format!("({} ", instruction.spec_function())));
+            assert!(query.trim_end().ends_with("(check-sat)"));
+            // all parentheses must be balanced
+            let mut depth = 0i64;
+            for c in query.chars() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                assert!(depth >= 0);
+            }
+            assert_eq!(depth, 0, "unbalanced query for {:?}", instruction);
+        }
+    }
+
+    #[ignore = "requires z3 in PATH, runs in the `smt_soundness` CI job"]
+    #[test]
+    fn check_soundness_with_z3() {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let options = SmtExportOptions::default();
+        for instruction in Rv32RegisterOp::ALL {
+            let query = soundness_query(instruction, &options);
+            let mut z3 = Command::new("z3")
+                .args(["-in", "-smt2"])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("z3 must be available");
+            z3.stdin
+                .take()
+                .unwrap()
+                .write_all(query.as_bytes())
+                .unwrap();
+            let output = z3.wait_with_output().unwrap();
+            let output = String::from_utf8(output.stdout).unwrap();
+            assert_eq!(
+                output.trim(),
+                "unsat",
+                "constraints for {:?} are not sound",
+                instruction
+            );
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use super::*;
+use crate::cs::circuit::Circuit;
+use crate::cs::cs_reference::BasicAssembly;
+use crate::devices::optimization_context::OptimizationContext;
+use crate::machine::instruction_decoding_data::DecoderOutputExtraKeysHolder;
+use crate::machine::machine_configurations::minimal_state::MinimalStateRegistersInMemory;
+use crate::machine::machine_configurations::{BasicDecodingResultWithSigns, BasicFlagsSource};
+use crate::machine::MachineOp;
+use crate::types::{Num, Register, RegisterDecompositionWithSign};
+
+fn assert_u16_limbs<F: PrimeField>(dst: &mut String, register: &Register<F>) {
+    for limb in register.0.iter() {
+        writeln!(
+            dst,
+            "(assert (< {} 65536))",
+            smt_variable(limb.get_variable())
+        )
+        .unwrap();
+    }
+}
+
+fn smt_register_as_bitvector<F: PrimeField>(register: &Register<F>) -> String {
+    format!(
+        "((_ int2bv 32) (+ {} (* 65536 {})))",
+        smt_variable(register.0[0].get_variable()),
+        smt_variable(register.0[1].get_variable())
+    )
+}
+
+/// Builds a circuit that consists of the single `op` gadget applied to unconstrained register operands,
+/// and emits an SMT-LIB2 query that is satisfiable if and only if there exists a witness for the
+/// `instruction` where the value written into `rd` differs from the one given by `RV32_REFERENCE_SPEC`.
+/// So "unsat" from the solver means that the gadget is sound for this instruction.
+///
+/// Decoder output is modeled in the same way as the decoder produces it for R-type instructions: flags
+/// of the op for the instruction are set, all others are unset, and funct3/funct12 match the instruction.
+pub fn export_register_op_soundness_query<F, Op>(
+    op: Op,
+    instruction: Rv32RegisterOp,
+    options: &SmtExportOptions,
+) -> String
+where
+    F: PrimeField,
+    Op: MachineOp<
+        F,
+        MinimalStateRegistersInMemory<F>,
+        RegisterDecompositionWithSign<F>,
+        BasicDecodingResultWithSigns<F>,
+        BasicFlagsSource,
+    >,
+{
+    let (_, instruction_major, instruction_minors) = op
+        .define_decoder_subspace(
+            instruction.opcode(),
+            instruction.funct3(),
+            instruction.funct7(),
+        )
+        .unwrap_or_else(|_| panic!("{:?} is not supported by {:?}", instruction, op));
+
+    // the same keys that the decoder would have for the machine with only this op
+    let mut keys = DecoderOutputExtraKeysHolder::new();
+    for opcode in 0..(1u8 << 7) {
+        for funct3 in 0..(1u8 << 3) {
+            for funct7 in 0..(1u8 << 7) {
+                if let Ok((_, major, minors)) = op.define_decoder_subspace(opcode, funct3, funct7) {
+                    keys.collect(major, minors);
+                }
+            }
+        }
+    }
+
+    let mut cs = BasicAssembly::<F>::new();
+    for table_type in Op::define_used_tables() {
+        cs.materialize_table(table_type);
+    }
+
+    let state = MinimalStateRegistersInMemory::<F>::initialize(&mut cs);
+    let rs1 = Register::<F>::new_unchecked(&mut cs);
+    let rs2 = Register::<F>::new_unchecked(&mut cs);
+    let pc_next = Register::<F>::new_unchecked(&mut cs);
+    let imm = Register::<F>::new_unchecked(&mut cs);
+    let rs2_index = cs.add_variable();
+    let funct3 = cs.add_variable();
+    let funct12 = cs.add_variable();
+    let flags: Vec<Boolean> = (0..(keys.num_major_keys() + keys.max_minor_keys()))
+        .map(|_| cs.add_boolean_variable())
+        .collect();
+    let flags_source = BasicFlagsSource::new(keys.clone(), flags.clone());
+
+    let src1 = RegisterDecompositionWithSign::parse_reg(&mut cs, rs1);
+    let src2 = RegisterDecompositionWithSign::parse_reg(&mut cs, rs2);
+    let decoder_output = BasicDecodingResultWithSigns {
+        pc_next,
+        src1,
+        src2,
+        imm,
+        rs2_index: Constraint::from(rs2_index),
+        funct3: Num::Var(funct3),
+        funct12: Constraint::from(funct12),
+    };
+
+    let mut opt_ctx = OptimizationContext::<F, BasicAssembly<F>>::new();
+    let diffs = Op::apply::<_, true, false>(
+        &mut cs,
+        &state,
+        &decoder_output,
+        &flags_source,
+        &mut opt_ctx,
+    );
+    opt_ctx.enforce_all(&mut cs);
+    let (circuit, _) = cs.finalize();
+
+    let mut dst = export_circuit_to_smtlib(&circuit, options);
+
+    writeln!(dst, "; decoder output for {:?}", instruction).unwrap();
+    for register in [&rs1, &rs2, &pc_next, &imm] {
+        assert_u16_limbs(&mut dst, register);
+    }
+    let major_index = keys.get_major_index(&instruction_major);
+    for (idx, flag) in flags[..keys.num_major_keys()].iter().enumerate() {
+        let value = (idx == major_index) as u32;
+        writeln!(dst, "(assert (= {} {}))", smt_boolean(*flag), value).unwrap();
+    }
+    let minor_indexes: Vec<usize> = instruction_minors
+        .iter()
+        .map(|minor| keys.get_index_set(&instruction_major, minor).1)
+        .collect();
+    for (idx, flag) in flags[keys.num_major_keys()..].iter().enumerate() {
+        let value = minor_indexes.contains(&idx) as u32;
+        writeln!(dst, "(assert (= {} {}))", smt_boolean(*flag), value).unwrap();
+    }
+    let rs2_index = smt_variable(rs2_index);
+    writeln!(dst, "(assert (< {} 32))", rs2_index).unwrap();
+    writeln!(
+        dst,
+        "(assert (= {} {}))",
+        smt_variable(funct3),
+        instruction.funct3()
+    )
+    .unwrap();
+    writeln!(
+        dst,
+        "(assert (= {} (+ {} {})))",
+        smt_variable(funct12),
+        (instruction.funct7() as u32) << 5,
+        rs2_index
+    )
+    .unwrap();
+    writeln!(dst).unwrap();
+
+    dst.push_str(RV32_REFERENCE_SPEC);
+    writeln!(dst).unwrap();
+
+    writeln!(dst, "; property").unwrap();
+    writeln!(
+        dst,
+        "(define-const expected (_ BitVec 32) ({} {} {}))",
+        instruction.spec_function(),
+        smt_register_as_bitvector(&rs1),
+        smt_register_as_bitvector(&rs2)
+    )
+    .unwrap();
+    for (limb_idx, (name, [high_bit, low_bit])) in [("rd_low", [15, 0]), ("rd_high", [31, 16])]
+        .into_iter()
+        .enumerate()
+    {
+        let summands = diffs
+            .rd_value
+            .iter()
+            .map(|(value, flag)| {
+                format!(
+                    "(* {} {})",
+                    smt_boolean(*flag),
+                    smt_constraint(&value[limb_idx])
+                )
+            })
+            .collect();
+        writeln!(
+            dst,
+            "(define-const {} Int (mod {} P))",
+            name,
+            smt_sum(summands)
+        )
+        .unwrap();
+        writeln!(
+            dst,
+            "(define-const {}_expected Int (bv2nat ((_ extract {} {}) expected)))",
+            name, high_bit, low_bit
+        )
+        .unwrap();
+    }
+    writeln!(
+        dst,
+        "(assert (not (and (= rd_low rd_low_expected) (= rd_high rd_high_expected))))"
+    )
+    .unwrap();
+    writeln!(dst, "(check-sat)").unwrap();
+
+    dst
+}
@@ -0,0 +1,135 @@
+use crate::machine::ops::OPERATION_OP;
+
+/// Register-register instructions of RV32IM
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rv32RegisterOp {
+    Add,
+    Sub,
+    Sll,
+    Slt,
+    Sltu,
+    Xor,
+    Srl,
+    Sra,
+    Or,
+    And,
+    Mul,
+    Mulh,
+    Mulhsu,
+    Mulhu,
+    Div,
+    Divu,
+    Rem,
+    Remu,
+}
+
+impl Rv32RegisterOp {
+    pub const ALL: [Self; 18] = [
+        Self::Add,
+        Self::Sub,
+        Self::Sll,
+        Self::Slt,
+        Self::Sltu,
+        Self::Xor,
+        Self::Srl,
+        Self::Sra,
+        Self::Or,
+        Self::And,
+        Self::Mul,
+        Self::Mulh,
+        Self::Mulhsu,
+        Self::Mulhu,
+        Self::Div,
+        Self::Divu,
+        Self::Rem,
+        Self::Remu,
+    ];
+
+    pub const fn opcode(&self) -> u8 {
+        OPERATION_OP
+    }
+
+    pub const fn funct3(&self) -> u8 {
+        match self {
+            Self::Add | Self::Sub | Self::Mul => 0b000,
+            Self::Sll | Self::Mulh => 0b001,
+            Self::Slt | Self::Mulhsu => 0b010,
+            Self::Sltu | Self::Mulhu => 0b011,
+            Self::Xor | Self::Div => 0b100,
+            Self::Srl | Self::Sra | Self::Divu => 0b101,
+            Self::Or | Self::Rem => 0b110,
+            Self::And | Self::Remu => 0b111,
+        }
+    }
+
+    pub const fn funct7(&self) -> u8 {
+        match self {
+            Self::Sub | Self::Sra => 0b010_0000,
+            Self::Mul
+            | Self::Mulh
+            | Self::Mulhsu
+            | Self::Mulhu
+            | Self::Div
+            | Self::Divu
+            | Self::Rem
+            | Self::Remu => 0b000_0001,
+            _ => 0b000_0000,
+        }
+    }
+
+    /// Name of the function in `RV32_REFERENCE_SPEC`
+    pub const fn spec_function(&self) -> &'static str {
+        match self {
+            Self::Add => "rv32_add",
+            Self::Sub => "rv32_sub",
+            Self::Sll => "rv32_sll",
+            Self::Slt => "rv32_slt",
+            Self::Sltu => "rv32_sltu",
+            Self::Xor => "rv32_xor",
+            Self::Srl => "rv32_srl",
+            Self::Sra => "rv32_sra",
+            Self::Or => "rv32_or",
+            Self::And => "rv32_and",
+            Self::Mul => "rv32_mul",
+            Self::Mulh => "rv32_mulh",
+            Self::Mulhsu => "rv32_mulhsu",
+            Self::Mulhu => "rv32_mulhu",
+            Self::Div => "rv32_div",
+            Self::Divu => "rv32_divu",
+            Self::Rem => "rv32_rem",
+            Self::Remu => "rv32_remu",
+        }
+    }
+}
+
+/// Semantics of the RV32IM register-register instructions over 32-bit vectors, written to match
+/// the `risc_v_simulator` implementation, including division by zero and signed overflow cases
+pub const RV32_REFERENCE_SPEC: &str = r#"; RV32IM reference semantics
+(define-fun rv32_add ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvadd a b))
+(define-fun rv32_sub ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvsub a b))
+(define-fun rv32_sll ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvshl a (bvand b #x0000001f)))
+(define-fun rv32_slt ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (ite (bvslt a b) #x00000001 #x00000000))
+(define-fun rv32_sltu ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (ite (bvult a b) #x00000001 #x00000000))
+(define-fun rv32_xor ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvxor a b))
+(define-fun rv32_srl ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvlshr a (bvand b #x0000001f)))
+(define-fun rv32_sra ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvashr a (bvand b #x0000001f)))
+(define-fun rv32_or ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvor a b))
+(define-fun rv32_and ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvand a b))
+(define-fun rv32_mul ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32) (bvmul a b))
+(define-fun rv32_mulh ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32)
+  ((_ extract 63 32) (bvmul ((_ sign_extend 32) a) ((_ sign_extend 32) b))))
+(define-fun rv32_mulhsu ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32)
+  ((_ extract 63 32) (bvmul ((_ sign_extend 32) a) ((_ zero_extend 32) b))))
+(define-fun rv32_mulhu ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32)
+  ((_ extract 63 32) (bvmul ((_ zero_extend 32) a) ((_ zero_extend 32) b))))
+(define-fun rv32_div ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32)
+  (ite (= b #x00000000) #xffffffff
+    (ite (and (= a #x80000000) (= b #xffffffff)) a (bvsdiv a b))))
+(define-fun rv32_divu ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32)
+  (ite (= b #x00000000) #xffffffff (bvudiv a b)))
+(define-fun rv32_rem ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32)
+  (ite (= b #x00000000) a
+    (ite (and (= a #x80000000) (= b #xffffffff)) #x00000000 (bvsrem a b))))
+(define-fun rv32_remu ((a (_ BitVec 32)) (b (_ BitVec 32))) (_ BitVec 32)
+  (ite (= b #x00000000) a (bvurem a b)))
+"#;
@@ -0,0 +1,41 @@
+use crate::tables::TableType;
+
+const BYTE_INPUTS: &str = "(<= 0 a) (< a 256) (<= 0 b) (< b 256)";
+
+/// Exact description of the table content as a predicate over the columns `a`, `b` and `c`, for tables
+/// that are too large to be enumerated. Mirrors the corresponding generation functions in `crate::tables`
+pub fn smt_table_closed_form(table_type: TableType) -> Option<String> {
+    let binop = |op: &str| {
+        format!(
+            "(and {} (= c (bv2nat ({} ((_ int2bv 8) a) ((_ int2bv 8) b)))))",
+            BYTE_INPUTS, op
+        )
+    };
+
+    let definition = match table_type {
+        TableType::And => binop("bvand"),
+        TableType::Or => binop("bvor"),
+        TableType::Xor => binop("bvxor"),
+        TableType::RangeCheckSmall => format!("(and {} (= c 0))", BYTE_INPUTS),
+        TableType::U16GetSignAndHighByte => {
+            "(and (<= 0 a) (< a 65536) (= b (div a 32768)) (= c (div a 256)))".to_string()
+        }
+        TableType::JumpCleanupOffset => {
+            "(and (<= 0 a) (< a 65536) (= b (mod (div a 2) 2)) (= c (* 4 (div a 4))))".to_string()
+        }
+        TableType::ShiftImplementation => {
+            // key is 16 bits of input || 5 bits of shift amount || is right shift
+            r#"(let ((x ((_ zero_extend 16) ((_ int2bv 16) a)))
+        (s ((_ zero_extend 27) ((_ int2bv 5) (div a 65536))))
+        (is_right (>= a 2097152)))
+    (let ((t (ite is_right (bvlshr (bvshl x #x00000010) s) (bvshl x s))))
+      (and (<= 0 a) (< a 4194304)
+        (= b (bv2nat (ite is_right ((_ extract 31 16) t) ((_ extract 15 0) t))))
+        (= c (bv2nat (ite is_right ((_ extract 15 0) t) ((_ extract 31 16) t)))))))"#
+                .to_string()
+        }
+        _ => return None,
+    };
+
+    Some(definition)
+}
@@ -394,6 +394,13 @@ impl<F: PrimeField, const N: usize> LookupTable<F, N> {
         }
     }
 
+    /// Index of the full `row` in the table, or `None` if the table doesn't contain it
+    pub fn try_lookup_row(&self, row: &[F]) -> Option<usize> {
+        assert_eq!(row.len(), N);
+        let row = unsafe { row.as_ptr().cast::<[F; N]>().read() };
+        self.content_data.get(&DataKey(row)).copied()
+    }
+
     #[track_caller]
     #[inline(always)]
     pub fn lookup_row(&self, key: &[F]) -> usize {
@@ -567,6 +574,15 @@ impl<F: PrimeField> LookupWrapper<F> {
         }
     }
 
+    pub fn try_lookup_row(&self, row: &[F]) -> Option<usize> {
+        match self {
+            Self::Dimensional1(inner) => inner.try_lookup_row(row),
+            Self::Dimensional2(inner) => inner.try_lookup_row(row),
+            Self::Dimensional3(inner) => inner.try_lookup_row(row),
+            Self::Uninitialized => None,
+        }
+    }
+
     pub fn get_size(&self) -> usize {
         match self {
             Self::Dimensional1(inner) => inner.table_size(),
@@ -668,6 +684,9 @@ impl quote::ToTokens for TableType {
             }
             TableType::ExtendLoadedValue => quote! { TableType::ExtendLoadedValue },
             TableType::TruncateShift => quote! { TableType::TruncateShift },
+            TableType::BitCountAndSignExtend => quote! { TableType::BitCountAndSignExtend },
+            TableType::ByteWiseBitmanip => quote! { TableType::ByteWiseBitmanip },
+            TableType::RomInstructionRead => quote! { TableType::RomInstructionRead },
             TableType::DynamicPlaceholder => {
                 unimplemented!("should not appear in final circuits")
             }
@@ -796,6 +815,12 @@ impl TableType {
             TableType::TruncateShift => {
                 LookupWrapper::Dimensional3(create_truncate_shift_amount_table::<F>(id))
             }
+            TableType::BitCountAndSignExtend => {
+                LookupWrapper::Dimensional3(create_bit_count_and_sign_extend_table::<F>(id))
+            }
+            TableType::ByteWiseBitmanip => {
+                LookupWrapper::Dimensional3(create_byte_wise_bitmanip_table::<F>(id))
+            }
             a @ _ => {
                 todo!("Support {:?}", a);
             }
@@ -1902,6 +1927,95 @@ pub fn create_u16_split_into_bytes_table<F: PrimeField>(id: u32) -> LookupTable<
     )
 }
 
+pub fn create_bit_count_and_sign_extend_table<F: PrimeField>(id: u32) -> LookupTable<F, 3> {
+    // take 16 bits of input half-word || 3 bits of selector (rs2 field of the opcode) || is_high_half
+    //
+    // Outputs are arranged such that for low half (a0, a1) and high half (b0, b1) the low word of the
+    // result is always a0 + b0 + a1 * b1, and high word is a1 for sign extensions and 0 otherwise
+    const SELECTOR_SHIFT: usize = 16;
+    const IS_HIGH_HALF_SHIFT: usize = 16 + 3;
+
+    let keys = key_for_continuous_log2_range(16 + 3 + 1);
+
+    let table_name = "Bit count and sign extend table".to_string();
+    LookupTable::create_table_from_key_and_pure_generation_fn(
+        &keys,
+        table_name,
+        1,
+        |keys| {
+            let a = keys[0].as_u64_reduced();
+            let input_word = a as u16;
+            let selector = (a >> SELECTOR_SHIFT) & 0b111;
+            let is_high_half = (a >> IS_HIGH_HALF_SHIFT) > 0;
+
+            let (first, second) = match (selector, is_high_half) {
+                // CLZ: clz(high) + [high == 0] * clz(low)
+                (0b000, false) => (0, input_word.leading_zeros() as u16),
+                (0b000, true) => (input_word.leading_zeros() as u16, (input_word == 0) as u16),
+                // CTZ: ctz(low) + [low == 0] * ctz(high)
+                (0b001, false) => (input_word.trailing_zeros() as u16, (input_word == 0) as u16),
+                (0b001, true) => (0, input_word.trailing_zeros() as u16),
+                // CPOP: cpop(low) + cpop(high)
+                (0b010, _) => (input_word.count_ones() as u16, 0),
+                // SEXT.B and SEXT.H only depend on the low half
+                (0b100, false) => {
+                    let extended = input_word as u8 as i8 as i32 as u32;
+                    (extended as u16, (extended >> 16) as u16)
+                }
+                (0b101, false) => {
+                    let extended = input_word as i16 as i32 as u32;
+                    (extended as u16, (extended >> 16) as u16)
+                }
+                _ => (0, 0),
+            };
+
+            let mut result = [F::ZERO; 3];
+            result[0] = F::from_u64_unchecked(first as u64);
+            result[1] = F::from_u64_unchecked(second as u64);
+
+            (a as usize, result)
+        },
+        Some(first_key_index_gen_fn::<F, 3>),
+        id,
+    )
+}
+
+pub fn create_byte_wise_bitmanip_table<F: PrimeField>(id: u32) -> LookupTable<F, 3> {
+    // take 16 bits of input half-word || 2 bits of mode, where mode is
+    // 0 for ORC.B, 1 for BREV8 and 2 for byte swap (REV8 over the half-word)
+    let keys = key_for_continuous_log2_range(16 + 2);
+
+    let table_name = "Byte-wise bitmanip table".to_string();
+    LookupTable::create_table_from_key_and_pure_generation_fn(
+        &keys,
+        table_name,
+        1,
+        |keys| {
+            let a = keys[0].as_u64_reduced();
+            let input_word = a as u16;
+            let mode = a >> 16;
+
+            let [low, high] = input_word.to_le_bytes();
+            let output = match mode {
+                0 => {
+                    let orc = |x: u8| if x != 0 { 0xffu8 } else { 0u8 };
+                    u16::from_le_bytes([orc(low), orc(high)])
+                }
+                1 => u16::from_le_bytes([low.reverse_bits(), high.reverse_bits()]),
+                2 => input_word.swap_bytes(),
+                _ => 0,
+            };
+
+            let mut result = [F::ZERO; 3];
+            result[0] = F::from_u64_unchecked(output as u64);
+
+            (a as usize, result)
+        },
+        Some(first_key_index_gen_fn::<F, 3>),
+        id,
+    )
+}
+
 pub const TABLE_TYPES_UPPER_BOUNDS: usize = const {
     if TOTAL_NUM_OF_TABLES < 48 {
         TOTAL_NUM_OF_TABLES
@@ -2012,6 +2126,18 @@ impl<F: PrimeField> TableDriver<F> {
         index
     }
 
+    /// Same as `enforce_values_and_get_absolute_index`, but returns `None` instead of panicking
+    /// if there is no such table or the table doesn't contain the tuple
+    pub fn try_get_absolute_index<const N: usize>(&self, keys: &[F; N], id: u32) -> Option<usize> {
+        let table = self.tables.get(id as usize)?;
+        if table.width() != N {
+            return None;
+        }
+        let index = table.try_lookup_row(keys)?;
+
+        Some(index + self.get_start_table_offset(id))
+    }
+
     // #[inline(always)]
     // pub fn lookup_row(&self, row: &[F], id: u32) -> bool {
     //     self.tables[id as usize].lookup_row(row).is_some()
@@ -2127,4 +2253,62 @@ mod test {
         }
         dbg!(vec_time.elapsed());
     }
+
+    #[test]
+    fn test_bitmanip_tables() {
+        let bit_count_table = TableType::BitCountAndSignExtend.generate_table::<Mersenne31Field>();
+        let byte_wise_table = TableType::ByteWiseBitmanip.generate_table::<Mersenne31Field>();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let lookup = |table: &LookupWrapper<Mersenne31Field>, key: u64| {
+            table
+                .lookup_value::<2>(&[Mersenne31Field::from_u64_unchecked(key)])
+                .map(|el| el.as_u64_reduced())
+        };
+
+        let mut inputs: Vec<u32> = vec![0, 1, 0x8000_0000, 0xffff_ffff, 0x0001_0000, 0x0000_ffff];
+        inputs.extend((0..1000).map(|_| rng.random::<u32>() >> rng.random_range(0..32)));
+
+        for input in inputs {
+            let [low, high] = [input as u64 & 0xffff, (input >> 16) as u64];
+
+            for (selector, expected) in [
+                (0b000u64, input.leading_zeros()),
+                (0b001, input.trailing_zeros()),
+                (0b010, input.count_ones()),
+                (0b100, input as u8 as i8 as i32 as u32),
+                (0b101, input as u16 as i16 as i32 as u32),
+            ] {
+                // same combination as in the circuit
+                let [a0, a1] = lookup(&bit_count_table, low | (selector << 16));
+                let [b0, b1] = lookup(&bit_count_table, high | (selector << 16) | (1 << 19));
+                let result_low = a0 + b0 + a1 * b1;
+                let result_high = if selector & 0b100 != 0 { a1 } else { 0 };
+                assert_eq!(
+                    result_low | (result_high << 16),
+                    expected as u64,
+                    "selector {} for input 0x{:08x}",
+                    selector,
+                    input
+                );
+            }
+
+            let bytes = input.to_le_bytes();
+            for (mode, expected) in [
+                (
+                    0u64,
+                    u32::from_le_bytes(bytes.map(|el| if el != 0 { 0xff } else { 0 })),
+                ),
+                (1, u32::from_le_bytes(bytes.map(|el| el.reverse_bits()))),
+            ] {
+                let [out_low, _] = lookup(&byte_wise_table, low | (mode << 16));
+                let [out_high, _] = lookup(&byte_wise_table, high | (mode << 16));
+                assert_eq!(out_low | (out_high << 16), expected as u64);
+            }
+            // REV8 additionally swaps half-words
+            let [out_low, _] = lookup(&byte_wise_table, low | (2 << 16));
+            let [out_high, _] = lookup(&byte_wise_table, high | (2 << 16));
+            assert_eq!(out_high | (out_low << 16), input.swap_bytes() as u64);
+        }
+    }
 }
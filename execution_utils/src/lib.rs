@@ -6,11 +6,16 @@ use std::collections::BTreeMap;
 
 use risc_v_simulator::abstractions::non_determinism::QuasiUARTSource;
 use risc_v_simulator::cycle::MachineConfig;
+use risc_v_simulator::elf::ProgramImage;
 use serde::{Deserialize, Serialize};
 use trace_and_split::FinalRegisterValue;
 use verifier_common::cs::utils::split_timestamp;
 use verifier_common::prover::definitions::MerkleTreeCap;
 use verifier_common::prover::fft::GoodAllocator;
+use verifier_common::prover::proof_encoding::{
+    decode_with_headers, encode_with_headers, EncodedPayload, ProofCircuitType, ProofEncodingError,
+    ProofHeader, ProofV1, PROGRAM_PROOF_MAGIC,
+};
 use verifier_common::prover::prover_stages::{flatten_merkle_caps, Proof};
 use verifier_common::transcript::Blake2sBufferingTranscript;
 
@@ -84,13 +89,29 @@ pub fn universal_circuit_no_delegation_verifier_vk() -> VerificationKey {
     .unwrap()
 }
 
-pub fn get_padded_binary(binary: &[u8]) -> Vec<u32> {
-    let mut bytecode = binary
+// Both flat binaries and ELF executables are accepted. Circuits zero-initialize RAM, so ELF
+// must not have initialized data there
+fn get_unpadded_binary(binary: &[u8]) -> Vec<u32> {
+    if ProgramImage::is_elf(binary) {
+        let image = trace_and_split::memory_image_from_elf(binary)
+            .unwrap_or_else(|e| panic!("ELF can not be used for proving: {}", e));
+        assert!(
+            image.ram.is_empty(),
+            "ELF with initialized data in RAM can not be used for proving"
+        );
+        return image.rom;
+    }
+
+    binary
         .as_chunks::<4>()
         .0
         .iter()
         .map(|el| u32::from_le_bytes(*el))
-        .collect();
+        .collect()
+}
+
+pub fn get_padded_binary(binary: &[u8]) -> Vec<u32> {
+    let mut bytecode = get_unpadded_binary(binary);
     trace_and_split::setups::pad_bytecode_for_proving(&mut bytecode);
 
     bytecode
@@ -212,6 +233,78 @@ impl ProgramProof {
 
         responses
     }
+
+    /// Compact binary encoding. `headers` must contain exactly one header for the main circuit
+    /// that produced all the base layer proofs, and one header for every delegation type present.
+    pub fn encode(&self, headers: &[ProofHeader]) -> Vec<u8> {
+        let num_main_headers = headers
+            .iter()
+            .filter(|header| header.circuit_type != ProofCircuitType::Delegation)
+            .count();
+        assert_eq!(
+            num_main_headers, 1,
+            "expected exactly one main circuit header"
+        );
+        for delegation_type in self.delegation_proofs.keys() {
+            assert!(
+                headers
+                    .iter()
+                    .any(|header| header.circuit_type == ProofCircuitType::Delegation
+                        && header.delegation_type as u32 == *delegation_type),
+                "no header for delegation type {}",
+                delegation_type
+            );
+        }
+
+        encode_with_headers(PROGRAM_PROOF_MAGIC, headers, self)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Vec<ProofHeader>, Self), ProofEncodingError> {
+        decode_with_headers(PROGRAM_PROOF_MAGIC, data)
+    }
+}
+
+/// `ProgramProof` as encoded by version 1
+#[derive(Deserialize)]
+pub struct ProgramProofV1 {
+    pub base_layer_proofs: Vec<ProofV1>,
+    pub delegation_proofs: BTreeMap<u32, Vec<ProofV1>>,
+    pub register_final_values: Vec<FinalRegisterValue>,
+    pub end_params: [u32; 8],
+    pub recursion_chain_preimage: Option<[u32; 16]>,
+    pub recursion_chain_hash: Option<[u32; 8]>,
+}
+
+impl EncodedPayload for ProgramProof {
+    type V1 = ProgramProofV1;
+
+    fn from_v1(payload: Self::V1) -> Self {
+        let ProgramProofV1 {
+            base_layer_proofs,
+            delegation_proofs,
+            register_final_values,
+            end_params,
+            recursion_chain_preimage,
+            recursion_chain_hash,
+        } = payload;
+
+        Self {
+            base_layer_proofs: base_layer_proofs.into_iter().map(Proof::from).collect(),
+            delegation_proofs: delegation_proofs
+                .into_iter()
+                .map(|(delegation_type, proofs)| {
+                    (
+                        delegation_type,
+                        proofs.into_iter().map(Proof::from).collect(),
+                    )
+                })
+                .collect(),
+            register_final_values,
+            end_params,
+            recursion_chain_preimage,
+            recursion_chain_hash,
+        }
+    }
 }
 
 /// VerificationKey represents the verification key for a specific machine type and bytecode hash.
@@ -236,11 +329,16 @@ pub fn run_verifier_binary(binary: &[u8], reads: Vec<u32>) -> Option<[u32; 16]>
 
     let source = QuasiUARTSource::new_with_reads(reads);
 
-    let final_state = risc_v_simulator::runner::run_simple_for_num_cycles::<
+    let (final_state, outcome) = risc_v_simulator::runner::run_simple_for_num_cycles::<
         _,
         IMIsaConfigWithAllDelegations,
     >(binary, 0, 1 << 30, source);
 
+    if !outcome.is_finished() {
+        println!("Execution failed: {}", outcome);
+        return None;
+    }
+
     if final_state.pc != final_pc {
         println!(
             "Execution ended on the unexpected PC: was expecting 0x{:08x}, but ended at {:08x}",
@@ -257,14 +355,9 @@ pub fn run_verifier_binary(binary: &[u8], reads: Vec<u32>) -> Option<[u32; 16]>
 }
 
 pub fn find_binary_exit_point(binary: &[u8]) -> u32 {
-    assert!(binary.len() % 4 == 0);
+    assert!(ProgramImage::is_elf(binary) || binary.len() % 4 == 0);
 
-    let binary: Vec<u32> = binary
-        .as_chunks::<4>()
-        .0
-        .iter()
-        .map(|el| u32::from_le_bytes(*el))
-        .collect();
+    let binary = get_unpadded_binary(binary);
 
     let mut candidates = vec![];
 
@@ -413,6 +506,57 @@ mod test {
         find_binary_exit_point(&data)
     }
 
+    #[test]
+    fn test_program_proof_encoding_round_trip() {
+        let program_proof = ProgramProof {
+            base_layer_proofs: vec![],
+            delegation_proofs: BTreeMap::from([(1991, vec![])]),
+            register_final_values: (0..32)
+                .map(|idx| FinalRegisterValue {
+                    value: idx,
+                    last_access_timestamp: idx as u64 * 4,
+                })
+                .collect(),
+            end_params: [1, 2, 3, 4, 5, 6, 7, 8],
+            recursion_chain_preimage: Some([9; 16]),
+            recursion_chain_hash: None,
+        };
+        let headers = [
+            ProofHeader {
+                circuit_type: ProofCircuitType::RiscVReduced,
+                delegation_type: 0,
+                layout_hash: [1; 8],
+            },
+            ProofHeader {
+                circuit_type: ProofCircuitType::Delegation,
+                delegation_type: 1991,
+                layout_hash: [2; 8],
+            },
+        ];
+        let encoded = program_proof.encode(&headers);
+        let (decoded_headers, decoded) = ProgramProof::decode(&encoded).unwrap();
+        assert_eq!(decoded_headers, headers);
+        assert_eq!(
+            serde_json::to_string(&decoded).unwrap(),
+            serde_json::to_string(&program_proof).unwrap()
+        );
+
+        // single proof encoding is not accepted as the program proof
+        assert!(matches!(
+            ProgramProof::decode(b"ABPF"),
+            Err(ProofEncodingError::InvalidMagic)
+        ));
+
+        // version 1 has the same layout if there are no proofs inside
+        let mut encoded_v1 = encoded.clone();
+        encoded_v1[4..8].copy_from_slice(&1u32.to_le_bytes());
+        let (_, decoded) = ProgramProof::decode(&encoded_v1).unwrap();
+        assert_eq!(
+            serde_json::to_string(&decoded).unwrap(),
+            serde_json::to_string(&program_proof).unwrap()
+        );
+    }
+
     #[test]
     fn test_binaries() {
         run_on_binary("../tools/verifier/base_layer.bin");
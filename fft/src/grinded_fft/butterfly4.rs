@@ -2,8 +2,7 @@ use super::*;
 use crate::grinded_fft::utils::Load;
 use crate::grinded_fft::utils::LoadStore;
 use butterfly2::Butterfly2;
-use field::FieldLikeVectorized;
-use field::Mersenne31ComplexVectorized;
+use field::backend::mul_assign_complex_interleaved_by_constant_slice;
 use field::Mersenne31ComplexVectorizedInterleaved;
 use field::{Mersenne31Complex, Mersenne31Field};
 use trace_holder::RowMajorTraceView;
@@ -113,15 +112,9 @@ pub(crate) unsafe fn butterfly_4_vectorized2_full_trace<const N: usize>(
         let mut row2 = trace.get_row_mut_vectorized(idx + 2 * num_columns);
         let mut row3 = trace.get_row_mut_vectorized(idx + 3 * num_columns);
 
-        let twiddle0 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 0));
-        let twiddle1 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 1));
-        let twiddle2 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 2));
-
-        for i in 0..row1.len() {
-            row1[i] = row1[i] * twiddle0;
-            row2[i] = row2[i] * twiddle1;
-            row3[i] = row3[i] * twiddle2;
-        }
+        mul_assign_complex_interleaved_by_constant_slice(row1, twiddles.load(tw_idx + 0));
+        mul_assign_complex_interleaved_by_constant_slice(row2, twiddles.load(tw_idx + 1));
+        mul_assign_complex_interleaved_by_constant_slice(row3, twiddles.load(tw_idx + 2));
 
         butterfly4.perform_fft_contiguous_vectorized2_full_trace(
             &mut row0, &mut row1, &mut row2, &mut row3,
@@ -150,15 +143,9 @@ pub(crate) unsafe fn butterfly_4_vectorized2_mirrored_full_trace<const N: usize>
             &mut row0, &mut row1, &mut row2, &mut row3,
         );
 
-        let twiddle0 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 0));
-        let twiddle1 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 1));
-        let twiddle2 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 2));
-
-        for i in 0..row1.len() {
-            row1[i] = row1[i] * twiddle0;
-            row2[i] = row2[i] * twiddle1;
-            row3[i] = row3[i] * twiddle2;
-        }
+        mul_assign_complex_interleaved_by_constant_slice(row1, twiddles.load(tw_idx + 0));
+        mul_assign_complex_interleaved_by_constant_slice(row2, twiddles.load(tw_idx + 1));
+        mul_assign_complex_interleaved_by_constant_slice(row3, twiddles.load(tw_idx + 2));
     }
 }
 
@@ -177,28 +164,33 @@ pub(crate) unsafe fn butterfly_4_vectorized2_full_trace_parallel<const N: usize>
             let chunk_start = geometry.get_chunk_start_pos(thread_idx);
             let chunk_size = geometry.get_chunk_size(thread_idx);
             Worker::smart_spawn(scope, thread_idx == geometry.len() - 1, move |_| {
-                for idx in chunk_start..chunk_start + chunk_size {
-                    let tw_idx = idx * 3;
-
-                    let mut row0 = trace.get_row_mut_vectorized(idx + 0 * num_columns);
-                    let mut row1 = trace.get_row_mut_vectorized(idx + 1 * num_columns);
-                    let mut row2 = trace.get_row_mut_vectorized(idx + 2 * num_columns);
-                    let mut row3 = trace.get_row_mut_vectorized(idx + 3 * num_columns);
-
-                    let twiddle0 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 0));
-                    let twiddle1 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 1));
-                    let twiddle2 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 2));
-
-                    for i in 0..row1.len() {
-                        row1[i] = row1[i] * twiddle0;
-                        row2[i] = row2[i] * twiddle1;
-                        row3[i] = row3[i] * twiddle2;
+                field::with_vector_backend(|| {
+                    for idx in chunk_start..chunk_start + chunk_size {
+                        let tw_idx = idx * 3;
+
+                        let mut row0 = trace.get_row_mut_vectorized(idx + 0 * num_columns);
+                        let mut row1 = trace.get_row_mut_vectorized(idx + 1 * num_columns);
+                        let mut row2 = trace.get_row_mut_vectorized(idx + 2 * num_columns);
+                        let mut row3 = trace.get_row_mut_vectorized(idx + 3 * num_columns);
+
+                        mul_assign_complex_interleaved_by_constant_slice(
+                            row1,
+                            twiddles.load(tw_idx + 0),
+                        );
+                        mul_assign_complex_interleaved_by_constant_slice(
+                            row2,
+                            twiddles.load(tw_idx + 1),
+                        );
+                        mul_assign_complex_interleaved_by_constant_slice(
+                            row3,
+                            twiddles.load(tw_idx + 2),
+                        );
+
+                        butterfly4.perform_fft_contiguous_vectorized2_full_trace(
+                            &mut row0, &mut row1, &mut row2, &mut row3,
+                        );
                     }
-
-                    butterfly4.perform_fft_contiguous_vectorized2_full_trace(
-                        &mut row0, &mut row1, &mut row2, &mut row3,
-                    );
-                }
+                });
             });
         }
     });
@@ -219,28 +211,33 @@ pub(crate) unsafe fn butterfly_4_vectorized2_mirrored_full_trace_parallel<const
             let chunk_start = geometry.get_chunk_start_pos(thread_idx);
             let chunk_size = geometry.get_chunk_size(thread_idx);
             Worker::smart_spawn(scope, thread_idx == geometry.len() - 1, move |_| {
-                for idx in chunk_start..chunk_start + chunk_size {
-                    let tw_idx = idx * 3;
-
-                    let mut row0 = trace.get_row_mut_vectorized(idx + 0 * num_columns);
-                    let mut row1 = trace.get_row_mut_vectorized(idx + 1 * num_columns);
-                    let mut row2 = trace.get_row_mut_vectorized(idx + 2 * num_columns);
-                    let mut row3 = trace.get_row_mut_vectorized(idx + 3 * num_columns);
-
-                    butterfly4.perform_fft_contiguous_vectorized2_full_trace(
-                        &mut row0, &mut row1, &mut row2, &mut row3,
-                    );
-
-                    let twiddle0 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 0));
-                    let twiddle1 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 1));
-                    let twiddle2 = Mersenne31ComplexVectorized::constant(twiddles.load(tw_idx + 2));
-
-                    for i in 0..row1.len() {
-                        row1[i] = row1[i] * twiddle0;
-                        row2[i] = row2[i] * twiddle1;
-                        row3[i] = row3[i] * twiddle2;
+                field::with_vector_backend(|| {
+                    for idx in chunk_start..chunk_start + chunk_size {
+                        let tw_idx = idx * 3;
+
+                        let mut row0 = trace.get_row_mut_vectorized(idx + 0 * num_columns);
+                        let mut row1 = trace.get_row_mut_vectorized(idx + 1 * num_columns);
+                        let mut row2 = trace.get_row_mut_vectorized(idx + 2 * num_columns);
+                        let mut row3 = trace.get_row_mut_vectorized(idx + 3 * num_columns);
+
+                        butterfly4.perform_fft_contiguous_vectorized2_full_trace(
+                            &mut row0, &mut row1, &mut row2, &mut row3,
+                        );
+
+                        mul_assign_complex_interleaved_by_constant_slice(
+                            row1,
+                            twiddles.load(tw_idx + 0),
+                        );
+                        mul_assign_complex_interleaved_by_constant_slice(
+                            row2,
+                            twiddles.load(tw_idx + 1),
+                        );
+                        mul_assign_complex_interleaved_by_constant_slice(
+                            row3,
+                            twiddles.load(tw_idx + 2),
+                        );
                     }
-                }
+                });
             });
         }
     });
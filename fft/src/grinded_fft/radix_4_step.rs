@@ -7,8 +7,7 @@ use butterfly4::{
     butterfly_4_vectorized2_mirrored_full_trace,
     butterfly_4_vectorized2_mirrored_full_trace_parallel,
 };
-use field::FieldLikeVectorized;
-use field::Mersenne31ComplexVectorized;
+use field::backend::mul_assign_complex_interleaved_by_constant_slice;
 use field::{Mersenne31Complex, Mersenne31Field};
 use std::sync::Arc;
 use trace_holder::RowMajorTraceView;
@@ -196,11 +195,10 @@ impl Radix4 {
                         &scales_chunk[j * self.base_len..(j + 1) * self.base_len];
                     unsafe {
                         for (idx, scale) in scales_chunk_base.iter().enumerate() {
-                            let scale_vec = Mersenne31ComplexVectorized::constant(*scale);
-                            chunk_base
-                                .get_row_mut_vectorized(idx)
-                                .iter_mut()
-                                .for_each(|x| *x = *x * scale_vec);
+                            mul_assign_complex_interleaved_by_constant_slice(
+                                chunk_base.get_row_mut_vectorized(idx),
+                                *scale,
+                            );
                         }
 
                         if self.base_len == 16 {
@@ -357,15 +355,17 @@ impl Radix4 {
                     let chunk_size = geometry.get_chunk_size(thread_idx);
                     // println!("thread_idx: {:?}, chunk_start: {:?}, chunk_size: {:?}", thread_idx, chunk_start, chunk_size);
                     Worker::smart_spawn(scope, thread_idx == geometry.len() - 1, move |_| {
-                        for idx in chunk_start..chunk_start + chunk_size {
-                            // println!("range: {:?}", idx * cross_fft_len..(idx + 1) * cross_fft_len);
-                            self.cross_fft_recursive_mirrored_full_trace(
-                                &trace.row_view(idx * cross_fft_len..(idx + 1) * cross_fft_len),
-                                cross_fft_len,
-                                butterfly4,
-                                twiddle_offset,
-                            );
-                        }
+                        field::with_vector_backend(|| {
+                            for idx in chunk_start..chunk_start + chunk_size {
+                                // println!("range: {:?}", idx * cross_fft_len..(idx + 1) * cross_fft_len);
+                                self.cross_fft_recursive_mirrored_full_trace(
+                                    &trace.row_view(idx * cross_fft_len..(idx + 1) * cross_fft_len),
+                                    cross_fft_len,
+                                    butterfly4,
+                                    twiddle_offset,
+                                );
+                            }
+                        });
                     });
                 }
             });
@@ -396,16 +396,18 @@ impl Radix4 {
                     let chunk_size = geometry.get_chunk_size(thread_idx);
                     // println!("thread_idx: {:?}, chunk_start: {:?}, chunk_size: {:?}", thread_idx, chunk_start, chunk_size);
                     Worker::smart_spawn(scope, thread_idx == geometry.len() - 1, move |_| {
-                        for idx in chunk_start..chunk_start + chunk_size {
-                            // println!("range: {:?}", idx * cross_fft_len..(idx + 1) * cross_fft_len);
-                            self.cross_fft_recursive_scaled_full_trace(
-                                &trace.row_view(idx * cross_fft_len..(idx + 1) * cross_fft_len),
-                                &scales[idx * cross_fft_len..(idx + 1) * cross_fft_len],
-                                cross_fft_len,
-                                butterfly4,
-                                0,
-                            );
-                        }
+                        field::with_vector_backend(|| {
+                            for idx in chunk_start..chunk_start + chunk_size {
+                                // println!("range: {:?}", idx * cross_fft_len..(idx + 1) * cross_fft_len);
+                                self.cross_fft_recursive_scaled_full_trace(
+                                    &trace.row_view(idx * cross_fft_len..(idx + 1) * cross_fft_len),
+                                    &scales[idx * cross_fft_len..(idx + 1) * cross_fft_len],
+                                    cross_fft_len,
+                                    butterfly4,
+                                    0,
+                                );
+                            }
+                        });
                     });
                 }
             });
@@ -608,4 +610,59 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_ifft_all_simd_backends() {
+        use cpu_features::{force_simd_backend, SimdBackend};
+
+        let trace_len: usize = 1 << 9;
+        let num_columns = 32;
+
+        let mut rng = rand::rng();
+        let input: Vec<Mersenne31Field> = (0..trace_len * num_columns)
+            .map(|_| Mersenne31Field::random_element(&mut rng))
+            .collect();
+
+        let fft_inverse = Radix4::new_with_base(trace_len, FftDirection::Inverse);
+        let butterfly4 = Butterfly4::new(fft_inverse.direction);
+        let run_ifft = || {
+            let trace = RowMajorTrace::<Mersenne31Field, TEST_WIDTH, _>::new_zeroed_for_size(
+                trace_len,
+                num_columns,
+                Global,
+            );
+            let mut row_view = trace.row_view(0..trace_len);
+            for values in input.chunks(num_columns) {
+                row_view.current_row().copy_from_slice(values);
+                row_view.advance_row();
+            }
+            field::with_vector_backend(|| {
+                fft_inverse.cross_fft_recursive_mirrored_full_trace(
+                    &mut trace.row_view(0..trace.len()),
+                    trace_len,
+                    &butterfly4,
+                    fft_inverse.twiddles.len(),
+                )
+            });
+
+            let mut result = Vec::with_capacity(trace_len * num_columns);
+            let mut row_view = trace.row_view(0..trace_len);
+            for _ in 0..trace_len {
+                result.extend_from_slice(row_view.current_row_ref());
+                row_view.advance_row();
+            }
+            result
+        };
+
+        force_simd_backend(Some(SimdBackend::Scalar));
+        let expected = run_ifft();
+        for backend in SimdBackend::ALL {
+            if !backend.is_supported() {
+                continue;
+            }
+            force_simd_backend(Some(backend));
+            assert_eq!(run_ifft(), expected, "{} backend diverged", backend);
+        }
+        force_simd_backend(None);
+    }
 }
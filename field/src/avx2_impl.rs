@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Portions derived from Plonky3 and adapted by Matter Labs.
+// © 2024 Polygon Labs – original; © 2025 Matter Labs - adapted for RISC-V.
+
+//! AVX2 kernels for the slice operations in `backend`. Every vector of `WIDTH` elements is processed as two
+//! 256-bit halves. Callers must check that the host supports AVX2.
+
+use crate::Mersenne31Field;
+use core::arch::x86_64::{self, __m256i};
+use core::mem::transmute;
+
+const WIDTH: usize = 16;
+const P: __m256i = unsafe { transmute::<[u32; 8], _>([0x7fffffff; 8]) };
+const ODDS: i32 = 0b10101010;
+
+type Lanes = [Mersenne31Field; WIDTH];
+
+#[inline]
+#[target_feature(enable = "avx2")]
+fn to_vectors(value: &Lanes) -> [__m256i; 2] {
+    unsafe { transmute(*value) }
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+fn from_vectors(value: [__m256i; 2]) -> Lanes {
+    unsafe { transmute(value) }
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+fn moveldup_epi32(a: __m256i) -> __m256i {
+    x86_64::_mm256_shuffle_epi32::<0b10_10_00_00>(a)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+fn movehdup_epi32(a: __m256i) -> __m256i {
+    x86_64::_mm256_shuffle_epi32::<0b11_11_01_01>(a)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+fn add_vec(lhs: __m256i, rhs: __m256i) -> __m256i {
+    // inputs are in 0..=P, so sum fits into 32 bits and min picks the reduced value
+    let t = x86_64::_mm256_add_epi32(lhs, rhs);
+    let u = x86_64::_mm256_sub_epi32(t, P);
+    x86_64::_mm256_min_epu32(t, u)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+fn sub_vec(lhs: __m256i, rhs: __m256i) -> __m256i {
+    let t = x86_64::_mm256_sub_epi32(lhs, rhs);
+    let u = x86_64::_mm256_add_epi32(t, P);
+    x86_64::_mm256_min_epu32(t, u)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+fn mul_vec(lhs: __m256i, rhs: __m256i) -> __m256i {
+    // 32x32 multiplications only use even lanes, so odd ones are shifted down. Both halves
+    // are doubled in the process, so low 32 bits of the products are 2 * (ab mod 2^31),
+    // and high 32 bits are ab >> 31
+    let lhs_even_dbl = x86_64::_mm256_add_epi32(lhs, lhs);
+    let lhs_odd_dbl = x86_64::_mm256_srli_epi64::<31>(lhs);
+    let rhs_odd = movehdup_epi32(rhs);
+    let mul_even_dbl = x86_64::_mm256_mul_epu32(lhs_even_dbl, rhs);
+    let mul_odd_dbl = x86_64::_mm256_mul_epu32(lhs_odd_dbl, rhs_odd);
+    let mul_lo_dbl = x86_64::_mm256_blend_epi32::<ODDS>(mul_even_dbl, moveldup_epi32(mul_odd_dbl));
+    let mul_hi = x86_64::_mm256_blend_epi32::<ODDS>(movehdup_epi32(mul_even_dbl), mul_odd_dbl);
+    let mul_lo = x86_64::_mm256_srli_epi32::<1>(mul_lo_dbl);
+    add_vec(mul_lo, mul_hi)
+}
+
+/// Complex multiplication of (real, imaginary) pairs laid out in adjacent lanes
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) fn mul_complex_interleaved(lhs: &Lanes, rhs: &Lanes) -> Lanes {
+    let lhs = to_vectors(lhs);
+    let rhs = to_vectors(rhs);
+    let mut result = [x86_64::_mm256_setzero_si256(); 2];
+    for i in 0..2 {
+        // (a0, a0) * (b0, b1) +- (a1, a1) * (b1, b0)
+        let re = moveldup_epi32(lhs[i]);
+        let im = movehdup_epi32(lhs[i]);
+        let swapped = x86_64::_mm256_shuffle_epi32::<0b10_11_00_01>(rhs[i]);
+        let t0 = mul_vec(re, rhs[i]);
+        let t1 = mul_vec(im, swapped);
+        result[i] = x86_64::_mm256_blend_epi32::<ODDS>(sub_vec(t0, t1), add_vec(t0, t1));
+    }
+    from_vectors(result)
+}
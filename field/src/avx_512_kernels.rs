@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Portions derived from Plonky3 and adapted by Matter Labs.
+// © 2024 Polygon Labs – original; © 2025 Matter Labs - adapted for RISC-V.
+
+//! AVX-512 kernels for the slice operations in `backend`. Same interface as `avx2_impl`, but every vector fits
+//! into a single register.
+
+use crate::Mersenne31Field;
+use core::arch::x86_64::{self, __m512i, __mmask16};
+use core::mem::transmute;
+
+const WIDTH: usize = 16;
+const P: __m512i = unsafe { transmute::<[u32; WIDTH], _>([0x7fffffff; WIDTH]) };
+const EVENS: __mmask16 = 0b0101010101010101;
+const ODDS: __mmask16 = 0b1010101010101010;
+
+type Lanes = [Mersenne31Field; WIDTH];
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn to_vector(value: &Lanes) -> __m512i {
+    unsafe { transmute(*value) }
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn from_vector(value: __m512i) -> Lanes {
+    unsafe { transmute(value) }
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn movehdup_epi32(a: __m512i) -> __m512i {
+    x86_64::_mm512_castps_si512(x86_64::_mm512_movehdup_ps(x86_64::_mm512_castsi512_ps(a)))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn moveldup_epi32(a: __m512i) -> __m512i {
+    x86_64::_mm512_castps_si512(x86_64::_mm512_moveldup_ps(x86_64::_mm512_castsi512_ps(a)))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn mask_movehdup_epi32(src: __m512i, k: __mmask16, a: __m512i) -> __m512i {
+    let src = x86_64::_mm512_castsi512_ps(src);
+    let a = x86_64::_mm512_castsi512_ps(a);
+    x86_64::_mm512_castps_si512(x86_64::_mm512_mask_movehdup_ps(src, k, a))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn mask_moveldup_epi32(src: __m512i, k: __mmask16, a: __m512i) -> __m512i {
+    let src = x86_64::_mm512_castsi512_ps(src);
+    let a = x86_64::_mm512_castsi512_ps(a);
+    x86_64::_mm512_castps_si512(x86_64::_mm512_mask_moveldup_ps(src, k, a))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn add_vec(lhs: __m512i, rhs: __m512i) -> __m512i {
+    let t = x86_64::_mm512_add_epi32(lhs, rhs);
+    let u = x86_64::_mm512_sub_epi32(t, P);
+    x86_64::_mm512_min_epu32(t, u)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn sub_vec(lhs: __m512i, rhs: __m512i) -> __m512i {
+    let t = x86_64::_mm512_sub_epi32(lhs, rhs);
+    let u = x86_64::_mm512_add_epi32(t, P);
+    x86_64::_mm512_min_epu32(t, u)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn mul_vec(lhs: __m512i, rhs: __m512i) -> __m512i {
+    let lhs_even_dbl = x86_64::_mm512_add_epi32(lhs, lhs);
+    let lhs_odd_dbl = x86_64::_mm512_srli_epi64::<31>(lhs);
+    let rhs_odd = movehdup_epi32(rhs);
+    let mul_even_dbl = x86_64::_mm512_mul_epu32(lhs_even_dbl, rhs);
+    let mul_odd_dbl = x86_64::_mm512_mul_epu32(lhs_odd_dbl, rhs_odd);
+    let mul_lo_dbl = mask_moveldup_epi32(mul_even_dbl, ODDS, mul_odd_dbl);
+    let mul_hi = mask_movehdup_epi32(mul_odd_dbl, EVENS, mul_even_dbl);
+    let mul_lo = x86_64::_mm512_srli_epi32::<1>(mul_lo_dbl);
+    add_vec(mul_lo, mul_hi)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn mul_complex_interleaved(lhs: &Lanes, rhs: &Lanes) -> Lanes {
+    let lhs = to_vector(lhs);
+    let rhs = to_vector(rhs);
+    let re = moveldup_epi32(lhs);
+    let im = movehdup_epi32(lhs);
+    let swapped = x86_64::_mm512_shuffle_epi32::<0b10_11_00_01>(rhs);
+    let t0 = mul_vec(re, rhs);
+    let t1 = mul_vec(im, swapped);
+    from_vector(x86_64::_mm512_mask_blend_epi32(
+        ODDS,
+        sub_vec(t0, t1),
+        add_vec(t0, t1),
+    ))
+}
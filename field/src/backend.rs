@@ -0,0 +1,251 @@
+//! Selection of the SIMD kernels behind the vectorized field types. Kernels are picked at
+//! runtime from the features of the host CPU, so the same binary runs the AVX2 kernels on AVX2
+//! machines, AVX-512 kernels where those are available and NEON kernels on aarch64.
+//!
+//! Arithmetic of the vectorized types itself stays generic and inlinable. Backend is resolved
+//! once per batch of work with `with_vector_backend`, that compiles the batch with the features
+//! of the selected backend, or once per slice operation (`*_slice` functions below) that run the
+//! hand-written kernels in the FFT hot loops.
+
+use crate::{
+    FieldLikeVectorized, Mersenne31Complex, Mersenne31ComplexVectorized,
+    Mersenne31ComplexVectorizedInterleaved,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum VectorBackend {
+    Generic = 1,
+    Avx2 = 2,
+    Avx512 = 3,
+    Neon = 4,
+}
+
+/// Backend used by the vectorized field types. Follows `cpu_features::simd_backend`, so it is
+/// detected once and can be overridden with `cpu_features::force_simd_backend`.
+#[inline(always)]
+pub fn vector_backend() -> VectorBackend {
+    if cfg!(target_feature = "avx512f") {
+        // vector types are compiled for AVX-512 directly
+        return VectorBackend::Avx512;
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    return match cpu_features::simd_backend() {
+        cpu_features::SimdBackend::Scalar => VectorBackend::Generic,
+        cpu_features::SimdBackend::Neon => VectorBackend::Neon,
+        cpu_features::SimdBackend::Avx2 => VectorBackend::Avx2,
+        cpu_features::SimdBackend::Avx512 => VectorBackend::Avx512,
+    };
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    VectorBackend::Generic
+}
+
+/// Runs `f` compiled with the target features of the selected backend, so vectorized arithmetic
+/// inlined into it uses wide registers. Intended for whole batches of work, e.g. FFT passes
+/// over a trace chunk, not for single operations.
+#[inline(always)]
+pub fn with_vector_backend<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(all(target_arch = "x86_64", not(target_feature = "avx512f")))]
+    {
+        #[target_feature(enable = "avx512f")]
+        fn run_avx512<R>(f: impl FnOnce() -> R) -> R {
+            f()
+        }
+
+        #[target_feature(enable = "avx2")]
+        fn run_avx2<R>(f: impl FnOnce() -> R) -> R {
+            f()
+        }
+
+        match vector_backend() {
+            VectorBackend::Avx512 => return unsafe { run_avx512(f) },
+            VectorBackend::Avx2 => return unsafe { run_avx2(f) },
+            _ => {}
+        }
+    }
+
+    // NEON is a baseline feature of aarch64, and AVX-512 builds are compiled for it already
+    f()
+}
+
+/// Loops over the slice with the kernels of one backend. Every kernel is inlined into the loop,
+/// as it's compiled with the same target feature.
+#[cfg(any(
+    all(target_arch = "x86_64", not(target_feature = "avx512f")),
+    target_arch = "aarch64"
+))]
+macro_rules! slice_kernels {
+    ($name:ident, $kernels:ident, $feature:literal) => {
+        mod $name {
+            use super::*;
+            use crate::$kernels as kernels;
+
+            #[target_feature(enable = $feature)]
+            pub(super) fn mul_assign_complex_interleaved_by_constant(
+                dst: &mut [Mersenne31ComplexVectorizedInterleaved],
+                by: Mersenne31Complex,
+            ) {
+                let by = Mersenne31ComplexVectorizedInterleaved::from(by);
+                for dst in dst.iter_mut() {
+                    dst.chunk_0.0 = kernels::mul_complex_interleaved(&dst.chunk_0.0, &by.chunk_0.0);
+                    dst.chunk_1.0 = kernels::mul_complex_interleaved(&dst.chunk_1.0, &by.chunk_1.0);
+                }
+            }
+        }
+    };
+}
+
+#[cfg(all(target_arch = "x86_64", not(target_feature = "avx512f")))]
+slice_kernels!(avx2_slices, avx2_impl, "avx2");
+#[cfg(all(target_arch = "x86_64", not(target_feature = "avx512f")))]
+slice_kernels!(avx_512_slices, avx_512_kernels, "avx512f");
+#[cfg(target_arch = "aarch64")]
+slice_kernels!(neon_slices, neon_impl, "neon");
+
+/// `dst[i] *= by`, e.g. scaling rows of a trace by twiddles in FFT. Picks the backend once and
+/// falls back to the generic arithmetic of the vectorized type.
+pub fn mul_assign_complex_interleaved_by_constant_slice(
+    dst: &mut [Mersenne31ComplexVectorizedInterleaved],
+    by: Mersenne31Complex,
+) {
+    match vector_backend() {
+        #[cfg(all(target_arch = "x86_64", not(target_feature = "avx512f")))]
+        VectorBackend::Avx512 => unsafe {
+            avx_512_slices::mul_assign_complex_interleaved_by_constant(dst, by)
+        },
+        #[cfg(all(target_arch = "x86_64", not(target_feature = "avx512f")))]
+        VectorBackend::Avx2 => unsafe {
+            avx2_slices::mul_assign_complex_interleaved_by_constant(dst, by)
+        },
+        #[cfg(target_arch = "aarch64")]
+        VectorBackend::Neon => unsafe {
+            neon_slices::mul_assign_complex_interleaved_by_constant(dst, by)
+        },
+        _ => {
+            let by = Mersenne31ComplexVectorized::constant(by);
+            for dst in dst.iter_mut() {
+                *dst = *dst * by;
+            }
+        }
+    }
+}
+
+#[cfg(all(
+    test,
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(target_feature = "avx512f")
+))]
+mod tests {
+    use super::*;
+    use crate::*;
+    use cpu_features::{force_simd_backend, SimdBackend};
+    use rand::SeedableRng;
+
+    fn random_vector(rng: &mut rand::rngs::StdRng) -> Mersenne31FieldVectorized {
+        let mut vector = Mersenne31FieldVectorized::random_element(rng);
+        // non-canonical zero and the largest element are valid inputs too
+        vector.0[0] = Mersenne31Field(Mersenne31Field::ORDER);
+        vector.0[1] = Mersenne31Field(Mersenne31Field::ORDER - 1);
+        vector.0[2] = Mersenne31Field::ZERO;
+        vector
+    }
+
+    fn random_complex(rng: &mut rand::rngs::StdRng) -> Mersenne31ComplexVectorized {
+        Mersenne31ComplexVectorized {
+            c0: random_vector(rng),
+            c1: random_vector(rng),
+        }
+    }
+
+    fn random_quartic(rng: &mut rand::rngs::StdRng) -> Mersenne31QuarticVectorized {
+        Mersenne31QuarticVectorized {
+            c0: random_complex(rng),
+            c1: random_complex(rng),
+        }
+    }
+
+    fn check_vectorized_ops() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let (a, b) = (random_vector(&mut rng), random_vector(&mut rng));
+            let (sum, diff, prod) = (a + b, a - b, a * b);
+            for i in 0..WIDTH {
+                assert_eq!(sum.0[i], *a.0[i].clone().add_assign(&b.0[i]));
+                assert_eq!(diff.0[i], *a.0[i].clone().sub_assign(&b.0[i]));
+                assert_eq!(prod.0[i], *a.0[i].clone().mul_assign(&b.0[i]));
+            }
+
+            let (a, b) = (random_complex(&mut rng), random_complex(&mut rng));
+            let prod = *a.clone().mul_assign(&b);
+            assert_eq!(a * b, prod);
+            for i in 0..WIDTH {
+                let expected = *a.get_base_element(i).mul_assign(&b.get_base_element(i));
+                assert_eq!(prod.get_base_element(i), expected);
+            }
+
+            let (a, b) = (
+                Mersenne31ComplexVectorizedInterleaved::from_base_array(&a.as_base_array()),
+                Mersenne31ComplexVectorizedInterleaved::from_base_array(&b.as_base_array()),
+            );
+            let prod = a * b;
+            for i in 0..WIDTH {
+                let expected = *a.get_base_element(i).mul_assign(&b.get_base_element(i));
+                assert_eq!(prod.get_base_element(i), expected);
+            }
+
+            let (a, b) = (random_quartic(&mut rng), random_quartic(&mut rng));
+            let prod = *a.clone().mul_assign(&b);
+            for i in 0..WIDTH {
+                let expected = *a.get_base_element(i).mul_assign(&b.get_base_element(i));
+                assert_eq!(prod.get_base_element(i), expected);
+            }
+        }
+    }
+
+    fn check_slice_ops() {
+        const LEN: usize = 100;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let interleaved = |a: Mersenne31ComplexVectorized| {
+            Mersenne31ComplexVectorizedInterleaved::from_base_array(&a.as_base_array())
+        };
+        let values: Vec<_> = (0..LEN)
+            .map(|_| interleaved(random_complex(&mut rng)))
+            .collect();
+        for by in [
+            Mersenne31Complex::random_element(&mut rng),
+            Mersenne31Complex::ZERO,
+            Mersenne31Complex::from_coeffs_in_base(&[
+                Mersenne31Field(Mersenne31Field::ORDER - 1),
+                Mersenne31Field(Mersenne31Field::ORDER),
+            ]),
+        ] {
+            let mut result = values.clone();
+            mul_assign_complex_interleaved_by_constant_slice(&mut result, by);
+            for (result, value) in result.iter().zip(values.iter()) {
+                for i in 0..WIDTH {
+                    let expected = *value.get_base_element(i).mul_assign(&by);
+                    assert_eq!(result.get_base_element(i), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_backends() {
+        force_simd_backend(Some(SimdBackend::Scalar));
+        check_vectorized_ops();
+        for backend in SimdBackend::ALL {
+            if !backend.is_supported() {
+                continue;
+            }
+            force_simd_backend(Some(backend));
+            // scalar arithmetic is the reference for slice operations
+            check_slice_ops();
+            with_vector_backend(check_vectorized_ops);
+        }
+        force_simd_backend(None);
+    }
+}
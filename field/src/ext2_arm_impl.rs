@@ -0,0 +1,390 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Portions derived from Plonky3 and adapted by Matter Labs.
+// © 2024 Polygon Labs – original; © 2025 Matter Labs - adapted for RISC-V.
+use super::Field;
+use super::Mersenne31Quartic;
+use super::*;
+use crate::ext_arm_impl::Mersenne31ComplexVectorized;
+use crate::FieldExtension;
+use crate::FieldLikeVectorized;
+use crate::Mersenne31Complex;
+use crate::Mersenne31FieldVectorized;
+use crate::WIDTH;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(C, align(64))]
+pub struct Mersenne31QuarticVectorized {
+    pub c0: Mersenne31ComplexVectorized,
+    pub c1: Mersenne31ComplexVectorized,
+}
+
+impl From<Mersenne31Quartic> for Mersenne31QuarticVectorized {
+    #[inline]
+    fn from(value: Mersenne31Quartic) -> Self {
+        Self {
+            c0: Mersenne31ComplexVectorized::from(
+                <Mersenne31Quartic as FieldExtension<Mersenne31Complex>>::into_coeffs_in_base(
+                    value,
+                )[0],
+            ),
+            c1: Mersenne31ComplexVectorized::from(
+                <Mersenne31Quartic as FieldExtension<Mersenne31Complex>>::into_coeffs_in_base(
+                    value,
+                )[1],
+            ),
+        }
+    }
+}
+
+impl Default for Mersenne31QuarticVectorized {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            c0: Mersenne31ComplexVectorized::default(),
+            c1: Mersenne31ComplexVectorized::default(),
+        }
+    }
+}
+
+impl core::fmt::Display for Mersenne31QuarticVectorized {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?} {:?}", self.c0, self.c1)
+    }
+}
+
+use rand::Rng;
+impl Rand for Mersenne31QuarticVectorized {
+    fn random_element<R: Rng + ?Sized>(rng: &mut R) -> Mersenne31QuarticVectorized {
+        let c0 = Mersenne31ComplexVectorized::random_element(rng);
+        let c1 = Mersenne31ComplexVectorized::random_element(rng);
+        Mersenne31QuarticVectorized { c0, c1 }
+    }
+}
+
+impl Field for Mersenne31QuarticVectorized {
+    const ZERO: Self = Self {
+        c0: Mersenne31ComplexVectorized::ZERO,
+        c1: Mersenne31ComplexVectorized::ZERO,
+    };
+    const ONE: Self = Self {
+        c0: Mersenne31ComplexVectorized::ONE,
+        c1: Mersenne31ComplexVectorized::ZERO,
+    };
+
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    #[inline(always)]
+    fn is_one(&self) -> bool {
+        *self == Self::ONE
+    }
+
+    #[inline(always)]
+    fn add_assign(&'_ mut self, other: &Self) -> &'_ mut Self {
+        self.c0.add_assign(&other.c0);
+        self.c1.add_assign(&other.c1);
+        self
+    }
+
+    #[inline(always)]
+    fn sub_assign(&'_ mut self, other: &Self) -> &'_ mut Self {
+        self.c0.sub_assign(&other.c0);
+        self.c1.sub_assign(&other.c1);
+        self
+    }
+
+    #[inline(always)]
+    fn mul_assign(&'_ mut self, other: &Self) -> &'_ mut Self {
+        let mut v0 = self.c0;
+        v0.mul_assign(&other.c0);
+        let mut v1 = self.c1;
+        v1.mul_assign(&other.c1);
+
+        let t = self.c0;
+        self.c1.add_assign(&t);
+
+        let mut t0 = other.c0;
+        t0.add_assign(&other.c1);
+        self.c1.mul_assign(&t0);
+        self.c1.sub_assign(&v0);
+        self.c1.sub_assign(&v1);
+        self.c0 = v0;
+        Mersenne31ComplexVectorized::mul_by_non_residue(&mut v1);
+        self.c0.add_assign(&v1);
+
+        self
+    }
+
+    #[inline(always)]
+    fn square(&'_ mut self) -> &'_ mut Self {
+        let mut v0 = self.c0;
+        v0.sub_assign(&self.c1);
+        let mut v3 = self.c0;
+        let mut t0 = self.c1;
+        Mersenne31ComplexVectorized::mul_by_non_residue(&mut t0);
+        v3.sub_assign(&t0);
+        let mut v2 = self.c0;
+        v2.mul_assign(&self.c1);
+        v0.mul_assign(&v3);
+        v0.add_assign(&v2);
+
+        self.c1 = v2;
+        self.c1.double();
+        self.c0 = v0;
+        Mersenne31ComplexVectorized::mul_by_non_residue(&mut v2);
+        self.c0.add_assign(&v2);
+
+        self
+    }
+
+    #[inline(always)]
+    fn negate(&'_ mut self) -> &'_ mut Self {
+        self.c0.negate();
+        self.c1.negate();
+        self
+    }
+
+    #[inline(always)]
+    fn double(&mut self) -> &mut Self {
+        self.c0.double();
+        self.c1.double();
+        self
+    }
+
+    #[inline(always)]
+    fn inverse(&self) -> Option<Self> {
+        let mut v0 = self.c0;
+        v0.square();
+        let mut v1 = self.c1;
+        v1.square();
+        // v0 = v0 - beta * v1
+        let mut v1_by_nonresidue = v1;
+        Mersenne31ComplexVectorized::mul_by_non_residue(&mut v1_by_nonresidue);
+        v0.sub_assign(&v1_by_nonresidue);
+        match v0.inverse() {
+            Some(inversed) => {
+                let mut c0 = self.c0;
+                c0.mul_assign(&inversed);
+                let mut c1 = self.c1;
+                c1.mul_assign(&inversed);
+                c1.negate();
+
+                let new = Self { c0, c1 };
+                Some(new)
+            }
+            None => None,
+        }
+    }
+}
+
+impl FieldExtension<Mersenne31ComplexVectorized> for Mersenne31QuarticVectorized {
+    const DEGREE: usize = 2;
+    #[inline(always)]
+    fn mul_assign_by_base(&mut self, base: &Mersenne31ComplexVectorized) -> &mut Self {
+        self.c0.mul_assign(base);
+        self.c1.mul_assign(base);
+        self
+    }
+
+    #[inline(always)]
+    fn add_assign_base(&mut self, elem: &Mersenne31ComplexVectorized) -> &mut Self {
+        self.c0.add_assign(elem);
+        self
+    }
+
+    #[inline(always)]
+    fn sub_assign_base(&mut self, elem: &Mersenne31ComplexVectorized) -> &mut Self {
+        self.c0.sub_assign(elem);
+        self
+    }
+
+    #[inline(always)]
+    fn into_coeffs_in_base(self) -> [Mersenne31ComplexVectorized; 2] {
+        [self.c0, self.c1]
+    }
+
+    #[inline(always)]
+    fn coeffs_in_base(&self) -> &[Mersenne31ComplexVectorized] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self.c0.c0.0.as_ptr() as *const Mersenne31ComplexVectorized,
+                2,
+            )
+        }
+    }
+
+    #[inline(always)]
+    fn from_coeffs_in_base(coeffs: &[Mersenne31ComplexVectorized]) -> Self {
+        Self {
+            c0: coeffs[0],
+            c1: coeffs[1],
+        }
+    }
+
+    fn from_coeffs_in_base_ref(coeffs: &[&Mersenne31ComplexVectorized]) -> Self {
+        Self {
+            c0: *coeffs[0],
+            c1: *coeffs[1],
+        }
+    }
+
+    fn from_coeffs_in_base_iter<I: Iterator<Item = Mersenne31ComplexVectorized>>(
+        mut coeffs_iter: I,
+    ) -> Self {
+        Self {
+            c0: coeffs_iter.next().unwrap(),
+            c1: coeffs_iter.next().unwrap(),
+        }
+    }
+
+    #[inline(always)]
+    fn from_base(elem: Mersenne31ComplexVectorized) -> Self {
+        Self {
+            c0: elem,
+            c1: Mersenne31ComplexVectorized::ZERO,
+        }
+    }
+
+    #[inline(always)]
+    fn get_coef_mut(&mut self, idx: usize) -> &mut Mersenne31ComplexVectorized {
+        match idx {
+            0 => &mut self.c0,
+            1 => &mut self.c1,
+            _ => panic!("Invalid index"),
+        }
+    }
+
+    fn from_base_coeffs_array(coefs: &[Mersenne31ComplexVectorized; 2]) -> Self {
+        Self {
+            c0: coefs[0],
+            c1: coefs[1],
+        }
+    }
+}
+
+impl FieldExtension<Mersenne31FieldVectorized> for Mersenne31QuarticVectorized {
+    const DEGREE: usize = 4;
+
+    fn mul_assign_by_base(&mut self, elem: &Mersenne31FieldVectorized) -> &mut Self {
+        self.c0.c0.mul_assign_by_base(elem);
+        self.c0.c1.mul_assign_by_base(elem);
+        self.c1.c0.mul_assign_by_base(elem);
+        self.c1.c1.mul_assign_by_base(elem);
+        self
+    }
+
+    fn into_coeffs_in_base(self) -> [Mersenne31FieldVectorized; 4] {
+        let Mersenne31QuarticVectorized { c0: a, c1: b } = self;
+        let [c0, c1] = a.into_coeffs_in_base();
+        let [c2, c3] = b.into_coeffs_in_base();
+        [c0, c1, c2, c3]
+    }
+
+    fn from_coeffs_in_base(coeffs: &[Mersenne31FieldVectorized]) -> Self {
+        let c0 = Mersenne31ComplexVectorized::from_coeffs_in_base(&coeffs[0..2]);
+        let c1 = Mersenne31ComplexVectorized::from_coeffs_in_base(&coeffs[2..4]);
+        Self { c0, c1 }
+    }
+
+    fn from_coeffs_in_base_ref(coeffs: &[&Mersenne31FieldVectorized]) -> Self {
+        let c0 = Mersenne31ComplexVectorized::from_coeffs_in_base_ref(&coeffs[0..2]);
+        let c1 = Mersenne31ComplexVectorized::from_coeffs_in_base_ref(&coeffs[2..4]);
+        Self { c0, c1 }
+    }
+
+    fn from_coeffs_in_base_iter<I: Iterator<Item = Mersenne31FieldVectorized>>(
+        mut coeffs_iter: I,
+    ) -> Self {
+        let c0 = Mersenne31ComplexVectorized::from_coeffs_in_base(&[
+            coeffs_iter.next().unwrap(),
+            coeffs_iter.next().unwrap(),
+        ]);
+        let c1 = Mersenne31ComplexVectorized::from_coeffs_in_base(&[
+            coeffs_iter.next().unwrap(),
+            coeffs_iter.next().unwrap(),
+        ]);
+        Self { c0, c1 }
+    }
+
+    fn coeffs_in_base(&self) -> &[Mersenne31FieldVectorized] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self.c0.c0.0.as_ptr() as *const Mersenne31FieldVectorized,
+                4,
+            )
+        }
+    }
+
+    fn add_assign_base(&mut self, elem: &Mersenne31FieldVectorized) -> &mut Self {
+        self.c0.add_assign_base(elem);
+        self
+    }
+
+    fn sub_assign_base(&mut self, elem: &Mersenne31FieldVectorized) -> &mut Self {
+        self.c0.sub_assign_base(elem);
+        self
+    }
+
+    fn from_base(elem: Mersenne31FieldVectorized) -> Self {
+        let c0 = Mersenne31ComplexVectorized::from_base(elem);
+        Self {
+            c0,
+            c1: Mersenne31ComplexVectorized::ZERO,
+        }
+    }
+
+    fn get_coef_mut(&mut self, idx: usize) -> &mut Mersenne31FieldVectorized {
+        if idx < 2 {
+            self.c0.get_coef_mut(idx % 2)
+        } else {
+            self.c1.get_coef_mut(idx % 2)
+        }
+    }
+
+    fn from_base_coeffs_array(coefs: &[Mersenne31FieldVectorized; 4]) -> Self {
+        let c0 = Mersenne31ComplexVectorized::from_coeffs_in_base(&coefs[0..2]);
+        let c1 = Mersenne31ComplexVectorized::from_coeffs_in_base(&coefs[2..4]);
+        Self { c0, c1 }
+    }
+}
+
+impl FieldLikeVectorized for Mersenne31QuarticVectorized {
+    type Base = Mersenne31Quartic;
+    const SIZE_FACTOR: usize = WIDTH;
+
+    #[inline(always)]
+    fn constant(value: Self::Base) -> Self {
+        Self {
+            c0: Mersenne31ComplexVectorized::constant(value.c0),
+            c1: Mersenne31ComplexVectorized::constant(value.c1),
+        }
+    }
+
+    fn get_base_element(&self, idx: usize) -> Self::Base {
+        Self::Base::from_coeffs_in_base(&[
+            self.c0.get_base_element(idx),
+            self.c1.get_base_element(idx),
+        ])
+    }
+
+    fn from_base_elements(input: &[Self::Base]) -> Self {
+        Self::from_base_array(input.try_into().unwrap())
+    }
+
+    fn from_base_array(input: &[Self::Base; Self::SIZE_FACTOR]) -> Self {
+        let mut res = Self::default();
+        for (i, el) in input.iter().enumerate() {
+            res.c0.c0.0[i] = el.c0.c0;
+            res.c0.c1.0[i] = el.c0.c1;
+            res.c1.c0.0[i] = el.c1.c0;
+            res.c1.c1.0[i] = el.c1.c1;
+        }
+        res
+    }
+
+    fn as_base_array(&self) -> [Self::Base; Self::SIZE_FACTOR] {
+        core::array::from_fn(|i| self.get_base_element(i))
+    }
+}
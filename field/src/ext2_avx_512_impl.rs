@@ -585,3 +585,5 @@ impl FieldLikeVectorized for Mersenne31FieldAVX512Quartic {
         res
     }
 }
+
+pub type Mersenne31QuarticVectorized = Mersenne31FieldAVX512Quartic;
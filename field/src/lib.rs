@@ -34,6 +34,18 @@ pub use self::quartic::*;
 pub use self::field::*;
 pub use self::field_like::*;
 
+pub mod backend;
+pub use self::backend::{vector_backend, with_vector_backend, VectorBackend};
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub use cpu_features::{simd_backend, SimdBackend};
+
+#[cfg(all(target_arch = "x86_64", not(target_feature = "avx512f")))]
+mod avx2_impl;
+#[cfg(all(target_arch = "x86_64", not(target_feature = "avx512f")))]
+mod avx_512_kernels;
+#[cfg(target_arch = "aarch64")]
+mod neon_impl;
+
 #[cfg(target_feature = "avx512f")]
 pub mod avx_512_impl;
 #[cfg(target_feature = "avx512f")]
@@ -55,6 +67,8 @@ pub use self::ext_avx_512_interleaved_impl::*;
 #[cfg(not(target_feature = "avx512f"))]
 pub mod arm_impl;
 #[cfg(not(target_feature = "avx512f"))]
+pub mod ext2_arm_impl;
+#[cfg(not(target_feature = "avx512f"))]
 pub mod ext_arm_impl;
 #[cfg(not(target_feature = "avx512f"))]
 pub mod ext_arm_interleaved_impl;
@@ -62,6 +76,8 @@ pub mod ext_arm_interleaved_impl;
 #[cfg(not(target_feature = "avx512f"))]
 pub use self::arm_impl::*;
 #[cfg(not(target_feature = "avx512f"))]
+pub use self::ext2_arm_impl::*;
+#[cfg(not(target_feature = "avx512f"))]
 pub use self::ext_arm_impl::*;
 #[cfg(not(target_feature = "avx512f"))]
 pub use self::ext_arm_interleaved_impl::*;
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Portions derived from Plonky3 and adapted by Matter Labs.
+// © 2024 Polygon Labs – original; © 2025 Matter Labs - adapted for RISC-V.
+
+//! NEON kernels for the slice operations in `backend`. Same interface as `avx2_impl`, every vector is processed
+//! as four 128-bit quarters.
+
+use crate::Mersenne31Field;
+use core::arch::aarch64::{self, uint32x4_t};
+use core::mem::transmute;
+
+const WIDTH: usize = 16;
+const P: uint32x4_t = unsafe { transmute::<[u32; 4], _>([0x7fffffff; 4]) };
+
+type Lanes = [Mersenne31Field; WIDTH];
+
+#[inline]
+#[target_feature(enable = "neon")]
+fn to_vectors(value: &Lanes) -> [uint32x4_t; 4] {
+    unsafe { transmute(*value) }
+}
+
+#[inline]
+#[target_feature(enable = "neon")]
+fn from_vectors(value: [uint32x4_t; 4]) -> Lanes {
+    unsafe { transmute(value) }
+}
+
+#[inline]
+#[target_feature(enable = "neon")]
+fn reduce_sum(t: uint32x4_t) -> uint32x4_t {
+    let u = aarch64::vsubq_u32(t, P);
+    aarch64::vminq_u32(t, u)
+}
+
+#[inline]
+#[target_feature(enable = "neon")]
+fn add_vec(lhs: uint32x4_t, rhs: uint32x4_t) -> uint32x4_t {
+    reduce_sum(aarch64::vaddq_u32(lhs, rhs))
+}
+
+#[inline]
+#[target_feature(enable = "neon")]
+fn sub_vec(lhs: uint32x4_t, rhs: uint32x4_t) -> uint32x4_t {
+    let t = aarch64::vsubq_u32(lhs, rhs);
+    let u = aarch64::vaddq_u32(t, P);
+    aarch64::vminq_u32(t, u)
+}
+
+#[inline]
+#[target_feature(enable = "neon")]
+fn mul_vec(lhs: uint32x4_t, rhs: uint32x4_t) -> uint32x4_t {
+    // doubling multiplication gives ab >> 31, and subtracting (ab >> 31) * P from the low 32 bits
+    // of the product leaves the sum of the low 31 bits and the high part
+    let mul_hi = aarch64::vreinterpretq_u32_s32(aarch64::vqdmulhq_s32(
+        aarch64::vreinterpretq_s32_u32(lhs),
+        aarch64::vreinterpretq_s32_u32(rhs),
+    ));
+    let mul_lo = aarch64::vmulq_u32(lhs, rhs);
+    reduce_sum(aarch64::vmlsq_u32(mul_lo, mul_hi, P))
+}
+
+#[inline]
+#[target_feature(enable = "neon")]
+pub(crate) fn mul_complex_interleaved(lhs: &Lanes, rhs: &Lanes) -> Lanes {
+    let (lhs, rhs) = (to_vectors(lhs), to_vectors(rhs));
+    let mut result = lhs;
+    for i in 0..4 {
+        // (a0, a0) * (b0, b1) +- (a1, a1) * (b1, b0)
+        let re = aarch64::vtrn1q_u32(lhs[i], lhs[i]);
+        let im = aarch64::vtrn2q_u32(lhs[i], lhs[i]);
+        let swapped = aarch64::vrev64q_u32(rhs[i]);
+        let t0 = mul_vec(re, rhs[i]);
+        let t1 = mul_vec(im, swapped);
+        // even lanes of the difference and odd lanes of the sum
+        let sum = add_vec(t0, t1);
+        result[i] = aarch64::vtrn1q_u32(sub_vec(t0, t1), aarch64::vtrn2q_u32(sum, sum));
+    }
+    from_vectors(result)
+}
@@ -0,0 +1,521 @@
+//! Native verification of the full statement, for services that want to check proofs without
+//! running the verifier program in the simulator and need to know why a proof was rejected.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use verifier_common::blake2s_u32::BLAKE2S_DIGEST_SIZE_U32_WORDS;
+use verifier_common::prover::definitions::HashFamily;
+use verifier_common::prover::nd_source_std::set_iterator;
+use verifier_common::{check_hash_family, DefaultLeafInclusionVerifier, VerificationError};
+
+// verifier keeps the full proof skeleton and queries on the stack
+const VERIFIER_STACK_SIZE: usize = 1 << 27;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VerifierLayer {
+    Base,
+    Recursion,
+    FinalRecursion,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VerificationKey {
+    pub layer: VerifierLayer,
+    /// Hash of the final PC and setup caps of the main circuit, same as the `params` of the
+    /// verification keys of the verifier programs
+    pub end_params: [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PublicOutput {
+    /// Registers x10-x17 at the end of the base program
+    pub program_output: [u32; 8],
+    /// Hash chain over the end parameters of all the programs verified so far
+    pub recursion_chain: [u32; 8],
+}
+
+impl PublicOutput {
+    pub fn from_words(words: [u32; 16]) -> Self {
+        Self {
+            program_output: words[..8].try_into().unwrap(),
+            recursion_chain: words[8..].try_into().unwrap(),
+        }
+    }
+
+    pub fn to_words(&self) -> [u32; 16] {
+        let mut words = [0u32; 16];
+        words[..8].copy_from_slice(&self.program_output);
+        words[8..].copy_from_slice(&self.recursion_chain);
+
+        words
+    }
+}
+
+struct OracleWords {
+    words: Vec<u32>,
+    position: Arc<AtomicUsize>,
+}
+
+impl Iterator for OracleWords {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let position = self.position.fetch_add(1, Ordering::Relaxed);
+        // past the end we feed zeroes, and report truncation once verifier is done
+        Some(self.words.get(position).copied().unwrap_or(0))
+    }
+}
+
+/// Verifies the full statement. `proof` is the same sequence of words that the verifier program
+/// reads from the CSR, e.g. `ProgramProof::flatten_for_delegation_circuits_set` for the set of
+/// delegation circuits of the `vk.layer`. `hash_family` is the one recorded in the flattened proofs,
+/// as it's not a part of the words.
+pub fn verify(
+    proof: &[u32],
+    hash_family: HashFamily,
+    vk: &VerificationKey,
+) -> Result<PublicOutput, VerificationError> {
+    check_hash_family::<DefaultLeafInclusionVerifier>(hash_family)?;
+
+    let num_words = proof.len();
+    let position = Arc::new(AtomicUsize::new(0));
+    let oracle = OracleWords {
+        words: proof.to_vec(),
+        position: position.clone(),
+    };
+    let vk = *vk;
+
+    // dedicated thread gives us enough stack, and doesn't touch the caller's thread-local source
+    let result = std::thread::Builder::new()
+        .stack_size(VERIFIER_STACK_SIZE)
+        .spawn(move || {
+            set_iterator(oracle);
+            match vk.layer {
+                VerifierLayer::Base => crate::verify_base_layer_checked(Some(&vk.end_params)),
+                VerifierLayer::Recursion => {
+                    crate::verify_recursion_layer_checked(Some(&vk.end_params))
+                }
+                VerifierLayer::FinalRecursion => {
+                    crate::verify_final_recursion_layer_checked(Some(&vk.end_params))
+                }
+            }
+        })
+        .expect("verifier thread must spawn")
+        .join()
+        // verifier asserts on the data it can't make sense of, that is a rejected proof too
+        .unwrap_or(Err(VerificationError::MalformedProof));
+
+    let consumed = position.load(Ordering::Relaxed);
+    if consumed > num_words {
+        return Err(VerificationError::TruncatedProof);
+    }
+    let output = result?;
+    if consumed < num_words {
+        return Err(VerificationError::TrailingProofData);
+    }
+
+    Ok(PublicOutput::from_words(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::Global;
+    use std::cell::Cell;
+    use std::mem::MaybeUninit;
+    use std::sync::OnceLock;
+
+    use execution_utils::{
+        compute_end_parameters, find_binary_exit_point, get_padded_binary, ProgramProof,
+        BASE_PROGRAM,
+    };
+    use prover_examples::prover::risc_v_simulator::abstractions::non_determinism::QuasiUARTSource;
+    use prover_examples::prover::risc_v_simulator::cycle::IMStandardIsaConfig;
+    use prover_examples::setups::{
+        all_delegation_circuits_precomputations, get_main_riscv_circuit_setup,
+        DelegationCircuitPrecomputations, MainCircuitPrecomputations,
+    };
+    use prover_examples::{
+        commit_memory_for_traced_execution, prove_traced_execution, TracedExecution, POW_BITS,
+    };
+    use trace_and_split::{fs_transform_for_memory_and_delegation_arguments, FinalRegisterValue};
+    use verifier_common::field::{Field, Mersenne31Field, Mersenne31Quartic};
+    use verifier_common::non_determinism_source::NonDeterminismSource;
+    use verifier_common::proof_flattener::flatten_full_proof;
+    use verifier_common::prover::definitions::{
+        Blake2sForEverythingVerifier, ExternalChallenges, LeafInclusionVerifier, MerkleTreeCap,
+    };
+    use verifier_common::prover::merkle_trees::{DefaultTreeConstructor, MerkleTreeConstructor};
+    use verifier_common::prover::prover_stages::Proof;
+    use verifier_common::prover::worker::Worker;
+    use verifier_common::transcript::{Blake2sTranscript, Seed, StatelessTranscript};
+    use verifier_common::{CommittedOracle, DefaultNonDeterminismSource, ProofPublicInputs};
+
+    const NUM_REGISTER_WORDS: usize = 32 * 3;
+
+    fn vk(layer: VerifierLayer) -> VerificationKey {
+        VerificationKey {
+            layer,
+            end_params: [0; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+        }
+    }
+
+    #[test]
+    fn test_truncated_proof() {
+        let result = verify(
+            &[0; 10],
+            DefaultLeafInclusionVerifier::HASH_FAMILY,
+            &vk(VerifierLayer::Base),
+        );
+        assert_eq!(result, Err(VerificationError::TruncatedProof));
+    }
+
+    #[test]
+    fn test_invalid_number_of_circuits() {
+        // all registers are zero, and there are no main circuits
+        let proof = vec![0; NUM_REGISTER_WORDS + 1];
+        let result = verify(
+            &proof,
+            DefaultLeafInclusionVerifier::HASH_FAMILY,
+            &vk(VerifierLayer::Recursion),
+        );
+        assert_eq!(result, Err(VerificationError::InvalidNumberOfCircuits));
+    }
+
+    #[test]
+    fn test_invalid_register_values() {
+        let mut proof = vec![0; NUM_REGISTER_WORDS + 1];
+        // x0 must be zero
+        proof[0] = 1;
+        let result = verify(
+            &proof,
+            DefaultLeafInclusionVerifier::HASH_FAMILY,
+            &vk(VerifierLayer::Base),
+        );
+        assert_eq!(result, Err(VerificationError::InvalidRegisterValues));
+    }
+
+    #[test]
+    fn test_hash_family_mismatch() {
+        let hash_family =
+            if DefaultLeafInclusionVerifier::HASH_FAMILY == HashFamily::Poseidon2ForEverything {
+                HashFamily::Blake2sForEverything
+            } else {
+                HashFamily::Poseidon2ForEverything
+            };
+        // checked before any data is read
+        let result = verify(&[], hash_family, &vk(VerifierLayer::Base));
+        assert_eq!(result, Err(VerificationError::HashFamilyMismatch));
+    }
+
+    #[test]
+    fn test_public_output_words() {
+        let words: [u32; 16] = core::array::from_fn(|i| i as u32);
+        let output = PublicOutput::from_words(words);
+        assert_eq!(output.program_output, [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(output.to_words(), words);
+    }
+
+    type Precomputations = (
+        Vec<u32>,
+        MainCircuitPrecomputations<IMStandardIsaConfig, Global>,
+        Vec<(u32, DelegationCircuitPrecomputations<Global>)>,
+        [u32; 8],
+    );
+
+    fn base_program_precomputations(worker: &Worker) -> Precomputations {
+        let expected_final_pc = find_binary_exit_point(BASE_PROGRAM);
+        let binary = get_padded_binary(BASE_PROGRAM);
+        let main_circuit_precomputations =
+            get_main_riscv_circuit_setup::<Global, Global>(&binary, worker);
+        let delegation_precomputations =
+            all_delegation_circuits_precomputations::<Global, Global>(worker);
+        let end_params = compute_end_parameters(expected_final_pc, &main_circuit_precomputations);
+
+        (
+            binary,
+            main_circuit_precomputations,
+            delegation_precomputations,
+            end_params,
+        )
+    }
+
+    fn program_proof(
+        base_layer_proofs: Vec<Proof>,
+        delegation_proofs: Vec<(u32, Vec<Proof>)>,
+        register_final_values: Vec<FinalRegisterValue>,
+        end_params: [u32; 8],
+    ) -> ProgramProof {
+        ProgramProof {
+            base_layer_proofs,
+            delegation_proofs: delegation_proofs.into_iter().collect(),
+            register_final_values,
+            end_params,
+            recursion_chain_preimage: None,
+            recursion_chain_hash: None,
+        }
+    }
+
+    /// Honest proof of the base program, shared by the tests that tamper with its copies
+    fn base_layer_proof() -> &'static ProgramProof {
+        static PROOF: OnceLock<ProgramProof> = OnceLock::new();
+        PROOF.get_or_init(|| {
+            let worker = Worker::new_with_num_threads(8);
+            let (binary, main_circuit_precomputations, delegation_precomputations, end_params) =
+                base_program_precomputations(&worker);
+            let (main_proofs, delegation_proofs, register_values) =
+                prover_examples::prove_image_execution(
+                    10,
+                    &binary,
+                    QuasiUARTSource::new_with_reads(vec![123, 10]),
+                    &main_circuit_precomputations,
+                    &delegation_precomputations,
+                    &worker,
+                );
+
+            program_proof(main_proofs, delegation_proofs, register_values, end_params)
+        })
+    }
+
+    fn verify_program_proof(
+        program_proof: &ProgramProof,
+    ) -> Result<PublicOutput, VerificationError> {
+        let delegation_types: Vec<u32> =
+            crate::BASE_LAYER_DELEGATION_CIRCUITS_VERIFICATION_PARAMETERS
+                .iter()
+                .map(|el| el.0)
+                .collect();
+        let vk = VerificationKey {
+            layer: VerifierLayer::Base,
+            end_params: program_proof.end_params,
+        };
+
+        verify(
+            &program_proof.flatten_for_delegation_circuits_set(&delegation_types),
+            program_proof.base_layer_proofs[0].hash_family,
+            &vk,
+        )
+    }
+
+    thread_local! {
+        static SEED_BEFORE_POW: Cell<Option<Seed>> = const { Cell::new(None) };
+    }
+
+    /// Blake2s transcript that remembers the seed the PoW nonce is checked against
+    #[derive(Clone, Copy, Debug)]
+    struct PowSeedRecordingTranscript;
+
+    impl StatelessTranscript for PowSeedRecordingTranscript {
+        type Hasher = <Blake2sTranscript as StatelessTranscript>::Hasher;
+
+        fn new_hasher() -> Self::Hasher {
+            <Blake2sTranscript as StatelessTranscript>::new_hasher()
+        }
+
+        fn commit_initial_using_hasher(hasher: &mut Self::Hasher, input: &[u32]) -> Seed {
+            <Blake2sTranscript as StatelessTranscript>::commit_initial_using_hasher(hasher, input)
+        }
+
+        fn commit_with_seed_using_hasher(
+            hasher: &mut Self::Hasher,
+            seed: &mut Seed,
+            input: &[u32],
+        ) {
+            <Blake2sTranscript as StatelessTranscript>::commit_with_seed_using_hasher(
+                hasher, seed, input,
+            )
+        }
+
+        fn draw_randomness_using_hasher(
+            hasher: &mut Self::Hasher,
+            seed: &mut Seed,
+            dst: &mut [u32],
+        ) {
+            <Blake2sTranscript as StatelessTranscript>::draw_randomness_using_hasher(
+                hasher, seed, dst,
+            )
+        }
+
+        fn check_pow_using_hasher(
+            hasher: &mut Self::Hasher,
+            seed: &mut Seed,
+            nonce: u64,
+            pow_bits: u32,
+        ) -> bool {
+            SEED_BEFORE_POW.with(|el| el.set(Some(*seed)));
+            <Blake2sTranscript as StatelessTranscript>::check_pow_using_hasher(
+                hasher, seed, nonce, pow_bits,
+            )
+        }
+
+        fn search_pow(seed: &Seed, pow_bits: u32, worker: &Worker) -> (Seed, u64) {
+            <Blake2sTranscript as StatelessTranscript>::search_pow(seed, pow_bits, worker)
+        }
+    }
+
+    #[derive(Debug)]
+    struct PowSeedRecordingVerifier(Blake2sForEverythingVerifier);
+
+    impl LeafInclusionVerifier for PowSeedRecordingVerifier {
+        type Transcript = PowSeedRecordingTranscript;
+        const HASH_FAMILY: HashFamily = HashFamily::Blake2sForEverything;
+
+        fn new() -> Self {
+            Self(Blake2sForEverythingVerifier::new())
+        }
+
+        unsafe fn verify_leaf_inclusion<
+            I: NonDeterminismSource,
+            const CAP_SIZE: usize,
+            const NUM_COSETS: usize,
+        >(
+            &mut self,
+            coset_index: u32,
+            leaf_index: u32,
+            depth: usize,
+            leaf_encoding: &[u32],
+            merkle_cap: &[MerkleTreeCap<CAP_SIZE>; NUM_COSETS],
+        ) -> bool {
+            self.0.verify_leaf_inclusion::<I, CAP_SIZE, NUM_COSETS>(
+                coset_index,
+                leaf_index,
+                depth,
+                leaf_encoding,
+                merkle_cap,
+            )
+        }
+    }
+
+    /// Everything the verifier checks before PoW is bound by the transcript, so tampering with it
+    /// is caught by PoW. To reach the checks after it we grind a new nonce for the tampered proof.
+    fn regrind_pow(proof: &mut Proof) {
+        assert_eq!(proof.hash_family, HashFamily::Blake2sForEverything);
+        let words = flatten_full_proof(proof, true);
+        let seed = std::thread::Builder::new()
+            .stack_size(VERIFIER_STACK_SIZE)
+            .spawn(move || {
+                set_iterator(words.into_iter());
+                #[allow(invalid_value)]
+                let result = unsafe {
+                    risc_v_cycles_verifier::verify_with_configuration_checked::<
+                        DefaultNonDeterminismSource,
+                        PowSeedRecordingVerifier,
+                    >(
+                        &mut MaybeUninit::uninit().assume_init(),
+                        &mut ProofPublicInputs::uninit(),
+                    )
+                };
+                assert_eq!(result, Err(VerificationError::PowFailure));
+
+                SEED_BEFORE_POW.with(|el| el.get()).unwrap()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        let worker = Worker::new_with_num_threads(8);
+        let (_, nonce) = PowSeedRecordingTranscript::search_pow(&seed, POW_BITS, &worker);
+        proof.pow_nonce = nonce;
+    }
+
+    #[test]
+    fn test_verify_base_layer_proof() {
+        let program_proof = base_layer_proof();
+        let output = verify_program_proof(program_proof).unwrap();
+        let expected: Vec<u32> = program_proof.register_final_values[10..18]
+            .iter()
+            .map(|el| el.value)
+            .collect();
+        assert_eq!(output.program_output.to_vec(), expected);
+    }
+
+    #[test]
+    fn test_tampered_witness_leaf() {
+        let mut program_proof = base_layer_proof().clone();
+        let query = &mut program_proof.base_layer_proofs[0].queries[0].witness_query;
+        query.leaf_content[0].add_assign(&Mersenne31Field::ONE);
+        let query_index = query.query_index;
+
+        let result = verify_program_proof(&program_proof);
+        assert_eq!(
+            result,
+            Err(VerificationError::MerklePathFailure {
+                oracle: CommittedOracle::Witness,
+                query_index,
+            })
+        );
+    }
+
+    #[test]
+    fn test_tampered_pow_nonce() {
+        let mut program_proof = base_layer_proof().clone();
+        program_proof.base_layer_proofs[0].pow_nonce ^= 1;
+
+        let result = verify_program_proof(&program_proof);
+        assert_eq!(result, Err(VerificationError::PowFailure));
+    }
+
+    #[test]
+    fn test_tampered_evaluation_at_z() {
+        let mut program_proof = base_layer_proof().clone();
+        let proof = &mut program_proof.base_layer_proofs[0];
+        proof.evaluations_at_random_points[0].add_assign(&Mersenne31Quartic::ONE);
+        regrind_pow(proof);
+
+        let result = verify_program_proof(&program_proof);
+        assert_eq!(result, Err(VerificationError::QuotientMismatch));
+    }
+
+    // FRI folding can't be reached by tampering with a proof: every value it checks is committed
+    // before the queries are drawn, so any change moves the query indexes and is reported as
+    // `QueryIndexMismatch`. That error needs a prover that commits to inconsistent FRI oracles.
+
+    #[test]
+    fn test_tampered_final_register_value() {
+        let worker = Worker::new_with_num_threads(8);
+        let (binary, main_circuit_precomputations, delegation_precomputations, end_params) =
+            base_program_precomputations(&worker);
+        let mut traced_execution = TracedExecution::<IMStandardIsaConfig>::trace(
+            10,
+            &binary,
+            QuasiUARTSource::new_with_reads(vec![123, 10]),
+            &worker,
+        );
+        // Prover commits to the wrong final value of x1 and derives the memory challenges from it,
+        // so transcript is consistent, but memory argument doesn't balance
+        traced_execution.final_register_values[1].value ^= 1;
+
+        let (memory_trees, delegation_memory_trees) = commit_memory_for_traced_execution(
+            &traced_execution,
+            &main_circuit_precomputations,
+            &delegation_precomputations,
+            &worker,
+        );
+        let setup_caps =
+            DefaultTreeConstructor::dump_caps(&main_circuit_precomputations.setup.trees);
+        let memory_challenges_seed = fs_transform_for_memory_and_delegation_arguments(
+            &setup_caps,
+            &traced_execution.final_register_values,
+            &memory_trees,
+            &delegation_memory_trees,
+        );
+        let external_challenges =
+            ExternalChallenges::draw_from_transcript_seed(memory_challenges_seed, true);
+        let (main_proofs, delegation_proofs) = prove_traced_execution(
+            &traced_execution,
+            &main_circuit_precomputations,
+            &delegation_precomputations,
+            external_challenges,
+            &worker,
+        );
+        let program_proof = program_proof(
+            main_proofs,
+            delegation_proofs,
+            traced_execution.final_register_values,
+            end_params,
+        );
+
+        let result = verify_program_proof(&program_proof);
+        assert_eq!(result, Err(VerificationError::GrandProductImbalance));
+    }
+}
@@ -1,6 +1,7 @@
 #![cfg_attr(not(any(test, feature = "replace_csr")), no_std)]
 #![allow(incomplete_features)]
 #![feature(generic_const_exprs)]
+#![cfg_attr(test, feature(allocator_api))]
 
 use core::mem::MaybeUninit;
 
@@ -20,9 +21,12 @@ use self::constants::*;
 use verifier_common::non_determinism_source::NonDeterminismSource;
 use verifier_common::prover::definitions::ExternalChallenges;
 use verifier_common::transcript::Blake2sBufferingTranscript;
-use verifier_common::VerifierFunctionPointer;
-use verifier_common::{parse_field_els_as_u32_from_u16_limbs_checked, ProofPublicInputs};
 use verifier_common::{prover, ProofOutput};
+use verifier_common::{try_parse_field_els_as_u32_from_u16_limbs, ProofPublicInputs};
+use verifier_common::{CheckedVerifierFunctionPointer, VerificationError, VerifierFunctionPointer};
+
+#[cfg(feature = "proof_utils")]
+pub mod host;
 
 pub const MAX_BASE_LAYER_CIRCUITS: usize = const {
     let max_unique_timestamps =
@@ -82,19 +86,19 @@ pub const BASE_LAYER_DELEGATION_CIRCUITS_VERIFICATION_PARAMETERS: &[(
     u32, // delegation type
     u32, // delegation capacity
     &[MerkleTreeCap<CAP_SIZE>; NUM_COSETS],
-    VerifierFunctionPointer<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, 0, 0>,
+    CheckedVerifierFunctionPointer<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, 0, 0>,
 )] = &[
     (
         ALL_DELEGATION_CIRCUITS_PARAMS[0].0,
         ALL_DELEGATION_CIRCUITS_PARAMS[0].1,
         &ALL_DELEGATION_CIRCUITS_PARAMS[0].2,
-        BLAKE_WITH_COMPRESSION_VERIFIER_PTR,
+        blake2_with_compression_verifier::verify_checked,
     ),
     (
         ALL_DELEGATION_CIRCUITS_PARAMS[1].0,
         ALL_DELEGATION_CIRCUITS_PARAMS[1].1,
         &ALL_DELEGATION_CIRCUITS_PARAMS[1].2,
-        BIGINT_WITH_CONTROL_VERIFIER_PTR,
+        bigint_with_control_verifier::verify_checked,
     ),
 ];
 
@@ -102,19 +106,19 @@ pub const RECURSION_LAYER_CIRCUITS_VERIFICATION_PARAMETERS: &[(
     u32,
     u32,
     &[MerkleTreeCap<CAP_SIZE>; NUM_COSETS],
-    VerifierFunctionPointer<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, 0, 0>,
+    CheckedVerifierFunctionPointer<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, 0, 0>,
 )] = &[(
     ALL_DELEGATION_CIRCUITS_PARAMS[0].0,
     ALL_DELEGATION_CIRCUITS_PARAMS[0].1,
     &ALL_DELEGATION_CIRCUITS_PARAMS[0].2,
-    BLAKE_WITH_COMPRESSION_VERIFIER_PTR,
+    blake2_with_compression_verifier::verify_checked,
 )];
 
 pub const FINAL_RECURSION_LAYER_CIRCUITS_VERIFICATION_PARAMETERS: &[(
     u32,
     u32,
     &[MerkleTreeCap<CAP_SIZE>; NUM_COSETS],
-    VerifierFunctionPointer<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, 0, 0>,
+    CheckedVerifierFunctionPointer<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, 0, 0>,
 )] = &[];
 
 const _: () = {
@@ -139,11 +143,12 @@ const _: () = {
 
 /// If we recurse over user's program -> we must provide expected final PC,
 /// and setup caps (that encode the program itself!),
-/// otherwise we only need to provide final PC
+/// otherwise we only need to provide final PC.
+/// If `expected_end_params` is provided, then hash of the final PC and setup caps must match it
 #[allow(invalid_value)]
 #[inline(never)]
 unsafe fn verify_full_statement<const BASE_LAYER: bool>(
-    main_risc_v_circuit_verifier: VerifierFunctionPointer<
+    main_risc_v_circuit_verifier: CheckedVerifierFunctionPointer<
         CAP_SIZE,
         NUM_COSETS,
         NUM_DELEGATION_CHALLENGES,
@@ -154,9 +159,10 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
         u32,
         u32,
         &[MerkleTreeCap<CAP_SIZE>; NUM_COSETS],
-        VerifierFunctionPointer<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, 0, 0>,
+        CheckedVerifierFunctionPointer<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, 0, 0>,
     )],
-) -> [u32; 16] {
+    expected_end_params: Option<&[u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]>,
+) -> Result<[u32; 16], VerificationError> {
     // we should in parallel verify proofs, and drag along the transcript to assert equality of challenges
     let mut transcript = Blake2sBufferingTranscript::new();
 
@@ -173,7 +179,9 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
     }
 
     // x0 is always 0, for sanity
-    assert_eq!(registers_buffer[0], 0);
+    if registers_buffer[0] != 0 {
+        return Err(VerificationError::InvalidRegisterValues);
+    }
 
     transcript.absorb(&registers_buffer);
 
@@ -190,11 +198,13 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
     let mut state_variables = ProofPublicInputs::uninit();
 
     let num_circuits = verifier_common::DefaultNonDeterminismSource::read_word();
-    assert!(num_circuits > 0);
-    if BASE_LAYER {
-        assert!(num_circuits <= MAX_BASE_LAYER_CIRCUITS as u32);
+    let max_circuits = if BASE_LAYER {
+        MAX_BASE_LAYER_CIRCUITS
     } else {
-        assert!(num_circuits <= MAX_RECURSION_LAYER_CIRCUITS as u32);
+        MAX_RECURSION_LAYER_CIRCUITS
+    };
+    if num_circuits == 0 || num_circuits > max_circuits as u32 {
+        return Err(VerificationError::InvalidNumberOfCircuits);
     }
     for circuit_sequence in 0..num_circuits {
         let (current, previous) = if circuit_sequence & 1 == 0 {
@@ -204,10 +214,14 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
         };
         // Note: this will make sure that all external challenges are the same as we progress,
         // and so we will only need to save the result at the very end
-        (main_risc_v_circuit_verifier)(current, &mut state_variables);
+        (main_risc_v_circuit_verifier)(current, &mut state_variables)?;
 
-        assert_eq!(current.circuit_sequence, circuit_sequence);
-        assert_eq!(current.delegation_type, 0);
+        if current.circuit_sequence != circuit_sequence {
+            return Err(VerificationError::UnexpectedCircuitSequence);
+        }
+        if current.delegation_type != 0 {
+            return Err(VerificationError::UnexpectedDelegationType);
+        }
 
         if circuit_sequence == 0 {
             // commit setup into transcript
@@ -221,64 +235,58 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
         // first over ProofOutput
         if circuit_sequence > 0 {
             // and check equality of the setup
-            assert!(MerkleTreeCap::compare(
-                &previous.setup_caps,
-                &current.setup_caps
-            ));
+            if !MerkleTreeCap::compare(&previous.setup_caps, &current.setup_caps) {
+                return Err(VerificationError::SetupMismatch);
+            }
             // check that all challenges are the same
-            assert_eq!(previous.memory_challenges, current.memory_challenges);
-            assert_eq!(
-                previous.delegation_challenges,
-                current.delegation_challenges
-            );
+            if previous.memory_challenges != current.memory_challenges
+                || previous.delegation_challenges != current.delegation_challenges
+            {
+                return Err(VerificationError::ChallengesMismatch);
+            }
 
             // check lazy inits
-            let last_previous = parse_field_els_as_u32_from_u16_limbs_checked(
+            let last_previous = try_parse_field_els_as_u32_from_u16_limbs(
                 previous.lazy_init_boundary_values[0].lazy_init_one_before_last_row,
-            );
-            let first_current = parse_field_els_as_u32_from_u16_limbs_checked(
+            )
+            .ok_or(VerificationError::MalformedProof)?;
+            let first_current = try_parse_field_els_as_u32_from_u16_limbs(
                 current.lazy_init_boundary_values[0].lazy_init_first_row,
-            );
+            )
+            .ok_or(VerificationError::MalformedProof)?;
 
             // if it's
             if first_current > last_previous {
                 // nothing, we are all good
             } else {
                 // we require padding of 0 init address, and 0 teardown value and timestamp
-                assert_eq!(last_previous, 0);
+                let boundary_values = &previous.lazy_init_boundary_values[0];
 
                 // just compare to 0 after reduction to avoid parsing u16 or timestamp bits
-                assert_eq!(
-                    previous.lazy_init_boundary_values[0].teardown_value_one_before_last_row[0]
-                        .to_reduced_u32(),
-                    0
-                );
-                assert_eq!(
-                    previous.lazy_init_boundary_values[0].teardown_value_one_before_last_row[1]
-                        .to_reduced_u32(),
-                    0
-                );
-
-                assert_eq!(
-                    previous.lazy_init_boundary_values[0].teardown_timestamp_one_before_last_row[0]
-                        .to_reduced_u32(),
-                    0
-                );
-                assert_eq!(
-                    previous.lazy_init_boundary_values[0].teardown_timestamp_one_before_last_row[1]
-                        .to_reduced_u32(),
-                    0
-                );
+                let is_padding = last_previous == 0
+                    && boundary_values.teardown_value_one_before_last_row[0].to_reduced_u32() == 0
+                    && boundary_values.teardown_value_one_before_last_row[1].to_reduced_u32() == 0
+                    && boundary_values.teardown_timestamp_one_before_last_row[0].to_reduced_u32()
+                        == 0
+                    && boundary_values.teardown_timestamp_one_before_last_row[1].to_reduced_u32()
+                        == 0;
+                if !is_padding {
+                    return Err(VerificationError::LazyInitBoundaryMismatch);
+                }
             }
         }
         // then over state variables
 
         // check continuous PC
         let start_pc =
-            parse_field_els_as_u32_from_u16_limbs_checked(state_variables.input_state_variables);
-        assert_eq!(start_pc, expected_input_pc);
+            try_parse_field_els_as_u32_from_u16_limbs(state_variables.input_state_variables)
+                .ok_or(VerificationError::MalformedProof)?;
+        if start_pc != expected_input_pc {
+            return Err(VerificationError::PcDiscontinuity);
+        }
         let end_pc =
-            parse_field_els_as_u32_from_u16_limbs_checked(state_variables.output_state_variables);
+            try_parse_field_els_as_u32_from_u16_limbs(state_variables.output_state_variables)
+                .ok_or(VerificationError::MalformedProof)?;
         expected_input_pc = end_pc;
 
         // update accumulators
@@ -318,27 +326,28 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
             for _circuit_sequence in 0..num_circuits {
                 // Note: this will make sure that all external challenges are the same as we progress,
                 // and so we will only need to save the result at the very end
-                (verification_function)(&mut delegation_proof_output, &mut state_variables);
-
-                assert_eq!(delegation_proof_output.circuit_sequence, 0);
-                assert_eq!(delegation_proof_output.delegation_type, *delegation_type);
-                assert!(MerkleTreeCap::compare(
-                    &delegation_proof_output.setup_caps,
-                    setup_caps
-                ));
+                (verification_function)(&mut delegation_proof_output, &mut state_variables)?;
+
+                if delegation_proof_output.circuit_sequence != 0 {
+                    return Err(VerificationError::UnexpectedCircuitSequence);
+                }
+                if delegation_proof_output.delegation_type != *delegation_type {
+                    return Err(VerificationError::UnexpectedDelegationType);
+                }
+                if !MerkleTreeCap::compare(&delegation_proof_output.setup_caps, setup_caps) {
+                    return Err(VerificationError::SetupMismatch);
+                }
 
                 // and commit memory caps
                 transcript.absorb(delegation_proof_output.memory_caps_flattened());
 
                 // check that we use the same challenges
-                assert_eq!(
-                    delegation_proof_output.memory_challenges,
-                    proof_output_0.memory_challenges
-                );
-                assert_eq!(
-                    delegation_proof_output.delegation_challenges,
-                    proof_output_0.delegation_challenges
-                );
+                if delegation_proof_output.memory_challenges != proof_output_0.memory_challenges
+                    || delegation_proof_output.delegation_challenges
+                        != proof_output_0.delegation_challenges
+                {
+                    return Err(VerificationError::ChallengesMismatch);
+                }
 
                 // update accumulators
                 memory_grand_product_accumulator
@@ -356,7 +365,9 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
         // we use LogUp like argument for permutation between all delegation requests and responses.
         // All requests are unique (due to timestamps), so to ensure soundness we just require that total number
         // of responses processed it < field size
-        assert!(total_delegation_requests < Mersenne31Field::CHARACTERISTICS as u64);
+        if total_delegation_requests >= Mersenne31Field::CHARACTERISTICS as u64 {
+            return Err(VerificationError::TooManyDelegationRequests);
+        }
     }
 
     // finish with the transcript, compare memory values from transcript with ones used in proofs
@@ -364,15 +375,15 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
 
     let expected_challenges =
         ExternalChallenges::draw_from_transcript_seed(memory_seed, NUM_DELEGATION_CHALLENGES > 0);
-    assert_eq!(
-        expected_challenges.memory_argument,
-        proof_output_0.memory_challenges
-    );
+    if expected_challenges.memory_argument != proof_output_0.memory_challenges {
+        return Err(VerificationError::ChallengesMismatch);
+    }
     if NUM_DELEGATION_CHALLENGES > 0 {
-        assert_eq!(
-            expected_challenges.delegation_argument.unwrap_unchecked(),
-            proof_output_0.delegation_challenges[0]
-        );
+        if expected_challenges.delegation_argument.unwrap_unchecked()
+            != proof_output_0.delegation_challenges[0]
+        {
+            return Err(VerificationError::ChallengesMismatch);
+        }
     }
 
     // conclude that our memory argument is valid
@@ -385,8 +396,12 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
             proof_output_0.memory_challenges.memory_argument_gamma,
         );
     memory_grand_product_accumulator.mul_assign(&register_contribution);
-    assert_eq!(memory_grand_product_accumulator, Mersenne31Quartic::ONE);
-    assert_eq!(delegation_set_accumulator, Mersenne31Quartic::ZERO);
+    if memory_grand_product_accumulator != Mersenne31Quartic::ONE {
+        return Err(VerificationError::GrandProductImbalance);
+    }
+    if delegation_set_accumulator != Mersenne31Quartic::ZERO {
+        return Err(VerificationError::DelegationArgumentImbalance);
+    }
 
     // Now we only need to reason about "which program do we execute", and "did it finish successfully or not".
 
@@ -403,6 +418,11 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
     result_hasher.absorb(&[expected_input_pc]);
     result_hasher.absorb(proof_output_0.setup_caps_flattened());
     let end_params_output = result_hasher.finalize_reset();
+    if let Some(expected_end_params) = expected_end_params {
+        if end_params_output.0 != *expected_end_params {
+            return Err(VerificationError::VerificationKeyMismatch);
+        }
+    }
 
     if BASE_LAYER {
         // we REQUIRE that remaining 8 registers are 0 in our convention
@@ -411,7 +431,9 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
             let value = registers_buffer[(10 + i) * 3];
             all_zeroes &= value == 0;
         }
-        assert!(all_zeroes);
+        if !all_zeroes {
+            return Err(VerificationError::InvalidRegisterValues);
+        }
 
         // we only start a chain, so we will hash a concatenation of 8x0u32 and end_params_output
         let mut buffer = [0u32; 16];
@@ -447,7 +469,9 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
         for i in 0..8 {
             equal &= preimage_hash.0[i] == aux_registers[i];
         }
-        assert!(equal);
+        if !equal {
+            return Err(VerificationError::RecursionChainMismatch);
+        }
 
         // then if last elements of the preimage are equal to the current end parameters - we do not need to continue the chain
         let mut equal = true;
@@ -478,32 +502,61 @@ unsafe fn verify_full_statement<const BASE_LAYER: bool>(
         }
     }
 
-    output
+    Ok(output)
+}
+
+#[inline(always)]
+fn unwrap_verification_result(result: Result<[u32; 16], VerificationError>) -> [u32; 16] {
+    match result {
+        Ok(output) => output,
+        Err(error) => panic!("proof verification failed: {}", error),
+    }
 }
 
 pub fn verify_base_layer() -> [u32; 16] {
+    unwrap_verification_result(verify_base_layer_checked(None))
+}
+
+pub fn verify_recursion_layer() -> [u32; 16] {
+    unwrap_verification_result(verify_recursion_layer_checked(None))
+}
+
+pub fn verify_final_recursion_layer() -> [u32; 16] {
+    unwrap_verification_result(verify_final_recursion_layer_checked(None))
+}
+
+pub fn verify_base_layer_checked(
+    expected_end_params: Option<&[u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]>,
+) -> Result<[u32; 16], VerificationError> {
     unsafe {
         verify_full_statement::<true>(
-            RISC_V_VERIFIER_PTR,
+            risc_v_cycles_verifier::verify_checked,
             BASE_LAYER_DELEGATION_CIRCUITS_VERIFICATION_PARAMETERS,
+            expected_end_params,
         )
     }
 }
 
-pub fn verify_recursion_layer() -> [u32; 16] {
+pub fn verify_recursion_layer_checked(
+    expected_end_params: Option<&[u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]>,
+) -> Result<[u32; 16], VerificationError> {
     unsafe {
         verify_full_statement::<false>(
-            RISC_V_REDUCED_MACHINE_VERIFIER_PTR,
+            reduced_risc_v_machine_verifier::verify_checked,
             RECURSION_LAYER_CIRCUITS_VERIFICATION_PARAMETERS,
+            expected_end_params,
         )
     }
 }
 
-pub fn verify_final_recursion_layer() -> [u32; 16] {
+pub fn verify_final_recursion_layer_checked(
+    expected_end_params: Option<&[u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]>,
+) -> Result<[u32; 16], VerificationError> {
     unsafe {
         verify_full_statement::<false>(
-            RISC_V_FINAL_REDUCED_MACHINE_VERIFIER_PTR,
+            final_reduced_risc_v_machine_verifier::verify_checked,
             FINAL_RECURSION_LAYER_CIRCUITS_VERIFICATION_PARAMETERS,
+            expected_end_params,
         )
     }
 }
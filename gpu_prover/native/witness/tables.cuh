@@ -46,6 +46,9 @@ enum TableType : u16 {
   StoreByteSourceContribution,
   StoreByteExistingContribution,
   TruncateShift,
+  BitCountAndSignExtend,
+  ByteWiseBitmanip,
+  RomInstructionRead,
   DynamicPlaceholder,
 };
 
@@ -325,6 +328,14 @@ template <unsigned K, unsigned V> struct TableDriver {
     return absolute_index;
   }
 
+  DEVICE_FORCEINLINE u32 rom_instruction_read(const bf keys[K], bf *values) const {
+    const u32 index = bf::into_canonical_u32(keys[0]) >> 1;
+    const u32 absolute_index = get_absolute_index<RomInstructionRead>(index);
+    if (V != 0)
+      set_values_from_tables(absolute_index, values);
+    return absolute_index;
+  }
+
   DEVICE_FORCEINLINE u32 special_csr_properties(const bf keys[K], bf *values) const {
     return single_key_set_values_from_tables<SpecialCSRProperties>(keys, values);
   }
@@ -457,6 +468,81 @@ template <unsigned K, unsigned V> struct TableDriver {
     return get_absolute_index<TruncateShift>(index);
   }
 
+  DEVICE_FORCEINLINE u32 bit_count_and_sign_extend(const bf keys[K], bf *values) const {
+    auto setter = [](const u32 index, u32 *result) {
+      const u32 word = index & 0xffff;
+      const u32 selector = index >> 16 & 0x7;
+      const bool is_high_half = index >> 19 != 0;
+      const u32 leading_zeros = __clz(word) - 16;
+      const u32 trailing_zeros = word == 0 ? 16 : __ffs(word) - 1;
+      const u32 is_zero = word == 0 ? 1 : 0;
+      result[0] = 0;
+      result[1] = 0;
+      switch (selector) {
+      case 0b000:
+        // CLZ
+        result[0] = is_high_half ? leading_zeros : 0;
+        result[1] = is_high_half ? is_zero : leading_zeros;
+        break;
+      case 0b001:
+        // CTZ
+        result[0] = is_high_half ? 0 : trailing_zeros;
+        result[1] = is_high_half ? trailing_zeros : is_zero;
+        break;
+      case 0b010:
+        // CPOP
+        result[0] = __popc(word);
+        break;
+      case 0b100:
+        // SEXT.B
+        if (!is_high_half) {
+          const u32 extended = (word & 0x80) != 0 ? word | 0xffffff00 : word & 0xff;
+          result[0] = extended & 0xffff;
+          result[1] = extended >> 16;
+        }
+        break;
+      case 0b101:
+        // SEXT.H
+        if (!is_high_half) {
+          const u32 extended = (word & 0x8000) != 0 ? word | 0xffff0000 : word;
+          result[0] = extended & 0xffff;
+          result[1] = extended >> 16;
+        }
+        break;
+      default:
+        break;
+      }
+    };
+    return set_values_from_single_key<BitCountAndSignExtend>(keys, values, setter);
+  }
+
+  DEVICE_FORCEINLINE u32 byte_wise_bitmanip(const bf keys[K], bf *values) const {
+    auto setter = [](const u32 index, u32 *result) {
+      const u32 word = index & 0xffff;
+      const u32 mode = index >> 16;
+      const u32 low = word & 0xff;
+      const u32 high = word >> 8;
+      switch (mode) {
+      case 0:
+        // ORC.B
+        result[0] = (low != 0 ? 0xff : 0) | (high != 0 ? 0xff00 : 0);
+        break;
+      case 1:
+        // BREV8
+        result[0] = __brev(low) >> 24 | (__brev(high) >> 24) << 8;
+        break;
+      case 2:
+        // byte swap
+        result[0] = low << 8 | high;
+        break;
+      default:
+        result[0] = 0;
+      }
+      result[1] = 0;
+    };
+    return set_values_from_single_key<ByteWiseBitmanip>(keys, values, setter);
+  }
+
   DEVICE_FORCEINLINE u32 get_index_and_set_values(const TableType table_type, const bf keys[K], bf *values) const {
     switch (table_type) {
     case ZeroEntry:
@@ -534,6 +620,12 @@ template <unsigned K, unsigned V> struct TableDriver {
       return store_byte_existing_contribution(keys, values);
     case TruncateShift:
       return truncate_shift(keys, values);
+    case BitCountAndSignExtend:
+      return bit_count_and_sign_extend(keys, values);
+    case ByteWiseBitmanip:
+      return byte_wise_bitmanip(keys, values);
+    case RomInstructionRead:
+      return rom_instruction_read(keys, values);
     default:
       __trap();
     }
@@ -1,6 +1,8 @@
 use prover::risc_v_simulator::delegations::blake2_round_function_with_compression_mode::BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID;
 use prover::risc_v_simulator::delegations::u256_ops_with_control::U256_OPS_WITH_CONTROL_ACCESS_ID;
 
+pub use trace_and_split::execution_prover::MainCircuitType;
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum CircuitType {
     Main(MainCircuitType),
@@ -30,14 +32,6 @@ impl CircuitType {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-pub enum MainCircuitType {
-    FinalReducedRiscVMachine,
-    MachineWithoutSignedMulDiv,
-    ReducedRiscVMachine,
-    RiscVCycles,
-}
-
 #[repr(u32)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum DelegationCircuitType {
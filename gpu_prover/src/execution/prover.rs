@@ -42,31 +42,22 @@ use std::hash::Hash;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Instant;
+use trace_and_split::execution_prover::{
+    ExecutionProofs, ExecutionProverBackend, MemoryCommitments,
+};
 use trace_and_split::{
     fs_transform_for_memory_and_delegation_arguments, setups, FinalRegisterValue,
 };
 use worker::Worker;
 
+pub use trace_and_split::execution_prover::ExecutableBinary;
+
 type A = ConcurrentStaticHostAllocator;
 
 const CPU_WORKERS_COUNT: usize = 6;
 const CYCLES_TRACING_WORKERS_COUNT: usize = CPU_WORKERS_COUNT - 2;
 const CACHE_DELEGATIONS: bool = false;
 
-/// Represents an executable binary that can be proven by the prover
-///
-///  # Fields
-/// * `key`: unique identifier for the binary, can be for example a &str or usize, anything that implements Clone, Debug, Eq, and Hash
-/// * `circuit_type`: the type of the circuit this binary is for, one of the values from the `MainCircuitType` enumeration
-/// * `bytecode`: the bytecode of the binary, can be a Vec<u32> or any other type that can be converted into Box<[u32]>
-///
-#[derive(Clone)]
-pub struct ExecutableBinary<K: Clone + Debug + Eq + Hash, B: Into<Box<[u32]>>> {
-    pub key: K,
-    pub circuit_type: MainCircuitType,
-    pub bytecode: B,
-}
-
 struct BinaryHolder {
     circuit_type: MainCircuitType,
     bytecode: Arc<Box<[u32]>>,
@@ -1025,12 +1016,9 @@ impl<K: Clone + Debug + Eq + Hash> ExecutionProver<K> {
             &main_memory_commitments,
             &delegation_memory_commitments,
         );
-        let produce_delegation_challenge = match &self.binaries[&binary_key].circuit_type {
-            MainCircuitType::FinalReducedRiscVMachine => false,
-            MainCircuitType::MachineWithoutSignedMulDiv => true,
-            MainCircuitType::ReducedRiscVMachine => true,
-            MainCircuitType::RiscVCycles => true,
-        };
+        let produce_delegation_challenge = self.binaries[&binary_key]
+            .circuit_type
+            .supports_delegation();
         let external_challenges = ExternalChallenges::draw_from_transcript_seed(
             memory_challenges_seed,
             produce_delegation_challenge,
@@ -1203,6 +1191,60 @@ impl<K: Clone + Debug + Eq + Hash> ExecutionProver<K> {
     }
 }
 
+impl<K: Clone + Debug + Eq + Hash, ND: NonDeterminism + Send + Sync + 'static>
+    ExecutionProverBackend<K, ND> for ExecutionProver<K>
+{
+    fn commit_memory(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+    ) -> MemoryCommitments {
+        ExecutionProver::commit_memory(
+            self,
+            batch_id,
+            binary_key,
+            num_instances_upper_bound,
+            non_determinism_source,
+        )
+    }
+
+    fn prove(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+        external_challenges: ExternalChallenges,
+    ) -> ExecutionProofs {
+        ExecutionProver::prove(
+            self,
+            batch_id,
+            binary_key,
+            num_instances_upper_bound,
+            non_determinism_source,
+            external_challenges,
+        )
+    }
+
+    fn commit_memory_and_prove(
+        &self,
+        batch_id: u64,
+        binary_key: &K,
+        num_instances_upper_bound: usize,
+        non_determinism_source: ND,
+    ) -> ExecutionProofs {
+        ExecutionProver::commit_memory_and_prove(
+            self,
+            batch_id,
+            binary_key,
+            num_instances_upper_bound,
+            non_determinism_source,
+        )
+    }
+}
+
 impl<'a, K: Debug + Eq + Hash> Drop for ExecutionProver<K> {
     fn drop(&mut self) {
         trace!("PROVER waiting for all threads to finish");
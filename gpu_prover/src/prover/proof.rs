@@ -19,13 +19,17 @@ use era_cudart::stream::{CudaStream, CudaStreamWaitEventFlags};
 use fft::{GoodAllocator, LdePrecomputations, Twiddles};
 use field::{Mersenne31Complex, Mersenne31Field};
 use itertools::Itertools;
-use prover::definitions::{ExternalValues, Transcript, OPTIMAL_FOLDING_PROPERTIES};
+use prover::definitions::{ExternalValues, HashFamily, Transcript, OPTIMAL_FOLDING_PROPERTIES};
 use prover::prover_stages::cached_data::ProverCachedData;
 use prover::prover_stages::Proof;
 use prover::transcript::Seed;
 use std::ops::Deref;
 use std::sync::{Arc, Mutex};
 
+// Trees are built by Blake2s kernels and the transcript is Blake2s `Transcript`, no matter which
+// hash family CPU prover is compiled with
+const HASH_FAMILY: HashFamily = HashFamily::Blake2sForEverything;
+
 pub struct ProofJob<'a> {
     ranges: Vec<device_tracing::Range<'a>>,
     is_finished_event: CudaEvent,
@@ -146,6 +150,7 @@ impl<'a> ProofJob<'a> {
             pow_nonce,
             circuit_sequence,
             delegation_type,
+            hash_family: HASH_FAMILY,
         };
         Ok((proof, proof_time_ms))
     }
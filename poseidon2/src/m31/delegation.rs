@@ -93,3 +93,147 @@ impl Poseidon2Compressor {
         }
     }
 }
+
+// Full permutation delegation ABI:
+// - x10 is a pointer to 64-byte aligned buffer of 16 words of the permutation state
+//
+// Delegation performs full permutation in place and does NOT reduce the output. It uses its own CSR,
+// as 0x7c6 is reserved for the compressor ABI above, that has no delegation circuit
+
+pub const CSR_REGISTER_TO_TRIGGER: u32 = 0x7cd;
+
+pub const POSEIDON2_STATE_WIDTH: usize = 16;
+
+#[cfg(all(target_arch = "riscv32", feature = "delegation"))]
+#[inline(always)]
+fn csr_trigger_permutation_delegation(state_ptr: *mut u32) {
+    unsafe {
+        core::arch::asm!(
+            "csrrw x0, 0x7cd, x0",
+            in("x10") state_ptr.addr(),
+            options(nostack, preserves_flags)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C, align(64))]
+pub struct Poseidon2Permutation {
+    pub state: [Mersenne31Field; POSEIDON2_STATE_WIDTH],
+}
+
+impl Poseidon2Permutation {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            state: [Mersenne31Field(0); POSEIDON2_STATE_WIDTH],
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_state(&self) -> [Mersenne31Field; POSEIDON2_STATE_WIDTH] {
+        let mut result = self.state;
+        for dst in result.iter_mut() {
+            *dst = Mersenne31Field::from_nonreduced_u32(dst.0);
+        }
+
+        result
+    }
+
+    #[inline(always)]
+    pub fn permute(&mut self) {
+        #[cfg(all(target_arch = "riscv32", feature = "delegation"))]
+        {
+            csr_trigger_permutation_delegation(self.state.as_mut_ptr().cast::<u32>());
+        }
+
+        #[cfg(not(all(target_arch = "riscv32", feature = "delegation")))]
+        {
+            use crate::m31::poseidon_permutation;
+
+            let mut state = self.get_state();
+            poseidon_permutation(&mut state);
+            self.state = state;
+        }
+    }
+
+    /// Compresses `input` with a sibling taken from the non-determinism source, using single permutation
+    /// with feed-forward of the first half of the state. Output is written back to `input` and is reduced
+    #[inline(always)]
+    pub unsafe fn provide_witness_and_compress<I: NonDeterminismSource>(
+        &mut self,
+        input: &mut [Mersenne31Field; HASH_SIZE_U32_WORDS],
+        input_is_right: bool,
+    ) {
+        use field::PrimeField;
+
+        let (input_offset, witness_offset) = if input_is_right {
+            (HASH_SIZE_U32_WORDS, 0)
+        } else {
+            (0, HASH_SIZE_U32_WORDS)
+        };
+        for i in 0..HASH_SIZE_U32_WORDS {
+            self.state[i + input_offset] = input[i];
+            self.state[i + witness_offset] = Mersenne31Field(I::read_reduced_field_element(
+                Mersenne31Field::CHARACTERISTICS as u32,
+            ));
+        }
+        let mut feed_forward = [Mersenne31Field(0); HASH_SIZE_U32_WORDS];
+        feed_forward.copy_from_slice(&self.state[..HASH_SIZE_U32_WORDS]);
+
+        self.permute();
+
+        for i in 0..HASH_SIZE_U32_WORDS {
+            input[i] = Mersenne31Field::from_u62(
+                Mersenne31Field::from_nonreduced_u32(self.state[i].0).0 as u64
+                    + feed_forward[i].0 as u64,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SIBLING_WORD: u32 = 0x1234_5678;
+
+    #[derive(Clone, Copy, Debug)]
+    struct ConstantSource;
+
+    impl NonDeterminismSource for ConstantSource {
+        fn read_word() -> u32 {
+            SIBLING_WORD
+        }
+        fn read_reduced_field_element(modulus: u32) -> u32 {
+            SIBLING_WORD % modulus
+        }
+    }
+
+    #[test]
+    fn permutation_based_compression_matches_compress() {
+        let input: [Mersenne31Field; HASH_SIZE_U32_WORDS] =
+            core::array::from_fn(|i| Mersenne31Field::new((i as u32 + 1) * 0x0101_0101));
+        let sibling = [Mersenne31Field::new(SIBLING_WORD); HASH_SIZE_U32_WORDS];
+
+        for input_is_right in [false, true] {
+            let (left, right) = if input_is_right {
+                (sibling, input)
+            } else {
+                (input, sibling)
+            };
+            let mut full_input = [Mersenne31Field(0); POSEIDON2_STATE_WIDTH];
+            full_input[..HASH_SIZE_U32_WORDS].copy_from_slice(&left);
+            full_input[HASH_SIZE_U32_WORDS..].copy_from_slice(&right);
+            let expected = crate::m31::poseidon2_compress(&full_input);
+
+            let mut output = input;
+            let mut permutation = Poseidon2Permutation::new();
+            unsafe {
+                permutation
+                    .provide_witness_and_compress::<ConstantSource>(&mut output, input_is_right);
+            }
+            assert_eq!(output, expected);
+        }
+    }
+}
@@ -1,13 +1,15 @@
 mod delegation;
 pub mod naive;
 mod poseidon2;
+mod sponge;
 
 pub const HASH_SIZE_U32_WORDS: usize = 8;
 
 use field::{Field, Mersenne31Field, PrimeField};
 
-pub use self::delegation::Poseidon2Compressor;
+pub use self::delegation::{Poseidon2Compressor, Poseidon2Permutation};
 pub use self::poseidon2::{poseidon2_compress, poseidon_permutation};
+pub use self::sponge::{Poseidon2Sponge, POSEIDON2_SPONGE_RATE};
 
 pub(crate) const POSEIDON2_INTERNAL_MATRIX_DIAG_16_SHIFTS: [u8; 15] =
     [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 12, 13, 14, 15, 16];
@@ -0,0 +1,104 @@
+use super::{poseidon_permutation, HASH_SIZE_U32_WORDS};
+use field::{Field, Mersenne31Field};
+
+pub const POSEIDON2_SPONGE_RATE: usize = 8;
+
+// Sponge over width 16 permutation in overwrite mode, with rate 8 and capacity 8. Input is always padded
+// by a single `1` followed by zeroes up to the multiple of the rate, so inputs of different length
+// never collide. It's used to hash Merkle tree leafs and to drive the transcript when Poseidon2 is
+// used for everything
+
+#[derive(Clone, Copy, Debug)]
+pub struct Poseidon2Sponge {
+    state: [Mersenne31Field; 16],
+    offset: usize,
+}
+
+impl Poseidon2Sponge {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            state: [Mersenne31Field::ZERO; 16],
+            offset: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.state = [Mersenne31Field::ZERO; 16];
+        self.offset = 0;
+    }
+
+    #[inline(always)]
+    pub fn absorb_single(&mut self, value: Mersenne31Field) {
+        self.state[self.offset] = value;
+        self.offset += 1;
+        if self.offset == POSEIDON2_SPONGE_RATE {
+            poseidon_permutation(&mut self.state);
+            self.offset = 0;
+        }
+    }
+
+    pub fn absorb(&mut self, values: &[Mersenne31Field]) {
+        for el in values.iter() {
+            self.absorb_single(*el);
+        }
+    }
+
+    /// Pads the input and returns fully reduced output. Sponge should be reset before reuse
+    pub fn finalize(&mut self) -> [Mersenne31Field; HASH_SIZE_U32_WORDS] {
+        self.state[self.offset] = Mersenne31Field::ONE;
+        for dst in self.state[(self.offset + 1)..POSEIDON2_SPONGE_RATE].iter_mut() {
+            *dst = Mersenne31Field::ZERO;
+        }
+        poseidon_permutation(&mut self.state);
+        self.offset = 0;
+
+        core::array::from_fn(|i| Mersenne31Field::new(self.state[i].to_reduced_u32()))
+    }
+
+    pub fn finalize_reset(&mut self) -> [Mersenne31Field; HASH_SIZE_U32_WORDS] {
+        let output = self.finalize();
+        self.reset();
+
+        output
+    }
+
+    pub fn hash(values: &[Mersenne31Field]) -> [Mersenne31Field; HASH_SIZE_U32_WORDS] {
+        let mut sponge = Self::new();
+        sponge.absorb(values);
+
+        sponge.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padding_separates_lengths() {
+        let values: [Mersenne31Field; 9] = core::array::from_fn(|i| Mersenne31Field(i as u32));
+        let mut outputs = vec![];
+        for len in 0..=values.len() {
+            outputs.push(Poseidon2Sponge::hash(&values[..len]));
+        }
+        // also trailing zero must not collide with shorter input
+        let mut with_zero = [Mersenne31Field::ZERO; 8];
+        with_zero[..7].copy_from_slice(&values[..7]);
+        outputs.push(Poseidon2Sponge::hash(&with_zero));
+
+        for i in 0..outputs.len() {
+            for j in (i + 1)..outputs.len() {
+                assert_ne!(outputs[i], outputs[j]);
+            }
+        }
+
+        let mut sponge = Poseidon2Sponge::new();
+        for el in values.iter() {
+            sponge.absorb_single(*el);
+        }
+        assert_eq!(sponge.finalize_reset(), Poseidon2Sponge::hash(&values));
+        assert_eq!(sponge.finalize(), Poseidon2Sponge::hash(&[]));
+    }
+}
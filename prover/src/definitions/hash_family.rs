@@ -0,0 +1,15 @@
+/// Hash family used for Merkle tree commitments and the transcript. It's recorded in the proof,
+/// so the verifier (or a wrapper that re-verifies the proof in another proof system) knows which
+/// one to instantiate
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[repr(u8)]
+pub enum HashFamily {
+    #[default]
+    Blake2sForEverything = 0,
+    // leafs are hashed by Blake2s, nodes by Poseidon2, transcript is Blake2s
+    Blake2sForLeafsPoseidon2ForNodes = 1,
+    // leafs, nodes and transcript are Poseidon2 over Mersenne31
+    Poseidon2ForEverything = 2,
+}
@@ -7,6 +7,9 @@ pub struct Blake2sForEverythingVerifier {
 }
 
 impl LeafInclusionVerifier for Blake2sForEverythingVerifier {
+    type Transcript = Blake2sTranscript;
+    const HASH_FAMILY: HashFamily = HashFamily::Blake2sForEverything;
+
     #[inline(always)]
     fn new() -> Self {
         Self {
@@ -10,6 +10,9 @@ pub struct Blake2sForEverythingVerifierWithAlternativeCompression {
 }
 
 impl LeafInclusionVerifier for Blake2sForEverythingVerifierWithAlternativeCompression {
+    type Transcript = Blake2sTranscript;
+    const HASH_FAMILY: HashFamily = HashFamily::Blake2sForEverything;
+
     #[inline(always)]
     fn new() -> Self {
         Self {
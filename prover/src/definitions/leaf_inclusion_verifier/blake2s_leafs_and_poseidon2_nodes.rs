@@ -5,15 +5,20 @@ use blake2s_u32::BLAKE2S_BLOCK_SIZE_U32_WORDS;
 #[derive(Debug)]
 pub struct Blake2sForLeafsPoseidon2ForNodesVerifier {
     hasher: blake2s_u32::Blake2sState,
-    poseidon2_hasher: poseidon2::m31::Poseidon2Compressor,
+    poseidon2_hasher: poseidon2::m31::Poseidon2Permutation,
+    node_hash: [::field::Mersenne31Field; poseidon2::m31::HASH_SIZE_U32_WORDS],
 }
 
 impl LeafInclusionVerifier for Blake2sForLeafsPoseidon2ForNodesVerifier {
+    type Transcript = Blake2sTranscript;
+    const HASH_FAMILY: HashFamily = HashFamily::Blake2sForLeafsPoseidon2ForNodes;
+
     #[inline(always)]
     fn new() -> Self {
         Self {
             hasher: blake2s_u32::Blake2sState::new(),
-            poseidon2_hasher: poseidon2::m31::Poseidon2Compressor::new(),
+            poseidon2_hasher: poseidon2::m31::Poseidon2Permutation::new(),
+            node_hash: [::field::Mersenne31Field(0); poseidon2::m31::HASH_SIZE_U32_WORDS],
         }
     }
 
@@ -33,7 +38,7 @@ impl LeafInclusionVerifier for Blake2sForLeafsPoseidon2ForNodesVerifier {
     ) -> bool {
         // our strategy is:
         // - since leaf is used for other purposes, we have to copy it into the buffer, no options here
-        // - but when we output the leaf hash, we will put it into the input buffer of Poseidon2 and keep it there
+        // - but when we output the leaf hash, we will put it into the node hash buffer and compress it in place with Poseidon2
         self.hasher.reset();
 
         let input_len_words = leaf_encoding.len();
@@ -107,23 +112,22 @@ impl LeafInclusionVerifier for Blake2sForLeafsPoseidon2ForNodesVerifier {
             let current_state = self.hasher.read_state_for_output_ref();
             blake2s_u32::spec_memcopy_u32_nonoverlapping(
                 current_state.as_ptr().cast::<u32>(),
-                self.poseidon2_hasher.input.as_mut_ptr().cast::<u32>(),
+                self.node_hash.as_mut_ptr().cast::<u32>(),
                 poseidon2::m31::HASH_SIZE_U32_WORDS,
             );
             self.poseidon2_hasher
-                .provide_witness_and_compress::<I>(input_is_right);
+                .provide_witness_and_compress::<I>(&mut self.node_hash, input_is_right);
         }
 
         for _ in 1..depth {
             let input_is_right = index & 1 == 1;
             index >>= 1;
             self.poseidon2_hasher
-                .provide_witness_and_compress::<I>(input_is_right);
+                .provide_witness_and_compress::<I>(&mut self.node_hash, input_is_right);
         }
 
-        // this performs full reduction
-        let output_hash: [u32; DIGEST_SIZE_U32_WORDS] =
-            core::mem::transmute(self.poseidon2_hasher.get_output());
+        // compression output is fully reduced
+        let output_hash: [u32; DIGEST_SIZE_U32_WORDS] = core::mem::transmute(self.node_hash);
 
         // here we manually compare, otherwise it's compiled as memcmp that does by byte(!) comparison
         // output_hash == &merkle_cap[coset_index as usize].cap[index]
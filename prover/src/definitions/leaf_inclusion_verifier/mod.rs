@@ -1,6 +1,7 @@
-use crate::definitions::MerkleTreeCap;
+use crate::definitions::{HashFamily, MerkleTreeCap};
 use core::fmt::Debug;
 use non_determinism_source::NonDeterminismSource;
+use transcript::{Blake2sTranscript, Poseidon2Transcript, StatelessTranscript};
 
 mod blake2s_leafs_and_poseidon2_nodes;
 pub use self::blake2s_leafs_and_poseidon2_nodes::Blake2sForLeafsPoseidon2ForNodesVerifier;
@@ -11,10 +12,17 @@ pub use self::blake2s_for_everything::Blake2sForEverythingVerifier;
 mod blake2s_for_everything_with_alternative_compression;
 pub use self::blake2s_for_everything_with_alternative_compression::Blake2sForEverythingVerifierWithAlternativeCompression;
 
+mod poseidon2_for_everything;
+pub use self::poseidon2_for_everything::Poseidon2ForEverythingVerifier;
+
 // const USE_REDUCED_BLAKE2_ROUNDS: bool = false;
 const USE_REDUCED_BLAKE2_ROUNDS: bool = true;
 
 pub trait LeafInclusionVerifier: 'static + Send + Sync + Debug {
+    // transcript is a part of the hash family, so the whole proof is either Blake2s based or arithmetization friendly
+    type Transcript: StatelessTranscript;
+    const HASH_FAMILY: HashFamily;
+
     fn new() -> Self;
     unsafe fn verify_leaf_inclusion<
         I: NonDeterminismSource,
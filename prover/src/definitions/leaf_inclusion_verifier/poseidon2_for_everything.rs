@@ -0,0 +1,72 @@
+use super::*;
+use crate::definitions::DIGEST_SIZE_U32_WORDS;
+use ::field::Mersenne31Field;
+
+#[derive(Debug)]
+pub struct Poseidon2ForEverythingVerifier {
+    sponge: poseidon2::m31::Poseidon2Sponge,
+    poseidon2_hasher: poseidon2::m31::Poseidon2Permutation,
+}
+
+impl LeafInclusionVerifier for Poseidon2ForEverythingVerifier {
+    type Transcript = Poseidon2Transcript;
+    const HASH_FAMILY: HashFamily = HashFamily::Poseidon2ForEverything;
+
+    #[inline(always)]
+    fn new() -> Self {
+        Self {
+            sponge: poseidon2::m31::Poseidon2Sponge::new(),
+            poseidon2_hasher: poseidon2::m31::Poseidon2Permutation::new(),
+        }
+    }
+
+    #[inline(always)]
+    #[unroll::unroll_for_loops]
+    unsafe fn verify_leaf_inclusion<
+        I: NonDeterminismSource,
+        const CAP_SIZE: usize,
+        const NUM_COSETS: usize,
+    >(
+        &mut self,
+        coset_index: u32,
+        leaf_index: u32,
+        depth: usize,
+        leaf_encoding: &[u32],
+        merkle_cap: &[MerkleTreeCap<CAP_SIZE>; NUM_COSETS],
+    ) -> bool {
+        // leaf is a sequence of field elements, so we just absorb it into the sponge,
+        // and then compress the leaf hash in place with Poseidon2 up to the cap
+        self.sponge.reset();
+        for word in leaf_encoding.iter() {
+            self.sponge
+                .absorb_single(Mersenne31Field::from_nonreduced_u32(*word));
+        }
+        let mut node_hash = self.sponge.finalize();
+
+        let mut index = leaf_index as usize;
+        for _ in 0..depth {
+            let input_is_right = index & 1 == 1;
+            index >>= 1;
+            self.poseidon2_hasher
+                .provide_witness_and_compress::<I>(&mut node_hash, input_is_right);
+        }
+
+        // sponge and compression outputs are fully reduced
+        let output_hash: [u32; DIGEST_SIZE_U32_WORDS] = core::mem::transmute(node_hash);
+
+        // here we manually compare, otherwise it's compiled as memcmp that does by byte(!) comparison
+        // output_hash == &merkle_cap[coset_index as usize].cap[index]
+
+        let cap = merkle_cap
+            .get_unchecked(coset_index as usize)
+            .cap
+            .get_unchecked(index);
+        let mut equal = true;
+        for i in 0..8 {
+            // DIGEST_SIZE_U32_WORDS
+            equal &= output_hash[i] == cap[i];
+        }
+
+        equal
+    }
+}
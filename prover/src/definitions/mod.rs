@@ -3,10 +3,12 @@ use ::field::*;
 use blake2s_u32::BLAKE2S_DIGEST_SIZE_U32_WORDS;
 use transcript::{Blake2sTranscript, Seed};
 
+mod hash_family;
 mod hash_like_holder;
 mod leaf_inclusion_verifier;
 mod optimal_folding;
 
+pub use self::hash_family::*;
 pub use self::hash_like_holder::*;
 pub use self::leaf_inclusion_verifier::*;
 pub use self::optimal_folding::*;
@@ -42,6 +42,8 @@ pub mod merkle_trees;
 #[cfg(feature = "prover")]
 pub mod nd_source_std;
 #[cfg(feature = "prover")]
+pub mod proof_encoding;
+#[cfg(feature = "prover")]
 pub mod prover_stages;
 #[cfg(feature = "prover")]
 pub mod quotient_evaluator;
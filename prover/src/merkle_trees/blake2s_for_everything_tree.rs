@@ -5,6 +5,7 @@ use blake2s_hash_leafs::{
     blake2s_leaf_hashes_for_column_major_coset, blake2s_leaf_hashes_for_coset,
     blake2s_leaf_hashes_separated_for_coset,
 };
+use blake2s_u32::vectorized_impls::Blake2sState;
 use blake2s_u32::*;
 use std::alloc::Global;
 
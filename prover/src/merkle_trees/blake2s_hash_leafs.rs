@@ -1,4 +1,5 @@
 use super::*;
+use blake2s_u32::vectorized_impls::Blake2sState;
 use blake2s_u32::*;
 use fft::bitreverse_enumeration_inplace;
 
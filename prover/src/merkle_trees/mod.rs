@@ -1,4 +1,4 @@
-use crate::definitions::{LeafInclusionVerifier, MerkleTreeCap, DIGEST_SIZE_U32_WORDS};
+use crate::definitions::{HashFamily, LeafInclusionVerifier, MerkleTreeCap, DIGEST_SIZE_U32_WORDS};
 
 // const USE_REDUCED_BLAKE2_ROUNDS: bool = false;
 const USE_REDUCED_BLAKE2_ROUNDS: bool = true;
@@ -14,17 +14,30 @@ use worker::Worker;
 pub mod blake2s_for_everything_tree;
 pub mod blake2s_for_leafs_poseidon2_for_nodes_tree;
 pub mod blake2s_hash_leafs;
+pub mod poseidon2_for_everything_tree;
+pub mod poseidon2_hash_leafs;
 
+#[cfg(not(feature = "poseidon2_for_everything"))]
 pub type DefaultTreeConstructor =
     crate::merkle_trees::blake2s_for_everything_tree::Blake2sU32MerkleTreeWithCap<
         std::alloc::Global,
     >;
 
+#[cfg(feature = "poseidon2_for_everything")]
+pub type DefaultTreeConstructor =
+    crate::merkle_trees::poseidon2_for_everything_tree::Poseidon2MerkleTreeWithCap<
+        std::alloc::Global,
+    >;
+
 // pub type DefaultTreeConstructor =
 //     crate::merkle_trees::blake2s_for_leafs_poseidon2_for_nodes_tree::Blake2sU32ForLeafsPoseidon2ForNodesTree<
 //         std::alloc::Global,
 //     >;
 
+/// Transcript of the hash family of the tree
+pub type TranscriptFor<T> =
+    <<T as MerkleTreeConstructor>::Verifier as LeafInclusionVerifier>::Transcript;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct MerkleTreeCapVarLength {
     pub cap: Vec<[u32; DIGEST_SIZE_U32_WORDS]>,
@@ -40,6 +53,7 @@ impl MerkleTreeCapVarLength {
 
 pub trait MerkleTreeConstructor: Sized + Send + Sync {
     type Verifier: LeafInclusionVerifier;
+    const HASH_FAMILY: HashFamily = <Self::Verifier as LeafInclusionVerifier>::HASH_FAMILY;
 
     fn construct_for_coset<A: GoodAllocator, const N: usize>(
         trace: &RowMajorTrace<Mersenne31Field, N, A>,
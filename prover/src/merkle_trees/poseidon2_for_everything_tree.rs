@@ -0,0 +1,264 @@
+use crate::definitions::Poseidon2ForEverythingVerifier;
+
+use super::*;
+use poseidon2::m31::poseidon2_compress;
+use poseidon2_hash_leafs::{
+    poseidon2_leaf_hashes_for_column_major_coset, poseidon2_leaf_hashes_for_coset,
+    poseidon2_leaf_hashes_separated_for_coset,
+};
+use std::alloc::Global;
+
+#[derive(Clone, Debug)]
+pub struct Poseidon2MerkleTreeWithCap<A: GoodAllocator = Global> {
+    pub cap_size: usize,
+    pub leaf_hashes: Vec<[Mersenne31Field; HASH_SIZE_U32_WORDS], A>,
+    pub node_hashes_enumerated_from_leafs: Vec<Vec<[Mersenne31Field; HASH_SIZE_U32_WORDS], A>>,
+}
+
+impl<B: GoodAllocator> MerkleTreeConstructor for Poseidon2MerkleTreeWithCap<B> {
+    type Verifier = Poseidon2ForEverythingVerifier;
+
+    fn construct_for_coset<A: GoodAllocator, const N: usize>(
+        trace: &RowMajorTrace<Mersenne31Field, N, A>,
+        cap_size: usize,
+        bitreverse: bool,
+        worker: &Worker,
+    ) -> Self {
+        let leaf_hashes = poseidon2_leaf_hashes_for_coset(trace, bitreverse, worker);
+
+        Self::continue_from_leaf_hashes(leaf_hashes, cap_size, worker)
+    }
+
+    fn construct_separated_for_coset<A: GoodAllocator, const N: usize>(
+        trace: &RowMajorTrace<Mersenne31Field, N, A>,
+        separators: &[usize],
+        cap_size: usize,
+        bitreverse: bool,
+        worker: &Worker,
+    ) -> Vec<Self> {
+        let leaf_hashes_set =
+            poseidon2_leaf_hashes_separated_for_coset(trace, separators, bitreverse, worker);
+
+        leaf_hashes_set
+            .into_iter()
+            .map(|lh| Self::continue_from_leaf_hashes(lh, cap_size, worker))
+            .collect()
+    }
+
+    fn construct_for_column_major_coset<A: GoodAllocator>(
+        trace: &ColumnMajorTrace<Mersenne31Quartic, A>,
+        combine_by: usize,
+        cap_size: usize,
+        bitreverse: bool,
+        worker: &Worker,
+    ) -> Self {
+        let leaf_hashes =
+            poseidon2_leaf_hashes_for_column_major_coset(trace, combine_by, bitreverse, worker);
+
+        Self::continue_from_leaf_hashes(leaf_hashes, cap_size, worker)
+    }
+
+    fn get_cap(&self) -> MerkleTreeCapVarLength {
+        let layer = if let Some(cap) = self.node_hashes_enumerated_from_leafs.last() {
+            &cap[..]
+        } else {
+            &self.leaf_hashes[..]
+        };
+        let output = layer
+            .iter()
+            .map(|el| el.map(|el| el.to_reduced_u32()))
+            .collect();
+
+        MerkleTreeCapVarLength { cap: output }
+    }
+
+    fn get_proof<C: GoodAllocator>(
+        &self,
+        idx: usize,
+    ) -> (
+        [u32; HASH_SIZE_U32_WORDS],
+        Vec<[u32; HASH_SIZE_U32_WORDS], C>,
+    ) {
+        let depth = self.node_hashes_enumerated_from_leafs.len(); // we do not need the element of the cap
+        let mut result = Vec::with_capacity_in(depth, C::default());
+        let mut idx = idx;
+        let this_el_leaf_hash = self.leaf_hashes[idx].map(|el| el.to_reduced_u32());
+        for i in 0..depth {
+            let pair_idx = idx ^ 1;
+            let proof_element = if i == 0 {
+                self.leaf_hashes[pair_idx]
+            } else {
+                self.node_hashes_enumerated_from_leafs[i - 1][pair_idx]
+            };
+
+            result.push(proof_element.map(|el| el.to_reduced_u32()));
+            idx >>= 1;
+        }
+
+        (this_el_leaf_hash, result)
+    }
+}
+
+impl<A: GoodAllocator> Poseidon2MerkleTreeWithCap<A> {
+    fn continue_from_leaf_hashes(
+        leaf_hashes: Vec<[Mersenne31Field; HASH_SIZE_U32_WORDS], A>,
+        cap_size: usize,
+        worker: &Worker,
+    ) -> Self {
+        assert!(leaf_hashes.len().is_power_of_two());
+        assert!(cap_size.is_power_of_two());
+        debug_assert!(leaf_hashes.len() >= cap_size);
+
+        let tree_depth = leaf_hashes.len().trailing_zeros();
+        let layers_to_skip = cap_size.trailing_zeros();
+        let num_layers_to_construct = tree_depth - layers_to_skip;
+
+        if num_layers_to_construct == 0 {
+            println!("Do not need to construct nodes, can use leaf hashes directly to form a cap");
+            assert_eq!(cap_size, leaf_hashes.len());
+            return Self {
+                cap_size,
+                leaf_hashes,
+                node_hashes_enumerated_from_leafs: Vec::new(),
+            };
+        }
+
+        #[cfg(feature = "timing_logs")]
+        let now = std::time::Instant::now();
+
+        let mut previous = &leaf_hashes[..];
+        let mut node_hashes_enumerated_from_leafs =
+            Vec::with_capacity(num_layers_to_construct as usize);
+        for _ in 0..num_layers_to_construct {
+            let next_layer_len = previous.len() / 2;
+            debug_assert!(next_layer_len > 0);
+            debug_assert!(next_layer_len.is_power_of_two());
+            let mut new_layer_node_hashes: Vec<[Mersenne31Field; HASH_SIZE_U32_WORDS], A> =
+                Vec::with_capacity_in(next_layer_len, A::default());
+
+            unsafe {
+                worker.scope(next_layer_len, |scope, geometry| {
+                    let mut dst = &mut new_layer_node_hashes.spare_capacity_mut()[..next_layer_len];
+                    let mut src = previous;
+                    for thread_idx in 0..geometry.len() {
+                        let chunk_size = geometry.get_chunk_size(thread_idx);
+
+                        let (dst_chunk, rest) = dst.split_at_mut_unchecked(chunk_size);
+                        dst = rest;
+                        let (src_chunk, rest) = src.split_at_unchecked(chunk_size * 2);
+                        src = rest;
+
+                        Worker::smart_spawn(scope, thread_idx == geometry.len() - 1, move |_| {
+                            let mut dst_ptr = dst_chunk.as_mut_ptr();
+                            // easier to use pointers
+                            let mut src_ptr = src_chunk.as_ptr().cast::<[Mersenne31Field; 16]>();
+                            for _i in 0..chunk_size {
+                                let read_from = &*src_ptr;
+                                let write_into = (&mut *dst_ptr).assume_init_mut();
+                                *write_into = poseidon2_compress(read_from)
+                                    .map(|el| Mersenne31Field::new(el.to_reduced_u32()));
+                                src_ptr = src_ptr.add(1);
+                                dst_ptr = dst_ptr.add(1);
+                            }
+                        });
+                    }
+                });
+
+                new_layer_node_hashes.set_len(next_layer_len)
+            };
+
+            node_hashes_enumerated_from_leafs.push(new_layer_node_hashes);
+            previous = node_hashes_enumerated_from_leafs.last().unwrap();
+        }
+
+        debug_assert_eq!(previous.len(), cap_size);
+
+        #[cfg(feature = "timing_logs")]
+        println!(
+            "Nodes construction of size 2^{} taken {:?}",
+            leaf_hashes.len().trailing_zeros(),
+            now.elapsed()
+        );
+
+        Self {
+            cap_size,
+            leaf_hashes,
+            node_hashes_enumerated_from_leafs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::{LeafInclusionVerifier, MerkleTreeCap};
+    use crate::nd_source_std::{set_iterator, ThreadLocalBasedSource};
+
+    #[test]
+    fn test_poseidon2_tree_proofs_are_accepted_by_verifier() {
+        const LENGTH: usize = 1 << 6;
+        const WIDTH: usize = 21;
+        const CAP_SIZE: usize = 4;
+
+        let worker = Worker::new();
+        let trace = RowMajorTrace::<
+            Mersenne31Field,
+            { crate::DEFAULT_TRACE_PADDING_MULTIPLE },
+            Global,
+        >::new_zeroed_for_size(LENGTH, WIDTH, Global::default());
+        let mut view = trace.row_view(0..LENGTH);
+        for row in 0..LENGTH {
+            for (column, dst) in view.current_row().iter_mut().enumerate() {
+                *dst = Mersenne31Field::from_nonreduced_u32((row * 1009 + column * 7919) as u32);
+            }
+            view.advance_row();
+        }
+
+        let tree = Poseidon2MerkleTreeWithCap::<Global>::construct_for_coset(
+            &trace, CAP_SIZE, false, &worker,
+        );
+        let separated = Poseidon2MerkleTreeWithCap::<Global>::construct_separated_for_coset(
+            &trace,
+            &[WIDTH],
+            CAP_SIZE,
+            false,
+            &worker,
+        );
+        assert_eq!(tree.get_cap(), separated[0].get_cap());
+
+        let cap: [MerkleTreeCap<CAP_SIZE>; 1] = [tree.get_cap().into_fixed_holder()];
+        let depth = LENGTH.trailing_zeros() as usize - CAP_SIZE.trailing_zeros() as usize;
+
+        let mut view = trace.row_view(0..LENGTH);
+        for idx in 0..LENGTH {
+            let leaf: Vec<u32> = view
+                .current_row_ref()
+                .iter()
+                .map(|el| el.to_reduced_u32())
+                .collect();
+            view.advance_row();
+
+            let (_, path) = tree.get_proof::<Global>(idx);
+            assert_eq!(path.len(), depth);
+            set_iterator(path.clone().into_iter().flatten());
+            let mut verifier = Poseidon2ForEverythingVerifier::new();
+            let valid = unsafe {
+                verifier.verify_leaf_inclusion::<ThreadLocalBasedSource, CAP_SIZE, 1>(
+                    0, idx as u32, depth, &leaf, &cap,
+                )
+            };
+            assert!(valid);
+
+            // and any tampering with the leaf is detected
+            let mut tampered = leaf.clone();
+            tampered[idx % WIDTH] = (tampered[idx % WIDTH] + 1) % Mersenne31Field::ORDER;
+            set_iterator(path.into_iter().flatten());
+            let valid = unsafe {
+                verifier.verify_leaf_inclusion::<ThreadLocalBasedSource, CAP_SIZE, 1>(
+                    0, idx as u32, depth, &tampered, &cap,
+                )
+            };
+            assert!(valid == false);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use super::*;
+use fft::bitreverse_enumeration_inplace;
+use poseidon2::m31::Poseidon2Sponge;
+
+#[inline(always)]
+fn absorb_reduced(sponge: &mut Poseidon2Sponge, values: &[Mersenne31Field]) {
+    for el in values.iter() {
+        sponge.absorb_single(Mersenne31Field::new(el.to_reduced_u32()));
+    }
+}
+
+pub fn poseidon2_leaf_hashes_for_coset<A: GoodAllocator, B: GoodAllocator, const N: usize>(
+    trace: &RowMajorTrace<Mersenne31Field, N, A>,
+    bitreverse: bool,
+    worker: &Worker,
+) -> Vec<[Mersenne31Field; HASH_SIZE_U32_WORDS], B> {
+    let mut result =
+        poseidon2_leaf_hashes_separated_for_coset(trace, &[trace.width()], bitreverse, worker);
+    assert_eq!(result.len(), 1);
+
+    result.pop().unwrap()
+}
+
+pub fn poseidon2_leaf_hashes_separated_for_coset<
+    A: GoodAllocator,
+    B: GoodAllocator,
+    const N: usize,
+>(
+    trace: &RowMajorTrace<Mersenne31Field, N, A>,
+    separators: &[usize],
+    bitreverse: bool,
+    worker: &Worker,
+) -> Vec<Vec<[Mersenne31Field; HASH_SIZE_U32_WORDS], B>> {
+    assert!(
+        *separators
+            .last()
+            .expect("Should contain at least one separator")
+            <= trace.width(),
+        "Separator is out of bounds"
+    );
+    for idx in 0..separators.len() - 1 {
+        assert!(
+            separators[idx] < separators[idx + 1],
+            "Separators are not sorted"
+        );
+    }
+
+    #[cfg(feature = "timing_logs")]
+    let now = std::time::Instant::now();
+
+    let tree_size = trace.len();
+    assert!(tree_size.is_power_of_two());
+
+    let mut chunk_widths = vec![separators[0]];
+    for i in 0..separators.len() - 1 {
+        chunk_widths.push(separators[i + 1] - separators[i]);
+    }
+
+    let mut leaf_hashes: Vec<_> = (0..separators.len())
+        .map(|_| Vec::with_capacity_in(tree_size, B::default()))
+        .collect();
+
+    unsafe {
+        worker.scope(tree_size, |scope, geometry| {
+            let mut dst: Vec<_> = leaf_hashes
+                .iter_mut()
+                .map(|lh| &mut lh.spare_capacity_mut()[..tree_size])
+                .collect();
+            for thread_idx in 0..geometry.len() {
+                let chunk_size = geometry.get_chunk_size(thread_idx);
+                let chunk_start = geometry.get_chunk_start_pos(thread_idx);
+
+                let range = chunk_start..(chunk_start + chunk_size);
+                let mut trace_view = trace.row_view(range.clone());
+                let chunk_widths = &chunk_widths;
+
+                let mut rest_chunks = vec![];
+                let mut dst_chunks = vec![];
+
+                dst.into_iter().for_each(|dst| {
+                    let (dst_chunk, rest) = dst.split_at_mut_unchecked(chunk_size);
+                    dst_chunks.push(dst_chunk);
+                    rest_chunks.push(rest);
+                });
+                dst = rest_chunks;
+
+                Worker::smart_spawn(scope, thread_idx == geometry.len() - 1, move |_| {
+                    let mut sponge = Poseidon2Sponge::new();
+                    for i in 0..chunk_size {
+                        let mut trace_view_row = trace_view.current_row_ref();
+                        for (j, dst_chunk) in dst_chunks.iter_mut().enumerate() {
+                            let (cur_trace_view_row, rest) =
+                                trace_view_row.split_at_unchecked(chunk_widths[j]);
+                            trace_view_row = rest;
+
+                            sponge.reset();
+                            absorb_reduced(&mut sponge, cur_trace_view_row);
+                            dst_chunk[i].write(sponge.finalize());
+                        }
+
+                        trace_view.advance_row();
+                    }
+                });
+            }
+
+            assert!(dst.iter().all(|d| d.is_empty()));
+        });
+
+        leaf_hashes.iter_mut().for_each(|lh| lh.set_len(tree_size));
+    };
+
+    #[cfg(feature = "timing_logs")]
+    println!(
+        "Merkle tree of size 2^{} leaf hashes taken {:?} for {} elements per leaf",
+        tree_size.trailing_zeros(),
+        now.elapsed(),
+        trace.width(),
+    );
+
+    if bitreverse {
+        for lh in leaf_hashes.iter_mut() {
+            bitreverse_enumeration_inplace(lh);
+        }
+    }
+
+    leaf_hashes
+}
+
+pub fn poseidon2_leaf_hashes_for_column_major_coset<A: GoodAllocator, B: GoodAllocator>(
+    trace: &ColumnMajorTrace<Mersenne31Quartic, A>,
+    combine_by: usize,
+    bitreverse: bool,
+    worker: &Worker,
+) -> Vec<[Mersenne31Field; HASH_SIZE_U32_WORDS], B> {
+    assert_eq!(
+        trace.width(),
+        1,
+        "we only support it for narrow traces for now"
+    );
+    assert!(combine_by.is_power_of_two());
+    assert_eq!(trace.len() % combine_by, 0);
+
+    #[cfg(feature = "timing_logs")]
+    let now = std::time::Instant::now();
+
+    let tree_size = trace.len() / combine_by;
+    assert!(tree_size.is_power_of_two());
+
+    let mut leaf_hashes = Vec::with_capacity_in(tree_size, B::default());
+    let source_column = trace.columns_iter().next().unwrap();
+
+    unsafe {
+        worker.scope(tree_size, |scope, geometry| {
+            let mut dst = &mut leaf_hashes.spare_capacity_mut()[..tree_size];
+            for thread_idx in 0..geometry.len() {
+                let chunk_size = geometry.get_chunk_size(thread_idx);
+                let chunk_start = geometry.get_chunk_start_pos(thread_idx);
+
+                let src_range = chunk_start * combine_by..(chunk_start + chunk_size) * combine_by;
+                let (dst_chunk, rest) = dst.split_at_mut_unchecked(chunk_size);
+                dst = rest;
+
+                Worker::smart_spawn(scope, thread_idx == geometry.len() - 1, move |_| {
+                    let source_chunk = &source_column[src_range];
+                    assert_eq!(source_chunk.len(), chunk_size * combine_by);
+                    let mut sponge = Poseidon2Sponge::new();
+                    for (dst, leaf) in dst_chunk
+                        .iter_mut()
+                        .zip(source_chunk.chunks_exact(combine_by))
+                    {
+                        let leaf = core::slice::from_raw_parts(
+                            leaf.as_ptr().cast::<Mersenne31Field>(),
+                            combine_by * 4,
+                        );
+                        sponge.reset();
+                        absorb_reduced(&mut sponge, leaf);
+                        dst.write(sponge.finalize());
+                    }
+                });
+            }
+
+            assert!(dst.is_empty());
+        });
+
+        leaf_hashes.set_len(tree_size)
+    };
+
+    #[cfg(feature = "timing_logs")]
+    println!(
+        "Merkle tree of size 2^{} leaf hashes taken {:?} for {} elements per leaf",
+        tree_size.trailing_zeros(),
+        now.elapsed(),
+        combine_by * 4,
+    );
+
+    if bitreverse {
+        bitreverse_enumeration_inplace(&mut leaf_hashes);
+    }
+
+    leaf_hashes
+}
@@ -0,0 +1,500 @@
+//! Compact versioned binary encoding of proofs.
+//!
+//! Layout of the encoded blob (all integers are little-endian):
+//! - 4 bytes of magic, different for single proofs and program proofs
+//! - `u32` format version
+//! - `u32` number of headers, followed by headers of `1 + 2 + 32` bytes each:
+//!   circuit type, delegation type and layout hash
+//! - bincode-encoded payload
+//!
+//! Single proof always has exactly one header. Containers of proofs (e.g. `ProgramProof`) carry one
+//! header per distinct circuit inside.
+//!
+//! Payloads encoded by the previous format versions are still decoded, see `EncodedPayload`.
+
+use crate::definitions::{ExternalValues, HashFamily};
+use crate::merkle_trees::MerkleTreeCapVarLength;
+use crate::prover_stages::{Proof, QuerySet};
+use blake2s_u32::BLAKE2S_DIGEST_SIZE_U32_WORDS;
+use cs::one_row_compiler::CompiledCircuitArtifact;
+use field::{Mersenne31Field, Mersenne31Quartic};
+use transcript::Blake2sTranscript;
+
+// version 2 added hash family to the proof
+pub const PROOF_ENCODING_VERSION: u32 = 2;
+// proofs of version 1 are all Blake2s
+const PROOF_ENCODING_VERSION_WITHOUT_HASH_FAMILY: u32 = 1;
+pub const PROOF_MAGIC: [u8; 4] = *b"ABPF";
+pub const PROGRAM_PROOF_MAGIC: [u8; 4] = *b"ABPP";
+
+const HEADER_ENCODING_SIZE: usize = 1 + 2 + BLAKE2S_DIGEST_SIZE_U32_WORDS * 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ProofCircuitType {
+    RiscV = 0,
+    RiscVReduced = 1,
+    RiscVFinalReduced = 2,
+    RiscVWithoutSignedMulDiv = 3,
+    Delegation = 4,
+    RiscVWithBitmanip = 5,
+}
+
+impl ProofCircuitType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        let circuit_type = match value {
+            0 => Self::RiscV,
+            1 => Self::RiscVReduced,
+            2 => Self::RiscVFinalReduced,
+            3 => Self::RiscVWithoutSignedMulDiv,
+            4 => Self::Delegation,
+            5 => Self::RiscVWithBitmanip,
+            _ => return None,
+        };
+
+        Some(circuit_type)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProofHeader {
+    pub circuit_type: ProofCircuitType,
+    // 0 for main circuits
+    pub delegation_type: u16,
+    pub layout_hash: [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+}
+
+#[derive(Debug)]
+pub enum ProofEncodingError {
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    UnknownCircuitType(u8),
+    Truncated,
+    Payload(bincode::Error),
+}
+
+impl core::fmt::Display for ProofEncodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "not a binary encoded proof"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported proof encoding version {}, expected {}",
+                version, PROOF_ENCODING_VERSION
+            ),
+            Self::UnknownCircuitType(value) => write!(f, "unknown circuit type {}", value),
+            Self::Truncated => write!(f, "encoded proof is truncated"),
+            Self::Payload(e) => write!(f, "failed to decode proof payload: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProofEncodingError {}
+
+/// Payload of the binary encoding. Bincode has no field names or defaults, so payloads encoded by
+/// the previous format versions are decoded as their own types and converted.
+pub trait EncodedPayload: serde::Serialize + serde::de::DeserializeOwned {
+    /// Payload as encoded by version 1, before hash family was added to the proof
+    type V1: serde::de::DeserializeOwned;
+
+    fn from_v1(payload: Self::V1) -> Self;
+}
+
+/// `Proof` as encoded by version 1
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProofV1 {
+    pub external_values: ExternalValues,
+    pub public_inputs: Vec<Mersenne31Field>,
+    pub witness_tree_caps: Vec<MerkleTreeCapVarLength>,
+    pub memory_tree_caps: Vec<MerkleTreeCapVarLength>,
+    pub setup_tree_caps: Vec<MerkleTreeCapVarLength>,
+    pub stage_2_tree_caps: Vec<MerkleTreeCapVarLength>,
+    pub memory_grand_product_accumulator: Mersenne31Quartic,
+    pub delegation_argument_accumulator: Option<Mersenne31Quartic>,
+    pub quotient_tree_caps: Vec<MerkleTreeCapVarLength>,
+    pub evaluations_at_random_points: Vec<Mersenne31Quartic>,
+    pub deep_poly_caps: Vec<MerkleTreeCapVarLength>,
+    pub intermediate_fri_oracle_caps: Vec<Vec<MerkleTreeCapVarLength>>,
+    pub last_fri_step_plain_leaf_values: Vec<Vec<Mersenne31Quartic>>,
+    pub final_monomial_form: Vec<Mersenne31Quartic>,
+    pub queries: Vec<QuerySet>,
+    pub pow_nonce: u64,
+    pub circuit_sequence: u16,
+    pub delegation_type: u16,
+}
+
+impl From<ProofV1> for Proof {
+    fn from(proof: ProofV1) -> Self {
+        let ProofV1 {
+            external_values,
+            public_inputs,
+            witness_tree_caps,
+            memory_tree_caps,
+            setup_tree_caps,
+            stage_2_tree_caps,
+            memory_grand_product_accumulator,
+            delegation_argument_accumulator,
+            quotient_tree_caps,
+            evaluations_at_random_points,
+            deep_poly_caps,
+            intermediate_fri_oracle_caps,
+            last_fri_step_plain_leaf_values,
+            final_monomial_form,
+            queries,
+            pow_nonce,
+            circuit_sequence,
+            delegation_type,
+        } = proof;
+
+        Self {
+            external_values,
+            public_inputs,
+            witness_tree_caps,
+            memory_tree_caps,
+            setup_tree_caps,
+            stage_2_tree_caps,
+            memory_grand_product_accumulator,
+            delegation_argument_accumulator,
+            quotient_tree_caps,
+            evaluations_at_random_points,
+            deep_poly_caps,
+            intermediate_fri_oracle_caps,
+            last_fri_step_plain_leaf_values,
+            final_monomial_form,
+            queries,
+            pow_nonce,
+            circuit_sequence,
+            delegation_type,
+            hash_family: HashFamily::Blake2sForEverything,
+        }
+    }
+}
+
+impl EncodedPayload for Proof {
+    type V1 = ProofV1;
+
+    fn from_v1(payload: Self::V1) -> Self {
+        payload.into()
+    }
+}
+
+/// Hash of the serialized circuit layout, to detect that the proof was produced for a different
+/// version of the circuit
+pub fn compute_layout_hash(
+    compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+) -> [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS] {
+    let bytes = bincode::serialize(compiled_circuit).expect("layout must be serializable");
+    let words: Vec<u32> = bytes
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(word)
+        })
+        .collect();
+
+    Blake2sTranscript::commit_initial(&words).0
+}
+
+/// Returns true if the data starts with the magic of either proof or program proof
+pub fn is_binary_encoded(data: &[u8]) -> bool {
+    data.starts_with(&PROOF_MAGIC) || data.starts_with(&PROGRAM_PROOF_MAGIC)
+}
+
+pub fn encode_with_headers<T: serde::Serialize>(
+    magic: [u8; 4],
+    headers: &[ProofHeader],
+    payload: &T,
+) -> Vec<u8> {
+    let payload = bincode::serialize(payload).expect("proof must be serializable");
+    let mut result = Vec::with_capacity(12 + headers.len() * HEADER_ENCODING_SIZE + payload.len());
+    result.extend_from_slice(&magic);
+    result.extend_from_slice(&PROOF_ENCODING_VERSION.to_le_bytes());
+    result.extend_from_slice(&(headers.len() as u32).to_le_bytes());
+    for header in headers.iter() {
+        result.push(header.circuit_type as u8);
+        result.extend_from_slice(&header.delegation_type.to_le_bytes());
+        for word in header.layout_hash.iter() {
+            result.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+    result.extend_from_slice(&payload);
+
+    result
+}
+
+pub fn decode_with_headers<T: EncodedPayload>(
+    magic: [u8; 4],
+    data: &[u8],
+) -> Result<(Vec<ProofHeader>, T), ProofEncodingError> {
+    let mut reader = ByteReader { data };
+    if reader.take(4)? != magic {
+        return Err(ProofEncodingError::InvalidMagic);
+    }
+    let version = reader.read_u32()?;
+    if version != PROOF_ENCODING_VERSION && version != PROOF_ENCODING_VERSION_WITHOUT_HASH_FAMILY {
+        return Err(ProofEncodingError::UnsupportedVersion(version));
+    }
+    let num_headers = reader.read_u32()? as usize;
+    if num_headers > reader.data.len() / HEADER_ENCODING_SIZE {
+        return Err(ProofEncodingError::Truncated);
+    }
+    let mut headers = Vec::with_capacity(num_headers);
+    for _ in 0..num_headers {
+        let circuit_type = reader.take(1)?[0];
+        let circuit_type = ProofCircuitType::from_u8(circuit_type)
+            .ok_or(ProofEncodingError::UnknownCircuitType(circuit_type))?;
+        let delegation_type = u16::from_le_bytes(reader.take(2)?.try_into().unwrap());
+        let mut layout_hash = [0u32; BLAKE2S_DIGEST_SIZE_U32_WORDS];
+        for word in layout_hash.iter_mut() {
+            *word = reader.read_u32()?;
+        }
+        headers.push(ProofHeader {
+            circuit_type,
+            delegation_type,
+            layout_hash,
+        });
+    }
+    let payload = if version == PROOF_ENCODING_VERSION_WITHOUT_HASH_FAMILY {
+        T::from_v1(bincode::deserialize(reader.data).map_err(ProofEncodingError::Payload)?)
+    } else {
+        bincode::deserialize(reader.data).map_err(ProofEncodingError::Payload)?
+    };
+
+    Ok((headers, payload))
+}
+
+pub fn encode_proof(proof: &Proof, header: &ProofHeader) -> Vec<u8> {
+    assert_eq!(proof.delegation_type, header.delegation_type);
+    encode_with_headers(PROOF_MAGIC, &[*header], proof)
+}
+
+pub fn decode_proof(data: &[u8]) -> Result<(ProofHeader, Proof), ProofEncodingError> {
+    let (headers, proof): (_, Proof) = decode_with_headers(PROOF_MAGIC, data)?;
+    let [header] = headers[..] else {
+        return Err(ProofEncodingError::Truncated);
+    };
+
+    Ok((header, proof))
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProofEncodingError> {
+        if self.data.len() < len {
+            return Err(ProofEncodingError::Truncated);
+        }
+        let (result, rest) = self.data.split_at(len);
+        self.data = rest;
+
+        Ok(result)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ProofEncodingError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::ExternalChallenges;
+    use crate::prover_stages::stage5::Query;
+    use field::Mersenne31Complex;
+
+    fn quartic(value: u32) -> Mersenne31Quartic {
+        Mersenne31Quartic {
+            c0: Mersenne31Complex {
+                c0: Mersenne31Field(value),
+                c1: Mersenne31Field(value + 1),
+            },
+            c1: Mersenne31Complex {
+                c0: Mersenne31Field(value + 2),
+                c1: Mersenne31Field(value + 3),
+            },
+        }
+    }
+
+    fn caps(num_caps: usize) -> Vec<MerkleTreeCapVarLength> {
+        (0..num_caps)
+            .map(|idx| MerkleTreeCapVarLength {
+                cap: vec![[idx as u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]; 4],
+            })
+            .collect()
+    }
+
+    fn query(idx: u32) -> Query {
+        Query {
+            query_index: idx,
+            tree_index: idx % 2,
+            leaf_content: (0..8).map(Mersenne31Field).collect(),
+            merkle_proof: vec![[idx; BLAKE2S_DIGEST_SIZE_U32_WORDS]; 3],
+        }
+    }
+
+    fn test_proof(delegation_type: u16) -> Proof {
+        Proof {
+            external_values: ExternalValues {
+                challenges: ExternalChallenges {
+                    memory_argument: Default::default(),
+                    delegation_argument: None,
+                },
+                aux_boundary_values: Default::default(),
+            },
+            public_inputs: vec![Mersenne31Field(1), Mersenne31Field(2)],
+            witness_tree_caps: caps(2),
+            memory_tree_caps: caps(2),
+            setup_tree_caps: caps(2),
+            stage_2_tree_caps: caps(2),
+            memory_grand_product_accumulator: quartic(10),
+            delegation_argument_accumulator: Some(quartic(20)),
+            quotient_tree_caps: caps(2),
+            evaluations_at_random_points: (0..5).map(quartic).collect(),
+            deep_poly_caps: caps(2),
+            intermediate_fri_oracle_caps: vec![caps(1), caps(1)],
+            last_fri_step_plain_leaf_values: vec![vec![quartic(30); 4]],
+            final_monomial_form: vec![quartic(40); 8],
+            queries: (0..3)
+                .map(|idx| QuerySet {
+                    witness_query: query(idx),
+                    memory_query: query(idx),
+                    setup_query: query(idx),
+                    stage_2_query: query(idx),
+                    quotient_query: query(idx),
+                    initial_fri_query: query(idx),
+                    intermediate_fri_queries: vec![query(idx)],
+                })
+                .collect(),
+            pow_nonce: 0x1234_5678_9abc,
+            circuit_sequence: 3,
+            delegation_type,
+            hash_family: HashFamily::Poseidon2ForEverything,
+        }
+    }
+
+    #[test]
+    fn test_proof_round_trip() {
+        let proof = test_proof(1991);
+        let header = ProofHeader {
+            circuit_type: ProofCircuitType::Delegation,
+            delegation_type: 1991,
+            layout_hash: [7; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+        };
+        let encoded = encode_proof(&proof, &header);
+        assert!(is_binary_encoded(&encoded));
+
+        let (decoded_header, decoded) = decode_proof(&encoded).unwrap();
+        assert_eq!(decoded_header, header);
+        assert_eq!(decoded.hash_family, HashFamily::Poseidon2ForEverything);
+        assert_eq!(
+            bincode::serialize(&decoded).unwrap(),
+            bincode::serialize(&proof).unwrap()
+        );
+
+        // should be a lot more compact than JSON that we write to files
+        let json = serde_json::to_vec_pretty(&proof).unwrap();
+        assert!(encoded.len() < json.len() / 2);
+    }
+
+    #[test]
+    fn test_decode_version_1() {
+        let Proof {
+            external_values,
+            public_inputs,
+            witness_tree_caps,
+            memory_tree_caps,
+            setup_tree_caps,
+            stage_2_tree_caps,
+            memory_grand_product_accumulator,
+            delegation_argument_accumulator,
+            quotient_tree_caps,
+            evaluations_at_random_points,
+            deep_poly_caps,
+            intermediate_fri_oracle_caps,
+            last_fri_step_plain_leaf_values,
+            final_monomial_form,
+            queries,
+            pow_nonce,
+            circuit_sequence,
+            delegation_type,
+            ..
+        } = test_proof(0);
+        let proof_v1 = ProofV1 {
+            external_values,
+            public_inputs,
+            witness_tree_caps,
+            memory_tree_caps,
+            setup_tree_caps,
+            stage_2_tree_caps,
+            memory_grand_product_accumulator,
+            delegation_argument_accumulator,
+            quotient_tree_caps,
+            evaluations_at_random_points,
+            deep_poly_caps,
+            intermediate_fri_oracle_caps,
+            last_fri_step_plain_leaf_values,
+            final_monomial_form,
+            queries,
+            pow_nonce,
+            circuit_sequence,
+            delegation_type,
+        };
+        let header = ProofHeader {
+            circuit_type: ProofCircuitType::RiscV,
+            delegation_type: 0,
+            layout_hash: [3; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+        };
+        let mut encoded = encode_with_headers(PROOF_MAGIC, &[header], &proof_v1);
+        encoded[4..8].copy_from_slice(&1u32.to_le_bytes());
+
+        let (decoded_header, decoded) = decode_proof(&encoded).unwrap();
+        assert_eq!(decoded_header, header);
+        assert_eq!(decoded.hash_family, HashFamily::Blake2sForEverything);
+        let expected = Proof {
+            hash_family: HashFamily::Blake2sForEverything,
+            ..test_proof(0)
+        };
+        assert_eq!(
+            bincode::serialize(&decoded).unwrap(),
+            bincode::serialize(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        let proof = test_proof(0);
+        let header = ProofHeader {
+            circuit_type: ProofCircuitType::RiscV,
+            delegation_type: 0,
+            layout_hash: [0; BLAKE2S_DIGEST_SIZE_U32_WORDS],
+        };
+        let encoded = encode_proof(&proof, &header);
+
+        let json = serde_json::to_vec(&proof).unwrap();
+        assert!(is_binary_encoded(&json) == false);
+        assert!(matches!(
+            decode_proof(&json),
+            Err(ProofEncodingError::InvalidMagic)
+        ));
+
+        let mut wrong_version = encoded.clone();
+        wrong_version[4] = 3;
+        assert!(matches!(
+            decode_proof(&wrong_version),
+            Err(ProofEncodingError::UnsupportedVersion(3))
+        ));
+
+        let mut wrong_circuit_type = encoded.clone();
+        wrong_circuit_type[12] = 100;
+        assert!(matches!(
+            decode_proof(&wrong_circuit_type),
+            Err(ProofEncodingError::UnknownCircuitType(100))
+        ));
+
+        assert!(decode_proof(&encoded[..20]).is_err());
+        assert!(decode_proof(&encoded[..encoded.len() - 1]).is_err());
+    }
+}
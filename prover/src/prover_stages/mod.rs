@@ -2,6 +2,7 @@ use super::*;
 use crate::definitions::*;
 use crate::merkle_trees::DefaultTreeConstructor;
 use crate::merkle_trees::MerkleTreeConstructor;
+use crate::merkle_trees::TranscriptFor;
 use ::field::*;
 use blake2s_u32::BLAKE2S_DIGEST_SIZE_U32_WORDS;
 use cs::definitions::*;
@@ -56,6 +57,10 @@ pub struct Proof {
     pub pow_nonce: u64,
     pub circuit_sequence: u16,
     pub delegation_type: u16,
+    // proofs produced before hash family became configurable are all Blake2s. Default only applies
+    // to self-describing formats (JSON), binary encoding handles old versions by itself
+    #[serde(default)]
+    pub hash_family: HashFamily,
 }
 
 #[derive(Clone, Debug, Hash, serde::Serialize, serde::Deserialize)]
@@ -482,7 +487,7 @@ pub fn prove_configured<const N: usize, A: GoodAllocator, T: MerkleTreeConstruct
         delegation_processing_type,
     );
 
-    let mut seed = Transcript::commit_initial(&transcript_input);
+    let mut seed = TranscriptFor::<T>::commit_initial(&transcript_input);
 
     let stage_2_output = stage2::prover_stage_2(
         &mut seed,
@@ -520,7 +525,7 @@ pub fn prove_configured<const N: usize, A: GoodAllocator, T: MerkleTreeConstruct
                 .map(|el: Mersenne31Field| el.to_reduced_u32()),
         );
     }
-    Transcript::commit_with_seed(&mut seed, &transcript_input);
+    TranscriptFor::<T>::commit_with_seed(&mut seed, &transcript_input);
 
     // TODO: move to precomputations
 
@@ -562,7 +567,7 @@ pub fn prove_configured<const N: usize, A: GoodAllocator, T: MerkleTreeConstruct
 
     let mut transcript_input = vec![];
     flatten_merkle_caps_into(&stage_3_output.trees, &mut transcript_input);
-    Transcript::commit_with_seed(&mut seed, &transcript_input);
+    TranscriptFor::<T>::commit_with_seed(&mut seed, &transcript_input);
 
     // now we should compute deep-poly
 
@@ -583,7 +588,7 @@ pub fn prove_configured<const N: usize, A: GoodAllocator, T: MerkleTreeConstruct
 
     let mut transcript_input = vec![];
     flatten_merkle_caps_into(&stage_4_output.trees, &mut transcript_input);
-    Transcript::commit_with_seed(&mut seed, &transcript_input);
+    TranscriptFor::<T>::commit_with_seed(&mut seed, &transcript_input);
 
     let stage_5_output = stage5::prover_stage_5(
         &mut seed,
@@ -600,7 +605,7 @@ pub fn prove_configured<const N: usize, A: GoodAllocator, T: MerkleTreeConstruct
 
     #[cfg(feature = "timing_logs")]
     let now = std::time::Instant::now();
-    let (mut seed, pow_challenge) = Transcript::search_pow(&seed, pow_bits, worker);
+    let (mut seed, pow_challenge) = TranscriptFor::<T>::search_pow(&seed, pow_bits, worker);
     #[cfg(feature = "timing_logs")]
     println!("PoW for {} took {:?}", pow_bits, now.elapsed());
 
@@ -625,7 +630,7 @@ pub fn prove_configured<const N: usize, A: GoodAllocator, T: MerkleTreeConstruct
     }
 
     let mut source = vec![0u32; num_required_words_padded];
-    Transcript::draw_randomness(&mut seed, &mut source);
+    TranscriptFor::<T>::draw_randomness(&mut seed, &mut source);
     // Remember - skip top word
     let mut bit_source = BitSource::new(source[1..].to_vec());
 
@@ -784,6 +789,7 @@ pub fn prove_configured<const N: usize, A: GoodAllocator, T: MerkleTreeConstruct
         pow_nonce: pow_challenge,
         circuit_sequence: circuit_sequence as u16,
         delegation_type: cached_data_values.delegation_type.to_reduced_u32() as u16,
+        hash_family: T::HASH_FAMILY,
     };
 
     let prover_data = ProverData {
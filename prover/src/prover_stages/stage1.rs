@@ -84,9 +84,7 @@ pub fn compute_wide_ldes_grinded<const N: usize, A: GoodAllocator>(
                 trace.padded_width
             );
             #[cfg(feature = "debug_logs")]
-            if cfg!(target_feature = "avx512f") {
-                println!("avx512f is enabled");
-            }
+            println!("SIMD backend: {}", ::field::simd_backend());
 
             assert!(trace.padded_width % 32 == 0);
 
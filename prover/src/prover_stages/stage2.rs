@@ -68,7 +68,7 @@ pub fn prover_stage_2<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
     let mut transcript_challenges = [0u32;
         ((NUM_LOOKUP_ARGUMENT_LINEARIZATION_CHALLENGES + 1) * 4)
             .next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)];
-    Transcript::draw_randomness(seed, &mut transcript_challenges);
+    TranscriptFor::<T>::draw_randomness(seed, &mut transcript_challenges);
 
     let mut it = transcript_challenges.as_chunks::<4>().0.iter();
     let lookup_argument_linearization_challenges: [Mersenne31Quartic;
@@ -118,7 +118,7 @@ pub fn prover_stage_3<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
 
     let mut transcript_challenges =
         [0u32; (2usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)];
-    Transcript::draw_randomness(seed, &mut transcript_challenges);
+    TranscriptFor::<T>::draw_randomness(seed, &mut transcript_challenges);
 
     let mut it = transcript_challenges.as_chunks::<4>().0.iter();
     let quotient_alpha = Mersenne31Quartic::from_coeffs_in_base(
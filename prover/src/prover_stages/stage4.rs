@@ -160,7 +160,7 @@ pub fn prover_stage_4<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
 
     let mut transcript_challenges =
         [0u32; (1usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)];
-    Transcript::draw_randomness(seed, &mut transcript_challenges);
+    TranscriptFor::<T>::draw_randomness(seed, &mut transcript_challenges);
 
     let mut it = transcript_challenges.as_chunks::<4>().0.iter();
     let z = Mersenne31Quartic::from_coeffs_in_base(
@@ -484,13 +484,13 @@ pub fn prover_stage_4<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
             })
             .flatten(),
     );
-    Transcript::commit_with_seed(seed, &transcript_input);
+    TranscriptFor::<T>::commit_with_seed(seed, &transcript_input);
 
     // now we can compute \sum alpha^i f_i(z)
 
     let mut transcript_challenges =
         [0u32; (1usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)];
-    Transcript::draw_randomness(seed, &mut transcript_challenges);
+    TranscriptFor::<T>::draw_randomness(seed, &mut transcript_challenges);
 
     let mut it = transcript_challenges.as_chunks::<4>().0.iter();
     let deep_poly_alpha = Mersenne31Quartic::from_coeffs_in_base(
@@ -137,7 +137,7 @@ pub fn prover_stage_5<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
         let challenge = {
             let mut transcript_challenges =
                 [0u32; (1usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)];
-            Transcript::draw_randomness(seed, &mut transcript_challenges);
+            TranscriptFor::<T>::draw_randomness(seed, &mut transcript_challenges);
 
             let mut it = transcript_challenges.as_chunks::<4>().0.iter();
             let challenge = Mersenne31Quartic::from_coeffs_in_base(
@@ -268,7 +268,7 @@ pub fn prover_stage_5<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
 
             let mut transcript_input = vec![];
             flatten_merkle_caps_into(&oracle.trees, &mut transcript_input);
-            Transcript::commit_with_seed(seed, &transcript_input);
+            TranscriptFor::<T>::commit_with_seed(seed, &transcript_input);
 
             intermediate_oracles.push(oracle);
         } else {
@@ -290,7 +290,7 @@ pub fn prover_stage_5<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
                 last_fri_step_plain_leaf_values.push(subvec);
             }
 
-            Transcript::commit_with_seed(seed, &transcript_input);
+            TranscriptFor::<T>::commit_with_seed(seed, &transcript_input);
 
             let oracle = FRIStep {
                 folding_challenge: challenge,
@@ -317,7 +317,7 @@ pub fn prover_stage_5<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
         let final_folding_challenge = {
             let mut transcript_challenges =
                 [0u32; (1usize * 4).next_multiple_of(BLAKE2S_DIGEST_SIZE_U32_WORDS)];
-            Transcript::draw_randomness(seed, &mut transcript_challenges);
+            TranscriptFor::<T>::draw_randomness(seed, &mut transcript_challenges);
 
             let mut it = transcript_challenges.as_chunks::<4>().0.iter();
             let challenge = Mersenne31Quartic::from_coeffs_in_base(
@@ -411,7 +411,7 @@ pub fn prover_stage_5<const N: usize, A: GoodAllocator, T: MerkleTreeConstructor
             })
             .flatten(),
     );
-    Transcript::commit_with_seed(seed, &transcript_input);
+    TranscriptFor::<T>::commit_with_seed(seed, &transcript_input);
 
     let output = FifthStageOutput {
         fri_oracles: intermediate_oracles,
@@ -6,9 +6,7 @@ use trace_holder::RowMajorTrace;
 
 #[test]
 fn test_compute_wide_lde() {
-    if cfg!(target_feature = "avx512f") {
-        println!("avx512f is enabled");
-    }
+    println!("SIMD backend: {}", ::field::simd_backend());
     let trace_len: usize = 1 << 21;
     // let log_n = trace_len.trailing_zeros();
     let num_cores = 16;
@@ -112,6 +112,9 @@ mod delegation_test;
 #[cfg(test)]
 mod lde_tests;
 
+#[cfg(test)]
+mod trap_handling_test;
+
 pub use delegation_test::run_basic_delegation_test_impl;
 
 // NOTE: For some reason tryint to add generic tree constructor to GPU arguments just makes resolver crazy,
@@ -0,0 +1,150 @@
+use super::*;
+
+use crate::tracers::main_cycle_optimized::{
+    DelegationTracingData, GPUFriendlyTracer, RamTracingData,
+};
+use crate::tracers::oracles::main_risc_v_circuit::MainRiscVOracle;
+use crate::tracers::oracles::main_risc_v_circuit_with_traps::MainRiscVWithTrapHandlingOracle;
+use crate::tracers::trap_handling_csrs::TrapHandlingCSRsTracer;
+use cs::cs::{circuit::Circuit, cs_reference::BasicAssembly};
+use cs::machine::{Machine, UNIMP_OPCODE};
+use full_isa_with_delegation_and_trap_handling::FullIsaMachineWithDelegationAndTrapHandling;
+use risc_v_simulator::abstractions::non_determinism::QuasiUARTSource;
+use risc_v_simulator::cycle::state::RiscV32State;
+use risc_v_simulator::cycle::status_registers::TrapReason;
+use risc_v_simulator::cycle::{IMWithTrapHandlingIsaConfigWithAllDelegations, MachineConfig};
+use risc_v_simulator::delegations::DelegationsCSRProcessor;
+use risc_v_simulator::mmu::NoMMU;
+
+type Config = IMWithTrapHandlingIsaConfigWithAllDelegations;
+
+const SECOND_WORD_BITS: usize = 4;
+const ENTRY_POINT: u32 = 0;
+const TRAP_VECTOR: u32 = 0x40;
+
+/// Executes single instruction that traps in the simulator, resolves witness for one cycle of the
+/// circuit from the simulator's trace, and checks that the circuit is satisfied and agrees with
+/// the simulator on the resulting trap handling CSRs
+fn run_single_trapping_cycle(instruction: u32, expected_cause: TrapReason) {
+    const NUM_CYCLES_IN_CHUNK: usize = (1 << 4) - 1;
+    const RAM_SIZE: usize = 1 << 24;
+    const ROM_BOUND: usize = 1 << (16 + SECOND_WORD_BITS);
+
+    let mut state = RiscV32State::<Config>::initial(ENTRY_POINT);
+    state.machine_mode_trap_data.setup.tvec = TRAP_VECTOR;
+    // previous trap data must be overwritten
+    state.machine_mode_trap_data.handling.epc = 0x1234;
+    state.machine_mode_trap_data.handling.cause = 0x5678;
+    state.machine_mode_trap_data.handling.tval = 0x9abc;
+    state.registers[1] = 0xdeadbeef;
+
+    let bytecode = [instruction];
+    let mut memory = VectorMemoryImplWithRom::new_for_byte_size(RAM_SIZE, ROM_BOUND);
+    for (i, opcode) in bytecode.iter().enumerate() {
+        memory.populate(ENTRY_POINT + (i as u32) * 4, *opcode);
+    }
+
+    let delegation_tracer = DelegationTracingData {
+        all_per_type_logs: HashMap::new(),
+        delegation_witness_factories: HashMap::new(),
+        current_per_type_logs: HashMap::new(),
+        num_traced_registers: 0,
+        mem_reads_offset: 0,
+        mem_writes_offset: 0,
+    };
+    let gpu_tracer = GPUFriendlyTracer::<Config, Global, true, true, true>::new(
+        timestamp_from_chunk_cycle_and_sequence(0, NUM_CYCLES_IN_CHUNK, 0),
+        RamTracingData::<true>::new_for_ram_size_and_rom_bound(RAM_SIZE, ROM_BOUND),
+        delegation_tracer,
+        NUM_CYCLES_IN_CHUNK,
+        1,
+    );
+    let mut tracer = TrapHandlingCSRsTracer::new(gpu_tracer, NUM_CYCLES_IN_CHUNK);
+
+    let mut mmu = NoMMU { sapt: state.sapt };
+    let mut non_determinism = QuasiUARTSource::default();
+    state.cycle_ext(
+        &mut memory,
+        &mut tracer,
+        &mut mmu,
+        &mut non_determinism,
+        &mut DelegationsCSRProcessor,
+    );
+
+    assert_eq!(state.pc, TRAP_VECTOR);
+    assert_eq!(state.machine_mode_trap_data.handling.epc, ENTRY_POINT);
+    assert_eq!(
+        state.machine_mode_trap_data.handling.cause,
+        expected_cause.as_register_value()
+    );
+    assert_eq!(state.machine_mode_trap_data.handling.tval, instruction);
+
+    let csr_table = create_csr_table_for_delegation::<Mersenne31Field>(
+        true,
+        Config::ALLOWED_DELEGATION_CSRS,
+        TableType::SpecialCSRProperties.to_table_id(),
+    );
+    let oracle: MainRiscVWithTrapHandlingOracle<'static, Config> = unsafe {
+        std::mem::transmute(MainRiscVWithTrapHandlingOracle {
+            inner: MainRiscVOracle {
+                cycle_data: &tracer.inner.trace_chunk,
+            },
+            trap_csrs_data: &tracer.per_cycle_data[..],
+        })
+    };
+    let mut cs = BasicAssembly::<Mersenne31Field>::new_with_oracle(oracle);
+    let (state_prev, state_next) =
+        FullIsaMachineWithDelegationAndTrapHandling::run_single_cycle::<SECOND_WORD_BITS>(
+            &bytecode,
+            &mut cs,
+            Some(LookupWrapper::Dimensional3(csr_table)),
+        );
+    assert!(cs.is_satisfied());
+
+    assert_eq!(state_prev.pc.get_value_unsigned(&cs), Some(ENTRY_POINT));
+    assert_eq!(state_prev.mepc.get_value_unsigned(&cs), Some(0x1234));
+    assert_eq!(state_next.pc.get_value_unsigned(&cs), Some(state.pc));
+    assert_eq!(state_next.mtvec.get_value_unsigned(&cs), Some(TRAP_VECTOR));
+    assert_eq!(
+        state_next.mepc.get_value_unsigned(&cs),
+        Some(state.machine_mode_trap_data.handling.epc)
+    );
+    assert_eq!(
+        state_next.mcause.get_value_unsigned(&cs),
+        Some(state.machine_mode_trap_data.handling.cause)
+    );
+    assert_eq!(
+        state_next.mtval.get_value_unsigned(&cs),
+        Some(state.machine_mode_trap_data.handling.tval)
+    );
+
+    // trapping instruction doesn't write RD
+    assert!(cs
+        .shuffle_ram_queries
+        .iter()
+        .all(|query| query.get_read_value(&cs) == query.get_write_value(&cs)));
+}
+
+#[test]
+fn ecall_trap_is_satisfied() {
+    const ECALL_OPCODE: u32 = 0x00000073;
+    run_single_trapping_cycle(ECALL_OPCODE, TrapReason::EnvironmentCallFromMMode);
+}
+
+#[test]
+fn ebreak_trap_is_satisfied() {
+    const EBREAK_OPCODE: u32 = 0x00100073;
+    run_single_trapping_cycle(EBREAK_OPCODE, TrapReason::Breakpoint);
+}
+
+#[test]
+fn illegal_instruction_trap_is_satisfied() {
+    const ILLEGAL_OPCODE: u32 = 0x00000000;
+    run_single_trapping_cycle(ILLEGAL_OPCODE, TrapReason::IllegalInstruction);
+}
+
+#[test]
+fn unsupported_csr_trap_is_satisfied() {
+    // csrrw x0, cycle, x0
+    run_single_trapping_cycle(UNIMP_OPCODE, TrapReason::IllegalInstruction);
+}
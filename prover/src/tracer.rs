@@ -3,6 +3,7 @@ use cs::definitions::TimestampScalar;
 use fft::GoodAllocator;
 use risc_v_simulator::abstractions::memory::*;
 use risc_v_simulator::cycle::status_registers::*;
+use risc_v_simulator::snapshot::{MemorySnapshot, TouchedPages};
 use std::alloc::Global;
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -20,6 +21,7 @@ pub struct RamShuffleMemStateRecord {
 #[derive(Clone, Debug)]
 pub struct VectorMemoryImplWithRom {
     ram: Vec<u32>,
+    touched_pages: TouchedPages,
     pub rom_bound: usize,
 }
 
@@ -34,6 +36,7 @@ impl VectorMemoryImplWithRom {
 
         Self {
             ram: vec![0u32; allocation_size / 4],
+            touched_pages: TouchedPages::new_for_num_words(allocation_size / 4),
             rom_bound,
         }
     }
@@ -41,6 +44,7 @@ impl VectorMemoryImplWithRom {
     pub fn populate(&mut self, address: u32, value: u32) {
         assert!(address % 4 == 0);
         self.ram[(address / 4) as usize] = value;
+        self.touched_pages.mark_word((address / 4) as usize);
     }
 
     pub fn load_image<'a, B>(&mut self, entry_point: u32, bytes: B)
@@ -54,10 +58,18 @@ impl VectorMemoryImplWithRom {
         }
     }
 
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot::capture(&self.ram, &self.touched_pages)
+    }
+
+    pub fn restore_from_snapshot(&mut self, snapshot: &MemorySnapshot) {
+        snapshot.restore_into(&mut self.ram, &mut self.touched_pages)
+    }
+
     pub fn get_final_ram_state(self) -> Vec<u32> {
         // NOTE: important: even though we use single allocation for ROM and RAM,
         // we should NOT expose ROM values, so we will instead zero-out
-        let Self { ram, rom_bound } = self;
+        let Self { ram, rom_bound, .. } = self;
 
         let mut ram = ram;
         let rom_words = rom_bound / 4;
@@ -102,6 +114,7 @@ impl MemorySource for VectorMemoryImplWithRom {
             );
         } else if ((phys_address / 4) as usize) < self.ram.len() {
             self.ram[(phys_address / 4) as usize] = value;
+            self.touched_pages.mark_word((phys_address / 4) as usize);
         } else {
             match access_type {
                 AccessType::Instruction => *trap = TrapReason::InstructionAccessFault,
@@ -122,6 +135,7 @@ impl MemorySource for VectorMemoryImplWithRom {
             );
         } else if ((phys_address / 4) as usize) < self.ram.len() {
             self.ram[(phys_address / 4) as usize] = value;
+            self.touched_pages.mark_word((phys_address / 4) as usize);
         } else {
             panic!("Out of bound memory access at address 0x{:x}", phys_address);
         }
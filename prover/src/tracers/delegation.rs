@@ -188,3 +188,108 @@ pub fn bigint_with_control_factory_fn<A: GoodAllocator>(
         indirect_writes: Vec::with_capacity_in(capacity * 8, A::default()),
     }
 }
+
+pub fn sha256_with_compression_factory_fn<A: GoodAllocator>(
+    delegation_type: u16,
+    num_requests: usize,
+) -> DelegationWitness<A> {
+    let capacity = num_requests + 1;
+    assert!(
+        capacity.is_power_of_two(),
+        "expected capacity to be power of two, got {}",
+        capacity
+    );
+
+    let x10_indirect_access_properties: Vec<_> = (0..24)
+        .map(|el| IndirectAccessLocation {
+            use_writes: true,
+            index: el,
+        })
+        .collect();
+
+    DelegationWitness {
+        num_requests,
+        num_register_accesses_per_delegation: 2,
+        num_indirect_reads_per_delegation: 0,
+        num_indirect_writes_per_delegation: 24,
+        base_register_index: 10,
+        delegation_type,
+        indirect_accesses_properties: vec![x10_indirect_access_properties], // x11 holds round index
+
+        write_timestamp: Vec::with_capacity_in(capacity, A::default()),
+
+        register_accesses: Vec::with_capacity_in(capacity * 2, A::default()),
+        indirect_reads: Vec::with_capacity_in(0, A::default()),
+        indirect_writes: Vec::with_capacity_in(capacity * 24, A::default()),
+    }
+}
+
+pub fn keccak_f1600_factory_fn<A: GoodAllocator>(
+    delegation_type: u16,
+    num_requests: usize,
+) -> DelegationWitness<A> {
+    let capacity = num_requests + 1;
+    assert!(
+        capacity.is_power_of_two(),
+        "expected capacity to be power of two, got {}",
+        capacity
+    );
+
+    let x10_indirect_access_properties: Vec<_> = (0..50)
+        .map(|el| IndirectAccessLocation {
+            use_writes: true,
+            index: el,
+        })
+        .collect();
+
+    DelegationWitness {
+        num_requests,
+        num_register_accesses_per_delegation: 2,
+        num_indirect_reads_per_delegation: 0,
+        num_indirect_writes_per_delegation: 50,
+        base_register_index: 10,
+        delegation_type,
+        indirect_accesses_properties: vec![x10_indirect_access_properties], // x11 holds round index
+
+        write_timestamp: Vec::with_capacity_in(capacity, A::default()),
+
+        register_accesses: Vec::with_capacity_in(capacity * 2, A::default()),
+        indirect_reads: Vec::with_capacity_in(0, A::default()),
+        indirect_writes: Vec::with_capacity_in(capacity * 50, A::default()),
+    }
+}
+
+pub fn poseidon2_permutation_factory_fn<A: GoodAllocator>(
+    delegation_type: u16,
+    num_requests: usize,
+) -> DelegationWitness<A> {
+    let capacity = num_requests + 1;
+    assert!(
+        capacity.is_power_of_two(),
+        "expected capacity to be power of two, got {}",
+        capacity
+    );
+
+    let x10_indirect_access_properties: Vec<_> = (0..16)
+        .map(|el| IndirectAccessLocation {
+            use_writes: true,
+            index: el,
+        })
+        .collect();
+
+    DelegationWitness {
+        num_requests,
+        num_register_accesses_per_delegation: 1,
+        num_indirect_reads_per_delegation: 0,
+        num_indirect_writes_per_delegation: 16,
+        base_register_index: 10,
+        delegation_type,
+        indirect_accesses_properties: vec![x10_indirect_access_properties],
+
+        write_timestamp: Vec::with_capacity_in(capacity, A::default()),
+
+        register_accesses: Vec::with_capacity_in(capacity, A::default()),
+        indirect_reads: Vec::with_capacity_in(0, A::default()),
+        indirect_writes: Vec::with_capacity_in(capacity * 16, A::default()),
+    }
+}
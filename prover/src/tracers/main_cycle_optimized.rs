@@ -145,6 +145,15 @@ impl RegIndexOrMemWordIndex {
     }
 }
 
+/// Sparse copy of `RamTracingData` to resume tracing from the middle of execution
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RamTracingSnapshot {
+    pub register_last_live_timestamps: [TimestampScalar; 32],
+    // (word index, last live timestamp) for every touched RAM word
+    pub touched_ram_words: Vec<(u32, TimestampScalar)>,
+    pub num_touched_ram_cells: usize,
+}
+
 #[derive(Clone, Debug)]
 pub struct RamTracingData<const TRACE_FOR_TEARDOWNS: bool> {
     pub register_last_live_timestamps: [TimestampScalar; 32],
@@ -174,6 +183,39 @@ impl<const TRACE_FOR_TEARDOWNS: bool> RamTracingData<TRACE_FOR_TEARDOWNS> {
         }
     }
 
+    pub fn snapshot(&self) -> RamTracingSnapshot {
+        // any access happens at non-zero timestamp
+        let touched_ram_words = self
+            .ram_words_last_live_timestamps
+            .iter()
+            .enumerate()
+            .filter(|(_, timestamp)| **timestamp != 0)
+            .map(|(word_idx, timestamp)| (word_idx as u32, *timestamp))
+            .collect();
+
+        RamTracingSnapshot {
+            register_last_live_timestamps: self.register_last_live_timestamps,
+            touched_ram_words,
+            num_touched_ram_cells: self.num_touched_ram_cells,
+        }
+    }
+
+    /// Expects freshly created tracing data
+    pub fn restore_from_snapshot(&mut self, snapshot: &RamTracingSnapshot) {
+        assert_eq!(self.num_touched_ram_cells, 0);
+
+        self.register_last_live_timestamps = snapshot.register_last_live_timestamps;
+        for (word_idx, timestamp) in snapshot.touched_ram_words.iter() {
+            self.ram_words_last_live_timestamps[*word_idx as usize] = *timestamp;
+            if TRACE_FOR_TEARDOWNS {
+                let bookkeeping_word_idx = (*word_idx / usize::BITS) as usize;
+                let bit_idx = *word_idx % usize::BITS;
+                self.access_bitmask[bookkeeping_word_idx] |= 1 << bit_idx;
+            }
+        }
+        self.num_touched_ram_cells = snapshot.num_touched_ram_cells;
+    }
+
     #[inline(always)]
     pub(crate) fn mark_register_use(
         &mut self,
@@ -1,4 +1,5 @@
 pub mod delegation;
 pub mod main_cycle_optimized;
+pub mod trap_handling_csrs;
 
 pub mod oracles;
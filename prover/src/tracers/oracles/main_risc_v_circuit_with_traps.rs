@@ -0,0 +1,67 @@
+use super::main_risc_v_circuit::MainRiscVOracle;
+use crate::tracers::trap_handling_csrs::TrapHandlingCSRsData;
+use cs::cs::oracle::Oracle;
+use cs::cs::placeholder::Placeholder;
+use cs::definitions::TimestampScalar;
+use fft::GoodAllocator;
+use field::PrimeField;
+use risc_v_simulator::cycle::MachineConfig;
+use std::alloc::Global;
+
+/// Same as `MainRiscVOracle`, but also resolves initial values of trap handling CSRs
+/// for machines that keep them as a part of the state
+#[derive(Clone, Copy, Debug)]
+pub struct MainRiscVWithTrapHandlingOracle<'a, C: MachineConfig, A: GoodAllocator = Global> {
+    pub inner: MainRiscVOracle<'a, C, A>,
+    pub trap_csrs_data: &'a [TrapHandlingCSRsData],
+}
+
+impl<'a, C: MachineConfig, A: GoodAllocator, F: PrimeField> Oracle<F>
+    for MainRiscVWithTrapHandlingOracle<'a, C, A>
+{
+    #[track_caller]
+    fn get_witness_from_placeholder(
+        &self,
+        placeholder: Placeholder,
+        subindex: usize,
+        trace_step: usize,
+    ) -> F {
+        Oracle::<F>::get_witness_from_placeholder(&self.inner, placeholder, subindex, trace_step)
+    }
+
+    fn get_u32_witness_from_placeholder(&self, placeholder: Placeholder, trace_step: usize) -> u32 {
+        let trap_csrs = &self.trap_csrs_data[trace_step];
+
+        match placeholder {
+            Placeholder::TvecInit => trap_csrs.tvec,
+            Placeholder::EpcInit => trap_csrs.epc,
+            Placeholder::CauseInit => trap_csrs.cause,
+            Placeholder::TvalInit => trap_csrs.tval,
+            a @ _ => Oracle::<F>::get_u32_witness_from_placeholder(&self.inner, a, trace_step),
+        }
+    }
+
+    fn get_u16_witness_from_placeholder(&self, placeholder: Placeholder, trace_step: usize) -> u16 {
+        Oracle::<F>::get_u16_witness_from_placeholder(&self.inner, placeholder, trace_step)
+    }
+
+    fn get_u8_witness_from_placeholder(&self, placeholder: Placeholder, trace_step: usize) -> u8 {
+        Oracle::<F>::get_u8_witness_from_placeholder(&self.inner, placeholder, trace_step)
+    }
+
+    fn get_boolean_witness_from_placeholder(
+        &self,
+        placeholder: Placeholder,
+        trace_step: usize,
+    ) -> bool {
+        Oracle::<F>::get_boolean_witness_from_placeholder(&self.inner, placeholder, trace_step)
+    }
+
+    fn get_timestamp_witness_from_placeholder(
+        &self,
+        placeholder: Placeholder,
+        trace_step: usize,
+    ) -> TimestampScalar {
+        Oracle::<F>::get_timestamp_witness_from_placeholder(&self.inner, placeholder, trace_step)
+    }
+}
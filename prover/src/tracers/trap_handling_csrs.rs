@@ -0,0 +1,144 @@
+use risc_v_simulator::abstractions::tracer::{
+    RegisterOrIndirectReadData, RegisterOrIndirectReadWriteData, Tracer,
+};
+use risc_v_simulator::cycle::state::RiscV32State;
+use risc_v_simulator::cycle::state_new::RiscV32StateForUnrolledProver;
+use risc_v_simulator::cycle::MachineConfig;
+
+/// Values of `mtvec`, `mepc`, `mcause` and `mtval` at the start of the cycle. Machines that
+/// vector traps keep them as a part of the state, so they are inputs of every cycle
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TrapHandlingCSRsData {
+    pub tvec: u32,
+    pub epc: u32,
+    pub cause: u32,
+    pub tval: u32,
+}
+
+/// Wraps any tracer and additionally records trap handling CSRs per cycle. It's kept separate
+/// from `SingleCycleTracingData` to not change the layout that is shared with GPU.
+///
+/// NOTE: only `RiscV32State` has trap handling CSRs, so nothing is recorded for the unrolled state
+#[derive(Clone, Debug)]
+pub struct TrapHandlingCSRsTracer<T> {
+    pub inner: T,
+    pub per_cycle_data: Vec<TrapHandlingCSRsData>,
+}
+
+impl<T> TrapHandlingCSRsTracer<T> {
+    pub fn new(inner: T, num_cycles: usize) -> Self {
+        Self {
+            inner,
+            per_cycle_data: Vec::with_capacity(num_cycles),
+        }
+    }
+}
+
+impl<C: MachineConfig, T: Tracer<C>> Tracer<C> for TrapHandlingCSRsTracer<T> {
+    #[inline(always)]
+    fn at_cycle_start(&mut self, current_state: &RiscV32State<C>) {
+        let trap_data = &current_state.machine_mode_trap_data;
+        self.per_cycle_data.push(TrapHandlingCSRsData {
+            tvec: trap_data.setup.tvec,
+            epc: trap_data.handling.epc,
+            cause: trap_data.handling.cause,
+            tval: trap_data.handling.tval,
+        });
+        self.inner.at_cycle_start(current_state);
+    }
+
+    #[inline(always)]
+    fn at_cycle_end(&mut self, current_state: &RiscV32State<C>) {
+        self.inner.at_cycle_end(current_state);
+    }
+
+    #[inline(always)]
+    fn at_cycle_start_ext(&mut self, current_state: &RiscV32StateForUnrolledProver<C>) {
+        self.inner.at_cycle_start_ext(current_state);
+    }
+
+    #[inline(always)]
+    fn at_cycle_end_ext(&mut self, current_state: &RiscV32StateForUnrolledProver<C>) {
+        self.inner.at_cycle_end_ext(current_state);
+    }
+
+    #[inline(always)]
+    fn trace_opcode_read(&mut self, phys_address: u64, read_value: u32) {
+        self.inner.trace_opcode_read(phys_address, read_value);
+    }
+
+    #[inline(always)]
+    fn trace_opcode(&mut self, opcode: &'static str) {
+        self.inner.trace_opcode(opcode);
+    }
+
+    #[inline(always)]
+    fn trace_rs1_read(&mut self, reg_idx: u32, read_value: u32) {
+        self.inner.trace_rs1_read(reg_idx, read_value);
+    }
+
+    #[inline(always)]
+    fn trace_rs2_read(&mut self, reg_idx: u32, read_value: u32) {
+        self.inner.trace_rs2_read(reg_idx, read_value);
+    }
+
+    #[inline(always)]
+    fn trace_rd_write(&mut self, reg_idx: u32, read_value: u32, written_value: u32) {
+        self.inner
+            .trace_rd_write(reg_idx, read_value, written_value);
+    }
+
+    #[inline(always)]
+    fn trace_non_determinism_read(&mut self, read_value: u32) {
+        self.inner.trace_non_determinism_read(read_value);
+    }
+
+    #[inline(always)]
+    fn trace_non_determinism_write(&mut self, written_value: u32) {
+        self.inner.trace_non_determinism_write(written_value);
+    }
+
+    #[inline(always)]
+    fn trace_ram_read(&mut self, phys_address: u64, read_value: u32) {
+        self.inner.trace_ram_read(phys_address, read_value);
+    }
+
+    #[inline(always)]
+    fn trace_ram_read_write(&mut self, phys_address: u64, read_value: u32, written_value: u32) {
+        self.inner
+            .trace_ram_read_write(phys_address, read_value, written_value);
+    }
+
+    #[inline(always)]
+    fn trace_address_translation(
+        &mut self,
+        satp_value: u32,
+        virtual_address: u64,
+        phys_address: u64,
+    ) {
+        self.inner
+            .trace_address_translation(satp_value, virtual_address, phys_address);
+    }
+
+    #[inline(always)]
+    fn record_delegation(
+        &mut self,
+        access_id: u32,
+        base_register: u32,
+        register_accesses: &mut [RegisterOrIndirectReadWriteData],
+        indirect_read_addresses: &[u32],
+        indirect_reads: &mut [RegisterOrIndirectReadData],
+        indirect_write_addresses: &[u32],
+        indirect_writes: &mut [RegisterOrIndirectReadWriteData],
+    ) {
+        self.inner.record_delegation(
+            access_id,
+            base_register,
+            register_accesses,
+            indirect_read_addresses,
+            indirect_reads,
+            indirect_write_addresses,
+            indirect_writes,
+        );
+    }
+}
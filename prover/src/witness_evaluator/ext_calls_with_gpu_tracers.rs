@@ -2,6 +2,9 @@ use super::ext_calls::*;
 use super::*;
 use crate::tracers::delegation::bigint_with_control_factory_fn;
 use crate::tracers::delegation::blake2_with_control_factory_fn;
+use crate::tracers::delegation::keccak_f1600_factory_fn;
+use crate::tracers::delegation::poseidon2_permutation_factory_fn;
+use crate::tracers::delegation::sha256_with_compression_factory_fn;
 use crate::tracers::delegation::DelegationWitness;
 use crate::tracers::main_cycle_optimized::CycleData;
 use crate::tracers::oracles::delegation_oracle::DelegationCircuitOracle;
@@ -10,6 +13,9 @@ use crate::witness_evaluator::new::evaluate_witness;
 use crate::witness_evaluator::new::SimpleWitnessProxy;
 use risc_v_simulator::cycle::state_new::DelegationCSRProcessor;
 use risc_v_simulator::cycle::MachineConfig;
+use risc_v_simulator::delegations::keccak_f1600_round_function::KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID;
+use risc_v_simulator::delegations::poseidon2_permutation::POSEIDON2_PERMUTATION_ACCESS_ID;
+use risc_v_simulator::delegations::sha256_round_function::SHA256_ROUND_FUNCTION_ACCESS_ID;
 use risc_v_simulator::delegations::u256_ops_with_control::U256_OPS_WITH_CONTROL_ACCESS_ID;
 
 #[allow(unused_assignments)]
@@ -103,6 +109,34 @@ where
                 delegation_type,
                 Box::new(factory_fn) as Box<dyn Fn() -> DelegationWitness>,
             );
+        } else if *delegation_type == SHA256_ROUND_FUNCTION_ACCESS_ID {
+            let num_requests_per_circuit = circuit.num_requests_per_circuit;
+            let delegation_type = *delegation_type as u16;
+            let factory_fn = move || {
+                sha256_with_compression_factory_fn(delegation_type, num_requests_per_circuit)
+            };
+            factories.insert(
+                delegation_type,
+                Box::new(factory_fn) as Box<dyn Fn() -> DelegationWitness>,
+            );
+        } else if *delegation_type == KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID {
+            let num_requests_per_circuit = circuit.num_requests_per_circuit;
+            let delegation_type = *delegation_type as u16;
+            let factory_fn =
+                move || keccak_f1600_factory_fn(delegation_type, num_requests_per_circuit);
+            factories.insert(
+                delegation_type,
+                Box::new(factory_fn) as Box<dyn Fn() -> DelegationWitness>,
+            );
+        } else if *delegation_type == POSEIDON2_PERMUTATION_ACCESS_ID {
+            let num_requests_per_circuit = circuit.num_requests_per_circuit;
+            let delegation_type = *delegation_type as u16;
+            let factory_fn =
+                move || poseidon2_permutation_factory_fn(delegation_type, num_requests_per_circuit);
+            factories.insert(
+                delegation_type,
+                Box::new(factory_fn) as Box<dyn Fn() -> DelegationWitness>,
+            );
         } else {
             panic!(
                 "delegation type {} is unsupported for tests",
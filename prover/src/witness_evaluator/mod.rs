@@ -30,6 +30,7 @@ mod new;
 mod ext_calls;
 mod ext_calls_with_gpu_tracers;
 mod memory_witness;
+mod mutation_testing;
 pub mod witness_proxy;
 
 pub use self::new::{evaluate_witness, SimpleWitnessProxy};
@@ -46,6 +47,7 @@ pub use self::memory_witness::{evaluate_delegation_memory_witness, evaluate_memo
 
 pub use self::ext_calls::*;
 pub use self::ext_calls_with_gpu_tracers::*;
+pub use self::mutation_testing::*;
 use super::*;
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -0,0 +1,741 @@
+use super::*;
+use std::collections::BTreeSet;
+
+// Fault injection into the honest witness: single cells of the trace are perturbed one by one,
+// and every perturbation is checked against everything that is enforced within one circuit instance
+// on the main domain - constraints, state linkage, boundary values and the lookup argument. Perturbations
+// that pass all the checks are reported. Memory and delegation arguments are global over all the circuits
+// and are NOT modeled here, so memory subtree cells are only perturbed if they are used by local checks.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MutationTarget {
+    WitnessCell { row: usize, column: usize },
+    MemoryCell { row: usize, column: usize },
+    // column is an offset in the witness subtree, same as for witness cells
+    LookupMultiplicity { row: usize, column: usize },
+}
+
+impl MutationTarget {
+    pub fn row(&self) -> usize {
+        match self {
+            Self::WitnessCell { row, .. }
+            | Self::MemoryCell { row, .. }
+            | Self::LookupMultiplicity { row, .. } => *row,
+        }
+    }
+
+    pub fn address(&self) -> ColumnAddress {
+        match self {
+            Self::WitnessCell { column, .. } | Self::LookupMultiplicity { column, .. } => {
+                ColumnAddress::WitnessSubtree(*column)
+            }
+            Self::MemoryCell { column, .. } => ColumnAddress::MemorySubtree(*column),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Perturbation {
+    Add(Mersenne31Field),
+    Set(Mersenne31Field),
+}
+
+impl Perturbation {
+    pub fn apply(&self, value: Mersenne31Field) -> Mersenne31Field {
+        match self {
+            Self::Add(delta) => {
+                let mut value = value;
+                value.add_assign(delta);
+                value
+            }
+            Self::Set(new_value) => *new_value,
+        }
+    }
+}
+
+// off by one in both directions, trivial values, and a shift by the range check 16 bound
+pub const DEFAULT_PERTURBATIONS: [Perturbation; 5] = [
+    Perturbation::Add(Mersenne31Field::ONE),
+    Perturbation::Add(Mersenne31Field::MINUS_ONE),
+    Perturbation::Set(Mersenne31Field::ZERO),
+    Perturbation::Set(Mersenne31Field::ONE),
+    Perturbation::Add(Mersenne31Field(1 << 16)),
+];
+
+#[derive(Clone, Debug)]
+pub struct MutationTestingConfig {
+    pub rows: Vec<usize>,
+    pub perturbations: Vec<Perturbation>,
+    pub mutate_witness: bool,
+    pub mutate_memory: bool,
+    pub mutate_multiplicities: bool,
+}
+
+impl MutationTestingConfig {
+    pub fn for_rows(rows: &[usize]) -> Self {
+        Self {
+            rows: rows.to_vec(),
+            perturbations: DEFAULT_PERTURBATIONS.to_vec(),
+            mutate_witness: true,
+            mutate_memory: true,
+            mutate_multiplicities: true,
+        }
+    }
+
+    /// Selects `num_rows` rows evenly spread over the trace of `trace_len`, starting from the first one
+    pub fn spread_over_trace(trace_len: usize, num_rows: usize) -> Self {
+        assert!(trace_len.is_power_of_two());
+        assert!(num_rows > 0);
+        // last row is not constrained
+        let num_rows = num_rows.min(trace_len - 1);
+        let step = (trace_len - 1) / num_rows;
+        let rows: Vec<usize> = (0..num_rows).map(|i| i * step).collect();
+
+        Self::for_rows(&rows)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mutation {
+    pub target: MutationTarget,
+    pub original_value: Mersenne31Field,
+    pub mutated_value: Mersenne31Field,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MutationTestingReport {
+    pub num_mutations: usize,
+    pub survived: Vec<Mutation>,
+}
+
+impl MutationTestingReport {
+    pub fn all_caught(&self) -> bool {
+        self.survived.is_empty()
+    }
+
+    pub fn survived_for_row(&self, row: usize) -> impl Iterator<Item = &Mutation> {
+        self.survived
+            .iter()
+            .filter(move |el| el.target.row() == row)
+    }
+}
+
+impl std::fmt::Display for MutationTestingReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} out of {} mutations survived",
+            self.survived.len(),
+            self.num_mutations
+        )?;
+        for mutation in self.survived.iter() {
+            writeln!(
+                f,
+                "{:?}: {:?} -> {:?}",
+                mutation.target, mutation.original_value, mutation.mutated_value
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum LookupSlot {
+    RangeCheck16(usize),
+    TimestampRangeCheck(usize),
+    // absolute index in the concatenation of all the tables
+    Generic(usize),
+}
+
+// Current row of the trace together with its neighbours, that are needed for state linkage
+struct RowsWindow {
+    witness: [Vec<Mersenne31Field>; 3],
+    memory: [Vec<Mersenne31Field>; 3],
+    has_previous: bool,
+    has_next: bool,
+}
+
+impl RowsWindow {
+    const CURRENT: usize = 1;
+
+    fn new<const N: usize, A: GoodAllocator>(
+        exec_trace: &RowMajorTrace<Mersenne31Field, N, A>,
+        num_witness_columns: usize,
+        row: usize,
+    ) -> Self {
+        // linkage is enforced at every row except last two
+        let has_previous = row > 0;
+        let has_next = row + 2 < exec_trace.len();
+        let read_row = |row: usize| {
+            let row = unsafe { exec_trace.get_row(row) };
+            let (witness, memory) = row.split_at(num_witness_columns);
+
+            (witness.to_vec(), memory.to_vec())
+        };
+        let (previous_witness, previous_memory) = if has_previous {
+            read_row(row - 1)
+        } else {
+            (vec![], vec![])
+        };
+        let (witness, memory) = read_row(row);
+        let (next_witness, next_memory) = if has_next {
+            read_row(row + 1)
+        } else {
+            (vec![], vec![])
+        };
+
+        Self {
+            witness: [previous_witness, witness, next_witness],
+            memory: [previous_memory, memory, next_memory],
+            has_previous,
+            has_next,
+        }
+    }
+
+    fn current(&self) -> (&[Mersenne31Field], &[Mersenne31Field]) {
+        (&self.witness[Self::CURRENT], &self.memory[Self::CURRENT])
+    }
+
+    fn cell_mut(&mut self, target: MutationTarget) -> &mut Mersenne31Field {
+        match target.address() {
+            ColumnAddress::WitnessSubtree(offset) => &mut self.witness[Self::CURRENT][offset],
+            ColumnAddress::MemorySubtree(offset) => &mut self.memory[Self::CURRENT][offset],
+            _ => unreachable!(),
+        }
+    }
+
+    fn linkage_satisfied(
+        &self,
+        compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+    ) -> bool {
+        let mut pairs = vec![];
+        if self.has_previous {
+            pairs.push((Self::CURRENT - 1, Self::CURRENT));
+        }
+        if self.has_next {
+            pairs.push((Self::CURRENT, Self::CURRENT + 1));
+        }
+        for (src_row, dst_row) in pairs.into_iter() {
+            for (src, dst) in compiled_circuit.state_linkage_constraints.iter() {
+                let src_value = read_value(*src, &self.witness[src_row], &self.memory[src_row]);
+                let dst_value = read_value(*dst, &self.witness[dst_row], &self.memory[dst_row]);
+                if src_value != dst_value {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+fn constraints_satisfied_at_row(
+    compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+    witness_row: &[Mersenne31Field],
+    memory_row: &[Mersenne31Field],
+) -> bool {
+    let degree_1_satisfied = compiled_circuit
+        .degree_1_constraints
+        .iter()
+        .all(|constraint| {
+            constraint.evaluate_at_row_on_main_domain(witness_row, memory_row)
+                == Mersenne31Field::ZERO
+        });
+    let degree_2_satisfied = compiled_circuit
+        .degree_2_constraints
+        .iter()
+        .all(|constraint| {
+            constraint.evaluate_at_row_on_main_domain(witness_row, memory_row)
+                == Mersenne31Field::ZERO
+        });
+
+    degree_1_satisfied && degree_2_satisfied
+}
+
+fn evaluate_lookup_expression(
+    expression: &LookupExpression<Mersenne31Field>,
+    witness_row: &[Mersenne31Field],
+    memory_row: &[Mersenne31Field],
+    setup_row: &[Mersenne31Field],
+) -> Mersenne31Field {
+    match expression {
+        LookupExpression::Variable(place) => {
+            read_value_with_setup_access(*place, witness_row, memory_row, setup_row)
+        }
+        LookupExpression::Expression(constraint) => {
+            constraint.evaluate_at_row_on_main_domain_ext(witness_row, memory_row, setup_row)
+        }
+    }
+}
+
+// Same set of lookups as the witness generation counts multiplicities for, or `None` if any
+// of the tuples is not in the corresponding table
+fn evaluate_lookups_at_row(
+    compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+    table_driver: &TableDriver<Mersenne31Field>,
+    witness_row: &[Mersenne31Field],
+    memory_row: &[Mersenne31Field],
+    absolute_row_idx: usize,
+    timestamp_high_contribution_if_shuffle_ram: Mersenne31Field,
+) -> Option<Vec<LookupSlot>> {
+    let witness_layout = &compiled_circuit.witness_layout;
+    let [timestamp_low, timestamp_high] =
+        row_into_timestamp_limbs_for_setup(absolute_row_idx as u32);
+    let setup_row = [
+        Mersenne31Field(timestamp_low),
+        Mersenne31Field(timestamp_high),
+    ];
+
+    let mut result = vec![];
+    let range_check_16_slot = |value: Mersenne31Field| {
+        let value = value.to_reduced_u32() as usize;
+        (value <= u16::MAX as usize).then_some(LookupSlot::RangeCheck16(value))
+    };
+    let timestamp_slot = |value: Mersenne31Field| {
+        let value = value.to_reduced_u32() as usize;
+        (value < 1 << TIMESTAMP_COLUMNS_NUM_BITS).then_some(LookupSlot::TimestampRangeCheck(value))
+    };
+
+    for expression in witness_layout.range_check_16_lookup_expressions.iter() {
+        let value = evaluate_lookup_expression(expression, witness_row, memory_row, &setup_row);
+        result.push(range_check_16_slot(value)?);
+    }
+    if let Some(shuffle_ram_inits_and_teardowns) = compiled_circuit
+        .memory_layout
+        .shuffle_ram_inits_and_teardowns
+    {
+        for offset in shuffle_ram_inits_and_teardowns
+            .lazy_init_addresses_columns
+            .full_range()
+        {
+            result.push(range_check_16_slot(memory_row[offset])?);
+        }
+    }
+
+    let (timestamp_expressions, shuffle_ram_timestamp_expressions) = witness_layout
+        .timestamp_range_check_lookup_expressions
+        .split_at(witness_layout.offset_for_special_shuffle_ram_timestamps_range_check_expressions);
+    for expression in timestamp_expressions.iter() {
+        let value = evaluate_lookup_expression(expression, witness_row, memory_row, &setup_row);
+        result.push(timestamp_slot(value)?);
+    }
+    for [low, high] in shuffle_ram_timestamp_expressions.as_chunks::<2>().0.iter() {
+        let low_value = evaluate_lookup_expression(low, witness_row, memory_row, &setup_row);
+        result.push(timestamp_slot(low_value)?);
+        let mut high_value = evaluate_lookup_expression(high, witness_row, memory_row, &setup_row);
+        high_value.sub_assign(&timestamp_high_contribution_if_shuffle_ram);
+        result.push(timestamp_slot(high_value)?);
+    }
+
+    for lookup in witness_layout.width_3_lookups.iter() {
+        let tuple = lookup.input_columns.each_ref().map(|expression| {
+            evaluate_lookup_expression(expression, witness_row, memory_row, &setup_row)
+        });
+        let table_id = match lookup.table_index {
+            TableIndex::Constant(table_type) => table_type.to_table_id(),
+            TableIndex::Variable(place) => {
+                read_value(place, witness_row, memory_row).to_reduced_u32()
+            }
+        };
+        let index = table_driver.try_get_absolute_index(&tuple, table_id)?;
+        result.push(LookupSlot::Generic(index));
+    }
+
+    Some(result)
+}
+
+fn multiplicity_slot(
+    compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+    column: usize,
+    row: usize,
+    trace_len: usize,
+) -> LookupSlot {
+    let witness_layout = &compiled_circuit.witness_layout;
+    if witness_layout
+        .multiplicities_columns_for_range_check_16
+        .full_range()
+        .contains(&column)
+    {
+        LookupSlot::RangeCheck16(row)
+    } else if witness_layout
+        .multiplicities_columns_for_timestamp_range_check
+        .full_range()
+        .contains(&column)
+    {
+        LookupSlot::TimestampRangeCheck(row)
+    } else {
+        let generic_column = column
+            - witness_layout
+                .multiplicities_columns_for_generic_lookup
+                .start();
+        let index =
+            encoding_tuple_into_lookup_index(generic_column as u32, row as u32, trace_len - 1);
+        LookupSlot::Generic(index)
+    }
+}
+
+fn memory_columns_used_by_local_checks(
+    compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+) -> BTreeSet<usize> {
+    let mut places = vec![];
+    for constraint in compiled_circuit.degree_1_constraints.iter() {
+        places.extend(constraint.linear_terms.iter().map(|(_, place)| *place));
+    }
+    for constraint in compiled_circuit.degree_2_constraints.iter() {
+        places.extend(constraint.linear_terms.iter().map(|(_, place)| *place));
+        for (_, a, b) in constraint.quadratic_terms.iter() {
+            places.extend([*a, *b]);
+        }
+    }
+    let witness_layout = &compiled_circuit.witness_layout;
+    let lookup_expressions = witness_layout
+        .range_check_16_lookup_expressions
+        .iter()
+        .chain(
+            witness_layout
+                .timestamp_range_check_lookup_expressions
+                .iter(),
+        )
+        .chain(
+            witness_layout
+                .width_3_lookups
+                .iter()
+                .flat_map(|el| el.input_columns.iter()),
+        );
+    for expression in lookup_expressions {
+        match expression {
+            LookupExpression::Variable(place) => places.push(*place),
+            LookupExpression::Expression(constraint) => {
+                places.extend(constraint.linear_terms.iter().map(|(_, place)| *place));
+            }
+        }
+    }
+    for lookup in witness_layout.width_3_lookups.iter() {
+        if let TableIndex::Variable(place) = lookup.table_index {
+            places.push(place);
+        }
+    }
+    for (src, dst) in compiled_circuit.state_linkage_constraints.iter() {
+        places.extend([*src, *dst]);
+    }
+
+    let mut result: BTreeSet<usize> = places
+        .into_iter()
+        .filter_map(|place| match place {
+            ColumnAddress::MemorySubtree(offset) => Some(offset),
+            _ => None,
+        })
+        .collect();
+    if let Some(shuffle_ram_inits_and_teardowns) = compiled_circuit
+        .memory_layout
+        .shuffle_ram_inits_and_teardowns
+    {
+        result.extend(
+            shuffle_ram_inits_and_teardowns
+                .lazy_init_addresses_columns
+                .full_range(),
+        );
+    }
+
+    result
+}
+
+fn is_fixed_by_boundary_constraint(
+    compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+    target: MutationTarget,
+    trace_len: usize,
+) -> bool {
+    compiled_circuit
+        .public_inputs
+        .iter()
+        .any(|(location, place)| {
+            let row = match location {
+                BoundaryConstraintLocation::FirstRow => 0,
+                BoundaryConstraintLocation::OneBeforeLastRow => trace_len - 2,
+                BoundaryConstraintLocation::LastRow => trace_len - 1,
+            };
+            *place == target.address() && row == target.row()
+        })
+}
+
+/// Perturbs cells of the honest `witness` at the rows from the `config` one by one, and reports
+/// the perturbations that still satisfy all the checks local to this circuit instance. `witness`
+/// must be produced by `evaluate_witness` for the same circuit, `table_driver` and `circuit_sequence`
+pub fn run_mutation_testing<const N: usize, A: GoodAllocator>(
+    compiled_circuit: &CompiledCircuitArtifact<Mersenne31Field>,
+    table_driver: &TableDriver<Mersenne31Field>,
+    witness: &WitnessEvaluationData<N, A>,
+    circuit_sequence: usize,
+    config: &MutationTestingConfig,
+) -> MutationTestingReport {
+    let exec_trace = &witness.exec_trace;
+    let num_witness_columns = witness.num_witness_columns;
+    let trace_len = exec_trace.len();
+    assert!(trace_len.is_power_of_two());
+    assert_eq!(trace_len, compiled_circuit.trace_len);
+
+    let timestamp_high_contribution_if_shuffle_ram = Mersenne31Field(
+        (timestamp_high_contribution_from_circuit_sequence(circuit_sequence, trace_len)
+            >> TIMESTAMP_COLUMNS_NUM_BITS) as u32,
+    );
+
+    let witness_layout = &compiled_circuit.witness_layout;
+    let multiplicities_columns: BTreeSet<usize> = witness_layout
+        .multiplicities_columns_for_range_check_16
+        .full_range()
+        .chain(
+            witness_layout
+                .multiplicities_columns_for_timestamp_range_check
+                .full_range(),
+        )
+        .chain(
+            witness_layout
+                .multiplicities_columns_for_generic_lookup
+                .full_range(),
+        )
+        .collect();
+    let memory_columns = memory_columns_used_by_local_checks(compiled_circuit);
+
+    let mut report = MutationTestingReport::default();
+    for &row in config.rows.iter() {
+        assert!(
+            row < trace_len - 1,
+            "row {} is out of the constrained part of the trace of length {}",
+            row,
+            trace_len
+        );
+        let mut rows = RowsWindow::new(exec_trace, num_witness_columns, row);
+
+        let (witness_row, memory_row) = rows.current();
+        assert!(
+            check_satisfied_row(compiled_circuit, witness_row, memory_row, row),
+            "honest witness is not satisfied at row {}",
+            row
+        );
+        assert!(
+            rows.linkage_satisfied(compiled_circuit),
+            "honest witness doesn't satisfy state linkage at row {}",
+            row
+        );
+        let honest_lookups = evaluate_lookups_at_row(
+            compiled_circuit,
+            table_driver,
+            witness_row,
+            memory_row,
+            row,
+            timestamp_high_contribution_if_shuffle_ram,
+        )
+        .unwrap_or_else(|| panic!("honest witness has invalid lookup at row {}", row));
+
+        let mut targets = vec![];
+        if config.mutate_witness {
+            targets.extend(
+                (0..num_witness_columns)
+                    .filter(|column| multiplicities_columns.contains(column) == false)
+                    .map(|column| MutationTarget::WitnessCell { row, column }),
+            );
+        }
+        if config.mutate_memory {
+            targets.extend(
+                memory_columns
+                    .iter()
+                    .map(|&column| MutationTarget::MemoryCell { row, column }),
+            );
+        }
+        if config.mutate_multiplicities {
+            targets.extend(
+                multiplicities_columns
+                    .iter()
+                    .map(|&column| MutationTarget::LookupMultiplicity { row, column }),
+            );
+        }
+
+        for target in targets.into_iter() {
+            if is_fixed_by_boundary_constraint(compiled_circuit, target, trace_len) {
+                continue;
+            }
+            let original_value = *rows.cell_mut(target);
+            for perturbation in config.perturbations.iter() {
+                let mutated_value = perturbation.apply(original_value);
+                if mutated_value == original_value {
+                    continue;
+                }
+                *rows.cell_mut(target) = mutated_value;
+                report.num_mutations += 1;
+
+                let (witness_row, memory_row) = rows.current();
+                if constraints_satisfied_at_row(compiled_circuit, witness_row, memory_row) == false
+                    || rows.linkage_satisfied(compiled_circuit) == false
+                {
+                    continue;
+                }
+                let Some(lookups) = evaluate_lookups_at_row(
+                    compiled_circuit,
+                    table_driver,
+                    witness_row,
+                    memory_row,
+                    row,
+                    timestamp_high_contribution_if_shuffle_ram,
+                ) else {
+                    continue;
+                };
+
+                // lookup argument holds if the change in the looked up values is exactly
+                // compensated by the change in multiplicities
+                let mut balance: HashMap<LookupSlot, Mersenne31Field> = HashMap::new();
+                for slot in lookups.into_iter() {
+                    balance
+                        .entry(slot)
+                        .or_insert(Mersenne31Field::ZERO)
+                        .add_assign(&Mersenne31Field::ONE);
+                }
+                for slot in honest_lookups.iter() {
+                    balance
+                        .entry(*slot)
+                        .or_insert(Mersenne31Field::ZERO)
+                        .sub_assign(&Mersenne31Field::ONE);
+                }
+                if let MutationTarget::LookupMultiplicity { column, .. } = target {
+                    let slot = multiplicity_slot(compiled_circuit, column, row, trace_len);
+                    let mut multiplicity_change = mutated_value;
+                    multiplicity_change.sub_assign(&original_value);
+                    balance
+                        .entry(slot)
+                        .or_insert(Mersenne31Field::ZERO)
+                        .sub_assign(&multiplicity_change);
+                }
+                if balance.values().all(|el| *el == Mersenne31Field::ZERO) {
+                    report.survived.push(Mutation {
+                        target,
+                        original_value,
+                        mutated_value,
+                    });
+                }
+            }
+            *rows.cell_mut(target) = original_value;
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_TRACE_PADDING_MULTIPLE;
+    use cs::constraint::Term;
+    use cs::cs::circuit::{Circuit, RegisterAccessRequest};
+    use cs::cs::cs_reference::BasicAssembly;
+    use cs::definitions::REGISTER_SIZE;
+
+    const ROW: usize = 1;
+
+    #[test]
+    fn mutation_of_constrained_cell_is_caught() {
+        // smallest delegation circuit: a single register read and a * b = c, plus a variable
+        // that no constraint touches
+        let mut cs = BasicAssembly::<Mersenne31Field>::new();
+        let _ = cs.process_delegation_request();
+        let _ = cs.create_register_and_indirect_memory_accesses(RegisterAccessRequest {
+            register_index: 10,
+            register_write: false,
+            indirects_alignment_log2: 0,
+            indirect_accesses: vec![],
+        });
+        let a = cs.add_variable();
+        let b = cs.add_variable();
+        let c = cs.add_variable();
+        let free = cs.add_variable();
+        cs.add_constraint(Term::from(a) * Term::from(b) - Term::from(c));
+        let (circuit_output, _) = cs.finalize();
+        let table_driver = circuit_output.table_driver.clone();
+        let compiled_circuit =
+            OneRowCompiler::default().compile_to_evaluate_delegations(circuit_output, 20);
+
+        let trace_len = compiled_circuit.trace_len;
+        let num_witness_columns = compiled_circuit.witness_layout.total_width;
+        let num_memory_columns = compiled_circuit.memory_layout.total_width;
+        // padding rows of delegation circuits are all zeroes, and we only fill one row
+        let mut exec_trace: RowMajorTrace<Mersenne31Field, DEFAULT_TRACE_PADDING_MULTIPLE, _> =
+            RowMajorTrace::new_zeroed_for_size(
+                trace_len,
+                num_witness_columns + num_memory_columns,
+                Global,
+            );
+        let column_of = |variable| {
+            let ColumnAddress::WitnessSubtree(column) =
+                compiled_circuit.variable_mapping[&variable]
+            else {
+                panic!(
+                    "variable {:?} is not placed in the witness subtree",
+                    variable
+                );
+            };
+            column
+        };
+        let row = exec_trace.get_row_mut(ROW);
+        for (variable, value) in [(a, 3), (b, 5), (c, 15), (free, 7)] {
+            row[column_of(variable)] = Mersenne31Field(value);
+        }
+
+        let witness = WitnessEvaluationData {
+            aux_data: WitnessEvaluationAuxData {
+                first_row_public_inputs: vec![],
+                one_before_last_row_public_inputs: vec![],
+                lazy_init_first_row: [Mersenne31Field::ZERO; REGISTER_SIZE],
+                teardown_value_first_row: [Mersenne31Field::ZERO; REGISTER_SIZE],
+                teardown_timestamp_first_row: [Mersenne31Field::ZERO; REGISTER_SIZE],
+                lazy_init_one_before_last_row: [Mersenne31Field::ZERO; REGISTER_SIZE],
+                teardown_value_one_before_last_row: [Mersenne31Field::ZERO; REGISTER_SIZE],
+                teardown_timestamp_one_before_last_row: [Mersenne31Field::ZERO; REGISTER_SIZE],
+            },
+            exec_trace,
+            num_witness_columns,
+            lookup_mapping: RowMajorTrace::new_zeroed_for_size(
+                trace_len,
+                compiled_circuit.witness_layout.width_3_lookups.len(),
+                Global,
+            ),
+        };
+
+        let config = MutationTestingConfig {
+            mutate_memory: false,
+            mutate_multiplicities: false,
+            ..MutationTestingConfig::for_rows(&[ROW])
+        };
+        let report = run_mutation_testing(&compiled_circuit, &table_driver, &witness, 0, &config);
+        assert!(report.num_mutations > 0);
+
+        let survived_columns: BTreeSet<usize> = report
+            .survived_for_row(ROW)
+            .map(|el| match el.target {
+                MutationTarget::WitnessCell { column, .. } => column,
+                _ => unreachable!(),
+            })
+            .collect();
+        for variable in [a, b, c] {
+            assert!(
+                survived_columns.contains(&column_of(variable)) == false,
+                "mutation of constrained variable {:?} survived:\n{}",
+                variable,
+                report
+            );
+        }
+        // nothing constrains `free`, so any value of it is as good as the honest one
+        let free_column = column_of(free);
+        assert_eq!(
+            report
+                .survived
+                .iter()
+                .filter(|el| el.target
+                    == MutationTarget::WitnessCell {
+                        row: ROW,
+                        column: free_column
+                    })
+                .count(),
+            DEFAULT_PERTURBATIONS.len()
+        );
+        assert!(report.all_caught() == false);
+    }
+}
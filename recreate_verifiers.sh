@@ -6,8 +6,12 @@ circuit_names=(
     "reduced_risc_v_machine"
     "final_reduced_risc_v_machine"
     "machine_without_signed_mul_div"
+    "machine_with_bitmanip"
     "blake2_with_compression"
     "bigint_with_control"
+    "sha256_with_compression"
+    "keccak_f1600"
+    "poseidon2_permutation"
 )
 
 # update the generated files
@@ -19,6 +23,8 @@ cp tools/generator/output/all_delegation_circuits_params.rs circuit_defs/setups/
 for CIRCUIT_NAME in "${circuit_names[@]}"; do
     echo $CIRCUIT_NAME
 
+    # new circuits don't have it yet
+    mkdir -p circuit_defs/$CIRCUIT_NAME/generated
     cp tools/generator/output/${CIRCUIT_NAME}_layout.json circuit_defs/$CIRCUIT_NAME/generated/layout
     cp tools/generator/output/${CIRCUIT_NAME}_circuit_layout.rs circuit_defs/$CIRCUIT_NAME/generated/circuit_layout.rs
     cp tools/generator/output/${CIRCUIT_NAME}_quotient.rs circuit_defs/$CIRCUIT_NAME/generated/quotient.rs
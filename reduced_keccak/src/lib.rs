@@ -13,6 +13,11 @@ const RATE_LANES: usize = RATE_BITS / 64; // = 17 lanes per block
 const RATE_WORDS: usize = RATE_BITS / 32; // = 34 u32 words per block
 
 /// A Keccak-256 sponge with u32-word inputs/outputs, for RV32. No heap allocations.
+// With delegation the state is passed to the circuit by pointer, and it must be 256-byte aligned
+#[cfg_attr(
+    all(target_arch = "riscv32", feature = "delegation"),
+    repr(C, align(256))
+)]
 pub struct Keccak32 {
     state: [u64; LANES],       // internal 64-bit lanes
     buffer: [u32; RATE_WORDS], // fixed-size input buffer
@@ -144,7 +149,28 @@ pub fn keccak256_words(input: &[u32]) -> [u32; 8] {
     hasher.finalize()
 }
 
+/// CSR that triggers a single round of Keccak-f[1600] over the state.
+pub const CSR_REGISTER_TO_TRIGGER: u32 = 0x7cc;
+
+/// The 24-round permutation Keccak-f[1600], evaluated by the delegation circuit round by round.
+/// The state is passed in x10, and the round index in x11, round constant is selected by the circuit.
+#[cfg(all(target_arch = "riscv32", feature = "delegation"))]
+fn keccak_f(state: &mut [u64; 25]) {
+    debug_assert!(state.as_ptr().addr() % 256 == 0);
+    for round_index in 0..ROUND_CONSTANTS.len() {
+        unsafe {
+            core::arch::asm!(
+                "csrrw x0, 0x7cc, x0",
+                in("x10") state.as_mut_ptr().addr(),
+                in("x11") round_index as u32,
+                options(nostack, preserves_flags)
+            )
+        }
+    }
+}
+
 /// The 24-round permutation Keccak-f[1600].
+#[cfg(not(all(target_arch = "riscv32", feature = "delegation")))]
 fn keccak_f(state: &mut [u64; 25]) {
     for round in 0..24 {
         // Theta step
@@ -6,6 +6,22 @@ use crate::cycle::state::RiscV32State;
 use crate::cycle::status_registers::TrapReason;
 use crate::mmu::MMUImplementation;
 
+/// Delegation was requested with arguments that can not be processed (e.g. unaligned pointers
+/// or invalid control word). Unlike traps, it's not something that the program can handle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelegationAbiViolation {
+    pub id: u32,
+    pub reason: String,
+}
+
+impl std::fmt::Display for DelegationAbiViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "delegation 0x{:03x}: {}", self.id, self.reason)
+    }
+}
+
+impl std::error::Error for DelegationAbiViolation {}
+
 pub trait CustomCSRProcessor: 'static + Clone + Debug {
     // we are only interested in CSRs that are NOT in out basic list
     fn process_read<
@@ -44,7 +60,7 @@ pub trait CustomCSRProcessor: 'static + Clone + Debug {
         rs1_value: u32,
         zimm: u32,
         trap: &mut TrapReason,
-    );
+    ) -> Result<(), DelegationAbiViolation>;
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -93,7 +109,9 @@ impl CustomCSRProcessor for NoExtraCSRs {
         _rs1_value: u32,
         _zimm: u32,
         trap: &mut TrapReason,
-    ) {
+    ) -> Result<(), DelegationAbiViolation> {
         *trap = TrapReason::IllegalInstruction;
+
+        Ok(())
     }
 }
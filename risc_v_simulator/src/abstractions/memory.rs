@@ -1,4 +1,5 @@
 use crate::cycle::status_registers::TrapReason;
+use crate::snapshot::{MemorySnapshot, TouchedPages};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u32)]
@@ -81,7 +82,9 @@ pub trait MemorySource {
 }
 
 pub struct VectorMemoryImpl {
+    // direct writes must be reported with `mark_touched`, otherwise they are not snapshotted
     pub inner: Vec<u32>,
+    touched_pages: TouchedPages,
 }
 
 impl VectorMemoryImpl {
@@ -90,24 +93,44 @@ impl VectorMemoryImpl {
         let word_size = bytes / 4;
         Self {
             inner: vec![0u32; word_size],
+            touched_pages: TouchedPages::new_for_num_words(word_size),
         }
     }
 
     pub fn populate(&mut self, address: u32, value: u32) {
         assert!(address % 4 == 0);
         self.inner[(address / 4) as usize] = value;
+        self.touched_pages.mark_word((address / 4) as usize);
+    }
+
+    pub fn mark_touched(&mut self, address: u32, len_in_bytes: usize) {
+        let start = (address / 4) as usize;
+        let end = (address as usize + len_in_bytes).div_ceil(4);
+        self.touched_pages.mark_words(start..end);
     }
 
     pub fn load_image<'a, B>(&mut self, entry_point: u32, bytes: B)
     where
         B: Iterator<Item = u8>,
     {
+        let start = (entry_point / 4) as usize;
+        let mut num_words = 0;
         for (word, dst) in bytes
             .array_chunks::<4>()
-            .zip(self.inner[((entry_point / 4) as usize)..].iter_mut())
+            .zip(self.inner[start..].iter_mut())
         {
             *dst = u32::from_le_bytes(word);
+            num_words += 1;
         }
+        self.touched_pages.mark_words(start..(start + num_words));
+    }
+
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot::capture(&self.inner, &self.touched_pages)
+    }
+
+    pub fn restore_from_snapshot(&mut self, snapshot: &MemorySnapshot) {
+        snapshot.restore_into(&mut self.inner, &mut self.touched_pages)
     }
 }
 
@@ -141,6 +164,7 @@ impl MemorySource for VectorMemoryImpl {
         debug_assert_eq!(phys_address % 4, 0);
         if ((phys_address / 4) as usize) < self.inner.len() {
             self.inner[(phys_address / 4) as usize] = value;
+            self.touched_pages.mark_word((phys_address / 4) as usize);
         } else {
             match access_type {
                 AccessType::Instruction => *trap = TrapReason::InstructionAccessFault,
@@ -11,6 +11,19 @@ pub trait NonDeterminismCSRSource<M: MemorySource> {
     fn write_with_memory_access(&mut self, memory: &M, value: u32);
 }
 
+/// Non-determinism source that can be fast-forwarded when execution is resumed from the snapshot.
+/// Source is expected to be re-created from the same inputs, so only the number of consumed
+/// words and the state of partially processed writes are recorded.
+pub trait ResumableNonDeterminismSource {
+    fn num_words_read(&self) -> u64;
+    fn skip_words(&mut self, num_words: u64);
+
+    fn write_state(&self) -> Vec<u32> {
+        Vec::new()
+    }
+    fn restore_write_state(&mut self, _state: &[u32]) {}
+}
+
 pub struct ZeroedSource;
 
 impl<M: MemorySource> NonDeterminismCSRSource<M> for ZeroedSource {
@@ -20,6 +33,14 @@ impl<M: MemorySource> NonDeterminismCSRSource<M> for ZeroedSource {
     fn write_with_memory_access(&mut self, _memory: &M, _value: u32) {}
 }
 
+impl ResumableNonDeterminismSource for ZeroedSource {
+    fn num_words_read(&self) -> u64 {
+        0
+    }
+
+    fn skip_words(&mut self, _num_words: u64) {}
+}
+
 use super::memory::MemorySource;
 use std::collections::VecDeque;
 
@@ -27,6 +48,7 @@ use std::collections::VecDeque;
 pub struct QuasiUARTSource {
     pub oracle: VecDeque<u32>,
     write_state: QuasiUARTSourceState,
+    num_words_read: u64,
 }
 
 impl Default for QuasiUARTSource {
@@ -34,6 +56,7 @@ impl Default for QuasiUARTSource {
         Self {
             oracle: VecDeque::new(),
             write_state: QuasiUARTSourceState::Ready,
+            num_words_read: 0,
         }
     }
 }
@@ -43,6 +66,7 @@ impl QuasiUARTSource {
         Self {
             oracle: VecDeque::from(reads),
             write_state: QuasiUARTSourceState::Ready,
+            num_words_read: 0,
         }
     }
 }
@@ -60,6 +84,66 @@ pub enum QuasiUARTSourceState {
 impl QuasiUARTSourceState {
     const HELLO_VALUE: u32 = u32::MAX;
 
+    const HAS_REMAINING_WORDS: u32 = 1 << 0;
+    const HAS_REMAINING_LEN: u32 = 1 << 1;
+
+    // `Ready` is encoded as an empty slice, and `Buffering` as
+    // [flags, remaining words, remaining length, buffer length, buffer bytes packed into LE words]
+    pub fn encode(&self) -> Vec<u32> {
+        match self {
+            QuasiUARTSourceState::Ready => Vec::new(),
+            QuasiUARTSourceState::Buffering {
+                remaining_words,
+                remaining_len_in_bytes,
+                buffer,
+            } => {
+                let mut flags = 0;
+                if remaining_words.is_some() {
+                    flags |= Self::HAS_REMAINING_WORDS;
+                }
+                if remaining_len_in_bytes.is_some() {
+                    flags |= Self::HAS_REMAINING_LEN;
+                }
+                let mut result = vec![
+                    flags,
+                    remaining_words.unwrap_or_default() as u32,
+                    remaining_len_in_bytes.unwrap_or_default() as u32,
+                    buffer.len() as u32,
+                ];
+                result.extend(buffer.chunks(4).map(|chunk| {
+                    let mut word = [0u8; 4];
+                    word[..chunk.len()].copy_from_slice(chunk);
+                    u32::from_le_bytes(word)
+                }));
+
+                result
+            }
+        }
+    }
+
+    pub fn decode(encoding: &[u32]) -> Self {
+        if encoding.is_empty() {
+            return QuasiUARTSourceState::Ready;
+        }
+        assert!(encoding.len() >= 4, "malformed UART state encoding");
+        let flags = encoding[0];
+        let buffer_len = encoding[3] as usize;
+        let mut buffer: Vec<u8> = encoding[4..]
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        assert!(buffer.len() >= buffer_len, "malformed UART state encoding");
+        buffer.truncate(buffer_len);
+
+        QuasiUARTSourceState::Buffering {
+            remaining_words: (flags & Self::HAS_REMAINING_WORDS != 0)
+                .then_some(encoding[1] as usize),
+            remaining_len_in_bytes: (flags & Self::HAS_REMAINING_LEN != 0)
+                .then_some(encoding[2] as usize),
+            buffer,
+        }
+    }
+
     pub fn process_write(&mut self, value: u32) {
         match self {
             QuasiUARTSourceState::Ready => {
@@ -115,6 +199,7 @@ impl QuasiUARTSourceState {
 
 impl<M: MemorySource> NonDeterminismCSRSource<M> for QuasiUARTSource {
     fn read(&mut self) -> u32 {
+        self.num_words_read += 1;
         self.oracle.pop_front().unwrap_or_default()
     }
 
@@ -123,6 +208,26 @@ impl<M: MemorySource> NonDeterminismCSRSource<M> for QuasiUARTSource {
     }
 }
 
+impl ResumableNonDeterminismSource for QuasiUARTSource {
+    fn num_words_read(&self) -> u64 {
+        self.num_words_read
+    }
+
+    fn skip_words(&mut self, num_words: u64) {
+        let num_available = core::cmp::min(num_words, self.oracle.len() as u64);
+        self.oracle.drain(..num_available as usize);
+        self.num_words_read += num_words;
+    }
+
+    fn write_state(&self) -> Vec<u32> {
+        self.write_state.encode()
+    }
+
+    fn restore_write_state(&mut self, state: &[u32]) {
+        self.write_state = QuasiUARTSourceState::decode(state);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,4 +355,34 @@ mod tests {
             panic!("State did not transition to Ready");
         }
     }
+
+    #[test]
+    fn test_write_state_encoding() {
+        let mut state = QuasiUARTSourceState::Ready;
+        assert!(state.encode().is_empty());
+
+        state.process_write(QuasiUARTSourceState::HELLO_VALUE);
+        state.process_write(3); // 3 words
+        state.process_write(6); // 6 bytes
+        state.process_write(0x64636261); // "abcd"
+
+        let encoding = state.encode();
+        let mut restored = QuasiUARTSourceState::decode(&encoding);
+        assert_eq!(restored.encode(), encoding);
+        if let QuasiUARTSourceState::Buffering {
+            remaining_words,
+            remaining_len_in_bytes,
+            buffer,
+        } = &restored
+        {
+            assert_eq!(*remaining_words, Some(1));
+            assert_eq!(*remaining_len_in_bytes, Some(2));
+            assert_eq!(buffer, b"abcd");
+        } else {
+            panic!("State was not restored as Buffering");
+        }
+
+        restored.process_write(0x6665); // "ef"
+        assert!(matches!(restored, QuasiUARTSourceState::Ready));
+    }
 }
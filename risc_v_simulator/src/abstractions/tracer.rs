@@ -57,6 +57,10 @@ pub trait Tracer<C: MachineConfig>: Sized {
     #[inline(always)]
     fn trace_opcode_read(&mut self, _phys_address: u64, _read_value: u32) {}
 
+    // called once per executed instruction with its mnemonic (or instruction family for some of them)
+    #[inline(always)]
+    fn trace_opcode(&mut self, _opcode: &'static str) {}
+
     #[inline(always)]
     fn trace_rs1_read(&mut self, _reg_idx: u32, _read_value: u32) {}
 
@@ -0,0 +1,88 @@
+use super::MachineConfig;
+
+pub(crate) const ANDN_ORN_XNOR_FUNCT7: u8 = 0b0100000;
+pub(crate) const MIN_MAX_FUNCT7: u8 = 0b0000101;
+pub(crate) const PACK_FUNCT7: u8 = 0b0000100;
+pub(crate) const COUNT_AND_SEXT_FUNCT7: u8 = 0b0110000;
+pub(crate) const REV8_BREV8_FUNCT7: u8 = 0b0110100;
+pub(crate) const ORC_B_FUNCT7: u8 = 0b0010100;
+
+pub(crate) const CLZ_RS2: u8 = 0b00000;
+pub(crate) const CTZ_RS2: u8 = 0b00001;
+pub(crate) const CPOP_RS2: u8 = 0b00010;
+pub(crate) const SEXT_B_RS2: u8 = 0b00100;
+pub(crate) const SEXT_H_RS2: u8 = 0b00101;
+pub(crate) const REV8_RS2: u8 = 0b11000;
+pub(crate) const BREV8_AND_ORC_B_RS2: u8 = 0b00111;
+
+/// Resolves Zbb and Zbkb instructions that share OP and OP-IMM opcodes with the base ISA,
+/// except rotations that are controlled by `SUPPORT_ROT` and handled together with shifts.
+/// `rs2` is a formal rs2 field of the instruction, that is a part of the immediate for OP-IMM.
+/// Returns `None` if encoding is not a bit-manipulation instruction supported by the config
+#[must_use]
+#[inline(always)]
+pub(crate) fn bitmanip_op<Config: MachineConfig>(
+    is_r_type: bool,
+    funct3: u8,
+    funct7: u8,
+    rs2: u8,
+    operand_1: u32,
+    operand_2: u32,
+) -> Option<(&'static str, u32)> {
+    if Config::SUPPORT_ZBB == false && Config::SUPPORT_ZBKB == false {
+        return None;
+    }
+
+    let zbb = Config::SUPPORT_ZBB;
+    let zbb_or_zbkb = Config::SUPPORT_ZBB || Config::SUPPORT_ZBKB;
+    let zbkb = Config::SUPPORT_ZBKB;
+
+    let result = if is_r_type {
+        match (funct7, funct3) {
+            (ANDN_ORN_XNOR_FUNCT7, 0b111) if zbb_or_zbkb => ("ANDN", operand_1 & !operand_2),
+            (ANDN_ORN_XNOR_FUNCT7, 0b110) if zbb_or_zbkb => ("ORN", operand_1 | !operand_2),
+            (ANDN_ORN_XNOR_FUNCT7, 0b100) if zbb_or_zbkb => ("XNOR", !(operand_1 ^ operand_2)),
+            (MIN_MAX_FUNCT7, 0b100) if zbb => {
+                ("MIN", (operand_1 as i32).min(operand_2 as i32) as u32)
+            }
+            (MIN_MAX_FUNCT7, 0b101) if zbb => ("MINU", operand_1.min(operand_2)),
+            (MIN_MAX_FUNCT7, 0b110) if zbb => {
+                ("MAX", (operand_1 as i32).max(operand_2 as i32) as u32)
+            }
+            (MIN_MAX_FUNCT7, 0b111) if zbb => ("MAXU", operand_1.max(operand_2)),
+            // ZEXT.H from Zbb is PACK with rs2 = x0
+            (PACK_FUNCT7, 0b100) if zbkb || (zbb && rs2 == 0) => {
+                ("PACK", (operand_1 & 0xffff) | (operand_2 << 16))
+            }
+            (PACK_FUNCT7, 0b111) if zbkb => {
+                ("PACKH", (operand_1 & 0xff) | ((operand_2 & 0xff) << 8))
+            }
+            _ => return None,
+        }
+    } else {
+        match (funct3, funct7, rs2) {
+            (0b001, COUNT_AND_SEXT_FUNCT7, CLZ_RS2) if zbb => ("CLZ", operand_1.leading_zeros()),
+            (0b001, COUNT_AND_SEXT_FUNCT7, CTZ_RS2) if zbb => ("CTZ", operand_1.trailing_zeros()),
+            (0b001, COUNT_AND_SEXT_FUNCT7, CPOP_RS2) if zbb => ("CPOP", operand_1.count_ones()),
+            (0b001, COUNT_AND_SEXT_FUNCT7, SEXT_B_RS2) if zbb => {
+                ("SEXT.B", operand_1 as u8 as i8 as i32 as u32)
+            }
+            (0b001, COUNT_AND_SEXT_FUNCT7, SEXT_H_RS2) if zbb => {
+                ("SEXT.H", operand_1 as u16 as i16 as i32 as u32)
+            }
+            (0b101, REV8_BREV8_FUNCT7, REV8_RS2) if zbb_or_zbkb => ("REV8", operand_1.swap_bytes()),
+            (0b101, REV8_BREV8_FUNCT7, BREV8_AND_ORC_B_RS2) if zbkb => {
+                ("BREV8", operand_1.swap_bytes().reverse_bits())
+            }
+            (0b101, ORC_B_FUNCT7, BREV8_AND_ORC_B_RS2) if zbb => {
+                let bytes = operand_1
+                    .to_le_bytes()
+                    .map(|el| if el != 0 { 0xff } else { 0 });
+                ("ORC.B", u32::from_le_bytes(bytes))
+            }
+            _ => return None,
+        }
+    };
+
+    Some(result)
+}
@@ -1,5 +1,6 @@
 use std::hash::Hash;
 
+mod bitmanip;
 mod decoder_utils;
 pub mod opcode_formats;
 pub mod state;
@@ -29,6 +30,9 @@ pub trait MachineConfig:
     const SUPPORT_LOAD_LESS_THAN_WORD: bool;
     const SUPPORT_SRA: bool;
     const SUPPORT_ROT: bool;
+    const SUPPORT_ZBB: bool;
+    const SUPPORT_ZBKB: bool;
+    const SUPPORT_COMPRESSED: bool;
     const SUPPORT_MOPS: bool;
     const HANDLE_EXCEPTIONS: bool;
     const SUPPORT_STANDARD_CSRS: bool;
@@ -50,6 +54,9 @@ impl MachineConfig for IMStandardIsaConfig {
     const SUPPORT_LOAD_LESS_THAN_WORD: bool = true;
     const SUPPORT_SRA: bool = true;
     const SUPPORT_ROT: bool = false;
+    const SUPPORT_ZBB: bool = false;
+    const SUPPORT_ZBKB: bool = false;
+    const SUPPORT_COMPRESSED: bool = false;
     const SUPPORT_MOPS: bool = false;
     const HANDLE_EXCEPTIONS: bool = false;
     const SUPPORT_STANDARD_CSRS: bool = false;
@@ -78,6 +85,9 @@ impl MachineConfig for IMWithoutSignedMulDivIsaConfig {
     const SUPPORT_LOAD_LESS_THAN_WORD: bool = true;
     const SUPPORT_SRA: bool = true;
     const SUPPORT_ROT: bool = false;
+    const SUPPORT_ZBB: bool = false;
+    const SUPPORT_ZBKB: bool = false;
+    const SUPPORT_COMPRESSED: bool = false;
     const SUPPORT_MOPS: bool = false;
     const HANDLE_EXCEPTIONS: bool = false;
     const SUPPORT_STANDARD_CSRS: bool = false;
@@ -106,6 +116,9 @@ impl MachineConfig for IWithoutByteAccessIsaConfigWithDelegation {
     const SUPPORT_LOAD_LESS_THAN_WORD: bool = false;
     const SUPPORT_SRA: bool = true;
     const SUPPORT_ROT: bool = false;
+    const SUPPORT_ZBB: bool = false;
+    const SUPPORT_ZBKB: bool = false;
+    const SUPPORT_COMPRESSED: bool = false;
     const SUPPORT_MOPS: bool = true;
     const HANDLE_EXCEPTIONS: bool = false;
     const SUPPORT_STANDARD_CSRS: bool = false;
@@ -132,6 +145,9 @@ impl MachineConfig for IWithoutByteAccessIsaConfig {
     const SUPPORT_LOAD_LESS_THAN_WORD: bool = false;
     const SUPPORT_SRA: bool = true;
     const SUPPORT_ROT: bool = false;
+    const SUPPORT_ZBB: bool = false;
+    const SUPPORT_ZBKB: bool = false;
+    const SUPPORT_COMPRESSED: bool = false;
     const SUPPORT_MOPS: bool = true;
     const HANDLE_EXCEPTIONS: bool = false;
     const SUPPORT_STANDARD_CSRS: bool = false;
@@ -153,6 +169,9 @@ impl MachineConfig for IMIsaConfigWithAllDelegations {
     const SUPPORT_LOAD_LESS_THAN_WORD: bool = true;
     const SUPPORT_SRA: bool = true;
     const SUPPORT_ROT: bool = false;
+    const SUPPORT_ZBB: bool = false;
+    const SUPPORT_ZBKB: bool = false;
+    const SUPPORT_COMPRESSED: bool = false;
     const SUPPORT_MOPS: bool = true;
     const HANDLE_EXCEPTIONS: bool = false;
     const SUPPORT_STANDARD_CSRS: bool = false;
@@ -163,5 +182,111 @@ impl MachineConfig for IMIsaConfigWithAllDelegations {
     const ALLOWED_DELEGATION_CSRS: &'static [u32] = &[
         crate::delegations::blake2_round_function_with_compression_mode::BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID,
         crate::delegations::u256_ops_with_control::U256_OPS_WITH_CONTROL_ACCESS_ID,
+        crate::delegations::sha256_round_function::SHA256_ROUND_FUNCTION_ACCESS_ID,
+        crate::delegations::keccak_f1600_round_function::KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID,
+        crate::delegations::poseidon2_permutation::POSEIDON2_PERMUTATION_ACCESS_ID,
+    ];
+}
+
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize,
+)]
+pub struct IMWithBitmanipIsaConfigWithAllDelegations;
+
+impl MachineConfig for IMWithBitmanipIsaConfigWithAllDelegations {
+    const SUPPORT_MUL: bool = true;
+    const SUPPORT_DIV: bool = true;
+    const SUPPORT_SIGNED_MUL: bool = true;
+    const SUPPORT_SIGNED_DIV: bool = true;
+    const SUPPORT_SIGNED_LOAD: bool = true;
+    const SUPPORT_LOAD_LESS_THAN_WORD: bool = true;
+    const SUPPORT_SRA: bool = true;
+    const SUPPORT_ROT: bool = true;
+    const SUPPORT_ZBB: bool = true;
+    const SUPPORT_ZBKB: bool = true;
+    const SUPPORT_COMPRESSED: bool = false;
+    const SUPPORT_MOPS: bool = false;
+    const HANDLE_EXCEPTIONS: bool = false;
+    const SUPPORT_STANDARD_CSRS: bool = false;
+    const SUPPORT_ONLY_CSRRW: bool = true;
+    #[cfg(not(feature = "delegation"))]
+    const ALLOWED_DELEGATION_CSRS: &'static [u32] = &[];
+    #[cfg(feature = "delegation")]
+    const ALLOWED_DELEGATION_CSRS: &'static [u32] = &[
+        crate::delegations::blake2_round_function_with_compression_mode::BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID,
+        crate::delegations::u256_ops_with_control::U256_OPS_WITH_CONTROL_ACCESS_ID,
+        crate::delegations::sha256_round_function::SHA256_ROUND_FUNCTION_ACCESS_ID,
+        crate::delegations::keccak_f1600_round_function::KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID,
+        crate::delegations::poseidon2_permutation::POSEIDON2_PERMUTATION_ACCESS_ID,
+    ];
+}
+
+/// Experimental RV32C machine. It can be simulated, but there is no circuit to prove it yet
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize,
+)]
+pub struct IMWithCompressedIsaConfigWithAllDelegations;
+
+impl MachineConfig for IMWithCompressedIsaConfigWithAllDelegations {
+    const SUPPORT_MUL: bool = true;
+    const SUPPORT_DIV: bool = true;
+    const SUPPORT_SIGNED_MUL: bool = true;
+    const SUPPORT_SIGNED_DIV: bool = true;
+    const SUPPORT_SIGNED_LOAD: bool = true;
+    const SUPPORT_LOAD_LESS_THAN_WORD: bool = true;
+    const SUPPORT_SRA: bool = true;
+    const SUPPORT_ROT: bool = false;
+    const SUPPORT_ZBB: bool = false;
+    const SUPPORT_ZBKB: bool = false;
+    const SUPPORT_COMPRESSED: bool = true;
+    const SUPPORT_MOPS: bool = false;
+    const HANDLE_EXCEPTIONS: bool = false;
+    const SUPPORT_STANDARD_CSRS: bool = false;
+    const SUPPORT_ONLY_CSRRW: bool = true;
+    #[cfg(not(feature = "delegation"))]
+    const ALLOWED_DELEGATION_CSRS: &'static [u32] = &[];
+    #[cfg(feature = "delegation")]
+    const ALLOWED_DELEGATION_CSRS: &'static [u32] = &[
+        crate::delegations::blake2_round_function_with_compression_mode::BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID,
+        crate::delegations::u256_ops_with_control::U256_OPS_WITH_CONTROL_ACCESS_ID,
+        crate::delegations::sha256_round_function::SHA256_ROUND_FUNCTION_ACCESS_ID,
+        crate::delegations::keccak_f1600_round_function::KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID,
+        crate::delegations::poseidon2_permutation::POSEIDON2_PERMUTATION_ACCESS_ID,
+    ];
+}
+
+/// Machine that doesn't assume trusted code: illegal instructions, misaligned jumps and memory accesses,
+/// ECALL and EBREAK vector to `mtvec`, and MRET returns to `mepc`. Only `mtvec`, `mepc`, `mcause` and `mtval`
+/// are supported in addition to non-determinism and delegation CSRs, and access to any other CSR is an illegal instruction
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize,
+)]
+pub struct IMWithTrapHandlingIsaConfigWithAllDelegations;
+
+impl MachineConfig for IMWithTrapHandlingIsaConfigWithAllDelegations {
+    const SUPPORT_MUL: bool = true;
+    const SUPPORT_DIV: bool = true;
+    const SUPPORT_SIGNED_MUL: bool = true;
+    const SUPPORT_SIGNED_DIV: bool = true;
+    const SUPPORT_SIGNED_LOAD: bool = true;
+    const SUPPORT_LOAD_LESS_THAN_WORD: bool = true;
+    const SUPPORT_SRA: bool = true;
+    const SUPPORT_ROT: bool = false;
+    const SUPPORT_ZBB: bool = false;
+    const SUPPORT_ZBKB: bool = false;
+    const SUPPORT_COMPRESSED: bool = false;
+    const SUPPORT_MOPS: bool = false;
+    const HANDLE_EXCEPTIONS: bool = true;
+    const SUPPORT_STANDARD_CSRS: bool = false;
+    const SUPPORT_ONLY_CSRRW: bool = true;
+    #[cfg(not(feature = "delegation"))]
+    const ALLOWED_DELEGATION_CSRS: &'static [u32] = &[];
+    #[cfg(feature = "delegation")]
+    const ALLOWED_DELEGATION_CSRS: &'static [u32] = &[
+        crate::delegations::blake2_round_function_with_compression_mode::BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID,
+        crate::delegations::u256_ops_with_control::U256_OPS_WITH_CONTROL_ACCESS_ID,
+        crate::delegations::sha256_round_function::SHA256_ROUND_FUNCTION_ACCESS_ID,
+        crate::delegations::keccak_f1600_round_function::KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID,
+        crate::delegations::poseidon2_permutation::POSEIDON2_PERMUTATION_ACCESS_ID,
     ];
 }
@@ -25,3 +25,9 @@ pub use self::j_type::JTypeOpcode;
 pub use self::r_type::RTypeOpcode;
 pub use self::s_type::STypeOpcode;
 pub use self::u_type::UTypeOpcode;
+
+// RV32C instructions are executed by expanding them into 32-bit equivalents
+pub use cs::definitions::{
+    expand_compressed_instruction, is_compressed_instruction, COMPRESSED_INSTRUCTION_LEN,
+    FULL_INSTRUCTION_LEN,
+};
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 use std::hint::unreachable_unchecked;
 
+use super::bitmanip::bitmanip_op;
+use super::utils::pc_alignment_mask;
 use super::{status_registers::*, MachineConfig};
-use crate::abstractions::csr_processor::CustomCSRProcessor;
+use crate::abstractions::csr_processor::{CustomCSRProcessor, DelegationAbiViolation};
 use crate::abstractions::memory::{AccessType, MemorySource};
 use crate::abstractions::non_determinism::NonDeterminismCSRSource;
 use crate::abstractions::tracer::Tracer;
@@ -23,6 +25,25 @@ pub const MAX_MEMORY_OPS_PER_CYCLE: u32 = 3;
 pub const NON_DETERMINISM_CSR: u32 = 0x7c0;
 pub const MARKER_CSR: u32 = 0x7ff;
 
+/// Reason why the machine can not continue execution
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CycleError {
+    // Only for machines that do not handle exceptions
+    Trap { cause: TrapReason, pc: u32 },
+    DelegationAbiViolation(DelegationAbiViolation),
+}
+
+impl std::fmt::Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Trap { cause, pc } => write!(f, "trap {:?} at PC = 0x{:08x}", cause, pc),
+            Self::DelegationAbiViolation(violation) => write!(f, "{}", violation),
+        }
+    }
+}
+
+impl std::error::Error for CycleError {}
+
 // static CSR_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
 
 #[cfg(feature = "opcode_stats")]
@@ -32,10 +53,14 @@ thread_local! {
 }
 
 #[inline(always)]
-pub(crate) fn report_opcode(_opcode: &'static str) {
+pub(crate) fn report_opcode<C: MachineConfig, TR: Tracer<C>>(
+    tracer: &mut TR,
+    opcode: &'static str,
+) {
+    tracer.trace_opcode(opcode);
     #[cfg(feature = "opcode_stats")]
     OPCODES_COUNTER.with_borrow_mut(|el| {
-        *el.entry(_opcode).or_default() += 1;
+        *el.entry(opcode).or_default() += 1;
     });
 }
 
@@ -422,27 +447,56 @@ impl<Config: MachineConfig> RiscV32State<Config> {
         mmu: &'a mut MMU,
         non_determinism_source: &mut ND,
     ) {
+        if let Err(error) = self.try_cycle(memory_source, tracer, mmu, non_determinism_source) {
+            panic!("Simulator encountered an exception: {}", error);
+        }
+    }
+
+    pub fn try_cycle<
+        'a,
+        M: MemorySource,
+        TR: Tracer<Config>,
+        ND: NonDeterminismCSRSource<M>,
+        MMU: MMUImplementation<M, TR, Config>,
+    >(
+        &'a mut self,
+        memory_source: &'a mut M,
+        tracer: &'a mut TR,
+        mmu: &'a mut MMU,
+        non_determinism_source: &mut ND,
+    ) -> Result<(), CycleError> {
         #[cfg(not(feature = "delegation"))]
         {
             use crate::abstractions::csr_processor::NoExtraCSRs;
-            self.cycle_ext(
+            self.try_cycle_ext(
                 memory_source,
                 tracer,
                 mmu,
                 non_determinism_source,
                 &mut NoExtraCSRs,
-            );
+            )
         }
         #[cfg(feature = "delegation")]
         {
             use crate::delegations::DelegationsCSRProcessor;
-            self.cycle_ext(
+            self.try_cycle_ext(
                 memory_source,
                 tracer,
                 mmu,
                 non_determinism_source,
                 &mut DelegationsCSRProcessor,
-            );
+            )
+        }
+    }
+
+    #[cold]
+    fn unsupported_delegation(csr: u32) -> DelegationAbiViolation {
+        DelegationAbiViolation {
+            id: csr,
+            reason: format!(
+                "machine {:?} is not configured to support this delegation",
+                Config::default()
+            ),
         }
     }
 
@@ -479,11 +533,39 @@ impl<Config: MachineConfig> RiscV32State<Config> {
         non_determinism_source: &mut ND,
         csr_processor: &mut CSR,
     ) {
+        if let Err(error) = self.try_cycle_ext(
+            memory_source,
+            tracer,
+            mmu,
+            non_determinism_source,
+            csr_processor,
+        ) {
+            panic!("Simulator encountered an exception: {}", error);
+        }
+    }
+
+    /// Performs a single cycle. If the machine can not continue (trap on the machine that doesn't
+    /// handle exceptions, or invalid delegation call), the state is left at the failing instruction.
+    pub fn try_cycle_ext<
+        'a,
+        M: MemorySource,
+        TR: Tracer<Config>,
+        ND: NonDeterminismCSRSource<M>,
+        MMU: MMUImplementation<M, TR, Config>,
+        CSR: CustomCSRProcessor,
+    >(
+        &'a mut self,
+        memory_source: &'a mut M,
+        tracer: &'a mut TR,
+        mmu: &'a mut MMU,
+        non_determinism_source: &mut ND,
+        csr_processor: &mut CSR,
+    ) -> Result<(), CycleError> {
         tracer.at_cycle_start(&*self);
 
         if self.extra_flags.get_wait_for_interrupt() != 0 {
             tracer.at_cycle_end(&*self);
-            return;
+            return Ok(());
         }
 
         let current_privilege_mode = self.extra_flags.get_current_mode();
@@ -492,6 +574,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
         let mut ret_val: u32 = 0;
         let mut trap = TrapReason::NoTrap;
         let mut instr: u32 = 0;
+        let mut instruction_len = FULL_INSTRUCTION_LEN;
 
         'cycle_block: {
             // normal cycle
@@ -510,14 +593,70 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                 break 'cycle_block;
             }
 
-            instr = mem_read::<_, _, _>(
-                memory_source,
-                tracer,
-                instruction_phys_address,
-                4,
-                AccessType::Instruction,
-                &mut trap,
-            );
+            if Config::SUPPORT_COMPRESSED {
+                // PC is only 2 byte aligned, so we read the full word and take a half-word from it
+                let word = mem_read::<_, _, _>(
+                    memory_source,
+                    tracer,
+                    instruction_phys_address & !3,
+                    4,
+                    AccessType::Instruction,
+                    &mut trap,
+                );
+                let low_half = if instruction_phys_address & 2 == 0 {
+                    word & 0xffff
+                } else {
+                    word >> 16
+                };
+
+                if trap.is_a_trap() {
+                    // access fault is processed below in the same way as for non-compressed fetch
+                } else if is_compressed_instruction(low_half) {
+                    instruction_len = COMPRESSED_INSTRUCTION_LEN;
+                    if let Some(expanded) = expand_compressed_instruction(low_half as u16) {
+                        instr = expanded;
+                    } else {
+                        instr = low_half;
+                        trap = TrapReason::IllegalInstruction;
+                        break 'cycle_block;
+                    }
+                } else if instruction_phys_address & 2 == 0 {
+                    instr = word;
+                } else {
+                    // 32-bit instruction crosses the word boundary, and potentially the page boundary,
+                    // so we translate the address of the second half separately
+                    let high_half_phys_address = mmu.map_virtual_to_physical(
+                        pc.wrapping_add(2),
+                        current_privilege_mode,
+                        AccessType::Instruction,
+                        memory_source,
+                        tracer,
+                        &mut trap,
+                    );
+                    if trap.is_a_trap() {
+                        debug_assert_eq!(trap, TrapReason::InstructionPageFault);
+                        break 'cycle_block;
+                    }
+                    let next_word = mem_read::<_, _, _>(
+                        memory_source,
+                        tracer,
+                        high_half_phys_address,
+                        4,
+                        AccessType::Instruction,
+                        &mut trap,
+                    );
+                    instr = low_half | (next_word << 16);
+                }
+            } else {
+                instr = mem_read::<_, _, _>(
+                    memory_source,
+                    tracer,
+                    instruction_phys_address,
+                    4,
+                    AccessType::Instruction,
+                    &mut trap,
+                );
+            }
 
             if trap.is_a_trap() {
                 // error during address translation
@@ -545,35 +684,35 @@ impl<Config: MachineConfig> RiscV32State<Config> {
             let rs2 = self.get_second_register(formal_rs2, tracer);
 
             // note on all the PC operations below: if we modify PC in the opcode,
-            // we subtract 4 from it, to later on add 4 once at the end of the loop. For MOST
-            // of the opcodes it makes sense to shorten the opcode body
+            // we subtract instruction length (4, or 2 for compressed instructions) from it, to later on
+            // add it once at the end of the loop. For MOST of the opcodes it makes sense to shorten the opcode body
             const LOWEST_7_BITS_MASK: u32 = 0x7f;
 
             match instr & LOWEST_7_BITS_MASK {
                 0b0110111 => {
-                    report_opcode("LUI");
+                    report_opcode(tracer, "LUI");
                     // LUI
                     let imm = UTypeOpcode::imm(instr);
 
                     ret_val = imm;
                 },
                 0b0010111 => {
-                    report_opcode("AUIPC");
+                    report_opcode(tracer, "AUIPC");
                     // AUIPC
                     let imm = UTypeOpcode::imm(instr);
 
                     ret_val = pc.wrapping_add(imm);
                 },
                 0b1101111 => {
-                    report_opcode("JAL");
+                    report_opcode(tracer, "JAL");
                     // JAL
                     let mut rel_addr: u32 = JTypeOpcode::imm(instr);
                     // quasi-sign-extend
                     sign_extend(&mut rel_addr, 21);
-                    ret_val = pc.wrapping_add(4u32);
-                    let jmp_addr = pc.wrapping_sub(4u32).wrapping_add(rel_addr);
+                    ret_val = pc.wrapping_add(instruction_len);
+                    let jmp_addr = pc.wrapping_sub(instruction_len).wrapping_add(rel_addr);
 
-                    if jmp_addr & 0x3 != 0 {
+                    if jmp_addr & pc_alignment_mask::<Config>() != 0 {
                         // unaligned PC
                         trap = TrapReason::InstructionAddressMisaligned;
                         break 'cycle_block;
@@ -582,18 +721,18 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                     }
                 },
                 0b1100111 => {
-                    report_opcode("JALR");
+                    report_opcode(tracer, "JALR");
                     // JALR
                     let mut imm: u32 = ITypeOpcode::imm(instr);
                     // quasi sign extend
                     sign_extend(&mut imm, 12);
 
-                    ret_val = pc.wrapping_add(4u32);
+                    ret_val = pc.wrapping_add(instruction_len);
                     //  The target address is obtained by adding the 12-bit signed I-immediate
                     // to the register rs1, then setting the least-significant bit of the result to zero
-                    let jmp_addr = (rs1.wrapping_add(imm) & !0x1).wrapping_sub(4u32);
+                    let jmp_addr = (rs1.wrapping_add(imm) & !0x1).wrapping_sub(instruction_len);
 
-                    if jmp_addr & 0x3 != 0 {
+                    if jmp_addr & pc_alignment_mask::<Config>() != 0 {
                         // unaligned PC
                         trap = TrapReason::InstructionAddressMisaligned;
                         break 'cycle_block;
@@ -602,13 +741,13 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                     }
                 }
                 0b1100011 => {
-                    report_opcode("BRANCH");
+                    report_opcode(tracer, "BRANCH");
                     // BRANCH
                     let mut imm = BTypeOpcode::imm(instr);
                     sign_extend(&mut imm, 13);
 
                     rd = 0;
-                    let dst = pc.wrapping_add(imm).wrapping_sub(4u32);
+                    let dst = pc.wrapping_add(imm).wrapping_sub(instruction_len);
                     let funct3 = BTypeOpcode::funct3(instr);
 
                     let should_jump = match funct3 {
@@ -625,7 +764,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                     };
 
                     if should_jump {
-                        if dst & 0x3 != 0 {
+                        if dst & pc_alignment_mask::<Config>() != 0 {
                             // unaligned PC
                             trap = TrapReason::InstructionAddressMisaligned;
                             break 'cycle_block;
@@ -688,23 +827,23 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 // now depending on the type of load we extend it
                                 ret_val = match a {
                                     0 => {
-                                        report_opcode("LB");
+                                        report_opcode(tracer, "LB");
                                         sign_extend_8(operand)
                                     },
                                     1 => {
-                                        report_opcode("LH");
+                                        report_opcode(tracer, "LH");
                                         sign_extend_16(operand)
                                     },
                                     2 => {
-                                        report_opcode("LW");
+                                        report_opcode(tracer, "LW");
                                         operand
                                     },
                                     4 => {
-                                        report_opcode("LB");
+                                        report_opcode(tracer, "LBU");
                                         zero_extend_8(operand)
                                     },
                                     5 => {
-                                        report_opcode("LH");
+                                        report_opcode(tracer, "LHU");
                                         zero_extend_16(operand)
                                     },
                                     _ => unsafe {unreachable_unchecked()}
@@ -721,15 +860,15 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                         break 'cycle_block;
                                     },
                                     2 => {
-                                        report_opcode("LW");
+                                        report_opcode(tracer, "LW");
                                         operand
                                     },
                                     4 => {
-                                        report_opcode("LB");
+                                        report_opcode(tracer, "LBU");
                                         zero_extend_8(operand)
                                     },
                                     5 => {
-                                        report_opcode("LH");
+                                        report_opcode(tracer, "LHU");
                                         zero_extend_16(operand)
                                     },
                                     _ => unsafe {unreachable_unchecked()}
@@ -776,13 +915,13 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                             {
                                 match store_length {
                                     1 => {
-                                        report_opcode("SB");
+                                        report_opcode(tracer, "SB");
                                     },
                                     2 => {
-                                        report_opcode("SH");
+                                        report_opcode(tracer, "SH");
                                     },
                                     4 => {
-                                        report_opcode("SW");
+                                        report_opcode(tracer, "SW");
                                     },
                                     _ => {
                                         unreachable!()
@@ -828,7 +967,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                         // RV32M - multiplication subset
                         ret_val = match funct3 {
                             0 => {
-                                report_opcode("MUL");
+                                report_opcode(tracer, "MUL");
                                 // MUL
                                 if Config::SUPPORT_MUL {
                                     (operand_1 as i32).wrapping_mul(operand_2 as i32) as u32
@@ -838,7 +977,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 }
                             },
                             1 => {
-                                report_opcode("MULH");
+                                report_opcode(tracer, "MULH");
                                 // MULH
                                 if Config::SUPPORT_MUL && Config::SUPPORT_SIGNED_MUL {
                                     (((operand_1 as i32) as i64).wrapping_mul((operand_2 as i32) as i64) >> 32) as u32
@@ -848,7 +987,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 }
                             },
                             2 => {
-                                report_opcode("MULSU");
+                                report_opcode(tracer, "MULSU");
                                 // MULHSU
                                 if Config::SUPPORT_MUL && Config::SUPPORT_SIGNED_MUL {
                                     (((operand_1 as i32) as i64).wrapping_mul(((operand_2 as u32) as u64) as i64) >> 32) as u32
@@ -858,7 +997,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 }
                             },
                             3 => {
-                                report_opcode("MULHU");
+                                report_opcode(tracer, "MULHU");
                                 // MULHU
                                 if Config::SUPPORT_MUL {
                                     ((operand_1 as u64).wrapping_mul(operand_2 as u64) >> 32) as u32
@@ -868,7 +1007,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 }
                             },
                             4 => {
-                                report_opcode("DIV");
+                                report_opcode(tracer, "DIV");
                                 // DIV
                                 if Config::SUPPORT_DIV && Config::SUPPORT_SIGNED_DIV {
                                     if operand_2 == 0 {
@@ -886,7 +1025,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 }
                             },
                             5 => {
-                                report_opcode("DIVU");
+                                report_opcode(tracer, "DIVU");
                                 // DIVU
                                 if Config::SUPPORT_DIV {
                                     if operand_2 == 0 {
@@ -900,7 +1039,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 }
                             },
                             6 => {
-                                report_opcode("REM");
+                                report_opcode(tracer, "REM");
                                 // REM
                                 if Config::SUPPORT_DIV && Config::SUPPORT_SIGNED_DIV {
                                     if operand_2 == 0 {
@@ -918,7 +1057,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 }
                             },
                             7 => {
-                                report_opcode("REMU");
+                                report_opcode(tracer, "REMU");
                                 // REMU
                                 if Config::SUPPORT_DIV {
                                     if operand_2 == 0 {
@@ -935,6 +1074,17 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 unreachable_unchecked()
                             },
                         };
+                    } else if let Some((opcode_name, value)) = bitmanip_op::<Config>(
+                        is_r_type,
+                        funct3 as u8,
+                        funct7 as u8,
+                        RTypeOpcode::rs2(instr) as u8,
+                        operand_1,
+                        operand_2,
+                    ) {
+                        // Zbb/Zbkb subsets, if supported
+                        report_opcode(opcode_name);
+                        ret_val = value;
                     } else {
                         // basic set
                         const ARITHMETIC_SHIFT_RIGHT_TEST_MASK: u32 = 0x40000000;
@@ -943,16 +1093,16 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                         ret_val = match funct3 {
                             0 => {
                                 if is_r_type && instr & SUB_TEST_MASK != 0 {
-                                    report_opcode("SUB");
+                                    report_opcode(tracer, "SUB");
                                     operand_1.wrapping_sub(operand_2)
                                 } else {
-                                    report_opcode("ADD");
+                                    report_opcode(tracer, "ADD");
                                     operand_1.wrapping_add(operand_2)
                                 }
                             },
                             1 => {
                                 if instr & ROTATE_MASK != 0 {
-                                    report_opcode("ROL");
+                                    report_opcode(tracer, "ROL");
                                     if Config::SUPPORT_ROT {
                                         operand_1.rotate_left(operand_2 & 0x1f)
                                     } else {
@@ -960,30 +1110,30 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                         break 'cycle_block;
                                     }
                                 } else {
-                                    report_opcode("SLL");
+                                    report_opcode(tracer, "SLL");
                                     // Shift left
                                     // shift is encoded in lowest 5 bits
                                     operand_1 << (operand_2 & 0x1f)
                                 }
                             },
                             2 => {
-                                report_opcode("LT");
+                                report_opcode(tracer, "LT");
                                 // Signed LT
                                 ((operand_1 as i32) < (operand_2 as i32)) as u32
                             },
                             3 => {
-                                report_opcode("LTU");
+                                report_opcode(tracer, "LTU");
                                 // Unsigned LT
                                 (operand_1 < operand_2) as u32
                             },
                             4 => {
-                                report_opcode("XOR");
+                                report_opcode(tracer, "XOR");
                                 // XOR
                                 operand_1 ^ operand_2
                             },
                             5 => {
                                 if instr & ROTATE_MASK == ROTATE_MASK {
-                                    report_opcode("ROR");
+                                    report_opcode(tracer, "ROR");
                                     if Config::SUPPORT_ROT {
                                         operand_1.rotate_right(operand_2 & 0x1f)
                                     } else {
@@ -992,7 +1142,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                     }
                                 } else {
                                     if instr & ARITHMETIC_SHIFT_RIGHT_TEST_MASK != 0 {
-                                        report_opcode("SRA");
+                                        report_opcode(tracer, "SRA");
                                         // Arithmetic shift right
                                         // shift is encoded in lowest 5 bits
 
@@ -1003,18 +1153,18 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                             break 'cycle_block;
                                         }
                                     } else {
-                                        report_opcode("SRL");
+                                        report_opcode(tracer, "SRL");
                                         operand_1  >> (operand_2 & 0x1f)
                                     }
                                 }
                             },
                             6 => {
-                                report_opcode("OR");
+                                report_opcode(tracer, "OR");
                                 // OR
                                 operand_1 | operand_2
                             },
                             7 => {
-                                report_opcode("AND");
+                                report_opcode(tracer, "AND");
                                 // AND
                                 operand_1 & operand_2
                             },
@@ -1027,15 +1177,18 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                             // the only invalid encodings are in case of SLLI, SRLI, SRAI which require
                             // predetermined funct7
 
-                            // Also allow ROL/ROR/RORI
+                            // Also allow RORI
                             match funct3 {
-                                1 => funct7 == 0 || funct7 == 0b0110000,
+                                1 => funct7 == 0,
                                 5 => funct7 == 0 || funct7 == 0b0100000 || funct7 == 0b0110000,
                                 _ => true
                             }
                         } else {
+                            // Also allow ROL/ROR
                             match funct3 {
-                                0 | 5 => funct7 == 0 || funct7 == 0b0100000,
+                                0 => funct7 == 0 || funct7 == 0b0100000,
+                                1 => funct7 == 0 || funct7 == 0b0110000,
+                                5 => funct7 == 0 || funct7 == 0b0100000 || funct7 == 0b0110000,
                                 _ => funct7 == 0,
                             }
                         };
@@ -1060,7 +1213,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
 
                     // so now we can just use full integer values for csr numbers
                     if funct3 & ZICSR_MASK != 0 {
-                        report_opcode("CSR");
+                        report_opcode(tracer, "CSR");
                         let csr_privilege_mode = get_bits_and_align_right(csr_number, 8, 2);
                         let csr_privilege_mode = Mode::from_proper_bit_value(csr_privilege_mode);
                         if csr_privilege_mode.as_register_value() > current_privilege_mode.as_register_value() {
@@ -1096,6 +1249,15 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                 MARKER_CSR => {
                                   // Do nothing here, we do the work in the write case
                                 }
+                                // machines that handle exceptions only have trap related CSRs
+                                0x305 if Config::HANDLE_EXCEPTIONS => ret_val = self.machine_mode_trap_data.setup.tvec, // mtvec
+                                0x341 if Config::HANDLE_EXCEPTIONS => ret_val = self.machine_mode_trap_data.handling.epc, // mepc
+                                0x342 if Config::HANDLE_EXCEPTIONS => ret_val = self.machine_mode_trap_data.handling.cause, // mcause
+                                0x343 if Config::HANDLE_EXCEPTIONS => ret_val = self.machine_mode_trap_data.handling.tval, // mtval
+                                csr if Config::HANDLE_EXCEPTIONS && !Config::ALLOWED_DELEGATION_CSRS.contains(&csr) => {
+                                    trap = TrapReason::IllegalInstruction;
+                                    break 'cycle_block;
+                                }
                                 csr => {
                                     assert!(Config::ALLOWED_DELEGATION_CSRS.contains(&csr), "Machine {:?} is not configured to support CSR number {} at pc 0x{:08x}", Config::default(), csr, pc);
                                     // println!("Custom CSR = 0x{:04x} READ at cycle {}", csr_number, proc_cycle);
@@ -1142,20 +1304,28 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                             // do nothing
                                         } else {
                                             non_determinism_source.write_with_memory_access(&*memory_source, write_val);
+                                            tracer.trace_non_determinism_write(write_val);
                                         }
                                     } else {
                                         non_determinism_source.write_with_memory_access(&*memory_source, write_val);
+                                        tracer.trace_non_determinism_write(write_val);
                                     }
                                 }
                                 MARKER_CSR => {
                                   self.add_marker()
                                 }
+                                0x305 if Config::HANDLE_EXCEPTIONS => self.machine_mode_trap_data.setup.tvec = write_val, // mtvec
+                                0x341 if Config::HANDLE_EXCEPTIONS => self.machine_mode_trap_data.handling.epc = write_val, // mepc
+                                0x342 if Config::HANDLE_EXCEPTIONS => self.machine_mode_trap_data.handling.cause = write_val, // mcause
+                                0x343 if Config::HANDLE_EXCEPTIONS => self.machine_mode_trap_data.handling.tval = write_val, // mtval
                                 csr => {
-                                    assert!(Config::ALLOWED_DELEGATION_CSRS.contains(&csr), "Machine {:?} is not configured to support CSR number {}", Config::default(), csr);
+                                    if !Config::ALLOWED_DELEGATION_CSRS.contains(&csr) {
+                                        return Err(CycleError::DelegationAbiViolation(Self::unsupported_delegation(csr)));
+                                    }
                                     Self::add_delegation(csr);
                                     // let t = CSR_COUNTER.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
                                     // println!("Custom CSR = 0x{:04x} WRITE at cycle {}, total: {}", csr_number, proc_cycle, t + 1);
-                                    csr_processor.process_write(self, memory_source, non_determinism_source, tracer, mmu, csr_number, rs1, rs1_as_imm, &mut trap);
+                                    csr_processor.process_write(self, memory_source, non_determinism_source, tracer, mmu, csr_number, rs1, rs1_as_imm, &mut trap).map_err(CycleError::DelegationAbiViolation)?;
                                     if trap.is_a_trap() {
                                         break 'cycle_block;
                                     }
@@ -1266,19 +1436,23 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                                             // do nothing
                                         } else {
                                             non_determinism_source.write_with_memory_access(&*memory_source, write_val);
+                                            tracer.trace_non_determinism_write(write_val);
                                         }
                                     } else {
                                         non_determinism_source.write_with_memory_access(&*memory_source, write_val);
+                                        tracer.trace_non_determinism_write(write_val);
                                     }
                                 }
                                 MARKER_CSR => {
                                   self.add_marker()
                                 }
                                 csr => {
-                                    assert!(Config::ALLOWED_DELEGATION_CSRS.contains(&csr), "Machine {:?} is not configured to support CSR number {}", Config::default(), csr);
+                                    if !Config::ALLOWED_DELEGATION_CSRS.contains(&csr) {
+                                        return Err(CycleError::DelegationAbiViolation(Self::unsupported_delegation(csr)));
+                                    }
                                     // let t = CSR_COUNTER.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
                                     Self::add_delegation(csr);
-                                    csr_processor.process_write(self, memory_source, non_determinism_source, tracer, mmu, csr_number, rs1, rs1_as_imm, &mut trap);
+                                    csr_processor.process_write(self, memory_source, non_determinism_source, tracer, mmu, csr_number, rs1, rs1_as_imm, &mut trap).map_err(CycleError::DelegationAbiViolation)?;
                                     if trap.is_a_trap() {
                                         break 'cycle_block;
                                     }
@@ -1287,62 +1461,44 @@ impl<Config: MachineConfig> RiscV32State<Config> {
                         }
                         // and writeback
                     } else if funct3 == 0b000 {
-                        // TODO: add to configuration later
-                        trap = TrapReason::IllegalInstruction;
-                        break 'cycle_block;
+                        if Config::HANDLE_EXCEPTIONS == false {
+                            trap = TrapReason::IllegalInstruction;
+                            break 'cycle_block;
+                        }
 
-                        // // SYSTEM
-                        // rd = 0;
-                        // // mainly we support WFI, MRET, ECALL and EBREAK
-                        // if csr_number == 0x105 {
-                        //     println!("WFI: proc_cycle: {:?}, pc = {}, opcode = 0x{:08x}", proc_cycle, pc, instr);
-                        //     self.extra_flags.set_wait_for_interrupt_bit();
-                        //     self.pc = pc.wrapping_add(4u32);
-                        //     return;
-                        // } else if csr_number == 0x302 {
-                        //     // MRET
-                        //     let existing_mstatus = self.machine_mode_trap_data.state.status;
-                        //     let previous_privilege = MStatusRegister::mpp(existing_mstatus);
-                        //     // MRET then in mstatus/mstatush sets MPV=0, MPP=0,
-                        //     // MIE=MPIE, and MPIE=1. Lastly, MRET sets the privilege mode as previously determined, and
-                        //     // sets pc=mepc.
-                        //     MStatusRegister::clear_mpp(&mut self.machine_mode_trap_data.state.status);
-                        //     MStatusRegister::clear_mprv(&mut self.machine_mode_trap_data.state.status);
-                        //     let mpie = MStatusRegister::mpie_aligned_bit(self.machine_mode_trap_data.state.status);
-                        //     MStatusRegister::set_mie_to_value(&mut self.machine_mode_trap_data.state.status, mpie);
-                        //     MStatusRegister::set_mpie(&mut self.machine_mode_trap_data.state.status);
-
-                        //     // set privilege
-                        //     self.extra_flags.set_mode_raw(Mode::User.as_register_value() | previous_privilege);
-                        //     pc = self.machine_mode_trap_data.handling.epc.wrapping_sub(4u32);
-                        // } else {
-                        //     match csr_number {
-                        //         0 => {
-                        //             // ECALL
-                        //             trap = match current_privilege_mode {
-                        //                 Mode::Machine => TrapReason::EnvironmentCallFromMMode,
-                        //                 Mode::User => TrapReason::EnvironmentCallFromUMode,
-                        //                 _ => TrapReason::IllegalInstruction,
-                        //             };
-
-                        //             break 'cycle_block;
-                        //         },
-                        //         1 => {
-                        //             // EBREAK
-                        //             trap = TrapReason::Breakpoint;
-                        //             break 'cycle_block;
-                        //         },
-                        //         _ => {
-                        //             trap = TrapReason::IllegalInstruction;
-                        //             break 'cycle_block;
-                        //         }
-                        //     }
-                        // }
+                        // SYSTEM, we support MRET, ECALL and EBREAK in machine mode only
+                        report_opcode(tracer, "SYSTEM");
+                        rd = 0;
+                        match csr_number {
+                            0x302 => {
+                                // MRET. There is no other privilege mode to return to, so we only restore MIE from MPIE and set MPIE
+                                let mpie = MStatusRegister::mpie_aligned_bit(self.machine_mode_trap_data.state.status);
+                                MStatusRegister::set_mie_to_value(&mut self.machine_mode_trap_data.state.status, mpie);
+                                MStatusRegister::set_mpie(&mut self.machine_mode_trap_data.state.status);
+
+                                // PC is advanced by instruction length below
+                                pc = self.machine_mode_trap_data.handling.epc.wrapping_sub(instruction_len);
+                            },
+                            0 => {
+                                // ECALL
+                                trap = TrapReason::EnvironmentCallFromMMode;
+                                break 'cycle_block;
+                            },
+                            1 => {
+                                // EBREAK
+                                trap = TrapReason::Breakpoint;
+                                break 'cycle_block;
+                            },
+                            _ => {
+                                trap = TrapReason::IllegalInstruction;
+                                break 'cycle_block;
+                            }
+                        }
                     } else if funct3 & ZIMOP_MASK == ZIMOP_MASK {
                         const MOP_FUNCT7_TEST: u32 = 0b1000001u32;
                         let funct7 = RTypeOpcode::funct7(instr);
                         if funct7 & MOP_FUNCT7_TEST == MOP_FUNCT7_TEST {
-                            report_opcode("MOP");
+                            report_opcode(tracer, "MOP");
                             if Config::SUPPORT_MOPS {
                                 use field::{Field, Mersenne31Field};
 
@@ -1398,7 +1554,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
             self.set_register(rd, ret_val, tracer);
 
             // traps below will update PC themself, so it only happens if we have NO trap
-            pc = pc.wrapping_add(4u32);
+            pc = pc.wrapping_add(instruction_len);
         }
 
         // Handle traps and interrupts.
@@ -1406,7 +1562,7 @@ impl<Config: MachineConfig> RiscV32State<Config> {
             println!("trap: {:?}, pc: {:08x}, instr: {:08x}", trap, pc, instr);
 
             if Config::HANDLE_EXCEPTIONS == false {
-                panic!("Simulator encountered an exception");
+                return Err(CycleError::Trap { cause: trap, pc });
             } else {
                 let trap = trap.as_register_value();
                 if trap & INTERRUPT_MASK != 0 {
@@ -1451,6 +1607,8 @@ impl<Config: MachineConfig> RiscV32State<Config> {
 
         //let trap = trap.as_register_value();
         //println!("end of cycle: PC = 0x{:08x}, trap = 0x{:08x}, interrupt = {:?}", self.pc, trap, trap & INTERRUPT_MASK != 0);
+
+        Ok(())
     }
 
     pub fn pretty_dump(&self) {
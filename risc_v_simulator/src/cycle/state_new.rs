@@ -11,6 +11,7 @@ use crate::abstractions::csr_processor::NoExtraCSRs;
 use crate::abstractions::memory::{AccessType, MemorySource};
 use crate::abstractions::non_determinism::NonDeterminismCSRSource;
 use crate::abstractions::tracer::Tracer;
+use crate::cycle::bitmanip::bitmanip_op;
 use crate::cycle::state::report_opcode;
 use crate::cycle::state::MARKER_CSR;
 use crate::cycle::state::NON_DETERMINISM_CSR;
@@ -77,6 +78,9 @@ impl DelegationCSRProcessor for crate::delegations::DelegationsCSRProcessor {
         tracer: &mut TR,
     ) {
         use crate::delegations::unrolled::blake2_round_function_with_compression_mode::*;
+        use crate::delegations::unrolled::keccak_f1600_round_function::*;
+        use crate::delegations::unrolled::poseidon2_permutation::*;
+        use crate::delegations::unrolled::sha256_round_function::*;
         use crate::delegations::unrolled::u256_ops_with_control::*;
 
         match csr_index as u32 {
@@ -90,6 +94,15 @@ impl DelegationCSRProcessor for crate::delegations::DelegationsCSRProcessor {
             U256_OPS_WITH_CONTROL_ACCESS_ID => {
                 u256_ops_with_control_impl_over_unrolled_state(state, memory_source, tracer);
             }
+            SHA256_ROUND_FUNCTION_ACCESS_ID => {
+                sha256_round_function_over_unrolled_state(state, memory_source, tracer);
+            }
+            KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID => {
+                keccak_f1600_round_function_over_unrolled_state(state, memory_source, tracer);
+            }
+            POSEIDON2_PERMUTATION_ACCESS_ID => {
+                poseidon2_permutation_over_unrolled_state(state, memory_source, tracer);
+            }
             csr => {
                 panic!("Unsupported CSR = 0x{:04x}", csr);
             }
@@ -172,10 +185,14 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
         &mut self,
         memory_source: &mut M,
         tracer: &mut TR,
-    ) -> u32 {
-        let opcode = opcode_read(self.pc, memory_source);
+    ) -> (u32, u32) {
+        if Config::SUPPORT_COMPRESSED {
+            opcode_read_with_compressed(self.pc, memory_source)
+        } else {
+            let opcode = opcode_read(self.pc, memory_source);
 
-        opcode
+            (opcode, FULL_INSTRUCTION_LEN)
+        }
     }
 
     pub fn run_cycles<
@@ -196,7 +213,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
         for _cycle in 0..num_cycles {
             tracer.at_cycle_start_ext(&*self);
 
-            let opcode = self.decoder_step(memory_source, tracer);
+            let (opcode, instruction_len) = self.decoder_step(memory_source, tracer);
 
             let rd = get_rd_bits(opcode);
             let formal_rs1 = get_formal_rs1_bits(opcode);
@@ -212,7 +229,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                 core::hint::assert_unchecked(funct3 < 8);
             }
             let pc = self.pc;
-            self.pc = self.pc.wrapping_add(4);
+            self.pc = self.pc.wrapping_add(instruction_len);
 
             let rs1_value = self.get_register(formal_rs1 as u32);
             tracer.trace_rs1_read(formal_rs1 as u32, rs1_value);
@@ -230,7 +247,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
             match op {
                 OPCODE_LUI => {
                     // U format
-                    report_opcode("LUI");
+                    report_opcode(tracer, "LUI");
                     let imm = UTypeOpcode::imm(opcode);
                     let rd_value = imm;
 
@@ -239,7 +256,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                 }
                 OPCODE_AUIPC => {
                     // U format
-                    report_opcode("AUIPC");
+                    report_opcode(tracer, "AUIPC");
                     let imm = UTypeOpcode::imm(opcode);
                     let rd_value = pc.wrapping_add(imm);
 
@@ -247,14 +264,14 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                     tracer.trace_rd_write(rd, rd_old_value, rd_value);
                 }
                 OPCODE_JAL => {
-                    report_opcode("JAL");
+                    report_opcode(tracer, "JAL");
                     // J format
                     let mut imm: u32 = JTypeOpcode::imm(opcode);
                     sign_extend(&mut imm, 21);
-                    let rd_value = self.pc; // already incremented by 4
+                    let rd_value = self.pc; // already incremented by instruction length
                     let jmp_addr = pc.wrapping_add(imm); // this one is at this cycle
 
-                    if jmp_addr & 0x3 != 0 {
+                    if jmp_addr & pc_alignment_mask::<Config>() != 0 {
                         // unaligned PC
                         panic!("Unaligned jump address 0x{:08x}", jmp_addr);
                     } else {
@@ -265,17 +282,17 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                     tracer.trace_rd_write(rd, rd_old_value, rd_value);
                 }
                 OPCODE_JALR => {
-                    report_opcode("JALR");
+                    report_opcode(tracer, "JALR");
                     // I format
                     let mut imm: u32 = ITypeOpcode::imm(opcode);
                     // quasi sign extend
                     sign_extend(&mut imm, 12);
-                    let rd_value = self.pc; // already incremented by 4
+                    let rd_value = self.pc; // already incremented by instruction length
                                             //  The target address is obtained by adding the 12-bit signed I-immediate
                                             // to the register rs1, then setting the least-significant bit of the result to zero
                     let jmp_addr = (rs1_value.wrapping_add(imm) & !0x1);
 
-                    if jmp_addr & 0x3 != 0 {
+                    if jmp_addr & pc_alignment_mask::<Config>() != 0 {
                         // unaligned PC
                         panic!("Unaligned jump address 0x{:08x}", jmp_addr);
                     } else {
@@ -286,7 +303,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                     tracer.trace_rd_write(rd, rd_old_value, rd_value);
                 }
                 OPCODE_BRANCH => {
-                    report_opcode("BRANCH");
+                    report_opcode(tracer, "BRANCH");
                     // B format
                     let mut imm = BTypeOpcode::imm(opcode);
                     sign_extend(&mut imm, 13);
@@ -305,7 +322,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                     };
 
                     if should_jump {
-                        if jmp_addr & 0x3 != 0 {
+                        if jmp_addr & pc_alignment_mask::<Config>() != 0 {
                             // unaligned PC
                             panic!("Unaligned jump address 0x{:08x}", jmp_addr);
                         } else {
@@ -323,71 +340,79 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                     let mut imm = ITypeOpcode::imm(opcode);
                     sign_extend(&mut imm, 12);
                     let operand_2 = imm;
-                    let rd_value = match funct3 {
-                        0b000 => {
-                            report_opcode("ADD");
-                            operand_1.wrapping_add(operand_2)
-                        }
-                        0b001 if funct7 == SLL_FUNCT7 => {
-                            report_opcode("SLL");
-                            // shift is encoded in lowest 5 bits
-                            operand_1 << (operand_2 & 0x1f)
-                        }
-                        0b101 if funct7 == SRL_FUNCT7 => {
-                            report_opcode("SRL");
-                            // shift is encoded in lowest 5 bits
-                            operand_1 >> (operand_2 & 0x1f)
-                        }
-                        0b101 if funct7 == SRA_FUNCT7 => {
-                            report_opcode("SRA");
-                            // Arithmetic shift right
-                            // shift is encoded in lowest 5 bits
+                    let rd_value = if let Some((opcode_name, value)) = bitmanip_op::<Config>(
+                        false, funct3, funct7, formal_rs2, operand_1, operand_2,
+                    ) {
+                        // Zbb/Zbkb subsets, if supported
+                        report_opcode(opcode_name);
+                        value
+                    } else {
+                        match funct3 {
+                            0b000 => {
+                                report_opcode(tracer, "ADD");
+                                operand_1.wrapping_add(operand_2)
+                            }
+                            0b001 if funct7 == SLL_FUNCT7 => {
+                                report_opcode(tracer, "SLL");
+                                // shift is encoded in lowest 5 bits
+                                operand_1 << (operand_2 & 0x1f)
+                            }
+                            0b101 if funct7 == SRL_FUNCT7 => {
+                                report_opcode(tracer, "SRL");
+                                // shift is encoded in lowest 5 bits
+                                operand_1 >> (operand_2 & 0x1f)
+                            }
+                            0b101 if funct7 == SRA_FUNCT7 => {
+                                report_opcode(tracer, "SRA");
+                                // Arithmetic shift right
+                                // shift is encoded in lowest 5 bits
 
-                            if Config::SUPPORT_SRA {
-                                ((operand_1 as i32) >> (operand_2 & 0x1f)) as u32
-                            } else {
-                                panic!("Unknown opcode 0x{:08x}", opcode);
+                                if Config::SUPPORT_SRA {
+                                    ((operand_1 as i32) >> (operand_2 & 0x1f)) as u32
+                                } else {
+                                    panic!("Unknown opcode 0x{:08x}", opcode);
+                                }
                             }
-                        }
-                        0b101 if funct7 == ROT_FUNCT7 => {
-                            report_opcode("ROR");
-                            // Arithmetic shift right
-                            // shift is encoded in lowest 5 bits
+                            0b101 if funct7 == ROT_FUNCT7 => {
+                                report_opcode(tracer, "ROR");
+                                // Arithmetic shift right
+                                // shift is encoded in lowest 5 bits
 
-                            if Config::SUPPORT_ROT {
-                                operand_1.rotate_right(operand_2 & 0x1f)
-                            } else {
+                                if Config::SUPPORT_ROT {
+                                    operand_1.rotate_right(operand_2 & 0x1f)
+                                } else {
+                                    panic!("Unknown opcode 0x{:08x}", opcode);
+                                }
+                            }
+                            0b010 => {
+                                report_opcode(tracer, "SLT");
+                                // Store less than
+                                ((operand_1 as i32) < (operand_2 as i32)) as u32
+                            }
+                            0b011 => {
+                                report_opcode(tracer, "SLTU");
+                                // Store less than unsigned
+                                (operand_1 < operand_2) as u32
+                            }
+                            0b100 => {
+                                report_opcode(tracer, "XOR");
+                                // XOR
+                                operand_1 ^ operand_2
+                            }
+                            0b110 => {
+                                report_opcode(tracer, "OR");
+                                // OR
+                                operand_1 | operand_2
+                            }
+                            0b111 => {
+                                report_opcode(tracer, "AND");
+                                // AND
+                                operand_1 & operand_2
+                            }
+                            _ => {
                                 panic!("Unknown opcode 0x{:08x}", opcode);
                             }
                         }
-                        0b010 => {
-                            report_opcode("SLT");
-                            // Store less than
-                            ((operand_1 as i32) < (operand_2 as i32)) as u32
-                        }
-                        0b011 => {
-                            report_opcode("SLTU");
-                            // Store less than unsigned
-                            (operand_1 < operand_2) as u32
-                        }
-                        0b100 => {
-                            report_opcode("XOR");
-                            // XOR
-                            operand_1 ^ operand_2
-                        }
-                        0b110 => {
-                            report_opcode("OR");
-                            // OR
-                            operand_1 | operand_2
-                        }
-                        0b111 => {
-                            report_opcode("AND");
-                            // AND
-                            operand_1 & operand_2
-                        }
-                        _ => {
-                            panic!("Unknown opcode 0x{:08x}", opcode);
-                        }
                     };
 
                     let rd_old_value = self.set_register(rd, rd_value);
@@ -401,7 +426,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                         // Multiplication extension
                         let rd_value = match funct3 {
                             0b000 => {
-                                report_opcode("MUL");
+                                report_opcode(tracer, "MUL");
                                 // MUL
                                 if Config::SUPPORT_MUL {
                                     (operand_1 as i32).wrapping_mul(operand_2 as i32) as u32
@@ -410,7 +435,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b001 => {
-                                report_opcode("MULH");
+                                report_opcode(tracer, "MULH");
                                 // MULH
                                 if Config::SUPPORT_MUL && Config::SUPPORT_SIGNED_MUL {
                                     (((operand_1 as i32) as i64)
@@ -421,7 +446,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b010 => {
-                                report_opcode("MULSU");
+                                report_opcode(tracer, "MULSU");
                                 // MULHSU
                                 if Config::SUPPORT_MUL && Config::SUPPORT_SIGNED_MUL {
                                     (((operand_1 as i32) as i64)
@@ -432,7 +457,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b011 => {
-                                report_opcode("MULHU");
+                                report_opcode(tracer, "MULHU");
                                 // MULHU
                                 if Config::SUPPORT_MUL {
                                     ((operand_1 as u64).wrapping_mul(operand_2 as u64) >> 32) as u32
@@ -441,7 +466,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b100 => {
-                                report_opcode("DIV");
+                                report_opcode(tracer, "DIV");
                                 // DIV
                                 if Config::SUPPORT_DIV && Config::SUPPORT_SIGNED_DIV {
                                     if operand_2 == 0 {
@@ -458,7 +483,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b101 => {
-                                report_opcode("DIVU");
+                                report_opcode(tracer, "DIVU");
                                 // DIVU
                                 if Config::SUPPORT_DIV {
                                     if operand_2 == 0 {
@@ -471,7 +496,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b110 => {
-                                report_opcode("REM");
+                                report_opcode(tracer, "REM");
                                 // REM
                                 if Config::SUPPORT_DIV && Config::SUPPORT_SIGNED_DIV {
                                     if operand_2 == 0 {
@@ -488,7 +513,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b111 => {
-                                report_opcode("REMU");
+                                report_opcode(tracer, "REMU");
                                 // REMU
                                 if Config::SUPPORT_DIV {
                                     if operand_2 == 0 {
@@ -503,26 +528,34 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                             _ => unsafe { unreachable_unchecked() },
                         };
 
+                        let rd_old_value = self.set_register(rd, rd_value);
+                        tracer.trace_rd_write(rd, rd_old_value, rd_value);
+                    } else if let Some((opcode_name, rd_value)) = bitmanip_op::<Config>(
+                        true, funct3, funct7, formal_rs2, operand_1, operand_2,
+                    ) {
+                        // Zbb/Zbkb subsets, if supported
+                        report_opcode(opcode_name);
+
                         let rd_old_value = self.set_register(rd, rd_value);
                         tracer.trace_rd_write(rd, rd_old_value, rd_value);
                     } else {
                         // basic set
                         let rd_value = match funct3 {
                             0b000 if funct7 == 0 => {
-                                report_opcode("ADD");
+                                report_opcode(tracer, "ADD");
                                 operand_1.wrapping_add(operand_2)
                             }
                             0b000 if funct7 == SUB_FUNCT7 => {
-                                report_opcode("SUB");
+                                report_opcode(tracer, "SUB");
                                 operand_1.wrapping_sub(operand_2)
                             }
                             0b001 if funct7 == SLL_FUNCT7 => {
-                                report_opcode("SLL");
+                                report_opcode(tracer, "SLL");
                                 // shift is encoded in lowest 5 bits
                                 operand_1 << (operand_2 & 0x1f)
                             }
                             0b001 if funct7 == ROT_FUNCT7 => {
-                                report_opcode("ROL");
+                                report_opcode(tracer, "ROL");
                                 // Arithmetic shift right
                                 // shift is encoded in lowest 5 bits
 
@@ -533,12 +566,12 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b101 if funct7 == SRL_FUNCT7 => {
-                                report_opcode("SRL");
+                                report_opcode(tracer, "SRL");
                                 // shift is encoded in lowest 5 bits
                                 operand_1 >> (operand_2 & 0x1f)
                             }
                             0b101 if funct7 == SRA_FUNCT7 => {
-                                report_opcode("SRA");
+                                report_opcode(tracer, "SRA");
                                 // Arithmetic shift right
                                 // shift is encoded in lowest 5 bits
 
@@ -549,7 +582,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b101 if funct7 == ROT_FUNCT7 => {
-                                report_opcode("ROR");
+                                report_opcode(tracer, "ROR");
                                 // Arithmetic shift right
                                 // shift is encoded in lowest 5 bits
 
@@ -560,27 +593,27 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 }
                             }
                             0b010 => {
-                                report_opcode("SLT");
+                                report_opcode(tracer, "SLT");
                                 // Store less than
                                 ((operand_1 as i32) < (operand_2 as i32)) as u32
                             }
                             0b011 => {
-                                report_opcode("SLTU");
+                                report_opcode(tracer, "SLTU");
                                 // Store less than unsigned
                                 (operand_1 < operand_2) as u32
                             }
                             0b100 => {
-                                report_opcode("XOR");
+                                report_opcode(tracer, "XOR");
                                 // XOR
                                 operand_1 ^ operand_2
                             }
                             0b110 => {
-                                report_opcode("OR");
+                                report_opcode(tracer, "OR");
                                 // OR
                                 operand_1 | operand_2
                             }
                             0b111 => {
-                                report_opcode("AND");
+                                report_opcode(tracer, "AND");
                                 // AND
                                 operand_1 & operand_2
                             }
@@ -623,23 +656,23 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                 // now depending on the type of load we extend it
                                 match a {
                                     0 => {
-                                        report_opcode("LB");
+                                        report_opcode(tracer, "LB");
                                         sign_extend_8(ram_read_value)
                                     }
                                     1 => {
-                                        report_opcode("LH");
+                                        report_opcode(tracer, "LH");
                                         sign_extend_16(ram_read_value)
                                     }
                                     2 => {
-                                        report_opcode("LW");
+                                        report_opcode(tracer, "LW");
                                         ram_read_value
                                     }
                                     4 => {
-                                        report_opcode("LBU");
+                                        report_opcode(tracer, "LBU");
                                         zero_extend_8(ram_read_value)
                                     }
                                     5 => {
-                                        report_opcode("LHU");
+                                        report_opcode(tracer, "LHU");
                                         zero_extend_16(ram_read_value)
                                     }
                                     _ => unsafe { unreachable_unchecked() },
@@ -651,15 +684,15 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                                         panic!("Sign extension not enabled for LOAD");
                                     }
                                     2 => {
-                                        report_opcode("LW");
+                                        report_opcode(tracer, "LW");
                                         ram_read_value
                                     }
                                     4 => {
-                                        report_opcode("LBU");
+                                        report_opcode(tracer, "LBU");
                                         zero_extend_8(ram_read_value)
                                     }
                                     5 => {
-                                        report_opcode("LHU");
+                                        report_opcode(tracer, "LHU");
                                         zero_extend_16(ram_read_value)
                                     }
                                     _ => unsafe { unreachable_unchecked() },
@@ -692,13 +725,13 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                             {
                                 match store_length {
                                     1 => {
-                                        report_opcode("SB");
+                                        report_opcode(tracer, "SB");
                                     }
                                     2 => {
-                                        report_opcode("SH");
+                                        report_opcode(tracer, "SH");
                                     }
                                     4 => {
-                                        report_opcode("SW");
+                                        report_opcode(tracer, "SW");
                                     }
                                     _ => unsafe { core::hint::unreachable_unchecked() },
                                 }
@@ -733,7 +766,7 @@ impl<Config: MachineConfig> RiscV32StateForUnrolledProver<Config> {
                     if funct3 == ZIMOP_FUNCT3 {
                         const MOP_FUNCT7_TEST: u8 = 0b1000001u8;
                         if Config::SUPPORT_MOPS && funct7 & MOP_FUNCT7_TEST == MOP_FUNCT7_TEST {
-                            report_opcode("MOP");
+                            report_opcode(tracer, "MOP");
 
                             use field::{Field, Mersenne31Field};
 
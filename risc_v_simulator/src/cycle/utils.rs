@@ -1,5 +1,9 @@
 use super::*;
 use crate::abstractions::memory::MemorySource;
+use crate::cycle::opcode_formats::{
+    expand_compressed_instruction, is_compressed_instruction, COMPRESSED_INSTRUCTION_LEN,
+    FULL_INSTRUCTION_LEN,
+};
 use std::hint::unreachable_unchecked;
 
 #[must_use]
@@ -10,6 +14,52 @@ pub(crate) fn opcode_read<M: MemorySource>(pc: u32, memory_source: &mut M) -> u3
     value
 }
 
+/// Reads an instruction at half-word aligned PC, and expands it if it's compressed.
+/// Returns the 32-bit instruction and the length of the original encoding in bytes
+#[must_use]
+#[inline(always)]
+pub(crate) fn opcode_read_with_compressed<M: MemorySource>(
+    pc: u32,
+    memory_source: &mut M,
+) -> (u32, u32) {
+    let aligned_pc = pc & !3;
+    let word = memory_source.get_opcode_noexcept(aligned_pc as u64);
+    let low_half = if pc & 2 == 0 {
+        word & 0xffff
+    } else {
+        word >> 16
+    };
+    if is_compressed_instruction(low_half) {
+        let Some(opcode) = expand_compressed_instruction(low_half as u16) else {
+            panic!(
+                "Illegal compressed instruction 0x{:04x} at PC = 0x{:08x}",
+                low_half, pc
+            );
+        };
+
+        (opcode, COMPRESSED_INSTRUCTION_LEN)
+    } else if pc & 2 == 0 {
+        (word, FULL_INSTRUCTION_LEN)
+    } else {
+        // 32-bit instruction that crosses the word boundary
+        let next_word = memory_source.get_opcode_noexcept(aligned_pc as u64 + 4);
+
+        (low_half | (next_word << 16), FULL_INSTRUCTION_LEN)
+    }
+}
+
+/// Jump and branch targets must be 4 byte aligned, or only 2 byte aligned if compressed
+/// instructions are supported
+#[must_use]
+#[inline(always)]
+pub(crate) const fn pc_alignment_mask<C: MachineConfig>() -> u32 {
+    if C::SUPPORT_COMPRESSED {
+        0x1
+    } else {
+        0x3
+    }
+}
+
 #[must_use]
 #[inline(always)]
 pub fn mem_read<M: MemorySource, C: MachineConfig>(
@@ -29,8 +29,10 @@ pub fn blake2_round_function_with_extended_control<
     _mmu: &mut MMU,
     rs1_value: u32,
     trap: &mut TrapReason,
-) {
-    assert_eq!(rs1_value, 0, "aligned memory access is unused");
+) -> Result<(), DelegationAbiViolation> {
+    const ID: u32 = BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID;
+
+    ensure_abi!(rs1_value == 0, ID, "aligned memory access is unused");
 
     // read registers first
     let x10 = state.registers[10];
@@ -38,21 +40,33 @@ pub fn blake2_round_function_with_extended_control<
     let x12 = state.registers[12];
     let x13 = state.registers[13];
 
-    assert!(x10 % 128 == 0, "input pointer is unaligned");
-    assert!(x11 % 4 == 0, "input pointer is unaligned");
+    ensure_abi!(
+        x10 % 128 == 0,
+        ID,
+        "state pointer 0x{:08x} is unaligned",
+        x10
+    );
+    ensure_abi!(x11 % 4 == 0, ID, "input pointer 0x{:08x} is unaligned", x11);
 
     // self-check so that we do not touch ROM
-    assert!(x10 >= 1 << 21);
-    assert!(x11 >= 1 << 21);
+    ensure_abi!(x10 >= 1 << 21, ID, "pointer 0x{:08x} points into ROM", x10);
+    ensure_abi!(x11 >= 1 << 21, ID, "pointer 0x{:08x} points into ROM", x11);
 
-    assert!(x10 != x11);
+    ensure_abi!(x10 != x11, ID, "input pointers must not alias");
 
     let mut state_accesses: [RegisterOrIndirectReadWriteData; X10_NUM_WRITES] =
-        register_indirect_read_write_continuous::<_, X10_NUM_WRITES>(x10 as usize, memory_source);
+        register_indirect_read_write_continuous::<_, X10_NUM_WRITES>(
+            x10 as usize,
+            memory_source,
+            trap,
+        );
     let state_read_addresses: [u32; X10_NUM_WRITES] =
         std::array::from_fn(|i| x10 + (core::mem::size_of::<u32>() * i) as u32);
     let mut input_accesses: [RegisterOrIndirectReadData; X11_NUM_READS] =
-        register_indirect_read_continuous::<_, X11_NUM_READS>(x11 as usize, memory_source);
+        register_indirect_read_continuous::<_, X11_NUM_READS>(x11 as usize, memory_source, trap);
+    if trap.is_a_trap() {
+        return Ok(());
+    }
     let input_read_addresses: [u32; X11_NUM_READS] =
         std::array::from_fn(|i| x11 + (core::mem::size_of::<u32>() * i) as u32);
 
@@ -87,12 +101,19 @@ pub fn blake2_round_function_with_extended_control<
         control_register & TEST_IF_INPUT_IS_RIGHT_NODE_MASK == TEST_IF_INPUT_IS_RIGHT_NODE_MASK;
 
     let permutation_bitmask = x12;
-    assert!(
+    ensure_abi!(
         permutation_bitmask.is_power_of_two(),
+        ID,
         "permutation bitmask must be a bitmask, but got 0b{:b}",
         permutation_bitmask
     );
     let permutation_index = permutation_bitmask.trailing_zeros() as usize;
+    ensure_abi!(
+        permutation_index < SIGMAS.len(),
+        ID,
+        "permutation index {} is out of range",
+        permutation_index
+    );
 
     if mode_compression {
         if permutation_index == 0 {
@@ -160,7 +181,15 @@ pub fn blake2_round_function_with_extended_control<
     }
 
     // write down to RAM
-    write_indirect_accesses::<_, X10_NUM_WRITES>(x10 as usize, &state_accesses, memory_source);
+    write_indirect_accesses::<_, X10_NUM_WRITES>(
+        x10 as usize,
+        &state_accesses,
+        memory_source,
+        trap,
+    );
+    if trap.is_a_trap() {
+        return Ok(());
+    }
 
     // make witness structures - there are no register writes
     let mut register_accesses = [
@@ -195,4 +224,6 @@ pub fn blake2_round_function_with_extended_control<
         &state_read_addresses,
         &mut state_accesses,
     );
+
+    Ok(())
 }
@@ -0,0 +1,176 @@
+use crate::cycle::{state::NON_DETERMINISM_CSR, status_registers::TrapReason};
+use cs::definitions::TimestampData;
+
+use super::*;
+
+pub const KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID: u32 = NON_DETERMINISM_CSR + 12;
+
+pub const KECCAK_F1600_NUM_LANES: usize = 25;
+pub const KECCAK_F1600_NUM_ROUNDS: usize = 24;
+
+pub const X10_NUM_WRITES: usize = KECCAK_F1600_NUM_LANES * 2;
+
+/// Rotation offsets for the rho step, indexed by `x + 5 * y`
+pub const KECCAK_F1600_RHO_OFFSETS: [u32; KECCAK_F1600_NUM_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Round constants for the iota step
+pub const KECCAK_F1600_ROUND_CONSTANTS: [u64; KECCAK_F1600_NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Performs a single round `round_index` of Keccak-f[1600] (theta, rho, pi, chi and iota steps) over the state
+/// given as 25 lanes, each lane being two little-endian u32 words
+pub fn keccak_f1600_round(state: &mut [u32; X10_NUM_WRITES], round_index: usize) {
+    let mut lanes: [u64; KECCAK_F1600_NUM_LANES] =
+        std::array::from_fn(|i| (state[2 * i] as u64) | ((state[2 * i + 1] as u64) << 32));
+
+    // theta
+    let c: [u64; 5] = std::array::from_fn(|x| {
+        lanes[x] ^ lanes[x + 5] ^ lanes[x + 10] ^ lanes[x + 15] ^ lanes[x + 20]
+    });
+    for x in 0..5 {
+        let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        for y in 0..5 {
+            lanes[x + 5 * y] ^= d;
+        }
+    }
+
+    // rho and pi
+    let mut b = [0u64; KECCAK_F1600_NUM_LANES];
+    for x in 0..5 {
+        for y in 0..5 {
+            let idx = x + 5 * y;
+            b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                lanes[idx].rotate_left(KECCAK_F1600_RHO_OFFSETS[idx]);
+        }
+    }
+
+    // chi
+    for x in 0..5 {
+        for y in 0..5 {
+            lanes[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+        }
+    }
+
+    // iota
+    lanes[0] ^= KECCAK_F1600_ROUND_CONSTANTS[round_index];
+
+    for (i, lane) in lanes.into_iter().enumerate() {
+        state[2 * i] = lane as u32;
+        state[2 * i + 1] = (lane >> 32) as u32;
+    }
+}
+
+pub fn keccak_f1600_round_function<
+    M: MemorySource,
+    TR: Tracer<C>,
+    MMU: MMUImplementation<M, TR, C>,
+    C: MachineConfig,
+>(
+    state: &mut RiscV32State<C>,
+    memory_source: &mut M,
+    tracer: &mut TR,
+    _mmu: &mut MMU,
+    rs1_value: u32,
+    trap: &mut TrapReason,
+) -> Result<(), DelegationAbiViolation> {
+    const ID: u32 = KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID;
+
+    ensure_abi!(rs1_value == 0, ID, "aligned memory access is unused");
+
+    // read registers first
+    let x10 = state.registers[10];
+    let x11 = state.registers[11];
+
+    ensure_abi!(
+        x10 % 256 == 0,
+        ID,
+        "state pointer 0x{:08x} is unaligned",
+        x10
+    );
+
+    // self-check so that we do not touch ROM
+    ensure_abi!(x10 >= 1 << 21, ID, "pointer 0x{:08x} points into ROM", x10);
+
+    ensure_abi!(
+        x11 < KECCAK_F1600_NUM_ROUNDS as u32,
+        ID,
+        "round index {} is out of range",
+        x11
+    );
+
+    let mut state_accesses: [RegisterOrIndirectReadWriteData; X10_NUM_WRITES] =
+        register_indirect_read_write_continuous::<_, X10_NUM_WRITES>(
+            x10 as usize,
+            memory_source,
+            trap,
+        );
+    if trap.is_a_trap() {
+        return Ok(());
+    }
+    let state_read_addresses: [u32; X10_NUM_WRITES] =
+        std::array::from_fn(|i| x10 + (core::mem::size_of::<u32>() * i) as u32);
+
+    let mut keccak_state = state_accesses.map(|el| el.read_value);
+    keccak_f1600_round(&mut keccak_state, x11 as usize);
+
+    // write back into our bookkeeping
+    for (src, dst) in keccak_state.into_iter().zip(state_accesses.iter_mut()) {
+        dst.write_value = src;
+    }
+
+    // write down to RAM
+    write_indirect_accesses::<_, X10_NUM_WRITES>(
+        x10 as usize,
+        &state_accesses,
+        memory_source,
+        trap,
+    );
+    if trap.is_a_trap() {
+        return Ok(());
+    }
+
+    // make witness structures - there are no register writes, and round index is passed in x11
+    let mut register_accesses = [x10, x11].map(|value| RegisterOrIndirectReadWriteData {
+        read_value: value,
+        write_value: value,
+        timestamp: TimestampData::EMPTY,
+    });
+
+    tracer.record_delegation(
+        KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID,
+        10,
+        &mut register_accesses,
+        &[],
+        &mut [],
+        &state_read_addresses,
+        &mut state_accesses,
+    );
+
+    Ok(())
+}
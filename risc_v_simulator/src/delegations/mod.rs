@@ -4,7 +4,16 @@ use u256_ops_with_control::U256_OPS_WITH_CONTROL_ACCESS_ID;
 use blake2_round_function_with_compression_mode::blake2_round_function_with_extended_control;
 use blake2_round_function_with_compression_mode::BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID;
 
-use crate::abstractions::csr_processor::CustomCSRProcessor;
+use keccak_f1600_round_function::keccak_f1600_round_function;
+use keccak_f1600_round_function::KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID;
+
+use poseidon2_permutation::poseidon2_permutation_function;
+use poseidon2_permutation::POSEIDON2_PERMUTATION_ACCESS_ID;
+
+use sha256_round_function::sha256_round_function;
+use sha256_round_function::SHA256_ROUND_FUNCTION_ACCESS_ID;
+
+use crate::abstractions::csr_processor::{CustomCSRProcessor, DelegationAbiViolation};
 use crate::abstractions::memory::*;
 use crate::abstractions::non_determinism::NonDeterminismCSRSource;
 use crate::abstractions::tracer::*;
@@ -16,14 +25,31 @@ use cs::definitions::TimestampScalar;
 use std::mem::MaybeUninit;
 use std::ops::Range;
 
+// Terminates the delegation with an ABI violation if condition doesn't hold
+macro_rules! ensure_abi {
+    ($condition:expr, $id:expr, $($reason:tt)+) => {
+        if !$condition {
+            return Err(DelegationAbiViolation {
+                id: $id,
+                reason: format!($($reason)+),
+            });
+        }
+    };
+}
+
 pub mod unrolled;
 
 pub mod blake2_round_function_with_compression_mode;
+pub mod keccak_f1600_round_function;
+pub mod poseidon2_permutation;
+pub mod sha256_round_function;
 pub mod u256_ops_with_control;
 
 #[derive(Clone, Copy, Debug)]
 pub struct DelegationsCSRProcessor;
 
+// Memory helpers below stop at the first failed access and leave the reason in `trap`,
+// so caller must check it before using the results
 pub(crate) fn read_words_at_offsets_into<'a, M: MemorySource>(
     base_mem_offset: usize,
     words_range: Range<usize>,
@@ -43,7 +69,7 @@ pub(crate) fn read_words_at_offsets_into<'a, M: MemorySource>(
         let address: usize = base_mem_offset + offset * core::mem::size_of::<u32>();
         let read_value = memory_source.get(address as u64, AccessType::RegWrite, trap);
         if trap.is_a_trap() {
-            panic!("error in memory access");
+            return;
         }
 
         let record = if is_write_access {
@@ -79,7 +105,7 @@ pub(crate) fn write_words_at_offsets_into<'a, M: MemorySource>(
     for (src, bookkeeping_dst) in source.zip(accesses_bookkeeping_iterator) {
         memory_source.set(address as u64, *src, AccessType::RegWrite, trap);
         if trap.is_a_trap() {
-            panic!("error in memory access");
+            return;
         }
 
         let BatchAccessPartialData::Write { written_value, .. } = bookkeeping_dst else {
@@ -105,7 +131,7 @@ pub(crate) fn read_continuous_words<'a, M: MemorySource, const N: usize>(
         for i in 0..N {
             let read_value = memory_source.get(address as u64, AccessType::RegWrite, trap);
             if trap.is_a_trap() {
-                panic!("error in memory access");
+                return result;
             }
 
             *result.get_unchecked_mut(i) = read_value;
@@ -131,16 +157,16 @@ pub(crate) fn read_continuous_words<'a, M: MemorySource, const N: usize>(
 pub(crate) fn register_indirect_read_continuous<M: MemorySource, const N: usize>(
     base_mem_offset: usize,
     memory_source: &mut M,
+    trap: &mut TrapReason,
 ) -> [RegisterOrIndirectReadData; N] {
     let mut result = [RegisterOrIndirectReadData::EMPTY; N];
 
-    let mut trap = TrapReason::NoTrap;
     unsafe {
         let mut address = base_mem_offset;
         for i in 0..N {
-            let read_value = memory_source.get(address as u64, AccessType::RegWrite, &mut trap);
+            let read_value = memory_source.get(address as u64, AccessType::RegWrite, trap);
             if trap.is_a_trap() {
-                panic!("error in memory access");
+                return result;
             }
             result.get_unchecked_mut(i).read_value = read_value;
 
@@ -154,16 +180,16 @@ pub(crate) fn register_indirect_read_continuous<M: MemorySource, const N: usize>
 pub(crate) fn register_indirect_read_write_continuous<M: MemorySource, const N: usize>(
     base_mem_offset: usize,
     memory_source: &mut M,
+    trap: &mut TrapReason,
 ) -> [RegisterOrIndirectReadWriteData; N] {
     let mut result = [RegisterOrIndirectReadWriteData::EMPTY; N];
 
-    let mut trap = TrapReason::NoTrap;
     unsafe {
         let mut address = base_mem_offset;
         for i in 0..N {
-            let read_value = memory_source.get(address as u64, AccessType::RegWrite, &mut trap);
+            let read_value = memory_source.get(address as u64, AccessType::RegWrite, trap);
             if trap.is_a_trap() {
-                panic!("error in memory access");
+                return result;
             }
             result.get_unchecked_mut(i).read_value = read_value;
 
@@ -179,19 +205,13 @@ pub(crate) fn write_indirect_accesses<M: MemorySource, const N: usize>(
     base_mem_offset: usize,
     accesses: &[RegisterOrIndirectReadWriteData; N],
     memory_source: &mut M,
+    trap: &mut TrapReason,
 ) {
-    let mut trap = TrapReason::NoTrap;
-
     let mut address = base_mem_offset;
     for src in accesses {
-        memory_source.set(
-            address as u64,
-            src.write_value,
-            AccessType::RegWrite,
-            &mut trap,
-        );
+        memory_source.set(address as u64, src.write_value, AccessType::RegWrite, trap);
         if trap.is_a_trap() {
-            panic!("error in memory access");
+            return;
         }
 
         address += core::mem::size_of::<u32>();
@@ -209,7 +229,7 @@ pub(crate) fn read_single_value<'a, M: MemorySource>(
     let address: usize = base_mem_offset + word_index * core::mem::size_of::<u32>();
     let read_value = memory_source.get(address as u64, AccessType::RegWrite, trap);
     if trap.is_a_trap() {
-        panic!("error in memory access");
+        return 0;
     }
 
     let record = if is_write_access {
@@ -252,6 +272,9 @@ impl CustomCSRProcessor for DelegationsCSRProcessor {
         match csr_index {
             BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID => {}
             U256_OPS_WITH_CONTROL_ACCESS_ID => {}
+            SHA256_ROUND_FUNCTION_ACCESS_ID => {}
+            KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID => {}
+            POSEIDON2_PERMUTATION_ACCESS_ID => {}
             _ => {
                 *trap = TrapReason::IllegalInstruction;
             }
@@ -276,7 +299,7 @@ impl CustomCSRProcessor for DelegationsCSRProcessor {
         rs1_value: u32,
         _zimm: u32,
         trap: &mut TrapReason,
-    ) {
+    ) -> Result<(), DelegationAbiViolation> {
         match csr_index {
             BLAKE2_ROUND_FUNCTION_WITH_EXTENDED_CONTROL_ACCESS_ID => {
                 blake2_round_function_with_extended_control(
@@ -286,13 +309,24 @@ impl CustomCSRProcessor for DelegationsCSRProcessor {
                     mmu,
                     rs1_value,
                     trap,
-                );
+                )
             }
             U256_OPS_WITH_CONTROL_ACCESS_ID => {
-                u256_ops_with_control_impl(state, memory_source, tracer, mmu, rs1_value, trap);
+                u256_ops_with_control_impl(state, memory_source, tracer, mmu, rs1_value, trap)
+            }
+            SHA256_ROUND_FUNCTION_ACCESS_ID => {
+                sha256_round_function(state, memory_source, tracer, mmu, rs1_value, trap)
+            }
+            KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID => {
+                keccak_f1600_round_function(state, memory_source, tracer, mmu, rs1_value, trap)
+            }
+            POSEIDON2_PERMUTATION_ACCESS_ID => {
+                poseidon2_permutation_function(state, memory_source, tracer, mmu, rs1_value, trap)
             }
             _ => {
                 *trap = TrapReason::IllegalInstruction;
+
+                Ok(())
             }
         }
     }
@@ -0,0 +1,100 @@
+use crate::cycle::{state::NON_DETERMINISM_CSR, status_registers::TrapReason};
+use cs::definitions::TimestampData;
+use field::Mersenne31Field;
+
+use super::*;
+
+pub const POSEIDON2_PERMUTATION_ACCESS_ID: u32 = NON_DETERMINISM_CSR + 13;
+
+pub const X10_NUM_WRITES: usize = 16;
+
+/// Performs full Poseidon2 permutation over the state given as 16 (not necessarily reduced)
+/// Mersenne31 elements, and writes back reduced elements
+pub fn poseidon2_permutation(state: &mut [u32; X10_NUM_WRITES]) {
+    let mut field_state = state.map(Mersenne31Field::from_nonreduced_u32);
+    poseidon2::m31::poseidon_permutation(&mut field_state);
+    for (dst, src) in state.iter_mut().zip(field_state.iter()) {
+        *dst = src.to_reduced_u32();
+    }
+}
+
+pub fn poseidon2_permutation_function<
+    M: MemorySource,
+    TR: Tracer<C>,
+    MMU: MMUImplementation<M, TR, C>,
+    C: MachineConfig,
+>(
+    state: &mut RiscV32State<C>,
+    memory_source: &mut M,
+    tracer: &mut TR,
+    _mmu: &mut MMU,
+    rs1_value: u32,
+    trap: &mut TrapReason,
+) -> Result<(), DelegationAbiViolation> {
+    const ID: u32 = POSEIDON2_PERMUTATION_ACCESS_ID;
+
+    ensure_abi!(rs1_value == 0, ID, "aligned memory access is unused");
+
+    // read registers first
+    let x10 = state.registers[10];
+
+    ensure_abi!(
+        x10 % 64 == 0,
+        ID,
+        "state pointer 0x{:08x} is unaligned",
+        x10
+    );
+
+    // self-check so that we do not touch ROM
+    ensure_abi!(x10 >= 1 << 21, ID, "pointer 0x{:08x} points into ROM", x10);
+
+    let mut state_accesses: [RegisterOrIndirectReadWriteData; X10_NUM_WRITES] =
+        register_indirect_read_write_continuous::<_, X10_NUM_WRITES>(
+            x10 as usize,
+            memory_source,
+            trap,
+        );
+    if trap.is_a_trap() {
+        return Ok(());
+    }
+    let state_read_addresses: [u32; X10_NUM_WRITES] =
+        std::array::from_fn(|i| x10 + (core::mem::size_of::<u32>() * i) as u32);
+
+    let mut permutation_state = state_accesses.map(|el| el.read_value);
+    poseidon2_permutation(&mut permutation_state);
+
+    // write back into our bookkeeping
+    for (src, dst) in permutation_state.into_iter().zip(state_accesses.iter_mut()) {
+        dst.write_value = src;
+    }
+
+    // write down to RAM
+    write_indirect_accesses::<_, X10_NUM_WRITES>(
+        x10 as usize,
+        &state_accesses,
+        memory_source,
+        trap,
+    );
+    if trap.is_a_trap() {
+        return Ok(());
+    }
+
+    // make witness structures - there are no register writes
+    let mut register_accesses = [x10].map(|value| RegisterOrIndirectReadWriteData {
+        read_value: value,
+        write_value: value,
+        timestamp: TimestampData::EMPTY,
+    });
+
+    tracer.record_delegation(
+        POSEIDON2_PERMUTATION_ACCESS_ID,
+        10,
+        &mut register_accesses,
+        &[],
+        &mut [],
+        &state_read_addresses,
+        &mut state_accesses,
+    );
+
+    Ok(())
+}
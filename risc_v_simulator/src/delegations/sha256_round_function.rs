@@ -0,0 +1,168 @@
+use crate::cycle::{state::NON_DETERMINISM_CSR, status_registers::TrapReason};
+use cs::definitions::TimestampData;
+
+use super::*;
+
+pub const SHA256_ROUND_FUNCTION_ACCESS_ID: u32 = NON_DETERMINISM_CSR + 11;
+
+pub const SHA256_STATE_WIDTH_IN_U32_WORDS: usize = 8;
+pub const SHA256_MESSAGE_SCHEDULE_WINDOW_IN_U32_WORDS: usize = 16;
+pub const SHA256_NUM_ROUNDS: usize = 64;
+
+pub const SHA256_ROUND_CONSTANTS: [u32; SHA256_NUM_ROUNDS] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+pub const X10_NUM_WRITES: usize =
+    SHA256_STATE_WIDTH_IN_U32_WORDS + SHA256_MESSAGE_SCHEDULE_WINDOW_IN_U32_WORDS;
+
+/// Performs a single round `t` of SHA-256 compression over working variables `a..h` using the first word of the
+/// message schedule window `W[t]..W[t+15]`, and shifts the window by one word, appending `W[t+16]`
+pub fn sha256_round_with_message_schedule(
+    state_and_schedule: &mut [u32; X10_NUM_WRITES],
+    round_index: usize,
+) {
+    let k = SHA256_ROUND_CONSTANTS[round_index];
+    let [a, b, c, d, e, f, g, h]: [u32; SHA256_STATE_WIDTH_IN_U32_WORDS] = state_and_schedule
+        [..SHA256_STATE_WIDTH_IN_U32_WORDS]
+        .try_into()
+        .unwrap();
+    let w = &mut state_and_schedule[SHA256_STATE_WIDTH_IN_U32_WORDS..];
+
+    let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+    let ch = (e & f) ^ (!e & g);
+    let t1 = h
+        .wrapping_add(s1)
+        .wrapping_add(ch)
+        .wrapping_add(k)
+        .wrapping_add(w[0]);
+    let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+    let maj = (a & b) ^ (a & c) ^ (b & c);
+    let t2 = s0.wrapping_add(maj);
+
+    let sigma0 = w[1].rotate_right(7) ^ w[1].rotate_right(18) ^ (w[1] >> 3);
+    let sigma1 = w[14].rotate_right(17) ^ w[14].rotate_right(19) ^ (w[14] >> 10);
+    let next_w = sigma1
+        .wrapping_add(w[9])
+        .wrapping_add(sigma0)
+        .wrapping_add(w[0]);
+    w.copy_within(1.., 0);
+    w[SHA256_MESSAGE_SCHEDULE_WINDOW_IN_U32_WORDS - 1] = next_w;
+
+    state_and_schedule[..SHA256_STATE_WIDTH_IN_U32_WORDS].copy_from_slice(&[
+        t1.wrapping_add(t2),
+        a,
+        b,
+        c,
+        d.wrapping_add(t1),
+        e,
+        f,
+        g,
+    ]);
+}
+
+pub fn sha256_round_function<
+    M: MemorySource,
+    TR: Tracer<C>,
+    MMU: MMUImplementation<M, TR, C>,
+    C: MachineConfig,
+>(
+    state: &mut RiscV32State<C>,
+    memory_source: &mut M,
+    tracer: &mut TR,
+    _mmu: &mut MMU,
+    rs1_value: u32,
+    trap: &mut TrapReason,
+) -> Result<(), DelegationAbiViolation> {
+    const ID: u32 = SHA256_ROUND_FUNCTION_ACCESS_ID;
+
+    ensure_abi!(rs1_value == 0, ID, "aligned memory access is unused");
+
+    // read registers first
+    let x10 = state.registers[10];
+    let x11 = state.registers[11];
+
+    ensure_abi!(
+        x10 % 128 == 0,
+        ID,
+        "state pointer 0x{:08x} is unaligned",
+        x10
+    );
+
+    // self-check so that we do not touch ROM
+    ensure_abi!(x10 >= 1 << 21, ID, "pointer 0x{:08x} points into ROM", x10);
+
+    ensure_abi!(
+        x11 < SHA256_NUM_ROUNDS as u32,
+        ID,
+        "round index {} is out of range",
+        x11
+    );
+
+    let mut state_accesses: [RegisterOrIndirectReadWriteData; X10_NUM_WRITES] =
+        register_indirect_read_write_continuous::<_, X10_NUM_WRITES>(
+            x10 as usize,
+            memory_source,
+            trap,
+        );
+    if trap.is_a_trap() {
+        return Ok(());
+    }
+    let state_read_addresses: [u32; X10_NUM_WRITES] =
+        std::array::from_fn(|i| x10 + (core::mem::size_of::<u32>() * i) as u32);
+
+    let mut state_and_schedule = state_accesses.map(|el| el.read_value);
+    sha256_round_with_message_schedule(&mut state_and_schedule, x11 as usize);
+
+    // write back into our bookkeeping
+    for (src, dst) in state_and_schedule
+        .into_iter()
+        .zip(state_accesses.iter_mut())
+    {
+        dst.write_value = src;
+    }
+
+    // write down to RAM
+    write_indirect_accesses::<_, X10_NUM_WRITES>(
+        x10 as usize,
+        &state_accesses,
+        memory_source,
+        trap,
+    );
+    if trap.is_a_trap() {
+        return Ok(());
+    }
+
+    // make witness structures - there are no register writes, and round index is passed in x11
+    let mut register_accesses = [
+        RegisterOrIndirectReadWriteData {
+            read_value: x10,
+            write_value: x10,
+            timestamp: TimestampData::EMPTY,
+        },
+        RegisterOrIndirectReadWriteData {
+            read_value: x11,
+            write_value: x11,
+            timestamp: TimestampData::EMPTY,
+        },
+    ];
+
+    tracer.record_delegation(
+        SHA256_ROUND_FUNCTION_ACCESS_ID,
+        10,
+        &mut register_accesses,
+        &[],
+        &mut [],
+        &state_read_addresses,
+        &mut state_accesses,
+    );
+
+    Ok(())
+}
@@ -29,29 +29,44 @@ pub fn u256_ops_with_control_impl<
     _mmu: &mut MMU,
     rs1_value: u32,
     trap: &mut TrapReason,
-) {
-    assert_eq!(rs1_value, 0, "aligned memory access is unused");
+) -> Result<(), DelegationAbiViolation> {
+    const ID: u32 = U256_OPS_WITH_CONTROL_ACCESS_ID;
+
+    ensure_abi!(rs1_value == 0, ID, "aligned memory access is unused");
 
     // read registers first
     let x10 = state.registers[10];
     let x11 = state.registers[11];
     let x12 = state.registers[12];
 
-    assert!(x10 % 32 == 0, "input pointer is unaligned");
-    assert!(x11 % 32 == 0, "input pointer is unaligned");
+    ensure_abi!(
+        x10 % 32 == 0,
+        ID,
+        "input pointer 0x{:08x} is unaligned",
+        x10
+    );
+    ensure_abi!(
+        x11 % 32 == 0,
+        ID,
+        "input pointer 0x{:08x} is unaligned",
+        x11
+    );
 
     // self-check so that we do not touch ROM
-    assert!(x10 >= 1 << 21);
-    assert!(x11 >= 1 << 21);
+    ensure_abi!(x10 >= 1 << 21, ID, "pointer 0x{:08x} points into ROM", x10);
+    ensure_abi!(x11 >= 1 << 21, ID, "pointer 0x{:08x} points into ROM", x11);
 
-    assert!(x10 != x11);
+    ensure_abi!(x10 != x11, ID, "input pointers must not alias");
 
     let mut a_accesses: [RegisterOrIndirectReadWriteData; 8] =
-        register_indirect_read_write_continuous::<_, 8>(x10 as usize, memory_source);
+        register_indirect_read_write_continuous::<_, 8>(x10 as usize, memory_source, trap);
     let a_read_addresses: [u32; 8] =
         std::array::from_fn(|i| x10 + (core::mem::size_of::<u32>() * i) as u32);
     let mut b_accesses: [RegisterOrIndirectReadData; 8] =
-        register_indirect_read_continuous::<_, 8>(x11 as usize, memory_source);
+        register_indirect_read_continuous::<_, 8>(x11 as usize, memory_source, trap);
+    if trap.is_a_trap() {
+        return Ok(());
+    }
     let b_read_addresses: [u32; 8] =
         std::array::from_fn(|i| x11 + (core::mem::size_of::<u32>() * i) as u32);
 
@@ -90,14 +105,17 @@ pub fn u256_ops_with_control_impl<
 
     let result;
     let control_mask = x12;
-    assert!(
+    ensure_abi!(
         control_mask < (1 << NUM_CONTROL_BITS),
-        "control bits mask is too large"
+        ID,
+        "control bits mask 0b{:b} is too large",
+        control_mask
     );
-    assert_eq!(
-        (control_mask & !(1 << CARRY_BIT_IDX)).count_ones(),
-        1,
-        "at most one control bit must be set, except carry flag"
+    ensure_abi!(
+        (control_mask & !(1 << CARRY_BIT_IDX)).count_ones() == 1,
+        ID,
+        "at most one control bit must be set, except carry flag, but got 0b{:08b}",
+        control_mask
     );
     let carry_bit = control_mask & (1 << CARRY_BIT_IDX) != 0;
     let carry_or_borrow = U256::from(carry_bit as u64);
@@ -159,7 +177,10 @@ pub fn u256_ops_with_control_impl<
         h.write_value = (*src >> 32) as u32;
     }
 
-    write_indirect_accesses::<_, 8>(x10 as usize, &a_accesses, memory_source);
+    write_indirect_accesses::<_, 8>(x10 as usize, &a_accesses, memory_source, trap);
+    if trap.is_a_trap() {
+        return Ok(());
+    }
 
     // update register
     state.registers[12] = of as u32;
@@ -192,4 +213,6 @@ pub fn u256_ops_with_control_impl<
         &a_read_addresses,
         &mut a_accesses,
     );
+
+    Ok(())
 }
@@ -0,0 +1,75 @@
+use crate::cycle::state_new::RiscV32StateForUnrolledProver;
+use crate::delegations::keccak_f1600_round_function::{
+    keccak_f1600_round, KECCAK_F1600_NUM_ROUNDS,
+};
+use cs::definitions::TimestampData;
+
+use super::*;
+
+pub use crate::delegations::keccak_f1600_round_function::{
+    KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID, X10_NUM_WRITES,
+};
+
+pub fn keccak_f1600_round_function_over_unrolled_state<
+    M: MemorySource,
+    TR: Tracer<C>,
+    C: MachineConfig,
+>(
+    machine_state: &mut RiscV32StateForUnrolledProver<C>,
+    memory_source: &mut M,
+    tracer: &mut TR,
+) {
+    // read registers first
+    let x10 = machine_state.registers[10];
+    let x11 = machine_state.registers[11];
+
+    assert!(x10 % 256 == 0, "state pointer is unaligned");
+
+    // self-check so that we do not touch ROM
+    assert!(x10 >= 1 << 21);
+
+    assert!(
+        x11 < KECCAK_F1600_NUM_ROUNDS as u32,
+        "round index is out of range"
+    );
+
+    let mut state_accesses: [RegisterOrIndirectReadWriteData; X10_NUM_WRITES] =
+        register_indirect_read_write_continuous_noexcept::<_, X10_NUM_WRITES>(
+            x10 as usize,
+            memory_source,
+        );
+    let state_read_addresses: [u32; X10_NUM_WRITES] =
+        std::array::from_fn(|i| x10 + (core::mem::size_of::<u32>() * i) as u32);
+
+    let mut keccak_state = state_accesses.map(|el| el.read_value);
+    keccak_f1600_round(&mut keccak_state, x11 as usize);
+
+    // write back into our bookkeeping
+    for (src, dst) in keccak_state.into_iter().zip(state_accesses.iter_mut()) {
+        dst.write_value = src;
+    }
+
+    // write down to RAM
+    write_indirect_accesses_noexcept::<_, X10_NUM_WRITES>(
+        x10 as usize,
+        &state_accesses,
+        memory_source,
+    );
+
+    // make witness structures - there are no register writes
+    let mut register_accesses = [x10, x11].map(|value| RegisterOrIndirectReadWriteData {
+        read_value: value,
+        write_value: value,
+        timestamp: TimestampData::EMPTY,
+    });
+
+    tracer.record_delegation(
+        KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID,
+        10,
+        &mut register_accesses,
+        &[],
+        &mut [],
+        &state_read_addresses,
+        &mut state_accesses,
+    );
+}
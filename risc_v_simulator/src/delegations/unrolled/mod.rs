@@ -11,6 +11,9 @@ use std::mem::MaybeUninit;
 use std::ops::Range;
 
 pub mod blake2_round_function_with_compression_mode;
+pub mod keccak_f1600_round_function;
+pub mod poseidon2_permutation;
+pub mod sha256_round_function;
 pub mod u256_ops_with_control;
 
 #[derive(Clone, Copy, Debug)]
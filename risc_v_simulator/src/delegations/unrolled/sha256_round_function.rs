@@ -0,0 +1,83 @@
+use crate::cycle::state_new::RiscV32StateForUnrolledProver;
+use crate::delegations::sha256_round_function::sha256_round_with_message_schedule;
+use cs::definitions::TimestampData;
+
+use super::*;
+
+pub use crate::delegations::sha256_round_function::{
+    SHA256_NUM_ROUNDS, SHA256_ROUND_FUNCTION_ACCESS_ID, X10_NUM_WRITES,
+};
+
+pub fn sha256_round_function_over_unrolled_state<
+    M: MemorySource,
+    TR: Tracer<C>,
+    C: MachineConfig,
+>(
+    machine_state: &mut RiscV32StateForUnrolledProver<C>,
+    memory_source: &mut M,
+    tracer: &mut TR,
+) {
+    // read registers first
+    let x10 = machine_state.registers[10];
+    let x11 = machine_state.registers[11];
+
+    assert!(x10 % 128 == 0, "state pointer is unaligned");
+
+    // self-check so that we do not touch ROM
+    assert!(x10 >= 1 << 21);
+
+    assert!(
+        x11 < SHA256_NUM_ROUNDS as u32,
+        "round index is out of range"
+    );
+
+    let mut state_accesses: [RegisterOrIndirectReadWriteData; X10_NUM_WRITES] =
+        register_indirect_read_write_continuous_noexcept::<_, X10_NUM_WRITES>(
+            x10 as usize,
+            memory_source,
+        );
+    let state_read_addresses: [u32; X10_NUM_WRITES] =
+        std::array::from_fn(|i| x10 + (core::mem::size_of::<u32>() * i) as u32);
+
+    let mut state_and_schedule = state_accesses.map(|el| el.read_value);
+    sha256_round_with_message_schedule(&mut state_and_schedule, x11 as usize);
+
+    // write back into our bookkeeping
+    for (src, dst) in state_and_schedule
+        .into_iter()
+        .zip(state_accesses.iter_mut())
+    {
+        dst.write_value = src;
+    }
+
+    // write down to RAM
+    write_indirect_accesses_noexcept::<_, X10_NUM_WRITES>(
+        x10 as usize,
+        &state_accesses,
+        memory_source,
+    );
+
+    // make witness structures - there are no register writes
+    let mut register_accesses = [
+        RegisterOrIndirectReadWriteData {
+            read_value: x10,
+            write_value: x10,
+            timestamp: TimestampData::EMPTY,
+        },
+        RegisterOrIndirectReadWriteData {
+            read_value: x11,
+            write_value: x11,
+            timestamp: TimestampData::EMPTY,
+        },
+    ];
+
+    tracer.record_delegation(
+        SHA256_ROUND_FUNCTION_ACCESS_ID,
+        10,
+        &mut register_accesses,
+        &[],
+        &mut [],
+        &state_read_addresses,
+        &mut state_accesses,
+    );
+}
@@ -0,0 +1,318 @@
+use std::path::Path;
+
+use object::elf;
+use object::read::elf::{ElfFile32, FileHeader, ProgramHeader};
+use object::{Endianness, Object, ObjectSymbol, SymbolKind};
+
+use crate::abstractions::memory::VectorMemoryImpl;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ElfLoadError {
+    Io(std::io::ErrorKind),
+    NotAnElf,
+    UnsupportedClass,
+    UnsupportedMachine(u16),
+    UnsupportedFileType(u16),
+    Malformed(&'static str),
+    UnalignedBinary(usize),
+    SegmentOutOfMemory { paddr: u32, mem_size: u32 },
+    EntryPointMismatch { expected: u32, found: u32 },
+}
+
+impl std::fmt::Display for ElfLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "failed to read the program: {}", kind),
+            Self::NotAnElf => write!(f, "file is not an ELF"),
+            Self::UnsupportedClass => write!(f, "only little-endian ELF32 files are supported"),
+            Self::UnsupportedMachine(machine) => {
+                write!(f, "expected RISC-V machine type, got {}", machine)
+            }
+            Self::UnsupportedFileType(ty) => {
+                write!(
+                    f,
+                    "expected statically linked executable, got file type {}",
+                    ty
+                )
+            }
+            Self::Malformed(reason) => write!(f, "malformed ELF: {}", reason),
+            Self::UnalignedBinary(len) => {
+                write!(f, "flat binary length {} is not a multiple of 4", len)
+            }
+            Self::SegmentOutOfMemory { paddr, mem_size } => write!(
+                f,
+                "segment at 0x{:08x}..0x{:08x} is outside of the machine memory",
+                paddr,
+                *paddr as u64 + *mem_size as u64
+            ),
+            Self::EntryPointMismatch { expected, found } => write!(
+                f,
+                "entry point must be 0x{:08x}, but ELF header has 0x{:08x}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ElfLoadError {}
+
+/// Loadable (PT_LOAD) segment of the program. Initialized data is placed at the physical
+/// (load) address, same as `objcopy -O binary` does, and the rest of `mem_size` is zero-filled.
+#[derive(Clone, Debug)]
+pub struct LoadableSegment {
+    pub vaddr: u32,
+    pub paddr: u32,
+    pub mem_size: u32,
+    pub flags: u32,
+    pub data: Vec<u8>,
+}
+
+impl LoadableSegment {
+    pub fn is_executable(&self) -> bool {
+        self.flags & elf::PF_X != 0
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.flags & elf::PF_W != 0
+    }
+
+    fn check_bound(&self, memory_bound: u64) -> Result<(), ElfLoadError> {
+        if self.paddr as u64 + self.mem_size as u64 > memory_bound {
+            return Err(ElfLoadError::SegmentOutOfMemory {
+                paddr: self.paddr,
+                mem_size: self.mem_size,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElfSymbol {
+    pub name: String,
+    pub address: u32,
+    pub size: u32,
+    pub is_function: bool,
+}
+
+/// Program image as the machine sees it before the first cycle: where to start and what
+/// memory content is preloaded. Can be created either from the ELF executable or from the
+/// flat binary (e.g. output of `objcopy -O binary`).
+#[derive(Clone, Debug)]
+pub struct ProgramImage {
+    pub entry_point: u32,
+    pub segments: Vec<LoadableSegment>,
+    // Sorted by address, empty for flat binaries
+    pub symbols: Vec<ElfSymbol>,
+}
+
+impl ProgramImage {
+    pub fn is_elf(bytes: &[u8]) -> bool {
+        bytes.starts_with(&elf::ELFMAG)
+    }
+
+    /// Loads the image from file, detecting ELF executables by magic. Flat binaries
+    /// are mapped at `default_entry_point`, and execution starts from there.
+    pub fn from_path<P: AsRef<Path>>(
+        path: P,
+        default_entry_point: u32,
+    ) -> Result<Self, ElfLoadError> {
+        let bytes = std::fs::read(path).map_err(|e| ElfLoadError::Io(e.kind()))?;
+        Self::from_bytes(&bytes, default_entry_point)
+    }
+
+    pub fn from_bytes(bytes: &[u8], default_entry_point: u32) -> Result<Self, ElfLoadError> {
+        if Self::is_elf(bytes) {
+            Self::parse_elf(bytes)
+        } else {
+            if bytes.len() % 4 != 0 {
+                return Err(ElfLoadError::UnalignedBinary(bytes.len()));
+            }
+            Ok(Self::from_flat_binary(bytes, default_entry_point))
+        }
+    }
+
+    pub fn from_flat_binary(bytes: &[u8], load_address: u32) -> Self {
+        let segment = LoadableSegment {
+            vaddr: load_address,
+            paddr: load_address,
+            mem_size: bytes.len() as u32,
+            flags: elf::PF_R | elf::PF_X,
+            data: bytes.to_vec(),
+        };
+
+        Self {
+            entry_point: load_address,
+            segments: vec![segment],
+            symbols: vec![],
+        }
+    }
+
+    pub fn parse_elf(bytes: &[u8]) -> Result<Self, ElfLoadError> {
+        if !Self::is_elf(bytes) {
+            return Err(ElfLoadError::NotAnElf);
+        }
+        // EI_CLASS and EI_DATA bytes of e_ident
+        if bytes.len() < 16 || bytes[4] != elf::ELFCLASS32 || bytes[5] != elf::ELFDATA2LSB {
+            return Err(ElfLoadError::UnsupportedClass);
+        }
+
+        let header = elf::FileHeader32::<Endianness>::parse(bytes)
+            .map_err(|_| ElfLoadError::Malformed("invalid file header"))?;
+        let endian = header
+            .endian()
+            .map_err(|_| ElfLoadError::Malformed("invalid endianness"))?;
+
+        let machine = header.e_machine(endian);
+        if machine != elf::EM_RISCV {
+            return Err(ElfLoadError::UnsupportedMachine(machine));
+        }
+        let file_type = header.e_type(endian);
+        if file_type != elf::ET_EXEC {
+            return Err(ElfLoadError::UnsupportedFileType(file_type));
+        }
+
+        let program_headers = header
+            .program_headers(endian, bytes)
+            .map_err(|_| ElfLoadError::Malformed("invalid program headers"))?;
+
+        let mut segments = vec![];
+        for ph in program_headers.iter() {
+            if ph.p_type(endian) != elf::PT_LOAD {
+                continue;
+            }
+            let data = ph
+                .data(endian, bytes)
+                .map_err(|_| ElfLoadError::Malformed("segment data is out of file bounds"))?;
+            let mem_size = ph.p_memsz(endian);
+            if (data.len() as u32) > mem_size {
+                return Err(ElfLoadError::Malformed(
+                    "segment file size exceeds memory size",
+                ));
+            }
+            if ph.p_vaddr(endian).checked_add(mem_size).is_none()
+                || ph.p_paddr(endian).checked_add(mem_size).is_none()
+            {
+                return Err(ElfLoadError::Malformed("segment overflows address space"));
+            }
+
+            segments.push(LoadableSegment {
+                vaddr: ph.p_vaddr(endian),
+                paddr: ph.p_paddr(endian),
+                mem_size,
+                flags: ph.p_flags(endian),
+                data: data.to_vec(),
+            });
+        }
+
+        let file = ElfFile32::<Endianness>::parse(bytes)
+            .map_err(|_| ElfLoadError::Malformed("invalid section headers"))?;
+        let mut symbols: Vec<_> = file
+            .symbols()
+            .filter_map(|symbol| {
+                let name = symbol.name().ok()?;
+                if name.is_empty() || symbol.is_undefined() {
+                    return None;
+                }
+                Some(ElfSymbol {
+                    name: name.to_owned(),
+                    address: symbol.address() as u32,
+                    size: symbol.size() as u32,
+                    is_function: symbol.kind() == SymbolKind::Text,
+                })
+            })
+            .collect();
+        symbols.sort_by_key(|s| s.address);
+
+        Ok(Self {
+            entry_point: header.e_entry(endian),
+            segments,
+            symbols,
+        })
+    }
+
+    /// Finds the function symbol that covers the given address
+    pub fn function_at(&self, address: u32) -> Option<&ElfSymbol> {
+        let idx = self.symbols.partition_point(|s| s.address <= address);
+        self.symbols[..idx]
+            .iter()
+            .rev()
+            .filter(|s| s.is_function)
+            .find(|s| address < s.address.saturating_add(s.size.max(1)))
+    }
+
+    /// Places all the segments into RAM-only memory. Zero-initialized tails (BSS) are
+    /// not touched as memory is expected to be freshly allocated.
+    pub fn load_into(&self, memory: &mut VectorMemoryImpl) -> Result<(), ElfLoadError> {
+        let memory_bound = memory.inner.len() as u64 * 4;
+        for segment in self.segments.iter() {
+            segment.check_bound(memory_bound)?;
+            place_bytes(&mut memory.inner, segment.paddr, &segment.data);
+            memory.mark_touched(segment.paddr, segment.data.len());
+        }
+
+        Ok(())
+    }
+
+    /// Splits the image for machines that separate ROM and RAM: everything below `rom_bound`
+    /// goes into ROM (as words starting from address 0), and initialized data above it goes into RAM.
+    /// Zero-initialized parts of segments (BSS) only have to fit into `memory_bound`, as RAM is
+    /// zeroed before the execution.
+    pub fn memory_image(
+        &self,
+        rom_bound: u32,
+        memory_bound: u32,
+    ) -> Result<MemoryImage, ElfLoadError> {
+        assert_eq!(rom_bound % 4, 0);
+        assert!(rom_bound <= memory_bound);
+
+        let mut rom_end = 0u32;
+        for segment in self.segments.iter() {
+            segment.check_bound(memory_bound as u64)?;
+            let data_end = segment.paddr + segment.data.len() as u32;
+            if segment.data.is_empty() == false && segment.paddr < rom_bound {
+                rom_end = core::cmp::max(rom_end, core::cmp::min(data_end, rom_bound));
+            }
+        }
+
+        let mut rom = vec![0u32; rom_end.next_multiple_of(4) as usize / 4];
+        let mut ram = std::collections::BTreeMap::new();
+        for segment in self.segments.iter() {
+            let rom_part =
+                (rom_bound.saturating_sub(segment.paddr) as usize).min(segment.data.len());
+            let (rom_bytes, ram_bytes) = segment.data.split_at(rom_part);
+            place_bytes(&mut rom, segment.paddr, rom_bytes);
+            for (offset, byte) in ram_bytes.iter().enumerate() {
+                let byte_address = segment.paddr + (rom_part + offset) as u32;
+                let word = ram.entry(byte_address & !3).or_insert(0u32);
+                let shift = (byte_address % 4) * 8;
+                *word &= !(0xffu32 << shift);
+                *word |= (*byte as u32) << shift;
+            }
+        }
+        let ram = ram.into_iter().filter(|(_, value)| *value != 0).collect();
+
+        Ok(MemoryImage { rom, ram })
+    }
+}
+
+/// Initial memory content of the machine with separate ROM and RAM
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryImage {
+    // Words starting from address 0, not padded
+    pub rom: Vec<u32>,
+    // (address, value) of non-zero RAM words, sorted by address
+    pub ram: Vec<(u32, u32)>,
+}
+
+// Caller is responsible for checking that bytes fit into `dst`
+fn place_bytes(dst: &mut [u32], address: u32, bytes: &[u8]) {
+    for (offset, byte) in bytes.iter().enumerate() {
+        let byte_address = address as usize + offset;
+        let word = &mut dst[byte_address / 4];
+        let shift = (byte_address % 4) * 8;
+        *word &= !(0xffu32 << shift);
+        *word |= (*byte as u32) << shift;
+    }
+}
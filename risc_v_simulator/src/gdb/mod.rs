@@ -0,0 +1,548 @@
+//! GDB remote serial protocol server ("stub") that drives the simulator instruction by instruction.
+//! It's enough for stock `riscv32-*-gdb` to set breakpoints and watchpoints, single-step,
+//! and inspect or modify registers and memory:
+//!
+//! ```text
+//! (gdb) set architecture riscv:rv32
+//! (gdb) file app.elf
+//! (gdb) target remote 127.0.0.1:3333
+//! ```
+
+use std::collections::BTreeSet;
+use std::io;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::AssertUnwindSafe;
+
+use crate::abstractions::memory::{AccessType, MemorySource};
+use crate::abstractions::non_determinism::NonDeterminismCSRSource;
+use crate::cycle::state::{RiscV32State, NUM_REGISTERS};
+use crate::cycle::status_registers::TrapReason;
+use crate::cycle::MachineConfig;
+use crate::elf::ProgramImage;
+use crate::mmu::MMUImplementation;
+
+mod packet;
+mod watch;
+
+pub use self::packet::{DebuggerConnection, StdioConnection};
+pub use self::watch::{WatchKind, WatchedMemory, Watchpoint};
+
+use self::packet::*;
+use self::watch::WatchedNonDeterminismSource;
+
+const PC_REGISTER_NUMBER: usize = NUM_REGISTERS;
+// check for Ctrl-C from the debugger once per this many cycles when running freely
+const INTERRUPT_POLL_PERIOD: usize = 1 << 16;
+const MAX_PACKET_SIZE: usize = 0x4000;
+
+const SIGINT: u8 = 2;
+const SIGILL: u8 = 4;
+const SIGTRAP: u8 = 5;
+
+const ABI_REGISTER_NAMES: [&str; NUM_REGISTERS] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "fp", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+/// Waits for a single GDB connection on the given address
+pub fn accept_tcp_connection<A: ToSocketAddrs>(address: A) -> TcpStream {
+    let listener = TcpListener::bind(address).expect("must bind the debugger socket");
+    eprintln!(
+        "Waiting for GDB to connect on {}",
+        listener.local_addr().unwrap()
+    );
+    let (stream, peer) = listener.accept().expect("must accept GDB connection");
+    stream.set_nodelay(true).unwrap();
+    eprintln!("GDB connected from {}", peer);
+
+    stream
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    Step,
+    Breakpoint,
+    Watchpoint(Watchpoint),
+    Interrupted,
+    // PC didn't change over the cycle, same as in `Simulator::run`
+    EndOfExecution,
+    // Simulator can not continue from here (e.g. encountered an exception)
+    Fault,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExecutionStatus {
+    Running,
+    EndOfExecutionReported,
+    Faulted,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SessionControl {
+    Continue,
+    Detach,
+    Kill,
+}
+
+pub struct GdbStub<MS, MMU, ND, C>
+where
+    MS: MemorySource,
+    MMU: MMUImplementation<WatchedMemory<MS>, (), C>,
+    ND: NonDeterminismCSRSource<MS>,
+    C: MachineConfig,
+{
+    pub state: RiscV32State<C>,
+    pub memory: WatchedMemory<MS>,
+    pub mmu: MMU,
+    pub non_determinism_source: ND,
+    pub breakpoints: BTreeSet<u32>,
+    pub cycles: usize,
+    // only used to report where the machine stopped
+    program: ProgramImage,
+    status: ExecutionStatus,
+    last_stop: StopReason,
+}
+
+impl<MS, MMU, ND, C> GdbStub<MS, MMU, ND, C>
+where
+    MS: MemorySource,
+    MMU: MMUImplementation<WatchedMemory<MS>, (), C>,
+    ND: NonDeterminismCSRSource<MS>,
+    C: MachineConfig,
+{
+    pub fn new(
+        state: RiscV32State<C>,
+        memory: MS,
+        mmu: MMU,
+        non_determinism_source: ND,
+        program: ProgramImage,
+    ) -> Self {
+        Self {
+            state,
+            memory: WatchedMemory::new(memory),
+            mmu,
+            non_determinism_source,
+            breakpoints: BTreeSet::new(),
+            cycles: 0,
+            program,
+            status: ExecutionStatus::Running,
+            last_stop: StopReason::Step,
+        }
+    }
+
+    /// Performs a single cycle of the machine
+    pub fn step(&mut self) -> StopReason {
+        if self.status == ExecutionStatus::Faulted {
+            return StopReason::Fault;
+        }
+
+        let previous_pc = self.state.pc;
+        let mut non_determinism_source =
+            WatchedNonDeterminismSource(&mut self.non_determinism_source);
+        // anything that is still reported by panicking should not kill the debugging session
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            self.state.try_cycle(
+                &mut self.memory,
+                &mut (),
+                &mut self.mmu,
+                &mut non_determinism_source,
+            )
+        }));
+        self.cycles += 1;
+
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => {
+                eprintln!("Execution can not continue: {}", error);
+                self.status = ExecutionStatus::Faulted;
+                return StopReason::Fault;
+            }
+            Err(_) => {
+                self.status = ExecutionStatus::Faulted;
+                return StopReason::Fault;
+            }
+        }
+        if let Some(watchpoint) = self.memory.take_hit() {
+            return StopReason::Watchpoint(watchpoint);
+        }
+        if self.state.pc == previous_pc {
+            return StopReason::EndOfExecution;
+        }
+
+        StopReason::Step
+    }
+
+    fn resume<T: DebuggerConnection>(
+        &mut self,
+        stream: &mut PacketStream<T>,
+        single_step: bool,
+    ) -> StopReason {
+        loop {
+            let reason = self.step();
+            if reason != StopReason::Step || single_step {
+                return reason;
+            }
+            if self.breakpoints.contains(&self.state.pc) {
+                return StopReason::Breakpoint;
+            }
+            if self.cycles % INTERRUPT_POLL_PERIOD == 0 && stream.interrupt_requested() {
+                return StopReason::Interrupted;
+            }
+        }
+    }
+
+    fn stop_reply(&self, reason: StopReason) -> Vec<u8> {
+        match reason {
+            StopReason::Step | StopReason::Breakpoint | StopReason::EndOfExecution => {
+                format!("S{:02x}", SIGTRAP).into_bytes()
+            }
+            StopReason::Interrupted => format!("S{:02x}", SIGINT).into_bytes(),
+            StopReason::Fault => format!("S{:02x}", SIGILL).into_bytes(),
+            StopReason::Watchpoint(watchpoint) => {
+                let kind = match watchpoint.kind {
+                    WatchKind::Write => "watch",
+                    WatchKind::Read => "rwatch",
+                    WatchKind::Access => "awatch",
+                };
+                format!("T{:02x}{}:{:x};", SIGTRAP, kind, watchpoint.address).into_bytes()
+            }
+        }
+    }
+
+    fn report_stop(&self, reason: StopReason) {
+        let location = self
+            .program
+            .function_at(self.state.pc)
+            .map(|s| format!(" in {}", s.name))
+            .unwrap_or_default();
+        eprintln!(
+            "Stopped at PC = 0x{:08x}{} after {} cycles: {:?}",
+            self.state.pc, location, self.cycles, reason
+        );
+    }
+
+    fn read_register(&self, idx: usize) -> Option<u32> {
+        match idx {
+            0..NUM_REGISTERS => Some(self.state.registers[idx]),
+            PC_REGISTER_NUMBER => Some(self.state.pc),
+            _ => None,
+        }
+    }
+
+    fn write_register(&mut self, idx: usize, value: u32) -> bool {
+        match idx {
+            // x0 is hardwired
+            0 => {}
+            1..NUM_REGISTERS => self.state.registers[idx] = value,
+            PC_REGISTER_NUMBER => self.state.pc = value,
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn read_memory(&self, address: u32, len: u32) -> Option<Vec<u8>> {
+        let mut result = Vec::with_capacity(len as usize);
+        for offset in 0..len {
+            let byte_address = address.checked_add(offset)?;
+            let mut trap = TrapReason::NoTrap;
+            let word =
+                self.memory
+                    .inner
+                    .get((byte_address & !3) as u64, AccessType::MemLoad, &mut trap);
+            if trap.is_a_trap() {
+                // partial reads are allowed by the protocol
+                break;
+            }
+            result.push((word >> ((byte_address & 3) * 8)) as u8);
+        }
+
+        if result.is_empty() && len != 0 {
+            None
+        } else {
+            Some(result)
+        }
+    }
+
+    fn write_memory(&mut self, address: u32, bytes: &[u8]) -> bool {
+        for (offset, byte) in bytes.iter().enumerate() {
+            let Some(byte_address) = address.checked_add(offset as u32) else {
+                return false;
+            };
+            let word_address = (byte_address & !3) as u64;
+            let shift = (byte_address & 3) * 8;
+            let mut trap = TrapReason::NoTrap;
+            let word = self
+                .memory
+                .inner
+                .get(word_address, AccessType::MemLoad, &mut trap);
+            let word = (word & !(0xffu32 << shift)) | ((*byte as u32) << shift);
+            self.memory
+                .inner
+                .set(word_address, word, AccessType::MemStore, &mut trap);
+            if trap.is_a_trap() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn target_description() -> String {
+        let mut registers = String::new();
+        for (idx, name) in ABI_REGISTER_NAMES.iter().enumerate() {
+            let ty = match *name {
+                "sp" | "fp" => "data_ptr",
+                _ => "int",
+            };
+            registers.push_str(&format!(
+                "<reg name=\"{}\" bitsize=\"32\" type=\"{}\" regnum=\"{}\"/>",
+                name, ty, idx
+            ));
+        }
+        registers.push_str(&format!(
+            "<reg name=\"pc\" bitsize=\"32\" type=\"code_ptr\" regnum=\"{}\"/>",
+            PC_REGISTER_NUMBER
+        ));
+
+        format!(
+            "<?xml version=\"1.0\"?><!DOCTYPE target SYSTEM \"gdb-target.dtd\">\
+            <target version=\"1.0\"><architecture>riscv:rv32</architecture>\
+            <feature name=\"org.gnu.gdb.riscv.cpu\">{}</feature></target>",
+            registers
+        )
+    }
+
+    /// Serves the debugger session until GDB detaches or kills the target, or the connection is closed
+    pub fn serve<T: DebuggerConnection>(&mut self, connection: T) -> io::Result<()> {
+        let mut stream = PacketStream::new(connection);
+        while let Some(packet) = stream.read_packet()? {
+            let command = match packet {
+                Packet::Command(command) => command,
+                // we are not running, so just report where we are
+                Packet::Interrupt => {
+                    let reply = self.stop_reply(StopReason::Interrupted);
+                    stream.write_packet(&reply)?;
+                    continue;
+                }
+            };
+
+            let (reply, control) = self.handle_command(&command, &mut stream);
+            // kill request is the only one that is not acknowledged
+            if command != b"k" {
+                stream.write_packet(&reply)?;
+            }
+            if command == b"QStartNoAckMode" {
+                stream.set_no_ack_mode();
+            }
+            match control {
+                SessionControl::Continue => {}
+                SessionControl::Detach | SessionControl::Kill => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_command<T: DebuggerConnection>(
+        &mut self,
+        command: &[u8],
+        stream: &mut PacketStream<T>,
+    ) -> (Vec<u8>, SessionControl) {
+        const OK: &[u8] = b"OK";
+        const ERROR: &[u8] = b"E01";
+        const MEMORY_ERROR: &[u8] = b"E14";
+
+        let Some((&kind, args)) = command.split_first() else {
+            return (vec![], SessionControl::Continue);
+        };
+
+        let reply = match kind {
+            b'?' => self.stop_reply(self.last_stop),
+            b'g' => {
+                let mut bytes = vec![];
+                for idx in 0..=PC_REGISTER_NUMBER {
+                    bytes.extend(self.read_register(idx).unwrap().to_le_bytes());
+                }
+                encode_hex(&bytes)
+            }
+            b'G' => match decode_hex(args) {
+                Some(bytes) if bytes.len() >= (PC_REGISTER_NUMBER + 1) * 4 => {
+                    for (idx, chunk) in bytes.chunks_exact(4).enumerate() {
+                        self.write_register(idx, u32::from_le_bytes(chunk.try_into().unwrap()));
+                    }
+                    OK.to_vec()
+                }
+                _ => ERROR.to_vec(),
+            },
+            b'p' => match parse_hex_u32(args).and_then(|idx| self.read_register(idx as usize)) {
+                Some(value) => encode_hex(&value.to_le_bytes()),
+                None => ERROR.to_vec(),
+            },
+            b'P' => {
+                let parsed = split_once(args, b'=').and_then(|(idx, value)| {
+                    let idx = parse_hex_u32(idx)?;
+                    let value = decode_hex(value)?;
+                    let value: [u8; 4] = value.try_into().ok()?;
+                    Some((idx as usize, u32::from_le_bytes(value)))
+                });
+                match parsed {
+                    Some((idx, value)) if self.write_register(idx, value) => OK.to_vec(),
+                    _ => ERROR.to_vec(),
+                }
+            }
+            b'm' => match parse_address_and_len(args) {
+                Some((address, len)) => {
+                    let len = core::cmp::min(len, (MAX_PACKET_SIZE / 2) as u32);
+                    match self.read_memory(address, len) {
+                        Some(bytes) => encode_hex(&bytes),
+                        None => MEMORY_ERROR.to_vec(),
+                    }
+                }
+                None => ERROR.to_vec(),
+            },
+            b'M' | b'X' => {
+                let parsed = split_once(args, b':').and_then(|(header, data)| {
+                    let (address, len) = parse_address_and_len(header)?;
+                    let data = if kind == b'M' {
+                        decode_hex(data)?
+                    } else {
+                        data.to_vec()
+                    };
+                    (data.len() == len as usize).then_some((address, data))
+                });
+                match parsed {
+                    Some((address, data)) if self.write_memory(address, &data) => OK.to_vec(),
+                    Some(_) => MEMORY_ERROR.to_vec(),
+                    None => ERROR.to_vec(),
+                }
+            }
+            b'c' | b's' => {
+                if let Some(address) = parse_hex_u32(args) {
+                    self.state.pc = address;
+                }
+                match self.status {
+                    ExecutionStatus::EndOfExecutionReported => {
+                        // nothing can happen anymore, so report normal exit
+                        eprintln!("Program has finished after {} cycles", self.cycles);
+                        return (b"W00".to_vec(), SessionControl::Continue);
+                    }
+                    ExecutionStatus::Faulted => {
+                        return (
+                            format!("X{:02x}", SIGILL).into_bytes(),
+                            SessionControl::Continue,
+                        );
+                    }
+                    ExecutionStatus::Running => {}
+                }
+                let reason = self.resume(stream, kind == b's');
+                if reason == StopReason::EndOfExecution {
+                    self.status = ExecutionStatus::EndOfExecutionReported;
+                }
+                self.last_stop = reason;
+                self.report_stop(reason);
+                self.stop_reply(reason)
+            }
+            b'Z' | b'z' => {
+                let insert = kind == b'Z';
+                let mut parts = args.split(|b| *b == b',');
+                let parsed = (|| {
+                    let ty = parse_hex_u32(parts.next()?)?;
+                    let address = parse_hex_u32(parts.next()?)?;
+                    let len = parse_hex_u32(parts.next()?)?;
+                    Some((ty, address, len))
+                })();
+                match parsed {
+                    // software and hardware breakpoints are the same for us
+                    Some((0 | 1, address, _)) => {
+                        if insert {
+                            self.breakpoints.insert(address);
+                        } else {
+                            self.breakpoints.remove(&address);
+                        }
+                        OK.to_vec()
+                    }
+                    Some((ty @ 2..=4, address, len)) => {
+                        let kind = match ty {
+                            2 => WatchKind::Write,
+                            3 => WatchKind::Read,
+                            _ => WatchKind::Access,
+                        };
+                        let watchpoint = Watchpoint { address, len, kind };
+                        let watchpoints = &mut self.memory.watchpoints;
+                        if insert {
+                            watchpoints.push(watchpoint);
+                        } else if let Some(pos) = watchpoints.iter().position(|w| *w == watchpoint)
+                        {
+                            watchpoints.remove(pos);
+                        }
+                        OK.to_vec()
+                    }
+                    Some(_) => vec![],
+                    None => ERROR.to_vec(),
+                }
+            }
+            b'q' => self.handle_query(args),
+            b'Q' if command == b"QStartNoAckMode" => OK.to_vec(),
+            b'H' | b'T' => OK.to_vec(),
+            b'D' => return (OK.to_vec(), SessionControl::Detach),
+            b'k' => return (vec![], SessionControl::Kill),
+            b'v' if command == b"vKill" || command.starts_with(b"vKill;") => {
+                return (OK.to_vec(), SessionControl::Kill)
+            }
+            // empty reply means "not supported"
+            _ => vec![],
+        };
+
+        (reply, SessionControl::Continue)
+    }
+
+    fn handle_query(&self, query: &[u8]) -> Vec<u8> {
+        const TARGET_XML_QUERY: &[u8] = b"Xfer:features:read:target.xml:";
+
+        if query.starts_with(b"Supported") {
+            format!(
+                "PacketSize={:x};qXfer:features:read+;swbreak+;hwbreak+;QStartNoAckMode+",
+                MAX_PACKET_SIZE
+            )
+            .into_bytes()
+        } else if query == b"Attached" {
+            b"1".to_vec()
+        } else if query == b"C" {
+            b"QC1".to_vec()
+        } else if query == b"fThreadInfo" {
+            b"m1".to_vec()
+        } else if query == b"sThreadInfo" {
+            b"l".to_vec()
+        } else if query == b"Offsets" {
+            b"Text=0;Data=0;Bss=0".to_vec()
+        } else if let Some(range) = query.strip_prefix(TARGET_XML_QUERY) {
+            let Some((offset, len)) = parse_address_and_len(range) else {
+                return b"E01".to_vec();
+            };
+            let description = Self::target_description().into_bytes();
+            let start = core::cmp::min(offset as usize, description.len());
+            let end = core::cmp::min(start + len as usize, description.len());
+            let mut reply = if end == description.len() {
+                b"l".to_vec()
+            } else {
+                b"m".to_vec()
+            };
+            reply.extend_from_slice(&description[start..end]);
+            reply
+        } else {
+            vec![]
+        }
+    }
+}
+
+fn split_once(data: &[u8], separator: u8) -> Option<(&[u8], &[u8])> {
+    let pos = data.iter().position(|b| *b == separator)?;
+    Some((&data[..pos], &data[pos + 1..]))
+}
+
+fn parse_address_and_len(data: &[u8]) -> Option<(u32, u32)> {
+    let (address, len) = split_once(data, b',')?;
+    Some((parse_hex_u32(address)?, parse_hex_u32(len)?))
+}
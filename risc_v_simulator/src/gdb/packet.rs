@@ -0,0 +1,241 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+const INTERRUPT_BYTE: u8 = 0x03;
+const ESCAPE_BYTE: u8 = b'}';
+
+/// Transport for the GDB remote serial protocol. Anything that can read and write bytes
+/// can be used, but only some transports can be polled for the interrupt request (Ctrl-C)
+/// while the machine is running.
+pub trait DebuggerConnection: Read + Write {
+    // Must not block, returns `None` if there is no data available right now
+    fn try_read_byte(&mut self) -> Option<u8> {
+        None
+    }
+}
+
+impl<T: DebuggerConnection + ?Sized> DebuggerConnection for &mut T {
+    fn try_read_byte(&mut self) -> Option<u8> {
+        (**self).try_read_byte()
+    }
+}
+
+impl DebuggerConnection for TcpStream {
+    fn try_read_byte(&mut self) -> Option<u8> {
+        let mut byte = [0u8; 1];
+        self.set_nonblocking(true)
+            .expect("must switch socket into non-blocking mode");
+        let result = self.read(&mut byte);
+        self.set_nonblocking(false)
+            .expect("must switch socket into blocking mode");
+
+        matches!(result, Ok(1)).then_some(byte[0])
+    }
+}
+
+/// Serves the protocol over stdin/stdout, e.g. for `target remote | cli run ...`. Note
+/// that guest UART output is also printed into stdout, and GDB will skip it as junk
+/// between the packets, unless it contains protocol characters.
+pub struct StdioConnection {
+    stdin: io::Stdin,
+    stdout: io::Stdout,
+}
+
+impl StdioConnection {
+    pub fn new() -> Self {
+        Self {
+            stdin: io::stdin(),
+            stdout: io::stdout(),
+        }
+    }
+}
+
+impl Read for StdioConnection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stdin.read(buf)
+    }
+}
+
+impl Write for StdioConnection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stdout.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stdout.flush()
+    }
+}
+
+impl DebuggerConnection for StdioConnection {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Packet {
+    Command(Vec<u8>),
+    Interrupt,
+}
+
+pub(crate) struct PacketStream<T: DebuggerConnection> {
+    connection: T,
+    no_ack_mode: bool,
+    // bytes that were read while polling for the interrupt, but belong to the next packet
+    pending: VecDeque<u8>,
+}
+
+impl<T: DebuggerConnection> PacketStream<T> {
+    pub(crate) fn new(connection: T) -> Self {
+        Self {
+            connection,
+            no_ack_mode: false,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn set_no_ack_mode(&mut self) {
+        self.no_ack_mode = true;
+    }
+
+    /// Checks for the interrupt request without blocking. Anything else that was received
+    /// is kept and will be parsed by the next `read_packet`
+    pub(crate) fn interrupt_requested(&mut self) -> bool {
+        match self.connection.try_read_byte() {
+            Some(INTERRUPT_BYTE) => true,
+            Some(byte) => {
+                self.pending.push_back(byte);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        if let Some(byte) = self.pending.pop_front() {
+            return Ok(Some(byte));
+        }
+        let mut byte = [0u8; 1];
+        loop {
+            match self.connection.read(&mut byte) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(byte[0])),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns `None` if connection was closed
+    pub(crate) fn read_packet(&mut self) -> io::Result<Option<Packet>> {
+        loop {
+            let Some(byte) = self.read_byte()? else {
+                return Ok(None);
+            };
+            match byte {
+                INTERRUPT_BYTE => return Ok(Some(Packet::Interrupt)),
+                b'$' => {}
+                // acks and junk between the packets
+                _ => continue,
+            }
+
+            let mut data = vec![];
+            let mut checksum = 0u8;
+            let mut escaped = false;
+            loop {
+                let Some(byte) = self.read_byte()? else {
+                    return Ok(None);
+                };
+                if byte == b'#' && !escaped {
+                    break;
+                }
+                checksum = checksum.wrapping_add(byte);
+                if escaped {
+                    data.push(byte ^ 0x20);
+                    escaped = false;
+                } else if byte == ESCAPE_BYTE {
+                    escaped = true;
+                } else {
+                    data.push(byte);
+                }
+            }
+
+            let mut expected = [0u8; 2];
+            for dst in expected.iter_mut() {
+                let Some(byte) = self.read_byte()? else {
+                    return Ok(None);
+                };
+                *dst = byte;
+            }
+            let expected = std::str::from_utf8(&expected)
+                .ok()
+                .and_then(|s| u8::from_str_radix(s, 16).ok());
+
+            if self.no_ack_mode {
+                return Ok(Some(Packet::Command(data)));
+            }
+            if expected == Some(checksum) {
+                self.connection.write_all(b"+")?;
+                self.connection.flush()?;
+                return Ok(Some(Packet::Command(data)));
+            } else {
+                self.connection.write_all(b"-")?;
+                self.connection.flush()?;
+            }
+        }
+    }
+
+    pub(crate) fn write_packet(&mut self, data: &[u8]) -> io::Result<()> {
+        let mut encoded = Vec::with_capacity(data.len() + 4);
+        encoded.push(b'$');
+        let mut checksum = 0u8;
+        for byte in data.iter().copied() {
+            if matches!(byte, b'$' | b'#' | b'*' | ESCAPE_BYTE) {
+                encoded.push(ESCAPE_BYTE);
+                checksum = checksum.wrapping_add(ESCAPE_BYTE);
+                encoded.push(byte ^ 0x20);
+                checksum = checksum.wrapping_add(byte ^ 0x20);
+            } else {
+                encoded.push(byte);
+                checksum = checksum.wrapping_add(byte);
+            }
+        }
+        encoded.extend(format!("#{:02x}", checksum).into_bytes());
+
+        loop {
+            self.connection.write_all(&encoded)?;
+            self.connection.flush()?;
+            if self.no_ack_mode {
+                return Ok(());
+            }
+            match self.read_byte()? {
+                Some(b'-') => continue,
+                // anything else (including closed connection) means that we can not do better
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|b| format!("{:02x}", b).into_bytes())
+        .collect()
+}
+
+pub(crate) fn decode_hex(hex: &[u8]) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    hex.chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|s| u8::from_str_radix(s, 16).ok())
+        })
+        .collect()
+}
+
+pub(crate) fn parse_hex_u32(hex: &[u8]) -> Option<u32> {
+    std::str::from_utf8(hex)
+        .ok()
+        .and_then(|s| u32::from_str_radix(s, 16).ok())
+}
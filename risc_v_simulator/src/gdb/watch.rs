@@ -0,0 +1,116 @@
+use std::cell::Cell;
+
+use crate::abstractions::memory::{AccessType, MemorySource};
+use crate::abstractions::non_determinism::NonDeterminismCSRSource;
+use crate::cycle::status_registers::TrapReason;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchKind {
+    Write,
+    Read,
+    Access,
+}
+
+impl WatchKind {
+    fn matches(&self, is_write: bool) -> bool {
+        match self {
+            WatchKind::Write => is_write,
+            WatchKind::Read => !is_write,
+            WatchKind::Access => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub address: u32,
+    pub len: u32,
+    pub kind: WatchKind,
+}
+
+impl Watchpoint {
+    // memory is accessed by words, so sub-word accesses into the same word also trigger it
+    fn overlaps_word(&self, word_address: u64) -> bool {
+        let start = self.address as u64;
+        let end = start + core::cmp::max(self.len, 1) as u64;
+        start < word_address + 4 && word_address < end
+    }
+}
+
+/// Memory wrapper that remembers the first access that hits any of the watchpoints.
+/// Instruction fetches are never reported.
+pub struct WatchedMemory<M: MemorySource> {
+    pub inner: M,
+    pub watchpoints: Vec<Watchpoint>,
+    hit: Cell<Option<Watchpoint>>,
+}
+
+impl<M: MemorySource> WatchedMemory<M> {
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            watchpoints: vec![],
+            hit: Cell::new(None),
+        }
+    }
+
+    pub fn take_hit(&mut self) -> Option<Watchpoint> {
+        self.hit.take()
+    }
+
+    #[inline(always)]
+    fn check(&self, phys_address: u64, access_type: AccessType) {
+        if self.watchpoints.is_empty() || access_type == AccessType::Instruction {
+            return;
+        }
+        if self.hit.get().is_some() {
+            return;
+        }
+        let is_write = access_type == AccessType::MemStore;
+        if let Some(watchpoint) = self
+            .watchpoints
+            .iter()
+            .find(|w| w.kind.matches(is_write) && w.overlaps_word(phys_address & !3))
+        {
+            self.hit.set(Some(*watchpoint));
+        }
+    }
+}
+
+impl<M: MemorySource> MemorySource for WatchedMemory<M> {
+    #[inline(always)]
+    fn set(
+        &mut self,
+        phys_address: u64,
+        value: u32,
+        access_type: AccessType,
+        trap: &mut TrapReason,
+    ) {
+        self.check(phys_address, access_type);
+        self.inner.set(phys_address, value, access_type, trap);
+    }
+
+    #[inline(always)]
+    fn get(&self, phys_address: u64, access_type: AccessType, trap: &mut TrapReason) -> u32 {
+        self.check(phys_address, access_type);
+        self.inner.get(phys_address, access_type, trap)
+    }
+}
+
+// Non-determinism sources are defined over the original memory type, so we forward them
+pub(crate) struct WatchedNonDeterminismSource<'a, ND>(pub(crate) &'a mut ND);
+
+impl<'a, M: MemorySource, ND: NonDeterminismCSRSource<M>> NonDeterminismCSRSource<WatchedMemory<M>>
+    for WatchedNonDeterminismSource<'a, ND>
+{
+    const SHOULD_MOCK_READS_BEFORE_WRITES: bool = ND::SHOULD_MOCK_READS_BEFORE_WRITES;
+    const SHOULD_IGNORE_WRITES_AFTER_READS: bool = ND::SHOULD_IGNORE_WRITES_AFTER_READS;
+
+    fn read(&mut self) -> u32 {
+        self.0.read()
+    }
+
+    fn write_with_memory_access(&mut self, memory: &WatchedMemory<M>, value: u32) {
+        self.0.write_with_memory_access(&memory.inner, value)
+    }
+}
@@ -5,11 +5,16 @@
 
 pub mod abstractions;
 pub mod cycle;
+pub mod elf;
+pub mod gdb;
 pub mod mmio;
 pub mod mmu;
 mod qol;
+pub mod replay;
 pub mod runner;
 pub mod sim;
+pub mod snapshot;
+pub mod stats;
 pub mod utils;
 
 #[cfg(feature = "delegation")]
@@ -0,0 +1,272 @@
+//! Recording of the execution for deterministic replay. Program can only observe the outside world
+//! via the non-determinism CSR, so recording every read from and write into it together with the
+//! final machine state is enough to reproduce oracle-dependent execution without the oracle.
+
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use crate::abstractions::memory::MemorySource;
+use crate::abstractions::non_determinism::NonDeterminismCSRSource;
+
+/// Single interaction with the non-determinism source. Writes are also used for UART output
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OracleEvent {
+    Read(u32),
+    Write(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub cycle: u64,
+    pub event: OracleEvent,
+}
+
+/// Digest of the machine state after `cycle` cycles of execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateCheckpoint {
+    pub cycle: u64,
+    pub digest: u64,
+}
+
+/// Everything needed to replay the execution. `S` is the machine state, i.e. `RiscV32State`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionRecording<S> {
+    pub events: Vec<RecordedEvent>,
+    // state digests are taken every `checkpoint_period` cycles, so divergence that doesn't
+    // touch the oracle is localized up to this period
+    pub checkpoint_period: u64,
+    pub checkpoints: Vec<StateCheckpoint>,
+    pub cycles_limit: u64,
+    // number of executed cycles
+    pub cycles: u64,
+    // human readable `ExecutionOutcome`
+    pub outcome: String,
+    pub final_state: S,
+}
+
+/// FNV-1a, as digests must be stable between the runs and machines
+pub(crate) struct StateDigest(u64);
+
+impl StateDigest {
+    pub(crate) fn compute<T: Hash>(value: &T) -> u64 {
+        let mut hasher = Self(0xcbf29ce484222325);
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Hasher for StateDigest {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter() {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Wraps non-determinism source and records all the interactions with it
+#[derive(Clone, Debug)]
+pub struct RecordingSource<S> {
+    pub inner: S,
+    pub events: Vec<RecordedEvent>,
+    // updated by the runner before every cycle
+    pub cycle: u64,
+}
+
+impl<S> RecordingSource<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            events: Vec::new(),
+            cycle: 0,
+        }
+    }
+}
+
+impl<M: MemorySource, S: NonDeterminismCSRSource<M>> NonDeterminismCSRSource<M>
+    for RecordingSource<S>
+{
+    const SHOULD_MOCK_READS_BEFORE_WRITES: bool = S::SHOULD_MOCK_READS_BEFORE_WRITES;
+    const SHOULD_IGNORE_WRITES_AFTER_READS: bool = S::SHOULD_IGNORE_WRITES_AFTER_READS;
+
+    fn read(&mut self) -> u32 {
+        let value = self.inner.read();
+        self.events.push(RecordedEvent {
+            cycle: self.cycle,
+            event: OracleEvent::Read(value),
+        });
+
+        value
+    }
+
+    fn write_with_memory_access(&mut self, memory: &M, value: u32) {
+        self.events.push(RecordedEvent {
+            cycle: self.cycle,
+            event: OracleEvent::Write(value),
+        });
+        self.inner.write_with_memory_access(memory, value);
+    }
+}
+
+/// The first difference between the replay and the recording
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayMismatch {
+    Event {
+        cycle: u64,
+        expected: Option<RecordedEvent>,
+        actual: OracleEvent,
+    },
+    StateDigest {
+        cycle: u64,
+        expected: u64,
+        actual: u64,
+    },
+    UnconsumedEvents {
+        cycle: u64,
+        remaining: usize,
+    },
+    Outcome {
+        cycle: u64,
+        expected: String,
+        actual: String,
+    },
+    FinalState {
+        cycle: u64,
+        description: String,
+    },
+}
+
+impl ReplayMismatch {
+    pub fn cycle(&self) -> u64 {
+        match self {
+            Self::Event { cycle, .. }
+            | Self::StateDigest { cycle, .. }
+            | Self::UnconsumedEvents { cycle, .. }
+            | Self::Outcome { cycle, .. }
+            | Self::FinalState { cycle, .. } => *cycle,
+        }
+    }
+}
+
+impl std::fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Event {
+                cycle,
+                expected: Some(expected),
+                actual,
+            } => write!(
+                f,
+                "cycle {}: oracle access {:?}, but recorded {:?} at cycle {}",
+                cycle, actual, expected.event, expected.cycle
+            ),
+            Self::Event {
+                cycle,
+                expected: None,
+                actual,
+            } => write!(
+                f,
+                "cycle {}: oracle access {:?} after the end of recording",
+                cycle, actual
+            ),
+            Self::StateDigest {
+                cycle,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "cycle {}: state digest 0x{:016x}, but recorded 0x{:016x}",
+                cycle, actual, expected
+            ),
+            Self::UnconsumedEvents { cycle, remaining } => write!(
+                f,
+                "cycle {}: execution ended with {} recorded oracle accesses left",
+                cycle, remaining
+            ),
+            Self::Outcome {
+                cycle,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "cycle {}: execution {}, but recorded {}",
+                cycle, actual, expected
+            ),
+            Self::FinalState { cycle, description } => {
+                write!(f, "cycle {}: final state differs: {}", cycle, description)
+            }
+        }
+    }
+}
+
+/// Non-determinism source that plays back recorded reads and checks that writes are the same.
+/// It can be used with any runner, and if runner doesn't update the `cycle`, then mismatches
+/// are reported at the cycle of the recorded event. It uses the default oracle access policy,
+/// same as the recorded source
+#[derive(Clone, Debug)]
+pub struct ReplaySource {
+    events: VecDeque<RecordedEvent>,
+    pub cycle: Option<u64>,
+    pub mismatch: Option<ReplayMismatch>,
+}
+
+impl ReplaySource {
+    pub fn new(events: &[RecordedEvent]) -> Self {
+        Self {
+            events: events.iter().copied().collect(),
+            cycle: None,
+            mismatch: None,
+        }
+    }
+
+    pub fn num_remaining_events(&self) -> usize {
+        self.events.len()
+    }
+
+    fn next_event(&mut self, actual: OracleEvent) -> Option<RecordedEvent> {
+        let expected = self.events.pop_front();
+        let matches = match expected {
+            Some(expected) => {
+                expected.event == actual && self.cycle.map_or(true, |c| c == expected.cycle)
+            }
+            None => false,
+        };
+        if matches == false && self.mismatch.is_none() {
+            let cycle = self.cycle.or(expected.map(|e| e.cycle)).unwrap_or_default();
+            self.mismatch = Some(ReplayMismatch::Event {
+                cycle,
+                expected,
+                actual,
+            });
+        }
+
+        expected
+    }
+}
+
+impl<M: MemorySource> NonDeterminismCSRSource<M> for ReplaySource {
+    fn read(&mut self) -> u32 {
+        // we do not know the value that the program wants to read, so only the kind of access is checked
+        let expected = self.events.front().copied();
+        let value = match expected {
+            Some(RecordedEvent {
+                event: OracleEvent::Read(value),
+                ..
+            }) => value,
+            _ => 0,
+        };
+        self.next_event(OracleEvent::Read(value));
+
+        value
+    }
+
+    fn write_with_memory_access(&mut self, _memory: &M, value: u32) {
+        self.next_event(OracleEvent::Write(value));
+    }
+}
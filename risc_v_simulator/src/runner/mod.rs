@@ -1,26 +1,61 @@
-use std::path::Path;
-
 use crate::abstractions::non_determinism::NonDeterminismCSRSource;
 use crate::abstractions::non_determinism::QuasiUARTSource;
+use crate::abstractions::non_determinism::ResumableNonDeterminismSource;
 use crate::cycle::state::StateTracer;
 use crate::cycle::IMStandardIsaConfig;
 use crate::cycle::MachineConfig;
+use crate::elf::ProgramImage;
+use crate::gdb::{DebuggerConnection, GdbStub};
 use crate::mmu::NoMMU;
+use crate::replay::{
+    ExecutionRecording, RecordingSource, ReplayMismatch, ReplaySource, StateCheckpoint, StateDigest,
+};
+use crate::sim::ExecutionOutcome;
 use crate::sim::Simulator;
 use crate::sim::SimulatorConfig;
+use crate::snapshot::ExecutionSnapshot;
+use crate::stats::{CircuitChunkSizes, ExecutionStats, ExecutionStatsTracer};
 use crate::{abstractions::memory::VectorMemoryImpl, cycle::state::RiscV32State};
 
 pub const DEFAULT_ENTRY_POINT: u32 = 0x01000000;
 pub const CUSTOM_ENTRY_POINT: u32 = 0;
 
+// for ELF executables entry point comes from the header
+fn load_image(config: &SimulatorConfig) -> ProgramImage {
+    ProgramImage::from_path(&config.bin_path, config.entry_point).unwrap_or_else(|e| {
+        panic!(
+            "failed to load the program from {}: {}",
+            config.bin_path.display(),
+            e
+        )
+    })
+}
+
+fn memory_with_image(image: &ProgramImage) -> VectorMemoryImpl {
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 30); // use 1 GB RAM
+    image
+        .load_into(&mut memory)
+        .unwrap_or_else(|e| panic!("failed to load the program: {}", e));
+
+    memory
+}
+
+fn load_program(config: &SimulatorConfig) -> (ProgramImage, VectorMemoryImpl) {
+    let image = load_image(config);
+    let memory = memory_with_image(&image);
+
+    (image, memory)
+}
+
 pub fn run_simple_simulator(config: SimulatorConfig) -> [u32; 8] {
     run_simple_with_entry_point(config)
 }
 
+// Panics if program doesn't finish normally
 pub fn run_simple_with_entry_point(config: SimulatorConfig) -> [u32; 8] {
-    let (_, state) =
+    let (_, outcome) =
         run_simple_with_entry_point_and_non_determimism_source(config, QuasiUARTSource::default());
-    let registers = state.registers;
+    let registers = outcome.expect_finished();
     [
         registers[10],
         registers[11],
@@ -38,7 +73,7 @@ pub fn run_simple_with_entry_point_and_non_determimism_source<
 >(
     config: SimulatorConfig,
     non_determinism_source: S,
-) -> (S, RiscV32State<IMStandardIsaConfig>) {
+) -> (S, ExecutionOutcome) {
     run_simple_with_entry_point_and_non_determimism_source_for_config::<S, IMStandardIsaConfig>(
         config,
         non_determinism_source,
@@ -51,13 +86,73 @@ pub fn run_simple_with_entry_point_and_non_determimism_source_for_config<
 >(
     config: SimulatorConfig,
     non_determinism_source: S,
-) -> (S, RiscV32State<C>) {
-    let state = RiscV32State::<C>::initial(config.entry_point);
+) -> (S, ExecutionOutcome) {
+    let (image, memory) = load_program(&config);
+    let state = RiscV32State::<C>::initial(image.entry_point);
     let memory_tracer = ();
     let mmu = NoMMU { sapt: 0 };
 
-    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 30); // use 1 GB RAM
-    memory.load_image(config.entry_point, read_bin(&config.bin_path).into_iter());
+    let mut sim = Simulator::new(
+        config,
+        state,
+        memory,
+        memory_tracer,
+        mmu,
+        non_determinism_source,
+    );
+
+    let outcome = sim.run(|_, _| {}, |_, _| {});
+
+    (sim.non_determinism_source, outcome)
+}
+
+/// Same as `run_simple_with_entry_point_and_non_determimism_source_for_config`, but also collects
+/// execution statistics, including the estimate of the number of circuits to prove the execution
+pub fn run_with_stats_for_config<S: NonDeterminismCSRSource<VectorMemoryImpl>, C: MachineConfig>(
+    config: SimulatorConfig,
+    non_determinism_source: S,
+    chunk_sizes: CircuitChunkSizes,
+) -> (S, ExecutionOutcome, ExecutionStats) {
+    let (image, memory) = load_program(&config);
+    let state = RiscV32State::<C>::initial(image.entry_point);
+    let memory_tracer = ExecutionStatsTracer::new(chunk_sizes);
+    let mmu = NoMMU { sapt: 0 };
+
+    let mut sim = Simulator::new(
+        config,
+        state,
+        memory,
+        memory_tracer,
+        mmu,
+        non_determinism_source,
+    );
+
+    let outcome = sim.run(|_, _| {}, |_, _| {});
+
+    (
+        sim.non_determinism_source,
+        outcome,
+        sim.memory_tracer.finish(),
+    )
+}
+
+/// Same as `run_simple_with_entry_point_and_non_determimism_source_for_config`, but also captures
+/// the snapshot of the machine every `snapshot_period` cycles
+pub fn run_simple_with_snapshots_for_config<
+    S: NonDeterminismCSRSource<VectorMemoryImpl> + ResumableNonDeterminismSource,
+    C: MachineConfig,
+>(
+    config: SimulatorConfig,
+    non_determinism_source: S,
+    snapshot_period: usize,
+    mut on_snapshot: impl FnMut(ExecutionSnapshot<RiscV32State<C>>),
+) -> (S, ExecutionOutcome) {
+    assert!(snapshot_period > 0);
+
+    let (image, memory) = load_program(&config);
+    let state = RiscV32State::<C>::initial(image.entry_point);
+    let memory_tracer = ();
+    let mmu = NoMMU { sapt: 0 };
 
     let mut sim = Simulator::new(
         config,
@@ -68,9 +163,241 @@ pub fn run_simple_with_entry_point_and_non_determimism_source_for_config<
         non_determinism_source,
     );
 
-    sim.run(|_, _| {}, |_, _| {});
+    let outcome = sim.run(
+        |_, _| {},
+        |sim, cycle| {
+            let cycles_done = cycle + 1;
+            if cycles_done % snapshot_period == 0 {
+                on_snapshot(ExecutionSnapshot {
+                    cycle: cycles_done as u64,
+                    state: sim.state,
+                    memory: sim.memory_source.snapshot(),
+                    non_determinism_position: sim.non_determinism_source.num_words_read(),
+                    non_determinism_write_state: sim.non_determinism_source.write_state(),
+                });
+            }
+        },
+    );
 
-    (sim.non_determinism_source, sim.state)
+    (sim.non_determinism_source, outcome)
+}
+
+/// Continues execution from the snapshot. Non-determinism source must be created from the same
+/// inputs as the one used for the original execution, and it will be fast-forwarded to the snapshot
+/// position. `config.cycles` is the total cycles budget, including cycles before the snapshot.
+pub fn resume_from_snapshot_for_config<
+    S: NonDeterminismCSRSource<VectorMemoryImpl> + ResumableNonDeterminismSource,
+    C: MachineConfig,
+>(
+    mut config: SimulatorConfig,
+    snapshot: &ExecutionSnapshot<RiscV32State<C>>,
+    mut non_determinism_source: S,
+) -> (S, ExecutionOutcome) {
+    config.cycles = config.cycles.saturating_sub(snapshot.cycle as usize);
+    let memory_tracer = ();
+    let mmu = NoMMU {
+        sapt: snapshot.state.sapt,
+    };
+
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 30); // use 1 GB RAM
+    memory.restore_from_snapshot(&snapshot.memory);
+    non_determinism_source.skip_words(snapshot.non_determinism_position);
+    non_determinism_source.restore_write_state(&snapshot.non_determinism_write_state);
+
+    let mut sim = Simulator::new(
+        config,
+        snapshot.state,
+        memory,
+        memory_tracer,
+        mmu,
+        non_determinism_source,
+    );
+
+    let outcome = match sim.run(|_, _| {}, |_, _| {}) {
+        ExecutionOutcome::Finished { cycles, registers } => ExecutionOutcome::Finished {
+            cycles: cycles + snapshot.cycle as usize,
+            registers,
+        },
+        outcome => outcome,
+    };
+
+    (sim.non_determinism_source, outcome)
+}
+
+/// Same as `run_simple_with_entry_point_and_non_determimism_source_for_config`, but also records
+/// all the accesses to the non-determinism source, digests of the state every `checkpoint_period`
+/// cycles and the final state, so the execution can be replayed without the original source
+pub fn run_and_record_for_config<S: NonDeterminismCSRSource<VectorMemoryImpl>, C: MachineConfig>(
+    config: SimulatorConfig,
+    non_determinism_source: S,
+    checkpoint_period: u64,
+) -> (S, ExecutionOutcome, ExecutionRecording<RiscV32State<C>>) {
+    assert!(checkpoint_period > 0);
+    // replay source can only mimic the default behavior
+    assert!(
+        <S as NonDeterminismCSRSource<VectorMemoryImpl>>::SHOULD_MOCK_READS_BEFORE_WRITES
+            && <S as NonDeterminismCSRSource<VectorMemoryImpl>>::SHOULD_IGNORE_WRITES_AFTER_READS,
+        "only sources with default oracle access policy can be recorded"
+    );
+
+    let cycles_limit = config.cycles as u64;
+    let (image, memory) = load_program(&config);
+    let state = RiscV32State::<C>::initial(image.entry_point);
+    let memory_tracer = ();
+    let mmu = NoMMU { sapt: 0 };
+
+    let mut sim = Simulator::new(
+        config,
+        state,
+        memory,
+        memory_tracer,
+        mmu,
+        RecordingSource::new(non_determinism_source),
+    );
+
+    let mut cycles = 0u64;
+    let mut checkpoints = vec![];
+    let outcome = sim.run(
+        |sim, cycle| sim.non_determinism_source.cycle = cycle as u64,
+        |sim, cycle| {
+            cycles = cycle as u64 + 1;
+            if cycles % checkpoint_period == 0 {
+                checkpoints.push(StateCheckpoint {
+                    cycle: cycles,
+                    digest: StateDigest::compute(&sim.state),
+                });
+            }
+        },
+    );
+
+    let recording = ExecutionRecording {
+        events: std::mem::take(&mut sim.non_determinism_source.events),
+        checkpoint_period,
+        checkpoints,
+        cycles_limit,
+        cycles,
+        outcome: outcome.to_string(),
+        final_state: sim.state,
+    };
+
+    (sim.non_determinism_source.inner, outcome, recording)
+}
+
+/// Replays the recorded execution of the program at `config.bin_path`. Cycles limit is taken from
+/// the recording. Returns the outcome and the first difference from the recording, if any
+pub fn replay_for_config<C: MachineConfig>(
+    mut config: SimulatorConfig,
+    recording: &ExecutionRecording<RiscV32State<C>>,
+) -> (ExecutionOutcome, Result<(), ReplayMismatch>) {
+    config.cycles = recording.cycles_limit as usize;
+    let (image, memory) = load_program(&config);
+    let state = RiscV32State::<C>::initial(image.entry_point);
+    let memory_tracer = ();
+    let mmu = NoMMU { sapt: 0 };
+
+    let mut sim = Simulator::new(
+        config,
+        state,
+        memory,
+        memory_tracer,
+        mmu,
+        ReplaySource::new(&recording.events),
+    );
+
+    let mut cycles = 0u64;
+    let mut checkpoints = recording.checkpoints.iter().peekable();
+    let outcome = sim.run(
+        |sim, cycle| sim.non_determinism_source.cycle = Some(cycle as u64),
+        |sim, cycle| {
+            cycles = cycle as u64 + 1;
+            let Some(checkpoint) = checkpoints.next_if(|c| c.cycle == cycles) else {
+                return;
+            };
+            let digest = StateDigest::compute(&sim.state);
+            if digest != checkpoint.digest && sim.non_determinism_source.mismatch.is_none() {
+                sim.non_determinism_source.mismatch = Some(ReplayMismatch::StateDigest {
+                    cycle: cycles,
+                    expected: checkpoint.digest,
+                    actual: digest,
+                });
+            }
+        },
+    );
+
+    let source = &sim.non_determinism_source;
+    let result = if let Some(mismatch) = source.mismatch.clone() {
+        Err(mismatch)
+    } else if source.num_remaining_events() != 0 {
+        Err(ReplayMismatch::UnconsumedEvents {
+            cycle: cycles,
+            remaining: source.num_remaining_events(),
+        })
+    } else if outcome.to_string() != recording.outcome {
+        Err(ReplayMismatch::Outcome {
+            cycle: cycles,
+            expected: recording.outcome.clone(),
+            actual: outcome.to_string(),
+        })
+    } else if let Some(description) = describe_state_difference(&recording.final_state, &sim.state)
+    {
+        Err(ReplayMismatch::FinalState {
+            cycle: cycles,
+            description,
+        })
+    } else {
+        Ok(())
+    };
+
+    (outcome, result)
+}
+
+fn describe_state_difference<C: MachineConfig>(
+    expected: &RiscV32State<C>,
+    actual: &RiscV32State<C>,
+) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+
+    let description = if expected.pc != actual.pc {
+        format!(
+            "PC = 0x{:08x}, but recorded 0x{:08x}",
+            actual.pc, expected.pc
+        )
+    } else if let Some(idx) =
+        (0..expected.registers.len()).find(|idx| expected.registers[*idx] != actual.registers[*idx])
+    {
+        format!(
+            "x{} = 0x{:08x}, but recorded 0x{:08x}",
+            idx, actual.registers[idx], expected.registers[idx]
+        )
+    } else {
+        "status or trap registers differ".to_owned()
+    };
+
+    Some(description)
+}
+
+/// Same as `run_simple_with_entry_point_and_non_determimism_source_for_config`, but the execution
+/// is driven by the debugger over the GDB remote serial protocol. Returns when the debugger detaches.
+pub fn run_with_gdb_stub_for_config<
+    S: NonDeterminismCSRSource<VectorMemoryImpl>,
+    C: MachineConfig,
+    T: DebuggerConnection,
+>(
+    config: SimulatorConfig,
+    non_determinism_source: S,
+    connection: T,
+) -> (S, RiscV32State<C>) {
+    let (image, memory) = load_program(&config);
+    let state = RiscV32State::<C>::initial(image.entry_point);
+    let mmu = NoMMU { sapt: 0 };
+
+    let mut stub = GdbStub::new(state, memory, mmu, non_determinism_source, image);
+    stub.serve(connection).expect("debugger connection failed");
+    eprintln!("Debugger session ended after {} cycles", stub.cycles);
+
+    (stub.non_determinism_source, stub.state)
 }
 
 pub fn run_simple_for_num_cycles<S: NonDeterminismCSRSource<VectorMemoryImpl>, C: MachineConfig>(
@@ -78,35 +405,42 @@ pub fn run_simple_for_num_cycles<S: NonDeterminismCSRSource<VectorMemoryImpl>, C
     entry_point: u32,
     cycles: usize,
     mut non_determinism_source: S,
-) -> RiscV32State<C> {
-    let mut state = RiscV32State::<C>::initial(entry_point);
+) -> (RiscV32State<C>, ExecutionOutcome) {
+    let image = ProgramImage::from_bytes(binary, entry_point)
+        .unwrap_or_else(|e| panic!("failed to load the program: {}", e));
+    let mut state = RiscV32State::<C>::initial(image.entry_point);
     let mut memory_tracer = ();
     let mut mmu = NoMMU { sapt: 0 };
 
-    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 30); // use 1 GB RAM
-    memory.load_image(entry_point, binary.iter().copied());
+    let mut memory = memory_with_image(&image);
 
-    let mut previous_pc = entry_point;
-    let mut cycle_counter = 0u64;
+    let mut previous_pc = image.entry_point;
 
-    for _cycle in 0..cycles as usize {
-        cycle_counter += 1;
-        RiscV32State::<C>::cycle(
+    for cycle in 0..cycles {
+        let result = RiscV32State::<C>::try_cycle(
             &mut state,
             &mut memory,
             &mut memory_tracer,
             &mut mmu,
             &mut non_determinism_source,
         );
+        if let Err(error) = result {
+            println!("Execution terminated at cycle {}: {}", cycle, error);
+            return (state, error.into());
+        }
 
         if state.pc == previous_pc {
-            println!("Took {} cycles to finish", cycle_counter);
-            break;
+            println!("Took {} cycles to finish", cycle);
+            let outcome = ExecutionOutcome::Finished {
+                cycles: cycle,
+                registers: state.registers,
+            };
+            return (state, outcome);
         }
         previous_pc = state.pc;
     }
 
-    state
+    (state, ExecutionOutcome::CycleLimitExceeded)
 }
 
 // pub fn run_simple_with_entry_point_with_delegation_and_non_determimism_source<
@@ -136,20 +470,19 @@ pub fn run_simple_for_num_cycles<S: NonDeterminismCSRSource<VectorMemoryImpl>, C
 //     sim.non_determinism_source
 // }
 
-pub fn run_simulator_with_traces(config: SimulatorConfig) -> (StateTracer, ()) {
+pub fn run_simulator_with_traces(config: SimulatorConfig) -> (StateTracer, ExecutionOutcome) {
     run_simulator_with_traces_for_config(config)
 }
 
 pub fn run_simulator_with_traces_for_config<C: MachineConfig>(
     config: SimulatorConfig,
-) -> (StateTracer<C>, ()) {
+) -> (StateTracer<C>, ExecutionOutcome) {
     let state = RiscV32State::<C>::initial(CUSTOM_ENTRY_POINT);
     let memory_tracer = ();
     let mmu = NoMMU { sapt: state.sapt };
     let non_determinism_source = QuasiUARTSource::default();
 
-    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 30); // use 1 GB RAM
-    memory.load_image(config.entry_point, read_bin(&config.bin_path).into_iter());
+    let (_, memory) = load_program(&config);
 
     let cycles = config.cycles;
     println!("Will run for up to {} cycles", cycles);
@@ -166,7 +499,7 @@ pub fn run_simulator_with_traces_for_config<C: MachineConfig>(
     let mut state_tracer = StateTracer::new_for_num_cycles(cycles + 1);
     state_tracer.insert(0, sim.state);
 
-    sim.run(
+    let outcome = sim.run(
         |_, _| {},
         |sim, cycle| {
             println!("mtvec: {:?}", sim.state.machine_mode_trap_data.setup.tvec);
@@ -174,17 +507,5 @@ pub fn run_simulator_with_traces_for_config<C: MachineConfig>(
         },
     );
 
-    (state_tracer, sim.memory_tracer)
-}
-
-fn read_bin<P: AsRef<Path>>(path: P) -> Vec<u8> {
-    dbg!(path.as_ref());
-    let mut file = std::fs::File::open(path).expect("must open provided file");
-    let mut buffer = vec![];
-    std::io::Read::read_to_end(&mut file, &mut buffer).expect("must read the file");
-
-    assert_eq!(buffer.len() % 4, 0);
-    dbg!(buffer.len() / 4);
-
-    buffer
+    (state_tracer, outcome)
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use crate::cycle::IMStandardIsaConfig;
@@ -6,13 +7,16 @@ use crate::{
     abstractions::{
         memory::MemorySource, non_determinism::NonDeterminismCSRSource, tracer::Tracer,
     },
-    cycle::state::RiscV32State,
+    cycle::state::{CycleError, RiscV32State, NUM_REGISTERS},
+    cycle::status_registers::TrapReason,
     mmu::MMUImplementation,
     runner::DEFAULT_ENTRY_POINT,
 };
 
 use self::diag::Profiler;
 
+mod profile_formats;
+
 pub(crate) struct Simulator<MS, TR, MMU, ND, C: MachineConfig = IMStandardIsaConfig>
 where
     MS: MemorySource,
@@ -58,13 +62,17 @@ where
         }
     }
 
-    pub(crate) fn run<FnPre, FnPost>(&mut self, mut fn_pre: FnPre, mut fn_post: FnPost)
+    pub(crate) fn run<FnPre, FnPost>(
+        &mut self,
+        mut fn_pre: FnPre,
+        mut fn_post: FnPost,
+    ) -> ExecutionOutcome
     where
         FnPre: FnMut(&mut Self, usize),
         FnPost: FnMut(&mut Self, usize),
     {
         let mut previous_pc = self.state.pc;
-        let mut end_of_execution_reached = false;
+        let mut outcome = ExecutionOutcome::CycleLimitExceeded;
 
         for cycle in 0..self.cycles as usize {
             if let Some(profiler) = self.profiler.as_mut() {
@@ -79,39 +87,111 @@ where
 
             fn_pre(self, cycle);
 
-            RiscV32State::<C>::cycle(
+            let result = RiscV32State::<C>::try_cycle(
                 &mut self.state,
                 &mut self.memory_source,
                 &mut self.memory_tracer,
                 &mut self.mmu,
                 &mut self.non_determinism_source,
             );
+            if let Err(error) = result {
+                println!("Execution terminated at cycle {}: {}", cycle, error);
+                outcome = error.into();
+                break;
+            }
 
             fn_post(self, cycle);
 
             if self.state.pc == previous_pc {
-                end_of_execution_reached = true;
                 println!("Took {} cycles to finish", cycle);
+                // last cycle is an infinite loop that only marks the end of execution
+                outcome = ExecutionOutcome::Finished {
+                    cycles: cycle,
+                    registers: self.state.registers,
+                };
                 break;
             }
             previous_pc = self.state.pc;
         }
 
-        assert!(
-            end_of_execution_reached,
-            "program failed to each the end of execution over {} cycles",
-            self.cycles
-        );
+        if outcome == ExecutionOutcome::CycleLimitExceeded {
+            println!(
+                "Program failed to reach the end of execution over {} cycles",
+                self.cycles
+            );
+        }
 
         if let Some(profiler) = self.profiler.as_mut() {
             println!("Profiler begins execution");
             profiler.print_stats();
             profiler.write_stacktrace();
         }
+
+        outcome
+    }
+}
+
+/// How the program execution has ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionOutcome {
+    Finished {
+        // number of cycles before the final infinite loop, that only marks the end of execution
+        cycles: usize,
+        registers: [u32; NUM_REGISTERS],
+    },
+    CycleLimitExceeded,
+    Trap {
+        cause: TrapReason,
+        pc: u32,
+    },
+    DelegationAbiViolation {
+        id: u32,
+        reason: String,
+    },
+}
+
+impl ExecutionOutcome {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Finished { .. })
+    }
+
+    /// Returns final register values, and panics if program didn't finish normally
+    #[track_caller]
+    pub fn expect_finished(self) -> [u32; NUM_REGISTERS] {
+        match self {
+            Self::Finished { registers, .. } => registers,
+            other => panic!("program didn't finish normally: {}", other),
+        }
+    }
+}
+
+impl From<CycleError> for ExecutionOutcome {
+    fn from(error: CycleError) -> Self {
+        match error {
+            CycleError::Trap { cause, pc } => Self::Trap { cause, pc },
+            CycleError::DelegationAbiViolation(violation) => Self::DelegationAbiViolation {
+                id: violation.id,
+                reason: violation.reason,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Finished { cycles, .. } => write!(f, "finished after {} cycles", cycles),
+            Self::CycleLimitExceeded => write!(f, "cycle limit exceeded"),
+            Self::Trap { cause, pc } => write!(f, "trap {:?} at PC = 0x{:08x}", cause, pc),
+            Self::DelegationAbiViolation { id, reason } => {
+                write!(f, "invalid call of delegation 0x{:03x}: {}", id, reason)
+            }
+        }
     }
 }
 
 pub struct SimulatorConfig {
+    // Either flat binary or ELF executable. For the latter `entry_point` is taken from the header
     pub bin_path: PathBuf,
     pub entry_point: u32,
     pub cycles: usize,
@@ -154,6 +234,34 @@ pub struct ProfilerConfig {
     output_path: PathBuf,
     pub reverse_graph: bool,
     pub frequency_recip: usize,
+    pub output_format: ProfilerOutputFormat,
+    /// Cost used by formats that can only carry one value per stack, i.e. flamegraph and folded stacks
+    pub cost_metric: ProfilerCostMetric,
+    /// Delegation circuit rows charged per delegation call, in units of main circuit rows.
+    /// Delegations that are not listed cost 1 row per call
+    pub delegation_rows_per_call: HashMap<u32, u64>,
+}
+
+/// Output of the profiler
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfilerOutputFormat {
+    /// SVG flamegraph rendered by inferno
+    Flamegraph,
+    /// Brendan Gregg's folded stacks for flamegraph.pl, inferno or speedscope
+    FoldedStacks,
+    /// Uncompressed pprof protobuf with all the cost metrics
+    Pprof,
+    /// Callgrind profile with all the cost metrics for kcachegrind
+    Callgrind,
+}
+
+/// What the profiler attributes to the call stacks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfilerCostMetric {
+    /// Executed cycles, that is equal to the main circuit rows
+    Cycles,
+    /// Main circuit rows, plus delegation circuits rows for every delegation call
+    ProvingRows,
 }
 
 impl DiagnosticsConfig {
@@ -171,6 +279,9 @@ impl ProfilerConfig {
             output_path,
             reverse_graph: false,
             frequency_recip: 100,
+            output_format: ProfilerOutputFormat::Flamegraph,
+            cost_metric: ProfilerCostMetric::Cycles,
+            delegation_rows_per_call: HashMap::new(),
         }
     }
 }
@@ -203,7 +314,8 @@ mod diag {
         qol::PipeOp as _,
     };
 
-    use super::SimulatorConfig;
+    use super::profile_formats::{self, NamedStack, SampleCost};
+    use super::{ProfilerCostMetric, ProfilerOutputFormat, SimulatorConfig};
 
     #[derive(Default, Debug)]
     struct ProfilerStats {
@@ -221,6 +333,9 @@ mod diag {
         output_path: PathBuf,
         frequency_recip: usize,
         reverse_graph: bool,
+        output_format: ProfilerOutputFormat,
+        cost_metric: ProfilerCostMetric,
+        delegation_rows_per_call: HashMap<u32, u64>,
         pub stacktraces: StacktraceSet,
         stats: ProfilerStats,
     }
@@ -238,6 +353,9 @@ mod diag {
                         frequency_recip: p.frequency_recip,
                         reverse_graph: p.reverse_graph,
                         output_path: p.output_path,
+                        output_format: p.output_format,
+                        cost_metric: p.cost_metric,
+                        delegation_rows_per_call: p.delegation_rows_per_call,
                         stacktraces: StacktraceSet::new(),
                         dwarf_cache,
                         stats: ProfilerStats::default(),
@@ -265,7 +383,82 @@ mod diag {
             C: MachineConfig,
         {
             if cycle % self.frequency_recip == 0 {
-                self.collect_stacktrace(state, memory_source, memory_tracer, mmu, cycle);
+                // every sample stands for all the cycles until the next one
+                let cost = SampleCost {
+                    samples: 1,
+                    cycles: self.frequency_recip as u64,
+                    delegation_rows: 0,
+                };
+                self.collect_stacktrace(state, memory_source, memory_tracer, mmu, cycle, cost);
+            }
+
+            // delegations are rare and expensive, so we attribute every one of them instead of sampling
+            if let Some(csr) = Self::delegation_call(state, memory_source, memory_tracer, mmu) {
+                let cost = SampleCost {
+                    samples: 0,
+                    cycles: 0,
+                    delegation_rows: self
+                        .delegation_rows_per_call
+                        .get(&csr)
+                        .copied()
+                        .unwrap_or(1),
+                };
+                self.collect_stacktrace(state, memory_source, memory_tracer, mmu, cycle, cost);
+            }
+        }
+
+        /// Returns delegation CSR if the instruction at current PC is a delegation call
+        fn delegation_call<MS, TR, MMU, C>(
+            state: &RiscV32State<C>,
+            memory_source: &mut MS,
+            memory_tracer: &mut TR,
+            mmu: &mut MMU,
+        ) -> Option<u32>
+        where
+            MS: MemorySource,
+            TR: Tracer<C>,
+            MMU: MMUImplementation<MS, TR, C>,
+            C: MachineConfig,
+        {
+            // delegation is never a compressed instruction
+            if C::ALLOWED_DELEGATION_CSRS.is_empty() || state.pc % 4 != 0 {
+                return None;
+            }
+
+            let mut trap = TrapReason::NoTrap;
+            let pc = mmu.map_virtual_to_physical(
+                state.pc,
+                crate::cycle::state::Mode::Machine,
+                crate::abstractions::memory::AccessType::Instruction,
+                memory_source,
+                memory_tracer,
+                &mut trap,
+            );
+            if trap.is_a_trap() {
+                return None;
+            }
+            let instr = mem_read::<_, _, _>(
+                memory_source,
+                memory_tracer,
+                pc,
+                size_of::<u32>() as u32,
+                crate::abstractions::memory::AccessType::Instruction,
+                &mut trap,
+            );
+            if trap.is_a_trap() {
+                return None;
+            }
+
+            // CSRRW into one of the delegation CSRs
+            const OPERATION_SYSTEM: u32 = 0b1110011;
+            let csr = instr >> 20;
+            if instr & 0x7f == OPERATION_SYSTEM
+                && (instr >> 12) & 0x7 == 0b001
+                && C::ALLOWED_DELEGATION_CSRS.contains(&csr)
+            {
+                Some(csr)
+            } else {
+                None
             }
         }
 
@@ -276,6 +469,7 @@ mod diag {
             memory_tracer: &mut TR,
             mmu: &mut MMU,
             cycle: usize,
+            cost: SampleCost,
         ) where
             MS: MemorySource,
             TR: Tracer<C>,
@@ -395,7 +589,7 @@ mod diag {
 
             let stacktrace = Stacktrace::new(stackframes);
 
-            self.stacktraces.absorb(stacktrace);
+            self.stacktraces.absorb(stacktrace, cost);
         }
 
         pub(crate) fn write_stacktrace(&self) {
@@ -404,7 +598,8 @@ mod diag {
                 Ok(file) => file,
             };
 
-            let mut mapped = Vec::with_capacity(self.stacktraces.traces.len());
+            let mut stacks: Vec<(NamedStack<'_>, SampleCost)> =
+                Vec::with_capacity(self.stacktraces.traces.len());
 
             for (st, c) in &self.stacktraces.traces {
                 let names = st
@@ -423,17 +618,38 @@ mod diag {
                             .as_str()
                     })
                     .collect::<Vec<_>>();
-                names
-                    .join(";")
-                    .op(|x| format!("{} {}", x, c).to_owned().to(|x| mapped.push(x)));
+                stacks.push((names, *c));
             }
 
-            let mut opts = inferno::flamegraph::Options::default();
+            match self.output_format {
+                ProfilerOutputFormat::Flamegraph => {
+                    let mapped = profile_formats::folded_lines(&stacks, self.cost_metric);
 
-            opts.reverse_stack_order = self.reverse_graph;
+                    let mut opts = inferno::flamegraph::Options::default();
 
-            inferno::flamegraph::from_lines(&mut opts, mapped.iter().map(|x| x.as_str()), file)
-                .unwrap();
+                    opts.reverse_stack_order = self.reverse_graph;
+                    if self.cost_metric == ProfilerCostMetric::ProvingRows {
+                        opts.count_name = "rows".to_owned();
+                    }
+
+                    inferno::flamegraph::from_lines(
+                        &mut opts,
+                        mapped.iter().map(|x| x.as_str()),
+                        file,
+                    )
+                    .unwrap();
+                }
+                ProfilerOutputFormat::FoldedStacks => {
+                    profile_formats::write_folded(file, &stacks, self.cost_metric).unwrap();
+                }
+                ProfilerOutputFormat::Pprof => {
+                    profile_formats::write_pprof(file, &stacks, self.frequency_recip as u64)
+                        .unwrap();
+                }
+                ProfilerOutputFormat::Callgrind => {
+                    profile_formats::write_callgrind(file, &stacks).unwrap();
+                }
+            }
         }
 
         pub(crate) fn print_stats(&self) {
@@ -877,7 +1093,7 @@ mod diag {
 
     #[derive(Debug)]
     pub(crate) struct StacktraceSet {
-        traces: HashMap<Stacktrace, usize>,
+        traces: HashMap<Stacktrace, SampleCost>,
     }
 
     impl StacktraceSet {
@@ -887,11 +1103,8 @@ mod diag {
             }
         }
 
-        fn absorb(&mut self, stacktrace: Stacktrace) {
-            self.traces
-                .entry(stacktrace)
-                .and_modify(|x| *x += 1)
-                .or_insert(1);
+        fn absorb(&mut self, stacktrace: Stacktrace, cost: SampleCost) {
+            self.traces.entry(stacktrace).or_default().absorb(&cost);
         }
     }
 }
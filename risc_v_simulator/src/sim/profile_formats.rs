@@ -0,0 +1,340 @@
+//! Writers of the collected call stacks in formats understood by the common tooling:
+//! folded stacks (flamegraph.pl, inferno, speedscope), pprof protobuf (pprof, speedscope)
+//! and callgrind (kcachegrind, qcachegrind).
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::Write;
+
+use super::ProfilerCostMetric;
+
+/// Cost attributed to a single call stack
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SampleCost {
+    /// Number of times the stack was captured
+    pub(crate) samples: u64,
+    /// Estimated number of executed cycles. Main circuit proves one cycle per row,
+    /// so it's also the number of main circuit rows
+    pub(crate) cycles: u64,
+    /// Delegation circuits rows used by the delegation calls made from this stack
+    pub(crate) delegation_rows: u64,
+}
+
+impl SampleCost {
+    pub(crate) fn absorb(&mut self, other: &Self) {
+        self.samples += other.samples;
+        self.cycles += other.cycles;
+        self.delegation_rows += other.delegation_rows;
+    }
+
+    pub(crate) fn proving_rows(&self) -> u64 {
+        self.cycles + self.delegation_rows
+    }
+
+    pub(crate) fn metric(&self, metric: ProfilerCostMetric) -> u64 {
+        match metric {
+            ProfilerCostMetric::Cycles => self.cycles,
+            ProfilerCostMetric::ProvingRows => self.proving_rows(),
+        }
+    }
+}
+
+/// Call stack with function names from the outermost to the innermost frame
+pub(crate) type NamedStack<'a> = Vec<&'a str>;
+
+/// Brendan Gregg's folded stacks, one `outer;...;inner cost` line per stack
+pub(crate) fn folded_lines(
+    stacks: &[(NamedStack<'_>, SampleCost)],
+    metric: ProfilerCostMetric,
+) -> Vec<String> {
+    stacks
+        .iter()
+        .filter(|(_, cost)| cost.metric(metric) != 0)
+        .map(|(names, cost)| format!("{} {}", names.join(";"), cost.metric(metric)))
+        .collect()
+}
+
+pub(crate) fn write_folded<W: Write>(
+    mut dst: W,
+    stacks: &[(NamedStack<'_>, SampleCost)],
+    metric: ProfilerCostMetric,
+) -> std::io::Result<()> {
+    for line in folded_lines(stacks, metric) {
+        writeln!(dst, "{}", line)?;
+    }
+
+    Ok(())
+}
+
+/// Uncompressed pprof `Profile` message. Every sample carries cycles, proving rows and
+/// delegation rows values, and `period` is the number of cycles between samples
+pub(crate) fn write_pprof<W: Write>(
+    mut dst: W,
+    stacks: &[(NamedStack<'_>, SampleCost)],
+    period: u64,
+) -> std::io::Result<()> {
+    let mut strings = StringTable::default();
+    // we have no addresses that are stable across the stacks after symbolization,
+    // so every function gets a single location with the same id
+    let mut functions: BTreeMap<u64, u64> = BTreeMap::new();
+    let mut profile = Vec::new();
+
+    for (ty, unit) in [
+        ("samples", "count"),
+        ("cycles", "count"),
+        ("proving_rows", "count"),
+        ("delegation_rows", "count"),
+    ] {
+        let mut value_type = Vec::new();
+        encode_varint_field(&mut value_type, 1, strings.index(ty));
+        encode_varint_field(&mut value_type, 2, strings.index(unit));
+        encode_bytes_field(&mut profile, 1, &value_type);
+    }
+
+    for (names, cost) in stacks.iter() {
+        // pprof expects the innermost frame first
+        let location_ids: Vec<u64> = names
+            .iter()
+            .rev()
+            .map(|name| {
+                let name_idx = strings.index(name);
+                let next_id = functions.len() as u64 + 1;
+                *functions.entry(name_idx).or_insert(next_id)
+            })
+            .collect();
+        let values = [
+            cost.samples,
+            cost.cycles,
+            cost.proving_rows(),
+            cost.delegation_rows,
+        ];
+
+        let mut sample = Vec::new();
+        encode_packed_field(&mut sample, 1, &location_ids);
+        encode_packed_field(&mut sample, 2, &values);
+        encode_bytes_field(&mut profile, 2, &sample);
+    }
+
+    for (name_idx, id) in functions.iter() {
+        let mut line = Vec::new();
+        encode_varint_field(&mut line, 1, *id);
+
+        let mut location = Vec::new();
+        encode_varint_field(&mut location, 1, *id);
+        encode_bytes_field(&mut location, 4, &line);
+        encode_bytes_field(&mut profile, 4, &location);
+
+        let mut function = Vec::new();
+        encode_varint_field(&mut function, 1, *id);
+        encode_varint_field(&mut function, 2, *name_idx);
+        encode_varint_field(&mut function, 3, *name_idx);
+        encode_bytes_field(&mut profile, 5, &function);
+    }
+
+    let mut period_type = Vec::new();
+    encode_varint_field(&mut period_type, 1, strings.index("cycles"));
+    encode_varint_field(&mut period_type, 2, strings.index("count"));
+
+    // string table must be complete before it's written
+    for s in strings.strings.iter() {
+        encode_bytes_field(&mut profile, 6, s.as_bytes());
+    }
+    encode_bytes_field(&mut profile, 11, &period_type);
+    encode_varint_field(&mut profile, 12, period);
+
+    dst.write_all(&profile)
+}
+
+/// Callgrind profile with cycles and proving rows events. Self cost is attributed to the innermost
+/// frame of every stack, and inclusive cost to every distinct caller-callee pair in the stack
+pub(crate) fn write_callgrind<W: Write>(
+    mut dst: W,
+    stacks: &[(NamedStack<'_>, SampleCost)],
+) -> std::io::Result<()> {
+    let mut self_costs: BTreeMap<&str, SampleCost> = BTreeMap::new();
+    let mut call_costs: BTreeMap<&str, BTreeMap<&str, SampleCost>> = BTreeMap::new();
+
+    for (names, cost) in stacks.iter() {
+        let Some(innermost) = names.last() else {
+            continue;
+        };
+        self_costs.entry(*innermost).or_default().absorb(cost);
+
+        // recursion would otherwise count the same cost several times
+        let mut seen = HashSet::new();
+        for pair in names.windows(2) {
+            if seen.insert((pair[0], pair[1])) {
+                call_costs
+                    .entry(pair[0])
+                    .or_default()
+                    .entry(pair[1])
+                    .or_default()
+                    .absorb(cost);
+            }
+        }
+    }
+
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+
+    writeln!(dst, "# callgrind format")?;
+    writeln!(dst, "version: 1")?;
+    writeln!(dst, "creator: risc_v_simulator profiler")?;
+    writeln!(dst, "positions: line")?;
+    writeln!(dst, "events: Cycles ProvingRows DelegationRows")?;
+    writeln!(dst)?;
+
+    let functions: BTreeSet<&str> = self_costs
+        .keys()
+        .chain(call_costs.keys())
+        .copied()
+        .collect();
+    for name in functions.into_iter() {
+        writeln!(dst, "fn={}", callgrind_name(name, &mut ids))?;
+        let cost = self_costs.get(name).copied().unwrap_or_default();
+        writeln!(
+            dst,
+            "0 {} {} {}",
+            cost.cycles,
+            cost.proving_rows(),
+            cost.delegation_rows
+        )?;
+        for (callee, cost) in call_costs.get(name).into_iter().flatten() {
+            writeln!(dst, "cfn={}", callgrind_name(callee, &mut ids))?;
+            writeln!(dst, "calls={} 0", cost.samples)?;
+            writeln!(
+                dst,
+                "0 {} {} {}",
+                cost.cycles,
+                cost.proving_rows(),
+                cost.delegation_rows
+            )?;
+        }
+        writeln!(dst)?;
+    }
+
+    Ok(())
+}
+
+// callgrind allows to only name the function once, and then refer to it by id
+fn callgrind_name<'a>(name: &'a str, ids: &mut HashMap<&'a str, usize>) -> String {
+    if let Some(id) = ids.get(name) {
+        format!("({})", id)
+    } else {
+        let id = ids.len() + 1;
+        ids.insert(name, id);
+        format!("({}) {}", id, name)
+    }
+}
+
+#[derive(Default)]
+struct StringTable {
+    strings: Vec<String>,
+    indexes: HashMap<String, u64>,
+}
+
+impl StringTable {
+    fn index(&mut self, s: &str) -> u64 {
+        if self.strings.is_empty() {
+            // pprof requires the first string to be empty
+            self.strings.push(String::new());
+            self.indexes.insert(String::new(), 0);
+        }
+        if let Some(idx) = self.indexes.get(s) {
+            return *idx;
+        }
+        let idx = self.strings.len() as u64;
+        self.strings.push(s.to_owned());
+        self.indexes.insert(s.to_owned(), idx);
+
+        idx
+    }
+}
+
+fn encode_varint(dst: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        dst.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    dst.push(value as u8);
+}
+
+fn encode_varint_field(dst: &mut Vec<u8>, field: u32, value: u64) {
+    encode_varint(dst, (field as u64) << 3);
+    encode_varint(dst, value);
+}
+
+fn encode_bytes_field(dst: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    encode_varint(dst, ((field as u64) << 3) | 2);
+    encode_varint(dst, bytes.len() as u64);
+    dst.extend_from_slice(bytes);
+}
+
+fn encode_packed_field(dst: &mut Vec<u8>, field: u32, values: &[u64]) {
+    let mut packed = Vec::new();
+    for value in values.iter() {
+        encode_varint(&mut packed, *value);
+    }
+    encode_bytes_field(dst, field, &packed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacks() -> Vec<(NamedStack<'static>, SampleCost)> {
+        vec![
+            (
+                vec!["main", "hash"],
+                SampleCost {
+                    samples: 2,
+                    cycles: 200,
+                    delegation_rows: 3,
+                },
+            ),
+            (
+                vec!["main"],
+                SampleCost {
+                    samples: 1,
+                    cycles: 100,
+                    delegation_rows: 0,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_folded_stacks() {
+        let lines = folded_lines(&stacks(), ProfilerCostMetric::Cycles);
+        assert_eq!(lines, vec!["main;hash 200", "main 100"]);
+        let lines = folded_lines(&stacks(), ProfilerCostMetric::ProvingRows);
+        assert_eq!(lines, vec!["main;hash 203", "main 100"]);
+    }
+
+    #[test]
+    fn test_varint() {
+        let mut dst = Vec::new();
+        encode_varint(&mut dst, 300);
+        assert_eq!(dst, vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn test_pprof_string_table() {
+        let mut dst = Vec::new();
+        write_pprof(&mut dst, &stacks(), 100).unwrap();
+        // first value type is `samples`/`count`, referencing string table entries 1 and 2
+        assert_eq!(&dst[..6], &[0x0a, 0x04, 0x08, 0x01, 0x10, 0x02]);
+        // empty string comes first in the string table
+        let empty_string = [0x32, 0x00];
+        assert!(dst.windows(2).any(|w| w == empty_string));
+    }
+
+    #[test]
+    fn test_callgrind() {
+        let mut dst = Vec::new();
+        write_callgrind(&mut dst, &stacks()).unwrap();
+        let output = String::from_utf8(dst).unwrap();
+        assert!(output.contains("events: Cycles ProvingRows DelegationRows"));
+        // `hash` is sorted first, so it's named first
+        assert!(output.contains("fn=(1) hash\n0 200 203 3\n"));
+        assert!(output.contains("fn=(2) main\n0 100 100 0\ncfn=(1)\ncalls=2 0\n0 200 203 3\n"));
+    }
+}
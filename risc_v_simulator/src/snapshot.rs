@@ -0,0 +1,107 @@
+//! Snapshots of the machine in the middle of execution, so it can be resumed from there instead
+//! of replaying the full prefix. Snapshot doesn't include the program or non-determinism inputs -
+//! memory content already contains the program, and non-determinism source should be re-created
+//! from the same inputs and fast-forwarded, with its write state restored.
+
+use serde::{Deserialize, Serialize};
+
+pub const SNAPSHOT_PAGE_SIZE_IN_WORDS: usize = 1024;
+
+/// Sparse memory content. Memory is always zero-initialized, so only pages with non-zero
+/// words are stored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemorySnapshot {
+    // (page index, page content)
+    pub pages: Vec<(u32, Vec<u32>)>,
+}
+
+impl MemorySnapshot {
+    /// Captures pages that were marked as touched, and skips the ones that are still zero
+    pub fn capture(words: &[u32], touched_pages: &TouchedPages) -> Self {
+        let pages = touched_pages
+            .iter()
+            .map(|page_idx| {
+                let start = page_idx * SNAPSHOT_PAGE_SIZE_IN_WORDS;
+                let end = core::cmp::min(start + SNAPSHOT_PAGE_SIZE_IN_WORDS, words.len());
+                (page_idx, &words[start..end])
+            })
+            .filter(|(_, page)| page.iter().any(|word| *word != 0))
+            .map(|(page_idx, page)| (page_idx as u32, page.to_vec()))
+            .collect();
+
+        Self { pages }
+    }
+
+    /// Destination is expected to be freshly allocated (zeroed) memory
+    pub fn restore_into(&self, words: &mut [u32], touched_pages: &mut TouchedPages) {
+        for (page_idx, page) in self.pages.iter() {
+            let start = *page_idx as usize * SNAPSHOT_PAGE_SIZE_IN_WORDS;
+            assert!(
+                start + page.len() <= words.len(),
+                "snapshot page {} is out of memory bounds",
+                page_idx
+            );
+            words[start..][..page.len()].copy_from_slice(page);
+            touched_pages.mark_word(start);
+        }
+    }
+}
+
+/// Bitmask of memory pages that were written since allocation, so snapshots don't
+/// have to scan the full memory
+#[derive(Clone, Debug, Default)]
+pub struct TouchedPages {
+    bitmask: Vec<u64>,
+}
+
+impl TouchedPages {
+    pub fn new_for_num_words(num_words: usize) -> Self {
+        let num_pages = num_words.div_ceil(SNAPSHOT_PAGE_SIZE_IN_WORDS);
+        Self {
+            bitmask: vec![0u64; num_pages.div_ceil(u64::BITS as usize)],
+        }
+    }
+
+    #[inline(always)]
+    pub fn mark_word(&mut self, word_idx: usize) {
+        let page_idx = word_idx / SNAPSHOT_PAGE_SIZE_IN_WORDS;
+        self.bitmask[page_idx / u64::BITS as usize] |= 1u64 << (page_idx % u64::BITS as usize);
+    }
+
+    pub fn mark_words(&mut self, word_range: core::ops::Range<usize>) {
+        if word_range.is_empty() {
+            return;
+        }
+        let first_page = word_range.start / SNAPSHOT_PAGE_SIZE_IN_WORDS;
+        let last_page = (word_range.end - 1) / SNAPSHOT_PAGE_SIZE_IN_WORDS;
+        for page_idx in first_page..=last_page {
+            self.mark_word(page_idx * SNAPSHOT_PAGE_SIZE_IN_WORDS);
+        }
+    }
+
+    /// Indexes of touched pages in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bitmask
+            .iter()
+            .enumerate()
+            .filter(|(_, bits)| **bits != 0)
+            .flat_map(|(idx, bits)| {
+                (0..u64::BITS as usize)
+                    .filter(move |bit| bits & (1u64 << bit) != 0)
+                    .map(move |bit| idx * u64::BITS as usize + bit)
+            })
+    }
+}
+
+/// Full machine state after `cycle` cycles of execution. `S` is either `RiscV32State` or
+/// `RiscV32StateForUnrolledProver`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionSnapshot<S> {
+    pub cycle: u64,
+    pub state: S,
+    pub memory: MemorySnapshot,
+    // number of words consumed from the non-determinism source
+    pub non_determinism_position: u64,
+    // partially processed writes into the non-determinism source, see `ResumableNonDeterminismSource`
+    pub non_determinism_write_state: Vec<u32>,
+}
@@ -0,0 +1,176 @@
+//! Execution statistics collected via the `Tracer`, so guest programs can be compared by the proving cost
+//! without running the prover.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::abstractions::tracer::{
+    RegisterOrIndirectReadData, RegisterOrIndirectReadWriteData, Tracer,
+};
+use crate::cycle::{state::RiscV32State, state_new::RiscV32StateForUnrolledProver, MachineConfig};
+
+/// Number of cycles or delegation calls that fit into a single circuit instance. Main circuit is
+/// `2^22` rows and delegation circuits are `2^20` rows by default, with one row reserved in each
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitChunkSizes {
+    pub main_cycles_per_chunk: u64,
+    pub default_delegation_calls_per_chunk: u64,
+    // overrides for specific delegation access IDs
+    pub delegation_calls_per_chunk: BTreeMap<u32, u64>,
+}
+
+impl Default for CircuitChunkSizes {
+    fn default() -> Self {
+        Self {
+            main_cycles_per_chunk: (1 << 22) - 1,
+            default_delegation_calls_per_chunk: (1 << 20) - 1,
+            delegation_calls_per_chunk: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunksEstimate {
+    pub main: u64,
+    // per delegation access ID
+    pub delegations: BTreeMap<u32, u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionStats {
+    pub cycles: u64,
+    // per opcode mnemonic
+    pub opcodes: BTreeMap<String, u64>,
+    // number of times every PC was executed
+    pub pc_hits: BTreeMap<u32, u64>,
+    pub memory_reads: u64,
+    pub memory_writes: u64,
+    pub non_determinism_reads: u64,
+    pub non_determinism_writes: u64,
+    // per delegation access ID
+    pub delegations: BTreeMap<u32, u64>,
+    pub chunks: ChunksEstimate,
+}
+
+impl ExecutionStats {
+    /// Number of executed 4-byte aligned instructions in the address range, and total number
+    /// of such instructions in the range
+    pub fn coverage(&self, range: std::ops::Range<u32>) -> (usize, usize) {
+        let executed = self
+            .pc_hits
+            .range(range.clone())
+            .filter(|(pc, _)| *pc % 4 == 0)
+            .count();
+        let total = (range.end.saturating_sub(range.start) as usize).div_ceil(4);
+
+        (executed, total)
+    }
+}
+
+/// Tracer that collects `ExecutionStats`. Opcodes are counted by the simulator's own decoding,
+/// and memory accesses exclude instruction fetches
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionStatsTracer {
+    pub chunk_sizes: CircuitChunkSizes,
+    cycles: u64,
+    opcodes: BTreeMap<&'static str, u64>,
+    pc_hits: BTreeMap<u32, u64>,
+    memory_reads: u64,
+    memory_writes: u64,
+    non_determinism_reads: u64,
+    non_determinism_writes: u64,
+    delegations: BTreeMap<u32, u64>,
+}
+
+impl ExecutionStatsTracer {
+    pub fn new(chunk_sizes: CircuitChunkSizes) -> Self {
+        Self {
+            chunk_sizes,
+            ..Default::default()
+        }
+    }
+
+    fn at_cycle_start_with_pc(&mut self, pc: u32) {
+        self.cycles += 1;
+        *self.pc_hits.entry(pc).or_default() += 1;
+    }
+
+    pub fn finish(self) -> ExecutionStats {
+        let chunks = ChunksEstimate {
+            main: self.cycles.div_ceil(self.chunk_sizes.main_cycles_per_chunk),
+            delegations: self
+                .delegations
+                .iter()
+                .map(|(id, calls)| {
+                    let per_chunk = self
+                        .chunk_sizes
+                        .delegation_calls_per_chunk
+                        .get(id)
+                        .copied()
+                        .unwrap_or(self.chunk_sizes.default_delegation_calls_per_chunk);
+                    (*id, calls.div_ceil(per_chunk))
+                })
+                .collect(),
+        };
+
+        ExecutionStats {
+            cycles: self.cycles,
+            opcodes: self
+                .opcodes
+                .into_iter()
+                .map(|(name, count)| (name.to_owned(), count))
+                .collect(),
+            pc_hits: self.pc_hits,
+            memory_reads: self.memory_reads,
+            memory_writes: self.memory_writes,
+            non_determinism_reads: self.non_determinism_reads,
+            non_determinism_writes: self.non_determinism_writes,
+            delegations: self.delegations,
+            chunks,
+        }
+    }
+}
+
+impl<C: MachineConfig> Tracer<C> for ExecutionStatsTracer {
+    fn at_cycle_start(&mut self, current_state: &RiscV32State<C>) {
+        self.at_cycle_start_with_pc(current_state.pc);
+    }
+
+    fn at_cycle_start_ext(&mut self, current_state: &RiscV32StateForUnrolledProver<C>) {
+        self.at_cycle_start_with_pc(current_state.pc);
+    }
+
+    fn trace_opcode(&mut self, opcode: &'static str) {
+        *self.opcodes.entry(opcode).or_default() += 1;
+    }
+
+    fn trace_non_determinism_read(&mut self, _read_value: u32) {
+        self.non_determinism_reads += 1;
+    }
+
+    fn trace_non_determinism_write(&mut self, _written_value: u32) {
+        self.non_determinism_writes += 1;
+    }
+
+    fn trace_ram_read(&mut self, _phys_address: u64, _read_value: u32) {
+        self.memory_reads += 1;
+    }
+
+    fn trace_ram_read_write(&mut self, _phys_address: u64, _read_value: u32, _written_value: u32) {
+        self.memory_writes += 1;
+    }
+
+    fn record_delegation(
+        &mut self,
+        access_id: u32,
+        _base_register: u32,
+        _register_accesses: &mut [RegisterOrIndirectReadWriteData],
+        _indirect_read_addresses: &[u32],
+        _indirect_reads: &mut [RegisterOrIndirectReadData],
+        _indirect_write_addresses: &[u32],
+        _indirect_writes: &mut [RegisterOrIndirectReadWriteData],
+    ) {
+        *self.delegations.entry(access_id).or_default() += 1;
+    }
+}
@@ -0,0 +1,127 @@
+use super::*;
+use crate::cycle::IMWithBitmanipIsaConfigWithAllDelegations;
+
+const OP: u32 = 0b0110011;
+const OP_IMM: u32 = 0b0010011;
+
+fn encode(opcode: u32, funct3: u32, funct7: u32, rs2: u32) -> u32 {
+    // rd = x3, rs1 = x1
+    (funct7 << 25) | (rs2 << 20) | (1 << 15) | (funct3 << 12) | (3 << 7) | opcode
+}
+
+fn test_encoded_op(op_name: &str, encoding: u32, expected: u32, op1: u32, op2: u32) {
+    {
+        // old simulator
+        let mut state =
+            RiscV32State::<IMWithBitmanipIsaConfigWithAllDelegations>::initial(INITIAL_PC);
+        state.registers[1] = op1;
+        state.registers[2] = op2;
+        let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 12);
+        memory.populate(INITIAL_PC, encoding);
+        let mut mmu = NoMMU::default();
+        state.cycle(&mut memory, &mut (), &mut mmu, &mut ZeroedSource);
+        assert!(state.registers[3] == expected, "Unexpected output: expected 0x{:08x} for operation `{}` 0x{:08x}, 0x{:08x}, obtained 0x{:08x}", expected, op_name, op1, op2, state.registers[3]);
+    }
+    {
+        // new simulator
+        let mut state =
+            RiscV32StateForUnrolledProver::<IMWithBitmanipIsaConfigWithAllDelegations>::initial(
+                INITIAL_PC,
+            );
+        state.registers[1] = op1;
+        state.registers[2] = op2;
+        let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 12);
+        memory.populate(INITIAL_PC, encoding);
+        let _ = state.run_cycles(&mut memory, &mut (), &mut ZeroedSource, &mut NoExtraCSRs, 1);
+        assert!(state.registers[3] == expected, "Unexpected output: expected 0x{:08x} for operation `{}` 0x{:08x}, 0x{:08x}, obtained 0x{:08x}", expected, op_name, op1, op2, state.registers[3]);
+    }
+}
+
+fn test_bitmanip_reg_reg_op(
+    op_name: &str,
+    funct3: u32,
+    funct7: u32,
+    expected: u32,
+    op1: u32,
+    op2: u32,
+) {
+    test_encoded_op(op_name, encode(OP, funct3, funct7, 2), expected, op1, op2);
+}
+
+fn test_bitmanip_unary_op(
+    op_name: &str,
+    funct3: u32,
+    funct7: u32,
+    rs2: u32,
+    expected: u32,
+    op1: u32,
+) {
+    test_encoded_op(
+        op_name,
+        encode(OP_IMM, funct3, funct7, rs2),
+        expected,
+        op1,
+        0,
+    );
+}
+
+#[test]
+fn test_rotations() {
+    test_bitmanip_reg_reg_op("rol", 0b001, 0b0110000, 0x00000003, 0x80000001, 1);
+    test_bitmanip_reg_reg_op("rol", 0b001, 0b0110000, 0x34567812, 0x12345678, 40);
+    test_bitmanip_reg_reg_op("ror", 0b101, 0b0110000, 0xc0000000, 0x80000001, 1);
+    test_bitmanip_reg_reg_op("ror", 0b101, 0b0110000, 0x56781234, 0x12345678, 16);
+    test_bitmanip_unary_op("rori", 0b101, 0b0110000, 4, 0x81234567, 0x12345678);
+}
+
+#[test]
+fn test_negated_logic() {
+    test_bitmanip_reg_reg_op("andn", 0b111, 0b0100000, 0x00ff00f0, 0x00ff00ff, 0xff00ff0f);
+    test_bitmanip_reg_reg_op("orn", 0b110, 0b0100000, 0x00ff00f0, 0x000000f0, 0xff00ff0f);
+    test_bitmanip_reg_reg_op("xnor", 0b100, 0b0100000, 0xfffffff0, 0x12345678, 0x12345677);
+}
+
+#[test]
+fn test_min_max() {
+    test_bitmanip_reg_reg_op("min", 0b100, 0b0000101, 0xffffffff, 0xffffffff, 1);
+    test_bitmanip_reg_reg_op("minu", 0b101, 0b0000101, 1, 0xffffffff, 1);
+    test_bitmanip_reg_reg_op("max", 0b110, 0b0000101, 1, 0xffffffff, 1);
+    test_bitmanip_reg_reg_op("maxu", 0b111, 0b0000101, 0xffffffff, 0xffffffff, 1);
+    test_bitmanip_reg_reg_op("max", 0b110, 0b0000101, 0x80000001, 0x80000000, 0x80000001);
+}
+
+#[test]
+fn test_count_and_sign_extend() {
+    test_bitmanip_unary_op("clz", 0b001, 0b0110000, 0, 32, 0);
+    test_bitmanip_unary_op("clz", 0b001, 0b0110000, 0, 19, 0x00001234);
+    test_bitmanip_unary_op("ctz", 0b001, 0b0110000, 1, 32, 0);
+    test_bitmanip_unary_op("ctz", 0b001, 0b0110000, 1, 20, 0x12300000);
+    test_bitmanip_unary_op("cpop", 0b001, 0b0110000, 2, 32, 0xffffffff);
+    test_bitmanip_unary_op("cpop", 0b001, 0b0110000, 2, 13, 0x12345678);
+    test_bitmanip_unary_op("sext.b", 0b001, 0b0110000, 4, 0xffffff80, 0x12345680);
+    test_bitmanip_unary_op("sext.h", 0b001, 0b0110000, 5, 0x00005678, 0x12345678);
+    test_bitmanip_unary_op("sext.h", 0b001, 0b0110000, 5, 0xffff8765, 0x12348765);
+}
+
+#[test]
+fn test_byte_ops() {
+    test_bitmanip_unary_op("rev8", 0b101, 0b0110100, 0b11000, 0x78563412, 0x12345678);
+    test_bitmanip_unary_op("brev8", 0b101, 0b0110100, 0b00111, 0x482c6a1e, 0x12345678);
+    test_bitmanip_unary_op("orc.b", 0b101, 0b0010100, 0b00111, 0xff00ffff, 0x01000f80);
+}
+
+#[test]
+fn test_pack() {
+    test_bitmanip_reg_reg_op("pack", 0b100, 0b0000100, 0x56785678, 0x12345678, 0x12345678);
+    test_bitmanip_reg_reg_op(
+        "packh", 0b111, 0b0000100, 0x0000ab78, 0x12345678, 0x123456ab,
+    );
+    // ZEXT.H
+    test_encoded_op(
+        "zext.h",
+        encode(OP, 0b100, 0b0000100, 0),
+        0x00005678,
+        0x12345678,
+        0,
+    );
+}
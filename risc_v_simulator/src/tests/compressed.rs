@@ -0,0 +1,114 @@
+use super::*;
+use crate::cycle::opcode_formats::expand_compressed_instruction;
+use crate::cycle::IMWithCompressedIsaConfigWithAllDelegations;
+
+// pairs of compressed instructions and their 32-bit equivalents
+const EXPANSIONS: &[(&str, u16, u32)] = &[
+    ("c.addi4spn s0, sp, 16", 0x0800, 0x01010413),
+    ("c.lw a0, 0(a0)", 0x4108, 0x00052503),
+    ("c.sw a1, 4(a0)", 0xc14c, 0x00b52223),
+    ("c.li a0, 1", 0x4505, 0x00100513),
+    ("c.addi sp, -16", 0x1141, 0xff010113),
+    ("c.addi16sp sp, -32", 0x1101, 0xfe010113),
+    ("c.lui a0, 0x1", 0x6505, 0x00001537),
+    ("c.srai a0, 1", 0x8505, 0x40155513),
+    ("c.andi a0, -1", 0x997d, 0xfff57513),
+    ("c.sub a0, a1", 0x8d0d, 0x40b50533),
+    ("c.and a0, a1", 0x8d6d, 0x00b57533),
+    ("c.j 0", 0xa001, 0x0000006f),
+    ("c.jal 0", 0x2001, 0x000000ef),
+    ("c.beqz a0, 8", 0xc501, 0x00050463),
+    ("c.slli a0, 2", 0x050a, 0x00251513),
+    ("c.lwsp ra, 12(sp)", 0x40b2, 0x00c12083),
+    ("c.swsp ra, 12(sp)", 0xc606, 0x00112623),
+    ("c.jr ra", 0x8082, 0x00008067),
+    ("c.jalr a0", 0x9502, 0x000500e7),
+    ("c.mv a0, a1", 0x852e, 0x00b00533),
+    ("c.add a0, a1", 0x952e, 0x00b50533),
+    ("c.ebreak", 0x9002, 0x00100073),
+];
+
+#[test]
+fn test_compressed_expansion() {
+    for (name, compressed, expected) in EXPANSIONS.iter() {
+        let expanded = expand_compressed_instruction(*compressed);
+        assert_eq!(
+            expanded,
+            Some(*expected),
+            "Unexpected expansion of `{}` (0x{:04x})",
+            name,
+            compressed
+        );
+    }
+
+    // all zeroes, C.ADDI4SPN with zero immediate, C.LWSP into x0, C.JR x0, C.FLW and RV64-only C.SUBW
+    for illegal in [0x0000u16, 0x0004, 0x4002, 0x8002, 0x6000, 0x9d0d] {
+        assert!(expand_compressed_instruction(illegal).is_none());
+    }
+}
+
+fn program_into_memory(half_words: &[u16]) -> VectorMemoryImpl {
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 12);
+    for (idx, chunk) in half_words.chunks(2).enumerate() {
+        let low = chunk[0] as u32;
+        let high = chunk.get(1).copied().unwrap_or(0) as u32;
+        memory.populate(
+            INITIAL_PC + idx as u32 * (core::mem::size_of::<u32>() as u32),
+            low | (high << 16),
+        );
+    }
+
+    memory
+}
+
+#[test]
+fn test_mixed_compressed_program() {
+    let program: &[u16] = &[
+        0x4505, // 0x00: c.li a0, 1
+        0x0593, 0x0050, // 0x02: addi a1, x0, 5 that crosses the word boundary
+        0x952e, // 0x06: c.add a0, a1
+        0x2019, // 0x08: c.jal 6, so we jump to 0x0e and link 0x0a
+        0x0001, // 0x0a: c.nop
+        0x0001, // 0x0c: c.nop
+        0x8606, // 0x0e: c.mv a2, ra
+        0xc291, // 0x10: c.beqz a3, 4
+        0x4501, // 0x12: c.li a0, 0
+    ];
+    const NUM_CYCLES: usize = 6;
+
+    let check = |registers: &[u32], pc: u32| {
+        assert_eq!(pc, 0x14);
+        assert_eq!(registers[10], 6);
+        assert_eq!(registers[11], 5);
+        assert_eq!(registers[1], 0x0a);
+        assert_eq!(registers[12], 0x0a);
+    };
+
+    {
+        // old simulator
+        let mut state =
+            RiscV32State::<IMWithCompressedIsaConfigWithAllDelegations>::initial(INITIAL_PC);
+        let mut memory = program_into_memory(program);
+        let mut mmu = NoMMU::default();
+        for _ in 0..NUM_CYCLES {
+            state.cycle(&mut memory, &mut (), &mut mmu, &mut ZeroedSource);
+        }
+        check(&state.registers, state.pc);
+    }
+    {
+        // new simulator
+        let mut state =
+            RiscV32StateForUnrolledProver::<IMWithCompressedIsaConfigWithAllDelegations>::initial(
+                INITIAL_PC,
+            );
+        let mut memory = program_into_memory(program);
+        let _ = state.run_cycles(
+            &mut memory,
+            &mut (),
+            &mut ZeroedSource,
+            &mut NoExtraCSRs,
+            NUM_CYCLES,
+        );
+        check(&state.registers, state.pc);
+    }
+}
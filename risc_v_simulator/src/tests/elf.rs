@@ -0,0 +1,149 @@
+use crate::abstractions::memory::VectorMemoryImpl;
+use crate::elf::{ElfLoadError, ProgramImage};
+
+const ROM_BOUND: u32 = 1 << 21;
+const MEMORY_BOUND: u32 = 1 << 30;
+
+// (p_vaddr, p_paddr, p_memsz, p_flags, data)
+type Segment<'a> = (u32, u32, u32, u32, &'a [u8]);
+
+fn make_elf(entry_point: u32, segments: &[Segment]) -> Vec<u8> {
+    const EHSIZE: u32 = 52;
+    const PHENTSIZE: u32 = 32;
+
+    let mut header = vec![0x7f, b'E', b'L', b'F', 1, 1, 1, 0];
+    header.resize(16, 0);
+    header.extend_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    header.extend_from_slice(&243u16.to_le_bytes()); // EM_RISCV
+    header.extend_from_slice(&1u32.to_le_bytes());
+    header.extend_from_slice(&entry_point.to_le_bytes());
+    header.extend_from_slice(&EHSIZE.to_le_bytes()); // e_phoff
+    header.extend_from_slice(&0u32.to_le_bytes()); // e_shoff
+    header.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    header.extend_from_slice(&(EHSIZE as u16).to_le_bytes());
+    header.extend_from_slice(&(PHENTSIZE as u16).to_le_bytes());
+    header.extend_from_slice(&(segments.len() as u16).to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes()); // e_shentsize
+    header.extend_from_slice(&0u16.to_le_bytes()); // e_shnum
+    header.extend_from_slice(&0u16.to_le_bytes()); // e_shstrndx
+    assert_eq!(header.len() as u32, EHSIZE);
+
+    let mut data_offset = EHSIZE + PHENTSIZE * segments.len() as u32;
+    let mut data = vec![];
+    for (vaddr, paddr, mem_size, flags, bytes) in segments.iter() {
+        for word in [
+            1, // PT_LOAD
+            data_offset,
+            *vaddr,
+            *paddr,
+            bytes.len() as u32,
+            *mem_size,
+            *flags,
+            4,
+        ] {
+            header.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend_from_slice(bytes);
+        data_offset += bytes.len() as u32;
+    }
+    header.extend(data);
+
+    header
+}
+
+#[test]
+fn test_elf_segments_placement() {
+    let text = [0x13u8, 0, 0, 0, 0x6f, 0, 0, 0, 0xaa, 0xbb, 0xcc, 0xdd];
+    // .data is linked into RAM, but loaded right after .text in ROM
+    let data = [1u8, 2, 3, 4];
+    let elf = make_elf(
+        4,
+        &[
+            (0, 0, text.len() as u32, 0b101, &text),
+            (0x1000_0000, 0x10, 16, 0b110, &data),
+            (0x1000_0100, 0x1000_0100, 64, 0b110, &[]),
+        ],
+    );
+
+    let image = ProgramImage::parse_elf(&elf).unwrap();
+    assert_eq!(image.entry_point, 4);
+    assert_eq!(image.segments.len(), 3);
+    assert!(image.segments[0].is_executable());
+    assert!(image.segments[1].is_writable());
+
+    let memory_image = image.memory_image(ROM_BOUND, MEMORY_BOUND).unwrap();
+    assert_eq!(
+        memory_image.rom,
+        vec![0x13, 0x6f, 0xddccbbaa, 0, 0x04030201]
+    );
+    assert!(memory_image.ram.is_empty());
+
+    let from_path_like = ProgramImage::from_bytes(&elf, 0x100).unwrap();
+    assert_eq!(from_path_like.entry_point, 4);
+
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 12);
+    assert!(image.load_into(&mut memory).is_err());
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 30);
+    image.load_into(&mut memory).unwrap();
+    assert_eq!(&memory.inner[..5], &memory_image.rom[..]);
+}
+
+#[test]
+fn test_elf_initialized_ram() {
+    let text = [0x13u8, 0, 0, 0];
+    // segment crosses ROM bound, so it's split between ROM and RAM
+    let data = [1u8, 2, 3, 4, 5, 6, 0, 0, 0, 0, 7];
+    let elf = make_elf(
+        0,
+        &[
+            (0, 0, 4, 0b101, &text),
+            (ROM_BOUND - 4, ROM_BOUND - 4, 64, 0b110, &data),
+        ],
+    );
+
+    let image = ProgramImage::parse_elf(&elf).unwrap();
+    let memory_image = image.memory_image(ROM_BOUND, MEMORY_BOUND).unwrap();
+    assert_eq!(memory_image.rom.len(), ROM_BOUND as usize / 4);
+    assert_eq!(memory_image.rom[0], 0x13);
+    assert_eq!(*memory_image.rom.last().unwrap(), 0x04030201);
+    // zero words are not listed
+    assert_eq!(
+        memory_image.ram,
+        vec![(ROM_BOUND, 0x0605), (ROM_BOUND + 8, 0x07)]
+    );
+}
+
+#[test]
+fn test_segment_out_of_memory() {
+    let elf = make_elf(0, &[(MEMORY_BOUND - 4, MEMORY_BOUND - 4, 8, 0b110, &[])]);
+
+    let image = ProgramImage::parse_elf(&elf).unwrap();
+    assert_eq!(
+        image.memory_image(ROM_BOUND, MEMORY_BOUND),
+        Err(ElfLoadError::SegmentOutOfMemory {
+            paddr: MEMORY_BOUND - 4,
+            mem_size: 8
+        })
+    );
+    let mut memory = VectorMemoryImpl::new_for_byte_size(MEMORY_BOUND as usize);
+    assert!(image.load_into(&mut memory).is_err());
+}
+
+#[test]
+fn test_flat_binary_is_not_elf() {
+    let binary = [0x13u8, 0, 0, 0];
+    assert_eq!(
+        ProgramImage::parse_elf(&binary).unwrap_err(),
+        ElfLoadError::NotAnElf
+    );
+
+    let image = ProgramImage::from_bytes(&binary, 0x100).unwrap();
+    assert_eq!(image.entry_point, 0x100);
+    let memory_image = image.memory_image(ROM_BOUND, MEMORY_BOUND).unwrap();
+    assert_eq!(memory_image.rom[0x40], 0x13);
+
+    assert_eq!(
+        ProgramImage::from_bytes(&binary[..3], 0x100).unwrap_err(),
+        ElfLoadError::UnalignedBinary(3)
+    );
+}
@@ -0,0 +1,161 @@
+use std::io::{Cursor, Read, Write};
+
+use crate::abstractions::memory::VectorMemoryImpl;
+use crate::abstractions::non_determinism::ZeroedSource;
+use crate::cycle::state::RiscV32State;
+use crate::cycle::IMStandardIsaConfig;
+use crate::elf::ProgramImage;
+use crate::gdb::{DebuggerConnection, GdbStub};
+use crate::mmu::NoMMU;
+
+// addi x1, x0, 5
+// sw x1, 0x100(x0)
+// jal x0, 0
+const PROGRAM: [u32; 3] = [0x00500093, 0x10102023, 0x0000006f];
+
+struct ScriptedConnection {
+    input: Cursor<Vec<u8>>,
+    output: Vec<u8>,
+}
+
+impl Read for ScriptedConnection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for ScriptedConnection {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl DebuggerConnection for ScriptedConnection {
+    fn try_read_byte(&mut self) -> Option<u8> {
+        let mut byte = [0u8; 1];
+        (self.input.read(&mut byte).ok()? == 1).then_some(byte[0])
+    }
+}
+
+fn packet(data: &str) -> String {
+    let checksum = data.bytes().fold(0u8, |acc, b| acc.wrapping_add(b));
+    format!("${}#{:02x}", data, checksum)
+}
+
+fn replies(output: &[u8]) -> Vec<String> {
+    let output = String::from_utf8(output.to_vec()).unwrap();
+    output
+        .split('$')
+        .skip(1)
+        .map(|p| p.split('#').next().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn test_gdb_session() {
+    let commands = [
+        "QStartNoAckMode",
+        "Z0,4,4",
+        "c",
+        "p20",
+        "p1",
+        "Z2,100,4",
+        "c",
+        "m100,4",
+        "g",
+        "c",
+        "c",
+        "k",
+    ];
+    let mut input = String::new();
+    for (idx, command) in commands.iter().enumerate() {
+        input.push_str(&packet(command));
+        if idx == 0 {
+            // last ack before no-ack mode kicks in
+            input.push('+');
+        }
+    }
+
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 12);
+    for (idx, insn) in PROGRAM.iter().enumerate() {
+        memory.populate(idx as u32 * 4, *insn);
+    }
+    let state = RiscV32State::<IMStandardIsaConfig>::initial(0);
+    let program = ProgramImage::from_flat_binary(&[], 0);
+    let mut stub = GdbStub::new(state, memory, NoMMU { sapt: 0 }, ZeroedSource, program);
+
+    let mut connection = ScriptedConnection {
+        input: Cursor::new(input.into_bytes()),
+        output: vec![],
+    };
+    stub.serve(&mut connection).unwrap();
+
+    let mut expected_registers = String::new();
+    for idx in 0..33 {
+        let value: u32 = match idx {
+            1 => 5,
+            32 => 8,
+            _ => 0,
+        };
+        expected_registers.push_str(&format!("{:08x}", value.swap_bytes()));
+    }
+
+    let expected = [
+        "OK",
+        "OK",
+        "S05",
+        "04000000",
+        "05000000",
+        "OK",
+        "T05watch:100;",
+        "05000000",
+        &expected_registers,
+        "S05",
+        "W00",
+    ];
+    assert_eq!(replies(&connection.output), expected);
+    assert_eq!(stub.state.pc, 8);
+}
+
+#[test]
+fn test_gdb_interrupt_keeps_other_bytes() {
+    // addi x1, x1, 1
+    // jal x0, -4
+    let program = [0x00108093u32, 0xffdff06f];
+
+    // GDB is not expected to send anything but interrupt while the machine is running, but
+    // if it does, the bytes that were read while polling for the interrupt must not be lost
+    let mut input = packet("QStartNoAckMode");
+    input.push('+');
+    input.push_str(&packet("c"));
+    input.push_str(&packet("g"));
+    input.push('\x03');
+    input.push_str(&packet("k"));
+
+    let bytes: Vec<u8> = program.iter().flat_map(|insn| insn.to_le_bytes()).collect();
+    let program = ProgramImage::from_flat_binary(&bytes, 0);
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 12);
+    program.load_into(&mut memory).unwrap();
+    let state = RiscV32State::<IMStandardIsaConfig>::initial(0);
+    let mut stub = GdbStub::new(state, memory, NoMMU { sapt: 0 }, ZeroedSource, program);
+
+    let mut connection = ScriptedConnection {
+        input: Cursor::new(input.into_bytes()),
+        output: vec![],
+    };
+    stub.serve(&mut connection).unwrap();
+
+    // every poll consumes a single byte, and "$g#67" is followed by the interrupt
+    assert_eq!(stub.cycles, 6 << 16);
+    let replies = replies(&connection.output);
+    assert_eq!(replies.len(), 3);
+    assert_eq!(replies[..2], ["OK", "S02"]);
+    assert_eq!(
+        replies[2][8..16],
+        format!("{:08x}", stub.state.registers[1].swap_bytes())
+    );
+}
@@ -0,0 +1,89 @@
+use crate::abstractions::memory::{MemorySource, VectorMemoryImpl};
+use crate::cycle::state::RiscV32State;
+use crate::cycle::status_registers::TrapReason;
+use crate::cycle::IMIsaConfigWithAllDelegations;
+use crate::delegations::keccak_f1600_round_function::*;
+use crate::mmu::NoMMU;
+
+#[test]
+fn test_keccak_f1600_rounds_over_zero_state() {
+    let mut state = [0u32; X10_NUM_WRITES];
+    for round_index in 0..KECCAK_F1600_NUM_ROUNDS {
+        keccak_f1600_round(&mut state, round_index);
+    }
+
+    // first lanes of Keccak-f[1600] applied to all-zero state
+    let lanes: Vec<u64> = state
+        .as_chunks::<2>()
+        .0
+        .iter()
+        .map(|[low, high]| (*low as u64) | ((*high as u64) << 32))
+        .take(3)
+        .collect();
+    assert_eq!(
+        lanes,
+        vec![0xF1258F7940E1DDE7, 0x84D5CCF933C0478A, 0xD598261EA65AA9EE]
+    );
+}
+
+#[test]
+fn test_keccak_f1600_delegation_updates_memory() {
+    const STATE_PTR: u32 = 1 << 21;
+
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 22);
+    let mut expected: [u32; X10_NUM_WRITES] = std::array::from_fn(|i| (i as u32) * 0x01010101);
+    for (i, word) in expected.iter().enumerate() {
+        memory.populate(STATE_PTR + 4 * i as u32, *word);
+    }
+    let round_index = 2;
+    keccak_f1600_round(&mut expected, round_index);
+
+    let mut state = RiscV32State::<IMIsaConfigWithAllDelegations>::initial(0);
+    state.registers[10] = STATE_PTR;
+    state.registers[11] = round_index as u32;
+    let mut trap = TrapReason::NoTrap;
+    keccak_f1600_round_function(
+        &mut state,
+        &mut memory,
+        &mut (),
+        &mut NoMMU { sapt: 0 },
+        0,
+        &mut trap,
+    )
+    .unwrap();
+    assert!(!trap.is_a_trap());
+
+    for (i, word) in expected.iter().enumerate() {
+        assert_eq!(
+            memory.get_noexcept((STATE_PTR + 4 * i as u32) as u64),
+            *word
+        );
+    }
+
+    // round constant can only be selected by index
+    state.registers[11] = KECCAK_F1600_NUM_ROUNDS as u32;
+    let err = keccak_f1600_round_function(
+        &mut state,
+        &mut memory,
+        &mut (),
+        &mut NoMMU { sapt: 0 },
+        0,
+        &mut trap,
+    )
+    .unwrap_err();
+    assert_eq!(err.id, KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID);
+
+    // state must be aligned to avoid carries in the address computation
+    state.registers[11] = round_index as u32;
+    state.registers[10] = STATE_PTR + 128;
+    let err = keccak_f1600_round_function(
+        &mut state,
+        &mut memory,
+        &mut (),
+        &mut NoMMU { sapt: 0 },
+        0,
+        &mut trap,
+    )
+    .unwrap_err();
+    assert_eq!(err.id, KECCAK_F1600_ROUND_FUNCTION_ACCESS_ID);
+}
@@ -11,13 +11,28 @@ use std::collections::HashMap;
 mod add;
 mod addi;
 mod beq;
+mod bitmanip;
+mod compressed;
+mod elf;
+mod gdb;
+#[cfg(feature = "delegation")]
+mod keccak;
 mod mul;
 mod mulh;
 mod mulhu;
+mod outcome;
+#[cfg(feature = "delegation")]
+mod poseidon2;
 mod rem;
+mod replay;
+#[cfg(feature = "delegation")]
+mod sha256;
 mod slt;
 mod sltu;
+mod snapshot;
 mod sra;
+mod stats;
+mod traps;
 
 const INITIAL_PC: u32 = 0;
 
@@ -0,0 +1,90 @@
+use crate::abstractions::memory::VectorMemoryImpl;
+use crate::abstractions::non_determinism::ZeroedSource;
+use crate::cycle::state::RiscV32State;
+use crate::cycle::status_registers::TrapReason;
+use crate::cycle::IMStandardIsaConfig;
+use crate::mmu::NoMMU;
+use crate::runner::run_simple_for_num_cycles;
+use crate::sim::{ExecutionOutcome, Simulator, SimulatorConfig};
+
+// addi x1, x0, 5
+const ADDI_X1_5: u32 = 0x00500093;
+// addi x1, x1, 1
+const INCREMENT_X1: u32 = 0x00108093;
+// jal x0, 0
+const HALT: u32 = 0x0000006f;
+// jal x0, -4
+const JUMP_BACK: u32 = 0xffdff06f;
+
+fn run_program(program: &[u32], cycles: usize) -> ExecutionOutcome {
+    let config = SimulatorConfig::new("".into(), 0, cycles, None);
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 22);
+    for (idx, insn) in program.iter().enumerate() {
+        memory.populate(idx as u32 * 4, *insn);
+    }
+    let state = RiscV32State::<IMStandardIsaConfig>::initial(0);
+    let mut sim = Simulator::new(config, state, memory, (), NoMMU { sapt: 0 }, ZeroedSource);
+
+    sim.run(|_, _| {}, |_, _| {})
+}
+
+#[test]
+fn test_finished_outcome() {
+    let outcome = run_program(&[ADDI_X1_5, HALT], 16);
+    let ExecutionOutcome::Finished { cycles, registers } = outcome else {
+        panic!("unexpected outcome {:?}", outcome);
+    };
+    assert_eq!(cycles, 1);
+    assert_eq!(registers[1], 5);
+}
+
+#[test]
+fn test_finished_cycles_are_same_for_all_runners() {
+    let program = [ADDI_X1_5, INCREMENT_X1, HALT];
+    let binary: Vec<u8> = program.iter().flat_map(|insn| insn.to_le_bytes()).collect();
+    let (_, outcome) =
+        run_simple_for_num_cycles::<_, IMStandardIsaConfig>(&binary, 0, 16, ZeroedSource);
+    assert_eq!(outcome, run_program(&program, 16));
+    assert!(matches!(
+        outcome,
+        ExecutionOutcome::Finished { cycles: 2, .. }
+    ));
+}
+
+#[test]
+fn test_cycle_limit_outcome() {
+    let outcome = run_program(&[INCREMENT_X1, JUMP_BACK], 16);
+    assert_eq!(outcome, ExecutionOutcome::CycleLimitExceeded);
+}
+
+#[test]
+fn test_trap_outcome() {
+    // all-zeroes word is not a valid instruction
+    let outcome = run_program(&[ADDI_X1_5, 0, HALT], 16);
+    assert_eq!(
+        outcome,
+        ExecutionOutcome::Trap {
+            cause: TrapReason::IllegalInstruction,
+            pc: 4
+        }
+    );
+}
+
+#[cfg(feature = "delegation")]
+#[test]
+fn test_delegation_abi_violation_outcome() {
+    use crate::delegations::u256_ops_with_control::U256_OPS_WITH_CONTROL_ACCESS_ID;
+
+    let program = [
+        0x00200537, // lui x10, 0x200
+        0x00450513, // addi x10, x10, 4
+        0x7ca01073, // csrrw x0, 0x7ca, x0
+        HALT,
+    ];
+    let outcome = run_program(&program, 16);
+    let ExecutionOutcome::DelegationAbiViolation { id, reason } = outcome else {
+        panic!("unexpected outcome {:?}", outcome);
+    };
+    assert_eq!(id, U256_OPS_WITH_CONTROL_ACCESS_ID);
+    assert!(reason.contains("unaligned"), "{}", reason);
+}
@@ -0,0 +1,77 @@
+use crate::abstractions::memory::{MemorySource, VectorMemoryImpl};
+use crate::cycle::state::RiscV32State;
+use crate::cycle::status_registers::TrapReason;
+use crate::cycle::IMIsaConfigWithAllDelegations;
+use crate::delegations::poseidon2_permutation::*;
+use crate::mmu::NoMMU;
+use field::Mersenne31Field;
+
+#[test]
+fn test_poseidon2_permutation_with_feed_forward_matches_compression() {
+    let input: [u32; X10_NUM_WRITES] = std::array::from_fn(|i| (i as u32) * 0x00123457);
+    let mut state = input;
+    poseidon2_permutation(&mut state);
+    let compressed: Vec<u32> = state
+        .iter()
+        .zip(input.iter())
+        .take(8)
+        .map(|(a, b)| Mersenne31Field::from_u62(*a as u64 + *b as u64).to_reduced_u32())
+        .collect();
+
+    let expected = poseidon2::m31::poseidon2_compress(&input.map(Mersenne31Field::new));
+    assert_eq!(
+        compressed,
+        expected
+            .iter()
+            .map(|el| el.to_reduced_u32())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_poseidon2_delegation_updates_memory() {
+    const STATE_PTR: u32 = 1 << 21;
+
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 22);
+    // words are not required to be reduced
+    let initial: [u32; X10_NUM_WRITES] = std::array::from_fn(|i| u32::MAX - (i as u32));
+    for (i, word) in initial.iter().enumerate() {
+        memory.populate(STATE_PTR + 4 * i as u32, *word);
+    }
+    let mut expected = initial.map(Mersenne31Field::from_nonreduced_u32);
+    poseidon2::m31::poseidon_permutation(&mut expected);
+
+    let mut state = RiscV32State::<IMIsaConfigWithAllDelegations>::initial(0);
+    state.registers[10] = STATE_PTR;
+    let mut trap = TrapReason::NoTrap;
+    poseidon2_permutation_function(
+        &mut state,
+        &mut memory,
+        &mut (),
+        &mut NoMMU { sapt: 0 },
+        0,
+        &mut trap,
+    )
+    .unwrap();
+    assert!(!trap.is_a_trap());
+
+    for (i, el) in expected.iter().enumerate() {
+        assert_eq!(
+            memory.get_noexcept((STATE_PTR + 4 * i as u32) as u64),
+            el.to_reduced_u32()
+        );
+    }
+
+    // state must be aligned to avoid carries in the address computation
+    state.registers[10] = STATE_PTR + 32;
+    let err = poseidon2_permutation_function(
+        &mut state,
+        &mut memory,
+        &mut (),
+        &mut NoMMU { sapt: 0 },
+        0,
+        &mut trap,
+    )
+    .unwrap_err();
+    assert_eq!(err.id, POSEIDON2_PERMUTATION_ACCESS_ID);
+}
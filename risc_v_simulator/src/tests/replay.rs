@@ -0,0 +1,72 @@
+use crate::abstractions::non_determinism::QuasiUARTSource;
+use crate::cycle::IMStandardIsaConfig;
+use crate::replay::{OracleEvent, ReplayMismatch, ReplaySource};
+use crate::runner::{
+    replay_for_config, run_and_record_for_config,
+    run_simple_with_entry_point_and_non_determimism_source_for_config,
+};
+use crate::sim::SimulatorConfig;
+
+// csrrw x1, 0x7c0, x0
+const READ_X1: u32 = 0x7c0010f3;
+// add x3, x3, x1
+const ADD_X3_X1: u32 = 0x001181b3;
+// sw x3, 0x100(x0)
+const STORE_X3: u32 = 0x10302023;
+// csrrw x0, 0x7c0, x3
+const WRITE_X3: u32 = 0x7c019073;
+// jal x0, 0
+const HALT: u32 = 0x0000006f;
+
+#[test]
+fn test_record_and_replay() {
+    let mut program = vec![];
+    for _ in 0..4 {
+        program.extend([READ_X1, ADD_X3_X1, STORE_X3, WRITE_X3]);
+    }
+    program.push(HALT);
+    let bytes: Vec<u8> = program.iter().flat_map(|insn| insn.to_le_bytes()).collect();
+    let path = std::env::temp_dir().join(format!("replay_test_{}.bin", std::process::id()));
+    std::fs::write(&path, bytes).unwrap();
+
+    let config = || SimulatorConfig::new(path.clone(), 0, 64, None);
+
+    let (_, outcome, recording) = run_and_record_for_config::<_, IMStandardIsaConfig>(
+        config(),
+        QuasiUARTSource::new_with_reads(vec![1, 2, 3, 4]),
+        4,
+    );
+    assert!(outcome.is_finished());
+    assert_eq!(recording.events.len(), 8);
+    assert_eq!(recording.events[3].event, OracleEvent::Write(3));
+    assert_eq!(recording.checkpoints.len(), recording.cycles as usize / 4);
+
+    let (replayed, result) = replay_for_config(config(), &recording);
+    assert_eq!(result, Ok(()));
+    assert_eq!(replayed, outcome);
+
+    // replay source also works with the plain runner
+    let (source, replayed) = run_simple_with_entry_point_and_non_determimism_source_for_config::<
+        _,
+        IMStandardIsaConfig,
+    >(config(), ReplaySource::new(&recording.events));
+    assert!(source.mismatch.is_none());
+    assert_eq!(source.num_remaining_events(), 0);
+    assert_eq!(replayed, outcome);
+
+    // if the second read returns something else, then the second write diverges
+    let mut tampered = recording.clone();
+    tampered.events[2].event = OracleEvent::Read(20);
+    let (_, result) = replay_for_config(config(), &tampered);
+    let mismatch = result.unwrap_err();
+    assert_eq!(mismatch.cycle(), 7);
+    assert!(matches!(
+        mismatch,
+        ReplayMismatch::Event {
+            actual: OracleEvent::Write(21),
+            ..
+        }
+    ));
+
+    std::fs::remove_file(&path).unwrap();
+}
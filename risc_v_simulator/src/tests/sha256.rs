@@ -0,0 +1,101 @@
+use crate::abstractions::memory::{MemorySource, VectorMemoryImpl};
+use crate::cycle::state::RiscV32State;
+use crate::cycle::status_registers::TrapReason;
+use crate::cycle::IMStandardIsaConfig;
+use crate::delegations::sha256_round_function::*;
+use crate::mmu::NoMMU;
+
+const H: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+// single padded block of "abc"
+fn abc_block() -> [u32; 16] {
+    let mut block = [0u32; 16];
+    block[0] = 0x61626380;
+    block[15] = 24;
+
+    block
+}
+
+#[test]
+fn test_sha256_rounds_compress_abc() {
+    let mut state_and_schedule = [0u32; X10_NUM_WRITES];
+    state_and_schedule[..8].copy_from_slice(&H);
+    state_and_schedule[8..].copy_from_slice(&abc_block());
+    for round_index in 0..SHA256_NUM_ROUNDS {
+        sha256_round_with_message_schedule(&mut state_and_schedule, round_index);
+    }
+
+    let digest: [u32; 8] = std::array::from_fn(|i| H[i].wrapping_add(state_and_schedule[i]));
+    assert_eq!(
+        digest,
+        [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad
+        ]
+    );
+}
+
+#[test]
+fn test_sha256_delegation_updates_memory() {
+    const STATE_PTR: u32 = 1 << 21;
+
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 22);
+    let mut expected = [0u32; X10_NUM_WRITES];
+    expected[..8].copy_from_slice(&H);
+    expected[8..].copy_from_slice(&abc_block());
+    for (i, word) in expected.iter().enumerate() {
+        memory.populate(STATE_PTR + 4 * i as u32, *word);
+    }
+    sha256_round_with_message_schedule(&mut expected, 5);
+
+    let mut state = RiscV32State::<IMStandardIsaConfig>::initial(0);
+    state.registers[10] = STATE_PTR;
+    state.registers[11] = 5;
+    let mut trap = TrapReason::NoTrap;
+    sha256_round_function(
+        &mut state,
+        &mut memory,
+        &mut (),
+        &mut NoMMU { sapt: 0 },
+        0,
+        &mut trap,
+    )
+    .unwrap();
+    assert!(!trap.is_a_trap());
+
+    for (i, word) in expected.iter().enumerate() {
+        assert_eq!(
+            memory.get_noexcept((STATE_PTR + 4 * i as u32) as u64),
+            *word
+        );
+    }
+
+    // round constant is selected by the index, so index must be in range
+    state.registers[11] = SHA256_NUM_ROUNDS as u32;
+    let err = sha256_round_function(
+        &mut state,
+        &mut memory,
+        &mut (),
+        &mut NoMMU { sapt: 0 },
+        0,
+        &mut trap,
+    )
+    .unwrap_err();
+    assert_eq!(err.id, SHA256_ROUND_FUNCTION_ACCESS_ID);
+
+    // state must be aligned to avoid carries in the address computation
+    state.registers[10] = STATE_PTR + 32;
+    state.registers[11] = 0;
+    let err = sha256_round_function(
+        &mut state,
+        &mut memory,
+        &mut (),
+        &mut NoMMU { sapt: 0 },
+        0,
+        &mut trap,
+    )
+    .unwrap_err();
+    assert_eq!(err.id, SHA256_ROUND_FUNCTION_ACCESS_ID);
+}
@@ -0,0 +1,71 @@
+use crate::abstractions::memory::VectorMemoryImpl;
+use crate::abstractions::non_determinism::QuasiUARTSource;
+use crate::cycle::state::RiscV32State;
+use crate::cycle::IMStandardIsaConfig;
+use crate::runner::{resume_from_snapshot_for_config, run_simple_with_snapshots_for_config};
+use crate::sim::{ExecutionOutcome, SimulatorConfig};
+use crate::snapshot::ExecutionSnapshot;
+
+// csrrw x1, 0x7c0, x0
+const READ_X1: u32 = 0x7c0010f3;
+// add x3, x3, x1
+const ADD_X3_X1: u32 = 0x001181b3;
+// sw x3, 0x100(x0)
+const STORE_X3: u32 = 0x10302023;
+// jal x0, 0
+const HALT: u32 = 0x0000006f;
+
+#[test]
+fn test_memory_snapshot_is_sparse() {
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 20);
+    memory.populate(0x10, 1);
+    memory.populate(0x8_0000, 2);
+
+    let snapshot = memory.snapshot();
+    assert_eq!(snapshot.pages.len(), 2);
+
+    let mut restored = VectorMemoryImpl::new_for_byte_size(1 << 20);
+    restored.restore_from_snapshot(&snapshot);
+    assert_eq!(restored.inner, memory.inner);
+    assert_eq!(restored.snapshot(), snapshot);
+}
+
+#[test]
+fn test_resume_from_snapshot() {
+    let mut program = vec![];
+    for _ in 0..4 {
+        program.extend([READ_X1, ADD_X3_X1, STORE_X3]);
+    }
+    program.push(HALT);
+    let bytes: Vec<u8> = program.iter().flat_map(|insn| insn.to_le_bytes()).collect();
+    let path = std::env::temp_dir().join(format!("snapshot_test_{}.bin", std::process::id()));
+    std::fs::write(&path, bytes).unwrap();
+
+    let config = || SimulatorConfig::new(path.clone(), 0, 64, None);
+    let inputs = vec![1, 2, 3, 4];
+
+    let mut snapshots: Vec<ExecutionSnapshot<RiscV32State<IMStandardIsaConfig>>> = vec![];
+    let (_, outcome) = run_simple_with_snapshots_for_config::<_, IMStandardIsaConfig>(
+        config(),
+        QuasiUARTSource::new_with_reads(inputs.clone()),
+        4,
+        |snapshot| snapshots.push(snapshot),
+    );
+
+    let ExecutionOutcome::Finished { registers, .. } = &outcome else {
+        panic!("unexpected outcome {:?}", outcome);
+    };
+    assert_eq!(registers[3], 10);
+    assert!(snapshots.len() >= 3);
+
+    for snapshot in snapshots.iter() {
+        let (_, resumed) = resume_from_snapshot_for_config::<_, IMStandardIsaConfig>(
+            config(),
+            snapshot,
+            QuasiUARTSource::new_with_reads(inputs.clone()),
+        );
+        assert_eq!(resumed, outcome, "resumed from cycle {}", snapshot.cycle);
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
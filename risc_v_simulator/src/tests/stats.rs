@@ -0,0 +1,81 @@
+use crate::abstractions::non_determinism::QuasiUARTSource;
+use crate::cycle::IMStandardIsaConfig;
+use crate::runner::run_with_stats_for_config;
+use crate::sim::SimulatorConfig;
+use crate::stats::CircuitChunkSizes;
+
+// csrrw x1, 0x7c0, x0
+const READ_X1: u32 = 0x7c0010f3;
+// add x3, x3, x1
+const ADD_X3_X1: u32 = 0x001181b3;
+// sw x3, 0x100(x0)
+const STORE_X3: u32 = 0x10302023;
+// csrrw x0, 0x7c0, x3
+const WRITE_X3: u32 = 0x7c019073;
+// lw x4, 0x100(x0)
+const LOAD_X4: u32 = 0x10002203;
+// lbu x5, 0x100(x0)
+const LOAD_BYTE_UNSIGNED_X5: u32 = 0x10004283;
+// lb x6, 0x100(x0)
+const LOAD_BYTE_X6: u32 = 0x10000303;
+// lhu x7, 0x100(x0)
+const LOAD_HALF_UNSIGNED_X7: u32 = 0x10005383;
+// jal x0, 0
+const HALT: u32 = 0x0000006f;
+
+#[test]
+fn test_execution_stats() {
+    let mut program = vec![];
+    for _ in 0..4 {
+        program.extend([READ_X1, ADD_X3_X1, STORE_X3, WRITE_X3]);
+    }
+    program.extend([
+        LOAD_X4,
+        LOAD_BYTE_UNSIGNED_X5,
+        LOAD_BYTE_X6,
+        LOAD_HALF_UNSIGNED_X7,
+        HALT,
+    ]);
+    let bytes: Vec<u8> = program.iter().flat_map(|insn| insn.to_le_bytes()).collect();
+    let path = std::env::temp_dir().join(format!("stats_test_{}.bin", std::process::id()));
+    std::fs::write(&path, bytes).unwrap();
+
+    let chunk_sizes = CircuitChunkSizes {
+        main_cycles_per_chunk: 8,
+        ..Default::default()
+    };
+    let (_, outcome, stats) = run_with_stats_for_config::<_, IMStandardIsaConfig>(
+        SimulatorConfig::new(path.clone(), 0, 64, None),
+        QuasiUARTSource::new_with_reads(vec![1, 2, 3, 4]),
+        chunk_sizes,
+    );
+    assert!(outcome.is_finished());
+
+    assert_eq!(stats.cycles, program.len() as u64);
+    assert_eq!(stats.opcodes["CSR"], 8);
+    assert_eq!(stats.opcodes["ADD"], 4);
+    assert_eq!(stats.opcodes["SW"], 4);
+    assert_eq!(stats.opcodes["LW"], 1);
+    assert_eq!(stats.opcodes["LBU"], 1);
+    assert_eq!(stats.opcodes["LB"], 1);
+    assert_eq!(stats.opcodes["LHU"], 1);
+    assert!(!stats.opcodes.contains_key("LH"));
+    assert_eq!(stats.opcodes["JAL"], 1);
+    assert_eq!(stats.memory_reads, 4);
+    assert_eq!(stats.memory_writes, 4);
+    assert_eq!(stats.non_determinism_writes, 4);
+    assert!(stats.delegations.is_empty());
+
+    assert_eq!(stats.pc_hits[&0], 1);
+    let program_size = program.len() as u32 * 4;
+    assert_eq!(
+        stats.coverage(0..program_size),
+        (program.len(), program.len())
+    );
+    assert_eq!(stats.coverage(0..2 * program_size).1, 2 * program.len());
+
+    assert_eq!(stats.chunks.main, 3);
+    assert!(stats.chunks.delegations.is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,59 @@
+use super::*;
+use crate::cycle::IMWithTrapHandlingIsaConfigWithAllDelegations;
+
+const TRAP_HANDLER: u32 = 0x40;
+
+// every trap is handled by accumulating `mcause` into a0, counting traps in a1,
+// and returning to the instruction after the trapping one
+const HANDLER: &[u32] = &[
+    0x34101373, // csrrw t1, mepc, x0
+    0x00430313, // addi t1, t1, 4
+    0x34131073, // csrrw x0, mepc, t1
+    0x342013f3, // csrrw t2, mcause, x0
+    0x00750533, // add a0, a0, t2
+    0x00158593, // addi a1, a1, 1
+    0x30200073, // mret
+];
+
+const PROGRAM: &[u32] = &[
+    0x04000293, // 0x00: addi t0, x0, 0x40
+    0x30529073, // 0x04: csrrw x0, mtvec, t0
+    0x00000000, // 0x08: illegal instruction
+    0x00000073, // 0x0c: ecall
+    0x00102603, // 0x10: lw a2, 1(x0), misaligned
+    0x00100073, // 0x14: ebreak
+    0x343016f3, // 0x18: csrrw a3, mtval, x0
+    0x7c101773, // 0x1c: csrrw a4, 0x7c1, x0, unsupported CSR
+];
+
+#[test]
+fn test_traps_and_mret() {
+    let mut memory = VectorMemoryImpl::new_for_byte_size(1 << 12);
+    for (idx, word) in PROGRAM.iter().enumerate() {
+        memory.populate(INITIAL_PC + idx as u32 * 4, *word);
+    }
+    for (idx, word) in HANDLER.iter().enumerate() {
+        memory.populate(TRAP_HANDLER + idx as u32 * 4, *word);
+    }
+
+    // 5 traps, each taking the trapping instruction and the full handler
+    const NUM_CYCLES: usize = 2 + 5 * (1 + HANDLER.len()) + 1;
+
+    let mut state =
+        RiscV32State::<IMWithTrapHandlingIsaConfigWithAllDelegations>::initial(INITIAL_PC);
+    let mut mmu = NoMMU::default();
+    for _ in 0..NUM_CYCLES {
+        state.cycle(&mut memory, &mut (), &mut mmu, &mut ZeroedSource);
+    }
+
+    assert_eq!(state.pc, INITIAL_PC + PROGRAM.len() as u32 * 4);
+    // IllegalInstruction + EnvironmentCallFromMMode + LoadAddressMisaligned + Breakpoint + IllegalInstruction
+    assert_eq!(state.registers[10], 2 + 11 + 4 + 3 + 2);
+    assert_eq!(state.registers[11], 5);
+    // misaligned load and unsupported CSR read do not write into RD
+    assert_eq!(state.registers[12], 0);
+    assert_eq!(state.registers[14], 0);
+    // `mtval` holds the trapping opcode
+    assert_eq!(state.registers[13], 0x00100073);
+    assert_eq!(state.machine_mode_trap_data.setup.tvec, TRAP_HANDLER);
+}
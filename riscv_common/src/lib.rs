@@ -1,5 +1,7 @@
 #![no_std]
 
+pub mod sha256;
+
 #[inline(always)]
 /// Writes a given word into CRS register.
 pub fn csr_write_word(word: usize) {
@@ -0,0 +1,69 @@
+// Guest-side bindings for SHA-256 compression delegation.
+//
+// ABI:
+// - x10 is a pointer to 128-byte aligned buffer of 24 words: 8 words of working variables a..h,
+//   followed by 16 words of the message schedule window W[t]..W[t+15]
+// - x11 contains the round index t in [0, 64), and round constant K[t] is selected by the circuit
+//
+// Single call performs one round and shifts the message schedule window by one word, so we need
+// 64 calls per block, and caller does the final feed-forward into the hash state
+
+pub const CSR_REGISTER_TO_TRIGGER: u32 = 0x7cb;
+
+pub const SHA256_STATE_WIDTH_IN_U32_WORDS: usize = 8;
+pub const SHA256_BLOCK_SIZE_IN_U32_WORDS: usize = 16;
+pub const SHA256_NUM_ROUNDS: usize = 64;
+
+pub const SHA256_INITIAL_STATE: [u32; SHA256_STATE_WIDTH_IN_U32_WORDS] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+#[inline(always)]
+fn csr_trigger_delegation(state_and_schedule_ptr: *mut u32, round_index: u32) {
+    unsafe {
+        core::arch::asm!(
+            "csrrw x0, 0x7cb, x0",
+            in("x10") state_and_schedule_ptr.addr(),
+            in("x11") round_index,
+            options(nostack, preserves_flags)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C, align(128))]
+pub struct Sha256Compressor {
+    working_variables: [u32; SHA256_STATE_WIDTH_IN_U32_WORDS],
+    message_schedule: [u32; SHA256_BLOCK_SIZE_IN_U32_WORDS],
+    pub state: [u32; SHA256_STATE_WIDTH_IN_U32_WORDS],
+}
+
+impl Sha256Compressor {
+    pub const fn new() -> Self {
+        Self {
+            working_variables: [0u32; SHA256_STATE_WIDTH_IN_U32_WORDS],
+            message_schedule: [0u32; SHA256_BLOCK_SIZE_IN_U32_WORDS],
+            state: SHA256_INITIAL_STATE,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = SHA256_INITIAL_STATE;
+    }
+
+    /// Compresses a single 64-byte block, given as big-endian words, into the current state
+    #[inline(never)]
+    pub fn compress(&mut self, block: &[u32; SHA256_BLOCK_SIZE_IN_U32_WORDS]) {
+        self.working_variables = self.state;
+        self.message_schedule = *block;
+        // working variables and message schedule are continuous and aligned as required by ABI
+        let ptr = self.working_variables.as_mut_ptr();
+        for round_index in 0..SHA256_NUM_ROUNDS {
+            csr_trigger_delegation(ptr, round_index as u32);
+        }
+
+        for (dst, src) in self.state.iter_mut().zip(self.working_variables.iter()) {
+            *dst = dst.wrapping_add(*src);
+        }
+    }
+}
@@ -8,6 +8,7 @@ pub mod setup;
 pub mod vk;
 
 use clap::ValueEnum;
+use prover::proof_encoding::ProofCircuitType;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, ValueEnum, Serialize, Deserialize)]
@@ -17,3 +18,14 @@ pub enum Machine {
     // Final reduced machine, used to generate a single proof at the end.
     ReducedFinal,
 }
+
+impl Machine {
+    /// Type of the main circuit proofs produced by this machine, as recorded in proof headers.
+    pub fn proof_circuit_type(&self) -> ProofCircuitType {
+        match self {
+            Machine::Standard => ProofCircuitType::RiscV,
+            Machine::Reduced => ProofCircuitType::RiscVReduced,
+            Machine::ReducedFinal => ProofCircuitType::RiscVFinalReduced,
+        }
+    }
+}
@@ -6,11 +6,13 @@ use blake2s_u32::Blake2sState;
 use clap::{Parser, Subcommand};
 use cli_lib::generate_constants::generate_constants_for_binary;
 use cli_lib::prover_utils::{
-    create_final_proofs_from_program_proof, create_proofs, generate_oracle_data_from_metadata,
-    serialize_to_file, u32_from_hex_string, ProvingLimit, VerifierCircuitsIdentifiers,
-    DEFAULT_CYCLES,
+    check_program_proof_headers, create_final_proofs_from_program_proof, create_proofs,
+    estimate_proving_cost, generate_oracle_data_from_metadata, read_program_proof, read_proof,
+    report_circuit_layout, u32_from_hex_string, write_program_proof, ProofFormat, ProvingLimit,
+    VerifierCircuitsIdentifiers, DEFAULT_CYCLES,
 };
 use cli_lib::Machine;
+use prover_examples::cost_estimate::ProvingCostModel;
 
 use cli_lib::vk::generate_vk;
 use execution_utils::ProgramProof;
@@ -21,28 +23,33 @@ use std::{fs, io::Write, iter};
 
 use prover::{
     merkle_trees::{MerkleTreeCapVarLength, MerkleTreeConstructor},
-    prover_stages::Proof,
+    proof_encoding::{ProofCircuitType, ProofHeader},
     risc_v_simulator::{
         abstractions::non_determinism::QuasiUARTSource,
         cycle::{
-            IMStandardIsaConfig, IWithoutByteAccessIsaConfig,
-            IWithoutByteAccessIsaConfigWithDelegation,
+            state::RiscV32State, IMStandardIsaConfig, IWithoutByteAccessIsaConfig,
+            IWithoutByteAccessIsaConfigWithDelegation, MachineConfig,
         },
-        runner::run_simple_with_entry_point_and_non_determimism_source_for_config,
-        sim::SimulatorConfig,
+        gdb::{accept_tcp_connection, StdioConnection},
+        replay::ExecutionRecording,
+        runner::{
+            replay_for_config, run_and_record_for_config,
+            run_simple_with_entry_point_and_non_determimism_source_for_config,
+            run_with_gdb_stub_for_config,
+        },
+        sim::{ExecutionOutcome, SimulatorConfig},
     },
 };
 
-fn deserialize_from_file<T: serde::de::DeserializeOwned>(filename: &str) -> T {
-    let src = std::fs::File::open(filename).unwrap();
-    serde_json::from_reader(src).unwrap()
-}
-
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Directory to store circuit setups in, so they are not recomputed on every run.
+    /// Can also be set by the AIRBENDER_SETUP_CACHE_DIR environment variable.
+    #[arg(long, global = true)]
+    setup_cache_dir: Option<String>,
 }
 
 #[derive(Clone, Debug, Parser, Default)]
@@ -61,7 +68,7 @@ struct InputConfig {
 #[derive(Subcommand)]
 enum Commands {
     Prove {
-        /// Path to binary
+        /// Path to the program - either flat binary or ELF executable
         #[arg(short, long)]
         bin: String,
         // Either load data from the input file or from RPC
@@ -88,6 +95,49 @@ enum Commands {
         /// If true, use GPU for proving.
         #[arg(long)]
         gpu: bool,
+        /// Format of the output proofs. Metadata is always written as JSON.
+        #[arg(long, value_enum, default_value = "json")]
+        proof_format: ProofFormat,
+    },
+    /// Estimates the number of circuits, memory, proof size and proving time for the program
+    /// by only tracing its execution, without generating any proofs.
+    EstimateCost {
+        /// Path to the program - either flat binary or ELF executable
+        #[arg(short, long)]
+        bin: String,
+        // Either load data from the input file or from RPC
+        #[clap(flatten)]
+        input: InputConfig,
+        #[arg(long, value_enum, default_value = "standard")]
+        machine: Machine,
+        /// Number of riscV cycles to run. 32_000_000 if not set.
+        #[arg(long)]
+        cycles: Option<usize>,
+        /// If present - write the estimate as JSON into the given file.
+        #[arg(long)]
+        output: Option<String>,
+        /// Prover throughput in committed field elements per second, used to estimate the time.
+        #[arg(long)]
+        prover_cells_per_second: Option<f64>,
+        /// Cycles of the recursion verifier per verified proof.
+        #[arg(long)]
+        recursion_cycles_per_proof: Option<u64>,
+        /// Blake2s delegation calls of the recursion verifier per verified proof.
+        #[arg(long)]
+        recursion_delegation_calls_per_proof: Option<u64>,
+    },
+    /// Prints column, constraint, lookup and opening counts of the compiled circuit layout,
+    /// and how they changed relative to the baseline layout if one is given.
+    CircuitReport {
+        /// Path to the serialized layout, e.g. circuit_defs/risc_v_cycles/generated/layout
+        #[arg(long)]
+        layout: String,
+        /// Path to the layout to compare with, e.g. the same file from the base branch.
+        #[arg(long)]
+        baseline: Option<String>,
+        /// If present - write the report (or the changes if there is a baseline) as JSON into the given file.
+        #[arg(long)]
+        output: Option<String>,
     },
     /// Run the 'final' step of proving (for example on the output from ZKSmith)
     ProveFinal {
@@ -96,10 +146,13 @@ enum Commands {
         input: InputConfig,
         #[arg(long, default_value = "output")]
         output_dir: String,
+        /// Format of the output program proof.
+        #[arg(long, value_enum, default_value = "json")]
+        proof_format: ProofFormat,
     },
     /// Verifies a single proof.
     Verify {
-        /// Path to proof file.
+        /// Path to proof file, either JSON or binary encoded.
         #[arg(short, long)]
         proof: String,
     },
@@ -112,6 +165,7 @@ enum Commands {
         program_proof: Option<String>,
     },
     Run {
+        /// Path to the program - either flat binary or ELF executable
         #[arg(short, long)]
         bin: String,
         // Either load data from the input file or from RPC
@@ -126,6 +180,18 @@ enum Commands {
 
         #[arg(long, value_enum, default_value = "standard")]
         machine: Machine,
+        /// If present - wait for GDB to connect on the given address (e.g. 127.0.0.1:3333),
+        /// or serve it over stdin/stdout if set to "stdio", and run under the debugger.
+        #[arg(long)]
+        gdb: Option<String>,
+        /// If present - record all the non-determinism accesses and the final state into the given file,
+        /// so the execution can be reproduced without the original inputs.
+        #[arg(long, conflicts_with_all = ["replay", "gdb"])]
+        record: Option<String>,
+        /// If present - replay the execution recorded with `--record` instead of using the inputs,
+        /// and report the first cycle where execution diverges from the recording.
+        #[arg(long, conflicts_with_all = ["input_file", "input_rpc", "gdb"])]
+        replay: Option<String>,
     },
 
     /// Generates verification key hash, for a given binary.
@@ -250,6 +316,9 @@ fn main() {
         .format_target(false)
         .init();
     let cli = Cli::parse();
+    if let Some(setup_cache_dir) = &cli.setup_cache_dir {
+        setups::disk_cache::set_setup_cache_dir(Some(setup_cache_dir.into()));
+    }
     match &cli.command {
         Commands::Prove {
             bin,
@@ -261,6 +330,7 @@ fn main() {
             until,
             tmp_dir,
             gpu,
+            proof_format,
         } => {
             let input_hex = fetch_input_hex_string(input).expect("Failed to fetch");
             create_proofs(
@@ -273,19 +343,61 @@ fn main() {
                 until,
                 tmp_dir,
                 gpu.clone(),
+                *proof_format,
             );
         }
-        Commands::ProveFinal { input, output_dir } => {
-            let input = fetch_final_input_json(input).expect("Failed to fetch");
-
-            let input_program_proof: ProgramProof = serde_json::from_str(&input.unwrap())
-                .expect("Failed to parse input_hex into ProgramProof");
+        Commands::EstimateCost {
+            bin,
+            input,
+            machine,
+            cycles,
+            output,
+            prover_cells_per_second,
+            recursion_cycles_per_proof,
+            recursion_delegation_calls_per_proof,
+        } => {
+            let input_hex = fetch_input_hex_string(input).expect("Failed to fetch");
+            let mut model = ProvingCostModel::default();
+            if let Some(value) = prover_cells_per_second {
+                model.prover_cells_per_second = *value;
+            }
+            if let Some(value) = recursion_cycles_per_proof {
+                model.recursion_cycles_per_proof = *value;
+            }
+            if let Some(value) = recursion_delegation_calls_per_proof {
+                model.recursion_delegation_calls_per_proof = *value;
+            }
+            estimate_proving_cost(bin, &input_hex, machine, cycles, &model, output);
+        }
+        Commands::CircuitReport {
+            layout,
+            baseline,
+            output,
+        } => report_circuit_layout(layout, baseline, output),
+        Commands::ProveFinal {
+            input,
+            output_dir,
+            proof_format,
+        } => {
+            let input_program_proof = if let Some(input_file) = &input.input_file {
+                let (headers, program_proof) = read_program_proof(Path::new(input_file));
+                // final proofs are created over recursion layer proofs
+                check_program_proof_headers(&headers, Machine::Reduced.proof_circuit_type());
+                program_proof
+            } else {
+                let input = fetch_final_input_json(input).expect("Failed to fetch");
+                let input_program_proof: ProgramProof = serde_json::from_str(&input.unwrap())
+                    .expect("Failed to parse input_hex into ProgramProof");
+                input_program_proof
+            };
 
             let program_proof = create_final_proofs_from_program_proof(input_program_proof);
 
-            serialize_to_file(
+            write_program_proof(
                 &program_proof,
-                &Path::new(output_dir).join("final_program_proof.json"),
+                Machine::ReducedFinal.proof_circuit_type(),
+                &Path::new(output_dir).join("final_program_proof"),
+                *proof_format,
             );
         }
         Commands::Verify { proof } => {
@@ -324,10 +436,22 @@ fn main() {
             input,
             expected_results,
             machine,
+            gdb,
+            record,
+            replay,
         } => {
             let input_hex = fetch_input_hex_string(input).expect("Failed to fetch");
 
-            run_binary(bin, cycles, &input_hex, expected_results, machine);
+            run_binary(
+                bin,
+                cycles,
+                &input_hex,
+                expected_results,
+                machine,
+                gdb,
+                record,
+                replay,
+            );
         }
         Commands::GenerateVk {
             bin,
@@ -388,27 +512,50 @@ pub fn merkle_caps_to_hash(caps: &Vec<MerkleTreeCapVarLength>) -> String {
 pub enum CircuitType {
     RiscV,
     RiscVReduced,
+    RiscVFinalReduced,
+    RiscVWithoutSignedMulDiv,
     DelegatedExtendedBlake,
+    DelegatedBigInt,
 }
 
 pub fn proof_name_to_circuit_type(file_name: &str) -> CircuitType {
     if file_name.starts_with("delegation_proof_1991_") {
         CircuitType::DelegatedExtendedBlake
+    } else if file_name.starts_with("delegation_proof_1994_") {
+        CircuitType::DelegatedBigInt
     } else if file_name.starts_with("proof_") {
         CircuitType::RiscV
     } else if file_name.starts_with("reduced_proof_") {
         CircuitType::RiscVReduced
+    } else if file_name.starts_with("final_proof_") {
+        CircuitType::RiscVFinalReduced
     } else {
         panic!("Failed to map file {} to a proof type.", file_name);
     }
 }
 
+pub fn proof_header_to_circuit_type(header: &ProofHeader) -> CircuitType {
+    match (header.circuit_type, header.delegation_type) {
+        (ProofCircuitType::RiscV, _) => CircuitType::RiscV,
+        (ProofCircuitType::RiscVReduced, _) => CircuitType::RiscVReduced,
+        (ProofCircuitType::RiscVFinalReduced, _) => CircuitType::RiscVFinalReduced,
+        (ProofCircuitType::RiscVWithoutSignedMulDiv, _) => CircuitType::RiscVWithoutSignedMulDiv,
+        (ProofCircuitType::Delegation, 1991) => CircuitType::DelegatedExtendedBlake,
+        (ProofCircuitType::Delegation, 1994) => CircuitType::DelegatedBigInt,
+        // there are no verifiers generated for other circuits yet
+        (circuit_type, delegation_type) => panic!(
+            "Verification of {:?} circuit proofs (delegation type {}) is not supported.",
+            circuit_type, delegation_type
+        ),
+    }
+}
+
 #[cfg(feature = "include_verifiers")]
 fn verify_proof(proof_path: &String) {
-    use cli_lib::prover_utils::get_end_params_output_suffix_from_proof;
+    use cli_lib::prover_utils::{check_layout_hash, get_end_params_output_suffix_from_proof};
 
     println!("Verifying proof from {}", proof_path);
-    let proof: Proof = deserialize_from_file(proof_path);
+    let (header, proof) = read_proof(Path::new(proof_path));
 
     let end_params_output = get_end_params_output_suffix_from_proof(&proof);
     println!("Final params hash: {:?}", end_params_output);
@@ -416,20 +563,29 @@ fn verify_proof(proof_path: &String) {
     let verification_key = merkle_caps_to_hash(&proof.setup_tree_caps);
     println!("Proof verification key is {}", verification_key);
 
-    let circuit_type = proof_name_to_circuit_type(
-        std::path::Path::new(proof_path)
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap(),
-    );
+    let circuit_type = match header {
+        Some(header) => {
+            check_layout_hash(&header);
+            proof_header_to_circuit_type(&header)
+        }
+        None => proof_name_to_circuit_type(
+            std::path::Path::new(proof_path)
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap(),
+        ),
+    };
 
     println!("Circuit type detected as {:?}", circuit_type);
 
     let shuffle_ram_inits_and_teardowns: bool = match circuit_type {
         CircuitType::RiscV => true,
         CircuitType::RiscVReduced => true,
+        CircuitType::RiscVFinalReduced => true,
+        CircuitType::RiscVWithoutSignedMulDiv => true,
         CircuitType::DelegatedExtendedBlake => false,
+        CircuitType::DelegatedBigInt => false,
     };
 
     let mut oracle_data = vec![];
@@ -448,33 +604,61 @@ fn verify_proof(proof_path: &String) {
 
     verifier_common::prover::nd_source_std::set_iterator(it);
 
-    match circuit_type {
+    // verifier is instantiated for the hash family recorded in the proof
+    let hash_family = proof.hash_family;
+    println!("Hash family is {:?}", hash_family);
+    let result = match circuit_type {
         CircuitType::RiscV => unsafe {
-            risc_v_cycles_verifier::verify(
+            risc_v_cycles_verifier::verify_for_hash_family_checked(
+                hash_family,
                 std::mem::MaybeUninit::uninit().assume_init_mut(),
                 &mut verifier_common::ProofPublicInputs::uninit(),
             )
         },
         CircuitType::RiscVReduced => unsafe {
-            reduced_risc_v_machine_verifier::verify(
+            reduced_risc_v_machine_verifier::verify_for_hash_family_checked(
+                hash_family,
                 std::mem::MaybeUninit::uninit().assume_init_mut(),
                 &mut verifier_common::ProofPublicInputs::uninit(),
             )
         },
-        CircuitType::DelegatedExtendedBlake => {
-            unsafe {
-                blake2_with_compression_verifier::verify(
-                    std::mem::MaybeUninit::uninit().assume_init_mut(),
-                    &mut verifier_common::ProofPublicInputs::uninit(),
-                )
-            };
-        }
+        CircuitType::RiscVFinalReduced => unsafe {
+            final_reduced_risc_v_machine_verifier::verify_for_hash_family_checked(
+                hash_family,
+                std::mem::MaybeUninit::uninit().assume_init_mut(),
+                &mut verifier_common::ProofPublicInputs::uninit(),
+            )
+        },
+        CircuitType::RiscVWithoutSignedMulDiv => unsafe {
+            machine_without_signed_mul_div_verifier::verify_for_hash_family_checked(
+                hash_family,
+                std::mem::MaybeUninit::uninit().assume_init_mut(),
+                &mut verifier_common::ProofPublicInputs::uninit(),
+            )
+        },
+        CircuitType::DelegatedExtendedBlake => unsafe {
+            blake2_with_compression_verifier::verify_for_hash_family_checked(
+                hash_family,
+                std::mem::MaybeUninit::uninit().assume_init_mut(),
+                &mut verifier_common::ProofPublicInputs::uninit(),
+            )
+        },
+        CircuitType::DelegatedBigInt => unsafe {
+            bigint_with_control_verifier::verify_for_hash_family_checked(
+                hash_family,
+                std::mem::MaybeUninit::uninit().assume_init_mut(),
+                &mut verifier_common::ProofPublicInputs::uninit(),
+            )
+        },
+    };
+    if let Err(error) = result {
+        panic!("proof verification failed: {}", error);
     }
     println!("PROOF IS VALID");
 }
 
 fn flatten_file(input_file: &String, output_file: &String) {
-    let proof: Proof = deserialize_from_file(input_file);
+    let (_, proof) = read_proof(Path::new(input_file));
     //let compiled_circuit: CompiledCircuitArtifact<Mersenne31Field> =
     //        deserialize_from_file("../../prover/delegation_layout");
     let shuffle_ram_inits_and_teardowns = true;
@@ -563,7 +747,8 @@ fn verify_all_program_proof(program_proof_path: &String) {
         proof_list_and_metadata_from_program_proof,
     };
 
-    let input_program_proof: ProgramProof = deserialize_from_file(&program_proof_path);
+    let (headers, input_program_proof) = read_program_proof(Path::new(program_proof_path));
+    check_program_proof_headers(&headers, Machine::Reduced.proof_circuit_type());
     //serde_json::from_str(&input.unwrap()).expect("Failed to parse input_hex into ProgramProof");
     let (metadata, proof_list) = proof_list_and_metadata_from_program_proof(input_program_proof);
 
@@ -602,12 +787,87 @@ fn u32_to_file(output_file: &String, numbers: &[u32]) {
     println!("Successfully wrote to file: {}", output_file);
 }
 
+/// State digests in the execution recording are taken every this number of cycles
+const REPLAY_CHECKPOINT_PERIOD: u64 = 1 << 16;
+
+fn registers_or_exit(outcome: ExecutionOutcome) -> [u32; 32] {
+    match outcome {
+        ExecutionOutcome::Finished { registers, .. } => registers,
+        outcome => {
+            eprintln!("Execution failed: {}", outcome);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_for_config<C: MachineConfig>(
+    config: SimulatorConfig,
+    non_determinism_source: QuasiUARTSource,
+    gdb: &Option<String>,
+    record: &Option<String>,
+    replay: &Option<String>,
+) -> [u32; 32] {
+    if let Some(replay) = replay {
+        let src = fs::File::open(replay).expect("Failed to open replay file");
+        let recording: ExecutionRecording<RiscV32State<C>> =
+            serde_json::from_reader(src).expect("Failed to parse replay file");
+        let (outcome, result) = replay_for_config(config, &recording);
+        if let Err(mismatch) = result {
+            eprintln!("Replay diverged from the recording at {}", mismatch);
+            std::process::exit(1);
+        }
+        println!("Replay matches the recording");
+
+        return registers_or_exit(outcome);
+    }
+
+    if let Some(record) = record {
+        let (_, outcome, recording) = run_and_record_for_config::<_, C>(
+            config,
+            non_determinism_source,
+            REPLAY_CHECKPOINT_PERIOD,
+        );
+        let dst = fs::File::create(record).expect("Failed to create replay file");
+        serde_json::to_writer(dst, &recording).expect("Failed to write replay file");
+        println!("Execution recording written to {}", record);
+
+        return registers_or_exit(outcome);
+    }
+
+    let (_, final_state) = match gdb.as_deref() {
+        None => {
+            let (_, outcome) = run_simple_with_entry_point_and_non_determimism_source_for_config::<
+                _,
+                C,
+            >(config, non_determinism_source);
+            return registers_or_exit(outcome);
+        }
+        Some("stdio") => run_with_gdb_stub_for_config::<_, C, _>(
+            config,
+            non_determinism_source,
+            StdioConnection::new(),
+        ),
+        Some(address) => run_with_gdb_stub_for_config::<_, C, _>(
+            config,
+            non_determinism_source,
+            accept_tcp_connection(address),
+        ),
+    };
+
+    #[allow(deprecated)]
+    final_state.registers
+}
+
+#[allow(clippy::too_many_arguments)]
 fn run_binary(
     bin_path: &String,
     cycles: &Option<usize>,
     input_hex: &Option<String>,
     expected_results: &Option<Vec<u32>>,
     machine: &Machine,
+    gdb: &Option<String>,
+    record: &Option<String>,
+    replay: &Option<String>,
 ) {
     let config = SimulatorConfig {
         bin_path: bin_path.into(),
@@ -624,36 +884,27 @@ fn run_binary(
     }
 
     let registers = match machine {
-        Machine::Standard => {
-            let (_, final_state) =
-                run_simple_with_entry_point_and_non_determimism_source_for_config::<
-                    _,
-                    IMStandardIsaConfig,
-                >(config, non_determinism_source);
-
-            #[allow(deprecated)]
-            final_state.registers
-        }
-        Machine::Reduced => {
-            let (_, final_state) =
-                run_simple_with_entry_point_and_non_determimism_source_for_config::<
-                    _,
-                    IWithoutByteAccessIsaConfigWithDelegation,
-                >(config, non_determinism_source);
-
-            #[allow(deprecated)]
-            final_state.registers
-        }
-        Machine::ReducedFinal => {
-            let (_, final_state) =
-                run_simple_with_entry_point_and_non_determimism_source_for_config::<
-                    _,
-                    IWithoutByteAccessIsaConfig,
-                >(config, non_determinism_source);
-
-            #[allow(deprecated)]
-            final_state.registers
-        }
+        Machine::Standard => run_for_config::<IMStandardIsaConfig>(
+            config,
+            non_determinism_source,
+            gdb,
+            record,
+            replay,
+        ),
+        Machine::Reduced => run_for_config::<IWithoutByteAccessIsaConfigWithDelegation>(
+            config,
+            non_determinism_source,
+            gdb,
+            record,
+            replay,
+        ),
+        Machine::ReducedFinal => run_for_config::<IWithoutByteAccessIsaConfig>(
+            config,
+            non_determinism_source,
+            gdb,
+            record,
+            replay,
+        ),
     };
 
     // our convention is to return 32 bytes placed into registers x10-x17
@@ -5,19 +5,38 @@ use execution_utils::{
     get_padded_binary, ProgramProof, UNIVERSAL_CIRCUIT_NO_DELEGATION_VERIFIER,
     UNIVERSAL_CIRCUIT_VERIFIER,
 };
+use prover_examples::cost_estimate::{ProvingCostEstimate, ProvingCostModel};
 use trace_and_split::FinalRegisterValue;
 use verifier_common::parse_field_els_as_u32_from_u16_limbs_checked;
 
 use prover::{
-    cs::utils::split_timestamp,
+    cs::{
+        one_row_compiler::{report::CircuitLayoutReport, CompiledCircuitArtifact},
+        utils::split_timestamp,
+    },
+    field::Mersenne31Field,
+    proof_encoding::{
+        compute_layout_hash, decode_proof, encode_proof, is_binary_encoded, ProofCircuitType,
+        ProofHeader,
+    },
     prover_stages::Proof,
     risc_v_simulator::{
         abstractions::non_determinism::QuasiUARTSource,
-        cycle::{IMStandardIsaConfig, IWithoutByteAccessIsaConfigWithDelegation, MachineConfig},
+        cycle::{
+            IMStandardIsaConfig, IWithoutByteAccessIsaConfig,
+            IWithoutByteAccessIsaConfigWithDelegation, MachineConfig,
+        },
     },
     transcript::{Blake2sBufferingTranscript, Seed},
 };
-use std::{alloc::Global, fs, io::Read, path::Path};
+use std::{
+    alloc::Global,
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
 
 fn deserialize_from_file<T: serde::de::DeserializeOwned>(filename: &str) -> T {
     let src = std::fs::File::open(filename).unwrap();
@@ -28,6 +47,218 @@ pub fn serialize_to_file<T: serde::Serialize>(el: &T, filename: &Path) {
     serde_json::to_writer_pretty(&mut dst, el).unwrap();
 }
 
+/// Format used to store proofs and program proofs. Metadata is always stored as JSON.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProofFormat {
+    /// Pretty printed JSON.
+    #[default]
+    Json,
+    /// Compact versioned binary encoding.
+    Binary,
+}
+
+impl ProofFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ProofFormat::Json => "json",
+            ProofFormat::Binary => "bin",
+        }
+    }
+}
+
+fn compiled_circuit_for(
+    circuit_type: ProofCircuitType,
+    delegation_type: u16,
+) -> CompiledCircuitArtifact<Mersenne31Field> {
+    // particular bytecode doesn't affect the layout
+    let dummy_bytecode = vec![0u32; setups::risc_v_cycles::MAX_ROM_SIZE / 4];
+    match circuit_type {
+        ProofCircuitType::RiscV => setups::risc_v_cycles::get_machine(
+            &dummy_bytecode,
+            setups::risc_v_cycles::ALLOWED_DELEGATION_CSRS,
+        ),
+        ProofCircuitType::RiscVReduced => setups::reduced_risc_v_machine::get_machine(
+            &dummy_bytecode,
+            setups::reduced_risc_v_machine::ALLOWED_DELEGATION_CSRS,
+        ),
+        ProofCircuitType::RiscVFinalReduced => setups::final_reduced_risc_v_machine::get_machine(
+            &dummy_bytecode,
+            setups::final_reduced_risc_v_machine::ALLOWED_DELEGATION_CSRS,
+        ),
+        ProofCircuitType::RiscVWithoutSignedMulDiv => {
+            setups::machine_without_signed_mul_div::get_machine(
+                &dummy_bytecode,
+                setups::machine_without_signed_mul_div::ALLOWED_DELEGATION_CSRS,
+            )
+        }
+        ProofCircuitType::RiscVWithBitmanip => {
+            // not part of setups until its generated artifacts are checked in
+            panic!("{:?} circuit is not supported", circuit_type)
+        }
+        ProofCircuitType::Delegation => {
+            setups::get_delegation_compiled_circuits_for_default_machine()
+                .into_iter()
+                .find(|(t, _)| *t == delegation_type as u32)
+                .unwrap_or_else(|| panic!("unknown delegation type {}", delegation_type))
+                .1
+                .compiled_circuit
+        }
+    }
+}
+
+/// Header for the binary encoding of the proof produced by the given circuit. Layout hashes are
+/// cached, as computing them requires circuit compilation.
+pub fn proof_header(circuit_type: ProofCircuitType, delegation_type: u16) -> ProofHeader {
+    static LAYOUT_HASHES: OnceLock<Mutex<HashMap<(ProofCircuitType, u16), [u32; 8]>>> =
+        OnceLock::new();
+
+    let mut layout_hashes = LAYOUT_HASHES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap();
+    let layout_hash = *layout_hashes
+        .entry((circuit_type, delegation_type))
+        .or_insert_with(|| {
+            compute_layout_hash(&compiled_circuit_for(circuit_type, delegation_type))
+        });
+
+    ProofHeader {
+        circuit_type,
+        delegation_type,
+        layout_hash,
+    }
+}
+
+/// Panics if the proof was produced for a different circuit layout than the current one.
+pub fn check_layout_hash(header: &ProofHeader) {
+    let expected = proof_header(header.circuit_type, header.delegation_type);
+    assert_eq!(
+        header.layout_hash, expected.layout_hash,
+        "Proof was created for a different layout of {:?} circuit",
+        header.circuit_type
+    );
+}
+
+/// Panics if the header is for a different circuit than the one that is expected to have produced
+/// the proof, or if the proof was created for a different layout of it.
+pub fn check_proof_header(
+    header: &ProofHeader,
+    circuit_type: ProofCircuitType,
+    delegation_type: u16,
+) {
+    assert_eq!(
+        (header.circuit_type, header.delegation_type),
+        (circuit_type, delegation_type),
+        "Expected proof of {:?} circuit (delegation type {}), but header is for another circuit",
+        circuit_type,
+        delegation_type
+    );
+    check_layout_hash(header);
+}
+
+/// Same as `check_proof_header`, but for all headers of the binary encoded program proof.
+pub fn check_program_proof_headers(
+    headers: &[ProofHeader],
+    base_layer_circuit_type: ProofCircuitType,
+) {
+    for header in headers.iter() {
+        let circuit_type = if header.delegation_type == 0 {
+            base_layer_circuit_type
+        } else {
+            ProofCircuitType::Delegation
+        };
+        check_proof_header(header, circuit_type, header.delegation_type);
+    }
+}
+
+fn path_with_extension(path_without_extension: &Path, format: ProofFormat) -> PathBuf {
+    path_without_extension.with_extension(format.extension())
+}
+
+pub fn write_proof(
+    proof: &Proof,
+    circuit_type: ProofCircuitType,
+    path_without_extension: &Path,
+    format: ProofFormat,
+) {
+    let path = path_with_extension(path_without_extension, format);
+    match format {
+        ProofFormat::Json => serialize_to_file(proof, &path),
+        ProofFormat::Binary => {
+            let header = proof_header(circuit_type, proof.delegation_type);
+            fs::write(&path, encode_proof(proof, &header)).unwrap()
+        }
+    }
+}
+
+/// Reads the proof in either format. Header is only available for binary encoded proofs.
+pub fn read_proof(path: &Path) -> (Option<ProofHeader>, Proof) {
+    let data = fs::read(path).unwrap_or_else(|e| panic!("Failed to read {:?}: {}", path, e));
+    if is_binary_encoded(&data) {
+        let (header, proof) =
+            decode_proof(&data).unwrap_or_else(|e| panic!("Failed to decode {:?}: {}", path, e));
+        (Some(header), proof)
+    } else {
+        (None, serde_json::from_slice(&data).unwrap())
+    }
+}
+
+/// Picks whichever of the binary encoded and JSON proofs was written last, so a stale file left
+/// over from a run in the other format doesn't shadow a fresh one.
+fn find_proof_file(path_without_extension: &Path) -> PathBuf {
+    let binary_path = path_with_extension(path_without_extension, ProofFormat::Binary);
+    let json_path = path_with_extension(path_without_extension, ProofFormat::Json);
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+    match (modified(&binary_path), modified(&json_path)) {
+        (Ok(binary_modified), Ok(json_modified)) => {
+            assert_ne!(
+                binary_modified, json_modified,
+                "Both {:?} and {:?} exist, can't tell which one is current",
+                binary_path, json_path
+            );
+            if binary_modified > json_modified {
+                binary_path
+            } else {
+                json_path
+            }
+        }
+        (Ok(_), Err(_)) => binary_path,
+        _ => json_path,
+    }
+}
+
+pub fn write_program_proof(
+    program_proof: &ProgramProof,
+    base_layer_circuit_type: ProofCircuitType,
+    path_without_extension: &Path,
+    format: ProofFormat,
+) {
+    let path = path_with_extension(path_without_extension, format);
+    match format {
+        ProofFormat::Json => serialize_to_file(program_proof, &path),
+        ProofFormat::Binary => {
+            let mut headers = vec![proof_header(base_layer_circuit_type, 0)];
+            for delegation_type in program_proof.delegation_proofs.keys() {
+                headers.push(proof_header(
+                    ProofCircuitType::Delegation,
+                    *delegation_type as u16,
+                ));
+            }
+            fs::write(&path, program_proof.encode(&headers)).unwrap();
+        }
+    }
+}
+
+/// Reads the program proof in either format. Headers are only available for binary encoded proofs.
+pub fn read_program_proof(path: &Path) -> (Vec<ProofHeader>, ProgramProof) {
+    let data = fs::read(path).unwrap_or_else(|e| panic!("Failed to read {:?}: {}", path, e));
+    if is_binary_encoded(&data) {
+        ProgramProof::decode(&data).unwrap_or_else(|e| panic!("Failed to decode {:?}: {}", path, e))
+    } else {
+        (vec![], serde_json::from_slice(&data).unwrap())
+    }
+}
+
 /// Default amount of cycles, if no flag is set.
 pub const DEFAULT_CYCLES: usize = 32_000_000;
 
@@ -118,69 +349,94 @@ pub struct ProofList {
 }
 
 impl ProofList {
-    pub fn write_to_directory(&self, output_dir: &Path) {
+    pub fn write_to_directory(&self, output_dir: &Path, format: ProofFormat) {
         println!("Writing proofs to {:?}", output_dir);
 
-        for (i, proof) in self.basic_proofs.iter().enumerate() {
-            serialize_to_file(
-                proof,
-                &Path::new(output_dir).join(&format!("proof_{}.json", i)),
-            );
-        }
-        for (i, proof) in self.reduced_proofs.iter().enumerate() {
-            serialize_to_file(
-                proof,
-                &Path::new(output_dir).join(&format!("reduced_proof_{}.json", i)),
-            );
-        }
-        for (i, proof) in self.final_proofs.iter().enumerate() {
-            serialize_to_file(
-                proof,
-                &Path::new(output_dir).join(&format!("final_proof_{}.json", i)),
-            );
+        let main_proofs = [
+            (&self.basic_proofs, "proof", Machine::Standard),
+            (&self.reduced_proofs, "reduced_proof", Machine::Reduced),
+            (&self.final_proofs, "final_proof", Machine::ReducedFinal),
+        ];
+        for (proofs, prefix, machine) in main_proofs {
+            for (i, proof) in proofs.iter().enumerate() {
+                write_proof(
+                    proof,
+                    machine.proof_circuit_type(),
+                    &output_dir.join(format!("{}_{}", prefix, i)),
+                    format,
+                );
+            }
         }
         for (delegation_type, proofs) in self.delegation_proofs.iter() {
             for (i, proof) in proofs.iter().enumerate() {
-                serialize_to_file(
+                write_proof(
                     proof,
-                    &Path::new(output_dir)
-                        .join(&format!("delegation_proof_{}_{}.json", delegation_type, i)),
+                    ProofCircuitType::Delegation,
+                    &output_dir.join(format!("delegation_proof_{}_{}", delegation_type, i)),
+                    format,
                 );
             }
         }
     }
 
+    /// Proofs can be stored in either format. Headers of binary encoded proofs are checked against
+    /// the circuit that is expected to produce the proof.
     pub fn load_from_directory(input_dir: &String, metadata: &ProofMetadata) -> Self {
-        let mut basic_proofs = vec![];
-        for i in 0..metadata.basic_proof_count {
-            let proof_path = Path::new(input_dir).join(format!("proof_{}.json", i));
-            let proof: Proof = deserialize_from_file(proof_path.to_str().unwrap());
-            basic_proofs.push(proof);
-        }
+        let input_dir = Path::new(input_dir);
+        let load = |name: String, circuit_type: ProofCircuitType, delegation_type: u16| {
+            let path = find_proof_file(&input_dir.join(name));
+            let (header, proof) = read_proof(&path);
+            if let Some(header) = header {
+                check_proof_header(&header, circuit_type, delegation_type);
+            }
+            assert_eq!(
+                proof.delegation_type, delegation_type,
+                "Unexpected delegation type of proof in {:?}",
+                path
+            );
 
-        let mut reduced_proofs = vec![];
-        for i in 0..metadata.reduced_proof_count {
-            let proof_path = Path::new(input_dir).join(format!("reduced_proof_{}.json", i));
-            let proof: Proof = deserialize_from_file(proof_path.to_str().unwrap());
-            reduced_proofs.push(proof);
-        }
+            proof
+        };
 
-        let mut final_proofs = vec![];
-        for i in 0..metadata.final_proof_count {
-            let proof_path = Path::new(input_dir).join(format!("final_proof_{}.json", i));
-            let proof: Proof = deserialize_from_file(proof_path.to_str().unwrap());
-            final_proofs.push(proof);
-        }
+        let basic_proofs = (0..metadata.basic_proof_count)
+            .map(|i| {
+                load(
+                    format!("proof_{}", i),
+                    Machine::Standard.proof_circuit_type(),
+                    0,
+                )
+            })
+            .collect();
+        let reduced_proofs = (0..metadata.reduced_proof_count)
+            .map(|i| {
+                load(
+                    format!("reduced_proof_{}", i),
+                    Machine::Reduced.proof_circuit_type(),
+                    0,
+                )
+            })
+            .collect();
+        let final_proofs = (0..metadata.final_proof_count)
+            .map(|i| {
+                load(
+                    format!("final_proof_{}", i),
+                    Machine::ReducedFinal.proof_circuit_type(),
+                    0,
+                )
+            })
+            .collect();
 
         let mut delegation_proofs = vec![];
         for (delegation_type, count) in metadata.delegation_proof_count.iter() {
-            let mut proofs = vec![];
-            for i in 0..*count {
-                let proof_path = Path::new(input_dir)
-                    .join(format!("delegation_proof_{}_{}.json", delegation_type, i));
-                let proof: Proof = deserialize_from_file(proof_path.to_str().unwrap());
-                proofs.push(proof);
-            }
+            let proofs = (0..*count)
+                .map(|i| {
+                    load(
+                        format!("delegation_proof_{}_{}", delegation_type, i),
+                        ProofCircuitType::Delegation,
+                        *delegation_type as u16,
+                    )
+                })
+                .collect();
             delegation_proofs.push((*delegation_type, proofs));
         }
 
@@ -256,6 +512,7 @@ pub fn create_proofs(
     until: &Option<ProvingLimit>,
     tmp_dir: &Option<String>,
     use_gpu: bool,
+    proof_format: ProofFormat,
 ) {
     let prev_metadata: Option<ProofMetadata> = prev_metadata
         .as_ref()
@@ -306,7 +563,7 @@ pub fn create_proofs(
             if !base_tmp_dir.exists() {
                 fs::create_dir_all(&base_tmp_dir).expect("Failed to create tmp dir");
             }
-            proof_list.write_to_directory(&base_tmp_dir);
+            proof_list.write_to_directory(&base_tmp_dir, ProofFormat::Json);
             serialize_to_file(&proof_metadata, &base_tmp_dir.join("metadata.json"))
         }
         let (recursion_proof_list, recursion_proof_metadata) = create_recursion_proofs(
@@ -318,7 +575,7 @@ pub fn create_proofs(
         );
         match until {
             ProvingLimit::FinalRecursion => {
-                recursion_proof_list.write_to_directory(Path::new(output_dir));
+                recursion_proof_list.write_to_directory(Path::new(output_dir), proof_format);
 
                 serialize_to_file(
                     &recursion_proof_metadata,
@@ -328,24 +585,28 @@ pub fn create_proofs(
                     &recursion_proof_list,
                     &recursion_proof_metadata,
                 );
-                serialize_to_file(
+                write_program_proof(
                     &program_proof,
-                    &Path::new(output_dir).join("recursion_program_proof.json"),
+                    Machine::Reduced.proof_circuit_type(),
+                    &Path::new(output_dir).join("recursion_program_proof"),
+                    proof_format,
                 );
             }
             ProvingLimit::FinalProof => {
                 let program_proof =
                     create_final_proofs(recursion_proof_list, recursion_proof_metadata, tmp_dir);
 
-                serialize_to_file(
+                write_program_proof(
                     &program_proof,
-                    &Path::new(output_dir).join("final_program_proof.json"),
+                    Machine::ReducedFinal.proof_circuit_type(),
+                    &Path::new(output_dir).join("final_program_proof"),
+                    proof_format,
                 );
             }
             ProvingLimit::Snark => todo!(),
         }
     } else {
-        proof_list.write_to_directory(Path::new(output_dir));
+        proof_list.write_to_directory(Path::new(output_dir), proof_format);
 
         serialize_to_file(
             &proof_metadata,
@@ -361,6 +622,123 @@ pub fn create_proofs(
     }
 }
 
+/// Runs only the tracing and reports the projected number of circuits, memory, proof size and
+/// prover time for the base layer and recursion, without proving anything.
+pub fn estimate_proving_cost(
+    bin_path: &String,
+    input_hex: &Option<String>,
+    machine: &Machine,
+    cycles: &Option<usize>,
+    model: &ProvingCostModel,
+    output: &Option<String>,
+) -> ProvingCostEstimate {
+    let binary = load_binary_from_path(bin_path);
+    let num_instances = (cycles.unwrap_or(DEFAULT_CYCLES) / risc_v_cycles::NUM_CYCLES) + 1;
+
+    let mut non_determinism_source = QuasiUARTSource::default();
+    if let Some(input_hex) = input_hex {
+        for entry in u32_from_hex_string(input_hex) {
+            non_determinism_source.oracle.push_back(entry);
+        }
+    }
+
+    let worker = worker::Worker::new_with_num_threads(8);
+    let estimate = match machine {
+        Machine::Standard => {
+            prover_examples::cost_estimate::estimate_proving_cost::<_, IMStandardIsaConfig>(
+                num_instances,
+                &binary,
+                non_determinism_source,
+                model,
+                &worker,
+            )
+        }
+        Machine::Reduced => prover_examples::cost_estimate::estimate_proving_cost::<
+            _,
+            IWithoutByteAccessIsaConfigWithDelegation,
+        >(
+            num_instances,
+            &binary,
+            non_determinism_source,
+            model,
+            &worker,
+        ),
+        Machine::ReducedFinal => {
+            prover_examples::cost_estimate::estimate_proving_cost::<_, IWithoutByteAccessIsaConfig>(
+                num_instances,
+                &binary,
+                non_determinism_source,
+                model,
+                &worker,
+            )
+        }
+    };
+
+    println!(
+        "Executed {} cycles, final pc = 0x{:08x}",
+        estimate.cycles, estimate.final_pc
+    );
+    for layer in estimate.layers.iter() {
+        println!("{}:", layer.layer);
+        for circuit in layer.circuits.iter() {
+            println!(
+                "  {:<16} {:>6} circuits, {:>12} used of {:>12}, {:>8} MiB per circuit, {:>10} KiB of proofs, {:>10.1}s",
+                circuit.circuit,
+                circuit.count,
+                circuit.used,
+                circuit.count * circuit.dimensions.capacity,
+                circuit.memory_bytes >> 20,
+                circuit.proof_bytes >> 10,
+                circuit.prover_time_secs,
+            );
+        }
+    }
+    if estimate.recursion_converged == false {
+        println!(
+            "Recursion did not converge within {} layers",
+            model.max_recursion_layers
+        );
+    }
+    println!(
+        "Total: {} proofs, {} KiB of proofs, {} MiB peak memory, {:.1}s of proving",
+        estimate.total_proofs,
+        estimate.total_proof_bytes >> 10,
+        estimate.peak_memory_bytes >> 20,
+        estimate.total_prover_time_secs,
+    );
+
+    if let Some(output) = output {
+        serialize_to_file(&estimate, Path::new(output));
+    }
+
+    estimate
+}
+
+/// Prints the report of the circuit `layout` (as serialized into `generated/layout` of the circuit definitions),
+/// and the changes relative to the `baseline` layout if it's given. JSON of the report, or of the changes
+/// if there is a baseline, is written into `output`
+pub fn report_circuit_layout(layout: &String, baseline: &Option<String>, output: &Option<String>) {
+    let compiled_circuit: CompiledCircuitArtifact<Mersenne31Field> = deserialize_from_file(layout);
+    let report = CircuitLayoutReport::from_compiled_circuit(&compiled_circuit);
+    println!("{}", report);
+
+    let diff = baseline.as_ref().map(|baseline| {
+        let baseline_circuit: CompiledCircuitArtifact<Mersenne31Field> =
+            deserialize_from_file(baseline);
+        let diff = CircuitLayoutReport::from_compiled_circuit(&baseline_circuit).diff(&report);
+        println!("Changes relative to {}:\n{}", baseline, diff);
+
+        diff
+    });
+
+    if let Some(output) = output {
+        match diff {
+            Some(diff) => serialize_to_file(&diff, Path::new(output)),
+            None => serialize_to_file(&report, Path::new(output)),
+        }
+    }
+}
+
 pub fn load_binary_from_path(path: &String) -> Vec<u32> {
     let mut file = std::fs::File::open(path).expect("must open provided file");
     let mut buffer = vec![];
@@ -673,7 +1051,7 @@ pub fn create_recursion_proofs(
             if !base_tmp_dir.exists() {
                 fs::create_dir_all(&base_tmp_dir).expect("Failed to create tmp dir");
             }
-            current_proof_list.write_to_directory(&base_tmp_dir);
+            current_proof_list.write_to_directory(&base_tmp_dir, ProofFormat::Json);
             serialize_to_file(&current_proof_metadata, &base_tmp_dir.join("metadata.json"))
         }
 
@@ -724,7 +1102,7 @@ pub fn create_final_proofs(
             if !base_tmp_dir.exists() {
                 fs::create_dir_all(&base_tmp_dir).expect("Failed to create tmp dir");
             }
-            current_proof_list.write_to_directory(&base_tmp_dir);
+            current_proof_list.write_to_directory(&base_tmp_dir, ProofFormat::Json);
             serialize_to_file(&current_proof_metadata, &base_tmp_dir.join("metadata.json"))
         }
         final_proof_level += 1;
@@ -845,6 +1223,22 @@ pub fn generate_oracle_data_from_metadata_and_proof_list(
     metadata: &ProofMetadata,
     proofs: &ProofList,
 ) -> Vec<u32> {
+    // full statement verifier is instantiated for the default hash family only
+    for proof in proofs
+        .basic_proofs
+        .iter()
+        .chain(proofs.reduced_proofs.iter())
+        .chain(proofs.final_proofs.iter())
+        .chain(proofs.delegation_proofs.iter().flat_map(|(_, v)| v.iter()))
+    {
+        if let Err(error) = verifier_common::check_hash_family::<
+            verifier_common::DefaultLeafInclusionVerifier,
+        >(proof.hash_family)
+        {
+            panic!("proof verification failed: {}", error);
+        }
+    }
+
     let mut oracle_data = vec![];
     // first - it reads all the register values.
 
@@ -44,6 +44,47 @@ pub(crate) fn create_machine_without_signed_mul_div_layout_with_delegation() ->
     (compiled_machine, ssa)
 }
 
+// Compiled straight from the machine definition, as circuit_defs/machine_with_bitmanip includes the files
+// generated here. Sizes must be the same as in that crate
+pub(crate) fn create_machine_with_bitmanip_layout_with_delegation() -> (
+    CompiledCircuitArtifact<Mersenne31Field>,
+    Vec<Vec<RawExpression<Mersenne31Field>>>,
+) {
+    use prover::cs::machine::machine_configurations::create_csr_table_for_delegation;
+    use prover::cs::machine::machine_configurations::create_table_for_rom_image;
+    use prover::cs::machine::machine_configurations::full_isa_with_delegation_and_bitmanip_no_exceptions::FullIsaMachineWithDelegationAndBitmanipNoExceptionHandling;
+    use prover::cs::tables::TableType;
+    use prover::risc_v_simulator::cycle::{IMWithBitmanipIsaConfigWithAllDelegations, MachineConfig};
+
+    const DOMAIN_SIZE_LOG2: usize = 22;
+    const MAX_ROM_SIZE: usize = 1 << 21; // bytes
+    const ROM_ADDRESS_SPACE_SECOND_WORD_BITS: usize = (MAX_ROM_SIZE.trailing_zeros() - 16) as usize;
+
+    let dummy_bytecode = vec![0u32; MAX_ROM_SIZE / 4];
+    let rom_table = create_table_for_rom_image::<_, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(
+        &dummy_bytecode,
+        TableType::RomRead.to_table_id(),
+    );
+    let csr_table = create_csr_table_for_delegation(
+        true,
+        IMWithBitmanipIsaConfigWithAllDelegations::ALLOWED_DELEGATION_CSRS,
+        TableType::SpecialCSRProperties.to_table_id(),
+    );
+    let compiled_machine =
+        prover::cs::default_compile_machine::<_, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(
+            FullIsaMachineWithDelegationAndBitmanipNoExceptionHandling,
+            rom_table,
+            Some(csr_table),
+            DOMAIN_SIZE_LOG2,
+        );
+
+    let ssa = dump_ssa_witness_eval_form::<_, _, ROM_ADDRESS_SPACE_SECOND_WORD_BITS>(
+        FullIsaMachineWithDelegationAndBitmanipNoExceptionHandling,
+    );
+
+    (compiled_machine, ssa)
+}
+
 pub(crate) fn create_reduced_machine_layout_with_delegation() -> (
     CompiledCircuitArtifact<Mersenne31Field>,
     Vec<Vec<RawExpression<Mersenne31Field>>>,
@@ -113,3 +154,57 @@ pub(crate) fn create_bigint_with_control_delegation_layout() -> (
         setups::bigint_with_control::get_ssa_form(),
     )
 }
+
+// Circuits below are compiled straight from their definitions, and not through `setups`, as their
+// circuit_defs crates include the files generated here, so can not be built before the first run.
+// Domain size must be the same as DOMAIN_SIZE of the corresponding circuit_defs crate
+const DELEGATION_DOMAIN_SIZE_LOG2: usize = 20;
+
+macro_rules! delegation_layout_from_definition {
+    ($definition_fn:path) => {{
+        use prover::cs::cs::circuit::Circuit;
+        use prover::cs::cs::cs_reference::BasicAssembly;
+        use prover::cs::cs::witness_placer::graph_description::WitnessGraphCreator;
+        use prover::cs::one_row_compiler::OneRowCompiler;
+
+        let mut cs = BasicAssembly::<Mersenne31Field>::new();
+        $definition_fn(&mut cs);
+        let (circuit_output, _) = cs.finalize();
+        let compiled_circuit = OneRowCompiler::default()
+            .compile_to_evaluate_delegations(circuit_output, DELEGATION_DOMAIN_SIZE_LOG2);
+
+        let mut cs = BasicAssembly::<Mersenne31Field, WitnessGraphCreator<Mersenne31Field>>::new();
+        cs.witness_placer = Some(WitnessGraphCreator::<Mersenne31Field>::new());
+        $definition_fn(&mut cs);
+        let (_resolution_order, ssa_forms) = cs.witness_placer.unwrap().compute_resolution_order();
+
+        (compiled_circuit, ssa_forms)
+    }};
+}
+
+pub(crate) fn create_sha256_with_compression_delegation_layout() -> (
+    CompiledCircuitArtifact<Mersenne31Field>,
+    Vec<Vec<RawExpression<Mersenne31Field>>>,
+) {
+    delegation_layout_from_definition!(
+        prover::cs::delegation::sha256_round_with_message_schedule::define_sha256_round_with_message_schedule_delegation_circuit
+    )
+}
+
+pub(crate) fn create_keccak_f1600_delegation_layout() -> (
+    CompiledCircuitArtifact<Mersenne31Field>,
+    Vec<Vec<RawExpression<Mersenne31Field>>>,
+) {
+    delegation_layout_from_definition!(
+        prover::cs::delegation::keccak_f1600_round::define_keccak_f1600_round_delegation_circuit
+    )
+}
+
+pub(crate) fn create_poseidon2_permutation_delegation_layout() -> (
+    CompiledCircuitArtifact<Mersenne31Field>,
+    Vec<Vec<RawExpression<Mersenne31Field>>>,
+) {
+    delegation_layout_from_definition!(
+        prover::cs::delegation::poseidon2_permutation::define_poseidon2_permutation_delegation_circuit
+    )
+}
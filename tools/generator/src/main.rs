@@ -161,6 +161,10 @@ const ALL_LAYOUTS: &[(
         create_machine_without_signed_mul_div_layout_with_delegation,
         "machine_without_signed_mul_div",
     ),
+    (
+        create_machine_with_bitmanip_layout_with_delegation,
+        "machine_with_bitmanip",
+    ),
     (
         create_blake_with_compression_delegation_layout,
         "blake2_with_compression",
@@ -169,6 +173,15 @@ const ALL_LAYOUTS: &[(
         create_bigint_with_control_delegation_layout,
         "bigint_with_control",
     ),
+    (
+        create_sha256_with_compression_delegation_layout,
+        "sha256_with_compression",
+    ),
+    (create_keccak_f1600_delegation_layout, "keccak_f1600"),
+    (
+        create_poseidon2_permutation_delegation_layout,
+        "poseidon2_permutation",
+    ),
 ];
 
 fn main() {
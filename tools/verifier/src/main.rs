@@ -196,10 +196,14 @@ unsafe fn workload() -> ! {
             };
             let verification_ptr =
                 full_statement_verifier::RECURSION_LAYER_CIRCUITS_VERIFICATION_PARAMETERS[pos].3;
-            (verification_ptr)(
+            if (verification_ptr)(
                 &mut core::mem::MaybeUninit::uninit().assume_init_mut(),
                 &mut full_statement_verifier::verifier_common::ProofPublicInputs::uninit(),
-            );
+            )
+            .is_err()
+            {
+                riscv_common::zksync_os_finish_error();
+            }
             riscv_common::zksync_os_finish_success(&[1, 1, 1, 1, 1, 1, 1, 1]);
         }
     }
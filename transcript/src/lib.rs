@@ -11,6 +11,9 @@ pub use blake2s_u32;
 #[cfg(feature = "pow")]
 pub mod pow;
 
+mod poseidon2_transcript;
+pub use self::poseidon2_transcript::Poseidon2Transcript;
+
 // Our transcript for verifier efficiency is effectively stateless and has 3 functions:
 // - commit initial -> seed
 // - commit using some seed -> new seed
@@ -22,6 +25,105 @@ pub struct Blake2sTranscript;
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Seed(pub [u32; BLAKE2S_DIGEST_SIZE_U32_WORDS]);
 
+/// Interface of the stateless transcript above, so prover and verifier can be instantiated
+/// with different hash families. `Hasher` is a pre-allocated state that implementation can drive by itself
+pub trait StatelessTranscript: 'static + Clone + Copy + core::fmt::Debug + Send + Sync {
+    type Hasher;
+
+    fn new_hasher() -> Self::Hasher;
+
+    fn commit_initial_using_hasher(hasher: &mut Self::Hasher, input: &[u32]) -> Seed;
+
+    fn commit_with_seed_using_hasher(hasher: &mut Self::Hasher, seed: &mut Seed, input: &[u32]);
+
+    fn draw_randomness_using_hasher(hasher: &mut Self::Hasher, seed: &mut Seed, dst: &mut [u32]);
+
+    /// Returns `false` on insufficient PoW. Seed is updated in any case
+    fn check_pow_using_hasher(
+        hasher: &mut Self::Hasher,
+        seed: &mut Seed,
+        nonce: u64,
+        pow_bits: u32,
+    ) -> bool;
+
+    #[cfg(feature = "pow")]
+    fn search_pow(seed: &Seed, pow_bits: u32, worker: &worker::Worker) -> (Seed, u64);
+
+    /// Returns `false` if `input` can not be committed unambiguously, e.g. contains non-canonical
+    /// field elements for transcripts over algebraic hashes. Verifier must check inputs taken from the proof
+    fn is_valid_input(_input: &[u32]) -> bool {
+        true
+    }
+
+    fn commit_initial(input: &[u32]) -> Seed {
+        let mut hasher = Self::new_hasher();
+        Self::commit_initial_using_hasher(&mut hasher, input)
+    }
+
+    fn commit_with_seed(seed: &mut Seed, input: &[u32]) {
+        let mut hasher = Self::new_hasher();
+        Self::commit_with_seed_using_hasher(&mut hasher, seed, input);
+    }
+
+    fn draw_randomness(seed: &mut Seed, dst: &mut [u32]) {
+        let mut hasher = Self::new_hasher();
+        Self::draw_randomness_using_hasher(&mut hasher, seed, dst);
+    }
+
+    fn verify_pow(seed: &mut Seed, nonce: u64, pow_bits: u32) {
+        let mut hasher = Self::new_hasher();
+        let valid = Self::check_pow_using_hasher(&mut hasher, seed, nonce, pow_bits);
+        assert!(
+            valid,
+            "we expect {} bits of PoW using nonce {}",
+            pow_bits, nonce
+        );
+    }
+}
+
+impl StatelessTranscript for Blake2sTranscript {
+    type Hasher = blake2s_u32::DelegatedBlake2sState;
+
+    #[inline(always)]
+    fn new_hasher() -> Self::Hasher {
+        blake2s_u32::DelegatedBlake2sState::new()
+    }
+
+    #[inline(always)]
+    fn commit_initial_using_hasher(hasher: &mut Self::Hasher, input: &[u32]) -> Seed {
+        Blake2sTranscript::commit_initial_using_hasher(hasher, input)
+    }
+
+    #[inline(always)]
+    fn commit_with_seed_using_hasher(hasher: &mut Self::Hasher, seed: &mut Seed, input: &[u32]) {
+        Blake2sTranscript::commit_with_seed_using_hasher(hasher, seed, input);
+    }
+
+    #[inline(always)]
+    fn draw_randomness_using_hasher(hasher: &mut Self::Hasher, seed: &mut Seed, dst: &mut [u32]) {
+        Blake2sTranscript::draw_randomness_using_hasher(hasher, seed, dst);
+    }
+
+    #[inline(always)]
+    fn check_pow_using_hasher(
+        hasher: &mut Self::Hasher,
+        seed: &mut Seed,
+        nonce: u64,
+        pow_bits: u32,
+    ) -> bool {
+        Blake2sTranscript::check_pow_using_hasher(hasher, seed, nonce, pow_bits)
+    }
+
+    #[cfg(feature = "pow")]
+    fn search_pow(seed: &Seed, pow_bits: u32, worker: &worker::Worker) -> (Seed, u64) {
+        Blake2sTranscript::search_pow(seed, pow_bits, worker)
+    }
+
+    fn verify_pow(seed: &mut Seed, nonce: u64, pow_bits: u32) {
+        Blake2sTranscript::verify_pow(seed, nonce, pow_bits);
+    }
+}
+
 impl Blake2sTranscript {
     pub fn commit_initial(input: &[u32]) -> Seed {
         let mut hasher = blake2s_u32::DelegatedBlake2sState::new();
@@ -219,6 +321,25 @@ impl Blake2sTranscript {
         nonce: u64,
         pow_bits: u32,
     ) {
+        let valid = Self::check_pow_using_hasher(hasher, seed, nonce, pow_bits);
+        assert!(
+            valid,
+            "we expect {} bits of PoW using nonce {}, but top word is 0x{:08x} and full state is {:?}",
+            pow_bits,
+            nonce,
+            hasher.state[0],
+            &hasher.state,
+        );
+    }
+
+    /// Same as `verify_pow_using_hasher`, but returns `false` instead of panicking on insufficient PoW.
+    /// Seed is updated in any case
+    pub fn check_pow_using_hasher(
+        hasher: &mut blake2s_u32::DelegatedBlake2sState,
+        seed: &mut Seed,
+        nonce: u64,
+        pow_bits: u32,
+    ) -> bool {
         assert!(pow_bits <= 32);
         unsafe {
             hasher.reset();
@@ -247,17 +368,12 @@ impl Blake2sTranscript {
         }
 
         // check that first element is small enough
-        assert!(
-            hasher.state[0] <= (0xffffffff >> pow_bits),
-            "we expect {} bits of PoW using nonce {}, but top word is 0x{:08x} and full state is {:?}",
-            pow_bits,
-            nonce,
-            hasher.state[0],
-            &hasher.state,
-        );
+        let valid = hasher.state[0] <= (0xffffffff >> pow_bits);
 
         // copy it out
         *seed = Seed(hasher.read_state_for_output());
+
+        valid
     }
 }
 
@@ -0,0 +1,147 @@
+use super::*;
+use field::{Field, Mersenne31Field};
+use poseidon2::m31::{poseidon_permutation, Poseidon2Sponge};
+
+// Same stateless transcript as `Blake2sTranscript`, but over Poseidon2 permutation in Mersenne31 field,
+// so it can be cheaply re-evaluated in arithmetic circuits (e.g. in SNARK wrapper). Seed is 8 fully reduced field elements.
+// Committed words must be canonical field elements - it's the case for everything that prover commits
+// when Poseidon2 is also used for Merkle trees, and verifier rejects non-canonical words from the proof
+// using `is_valid_input` before committing them.
+// Drawn words are assembled from 16-bit low limbs of pairs of field elements, so they are (almost) uniform
+// over full 32 bits and the rest of the protocol (query indexes, extension field challenges) is unchanged
+
+const DRAW_WORDS_PER_PERMUTATION: usize = 8;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Poseidon2Transcript;
+
+impl Poseidon2Transcript {
+    #[inline(always)]
+    fn absorb_words(hasher: &mut Poseidon2Sponge, input: &[u32]) {
+        debug_assert!(Self::is_valid_input(input));
+        for word in input.iter() {
+            hasher.absorb_single(Mersenne31Field::from_nonreduced_u32(*word));
+        }
+    }
+
+    #[inline(always)]
+    fn seed_from_state(state: &[Mersenne31Field; 16]) -> Seed {
+        Seed(core::array::from_fn(|i| state[i].to_reduced_u32()))
+    }
+}
+
+impl StatelessTranscript for Poseidon2Transcript {
+    type Hasher = Poseidon2Sponge;
+
+    #[inline(always)]
+    fn new_hasher() -> Self::Hasher {
+        Poseidon2Sponge::new()
+    }
+
+    fn commit_initial_using_hasher(hasher: &mut Self::Hasher, input: &[u32]) -> Seed {
+        hasher.reset();
+        Self::absorb_words(hasher, input);
+
+        Seed(hasher.finalize().map(|el| el.to_reduced_u32()))
+    }
+
+    fn commit_with_seed_using_hasher(hasher: &mut Self::Hasher, seed: &mut Seed, input: &[u32]) {
+        hasher.reset();
+        Self::absorb_words(hasher, &seed.0);
+        Self::absorb_words(hasher, input);
+
+        *seed = Seed(hasher.finalize().map(|el| el.to_reduced_u32()));
+    }
+
+    fn draw_randomness_using_hasher(_hasher: &mut Self::Hasher, seed: &mut Seed, dst: &mut [u32]) {
+        debug_assert_eq!(
+            dst.len() % DRAW_WORDS_PER_PERMUTATION,
+            0,
+            "please pad the dst buffer to the multiple of {}",
+            DRAW_WORDS_PER_PERMUTATION
+        );
+        for chunk in dst.chunks_mut(DRAW_WORDS_PER_PERMUTATION) {
+            let mut state = [Mersenne31Field::ZERO; 16];
+            for (dst, src) in state.iter_mut().zip(seed.0.iter()) {
+                *dst = Mersenne31Field::new(*src);
+            }
+            poseidon_permutation(&mut state);
+            for (i, dst) in chunk.iter_mut().enumerate() {
+                let low = state[2 * i].to_reduced_u32() & 0xffff;
+                let high = state[2 * i + 1].to_reduced_u32() & 0xffff;
+                *dst = low | (high << 16);
+            }
+            *seed = Self::seed_from_state(&state);
+        }
+    }
+
+    fn check_pow_using_hasher(
+        _hasher: &mut Self::Hasher,
+        seed: &mut Seed,
+        nonce: u64,
+        pow_bits: u32,
+    ) -> bool {
+        assert!(pow_bits <= 31);
+        let mut state = [Mersenne31Field::ZERO; 16];
+        for (dst, src) in state.iter_mut().zip(seed.0.iter()) {
+            *dst = Mersenne31Field::new(*src);
+        }
+        // LE 16-bit limbs of nonce
+        for i in 0..4 {
+            state[8 + i] = Mersenne31Field::new(((nonce >> (16 * i)) & 0xffff) as u32);
+        }
+        poseidon_permutation(&mut state);
+
+        // check that first element is small enough
+        let valid = state[0].to_reduced_u32() >> (31 - pow_bits) == 0;
+
+        *seed = Self::seed_from_state(&state);
+
+        valid
+    }
+
+    #[cfg(feature = "pow")]
+    fn search_pow(seed: &Seed, pow_bits: u32, worker: &worker::Worker) -> (Seed, u64) {
+        crate::pow::search_pow_for_checker(seed, pow_bits, worker, |seed, nonce| {
+            let mut hasher = Self::new_hasher();
+            Self::check_pow_using_hasher(&mut hasher, seed, nonce, pow_bits)
+        })
+    }
+    fn is_valid_input(input: &[u32]) -> bool {
+        input.iter().all(|word| *word < Mersenne31Field::ORDER)
+    }
+}
+
+#[cfg(all(test, feature = "pow"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poseidon2_transcript() {
+        let input: Vec<u32> = (0..19).collect();
+        let mut seed = Poseidon2Transcript::commit_initial(&input);
+        assert_ne!(seed, Poseidon2Transcript::commit_initial(&input[..18]));
+        for word in seed.0.iter() {
+            assert!(*word < Mersenne31Field::ORDER);
+        }
+
+        Poseidon2Transcript::commit_with_seed(&mut seed, &[1, 2, 3]);
+        let mut challenges = [0u32; 16];
+        Poseidon2Transcript::draw_randomness(&mut seed, &mut challenges);
+        // words span full 32 bits
+        assert!(challenges.iter().any(|el| *el >= Mersenne31Field::ORDER));
+
+        let worker = worker::Worker::new();
+        let pow_bits = 10;
+        let (expected_seed, nonce) = Poseidon2Transcript::search_pow(&seed, pow_bits, &worker);
+        Poseidon2Transcript::verify_pow(&mut seed, nonce, pow_bits);
+        assert_eq!(seed, expected_seed);
+        // non-canonical words can not be committed
+        assert!(Poseidon2Transcript::is_valid_input(&input));
+        assert!(!Poseidon2Transcript::is_valid_input(&[
+            0,
+            Mersenne31Field::ORDER
+        ]));
+        assert!(Blake2sTranscript::is_valid_input(&[u32::MAX]));
+    }
+}
@@ -120,3 +120,58 @@ impl Blake2sTranscript {
         (new_seed, challenge_u64)
     }
 }
+
+const GENERIC_NO_RESULT: u64 = u64::MAX;
+const GENERIC_ROUNDS_PER_INVOCATION: u64 = 1 << 12;
+
+/// Parallel PoW search for transcripts that do not have a specialized implementation. `check` must
+/// update the seed same way as verifier does, and return whether the nonce is good
+pub(crate) fn search_pow_for_checker<F: Fn(&mut Seed, u64) -> bool + Sync>(
+    seed: &Seed,
+    pow_bits: u32,
+    worker: &Worker,
+    check: F,
+) -> (Seed, u64) {
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+
+    let result = AtomicU64::new(GENERIC_NO_RESULT);
+    let num_workers = worker.num_cores as u64;
+    worker.scope(usize::MAX, |scope, _| {
+        for worker_idx in 0..num_workers {
+            let result = &result;
+            let check = &check;
+            Worker::smart_spawn(scope, worker_idx == num_workers - 1, move |_| {
+                for i in 0..((GENERIC_NO_RESULT - 1) / num_workers / GENERIC_ROUNDS_PER_INVOCATION)
+                {
+                    if result.load(Ordering::Relaxed) != GENERIC_NO_RESULT {
+                        break;
+                    }
+                    let base = (worker_idx + i * num_workers) * GENERIC_ROUNDS_PER_INVOCATION;
+                    for j in 0..GENERIC_ROUNDS_PER_INVOCATION {
+                        let challenge_u64 = base + j;
+                        let mut candidate_seed = *seed;
+                        if check(&mut candidate_seed, challenge_u64) {
+                            let _ = result.fetch_min(challenge_u64, Ordering::AcqRel);
+                            break;
+                        }
+                    }
+                }
+            })
+        }
+    });
+
+    let challenge_u64 = result.load(Ordering::SeqCst);
+    assert!(
+        challenge_u64 != GENERIC_NO_RESULT,
+        "failed to find PoW for {} bits",
+        pow_bits
+    );
+
+    // we should just recompute
+    let mut new_seed = *seed;
+    let valid = check(&mut new_seed, challenge_u64);
+    assert!(valid);
+
+    (new_seed, challenge_u64)
+}
@@ -20,6 +20,8 @@ use field::PrimeField;
 use verifier_common::non_determinism_source::NonDeterminismSource;
 use verifier_common::prover::definitions::LeafInclusionVerifier;
 use verifier_common::prover::definitions::MerkleTreeCap;
+use verifier_common::transcript::StatelessTranscript;
+use verifier_common::{CommittedOracle, VerificationError};
 
 pub type ProofSkeletonInstance = ProofSkeleton<
     SKELETON_PADDING,
@@ -160,7 +162,7 @@ pub const BASE_CIRCUIT_QUERY_VALUES_NO_PADDING_U32_WORDS: usize = const {
 
 impl ProofSkeletonInstance {
     #[inline(never)]
-    pub unsafe fn fill<I: NonDeterminismSource>(this: *mut Self) {
+    pub unsafe fn fill<I: NonDeterminismSource>(this: *mut Self) -> Result<(), VerificationError> {
         let dst = this.cast::<u32>();
         let modulus = Mersenne31Field::CHARACTERISTICS as u32;
         // we need to make few stops here and switch between field elements and u32 unstructured values
@@ -237,13 +239,15 @@ impl ProofSkeletonInstance {
             i += 1;
         }
         // NOTE: black boxes here are to avoid u16 abuse by compiler
-        assert!(
-            this.as_ref_unchecked().circuit_sequence_idx & core::hint::black_box(0xffff0000u32)
-                == 0
-        );
-        assert!(
-            this.as_ref_unchecked().delegation_type & core::hint::black_box(0xffff0000u32) == 0
-        );
+        if this.as_ref_unchecked().circuit_sequence_idx & core::hint::black_box(0xffff0000u32) != 0
+        {
+            return Err(VerificationError::MalformedProof);
+        }
+        if this.as_ref_unchecked().delegation_type & core::hint::black_box(0xffff0000u32) != 0 {
+            return Err(VerificationError::MalformedProof);
+        }
+
+        Ok(())
     }
 
     pub fn transcript_elements_before_stage2(&'_ self) -> &'_ [u32] {
@@ -343,6 +347,24 @@ impl ProofSkeletonInstance {
             core::slice::from_raw_parts(start, len)
         }
     }
+
+    /// Checks that everything the verifier commits from the skeleton can be committed by transcript `T`,
+    /// e.g. caps are canonical field elements if transcript is over Poseidon2
+    pub fn is_valid_transcript_input<T: StatelessTranscript>(&self) -> bool {
+        let mut valid = T::is_valid_input(self.transcript_elements_before_stage2());
+        valid &= T::is_valid_input(self.transcript_elements_stage2_to_stage3());
+        valid &= T::is_valid_input(self.transcript_elements_stage3_to_stage4());
+        valid &= T::is_valid_input(self.transcript_elements_evaluations_at_z());
+        for caps in self.transcript_elements_fri_intermediate_oracles() {
+            valid &= T::is_valid_input(caps);
+        }
+        if LAST_FRI_STEP_EXPOSE_LEAFS {
+            valid &= T::is_valid_input(self.transcript_elements_last_fri_step_leaf_values());
+        }
+        valid &= T::is_valid_input(self.transcript_elements_monomial_coefficients());
+
+        valid
+    }
 }
 
 impl QueryValuesInstance {
@@ -351,17 +373,14 @@ impl QueryValuesInstance {
         this: *mut Self,
         proof_skeleton: &ProofSkeletonInstance,
         hasher: &mut V,
-    ) {
+    ) -> Result<(), VerificationError> {
         let dst = this.cast::<u32>();
         let modulus = Mersenne31Field::CHARACTERISTICS as u32;
         // query index
         let query_index = I::read_word();
-        assert!(
-            query_index < (1u32 << BITS_FOR_QUERY_INDEX),
-            "query index 0x{:08x} must be smaller than 0x{:08x}",
-            query_index,
-            1u32 << BITS_FOR_QUERY_INDEX
-        );
+        if query_index >= (1u32 << BITS_FOR_QUERY_INDEX) {
+            return Err(VerificationError::MalformedProof);
+        }
         dst.write(query_index);
         let mut i = 1;
         // leaf values are field elements
@@ -386,7 +405,12 @@ impl QueryValuesInstance {
             ),
             &proof_skeleton.setup_caps,
         );
-        assert!(setup_included);
+        if !setup_included {
+            return Err(VerificationError::MerklePathFailure {
+                oracle: CommittedOracle::Setup,
+                query_index,
+            });
+        }
 
         let witness_included = hasher.verify_leaf_inclusion::<I, TREE_CAP_SIZE, NUM_COSETS>(
             coset_index,
@@ -398,7 +422,12 @@ impl QueryValuesInstance {
             ),
             &proof_skeleton.witness_caps,
         );
-        assert!(witness_included);
+        if !witness_included {
+            return Err(VerificationError::MerklePathFailure {
+                oracle: CommittedOracle::Witness,
+                query_index,
+            });
+        }
 
         let memory_included = hasher.verify_leaf_inclusion::<I, TREE_CAP_SIZE, NUM_COSETS>(
             coset_index,
@@ -410,7 +439,12 @@ impl QueryValuesInstance {
             ),
             &proof_skeleton.memory_caps,
         );
-        assert!(memory_included);
+        if !memory_included {
+            return Err(VerificationError::MerklePathFailure {
+                oracle: CommittedOracle::Memory,
+                query_index,
+            });
+        }
 
         let stage_2_included = hasher.verify_leaf_inclusion::<I, TREE_CAP_SIZE, NUM_COSETS>(
             coset_index,
@@ -422,7 +456,12 @@ impl QueryValuesInstance {
             ),
             &proof_skeleton.stage_2_caps,
         );
-        assert!(stage_2_included);
+        if !stage_2_included {
+            return Err(VerificationError::MerklePathFailure {
+                oracle: CommittedOracle::Stage2,
+                query_index,
+            });
+        }
 
         let quotient_included = hasher.verify_leaf_inclusion::<I, TREE_CAP_SIZE, NUM_COSETS>(
             coset_index,
@@ -434,7 +473,12 @@ impl QueryValuesInstance {
             ),
             &proof_skeleton.quotient_caps,
         );
-        assert!(quotient_included);
+        if !quotient_included {
+            return Err(VerificationError::MerklePathFailure {
+                oracle: CommittedOracle::Quotient,
+                query_index,
+            });
+        }
 
         let mut fri_tree_index = tree_index;
         let mut fri_path_length = DEFAULT_MERKLE_PATH_LENGTH;
@@ -451,22 +495,33 @@ impl QueryValuesInstance {
                 core::slice::from_raw_parts(fri_leaf_start.cast::<u32>(), leaf_size),
                 caps,
             );
-            assert!(fri_oracle_included);
+            if !fri_oracle_included {
+                return Err(VerificationError::MerklePathFailure {
+                    oracle: CommittedOracle::FriIntermediate {
+                        step: fri_step as u32,
+                    },
+                    query_index,
+                });
+            }
 
             fri_leaf_start = fri_leaf_start.add(leaf_size);
         }
+
+        Ok(())
     }
 
     pub unsafe fn fill_array<I: NonDeterminismSource, V: LeafInclusionVerifier, const N: usize>(
         dst: *mut [Self; N],
         proof_skeleton: &ProofSkeletonInstance,
         hasher: &mut V,
-    ) {
+    ) -> Result<(), VerificationError> {
         let dst = dst.cast::<Self>();
         let mut i = 0;
         while i < N {
-            Self::fill::<I, V>(dst.add(i), proof_skeleton, hasher);
+            Self::fill::<I, V>(dst.add(i), proof_skeleton, hasher)?;
             i += 1;
         }
+
+        Ok(())
     }
 }
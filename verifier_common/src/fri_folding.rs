@@ -2,6 +2,7 @@ use core::mem::MaybeUninit;
 
 use field::{Field, FieldExtension, Mersenne31Complex, Mersenne31Field, Mersenne31Quartic};
 
+/// Returns `false` if the expected value is not present in the leaf at the expected position
 #[allow(invalid_value)]
 pub unsafe fn fri_fold_by_log_n<const FOLDING_DEGREE_LOG2: usize>(
     expected_value: &mut Mersenne31Quartic,
@@ -13,7 +14,7 @@ pub unsafe fn fri_fold_by_log_n<const FOLDING_DEGREE_LOG2: usize>(
     leaf: &[Mersenne31Field],
     fri_folding_challenges_powers: &[Mersenne31Quartic],
     shared_factors_for_folding: &[Mersenne31Complex],
-) {
+) -> bool {
     const MAX_SIZE_FOR_LEAF: usize = 32;
     const MAX_SIZE_FOR_ROOTS: usize = 16;
 
@@ -47,7 +48,9 @@ pub unsafe fn fri_fold_by_log_n<const FOLDING_DEGREE_LOG2: usize>(
     let value_at_expected_index =
         Mersenne31Quartic::from_array_of_base(value_at_expected_index.read());
     // check that our simulated value is actually in the leaf
-    assert_eq!(*expected_value, value_at_expected_index);
+    if *expected_value != value_at_expected_index {
+        return false;
+    }
 
     // note that our evaluation points share highest N-3 bits, so we can just precompute additional multiplication
     // factors for lower bits. We only need elements that are not negations of each other
@@ -149,8 +152,11 @@ pub unsafe fn fri_fold_by_log_n<const FOLDING_DEGREE_LOG2: usize>(
 
     *tree_index >>= FOLDING_DEGREE_LOG2;
     *domain_index = shared_bits_in_folding;
+
+    true
 }
 
+/// Same as `fri_fold_by_log_n`, but uses FMA and challenge passed without precomputed powers
 #[allow(invalid_value)]
 pub unsafe fn fri_fold_by_log_n_with_fma<const FOLDING_DEGREE_LOG2: usize>(
     expected_value: &mut Mersenne31Quartic,
@@ -162,7 +168,7 @@ pub unsafe fn fri_fold_by_log_n_with_fma<const FOLDING_DEGREE_LOG2: usize>(
     leaf: &[Mersenne31Field],
     fri_folding_challenge: &Mersenne31Quartic,
     shared_factors_for_folding: &[Mersenne31Complex],
-) {
+) -> bool {
     const MAX_SIZE_FOR_LEAF: usize = 32;
     const MAX_SIZE_FOR_ROOTS: usize = 16;
 
@@ -189,7 +195,9 @@ pub unsafe fn fri_fold_by_log_n_with_fma<const FOLDING_DEGREE_LOG2: usize>(
     let value_at_expected_index =
         Mersenne31Quartic::project_ref_from_array(value_at_expected_index.as_ref_unchecked());
     // check that our simulated value is actually in the leaf
-    assert!(*expected_value == *value_at_expected_index);
+    if *expected_value != *value_at_expected_index {
+        return false;
+    }
 
     // note that our evaluation points share highest N-3 bits, so we can just precompute additional multiplication
     // factors for lower bits. We only need elements that are not negations of each other
@@ -281,4 +289,6 @@ pub unsafe fn fri_fold_by_log_n_with_fma<const FOLDING_DEGREE_LOG2: usize>(
 
     *tree_index >>= FOLDING_DEGREE_LOG2;
     *domain_index = shared_bits_in_folding;
+
+    true
 }
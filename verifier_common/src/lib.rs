@@ -23,6 +23,9 @@ pub mod fri_folding;
 pub mod proof_flattener;
 
 pub mod structs;
+pub mod verification_error;
+
+pub use self::verification_error::{CommittedOracle, VerificationError};
 
 #[cfg(not(target_arch = "riscv32"))]
 pub type DefaultNonDeterminismSource = prover::nd_source_std::ThreadLocalBasedSource;
@@ -30,16 +33,42 @@ pub type DefaultNonDeterminismSource = prover::nd_source_std::ThreadLocalBasedSo
 #[cfg(target_arch = "riscv32")]
 pub type DefaultNonDeterminismSource = non_determinism_source::CSRBasedSource;
 
-#[cfg(not(all(target_arch = "riscv32", feature = "blake2_with_compression")))]
+#[cfg(not(any(
+    feature = "poseidon2_for_everything",
+    all(target_arch = "riscv32", feature = "blake2_with_compression")
+)))]
 pub type DefaultLeafInclusionVerifier = prover::definitions::Blake2sForEverythingVerifier;
 
 // pub type DefaultLeafInclusionVerifier =
 //     prover::definitions::Blake2sForLeafsPoseidon2ForNodesVerifier;
 
-#[cfg(all(target_arch = "riscv32", feature = "blake2_with_compression"))]
+#[cfg(all(
+    not(feature = "poseidon2_for_everything"),
+    target_arch = "riscv32",
+    feature = "blake2_with_compression"
+))]
 pub type DefaultLeafInclusionVerifier =
     prover::definitions::Blake2sForEverythingVerifierWithAlternativeCompression;
 
+#[cfg(feature = "poseidon2_for_everything")]
+pub type DefaultLeafInclusionVerifier = prover::definitions::Poseidon2ForEverythingVerifier;
+
+/// Transcript must always match the hash family of the leaf inclusion verifier
+pub type DefaultTranscript = <DefaultLeafInclusionVerifier as LeafInclusionVerifier>::Transcript;
+
+/// Hash family is not a part of the data that verifier reads, so the caller that has the proof
+/// must check the family recorded in it before running the verifier instantiated with `V`
+#[inline(always)]
+pub fn check_hash_family<V: LeafInclusionVerifier>(
+    hash_family: HashFamily,
+) -> Result<(), VerificationError> {
+    if hash_family != V::HASH_FAMILY {
+        return Err(VerificationError::HashFamilyMismatch);
+    }
+
+    Ok(())
+}
+
 pub type VerifierFunctionPointer<
     const CAP_SIZE: usize,
     const NUM_COSETS: usize,
@@ -51,6 +80,17 @@ pub type VerifierFunctionPointer<
     &mut ProofPublicInputs<NUM_STATE_ELEMENTS>,
 );
 
+pub type CheckedVerifierFunctionPointer<
+    const CAP_SIZE: usize,
+    const NUM_COSETS: usize,
+    const NUM_DELEGATION_CHALLENGES: usize,
+    const NUM_AUX_BOUNDARY_VALUES: usize,
+    const NUM_STATE_ELEMENTS: usize,
+> = unsafe fn(
+    &mut ProofOutput<CAP_SIZE, NUM_COSETS, NUM_DELEGATION_CHALLENGES, NUM_AUX_BOUNDARY_VALUES>,
+    &mut ProofPublicInputs<NUM_STATE_ELEMENTS>,
+) -> Result<(), VerificationError>;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash)]
 pub struct ProofOutput<
     const CAP_SIZE: usize,
@@ -145,11 +185,16 @@ impl<const NUM_STATE_ELEMENTS: usize> ProofPublicInputs<NUM_STATE_ELEMENTS> {
 }
 
 pub fn parse_field_els_as_u32_from_u16_limbs_checked(input: [Mersenne31Field; 2]) -> u32 {
+    try_parse_field_els_as_u32_from_u16_limbs(input).expect("limbs must be u16")
+}
+
+pub fn try_parse_field_els_as_u32_from_u16_limbs(input: [Mersenne31Field; 2]) -> Option<u32> {
     let [low, high] = input;
     let low = low.to_reduced_u32();
     let high = high.to_reduced_u32();
-    assert!(low & core::hint::black_box(0xffff0000u32) == 0);
-    assert!(high & core::hint::black_box(0xffff0000u32) == 0);
+    if (low | high) & core::hint::black_box(0xffff0000u32) != 0 {
+        return None;
+    }
 
-    low | (high << 16)
+    Some(low | (high << 16))
 }
@@ -0,0 +1,133 @@
+use core::fmt;
+
+/// Committed oracle whose Merkle path failed to verify
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommittedOracle {
+    Setup,
+    Witness,
+    Memory,
+    Stage2,
+    Quotient,
+    FriIntermediate { step: u32 },
+}
+
+/// Reason why the verifier rejected a proof. Single circuit verifiers report everything up to and
+/// including FRI, and full statement verifier additionally reports failures of the arguments that
+/// link the circuits together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VerificationError {
+    /// Proof contains values that are out of their expected ranges
+    MalformedProof,
+    /// Proof data ended before the verifier finished reading it
+    TruncatedProof,
+    /// Verifier finished, but not all the proof data was consumed
+    TrailingProofData,
+    /// Proof was produced for another hash family than the one verifier is instantiated with
+    HashFamilyMismatch,
+    /// Opened leaf is not included into the committed tree
+    MerklePathFailure {
+        oracle: CommittedOracle,
+        query_index: u32,
+    },
+    /// PoW nonce doesn't give the required number of zero bits
+    PowFailure,
+    /// Query is opened at index different from the one drawn from transcript
+    QueryIndexMismatch { query_round: u32 },
+    /// Challenge landed on the point where the quotient or DEEP poly can not be evaluated
+    DegenerateChallenge,
+    /// Quotient recomputed from openings at z doesn't match the opened quotient value
+    QuotientMismatch,
+    /// Value computed by folding of the previous FRI step is not in the leaf of the next one
+    FriFoldingMismatch { query_index: u32, step: u32 },
+    /// Fully folded value doesn't match the final polynomial in monomial form
+    FriFinalPolynomialMismatch { query_index: u32 },
+    /// Product of memory grand product accumulators over all circuits and registers is not 1
+    GrandProductImbalance,
+    /// Sum of delegation requests and delegation circuits accumulators is not 0
+    DelegationArgumentImbalance,
+    /// Total number of delegation requests over all delegation circuits is not below the field characteristic
+    TooManyDelegationRequests,
+    /// Number of circuits is zero or over the bound
+    InvalidNumberOfCircuits,
+    /// Circuit sequence index is not the next one in the chain
+    UnexpectedCircuitSequence,
+    /// Proof is produced by the circuit of unexpected delegation type
+    UnexpectedDelegationType,
+    /// Setup commitment differs between circuits or from the expected one
+    SetupMismatch,
+    /// External challenges differ between circuits or were not drawn from the memory commitments
+    ChallengesMismatch,
+    /// Lazy init address ranges of consecutive circuits overlap
+    LazyInitBoundaryMismatch,
+    /// Consecutive circuits do not continue from the same PC
+    PcDiscontinuity,
+    /// Final register values do not follow the output convention
+    InvalidRegisterValues,
+    /// Recursion chain preimage doesn't hash into the output registers
+    RecursionChainMismatch,
+    /// Final PC and setup do not match the verification key
+    VerificationKeyMismatch,
+}
+
+impl fmt::Display for CommittedOracle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Setup => write!(f, "setup"),
+            Self::Witness => write!(f, "witness"),
+            Self::Memory => write!(f, "memory"),
+            Self::Stage2 => write!(f, "stage 2"),
+            Self::Quotient => write!(f, "quotient"),
+            Self::FriIntermediate { step } => write!(f, "FRI step {}", step),
+        }
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedProof => write!(f, "malformed proof"),
+            Self::TruncatedProof => write!(f, "proof data is truncated"),
+            Self::TrailingProofData => write!(f, "proof data was not consumed in full"),
+            Self::HashFamilyMismatch => write!(f, "proof is produced for another hash family"),
+            Self::MerklePathFailure {
+                oracle,
+                query_index,
+            } => write!(
+                f,
+                "Merkle path failure for {} oracle at query index 0x{:08x}",
+                oracle, query_index
+            ),
+            Self::PowFailure => write!(f, "PoW failure"),
+            Self::QueryIndexMismatch { query_round } => {
+                write!(f, "query {} is opened at unexpected index", query_round)
+            }
+            Self::DegenerateChallenge => write!(f, "degenerate challenge"),
+            Self::QuotientMismatch => write!(f, "quotient evaluation mismatch"),
+            Self::FriFoldingMismatch { query_index, step } => write!(
+                f,
+                "FRI folding mismatch at step {} for query index 0x{:08x}",
+                step, query_index
+            ),
+            Self::FriFinalPolynomialMismatch { query_index } => write!(
+                f,
+                "FRI final polynomial mismatch for query index 0x{:08x}",
+                query_index
+            ),
+            Self::GrandProductImbalance => write!(f, "memory grand product imbalance"),
+            Self::DelegationArgumentImbalance => write!(f, "delegation argument imbalance"),
+            Self::TooManyDelegationRequests => write!(f, "too many delegation requests"),
+            Self::InvalidNumberOfCircuits => write!(f, "invalid number of circuits"),
+            Self::UnexpectedCircuitSequence => write!(f, "unexpected circuit sequence"),
+            Self::UnexpectedDelegationType => write!(f, "unexpected delegation type"),
+            Self::SetupMismatch => write!(f, "setup mismatch"),
+            Self::ChallengesMismatch => write!(f, "external challenges mismatch"),
+            Self::LazyInitBoundaryMismatch => write!(f, "lazy init boundary mismatch"),
+            Self::PcDiscontinuity => write!(f, "PC discontinuity between circuits"),
+            Self::InvalidRegisterValues => write!(f, "invalid final register values"),
+            Self::RecursionChainMismatch => write!(f, "recursion chain mismatch"),
+            Self::VerificationKeyMismatch => write!(f, "verification key mismatch"),
+        }
+    }
+}
+
+impl core::error::Error for VerificationError {}